@@ -1,4 +1,4 @@
-use crate::extension::{Extension, ListOptions, ListResult};
+use crate::extension::{scan_all_pages, Extension, ListOptions, ListResult};
 use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 
@@ -10,5 +10,12 @@ pub trait ExtensionClient: Send + Sync {
     async fn delete<E: Extension>(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
     async fn fetch<E: Extension + for<'de> Deserialize<'de>>(&self, name: &str) -> Result<Option<E>, Box<dyn std::error::Error + Send + Sync>>;
     async fn list<E: Extension + for<'de> Deserialize<'de>>(&self, options: ListOptions) -> Result<ListResult<E>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 逐页列出满足条件的全部扩展对象（忽略options中的page和size）
+    async fn list_all<E: Extension + for<'de> Deserialize<'de>>(&self, options: ListOptions) -> Result<Vec<E>, Box<dyn std::error::Error + Send + Sync>> {
+        scan_all_pages(options, |options| async move {
+            Ok(self.list::<E>(options).await?.items)
+        }).await
+    }
 }
 
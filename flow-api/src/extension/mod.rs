@@ -2,6 +2,7 @@ pub mod scheme;
 pub mod client;
 pub mod index;
 pub mod query;
+pub mod watch;
 
 use serde::{Deserialize, Serialize};
 
@@ -94,6 +95,32 @@ impl<T> ListResult<T> {
     }
 }
 
+/// 逐页列出全部对象时每页的数量
+pub const SCAN_PAGE_SIZE: u32 = 1000;
+
+/// 从第0页开始逐页调用`fetch`并合并结果，直到某一页不满`SCAN_PAGE_SIZE`
+pub async fn scan_all_pages<T, E, F, Fut>(options: ListOptions, mut fetch: F) -> Result<Vec<T>, E>
+where
+    F: FnMut(ListOptions) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<T>, E>>,
+{
+    let mut items = Vec::new();
+    let mut page = 0;
+    loop {
+        let batch = fetch(ListOptions {
+            page: Some(page),
+            size: Some(SCAN_PAGE_SIZE),
+            ..options.clone()
+        }).await?;
+        let count = batch.len();
+        items.extend(batch);
+        if count < SCAN_PAGE_SIZE as usize {
+            return Ok(items);
+        }
+        page += 1;
+    }
+}
+
 // ExtensionClient trait 已移动到 client.rs
 pub use client::ExtensionClient;
 
@@ -1,4 +1,7 @@
 use crate::extension::GroupVersionKind;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
 
 /// Scheme 定义扩展对象的类型和验证规则
 #[derive(Debug, Clone)]
@@ -6,6 +9,8 @@ pub struct Scheme {
     pub gvk: GroupVersionKind,
     pub type_name: String,
     pub json_schema: Option<serde_json::Value>,
+    /// 索引定义，用于按字段查询扩展对象
+    pub indexes: Vec<SchemeIndex>,
 }
 
 impl Scheme {
@@ -17,6 +22,7 @@ impl Scheme {
             gvk,
             type_name: type_name.into(),
             json_schema: None,
+            indexes: Vec::new(),
         }
     }
 
@@ -24,6 +30,117 @@ impl Scheme {
         self.json_schema = Some(schema);
         self
     }
+
+    pub fn with_index(mut self, index: SchemeIndex) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// 按JSON Schema校验扩展对象，没有定义Schema时总是通过
+    ///
+    /// 支持 `type`、`required`、`properties`、`items` 和 `enum`
+    pub fn validate(&self, extension: &Value) -> Result<(), Vec<String>> {
+        let Some(schema) = &self.json_schema else {
+            return Ok(());
+        };
+        let mut errors = Vec::new();
+        validate_value(schema, extension, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// 查找指定名称的索引
+    pub fn index(&self, name: &str) -> Option<&SchemeIndex> {
+        self.indexes.iter().find(|index| index.name == name)
+    }
+}
+
+/// 扩展对象的字段索引
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemeIndex {
+    /// 索引名称（字段选择器中使用，如 `spec.slug`）
+    pub name: String,
+    /// 字段的JSON Pointer（如 `/spec/slug`），未指定时由名称推导
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl SchemeIndex {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), path: None }
+    }
+
+    /// 索引字段的JSON Pointer
+    pub fn pointer(&self) -> String {
+        self.path.clone().unwrap_or_else(|| format!("/{}", self.name.replace('.', "/")))
+    }
+
+    /// 扩展对象在该索引上的值，数组字段产生多个值
+    pub fn values(&self, extension: &Value) -> Vec<String> {
+        match extension.pointer(&self.pointer()) {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(items)) => items.iter().filter_map(scalar_string).collect(),
+            Some(value) => scalar_string(value).into_iter().collect(),
+        }
+    }
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// 按JSON Schema子集递归校验，错误信息以字段路径开头
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let field = if path.is_empty() { "$" } else { path };
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let matches = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            errors.push(format!("{}: must be {}", field, expected));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!("{}: {} is not an allowed value", field, value));
+        }
+    }
+    if let Some(object) = value.as_object() {
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                errors.push(format!("{}.{}: is required", path, name).trim_start_matches('.').to_string());
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                if let Some(child) = object.get(name) {
+                    let child_path = if path.is_empty() { name.clone() } else { format!("{}.{}", path, name) };
+                    validate_value(property, child, &child_path, errors);
+                }
+            }
+        }
+    }
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate_value(item_schema, item, &format!("{}[{}]", field, i), errors);
+        }
+    }
 }
 
 /// SchemeManager 管理所有扩展对象的Scheme定义
@@ -34,6 +151,9 @@ pub trait SchemeManager: Send + Sync {
     fn list(&self) -> Vec<&Scheme>;
 }
 
+/// 可在运行时共享和修改的Scheme管理器（插件在启动和停止时注册和移除扩展类型）
+pub type SharedSchemeManager = Arc<RwLock<dyn SchemeManager>>;
+
 /// DefaultSchemeManager 默认的Scheme管理器实现
 pub struct DefaultSchemeManager {
     schemes: std::collections::HashMap<GroupVersionKind, Scheme>,
@@ -72,3 +192,57 @@ impl SchemeManager for DefaultSchemeManager {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scheme() -> Scheme {
+        Scheme::new(GroupVersionKind::new("note.flow.run", "v1alpha1", "Note"), "Note")
+            .with_schema(json!({
+                "type": "object",
+                "required": ["spec"],
+                "properties": {
+                    "spec": {
+                        "type": "object",
+                        "required": ["title"],
+                        "properties": {
+                            "title": { "type": "string" },
+                            "priority": { "type": "integer" },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "state": { "enum": ["draft", "done"] }
+                        }
+                    }
+                }
+            }))
+            .with_index(SchemeIndex::new("spec.tags"))
+    }
+
+    #[test]
+    fn test_validate() {
+        let scheme = scheme();
+        assert!(scheme.validate(&json!({"spec": {"title": "a", "priority": 1, "tags": ["x"]}})).is_ok());
+        assert_eq!(scheme.validate(&json!({})).unwrap_err(), vec!["spec: is required"]);
+        let mut errors = scheme.validate(&json!({"spec": {"priority": 1.5, "tags": ["x", 1], "state": "open"}})).unwrap_err();
+        errors.sort();
+        assert_eq!(errors, vec![
+            "spec.priority: must be integer",
+            "spec.state: \"open\" is not an allowed value",
+            "spec.tags[1]: must be string",
+            "spec.title: is required",
+        ]);
+        assert!(Scheme::new(scheme.gvk.clone(), "Note").validate(&json!(1)).is_ok());
+    }
+
+    #[test]
+    fn test_index_values() {
+        let scheme = scheme();
+        let index = scheme.index("spec.tags").unwrap();
+        assert_eq!(index.pointer(), "/spec/tags");
+        assert_eq!(index.values(&json!({"spec": {"tags": ["a", 1, {}]}})), vec!["a", "1"]);
+        assert!(index.values(&json!({"spec": {}})).is_empty());
+        let custom = SchemeIndex { name: "title".to_string(), path: Some("/spec/title".to_string()) };
+        assert_eq!(custom.values(&json!({"spec": {"title": "Hi"}})), vec!["Hi"]);
+    }
+}
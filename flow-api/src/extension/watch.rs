@@ -0,0 +1,39 @@
+//! 扩展对象变更监听
+//! 扩展对象创建、更新、删除后通知监听器，控制器据此将变更的对象加入工作队列
+
+use crate::extension::GroupVersionKind;
+use std::sync::Arc;
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEventType {
+    Added,
+    Updated,
+    Deleted,
+}
+
+/// 扩展对象变更事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub event_type: WatchEventType,
+    /// 变更对象的类型，删除时无法确定类型，为None
+    pub gvk: Option<GroupVersionKind>,
+    /// 变更对象的名称
+    pub name: String,
+}
+
+/// 扩展对象变更监听器
+///
+/// 在写入扩展对象的调用中同步通知，实现不能阻塞
+pub trait ExtensionWatcher: Send + Sync {
+    fn on_event(&self, event: &WatchEvent);
+}
+
+/// 可以监听扩展对象变更的来源（通常为扩展客户端）
+pub trait ExtensionWatchSource: Send + Sync {
+    /// 注册监听器，返回监听ID
+    fn watch(&self, watcher: Arc<dyn ExtensionWatcher>) -> u64;
+
+    /// 移除监听器
+    fn unwatch(&self, id: u64);
+}
@@ -183,6 +183,31 @@ pub struct CacheStats {
     pub size: u64,
 }
 
+/// 搜索建议类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionType {
+    /// 文档标题
+    Title,
+    /// 标签
+    Tag,
+}
+
+/// Suggestion 搜索建议
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion {
+    /// 建议的文本（标题或标签名称）
+    pub text: String,
+
+    /// 建议类型
+    #[serde(rename = "type")]
+    pub suggestion_type: SuggestionType,
+
+    /// 标题建议对应文档的永久链接
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permalink: Option<String>,
+}
+
 /// SearchEngine trait 定义搜索引擎接口
 #[async_trait::async_trait]
 pub trait SearchEngine: Send + Sync {
@@ -200,5 +225,17 @@ pub trait SearchEngine: Send + Sync {
     
     /// 搜索文档
     async fn search(&self, option: SearchOption) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 导出引擎中的所有文档，切换搜索引擎时用于重建新引擎的索引
+    /// 不支持导出的引擎返回空列表
+    async fn documents(&self) -> Result<Vec<HaloDocument>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+    
+    /// 按关键词前缀返回公开文档的标题和标签建议，标题和标签各最多 `limit` 条，用于边输入边搜索
+    /// 不支持建议的引擎返回空列表
+    async fn suggest(&self, _keyword: &str, _limit: u32) -> Result<Vec<Suggestion>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
 }
 
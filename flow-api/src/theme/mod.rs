@@ -8,6 +8,11 @@ use async_trait::async_trait;
 pub trait Finder: Send + Sync {
     /// Finder的名称（在模板中使用）
     fn name(&self) -> &str;
+    
+    /// 渲染页面前预加载的数据（在模板中以Finder名称访问），默认不预加载
+    async fn preload(&self) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
 }
 
 /// Finder注册表
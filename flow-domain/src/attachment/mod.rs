@@ -59,6 +59,42 @@ pub struct AttachmentStatus {
     
     /// 缩略图链接（key为缩略图尺寸：XL, L, M, S）
     pub thumbnails: Option<HashMap<String, String>>,
+    
+    /// 引用该附件的已发布文章和页面，在发布时扫描内容更新
+    #[serde(rename = "usedBy", default, skip_serializing_if = "Option::is_none")]
+    pub used_by: Option<Vec<AttachmentReference>>,
+    
+    /// 图片的BlurHash，用于在图片加载前显示模糊占位图
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blurhash: Option<String>,
+    
+    /// 图片的主色调，格式为 #rrggbb
+    #[serde(rename = "dominantColor", default, skip_serializing_if = "Option::is_none")]
+    pub dominant_color: Option<String>,
+}
+
+/// 引用附件的文章、页面或主题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentReference {
+    /// 引用方类型：Post、SinglePage、Theme
+    pub kind: String,
+    /// 引用方名称
+    pub name: String,
+    /// 引用方标题
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl AttachmentReference {
+    pub fn new(kind: &str, name: &str, title: Option<String>) -> Self {
+        Self { kind: kind.to_string(), name: name.to_string(), title }
+    }
+
+    /// 是否为同一个引用方（不比较标题）
+    pub fn is_same_subject(&self, other: &AttachmentReference) -> bool {
+        self.kind == other.kind && self.name == other.name
+    }
 }
 
 /// PolicyTemplate扩展对象
@@ -95,6 +131,8 @@ pub struct PolicyTemplateSpec {
 pub struct Policy {
     pub metadata: Metadata,
     pub spec: PolicySpec,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PolicyStatus>,
 }
 
 impl Extension for Policy {
@@ -123,6 +161,17 @@ pub struct PolicySpec {
     pub config_map_name: Option<String>,
 }
 
+/// Policy状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyStatus {
+    /// 使用该策略的附件总数
+    pub total_attachments: Option<u64>,
+    
+    /// 使用该策略的附件总大小（字节）
+    pub total_size: Option<u64>,
+}
+
 /// Group扩展对象
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Group {
@@ -147,6 +196,14 @@ pub struct GroupSpec {
     /// 显示名称（必需）
     #[serde(rename = "displayName")]
     pub display_name: String,
+    
+    /// 分组的存储配额，为空时不限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<StorageQuota>,
+    
+    /// 是否只允许上传图片（按文件内容识别）
+    #[serde(rename = "imageOnly", default, skip_serializing_if = "std::ops::Not::not")]
+    pub image_only: bool,
 }
 
 /// Group状态
@@ -160,6 +217,28 @@ pub struct GroupStatus {
     /// 该分组下的附件总数
     #[serde(rename = "totalAttachments")]
     pub total_attachments: Option<u64>,
+    
+    /// 该分组下附件的总大小（字节）
+    #[serde(rename = "totalSize", default)]
+    pub total_size: Option<u64>,
+}
+
+/// 存储配额
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuota {
+    /// 最大总字节数
+    pub max_bytes: Option<u64>,
+    /// 最大文件数
+    pub max_files: Option<u64>,
+}
+
+/// 存储用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    pub file_count: u64,
 }
 
 /// 缩略图尺寸
@@ -209,3 +288,39 @@ impl ThumbnailSize {
     }
 }
 
+
+/// 缩略图的现代图片格式（与原格式缩略图一同生成）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    Webp,
+    Avif,
+}
+
+impl ThumbnailFormat {
+    /// 按压缩率从高到低排列的所有格式
+    pub const ALL: [ThumbnailFormat; 2] = [ThumbnailFormat::Avif, ThumbnailFormat::Webp];
+
+    /// 转换为字符串（同时作为文件扩展名）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Webp => "webp",
+            ThumbnailFormat::Avif => "avif",
+        }
+    }
+
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Webp => "image/webp",
+            ThumbnailFormat::Avif => "image/avif",
+        }
+    }
+
+    /// 缩略图在AttachmentStatus.thumbnails中的键，原格式为尺寸（如 M），其他格式为 尺寸.格式（如 M.webp）
+    pub fn thumbnail_key(size: ThumbnailSize, format: Option<ThumbnailFormat>) -> String {
+        match format {
+            Some(format) => format!("{}.{}", size.as_str(), format.as_str()),
+            None => size.as_str().to_string(),
+        }
+    }
+}
@@ -26,6 +26,11 @@ impl Comment {
     pub fn status_or_default(&self) -> CommentStatus {
         self.status.clone().unwrap_or_default()
     }
+
+    /// 是否为垃圾评论
+    pub fn is_spam(&self) -> bool {
+        self.spec.spam.unwrap_or(false)
+    }
 }
 
 /// CommentSpec包含评论的规格信息
@@ -78,6 +83,10 @@ pub struct CommentSpec {
     
     #[serde(default)]
     pub hidden: Option<bool>,
+    
+    /// 是否被判定为垃圾评论（垃圾评论不会被批准，需在审核后恢复或删除）
+    #[serde(default)]
+    pub spam: Option<bool>,
 }
 
 fn default_true() -> bool {
@@ -150,6 +159,10 @@ pub struct CommentStatus {
     
     #[serde(rename = "observedVersion")]
     pub observed_version: Option<i64>,
+    
+    /// 表情回应计数（key为回应类型，如like、heart）
+    #[serde(default)]
+    pub reactions: Option<std::collections::HashMap<String, i64>>,
 }
 
 /// BaseCommentSpec是评论的基础规格（用于Reply）
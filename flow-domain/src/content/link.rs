@@ -0,0 +1,68 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::constant;
+
+/// Link实体（友情链接）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub metadata: Metadata,
+    pub spec: LinkSpec,
+}
+
+impl Extension for Link {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::LINK_API_GROUP, constant::VERSION, constant::LINK_KIND)
+    }
+}
+
+/// LinkSpec包含友情链接的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSpec {
+    pub url: String,
+    
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    pub logo: Option<String>,
+    pub description: Option<String>,
+    
+    /// 在分组中的排序，数值小的排在前面
+    #[serde(default)]
+    pub priority: i32,
+    
+    /// 所属分组名称，为空时不属于任何分组
+    #[serde(rename = "groupName")]
+    pub group_name: Option<String>,
+}
+
+/// LinkGroup实体（友情链接分组）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkGroup {
+    pub metadata: Metadata,
+    pub spec: LinkGroupSpec,
+}
+
+impl Extension for LinkGroup {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::LINK_API_GROUP, constant::VERSION, constant::LINK_GROUP_KIND)
+    }
+}
+
+/// LinkGroupSpec包含友情链接分组的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkGroupSpec {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    /// 分组的排序，数值小的排在前面
+    #[serde(default)]
+    pub priority: i32,
+}
@@ -0,0 +1,93 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::constant;
+
+/// Menu实体（站点导航菜单）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Menu {
+    pub metadata: Metadata,
+    pub spec: MenuSpec,
+}
+
+impl Extension for Menu {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::CORE_GROUP, constant::VERSION, constant::MENU_KIND)
+    }
+}
+
+/// MenuSpec包含菜单的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuSpec {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    /// 菜单的顶级菜单项名称
+    #[serde(rename = "menuItems", default)]
+    pub menu_items: Option<Vec<String>>,
+}
+
+/// MenuItem实体（菜单项）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub metadata: Metadata,
+    pub spec: MenuItemSpec,
+    pub status: Option<MenuItemStatus>,
+}
+
+impl Extension for MenuItem {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::CORE_GROUP, constant::VERSION, constant::MENU_ITEM_KIND)
+    }
+}
+
+impl MenuItem {
+    /// 显示名称（优先使用spec，其次使用status中解析出的名称）
+    pub fn display_name(&self) -> String {
+        self.spec.display_name.clone()
+            .or_else(|| self.status.as_ref().and_then(|s| s.display_name.clone()))
+            .unwrap_or_else(|| self.metadata.name.clone())
+    }
+    
+    /// 链接地址（优先使用spec，其次使用status中解析出的地址）
+    pub fn href(&self) -> Option<String> {
+        self.spec.href.clone()
+            .or_else(|| self.status.as_ref().and_then(|s| s.href.clone()))
+    }
+}
+
+/// MenuItemSpec包含菜单项的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItemSpec {
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    
+    pub href: Option<String>,
+    
+    /// 链接打开方式（如 _blank、_self）
+    pub target: Option<String>,
+    
+    /// 在同级菜单项中的排序，数值小的排在前面
+    #[serde(default)]
+    pub priority: i32,
+    
+    /// 子菜单项名称
+    #[serde(default)]
+    pub children: Option<Vec<String>>,
+}
+
+/// MenuItemStatus包含菜单项的状态信息
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MenuItemStatus {
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    
+    pub href: Option<String>,
+}
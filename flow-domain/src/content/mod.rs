@@ -4,6 +4,12 @@ pub mod comment;
 pub mod snapshot;
 pub mod category;
 pub mod tag;
+pub mod series;
+pub mod slug_redirect;
+pub mod menu;
+pub mod link;
+pub mod moment;
+pub mod photo;
 
 pub use post::{Post, PostSpec, PostStatus, PostPhase, VisibleEnum, Excerpt};
 pub use single_page::{SinglePage, SinglePageSpec, SinglePageStatus};
@@ -11,11 +17,19 @@ pub use comment::{Comment, CommentSpec, CommentStatus, CommentOwner, BaseComment
 pub use snapshot::{Snapshot, SnapshotSpec};
 pub use category::{Category, CategorySpec, CategoryStatus};
 pub use tag::{Tag, TagSpec, TagStatus};
+pub use series::{Series, SeriesSpec, SeriesStatus};
+pub use slug_redirect::{SlugRedirect, SlugRedirectSpec};
+pub use menu::{Menu, MenuSpec, MenuItem, MenuItemSpec, MenuItemStatus};
+pub use link::{Link, LinkSpec, LinkGroup, LinkGroupSpec};
+pub use moment::{Moment, MomentSpec, MomentContent, MomentMedia, MomentMediaType};
+pub use photo::{Photo, PhotoSpec, PhotoGroup, PhotoGroupSpec};
 
 /// 内容管理相关的常量
 pub mod constant {
     pub const GROUP: &str = "content.halo.run";
     pub const VERSION: &str = "v1alpha1";
+    /// 核心扩展组（Menu等站点级扩展）
+    pub const CORE_GROUP: &str = "";
     
     // Post相关
     pub const POST_KIND: &str = "Post";
@@ -32,6 +46,8 @@ pub mod constant {
     pub const POST_LAST_ASSOCIATED_TAGS_ANNO: &str = "content.halo.run/last-associated-tags";
     pub const POST_LAST_ASSOCIATED_CATEGORIES_ANNO: &str = "content.halo.run/last-associated-categories";
     pub const POST_STATS_ANNO: &str = "content.halo.run/stats";
+    /// 内容中的失效站内链接（JSON数组）
+    pub const BROKEN_LINKS_ANNO: &str = "content.halo.run/broken-links";
     
     // SinglePage相关
     pub const SINGLE_PAGE_KIND: &str = "SinglePage";
@@ -51,5 +67,29 @@ pub mod constant {
     
     // Tag相关
     pub const TAG_KIND: &str = "Tag";
+    
+    // Series相关
+    pub const SERIES_KIND: &str = "Series";
+    
+    // SlugRedirect相关
+    pub const SLUG_REDIRECT_KIND: &str = "SlugRedirect";
+    
+    // Menu相关
+    pub const MENU_KIND: &str = "Menu";
+    pub const MENU_ITEM_KIND: &str = "MenuItem";
+    
+    // Link相关
+    pub const LINK_API_GROUP: &str = "core.halo.run";
+    pub const LINK_KIND: &str = "Link";
+    pub const LINK_GROUP_KIND: &str = "LinkGroup";
+    
+    // Moment相关
+    pub const MOMENT_GROUP: &str = "moment.halo.run";
+    pub const MOMENT_KIND: &str = "Moment";
+    
+    // Photo相关
+    pub const PHOTO_API_GROUP: &str = "core.halo.run";
+    pub const PHOTO_KIND: &str = "Photo";
+    pub const PHOTO_GROUP_KIND: &str = "PhotoGroup";
 }
 
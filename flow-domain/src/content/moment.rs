@@ -0,0 +1,90 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::post::VisibleEnum;
+use super::constant;
+
+/// Moment实体（瞬间，短内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Moment {
+    pub metadata: Metadata,
+    pub spec: MomentSpec,
+}
+
+impl Extension for Moment {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::MOMENT_GROUP, constant::VERSION, constant::MOMENT_KIND)
+    }
+}
+
+impl Moment {
+    /// 检查瞬间是否公开可见
+    pub fn is_public(&self) -> bool {
+        self.spec.visible.unwrap_or(VisibleEnum::Public) == VisibleEnum::Public
+    }
+    
+    /// 检查瞬间是否属于指定用户
+    pub fn is_owned_by(&self, username: &str) -> bool {
+        self.spec.owner.as_deref() == Some(username)
+    }
+}
+
+/// MomentSpec包含瞬间的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MomentSpec {
+    pub content: MomentContent,
+    
+    /// 发布时间
+    #[serde(rename = "releaseTime")]
+    pub release_time: Option<chrono::DateTime<chrono::Utc>>,
+    
+    #[serde(default)]
+    pub visible: Option<VisibleEnum>,
+    
+    pub owner: Option<String>,
+    
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/// 瞬间的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MomentContent {
+    /// 原始内容
+    #[serde(default)]
+    pub raw: String,
+    
+    /// 渲染后的HTML
+    #[serde(default)]
+    pub html: String,
+    
+    /// 媒体列表（图片、视频等）
+    #[serde(default)]
+    pub medium: Option<Vec<MomentMedia>>,
+}
+
+/// 瞬间中的媒体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MomentMedia {
+    #[serde(rename = "type")]
+    pub media_type: MomentMediaType,
+    
+    pub url: String,
+    
+    /// 原始媒体类型（如 image/png）
+    #[serde(rename = "originType")]
+    pub origin_type: Option<String>,
+}
+
+/// 瞬间媒体类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MomentMediaType {
+    Photo,
+    Video,
+    Audio,
+    Post,
+}
@@ -0,0 +1,79 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::constant;
+
+/// Photo实体（图库照片，引用一个附件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Photo {
+    pub metadata: Metadata,
+    pub spec: PhotoSpec,
+}
+
+impl Extension for Photo {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::PHOTO_API_GROUP, constant::VERSION, constant::PHOTO_KIND)
+    }
+}
+
+/// PhotoSpec包含照片的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoSpec {
+    /// 照片引用的附件名称
+    #[serde(rename = "attachmentName")]
+    pub attachment_name: String,
+    
+    /// 显示名称，为空时使用附件的显示名称
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
+    
+    /// 照片说明
+    pub description: Option<String>,
+    
+    /// 照片链接（保存时从附件同步）
+    pub url: Option<String>,
+    
+    /// 缩略图链接（保存时从附件同步）
+    pub cover: Option<String>,
+    
+    /// 在分组中的排序，数值小的排在前面
+    #[serde(default)]
+    pub priority: i32,
+    
+    /// 所属分组名称，为空时不属于任何分组
+    #[serde(rename = "groupName")]
+    pub group_name: Option<String>,
+}
+
+/// PhotoGroup实体（图库分组）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoGroup {
+    pub metadata: Metadata,
+    pub spec: PhotoGroupSpec,
+}
+
+impl Extension for PhotoGroup {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::PHOTO_API_GROUP, constant::VERSION, constant::PHOTO_GROUP_KIND)
+    }
+}
+
+/// PhotoGroupSpec包含图库分组的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoGroupSpec {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    pub description: Option<String>,
+    
+    /// 分组的排序，数值小的排在前面
+    #[serde(default)]
+    pub priority: i32,
+}
@@ -43,10 +43,51 @@ impl Post {
         matches!(self.spec.visible, Some(VisibleEnum::Public) | None)
     }
 
+    /// 检查文章是否需要密码访问
+    pub fn requires_password(&self) -> bool {
+        matches!(self.spec.visible, Some(VisibleEnum::Private)) && self.spec.password.is_some()
+    }
+
+    /// 去掉访问密码哈希，文章返回给客户端前调用
+    pub fn without_password(mut self) -> Self {
+        self.spec.password = None;
+        self
+    }
+
     /// 获取状态（如果不存在则返回默认值）
     pub fn status_or_default(&self) -> PostStatus {
         self.status.clone().unwrap_or_default()
     }
+
+    /// 检查用户是否为文章所有者
+    pub fn is_owned_by(&self, username: &str) -> bool {
+        self.spec.owner.as_deref() == Some(username)
+    }
+
+    /// 检查用户是否可以编辑文章（所有者或共同作者）
+    pub fn can_edit(&self, username: &str) -> bool {
+        self.is_owned_by(username)
+            || self.spec.contributors.as_ref().is_some_and(|c| c.iter().any(|name| name == username))
+    }
+
+    /// 合并贡献者：所有者、共同作者、快照作者依次去重
+    pub fn merge_contributors<'a>(&'a self, snapshot_contributors: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+        let mut contributors: Vec<String> = Vec::new();
+        let candidates = self.spec.owner.iter()
+            .chain(self.spec.contributors.iter().flatten())
+            .chain(snapshot_contributors);
+        for name in candidates {
+            if !contributors.contains(name) {
+                contributors.push(name.clone());
+            }
+        }
+        contributors
+    }
+
+    /// 获取翻译组标识（来源文章名称，未设置时为自身名称）
+    pub fn translation_group(&self) -> &str {
+        self.spec.translation_of.as_deref().unwrap_or(&self.metadata.name)
+    }
 }
 
 /// PostSpec包含文章的规格信息
@@ -103,6 +144,22 @@ pub struct PostSpec {
     
     #[serde(rename = "htmlMetas")]
     pub html_metas: Option<Vec<std::collections::HashMap<String, String>>>,
+    
+    /// 文章语言（BCP 47语言标签，如zh-CN、en）
+    #[serde(default)]
+    pub language: Option<String>,
+    
+    /// 翻译来源文章名称，同一篇文章的各语言版本指向同一来源
+    #[serde(rename = "translationOf", default)]
+    pub translation_of: Option<String>,
+    
+    /// 显式添加的共同作者（用户名），与所有者一样可以编辑草稿
+    #[serde(default)]
+    pub contributors: Option<Vec<String>>,
+    
+    /// 访问密码哈希（仅在visible为Private时生效）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -138,6 +195,14 @@ pub struct PostStatus {
     
     #[serde(rename = "observedVersion")]
     pub observed_version: Option<i64>,
+    
+    /// 发布内容的字数（CJK字符按单字计数）
+    #[serde(rename = "wordCount")]
+    pub word_count: Option<u64>,
+    
+    /// 预计阅读时间（分钟）
+    #[serde(rename = "readingTime")]
+    pub reading_time: Option<u32>,
 }
 
 /// PostPhase表示文章的阶段
@@ -168,3 +233,39 @@ pub struct Excerpt {
     pub raw: Option<String>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(owner: Option<&str>, contributors: Option<Vec<&str>>) -> Post {
+        let mut spec: PostSpec = serde_json::from_value(serde_json::json!({"title": "hello", "slug": "hello"})).unwrap();
+        spec.owner = owner.map(str::to_string);
+        spec.contributors = contributors.map(|names| names.into_iter().map(str::to_string).collect());
+        Post { metadata: Metadata::new("hello"), spec, status: None }
+    }
+
+    #[test]
+    fn test_can_edit() {
+        let shared = post(Some("alice"), Some(vec!["bob"]));
+        assert!(shared.is_owned_by("alice"));
+        assert!(shared.can_edit("alice"));
+        assert!(!shared.is_owned_by("bob"));
+        assert!(shared.can_edit("bob"));
+        assert!(!shared.can_edit("carol"));
+
+        let orphan = post(None, None);
+        assert!(!orphan.can_edit("alice"));
+        assert!(!orphan.can_edit(""));
+    }
+
+    #[test]
+    fn test_merge_contributors() {
+        let shared = post(Some("alice"), Some(vec!["bob", "alice"]));
+        let snapshot_contributors = ["carol".to_string(), "bob".to_string(), "dave".to_string(), "carol".to_string()];
+        // 所有者在前，其次是共同作者和快照作者，重复的只保留第一次出现
+        assert_eq!(shared.merge_contributors(&snapshot_contributors), vec!["alice", "bob", "carol", "dave"]);
+
+        let orphan = post(None, None);
+        assert!(orphan.merge_contributors(&[]).is_empty());
+    }
+}
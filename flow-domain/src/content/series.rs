@@ -0,0 +1,63 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::constant;
+
+/// Series实体（文章系列/合集，按顺序组织多篇文章）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Series {
+    pub metadata: Metadata,
+    pub spec: SeriesSpec,
+    pub status: Option<SeriesStatus>,
+}
+
+impl Extension for Series {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::GROUP, constant::VERSION, constant::SERIES_KIND)
+    }
+}
+
+impl Series {
+    /// 获取状态（如果不存在则返回默认值）
+    pub fn status_or_default(&self) -> SeriesStatus {
+        self.status.clone().unwrap_or_default()
+    }
+
+    /// 获取文章在系列中的位置（从0开始）
+    pub fn position_of(&self, post_name: &str) -> Option<usize> {
+        self.spec.posts.iter().position(|name| name == post_name)
+    }
+}
+
+/// SeriesSpec包含系列的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesSpec {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+
+    pub slug: String,
+
+    pub description: Option<String>,
+
+    pub cover: Option<String>,
+
+    /// 系列归档页使用的模板
+    pub template: Option<String>,
+
+    /// 系列中的文章名称（按阅读顺序排列）
+    #[serde(default)]
+    pub posts: Vec<String>,
+}
+
+/// SeriesStatus包含系列的状态信息
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SeriesStatus {
+    pub permalink: Option<String>,
+
+    /// 文章数量
+    #[serde(rename = "postCount")]
+    pub post_count: Option<i32>,
+}
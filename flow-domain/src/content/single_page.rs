@@ -80,6 +80,14 @@ pub struct SinglePageSpec {
     #[serde(default)]
     pub priority: Option<i32>,
     
+    /// 父页面名称，用于构建页面层级（如 /about/team）
+    #[serde(default)]
+    pub parent: Option<String>,
+    
+    /// 在同级页面中的排序，数值小的排在前面
+    #[serde(default)]
+    pub order: Option<i32>,
+    
     pub excerpt: Option<Excerpt>,
     
     #[serde(rename = "htmlMetas")]
@@ -0,0 +1,37 @@
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+use serde::{Deserialize, Serialize};
+use super::constant;
+use super::comment::SubjectRef;
+
+/// SlugRedirect实体（slug变更后旧链接到新链接的301重定向记录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlugRedirect {
+    pub metadata: Metadata,
+    pub spec: SlugRedirectSpec,
+}
+
+impl Extension for SlugRedirect {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new(constant::GROUP, constant::VERSION, constant::SLUG_REDIRECT_KIND)
+    }
+}
+
+/// SlugRedirectSpec包含重定向的规格信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlugRedirectSpec {
+    /// 旧链接路径
+    #[serde(rename = "sourcePath")]
+    pub source_path: String,
+
+    /// 新链接路径
+    #[serde(rename = "targetPath")]
+    pub target_path: String,
+
+    /// 重定向目标（文章或分类）
+    #[serde(rename = "subjectRef")]
+    pub subject_ref: SubjectRef,
+}
@@ -5,6 +5,8 @@ pub mod attachment;
 pub mod notification;
 pub mod migration;
 pub mod plugin;
+pub mod setting;
+pub mod scheme;
 
 pub use security::{
     User, UserSpec, UserStatus,
@@ -21,9 +23,15 @@ pub use content::{
     Snapshot, SnapshotSpec,
     Category, CategorySpec, CategoryStatus,
     Tag, TagSpec, TagStatus,
+    Series, SeriesSpec, SeriesStatus,
+    SlugRedirect, SlugRedirectSpec,
+    Menu, MenuSpec, MenuItem, MenuItemSpec, MenuItemStatus,
+    Link, LinkSpec, LinkGroup, LinkGroupSpec,
+    Moment, MomentSpec, MomentContent, MomentMedia, MomentMediaType,
+    Photo, PhotoSpec, PhotoGroup, PhotoGroupSpec,
 };
 
-pub use attachment::{Attachment, AttachmentSpec, AttachmentStatus, ThumbnailSize};
+pub use attachment::{Attachment, AttachmentSpec, AttachmentStatus, AttachmentReference, ThumbnailSize, ThumbnailFormat, StorageQuota, StorageUsage};
 pub use attachment::{Policy, PolicySpec, PolicyTemplate, PolicyTemplateSpec};
 pub use attachment::{Group, GroupSpec, GroupStatus};
 
@@ -32,8 +40,12 @@ pub use notification::{
     NotificationTemplate, NotificationTemplateSpec, ReasonSelector, TemplateContent,
     Reason, ReasonSpec, ReasonSubject,
     Subscription, SubscriptionSpec, SubscriptionSubscriber, InterestReason, InterestReasonSubject,
+    NotifierDescriptor, NotifierDescriptorSpec, NotifierSettingRef,
+    ReasonType, ReasonTypeSpec, ReasonProperty,
 };
 
 pub use migration::{Backup, BackupSpec, BackupStatus, BackupPhase, BackupFile};
 
 pub use plugin::{Plugin, PluginSpec, PluginStatus, PluginPhase, PluginAuthor, License};
+
+pub use setting::{Setting, SettingSpec, SettingForm};
@@ -230,6 +230,108 @@ pub struct InterestReasonSubject {
     pub kind: String,
 }
 
+/// NotifierDescriptor扩展对象
+/// 描述一个通知渠道：由哪个通知器发送，以及发送者（站点）和接收者（用户）需要填写的设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifierDescriptor {
+    pub metadata: Metadata,
+    pub spec: NotifierDescriptorSpec,
+}
+
+impl Extension for NotifierDescriptor {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new("notification.halo.run", "v1alpha1", "NotifierDescriptor")
+    }
+}
+
+/// NotifierDescriptor规格
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifierDescriptorSpec {
+    /// 显示名称（必需）
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    /// 描述
+    pub description: Option<String>,
+    
+    /// 发送通知的通知器扩展名称（必需）
+    #[serde(rename = "notifierExtName")]
+    pub notifier_ext_name: String,
+    
+    /// 发送者设置（站点级，如机器人令牌）
+    #[serde(rename = "senderSettingRef")]
+    pub sender_setting_ref: Option<NotifierSettingRef>,
+    
+    /// 接收者设置（每个用户各自填写，如会话ID）
+    #[serde(rename = "receiverSettingRef")]
+    pub receiver_setting_ref: Option<NotifierSettingRef>,
+}
+
+/// 引用Setting中的一个表单分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifierSettingRef {
+    /// Setting名称（必需）
+    pub name: String,
+    
+    /// 表单分组（必需）
+    pub group: String,
+}
+
+/// ReasonType扩展对象
+/// 声明一种通知原因：显示名称、描述，以及产生的Reason需要携带的属性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasonType {
+    pub metadata: Metadata,
+    pub spec: ReasonTypeSpec,
+}
+
+impl Extension for ReasonType {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new("notification.halo.run", "v1alpha1", "ReasonType")
+    }
+}
+
+/// ReasonType规格
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasonTypeSpec {
+    /// 显示名称（必需）
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    
+    /// 描述
+    pub description: Option<String>,
+    
+    /// Reason属性的定义
+    #[serde(default)]
+    pub properties: Vec<ReasonProperty>,
+}
+
+/// Reason属性的定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasonProperty {
+    /// 属性名称（必需）
+    pub name: String,
+    
+    /// 属性类型：string、number或boolean（必需）
+    #[serde(rename = "type")]
+    pub property_type: String,
+    
+    /// 描述
+    pub description: Option<String>,
+    
+    /// 是否可以省略
+    #[serde(default)]
+    pub optional: bool,
+}
+
 impl Subscription {
     /// 生成取消订阅token
     pub fn generate_unsubscribe_token() -> String {
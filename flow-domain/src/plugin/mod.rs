@@ -91,7 +91,7 @@ pub struct PluginAuthor {
 }
 
 /// Plugin状态
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PluginStatus {
     /// 插件阶段
     #[serde(default)]
@@ -103,6 +103,14 @@ pub struct PluginStatus {
     /// 最后探测状态
     pub last_probe_state: Option<String>,
     
+    /// 最后探测时间
+    #[serde(default)]
+    pub last_probe_time: Option<DateTime<Utc>>,
+    
+    /// 连续探测失败次数
+    #[serde(default)]
+    pub probe_failures: u32,
+    
     /// 入口文件路径
     pub entry: Option<String>,
     
@@ -114,6 +122,24 @@ pub struct PluginStatus {
     
     /// 加载位置（通常是路径）
     pub load_location: Option<String>,
+    
+    /// 状态条件，记录插件无法加载或启动的具体原因
+    #[serde(default)]
+    pub conditions: Vec<PluginCondition>,
+}
+
+/// 插件状态条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCondition {
+    /// 条件类型，如 `Failed`
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    /// 机器可读的原因，如 `InvalidVersion`
+    pub reason: String,
+    /// 详细信息
+    pub message: String,
+    pub last_transition_time: DateTime<Utc>,
 }
 
 /// 插件阶段
@@ -0,0 +1,78 @@
+//! 宿主内置的扩展类型
+//! 服务启动时在加载插件前注册到Scheme管理器，插件不能注册同组同名的类型
+
+use crate::content::constant;
+use crate::security::{auth_provider, pat, role, role_binding, user, user_connection};
+use flow_api::extension::scheme::Scheme;
+use flow_api::extension::GroupVersionKind;
+
+/// 内置类型的版本
+const VERSION: &str = "v1alpha1";
+
+/// 宿主内置扩展类型的GVK
+pub fn builtin_kinds() -> Vec<GroupVersionKind> {
+    let mut kinds: Vec<GroupVersionKind> = [
+        constant::POST_KIND, constant::SINGLE_PAGE_KIND, constant::COMMENT_KIND, constant::SNAPSHOT_KIND,
+        constant::CATEGORY_KIND, constant::TAG_KIND, constant::SERIES_KIND, constant::SLUG_REDIRECT_KIND,
+    ].into_iter().map(|kind| GroupVersionKind::new(constant::GROUP, constant::VERSION, kind)).collect();
+    kinds.extend([
+        GroupVersionKind::new(constant::CORE_GROUP, constant::VERSION, constant::MENU_KIND),
+        GroupVersionKind::new(constant::CORE_GROUP, constant::VERSION, constant::MENU_ITEM_KIND),
+        GroupVersionKind::new(constant::LINK_API_GROUP, constant::VERSION, constant::LINK_KIND),
+        GroupVersionKind::new(constant::LINK_API_GROUP, constant::VERSION, constant::LINK_GROUP_KIND),
+        GroupVersionKind::new(constant::PHOTO_API_GROUP, constant::VERSION, constant::PHOTO_KIND),
+        GroupVersionKind::new(constant::PHOTO_API_GROUP, constant::VERSION, constant::PHOTO_GROUP_KIND),
+        GroupVersionKind::new(constant::MOMENT_GROUP, constant::VERSION, constant::MOMENT_KIND),
+        GroupVersionKind::new(user::USER_GROUP, user::USER_VERSION, user::USER_KIND),
+        GroupVersionKind::new(role::ROLE_GROUP, role::ROLE_VERSION, role::ROLE_KIND),
+        GroupVersionKind::new(role_binding::ROLE_BINDING_GROUP, role_binding::ROLE_BINDING_VERSION, role_binding::ROLE_BINDING_KIND),
+        GroupVersionKind::new(pat::PAT_GROUP, pat::PAT_VERSION, pat::PAT_KIND),
+        GroupVersionKind::new(auth_provider::AUTH_PROVIDER_GROUP, auth_provider::AUTH_PROVIDER_VERSION, auth_provider::AUTH_PROVIDER_KIND),
+        GroupVersionKind::new(user_connection::USER_CONNECTION_GROUP, user_connection::USER_CONNECTION_VERSION, user_connection::USER_CONNECTION_KIND),
+    ]);
+    kinds.extend([
+        ("notification.halo.run", "Notification"),
+        ("notification.halo.run", "NotificationTemplate"),
+        ("notification.halo.run", "Reason"),
+        ("notification.halo.run", "Subscription"),
+        ("notification.halo.run", "NotifierDescriptor"),
+        ("notification.halo.run", "ReasonType"),
+        ("storage.halo.run", "Attachment"),
+        ("storage.halo.run", "PolicyTemplate"),
+        ("storage.halo.run", "Policy"),
+        ("storage.halo.run", "Group"),
+        ("theme.halo.run", "Theme"),
+        ("plugin.halo.run", "Plugin"),
+        ("migration.halo.run", "Backup"),
+        ("", "Setting"),
+        // 系统设置（flow-infra）
+        ("", "ConfigMap"),
+    ].into_iter().map(|(group, kind)| GroupVersionKind::new(group, VERSION, kind)));
+    kinds
+}
+
+/// 宿主内置扩展类型的Scheme，类型名称为扩展类型名
+pub fn builtin_schemes() -> Vec<Scheme> {
+    builtin_kinds().into_iter()
+        .map(|gvk| {
+            let type_name = gvk.kind.clone();
+            Scheme::new(gvk, type_name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_kinds() {
+        let kinds = builtin_kinds();
+        assert!(kinds.contains(&GroupVersionKind::new("content.halo.run", "v1alpha1", "Post")));
+        assert!(kinds.contains(&GroupVersionKind::new("", "v1alpha1", "ConfigMap")));
+        let mut keys: Vec<String> = kinds.iter().map(GroupVersionKind::to_string).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), kinds.len());
+    }
+}
@@ -1,6 +1,7 @@
 use flow_api::extension::{Extension, GroupVersionKind, Metadata};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::attachment::{StorageQuota, StorageUsage};
 
 /// User实体的GVK常量
 pub const USER_GROUP: &str = "";
@@ -49,6 +50,12 @@ pub struct UserSpec {
     pub totp_encrypted_secret: Option<String>,
     pub disabled: Option<bool>,
     pub login_history_limit: Option<u32>,
+    /// 用户的附件存储配额，为空时不限制
+    #[serde(default)]
+    pub attachment_quota: Option<StorageQuota>,
+    /// 用户的首选语言（如 `zh-CN`），用于选择通知模板，为空时使用默认语言
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 impl Default for UserSpec {
@@ -66,6 +73,8 @@ impl Default for UserSpec {
             totp_encrypted_secret: None,
             disabled: Some(false),
             login_history_limit: Some(10),
+            attachment_quota: None,
+            language: None,
         }
     }
 }
@@ -74,6 +83,9 @@ impl Default for UserSpec {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UserStatus {
     pub permalink: Option<String>,
+    /// 用户上传附件的存储用量
+    #[serde(default)]
+    pub attachment_usage: Option<StorageUsage>,
 }
 
 #[cfg(test)]
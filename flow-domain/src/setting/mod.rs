@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use flow_api::extension::{Extension, GroupVersionKind, Metadata};
+
+/// Setting扩展对象
+/// 定义主题或插件设置的表单，设置值保存在对应的ConfigMap中（每个表单分组一个键，值为JSON字符串）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Setting {
+    pub metadata: Metadata,
+    pub spec: SettingSpec,
+}
+
+impl Extension for Setting {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        GroupVersionKind::new("", "v1alpha1", "Setting")
+    }
+}
+
+/// Setting规格
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingSpec {
+    /// 表单列表，每个表单对应一个设置分组
+    #[serde(default)]
+    pub forms: Vec<SettingForm>,
+}
+
+/// 设置表单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingForm {
+    /// 分组名称（ConfigMap中的键）
+    pub group: String,
+
+    /// 显示名称
+    pub label: Option<String>,
+
+    /// FormKit表单定义，每个字段包含 `$formkit`、`name`、`value`（默认值）和 `validation` 等属性
+    #[serde(rename = "formSchema", default)]
+    pub form_schema: Vec<serde_json::Value>,
+}
@@ -2,6 +2,9 @@ use flow_api::extension::{Extension, GroupVersionKind, Metadata};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+/// 文章、页面或分类记录所选自定义模板的注解（值为模板文件名，可省略.html后缀）
+pub const CUSTOM_TEMPLATE_ANNO: &str = "theme.halo.run/custom-template";
+
 /// Theme扩展对象
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theme {
@@ -63,6 +66,10 @@ pub struct ThemeSpec {
     
     /// 自定义模板
     pub custom_templates: Option<CustomTemplates>,
+    
+    /// 模板引擎名称（如 `tera`、`handlebars`），未指定时使用默认的Tera
+    #[serde(default)]
+    pub template_engine: Option<String>,
 }
 
 fn default_wildcard() -> String {
@@ -94,6 +101,34 @@ pub struct CustomTemplates {
     pub page: Option<Vec<TemplateDescriptor>>,
 }
 
+/// 自定义模板适用的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomTemplateKind {
+    Post,
+    Category,
+    Page,
+}
+
+impl CustomTemplates {
+    /// 获取指定内容类型可用的自定义模板
+    pub fn templates(&self, kind: CustomTemplateKind) -> &[TemplateDescriptor] {
+        let templates = match kind {
+            CustomTemplateKind::Post => &self.post,
+            CustomTemplateKind::Category => &self.category,
+            CustomTemplateKind::Page => &self.page,
+        };
+        templates.as_deref().unwrap_or_default()
+    }
+    
+    /// 查找内容所选的自定义模板，按模板文件匹配（可省略.html后缀）
+    pub fn find(&self, kind: CustomTemplateKind, chosen: &str) -> Option<&TemplateDescriptor> {
+        let chosen = chosen.trim();
+        self.templates(kind).iter()
+            .find(|t| t.file == chosen || t.file.strip_suffix(".html") == Some(chosen))
+    }
+}
+
 /// 模板描述符
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateDescriptor {
@@ -154,11 +189,19 @@ pub struct ThemeContext {
     
     /// 是否激活
     pub active: bool,
+    
+    /// 主题使用的模板引擎名称，为None时使用默认引擎
+    pub template_engine: Option<String>,
 }
 
 impl ThemeContext {
     pub fn new(name: String, path: std::path::PathBuf, active: bool) -> Self {
-        Self { name, path, active }
+        Self { name, path, active, template_engine: None }
+    }
+    
+    pub fn with_template_engine(mut self, template_engine: Option<String>) -> Self {
+        self.template_engine = template_engine.filter(|e| !e.is_empty());
+        self
     }
 }
 
@@ -0,0 +1,267 @@
+use super::{AttachmentStorage, ByteStream, range_header, range_response_stream, response_stream};
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode, Url};
+use serde::Deserialize;
+use sha1::Sha1;
+use std::path::Path;
+use std::time::Duration;
+
+/// 阿里云OSS存储策略模板名称
+pub const ALIYUN_OSS_TEMPLATE: &str = "aliyun-oss";
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+fn default_protocol() -> String {
+    "https".to_string()
+}
+
+/// 阿里云OSS存储策略配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliyunOssConfig {
+    pub bucket: String,
+
+    /// 外网Endpoint，如 oss-cn-hangzhou.aliyuncs.com
+    pub endpoint: String,
+
+    /// 内网Endpoint，如 oss-cn-hangzhou-internal.aliyuncs.com
+    pub internal_endpoint: Option<String>,
+
+    /// 是否通过内网Endpoint上传、读取和删除文件（服务器与Bucket同地域时可免流量费）
+    #[serde(default)]
+    pub use_internal_endpoint: bool,
+
+    pub access_key_id: String,
+    pub access_key_secret: String,
+
+    /// 文件在Bucket中的目录前缀
+    #[serde(default)]
+    pub location: String,
+
+    /// 自定义访问域名（如CDN域名），为空时使用外网Endpoint
+    pub domain: Option<String>,
+
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+}
+
+/// 阿里云OSS存储实现
+pub struct AliyunOssStorage {
+    config: AliyunOssConfig,
+    client: reqwest::Client,
+}
+
+impl AliyunOssStorage {
+    pub fn new(config: AliyunOssConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// 对象在Bucket中的Key（目录前缀 + 相对路径）
+    fn object_key(&self, path: &Path) -> String {
+        let relative = path.to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches('/');
+        let location = self.config.location.trim_matches('/');
+        if location.is_empty() {
+            relative.to_string()
+        } else {
+            format!("{}/{}", location, relative)
+        }
+    }
+
+    /// 服务端调用OSS API使用的Endpoint
+    fn api_endpoint(&self) -> &str {
+        match &self.config.internal_endpoint {
+            Some(internal) if self.config.use_internal_endpoint && !internal.is_empty() => internal,
+            _ => &self.config.endpoint,
+        }
+    }
+
+    fn object_url(&self, host: &str, key: &str) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}://{}", self.config.protocol, host))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid OSS host: {}", host))?
+            .extend(key.split('/'));
+        Ok(url)
+    }
+
+    fn api_url(&self, key: &str) -> Result<Url> {
+        self.object_url(&format!("{}.{}", self.config.bucket, self.api_endpoint()), key)
+    }
+
+    /// 规范化资源路径 /bucket/key
+    fn canonical_resource(&self, key: &str) -> String {
+        format!("/{}/{}", self.config.bucket, key)
+    }
+
+    /// 构建签名请求
+    fn signed_request(&self, method: Method, key: &str, content_type: &str) -> Result<reqwest::RequestBuilder> {
+        let date = chrono::Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let string_to_sign = format!(
+            "{}\n\n{}\n{}\n{}",
+            method.as_str(), content_type, date, self.canonical_resource(key)
+        );
+        let signature = sign(&self.config.access_key_secret, &string_to_sign)?;
+
+        Ok(self.client.request(method, self.api_url(key)?)
+            .header("Date", date)
+            .header("Authorization", format!("OSS {}:{}", self.config.access_key_id, signature)))
+    }
+
+    /// 发送签名请求，body为内容流及其长度
+    async fn send(&self, method: Method, key: &str, body: Option<(ByteStream, u64)>) -> Result<reqwest::Response> {
+        let content_type = if body.is_some() { DEFAULT_CONTENT_TYPE } else { "" };
+        let mut request = self.signed_request(method, key, content_type)?;
+        if let Some((content, size)) = body {
+            request = request
+                .header("Content-Type", content_type)
+                .header("Content-Length", size)
+                .body(reqwest::Body::wrap_stream(content));
+        }
+        Ok(request.send().await?)
+    }
+}
+
+/// 使用HMAC-SHA1计算OSS签名
+pub fn sign(secret: &str, string_to_sign: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Invalid access key secret: {}", e))?;
+    mac.update(string_to_sign.as_bytes());
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+async fn ensure_success(response: reqwest::Response, action: &str, key: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    anyhow::bail!("Failed to {} OSS object {}: {} {}", action, key, status, body)
+}
+
+#[async_trait]
+impl AttachmentStorage for AliyunOssStorage {
+    async fn save(&self, content: ByteStream, size: u64, path: &Path) -> Result<()> {
+        let key = self.object_key(path);
+        let response = self.send(Method::PUT, &key, Some((content, size))).await?;
+        ensure_success(response, "upload", &key).await?;
+        Ok(())
+    }
+
+    async fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        let key = self.object_key(path);
+        let response = self.send(Method::GET, &key, None).await?;
+        let response = ensure_success(response, "read", &key).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<ByteStream> {
+        let key = self.object_key(path);
+        let response = self.send(Method::GET, &key, None).await?;
+        Ok(response_stream(ensure_success(response, "read", &key).await?))
+    }
+
+    async fn read_range(&self, path: &Path, start: u64, len: u64) -> Result<ByteStream> {
+        let key = self.object_key(path);
+        let response = self.signed_request(Method::GET, &key, "")?
+            .header("Range", range_header(start, len))
+            .send().await?;
+        Ok(range_response_stream(ensure_success(response, "read", &key).await?, start, len))
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        let key = self.object_key(path);
+        let response = self.send(Method::DELETE, &key, None).await?;
+        // 删除不存在的对象也视为成功
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        ensure_success(response, "delete", &key).await?;
+        Ok(())
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        let key = self.object_key(path);
+        match self.send(Method::HEAD, &key, None).await {
+            Ok(response) => response.status().is_success(),
+            Err(e) => {
+                tracing::warn!("Failed to check OSS object {}: {}", key, e);
+                false
+            }
+        }
+    }
+
+    async fn size(&self, path: &Path) -> Result<u64> {
+        let key = self.object_key(path);
+        let response = self.send(Method::HEAD, &key, None).await?;
+        let response = ensure_success(response, "stat", &key).await?;
+        response.headers().get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| anyhow::anyhow!("Missing content length of OSS object {}", key))
+    }
+
+    fn permalink(&self, path: &Path) -> Option<String> {
+        let key = self.object_key(path);
+        let host = match self.config.domain.as_deref().filter(|d| !d.is_empty()) {
+            Some(domain) => domain.to_string(),
+            None => format!("{}.{}", self.config.bucket, self.config.endpoint),
+        };
+        self.object_url(&host, &key).ok().map(String::from)
+    }
+
+    async fn signed_url(&self, path: &Path, expires_in: Duration) -> Result<Option<String>> {
+        let key = self.object_key(path);
+        let expires = chrono::Utc::now().timestamp() + expires_in.as_secs() as i64;
+        let string_to_sign = format!("GET\n\n\n{}\n{}", expires, self.canonical_resource(&key));
+        let signature = sign(&self.config.access_key_secret, &string_to_sign)?;
+
+        let mut url = self.object_url(&format!("{}.{}", self.config.bucket, self.config.endpoint), &key)?;
+        url.query_pairs_mut()
+            .append_pair("OSSAccessKeyId", &self.config.access_key_id)
+            .append_pair("Expires", &expires.to_string())
+            .append_pair("Signature", &signature);
+        Ok(Some(url.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage(domain: Option<&str>) -> AliyunOssStorage {
+        AliyunOssStorage::new(AliyunOssConfig {
+            bucket: "flow".to_string(),
+            endpoint: "oss-cn-hangzhou.aliyuncs.com".to_string(),
+            internal_endpoint: Some("oss-cn-hangzhou-internal.aliyuncs.com".to_string()),
+            use_internal_endpoint: true,
+            access_key_id: "id".to_string(),
+            access_key_secret: "secret".to_string(),
+            location: "/blog/".to_string(),
+            domain: domain.map(str::to_string),
+            protocol: default_protocol(),
+        })
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 2202 HMAC-SHA1 测试向量
+        let signature = sign("Jefe", "what do ya want for nothing?").unwrap();
+        assert_eq!(signature, "7/zfauXrL6LSdBbV8YTfnCWafHk=");
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let oss = storage(None);
+        let path = Path::new("2024/a b.png");
+        assert_eq!(oss.object_key(path), "blog/2024/a b.png");
+        assert_eq!(oss.api_url(&oss.object_key(path)).unwrap().as_str(), "https://flow.oss-cn-hangzhou-internal.aliyuncs.com/blog/2024/a%20b.png");
+        assert_eq!(oss.permalink(path).unwrap(), "https://flow.oss-cn-hangzhou.aliyuncs.com/blog/2024/a%20b.png");
+        assert_eq!(storage(Some("cdn.example.com")).permalink(path).unwrap(), "https://cdn.example.com/blog/2024/a%20b.png");
+    }
+}
@@ -1,26 +1,93 @@
 pub mod storage;
+pub mod aliyun_oss;
+pub mod gcs;
+pub mod webdav;
 pub mod resource_mapping;
 pub mod file_validator;
 
-pub use storage::LocalAttachmentStorage;
+pub use storage::{LocalAttachmentStorage, file_stream, slice_stream};
+pub use aliyun_oss::{AliyunOssStorage, AliyunOssConfig};
+pub use gcs::{GcsStorage, GcsConfig};
+pub use webdav::{WebDavStorage, WebDavConfig};
 pub use resource_mapping::ResourceMapping;
 pub use file_validator::FileTypeValidator;
 
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use std::path::Path;
+use std::time::Duration;
+
+/// 附件内容的字节流
+pub type ByteStream = BoxStream<'static, std::io::Result<Vec<u8>>>;
+
+/// 将内存中的内容包装为只有一块的字节流
+pub fn once_stream(content: Vec<u8>) -> ByteStream {
+    Box::pin(futures_util::stream::once(async move { Ok(content) }))
+}
+
+/// 对象存储读取部分内容时使用的Range请求头
+pub(crate) fn range_header(start: u64, len: u64) -> String {
+    format!("bytes={}-{}", start, start + len.max(1) - 1)
+}
+
+/// 将HTTP响应体转换为字节流
+pub(crate) fn response_stream(response: reqwest::Response) -> ByteStream {
+    use futures_util::StreamExt;
+    response.bytes_stream()
+        .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(std::io::Error::other))
+        .boxed()
+}
+
+/// 将Range请求的响应转换为字节流，服务器忽略Range返回完整内容时自行截取
+pub(crate) fn range_response_stream(response: reqwest::Response, start: u64, len: u64) -> ByteStream {
+    if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        response_stream(response)
+    } else {
+        slice_stream(response_stream(response), start, len)
+    }
+}
+
 /// 附件存储trait
+#[async_trait]
 pub trait AttachmentStorage: Send + Sync {
-    /// 保存文件
-    fn save(&self, content: &[u8], path: &std::path::Path) -> anyhow::Result<()>;
+    /// 以流的方式保存文件，size为内容总长度（部分存储需要预先声明）
+    async fn save(&self, content: ByteStream, size: u64, path: &Path) -> anyhow::Result<()>;
     
     /// 读取文件
-    fn read(&self, path: &std::path::Path) -> anyhow::Result<Vec<u8>>;
+    async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>>;
+    
+    /// 以流的方式读取文件，默认一次性读取后返回
+    async fn read_stream(&self, path: &Path) -> anyhow::Result<ByteStream> {
+        Ok(once_stream(self.read(path).await?))
+    }
+    
+    /// 以流的方式读取文件从start开始的len个字节，默认读取整个文件后截取
+    async fn read_range(&self, path: &Path, start: u64, len: u64) -> anyhow::Result<ByteStream> {
+        let stream = self.read_stream(path).await?;
+        Ok(slice_stream(stream, start, len))
+    }
     
     /// 删除文件
-    fn delete(&self, path: &std::path::Path) -> anyhow::Result<()>;
+    async fn delete(&self, path: &Path) -> anyhow::Result<()>;
     
     /// 检查文件是否存在
-    fn exists(&self, path: &std::path::Path) -> bool;
+    async fn exists(&self, path: &Path) -> bool;
     
     /// 获取文件大小
-    fn size(&self, path: &std::path::Path) -> anyhow::Result<u64>;
+    async fn size(&self, path: &Path) -> anyhow::Result<u64>;
+    
+    /// 是否为本地存储（本地存储的文件由本站生成缩略图）
+    fn is_local(&self) -> bool {
+        false
+    }
+    
+    /// 文件的公开访问链接，返回None表示由本站提供访问
+    fn permalink(&self, _path: &Path) -> Option<String> {
+        None
+    }
+    
+    /// 生成带签名的临时访问链接，存储不支持签名时返回None
+    async fn signed_url(&self, _path: &Path, _expires_in: Duration) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
 }
-
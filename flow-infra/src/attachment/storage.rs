@@ -1,6 +1,57 @@
-use super::AttachmentStorage;
+use super::{AttachmentStorage, ByteStream};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+/// 以流的方式读取文件时每块的大小
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 以流的方式读取文件从start开始的内容，len为None时读取到文件末尾
+pub async fn file_stream(path: &Path, start: u64, len: Option<u64>) -> std::io::Result<ByteStream> {
+    let mut file = tokio::fs::File::open(path).await?;
+    if start > 0 {
+        file.seek(SeekFrom::Start(start)).await?;
+    }
+    let reader = file.take(len.unwrap_or(u64::MAX));
+    let stream = futures_util::stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        match reader.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((Ok(buffer), Some(reader)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    Ok(stream.boxed())
+}
+
+/// 截取字节流中从start开始的len个字节
+pub fn slice_stream(stream: ByteStream, start: u64, len: u64) -> ByteStream {
+    let end = start.saturating_add(len);
+    stream
+        .scan(0u64, move |offset, chunk| {
+            let item = match chunk {
+                Ok(_) if *offset >= end => None,
+                Ok(chunk) => {
+                    let chunk_start = *offset;
+                    *offset += chunk.len() as u64;
+                    let from = start.saturating_sub(chunk_start).min(chunk.len() as u64) as usize;
+                    let to = (end - chunk_start).min(chunk.len() as u64) as usize;
+                    Some(Ok(chunk[from..to].to_vec()))
+                }
+                Err(e) => Some(Err(e)),
+            };
+            futures_util::future::ready(item)
+        })
+        .filter(|chunk| futures_util::future::ready(!matches!(chunk, Ok(c) if c.is_empty())))
+        .boxed()
+}
 
 /// 本地文件存储实现
 pub struct LocalAttachmentStorage {
@@ -18,43 +69,97 @@ impl LocalAttachmentStorage {
     }
 }
 
+#[async_trait]
 impl AttachmentStorage for LocalAttachmentStorage {
-    fn save(&self, content: &[u8], path: &Path) -> Result<()> {
+    async fn save(&self, mut content: ByteStream, _size: u64, path: &Path) -> Result<()> {
         let full_path = self.build_path(path);
         
         // 创建父目录
         if let Some(parent) = full_path.parent() {
-            std::fs::create_dir_all(parent)?;
+            tokio::fs::create_dir_all(parent).await?;
         }
         
-        // 保存文件
-        std::fs::write(&full_path, content)?;
+        // 边读取边写入，写入失败时删除不完整的文件
+        let mut file = tokio::fs::File::create(&full_path).await?;
+        let result: std::io::Result<()> = async {
+            while let Some(chunk) = content.next().await {
+                file.write_all(&chunk?).await?;
+            }
+            file.flush().await
+        }.await;
+        if let Err(e) = result {
+            drop(file);
+            let _ = tokio::fs::remove_file(&full_path).await;
+            return Err(e.into());
+        }
         Ok(())
     }
     
-    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+    async fn read(&self, path: &Path) -> Result<Vec<u8>> {
         let full_path = self.build_path(path);
-        let content = std::fs::read(&full_path)?;
+        let content = tokio::fs::read(&full_path).await?;
         Ok(content)
     }
     
-    fn delete(&self, path: &Path) -> Result<()> {
+    async fn read_stream(&self, path: &Path) -> Result<ByteStream> {
+        Ok(file_stream(&self.build_path(path), 0, None).await?)
+    }
+    
+    async fn read_range(&self, path: &Path, start: u64, len: u64) -> Result<ByteStream> {
+        Ok(file_stream(&self.build_path(path), start, Some(len)).await?)
+    }
+    
+    async fn delete(&self, path: &Path) -> Result<()> {
         let full_path = self.build_path(path);
-        if full_path.exists() {
-            std::fs::remove_file(&full_path)?;
+        if tokio::fs::try_exists(&full_path).await? {
+            tokio::fs::remove_file(&full_path).await?;
         }
         Ok(())
     }
     
-    fn exists(&self, path: &Path) -> bool {
+    async fn exists(&self, path: &Path) -> bool {
         let full_path = self.build_path(path);
-        full_path.exists()
+        tokio::fs::try_exists(&full_path).await.unwrap_or(false)
     }
     
-    fn size(&self, path: &Path) -> Result<u64> {
+    async fn size(&self, path: &Path) -> Result<u64> {
         let full_path = self.build_path(path);
-        let metadata = std::fs::metadata(&full_path)?;
+        let metadata = tokio::fs::metadata(&full_path).await?;
         Ok(metadata.len())
     }
+    
+    fn is_local(&self) -> bool {
+        true
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slice_stream_across_chunks() {
+        let chunks: Vec<std::io::Result<Vec<u8>>> = vec![Ok(vec![0, 1, 2]), Ok(vec![3, 4]), Ok(vec![5, 6, 7])];
+        let stream = futures_util::stream::iter(chunks).boxed();
+        let sliced: Vec<u8> = slice_stream(stream, 2, 4)
+            .map(|chunk| chunk.unwrap())
+            .concat()
+            .await;
+        assert_eq!(sliced, vec![2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalAttachmentStorage::new(dir.path().to_path_buf());
+        let content: Vec<u8> = (0..200u8).collect();
+        let stream = futures_util::stream::iter(vec![Ok(content[..100].to_vec()), Ok(content[100..].to_vec())]).boxed();
+        storage.save(stream, content.len() as u64, Path::new("a/b.bin")).await.unwrap();
+
+        let range: Vec<u8> = storage.read_range(Path::new("a/b.bin"), 150, 10).await.unwrap()
+            .map(|chunk| chunk.unwrap())
+            .concat()
+            .await;
+        assert_eq!(range, content[150..160].to_vec());
+    }
+}
@@ -0,0 +1,198 @@
+use super::{AttachmentStorage, ByteStream, range_header, range_response_stream, response_stream};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Method, StatusCode, Url};
+use serde::Deserialize;
+use std::path::Path;
+
+/// WebDAV存储策略模板名称
+pub const WEBDAV_TEMPLATE: &str = "webdav";
+
+/// WebDAV存储策略配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavConfig {
+    /// WebDAV根目录地址，如 https://nas.example.com/remote.php/dav/files/admin/
+    pub url: String,
+
+    pub username: Option<String>,
+    pub password: Option<String>,
+
+    /// 文件在WebDAV中的目录
+    #[serde(default)]
+    pub location: String,
+
+    /// 文件的公开访问地址前缀（如反向代理地址），为空时通过本站代理访问
+    pub public_url: Option<String>,
+}
+
+/// WebDAV存储实现（适用于NAS、Nextcloud等）
+pub struct WebDavStorage {
+    config: WebDavConfig,
+    client: reqwest::Client,
+}
+
+/// 文件所在的各级目录（如 a/b/c.png 返回 ["a/", "a/b/"]）
+pub fn parent_collections(key: &str) -> Vec<String> {
+    let segments: Vec<&str> = key.split('/').collect();
+    (1..segments.len())
+        .map(|i| format!("{}/", segments[..i].join("/")))
+        .collect()
+}
+
+impl WebDavStorage {
+    pub fn new(config: WebDavConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// 文件相对WebDAV根目录的路径（目录 + 相对路径）
+    fn object_key(&self, path: &Path) -> String {
+        let relative = path.to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches('/');
+        let location = self.config.location.trim_matches('/');
+        if location.is_empty() {
+            relative.to_string()
+        } else {
+            format!("{}/{}", location, relative)
+        }
+    }
+
+    /// 将相对路径拼接到基础地址上，各段分别编码
+    fn join_url(base: &str, key: &str) -> Result<Url> {
+        let mut url = Url::parse(base)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid WebDAV url: {}", base))?
+            .pop_if_empty()
+            .extend(key.split('/'));
+        Ok(url)
+    }
+
+    fn request(&self, method: Method, key: &str) -> Result<reqwest::RequestBuilder> {
+        let request = self.client.request(method, Self::join_url(&self.config.url, key)?);
+        Ok(match &self.config.username {
+            Some(username) => request.basic_auth(username, self.config.password.as_deref()),
+            None => request,
+        })
+    }
+
+    /// 逐级创建文件所在的目录，已存在的目录会返回405，忽略即可
+    async fn ensure_collections(&self, key: &str) -> Result<()> {
+        let mkcol = Method::from_bytes(b"MKCOL")?;
+        for collection in parent_collections(key) {
+            let response = self.request(mkcol.clone(), collection.trim_end_matches('/'))?.send().await?;
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                anyhow::bail!("Failed to create WebDAV collection {}: {}", collection, status);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<reqwest::Response> {
+        let response = self.request(Method::GET, key)?.send().await?;
+        ensure_success(response, "read", key).await
+    }
+}
+
+async fn ensure_success(response: reqwest::Response, action: &str, key: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    anyhow::bail!("Failed to {} WebDAV file {}: {} {}", action, key, status, body)
+}
+
+#[async_trait]
+impl AttachmentStorage for WebDavStorage {
+    async fn save(&self, content: ByteStream, size: u64, path: &Path) -> Result<()> {
+        let key = self.object_key(path);
+        self.ensure_collections(&key).await?;
+        let response = self.request(Method::PUT, &key)?
+            .header("Content-Length", size)
+            .body(reqwest::Body::wrap_stream(content))
+            .send().await?;
+        ensure_success(response, "upload", &key).await?;
+        Ok(())
+    }
+
+    async fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        let key = self.object_key(path);
+        Ok(self.get(&key).await?.bytes().await?.to_vec())
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<ByteStream> {
+        let key = self.object_key(path);
+        Ok(response_stream(self.get(&key).await?))
+    }
+
+    async fn read_range(&self, path: &Path, start: u64, len: u64) -> Result<ByteStream> {
+        let key = self.object_key(path);
+        let response = self.request(Method::GET, &key)?
+            .header("Range", range_header(start, len))
+            .send().await?;
+        Ok(range_response_stream(ensure_success(response, "read", &key).await?, start, len))
+    }
+
+    async fn delete(&self, path: &Path) -> Result<()> {
+        let key = self.object_key(path);
+        let response = self.request(Method::DELETE, &key)?.send().await?;
+        // 删除不存在的文件也视为成功
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        ensure_success(response, "delete", &key).await?;
+        Ok(())
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        let key = self.object_key(path);
+        match self.request(Method::HEAD, &key) {
+            Ok(request) => request.send().await.is_ok_and(|r| r.status().is_success()),
+            Err(_) => false,
+        }
+    }
+
+    async fn size(&self, path: &Path) -> Result<u64> {
+        let key = self.object_key(path);
+        let response = self.request(Method::HEAD, &key)?.send().await?;
+        let response = ensure_success(response, "stat", &key).await?;
+        response.headers().get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| anyhow::anyhow!("Missing content length of WebDAV file {}", key))
+    }
+
+    fn permalink(&self, path: &Path) -> Option<String> {
+        let public_url = self.config.public_url.as_deref().filter(|u| !u.is_empty())?;
+        Self::join_url(public_url, &self.object_key(path)).ok().map(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_collections() {
+        assert_eq!(parent_collections("blog/2024/a.png"), vec!["blog/", "blog/2024/"]);
+        assert!(parent_collections("a.png").is_empty());
+    }
+
+    #[test]
+    fn test_urls() {
+        let storage = WebDavStorage::new(WebDavConfig {
+            url: "https://nas.example.com/dav/files/admin/".to_string(),
+            username: None,
+            password: None,
+            location: "flow".to_string(),
+            public_url: Some("https://files.example.com".to_string()),
+        });
+        let key = storage.object_key(Path::new("a b.png"));
+        assert_eq!(WebDavStorage::join_url(&storage.config.url, &key).unwrap().as_str(), "https://nas.example.com/dav/files/admin/flow/a%20b.png");
+        assert_eq!(storage.permalink(Path::new("a b.png")).unwrap(), "https://files.example.com/flow/a%20b.png");
+    }
+}
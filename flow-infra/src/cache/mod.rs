@@ -8,6 +8,8 @@ pub trait Cache: Send + Sync {
     async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>;
     async fn set(&self, key: &str, value: &str, ttl: Option<u64>) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
     async fn delete(&self, key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// 计数加一并返回加一后的值，键不存在时从0开始计数并设置过期时间（秒）
+    async fn incr(&self, key: &str, ttl: Option<u64>) -> Result<i64, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 /// RedisCache 使用Redis实现的缓存
@@ -59,6 +61,22 @@ impl Cache for RedisCache {
             .await?;
         Ok(())
     }
+
+    async fn incr(&self, key: &str, ttl: Option<u64>) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let count: i64 = redis::cmd("INCR")
+            .arg(key)
+            .query_async(&mut conn)
+            .await?;
+        if let (1, Some(ttl)) = (count, ttl) {
+            redis::cmd("EXPIRE")
+                .arg(key)
+                .arg(ttl)
+                .query_async::<_, ()>(&mut conn)
+                .await?;
+        }
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
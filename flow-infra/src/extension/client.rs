@@ -1,7 +1,9 @@
 use flow_api::extension::{Extension, ExtensionClient, ListOptions, ListResult};
+use flow_api::extension::watch::{ExtensionWatchSource, ExtensionWatcher, WatchEvent, WatchEventType};
 use crate::database::ExtensionRepository;
 use crate::extension::converter::{ExtensionConverter, JSONExtensionConverter};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 
@@ -9,6 +11,9 @@ use serde::{Serialize, Deserialize};
 pub struct ReactiveExtensionClient {
     repository: Arc<dyn ExtensionRepository>,
     converter: JSONExtensionConverter,
+    /// 扩展对象变更监听器
+    watchers: RwLock<Vec<(u64, Arc<dyn ExtensionWatcher>)>>,
+    next_watch_id: AtomicU64,
 }
 
 impl ReactiveExtensionClient {
@@ -16,8 +21,30 @@ impl ReactiveExtensionClient {
         Self {
             repository,
             converter: JSONExtensionConverter,
+            watchers: RwLock::new(Vec::new()),
+            next_watch_id: AtomicU64::new(0),
         }
     }
+
+    /// 通知监听器扩展对象已变更
+    fn notify(&self, event: WatchEvent) {
+        let watchers = self.watchers.read().unwrap_or_else(|e| e.into_inner());
+        for (_, watcher) in watchers.iter() {
+            watcher.on_event(&event);
+        }
+    }
+}
+
+impl ExtensionWatchSource for ReactiveExtensionClient {
+    fn watch(&self, watcher: Arc<dyn ExtensionWatcher>) -> u64 {
+        let id = self.next_watch_id.fetch_add(1, Ordering::Relaxed);
+        self.watchers.write().unwrap_or_else(|e| e.into_inner()).push((id, watcher));
+        id
+    }
+
+    fn unwatch(&self, id: u64) {
+        self.watchers.write().unwrap_or_else(|e| e.into_inner()).retain(|(watch_id, _)| *watch_id != id);
+    }
 }
 
 #[async_trait]
@@ -25,6 +52,11 @@ impl ExtensionClient for ReactiveExtensionClient {
     async fn create<E: Extension + Serialize>(&self, extension: E) -> Result<E, Box<dyn std::error::Error + Send + Sync>> {
         let store = self.converter.convert_to(&extension)?;
         self.repository.save(store).await?;
+        self.notify(WatchEvent {
+            event_type: WatchEventType::Added,
+            gvk: Some(extension.group_version_kind()),
+            name: extension.metadata().name.clone(),
+        });
         Ok(extension)
     }
 
@@ -32,12 +64,22 @@ impl ExtensionClient for ReactiveExtensionClient {
         // TODO: 实现乐观锁检查
         let store = self.converter.convert_to(&extension)?;
         self.repository.save(store).await?;
+        self.notify(WatchEvent {
+            event_type: WatchEventType::Updated,
+            gvk: Some(extension.group_version_kind()),
+            name: extension.metadata().name.clone(),
+        });
         Ok(extension)
     }
 
     async fn delete<E: Extension>(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // TODO: 根据GVK构建完整的存储名称
         self.repository.delete(name).await?;
+        self.notify(WatchEvent {
+            event_type: WatchEventType::Deleted,
+            gvk: None,
+            name: name.to_string(),
+        });
         Ok(())
     }
 
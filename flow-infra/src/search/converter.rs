@@ -1,9 +1,11 @@
 use flow_api::search::HaloDocument;
-use tantivy::schema::{Schema, Field, Value, STORED, TEXT};
+use tantivy::schema::{IndexRecordOption, Schema, Field, TextFieldIndexing, TextOptions, Value, STORED, TEXT};
 use tantivy::TantivyDocument;
 use chrono::DateTime;
 use std::collections::HashMap;
 
+use super::tokenizer::{CJK_TOKENIZER, KEYWORD_TOKENIZER, PREFIX_TOKENIZER};
+
 /// 将HaloDocument转换为Tantivy Document的转换器
 pub struct HaloDocumentConverter {
     schema: Schema,
@@ -23,6 +25,8 @@ pub struct HaloDocumentConverter {
     pub creation_timestamp_field: Field,
     pub update_timestamp_field: Field,
     pub permalink_field: Field,
+    pub title_prefix_field: Field,
+    pub tag_keyword_field: Field,
 }
 
 impl HaloDocumentConverter {
@@ -41,10 +45,17 @@ impl HaloDocumentConverter {
         let published_field = schema_builder.add_text_field("published", TEXT | STORED);
         let permalink_field = schema_builder.add_text_field("permalink", TEXT | STORED);
         
-        // 文本字段（分词，用于搜索）
-        let title_field = schema_builder.add_text_field("title", TEXT | STORED);
-        let description_field = schema_builder.add_text_field("description", TEXT | STORED);
-        let content_field = schema_builder.add_text_field("content", TEXT | STORED);
+        // 文本字段（中日韩分词，用于搜索）
+        let cjk_text = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(CJK_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+            )
+            .set_stored();
+        let title_field = schema_builder.add_text_field("title", cjk_text.clone());
+        let description_field = schema_builder.add_text_field("description", cjk_text.clone());
+        let content_field = schema_builder.add_text_field("content", cjk_text);
         
         // 文本字段（用于存储annotations的JSON字符串）
         let annotations_field = schema_builder.add_text_field("annotations", TEXT | STORED);
@@ -53,6 +64,16 @@ impl HaloDocumentConverter {
         let creation_timestamp_field = schema_builder.add_i64_field("creationTimestamp", STORED);
         let update_timestamp_field = schema_builder.add_i64_field("updateTimestamp", STORED);
         
+        // 搜索建议字段（只建索引）：标题中每个词的前缀，以及整个标签名称
+        let indexed_with = |tokenizer: &str, option: IndexRecordOption| TextOptions::default()
+            .set_indexing_options(TextFieldIndexing::default().set_tokenizer(tokenizer).set_index_option(option));
+        let title_prefix_field = schema_builder.add_text_field(
+            "titlePrefix", indexed_with(PREFIX_TOKENIZER, IndexRecordOption::WithFreqs)
+        );
+        let tag_keyword_field = schema_builder.add_text_field(
+            "tagKeyword", indexed_with(KEYWORD_TOKENIZER, IndexRecordOption::Basic)
+        );
+        
         let schema = schema_builder.build();
         
         Self {
@@ -73,6 +94,8 @@ impl HaloDocumentConverter {
             creation_timestamp_field,
             update_timestamp_field,
             permalink_field,
+            title_prefix_field,
+            tag_keyword_field,
         }
     }
     
@@ -88,6 +111,7 @@ impl HaloDocumentConverter {
         doc.add_text(self.doc_type_field, &halo_doc.doc_type);
         doc.add_text(self.owner_name_field, &halo_doc.owner_name);
         doc.add_text(self.title_field, &halo_doc.title);
+        doc.add_text(self.title_prefix_field, &halo_doc.title);
         doc.add_text(self.content_field, &halo_doc.content);
         doc.add_text(self.recycled_field, &halo_doc.recycled.to_string());
         doc.add_text(self.exposed_field, &halo_doc.exposed.to_string());
@@ -107,6 +131,7 @@ impl HaloDocumentConverter {
         if let Some(tags) = &halo_doc.tags {
             for tag in tags {
                 doc.add_text(self.tag_field, tag);
+                doc.add_text(self.tag_keyword_field, tag);
             }
         }
         
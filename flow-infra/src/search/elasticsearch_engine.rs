@@ -0,0 +1,478 @@
+use flow_api::search::{HaloDocument, SearchEngine, SearchOption, SearchResult, SortField, SortOrder, Suggestion, SuggestionType};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+/// 导出文档时每页的数量
+const SCROLL_PAGE_SIZE: usize = 1000;
+
+/// Elasticsearch/OpenSearch 搜索引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElasticsearchConfig {
+    /// 集群地址，如 http://localhost:9200
+    pub url: String,
+
+    /// 索引名称
+    #[serde(default = "default_index")]
+    pub index: String,
+
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+fn default_index() -> String {
+    "halo".to_string()
+}
+
+/// 索引映射：标题、描述和内容全文检索，其余字段用于过滤和排序
+pub fn index_mapping() -> Value {
+    json!({
+        "mappings": {
+            "properties": {
+                "id": { "type": "keyword" },
+                "metadata_name": { "type": "keyword" },
+                "annotations": { "type": "object", "enabled": false },
+                "title": { "type": "text", "fields": { "keyword": { "type": "keyword", "ignore_above": 256 } } },
+                "description": { "type": "text" },
+                "content": { "type": "text" },
+                "categories": { "type": "keyword" },
+                "tags": { "type": "keyword" },
+                "published": { "type": "boolean" },
+                "recycled": { "type": "boolean" },
+                "exposed": { "type": "boolean" },
+                "owner_name": { "type": "keyword" },
+                "creation_timestamp": { "type": "date" },
+                "update_timestamp": { "type": "date" },
+                "permalink": { "type": "keyword", "index": false },
+                "doc_type": { "type": "keyword" },
+            }
+        }
+    })
+}
+
+/// 把搜索选项转换为查询请求体
+///
+/// 关键词对标题、描述和内容做 multi_match，过滤条件放在 bool 查询的 filter 中：
+/// 类型和所有者满足任意一个即可，分类和标签必须全部满足
+pub fn build_query(option: &SearchOption) -> Value {
+    let mut filters = Vec::new();
+    for (field, value) in [
+        ("exposed", option.filter_exposed),
+        ("recycled", option.filter_recycled),
+        ("published", option.filter_published),
+    ] {
+        if let Some(value) = value {
+            filters.push(json!({ "term": { field: value } }));
+        }
+    }
+    for (field, values) in [("doc_type", &option.include_types), ("owner_name", &option.include_owner_names)] {
+        if let Some(values) = values.as_ref().filter(|values| !values.is_empty()) {
+            filters.push(json!({ "terms": { field: values } }));
+        }
+    }
+    for (field, values) in [("categories", &option.include_category_names), ("tags", &option.include_tag_names)] {
+        for value in values.iter().flatten() {
+            filters.push(json!({ "term": { field: value } }));
+        }
+    }
+
+    let keyword = option.keyword.trim();
+    let must = if keyword.is_empty() {
+        json!({ "match_all": {} })
+    } else {
+        json!({
+            "multi_match": {
+                "query": keyword,
+                "fields": ["title^5", "description^2.5", "content"],
+            }
+        })
+    };
+
+    let order = match option.sort_order {
+        SortOrder::Asc => "asc",
+        SortOrder::Desc => "desc",
+    };
+    let sort = match option.sort_by {
+        Some(SortField::Relevance) | None => json!(["_score"]),
+        Some(SortField::CreationTime) => json!([{ "creation_timestamp": { "order": order, "missing": "_last" } }]),
+        Some(SortField::UpdateTime) => json!([{ "update_timestamp": { "order": order, "missing": "_last" } }]),
+        Some(SortField::Title) => json!([{ "title.keyword": { "order": order } }]),
+    };
+
+    let mut body = json!({
+        "size": option.limit,
+        "track_total_hits": true,
+        "query": { "bool": { "must": [must], "filter": filters } },
+        "sort": sort,
+    });
+    if !keyword.is_empty() {
+        body["highlight"] = json!({
+            "pre_tags": [option.highlight_pre_tag],
+            "post_tags": [option.highlight_post_tag],
+            "fields": {
+                "title": { "number_of_fragments": 0 },
+                "description": { "number_of_fragments": 0 },
+                "content": { "fragment_size": 200, "number_of_fragments": 1 },
+            }
+        });
+    }
+    body
+}
+
+/// 只匹配公开文档（已发布、公开且未回收）的过滤条件
+fn public_filters() -> Value {
+    json!([
+        { "term": { "published": true } },
+        { "term": { "exposed": true } },
+        { "term": { "recycled": false } },
+    ])
+}
+
+/// 转义正则表达式（Lucene语法）中的保留字符
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if ".?+*|{}[]()\"\\#@&<>~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 搜索建议的请求体
+///
+/// 标题用 match_bool_prefix 匹配（最后一个词按前缀），标签在所有公开文档中按前缀聚合，按文档数量排序
+pub fn build_suggest_query(keyword: &str, limit: u32) -> Value {
+    json!({
+        "size": limit * 2,
+        "_source": ["title", "permalink"],
+        "query": {
+            "bool": {
+                "must": [{ "match_bool_prefix": { "title": keyword } }],
+                "filter": public_filters(),
+            }
+        },
+        "aggs": {
+            "all": {
+                "global": {},
+                "aggs": {
+                    "public": {
+                        "filter": { "bool": { "filter": public_filters() } },
+                        "aggs": {
+                            "tags": {
+                                "terms": { "field": "tags", "include": format!("{}.*", escape_regex(keyword)), "size": limit }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// 解析搜索建议的响应，标题相同的只保留一条
+pub fn parse_suggestions(response: &Value, limit: u32) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for hit in response["hits"]["hits"].as_array().into_iter().flatten() {
+        let Some(title) = hit["_source"]["title"].as_str() else {
+            continue;
+        };
+        if suggestions.len() < limit as usize && !suggestions.iter().any(|s| s.text == title) {
+            suggestions.push(Suggestion {
+                text: title.to_string(),
+                suggestion_type: SuggestionType::Title,
+                permalink: hit["_source"]["permalink"].as_str().map(str::to_string),
+            });
+        }
+    }
+    let buckets = response["aggregations"]["all"]["public"]["tags"]["buckets"].as_array();
+    for bucket in buckets.into_iter().flatten() {
+        if let Some(tag) = bucket["key"].as_str() {
+            suggestions.push(Suggestion { text: tag.to_string(), suggestion_type: SuggestionType::Tag, permalink: None });
+        }
+    }
+    suggestions
+}
+
+/// 解析搜索响应，返回命中的文档（高亮片段替换原字段）和命中总数
+pub fn parse_hits(response: &Value) -> Result<(Vec<HaloDocument>, u64)> {
+    let total = response["hits"]["total"]["value"].as_u64()
+        .or_else(|| response["hits"]["total"].as_u64())
+        .unwrap_or(0);
+    let mut documents = Vec::new();
+    for hit in response["hits"]["hits"].as_array().into_iter().flatten() {
+        let mut document: HaloDocument = serde_json::from_value(hit["_source"].clone())?;
+        let fragment = |field: &str| hit["highlight"][field][0].as_str().map(str::to_string);
+        if let Some(title) = fragment("title") {
+            document.title = title;
+        }
+        if let Some(description) = fragment("description") {
+            document.description = Some(description);
+        }
+        if let Some(content) = fragment("content") {
+            document.content = content;
+        }
+        documents.push(document);
+    }
+    Ok((documents, total))
+}
+
+/// 批量写入请求体（NDJSON），每个文档一行操作和一行文档
+pub fn bulk_index_body(index: &str, documents: &[HaloDocument]) -> Result<String> {
+    let mut body = String::new();
+    for document in documents {
+        body.push_str(&json!({ "index": { "_index": index, "_id": document.id } }).to_string());
+        body.push('\n');
+        body.push_str(&serde_json::to_string(document)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// 基于 Elasticsearch/OpenSearch REST API 的搜索引擎，适用于已有集群的部署
+pub struct ElasticsearchSearchEngine {
+    config: ElasticsearchConfig,
+    client: reqwest::Client,
+}
+
+impl ElasticsearchSearchEngine {
+    /// 创建搜索引擎实例，索引不存在时按映射创建
+    pub async fn new(config: ElasticsearchConfig) -> Result<Self> {
+        let engine = Self { config, client: reqwest::Client::new() };
+        engine.ensure_index().await?;
+        Ok(engine)
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/{}", self.config.url.trim_end_matches('/'), path.trim_start_matches('/'));
+        let request = self.client.request(method, url);
+        match &self.config.username {
+            Some(username) => request.basic_auth(username, self.config.password.as_deref()),
+            None => request,
+        }
+    }
+
+    async fn ensure_index(&self) -> Result<()> {
+        let index = &self.config.index;
+        let response = self.request(Method::HEAD, index).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            let response = self.request(Method::PUT, index).json(&index_mapping()).send().await?;
+            ensure_success(response, "create index").await?;
+            info!("Created search index {} at {}", index, self.config.url);
+        } else {
+            ensure_success(response, "check index").await?;
+        }
+        Ok(())
+    }
+
+    /// 发送批量请求，任一操作失败时返回第一个错误
+    async fn bulk(&self, body: String) -> Result<()> {
+        let response = self.request(Method::POST, "_bulk?refresh=wait_for")
+            .header("Content-Type", "application/x-ndjson")
+            .body(body)
+            .send().await?;
+        let result: Value = ensure_success(response, "bulk index").await?.json().await?;
+        if result["errors"].as_bool().unwrap_or(false) {
+            let error = result["items"].as_array().into_iter().flatten()
+                .filter_map(|item| item.as_object().and_then(|item| item.values().next()))
+                .find_map(|operation| operation.get("error"))
+                .cloned()
+                .unwrap_or(Value::Null);
+            anyhow::bail!("Bulk request to {} failed: {}", self.config.index, error);
+        }
+        Ok(())
+    }
+
+    async fn query(&self, body: &Value) -> Result<Value> {
+        let response = self.request(Method::POST, &format!("{}/_search", self.config.index))
+            .json(body)
+            .send().await?;
+        Ok(ensure_success(response, "search").await?.json().await?)
+    }
+}
+
+async fn ensure_success(response: reqwest::Response, action: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    anyhow::bail!("Failed to {}: {} {}", action, status, body)
+}
+
+#[async_trait]
+impl SearchEngine for ElasticsearchSearchEngine {
+    fn available(&self) -> bool {
+        true
+    }
+
+    async fn add_or_update(&self, documents: Vec<HaloDocument>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+        self.bulk(bulk_index_body(&self.config.index, &documents)?).await?;
+        Ok(())
+    }
+
+    async fn delete_document(&self, doc_ids: Vec<String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if doc_ids.is_empty() {
+            return Ok(());
+        }
+        let mut body = String::new();
+        for id in &doc_ids {
+            body.push_str(&json!({ "delete": { "_index": self.config.index, "_id": id } }).to_string());
+            body.push('\n');
+        }
+        self.bulk(body).await?;
+        Ok(())
+    }
+
+    async fn delete_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let response = self.request(Method::POST, &format!("{}/_delete_by_query?refresh=true", self.config.index))
+            .json(&json!({ "query": { "match_all": {} } }))
+            .send().await?;
+        ensure_success(response, "delete all documents").await?;
+        Ok(())
+    }
+
+    async fn search(&self, option: SearchOption) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+        let start_time = std::time::Instant::now();
+        let response = self.query(&build_query(&option)).await?;
+        let (hits, total) = parse_hits(&response)?;
+        Ok(SearchResult {
+            hits,
+            keyword: option.keyword,
+            total,
+            limit: option.limit,
+            processing_time_millis: start_time.elapsed().as_millis() as u64,
+            from_cache: false,
+            cache_stats: None,
+        })
+    }
+
+    async fn documents(&self) -> Result<Vec<HaloDocument>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut documents = Vec::new();
+        let mut search_after: Option<Value> = None;
+        loop {
+            let mut body = json!({
+                "size": SCROLL_PAGE_SIZE,
+                "query": { "match_all": {} },
+                "sort": [{ "id": "asc" }],
+            });
+            if let Some(after) = search_after.take() {
+                body["search_after"] = after;
+            }
+            let response = self.query(&body).await?;
+            let (page, _) = parse_hits(&response)?;
+            let count = page.len();
+            search_after = response["hits"]["hits"].as_array()
+                .and_then(|hits| hits.last())
+                .map(|hit| hit["sort"].clone());
+            documents.extend(page);
+            if count < SCROLL_PAGE_SIZE || search_after.is_none() {
+                break;
+            }
+        }
+        Ok(documents)
+    }
+
+    async fn suggest(&self, keyword: &str, limit: u32) -> Result<Vec<Suggestion>, Box<dyn std::error::Error + Send + Sync>> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let response = self.query(&build_suggest_query(keyword, limit)).await?;
+        Ok(parse_suggestions(&response, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(keyword: &str) -> SearchOption {
+        serde_json::from_value(json!({ "keyword": keyword })).unwrap()
+    }
+
+    #[test]
+    fn test_build_query() {
+        let mut option = option("rust");
+        option.filter_published = Some(true);
+        option.include_types = Some(vec!["post.content.halo.run".to_string(), "singlepage.content.halo.run".to_string()]);
+        option.include_tag_names = Some(vec!["a".to_string(), "b".to_string()]);
+        option.sort_by = Some(SortField::CreationTime);
+        let body = build_query(&option);
+        assert_eq!(body["query"]["bool"]["must"][0]["multi_match"]["query"], "rust");
+        assert_eq!(body["query"]["bool"]["filter"], json!([
+            { "term": { "published": true } },
+            { "terms": { "doc_type": ["post.content.halo.run", "singlepage.content.halo.run"] } },
+            { "term": { "tags": "a" } },
+            { "term": { "tags": "b" } },
+        ]));
+        assert_eq!(body["sort"][0]["creation_timestamp"]["order"], "desc");
+        assert_eq!(body["highlight"]["pre_tags"][0], option.highlight_pre_tag);
+
+        let body = build_query(&self::option(""));
+        assert!(body["query"]["bool"]["must"][0]["match_all"].is_object());
+        assert!(body.get("highlight").is_none());
+    }
+
+    #[test]
+    fn test_parse_hits() {
+        let response = json!({
+            "hits": {
+                "total": { "value": 7, "relation": "eq" },
+                "hits": [{
+                    "_id": "post.content.halo.run-hello",
+                    "_source": {
+                        "id": "post.content.halo.run-hello",
+                        "metadata_name": "hello",
+                        "annotations": null,
+                        "title": "Hello Rust",
+                        "description": null,
+                        "content": "Rust is fast",
+                        "categories": null,
+                        "tags": ["a"],
+                        "published": true,
+                        "recycled": false,
+                        "exposed": true,
+                        "owner_name": "admin",
+                        "creation_timestamp": null,
+                        "update_timestamp": null,
+                        "permalink": "/archives/hello",
+                        "doc_type": "post.content.halo.run",
+                    },
+                    "highlight": { "title": ["Hello <B>Rust</B>"] },
+                }],
+            }
+        });
+        let (hits, total) = parse_hits(&response).unwrap();
+        assert_eq!(total, 7);
+        assert_eq!(hits[0].title, "Hello <B>Rust</B>");
+        assert_eq!(hits[0].content, "Rust is fast");
+    }
+
+    #[test]
+    fn test_suggest_query() {
+        let body = build_suggest_query("c++ ru", 5);
+        assert_eq!(body["query"]["bool"]["must"][0]["match_bool_prefix"]["title"], "c++ ru");
+        let tags = &body["aggs"]["all"]["aggs"]["public"]["aggs"]["tags"]["terms"];
+        assert_eq!(tags["include"], "c\\+\\+ ru.*");
+
+        let response = json!({
+            "hits": { "hits": [
+                { "_source": { "title": "Rust", "permalink": "/archives/rust" } },
+                { "_source": { "title": "Rust", "permalink": "/archives/rust-2" } },
+            ] },
+            "aggregations": { "all": { "public": { "tags": { "buckets": [{ "key": "rust", "doc_count": 2 }] } } } },
+        });
+        let suggestions = parse_suggestions(&response, 5);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].permalink.as_deref(), Some("/archives/rust"));
+        assert_eq!(suggestions[1].suggestion_type, SuggestionType::Tag);
+    }
+}
@@ -1,9 +1,13 @@
 pub mod tantivy_engine;
 pub mod converter;
+pub mod elasticsearch_engine;
+pub mod tokenizer;
 
 #[cfg(test)]
 mod tests;
 
 pub use tantivy_engine::TantivySearchEngine;
+pub use elasticsearch_engine::{ElasticsearchConfig, ElasticsearchSearchEngine};
+pub use tokenizer::{CjkTokenizer, CjkTokenizerConfig, CJK_TOKENIZER, KEYWORD_TOKENIZER, PREFIX_TOKENIZER};
 pub use converter::{HaloDocumentConverter, DocumentConverter};
 
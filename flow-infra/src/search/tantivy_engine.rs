@@ -1,12 +1,13 @@
-use flow_api::search::{HaloDocument, SearchOption, SearchResult, SearchEngine, SortField, SortOrder};
+use flow_api::search::{HaloDocument, SearchOption, SearchResult, SearchEngine, SortField, SortOrder, Suggestion, SuggestionType};
 use tantivy::{
-    collector::TopDocs,
+    collector::{Count, DocSetCollector, TopDocs},
     directory::MmapDirectory,
-    query::{BooleanQuery, Occur, QueryParser, TermQuery},
+    query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, TermQuery},
     schema::{Field, IndexRecordOption, Value},
-    Index, IndexReader, IndexWriter, ReloadPolicy, Searcher, Term,
+    Index, IndexReader, IndexWriter, ReloadPolicy, Searcher, TantivyError, Term,
     snippet::SnippetGenerator,
 };
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 use tokio::sync::RwLock as AsyncRwLock;
@@ -14,6 +15,10 @@ use anyhow::{Context, Result};
 use tracing::{debug, info};
 
 use super::converter::{HaloDocumentConverter, DocumentConverter};
+use super::tokenizer::{prefixes, CjkTokenizer, CJK_TOKENIZER};
+
+/// 标签建议最多检查的候选标签数量
+const MAX_TAG_CANDIDATES: usize = 100;
 
 /// Tantivy搜索引擎实现
 pub struct TantivySearchEngine {
@@ -36,8 +41,13 @@ pub struct TantivySearchEngine {
 }
 
 impl TantivySearchEngine {
-    /// 创建新的Tantivy搜索引擎实例
+    /// 创建新的Tantivy搜索引擎实例，中文使用jieba分词
     pub async fn new(index_path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_tokenizer(index_path, CjkTokenizer::new()).await
+    }
+
+    /// 使用指定的中日韩分词器创建搜索引擎实例
+    pub async fn with_tokenizer(index_path: impl AsRef<Path>, tokenizer: CjkTokenizer) -> Result<Self> {
         let index_path = index_path.as_ref();
         
         // 确保索引目录存在
@@ -51,8 +61,12 @@ impl TantivySearchEngine {
         let directory = MmapDirectory::open(index_path)
             .context("Failed to open index directory")?;
         
-        let index = Index::open_or_create(directory, schema.clone())
-            .context("Failed to open or create index")?;
+        let index = match Index::open_or_create(directory, schema.clone()) {
+            Ok(index) => index,
+            Err(TantivyError::SchemaError(_)) => rebuild_index(index_path, &converter, &tokenizer)?,
+            Err(e) => return Err(e).context("Failed to open or create index"),
+        };
+        tokenizer.register(&index);
         
         let reader = Arc::new(RwLock::new(
             index.reader_builder()
@@ -115,6 +129,82 @@ impl TantivySearchEngine {
         let reader_guard = self.reader.read().unwrap();
         Ok(reader_guard.searcher())
     }
+    
+    /// 只匹配公开文档（已发布、公开且未回收）的过滤条件
+    fn public_clauses(&self) -> Vec<(Occur, Box<dyn Query>)> {
+        [(self.published_field, "true"), (self.exposed_field, "true"), (self.recycled_field, "false")]
+            .into_iter()
+            .map(|(field, value)| {
+                let query: Box<dyn Query> = Box::new(TermQuery::new(Term::from_field_text(field, value), IndexRecordOption::Basic));
+                (Occur::Must, query)
+            })
+            .collect()
+    }
+    
+    /// 标题中每个关键词都作为词的前缀出现的公开文档，按相关性排序，标题相同的只保留一条
+    fn suggest_titles(&self, searcher: &Searcher, keyword: &str, limit: usize) -> Result<Vec<Suggestion>> {
+        let mut analyzer = self.index.tokenizers().get(CJK_TOKENIZER)
+            .context("Search tokenizer is not registered")?;
+        let mut words = BTreeSet::new();
+        analyzer.token_stream(keyword).process(&mut |token| {
+            if let Some(prefix) = prefixes(&token.text).last() {
+                words.insert(prefix.to_string());
+            }
+        });
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut clauses = self.public_clauses();
+        for word in words {
+            let term = Term::from_field_text(self.converter.title_prefix_field, &word);
+            clauses.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))));
+        }
+        let top_docs = searcher.search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit * 2))?;
+        let mut titles = BTreeSet::new();
+        let mut suggestions = Vec::new();
+        for (_score, address) in top_docs {
+            let document = self.doc_converter.convert(&searcher.doc(address)?);
+            if suggestions.len() < limit && titles.insert(document.title.clone()) {
+                suggestions.push(Suggestion {
+                    text: document.title,
+                    suggestion_type: SuggestionType::Title,
+                    permalink: Some(document.permalink),
+                });
+            }
+        }
+        Ok(suggestions)
+    }
+    
+    /// 以关键词开头的标签，按使用该标签的公开文档数量从多到少排序
+    fn suggest_tags(&self, searcher: &Searcher, keyword: &str, limit: usize) -> Result<Vec<Suggestion>> {
+        let mut candidates = BTreeSet::new();
+        for segment in searcher.segment_readers() {
+            let inverted_index = segment.inverted_index(self.converter.tag_keyword_field)?;
+            let mut terms = inverted_index.terms().range().ge(keyword.as_bytes()).into_stream()?;
+            while candidates.len() < MAX_TAG_CANDIDATES && terms.advance() {
+                match std::str::from_utf8(terms.key()) {
+                    Ok(tag) if tag.starts_with(keyword) => candidates.insert(tag.to_string()),
+                    _ => break,
+                };
+            }
+        }
+        // 词典中可能还有已删除文档的标签，按公开文档重新计数
+        let mut tags = Vec::new();
+        for tag in candidates {
+            let mut clauses = self.public_clauses();
+            let term = Term::from_field_text(self.converter.tag_keyword_field, &tag);
+            clauses.push((Occur::Must, Box::new(TermQuery::new(term, IndexRecordOption::Basic))));
+            let count = searcher.search(&BooleanQuery::new(clauses), &Count)?;
+            if count > 0 {
+                tags.push((count, tag));
+            }
+        }
+        tags.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        Ok(tags.into_iter()
+            .take(limit)
+            .map(|(_, tag)| Suggestion { text: tag, suggestion_type: SuggestionType::Tag, permalink: None })
+            .collect())
+    }
 }
 
 #[async_trait::async_trait]
@@ -426,6 +516,62 @@ impl SearchEngine for TantivySearchEngine {
             cache_stats: None,
         })
     }
+    
+    async fn documents(&self) -> Result<Vec<HaloDocument>, Box<dyn std::error::Error + Send + Sync>> {
+        let searcher = self.get_searcher()?;
+        let addresses = searcher.search(&AllQuery, &DocSetCollector)?;
+        let mut documents = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let retrieved_doc = searcher.doc(address)?;
+            documents.push(self.doc_converter.convert(&retrieved_doc));
+        }
+        Ok(documents)
+    }
+    
+    async fn suggest(&self, keyword: &str, limit: u32) -> Result<Vec<Suggestion>, Box<dyn std::error::Error + Send + Sync>> {
+        let keyword = keyword.trim().to_lowercase();
+        let searcher = self.get_searcher()?;
+        if keyword.is_empty() || limit == 0 || searcher.num_docs() == 0 {
+            return Ok(Vec::new());
+        }
+        let mut suggestions = self.suggest_titles(&searcher, &keyword, limit as usize)?;
+        suggestions.extend(self.suggest_tags(&searcher, &keyword, limit as usize)?);
+        Ok(suggestions)
+    }
+}
+
+/// 按当前Schema重建索引（如旧版本使用默认分词器建立的索引）
+///
+/// 字段的顺序没有变化，旧索引中的文档仍可按当前Schema读取，读出后写入新建的索引
+fn rebuild_index(index_path: &Path, converter: &HaloDocumentConverter, tokenizer: &CjkTokenizer) -> Result<Index> {
+    let documents = {
+        let old_index = Index::open_in_dir(index_path)
+            .context("Failed to open existing index")?;
+        let searcher = old_index.reader()
+            .context("Failed to create index reader")?
+            .searcher();
+        let doc_converter = DocumentConverter::new(converter);
+        let mut documents = Vec::new();
+        for address in searcher.search(&AllQuery, &DocSetCollector)? {
+            documents.push(doc_converter.convert(&searcher.doc(address)?));
+        }
+        documents
+    };
+    std::fs::remove_dir_all(index_path)
+        .context("Failed to remove existing index")?;
+    std::fs::create_dir_all(index_path)
+        .context("Failed to create index directory")?;
+    let index = Index::create_in_dir(index_path, converter.schema().clone())
+        .context("Failed to create index")?;
+    tokenizer.register(&index);
+    let mut writer: IndexWriter = index.writer(50_000_000)
+        .context("Failed to create index writer")?;
+    for document in &documents {
+        writer.add_document(converter.convert(document))?;
+    }
+    writer.commit()?;
+    info!("Rebuilt search index at {:?} with {} documents for the new schema", index_path, documents.len());
+    Ok(index)
 }
 
 /// 应用高亮到文档字段
@@ -798,5 +944,48 @@ mod tests {
         }
         assert!(hit.content.contains("<B>Rust</B>"), "Content should be highlighted: {}", hit.content);
     }
-}
 
+    /// 测试中文分词：中文关键词可以匹配长句中的词，并高亮
+    #[tokio::test]
+    async fn test_chinese_search() {
+        let (_temp_dir, engine) = create_test_engine().await;
+
+        let doc = create_test_document("test-1", "使用Rust编写搜索引擎", "全文搜索引擎需要中文分词才能正确检索。");
+        let other = create_test_document("test-2", "Hello World", "Nothing about it.");
+        engine.add_or_update(vec![doc, other]).await.unwrap();
+
+        for keyword in ["搜索引擎", "中文分词", "检索"] {
+            let mut option: SearchOption = serde_json::from_value(serde_json::json!({ "keyword": keyword })).unwrap();
+            option.highlight_pre_tag = "<mark>".to_string();
+            option.highlight_post_tag = "</mark>".to_string();
+            let result = engine.search(option).await.unwrap();
+            assert_eq!(result.hits.len(), 1, "keyword {}", keyword);
+            assert!(result.hits[0].content.contains("<mark>"), "{}", result.hits[0].content);
+        }
+    }
+
+    /// 测试搜索建议：标题按词的前缀匹配，标签按前缀匹配并按使用次数排序，不公开的文档不参与
+    #[tokio::test]
+    async fn test_suggest() {
+        let (_temp_dir, engine) = create_test_engine().await;
+
+        let mut rust = create_test_document("test-1", "Rust 搜索引擎实践", "content");
+        rust.tags = Some(vec!["rust".to_string(), "search".to_string()]);
+        let mut tokio = create_test_document("test-2", "Tokio in Action", "content");
+        tokio.tags = Some(vec!["rust".to_string(), "runtime".to_string()]);
+        let mut draft = create_test_document("test-3", "Rust drafts", "content");
+        draft.published = false;
+        draft.tags = Some(vec!["rusty".to_string()]);
+        engine.add_or_update(vec![rust, tokio, draft]).await.unwrap();
+
+        let texts = |suggestions: Vec<Suggestion>, suggestion_type: SuggestionType| suggestions.into_iter()
+            .filter(|s| s.suggestion_type == suggestion_type)
+            .map(|s| s.text)
+            .collect::<Vec<_>>();
+        assert_eq!(texts(engine.suggest("Ru", 5).await.unwrap(), SuggestionType::Title), vec!["Rust 搜索引擎实践"]);
+        assert_eq!(texts(engine.suggest("搜索", 5).await.unwrap(), SuggestionType::Title), vec!["Rust 搜索引擎实践"]);
+        assert_eq!(texts(engine.suggest("act", 5).await.unwrap(), SuggestionType::Title), vec!["Tokio in Action"]);
+        assert_eq!(texts(engine.suggest("ru", 5).await.unwrap(), SuggestionType::Tag), vec!["rust", "runtime"]);
+        assert!(engine.suggest("  ", 5).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use anyhow::{Context, Result};
+use jieba_rs::{Jieba, TokenizeMode};
+use lindera::dictionary::load_dictionary;
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tantivy::tokenizer::{LowerCaser, RawTokenizer, RemoveLongFilter, TextAnalyzer, Token, TokenStream, Tokenizer};
+use tantivy::Index;
+
+/// 标题、描述和内容字段使用的分词器名称
+pub const CJK_TOKENIZER: &str = "cjk";
+
+/// 标题前缀字段使用的分词器名称，每个词产生它的所有前缀（edge n-gram），用于搜索建议
+pub const PREFIX_TOKENIZER: &str = "cjk_prefix";
+
+/// 标签字段使用的分词器名称，整个标签转为小写作为一个词，用于按前缀遍历词典
+pub const KEYWORD_TOKENIZER: &str = "keyword_lowercase";
+
+/// 超过该长度（字节）的词不建索引，与Tantivy默认分词器一致
+const MAX_TOKEN_LEN: usize = 40;
+
+/// 词的前缀最多的字符数，更长的关键词按该长度截断后匹配
+pub const MAX_PREFIX_CHARS: usize = 20;
+
+/// 中日韩分词配置
+///
+/// 中文使用jieba内置词典；日文和韩文需要Lindera词典（目录路径，或者以对应feature编译时的
+/// `embedded://ipadic`、`embedded://ko-dic`），未配置时按单字切分
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CjkTokenizerConfig {
+    /// 日文词典（如IPADIC）
+    pub japanese_dictionary: Option<String>,
+    /// 韩文词典（如ko-dic）
+    pub korean_dictionary: Option<String>,
+}
+
+/// 中日韩分词器
+///
+/// 包含韩文时使用韩文词典，包含假名时使用日文词典，其余文本（中文、英文等）使用jieba的搜索模式，
+/// 同时产生长词和其中的短词。词再按非字母数字字符切开，英文的行为与默认分词器相同。
+///
+/// 词的位置为它之前的字母数字字符数：索引和查询的位置只取决于文字本身，不受空白、标点和重叠的短词影响，
+/// 没有空格分隔的中文关键词按短语查询时也能匹配
+#[derive(Clone)]
+pub struct CjkTokenizer {
+    jieba: Arc<Jieba>,
+    japanese: Option<Arc<Segmenter>>,
+    korean: Option<Arc<Segmenter>>,
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}')
+}
+
+fn load_segmenter(uri: Option<&str>) -> Result<Option<Arc<Segmenter>>> {
+    let Some(uri) = uri else {
+        return Ok(None);
+    };
+    let dictionary = load_dictionary(uri)
+        .with_context(|| format!("Failed to load Lindera dictionary {}", uri))?;
+    Ok(Some(Arc::new(Segmenter::new(Mode::Normal, dictionary, None))))
+}
+
+impl CjkTokenizer {
+    /// 只使用jieba的分词器
+    pub fn new() -> Self {
+        Self { jieba: Arc::new(Jieba::new()), japanese: None, korean: None }
+    }
+
+    /// 按配置加载日文和韩文词典
+    pub fn from_config(config: &CjkTokenizerConfig) -> Result<Self> {
+        Ok(Self {
+            japanese: load_segmenter(config.japanese_dictionary.as_deref())?,
+            korean: load_segmenter(config.korean_dictionary.as_deref())?,
+            ..Self::new()
+        })
+    }
+
+    /// 注册到索引，供Schema中使用 [`CJK_TOKENIZER`]、[`PREFIX_TOKENIZER`] 和 [`KEYWORD_TOKENIZER`] 的字段和查询解析使用
+    pub fn register(&self, index: &Index) {
+        let analyzer = TextAnalyzer::builder(self.clone())
+            .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN))
+            .filter(LowerCaser)
+            .build();
+        index.tokenizers().register(CJK_TOKENIZER, analyzer);
+        let prefix_analyzer = TextAnalyzer::builder(PrefixTokenizer(self.clone()))
+            .filter(LowerCaser)
+            .build();
+        index.tokenizers().register(PREFIX_TOKENIZER, prefix_analyzer);
+        let keyword_analyzer = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(LowerCaser)
+            .build();
+        index.tokenizers().register(KEYWORD_TOKENIZER, keyword_analyzer);
+    }
+
+    /// 词在文本中的字节区间，按起始位置排序
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let segmenter = if text.chars().any(is_hangul) {
+            self.korean.as_ref()
+        } else if text.chars().any(is_kana) {
+            self.japanese.as_ref()
+        } else {
+            None
+        };
+        let words: Vec<(usize, usize)> = match segmenter.map(|segmenter| segmenter.segment(Cow::Borrowed(text))) {
+            Some(Ok(tokens)) => tokens.iter().map(|token| (token.byte_start, token.byte_end)).collect(),
+            _ => {
+                // jieba的位置是字符下标
+                let offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+                self.jieba.tokenize(text, TokenizeMode::Search, true).iter()
+                    .map(|token| (offsets[token.start], offsets[token.end]))
+                    .collect()
+            }
+        };
+
+        let mut spans = BTreeSet::new();
+        for (start, end) in words {
+            let mut span_start = None;
+            for (i, c) in text[start..end].char_indices() {
+                match (c.is_alphanumeric(), span_start) {
+                    (true, None) => span_start = Some(start + i),
+                    (false, Some(s)) => {
+                        spans.insert((s, start + i));
+                        span_start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(s) = span_start {
+                spans.insert((s, end));
+            }
+        }
+        spans.into_iter().collect()
+    }
+
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        let mut counted = 0;
+        for (start, end) in self.spans(text) {
+            // 区间按起始位置排序，只需累加上一个起始位置之后的字符
+            position += text[counted..start].chars().filter(|c| c.is_alphanumeric()).count();
+            counted = start;
+            tokens.push(Token {
+                offset_from: start,
+                offset_to: end,
+                position,
+                text: text[start..end].to_string(),
+                position_length: 1,
+            });
+        }
+        tokens
+    }
+}
+
+impl Default for CjkTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 产生每个词的前缀（最多 [`MAX_PREFIX_CHARS`] 个字符），前缀与词的位置相同
+#[derive(Clone)]
+pub struct PrefixTokenizer(CjkTokenizer);
+
+/// 词的前缀
+pub fn prefixes(word: &str) -> impl Iterator<Item = &str> {
+    word.char_indices()
+        .skip(1)
+        .map(|(i, _)| i)
+        .chain([word.len()])
+        .take(MAX_PREFIX_CHARS)
+        .map(move |end| &word[..end])
+}
+
+impl Tokenizer for PrefixTokenizer {
+    type TokenStream<'a> = CjkTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream {
+        let tokens = self.0.tokens(text).into_iter()
+            .flat_map(|token| {
+                prefixes(&token.text)
+                    .map(|prefix| Token {
+                        offset_to: token.offset_from + prefix.len(),
+                        text: prefix.to_string(),
+                        ..token.clone()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        CjkTokenStream { tokens, index: 0 }
+    }
+}
+
+/// 预先分好的词
+pub struct CjkTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream {
+        CjkTokenStream { tokens: self.tokens(text), index: 0 }
+    }
+}
+
+impl TokenStream for CjkTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<(String, usize)> {
+        CjkTokenizer::new().tokens(text).into_iter().map(|token| (token.text, token.position)).collect()
+    }
+
+    #[test]
+    fn test_chinese_words() {
+        let tokens = tokens("中华人民共和国成立了");
+        for word in ["中华", "人民", "共和国", "成立"] {
+            assert!(tokens.iter().any(|(text, _)| text == word), "{} missing in {:?}", word, tokens);
+        }
+        let position = |word: &str| tokens.iter().find(|(text, _)| text == word).unwrap().1;
+        assert_eq!(position("人民"), 2);
+        assert_eq!(position("共和国"), 4);
+    }
+
+    #[test]
+    fn test_latin_words_ignore_punctuation() {
+        assert_eq!(tokens("Hello, foo-bar  world"), vec![
+            ("Hello".to_string(), 0),
+            ("foo".to_string(), 5),
+            ("bar".to_string(), 8),
+            ("world".to_string(), 11),
+        ]);
+        assert_eq!(tokens("hello world"), vec![("hello".to_string(), 0), ("world".to_string(), 5)]);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        assert_eq!(prefixes("Rust").collect::<Vec<_>>(), vec!["R", "Ru", "Rus", "Rust"]);
+        assert_eq!(prefixes("搜索引擎").collect::<Vec<_>>(), vec!["搜", "搜索", "搜索引", "搜索引擎"]);
+        assert_eq!(prefixes(&"a".repeat(30)).count(), MAX_PREFIX_CHARS);
+    }
+}
@@ -27,6 +27,10 @@ impl Extension for ConfigMap {
 pub mod constants {
     pub const SYSTEM_CONFIG_MAP_NAME: &str = "system";
     pub const THEME_GROUP: &str = "theme";
+    pub const COMMENT_GROUP: &str = "comment";
+    pub const MENU_GROUP: &str = "menu";
+    pub const ROBOTS_GROUP: &str = "robots";
+    pub const BASIC_GROUP: &str = "basic";
 }
 
 /// 主题设置
@@ -35,6 +39,55 @@ pub struct ThemeSetting {
     pub active: Option<String>,
 }
 
+/// 评论设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommentSetting {
+    /// Akismet垃圾评论检测设置
+    #[serde(default)]
+    pub akismet: Option<AkismetSetting>,
+}
+
+/// Akismet垃圾评论检测设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AkismetSetting {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>,
+
+    /// 站点地址（Akismet的blog参数）
+    #[serde(rename = "siteUrl")]
+    pub site_url: Option<String>,
+}
+
+/// 菜单设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MenuSetting {
+    /// 主菜单名称（主题默认渲染的导航菜单）
+    pub primary: Option<String>,
+}
+
+/// robots.txt设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RobotsSetting {
+    /// 自定义的robots.txt内容，为空时使用默认内容
+    pub content: Option<String>,
+}
+
+/// 站点基本设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BasicSetting {
+    /// 站点标题
+    pub title: Option<String>,
+    /// 站点副标题
+    pub subtitle: Option<String>,
+    /// 站点Logo地址
+    pub logo: Option<String>,
+    /// 站点图标地址
+    pub favicon: Option<String>,
+}
+
 /// 系统设置服务
 #[async_trait]
 pub trait SystemSettingService: Send + Sync {
@@ -43,6 +96,21 @@ pub trait SystemSettingService: Send + Sync {
     
     /// 更新主题设置
     async fn update_theme_setting(&self, setting: ThemeSetting) -> Result<()>;
+
+    /// 获取评论设置
+    async fn get_comment_setting(&self) -> Result<Option<CommentSetting>>;
+
+    /// 获取菜单设置
+    async fn get_menu_setting(&self) -> Result<Option<MenuSetting>>;
+
+    /// 获取robots.txt设置
+    async fn get_robots_setting(&self) -> Result<Option<RobotsSetting>>;
+
+    /// 更新robots.txt设置
+    async fn update_robots_setting(&self, setting: RobotsSetting) -> Result<()>;
+
+    /// 获取站点基本设置
+    async fn get_basic_setting(&self) -> Result<Option<BasicSetting>>;
 }
 
 /// 默认系统设置服务实现
@@ -54,6 +122,27 @@ impl DefaultSystemSettingService {
     pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
         Self { extension_client }
     }
+
+    /// 保存系统ConfigMap中的一个设置分组，ConfigMap不存在时创建
+    async fn save_group(&self, group: &str, json: String) -> Result<()> {
+        let config_map: Option<ConfigMap> = self.extension_client
+            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?;
+
+        let result = match config_map {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).insert(group.to_string(), json);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(constants::SYSTEM_CONFIG_MAP_NAME),
+                data: Some(HashMap::from([(group.to_string(), json)])),
+            }).await,
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to update config map: {}", e))?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -78,41 +167,82 @@ impl SystemSettingService for DefaultSystemSettingService {
     }
     
     async fn update_theme_setting(&self, setting: ThemeSetting) -> Result<()> {
-        // 获取或创建ConfigMap
-        let mut config_map: ConfigMap = self.extension_client
-            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?
-            .unwrap_or_else(|| {
-                // 创建新的ConfigMap
-                ConfigMap {
-                    metadata: Metadata {
-                        name: constants::SYSTEM_CONFIG_MAP_NAME.to_string(),
-                        labels: None,
-                        annotations: None,
-                        creation_timestamp: None,
-                        version: None,
-                    },
-                    data: Some(HashMap::new()),
-                }
-            });
-        
-        // 更新数据
-        if config_map.data.is_none() {
-            config_map.data = Some(HashMap::new());
-        }
-        
         let theme_json = serde_json::to_string(&setting)
             .map_err(|e| anyhow::anyhow!("Failed to serialize theme setting: {}", e))?;
-        
-        config_map.data.as_mut().unwrap()
-            .insert(constants::THEME_GROUP.to_string(), theme_json);
-        
-        // 保存ConfigMap
-        self.extension_client.update(config_map).await
-            .map_err(|e| anyhow::anyhow!("Failed to update config map: {}", e))?;
-        
-        Ok(())
+        self.save_group(constants::THEME_GROUP, theme_json).await
+    }
+
+    async fn get_comment_setting(&self) -> Result<Option<CommentSetting>> {
+        let config_map: Option<ConfigMap> = self.extension_client
+            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?;
+
+        let Some(comment_json) = config_map
+            .and_then(|c| c.data)
+            .and_then(|mut data| data.remove(constants::COMMENT_GROUP)) else {
+            return Ok(None);
+        };
+
+        let setting: CommentSetting = serde_json::from_str(&comment_json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse comment setting: {}", e))?;
+        Ok(Some(setting))
     }
-}
 
+    async fn get_menu_setting(&self) -> Result<Option<MenuSetting>> {
+        let config_map: Option<ConfigMap> = self.extension_client
+            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?;
+
+        let Some(menu_json) = config_map
+            .and_then(|c| c.data)
+            .and_then(|mut data| data.remove(constants::MENU_GROUP)) else {
+            return Ok(None);
+        };
+
+        let setting: MenuSetting = serde_json::from_str(&menu_json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse menu setting: {}", e))?;
+        Ok(Some(setting))
+    }
+
+    async fn get_robots_setting(&self) -> Result<Option<RobotsSetting>> {
+        let config_map: Option<ConfigMap> = self.extension_client
+            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?;
+
+        let Some(robots_json) = config_map
+            .and_then(|c| c.data)
+            .and_then(|mut data| data.remove(constants::ROBOTS_GROUP)) else {
+            return Ok(None);
+        };
+
+        let setting: RobotsSetting = serde_json::from_str(&robots_json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse robots setting: {}", e))?;
+        Ok(Some(setting))
+    }
+
+    async fn update_robots_setting(&self, setting: RobotsSetting) -> Result<()> {
+        let robots_json = serde_json::to_string(&setting)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize robots setting: {}", e))?;
+        self.save_group(constants::ROBOTS_GROUP, robots_json).await
+    }
+
+    async fn get_basic_setting(&self) -> Result<Option<BasicSetting>> {
+        let config_map: Option<ConfigMap> = self.extension_client
+            .fetch(constants::SYSTEM_CONFIG_MAP_NAME)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map: {}", e))?;
+
+        let Some(basic_json) = config_map
+            .and_then(|c| c.data)
+            .and_then(|mut data| data.remove(constants::BASIC_GROUP)) else {
+            return Ok(None);
+        };
+
+        let setting: BasicSetting = serde_json::from_str(&basic_json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse basic setting: {}", e))?;
+        Ok(Some(setting))
+    }
+}
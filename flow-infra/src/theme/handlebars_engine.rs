@@ -0,0 +1,111 @@
+use super::template_engine::{TemplateContext, TemplateEngine, TemplateRenderer};
+use flow_domain::theme::ThemeContext;
+use handlebars::Handlebars;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 模板文件扩展名
+const TEMPLATE_EXTENSION: &str = "html";
+
+/// Handlebars模板引擎，便于移植使用Mustache/Handlebars语法的主题
+///
+/// 模板和局部模板以相对templates目录的路径注册（如 `post.html`、`partials/header.html`），
+/// 在模板中通过 `{{> partials/header.html}}` 引用
+pub struct HandlebarsEngine;
+
+impl TemplateEngine for HandlebarsEngine {
+    fn name(&self) -> &str {
+        "handlebars"
+    }
+
+    fn create_renderer(&self, theme_context: &ThemeContext) -> Result<Arc<dyn TemplateRenderer>, Box<dyn std::error::Error + Send + Sync>> {
+        let template_path = theme_context.path.join("templates");
+        let mut handlebars = Handlebars::new();
+        register_templates(&mut handlebars, &template_path, &template_path)?;
+        Ok(Arc::new(HandlebarsRenderer {
+            theme_name: theme_context.name.clone(),
+            handlebars,
+        }))
+    }
+}
+
+/// 递归注册目录中的模板文件
+fn register_templates(
+    handlebars: &mut Handlebars<'static>,
+    root: &Path,
+    dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            register_templates(handlebars, root, &path)?;
+        } else if path.extension().is_some_and(|ext| ext == TEMPLATE_EXTENSION) {
+            let name = path.strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            handlebars.register_template_file(&name, &path)?;
+        }
+    }
+    Ok(())
+}
+
+struct HandlebarsRenderer {
+    theme_name: String,
+    handlebars: Handlebars<'static>,
+}
+
+impl TemplateRenderer for HandlebarsRenderer {
+    fn render(&self, template_name: &str, context: &TemplateContext) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let variables = context.variables(&self.theme_name);
+        Ok(self.handlebars.render(template_name, &variables)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn theme_with_templates(files: &[(&str, &str)]) -> (tempfile::TempDir, ThemeContext) {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join("templates").join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let context = ThemeContext::new("earth".to_string(), dir.path().to_path_buf(), true)
+            .with_template_engine(Some("handlebars".to_string()));
+        (dir, context)
+    }
+
+    #[test]
+    fn test_render_with_partial() {
+        let (_dir, theme) = theme_with_templates(&[
+            ("post.html", "{{> partials/header.html}}<h1>{{post.spec.title}}</h1>"),
+            ("partials/header.html", "<title>{{theme.name}} {{theme.config.basic.color}}</title>"),
+        ]);
+        let renderer = HandlebarsEngine.create_renderer(&theme).unwrap();
+        let context = TemplateContext::new()
+            .with_model(HashMap::from([("post".to_string(), serde_json::json!({"spec": {"title": "Hello"}}))]))
+            .with_theme_config(serde_json::json!({"basic": {"color": "blue"}}));
+        assert_eq!(
+            renderer.render("post.html", &context).unwrap(),
+            "<title>earth blue</title><h1>Hello</h1>"
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_html() {
+        let (_dir, theme) = theme_with_templates(&[("index.html", "{{title}}|{{{title}}}")]);
+        let renderer = HandlebarsEngine.create_renderer(&theme).unwrap();
+        let context = TemplateContext::new()
+            .with_model(HashMap::from([("title".to_string(), serde_json::json!("<b>hi</b>"))]));
+        assert_eq!(
+            renderer.render("index.html", &context).unwrap(),
+            "&lt;b&gt;hi&lt;/b&gt;|<b>hi</b>"
+        );
+        assert!(renderer.render("missing.html", &context).is_err());
+    }
+}
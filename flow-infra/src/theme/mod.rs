@@ -1,8 +1,10 @@
 pub mod finder_registry;
 pub mod template_engine;
+pub mod handlebars_engine;
 pub mod resolver;
 
 pub use finder_registry::DefaultFinderRegistry;
-pub use template_engine::TemplateEngineManager;
-pub use resolver::ThemeResolver;
+pub use template_engine::{TemplateEngineManager, TemplateEngine, TemplateRenderer, DEFAULT_TEMPLATE_ENGINE};
+pub use handlebars_engine::HandlebarsEngine;
+pub use resolver::{ThemeResolver, ThemePreview};
 
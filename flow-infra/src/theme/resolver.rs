@@ -1,9 +1,15 @@
-use flow_domain::theme::ThemeContext;
+use flow_domain::theme::{CustomTemplateKind, Theme, ThemeContext};
 use flow_api::extension::ExtensionClient;
 use crate::extension::ReactiveExtensionClient;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 
 /// 主题解析器
 /// 负责解析当前请求应该使用的主题
@@ -11,6 +17,50 @@ pub struct ThemeResolver {
     extension_client: Arc<ReactiveExtensionClient>,
     theme_root: PathBuf,
     active_theme: Arc<tokio::sync::RwLock<Option<String>>>,
+    /// 预览令牌签名密钥，未设置时不支持预览
+    preview_secret: Option<Vec<u8>>,
+}
+
+/// 主题预览令牌中携带的信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemePreview {
+    /// 预览的主题
+    #[serde(rename = "t")]
+    pub theme_name: String,
+    /// 令牌签发给的用户，只有该用户可以使用
+    #[serde(rename = "u")]
+    pub username: String,
+    /// 过期时间（Unix时间戳，秒）
+    #[serde(rename = "e")]
+    pub expires_at: i64,
+}
+
+impl ThemePreview {
+    /// 距离过期的秒数，已过期时返回0
+    pub fn remaining_seconds(&self) -> u64 {
+        (self.expires_at - chrono::Utc::now().timestamp()).max(0) as u64
+    }
+}
+
+fn mac(secret: &[u8], payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// 签名生成令牌：`base64url(preview).base64url(HMAC-SHA256)`
+fn sign_preview(secret: &[u8], preview: &ThemePreview) -> Result<String> {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(preview)?);
+    let signature = URL_SAFE_NO_PAD.encode(mac(secret, &payload).finalize().into_bytes());
+    Ok(format!("{}.{}", payload, signature))
+}
+
+/// 校验令牌签名并解析，签名不正确或格式错误时返回None（不检查是否过期）
+fn verify_preview(secret: &[u8], token: &str) -> Option<ThemePreview> {
+    let (payload, signature) = token.split_once('.')?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    mac(secret, payload).verify_slice(&signature).ok()?;
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()
 }
 
 impl ThemeResolver {
@@ -22,9 +72,16 @@ impl ThemeResolver {
             extension_client,
             theme_root,
             active_theme: Arc::new(tokio::sync::RwLock::new(None)),
+            preview_secret: None,
         }
     }
     
+    /// 设置预览令牌签名密钥
+    pub fn with_preview_secret(mut self, secret: &str) -> Self {
+        self.preview_secret = Some(secret.as_bytes().to_vec());
+        self
+    }
+    
     /// 设置激活的主题
     pub async fn set_active_theme(&self, theme_name: &str) {
         let mut active = self.active_theme.write().await;
@@ -40,20 +97,20 @@ impl ThemeResolver {
     /// 获取主题上下文
     pub async fn get_theme_context(&self, theme_name: &str) -> Result<ThemeContext> {
         // 验证主题是否存在
-        use flow_domain::theme::Theme;
-        let _theme: Option<Theme> = self.extension_client.fetch(theme_name).await
+        let theme: Option<Theme> = self.extension_client.fetch(theme_name).await
             .map_err(|e| anyhow::anyhow!("Failed to fetch theme: {}", e))?;
         
-        if _theme.is_none() {
+        let Some(theme) = theme else {
             anyhow::bail!("Theme not found: {}", theme_name);
-        }
+        };
         
         let active_theme = self.get_active_theme().await;
         let active = active_theme.as_ref().map(|name| name == theme_name).unwrap_or(false);
         
         let path = self.theme_root.join(theme_name);
         
-        Ok(ThemeContext::new(theme_name.to_string(), path, active))
+        Ok(ThemeContext::new(theme_name.to_string(), path, active)
+            .with_template_engine(theme.spec.template_engine))
     }
     
     /// 获取激活的主题上下文
@@ -65,19 +122,125 @@ impl ThemeResolver {
         }
     }
     
-    /// 根据预览参数获取主题（用于主题预览功能）
+    /// 获取本次请求使用的主题：预览令牌有效时使用预览的主题，否则使用激活的主题
     pub async fn get_theme_with_preview(
         &self,
-        preview_theme: Option<&str>,
-    ) -> Result<ThemeContext> {
-        if let Some(preview) = preview_theme {
-            // 预览模式：使用指定的主题
-            self.get_theme_context(preview).await
-        } else {
-            // 正常模式：使用激活的主题
-            self.get_active_theme_context().await?
-                .ok_or_else(|| anyhow::anyhow!("No active theme"))
+        preview: Option<&ThemePreview>,
+    ) -> Result<Option<ThemeContext>> {
+        if let Some(preview) = preview {
+            match self.get_theme_context(&preview.theme_name).await {
+                Ok(ctx) => return Ok(Some(ctx)),
+                Err(e) => tracing::warn!("Failed to preview theme {}: {}", preview.theme_name, e),
+            }
         }
+        self.get_active_theme_context().await
+    }
+    
+    /// 为用户生成预览主题的签名令牌
+    pub fn sign_preview_token(&self, theme_name: &str, username: &str, ttl: Duration) -> Result<String> {
+        let secret = self.preview_secret.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Theme preview secret not configured"))?;
+        let preview = ThemePreview {
+            theme_name: theme_name.to_string(),
+            username: username.to_string(),
+            expires_at: chrono::Utc::now().timestamp() + ttl.as_secs() as i64,
+        };
+        sign_preview(secret, &preview)
+    }
+    
+    /// 校验预览令牌，签名错误、已过期或不是签发给该用户时返回None
+    pub fn verify_preview_token(&self, token: &str, username: &str) -> Option<ThemePreview> {
+        let preview = verify_preview(self.preview_secret.as_deref()?, token)?;
+        (preview.username == username && preview.remaining_seconds() > 0).then_some(preview)
+    }
+    
+    /// 在主题模板目录中按顺序查找第一个存在的模板
+    pub fn resolve_template(&self, theme_context: &ThemeContext, candidates: &[String]) -> Option<String> {
+        let template_dir = theme_context.path.join("templates");
+        candidates.iter()
+            .find(|name| template_dir.join(name.as_str()).is_file())
+            .cloned()
+    }
+    
+    /// 解析内容所选的自定义模板，只使用主题声明（ThemeSpec.customTemplates）且文件存在的模板
+    pub async fn resolve_custom_template(
+        &self,
+        theme_context: &ThemeContext,
+        kind: CustomTemplateKind,
+        chosen: &str,
+    ) -> Option<String> {
+        let theme: Theme = match self.extension_client.fetch(&theme_context.name).await {
+            Ok(theme) => theme?,
+            Err(e) => {
+                tracing::warn!("Failed to fetch theme {}: {}", theme_context.name, e);
+                return None;
+            }
+        };
+        let file = theme.spec.custom_templates.as_ref()?.find(kind, chosen)?.file.clone();
+        self.resolve_template(theme_context, &[file])
+    }
+    
+    /// 解析系列归档页模板
+    /// 查找顺序：系列自定义模板 -> series-{slug}.html -> series.html
+    pub fn resolve_series_template(
+        &self,
+        theme_context: &ThemeContext,
+        slug: &str,
+        custom_template: Option<&str>,
+    ) -> String {
+        let mut candidates = Vec::new();
+        if let Some(template) = custom_template {
+            candidates.push(template.to_string());
+        }
+        candidates.push(format!("series-{}.html", slug));
+        candidates.push("series.html".to_string());
+        
+        self.resolve_template(theme_context, &candidates)
+            .unwrap_or_else(|| "series.html".to_string())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(expires_at: i64) -> ThemePreview {
+        ThemePreview {
+            theme_name: "theme-earth".to_string(),
+            username: "admin".to_string(),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_find_custom_template() {
+        let templates: flow_domain::theme::CustomTemplates = serde_json::from_value(serde_json::json!({
+            "post": [{"name": "文档", "file": "post_documents.html"}],
+            "page": [{"name": "友链", "file": "page_links.html"}],
+        })).unwrap();
+        let find = |kind, chosen| templates.find(kind, chosen).map(|t| t.file.as_str());
+        assert_eq!(find(CustomTemplateKind::Post, "post_documents"), Some("post_documents.html"));
+        assert_eq!(find(CustomTemplateKind::Post, "post_documents.html"), Some("post_documents.html"));
+        assert_eq!(find(CustomTemplateKind::Post, "page_links"), None);
+        assert_eq!(find(CustomTemplateKind::Category, "post_documents"), None);
+    }
+
+    #[test]
+    fn test_preview_token_round_trip() {
+        let preview = preview(chrono::Utc::now().timestamp() + 60);
+        let token = sign_preview(b"secret", &preview).unwrap();
+        assert_eq!(verify_preview(b"secret", &token), Some(preview));
+        assert_eq!(verify_preview(b"other", &token), None);
+        assert_eq!(verify_preview(b"secret", "invalid"), None);
+    }
+
+    #[test]
+    fn test_preview_token_tampered() {
+        let token = sign_preview(b"secret", &preview(0)).unwrap();
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = sign_preview(b"other", &preview(i64::MAX)).unwrap();
+        let (payload, _) = forged.split_once('.').unwrap();
+        assert_eq!(verify_preview(b"secret", &format!("{}.{}", payload, signature)), None);
+        assert_eq!(preview(0).remaining_seconds(), 0);
+    }
+}
@@ -1,17 +1,32 @@
 use flow_domain::theme::ThemeContext;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use moka::future::Cache;
 use tera::{Tera, Context};
+use super::handlebars_engine::HandlebarsEngine;
+
+/// 主题未指定模板引擎时使用的引擎名称
+pub const DEFAULT_TEMPLATE_ENGINE: &str = "tera";
 
 /// 模板引擎管理器
-/// 管理每个主题的模板引擎实例（使用LRU缓存）
+/// 管理每个主题的模板引擎实例（使用LRU缓存），按主题清单中的 `template_engine` 选择模板引擎
 pub struct TemplateEngineManager {
     /// 模板引擎缓存（LRU，最多5个）
     engine_cache: Cache<String, Arc<dyn TemplateRenderer>>,
     /// 主题根目录
     theme_root: PathBuf,
+    /// 已注册的模板引擎（引擎名称 -> 引擎）
+    engines: RwLock<HashMap<String, Arc<dyn TemplateEngine>>>,
+}
+
+/// 模板引擎trait - 加载主题的模板目录并创建渲染器
+pub trait TemplateEngine: Send + Sync {
+    /// 引擎名称（在主题清单的 `template_engine` 中使用）
+    fn name(&self) -> &str;
+    
+    /// 为主题创建模板渲染器
+    fn create_renderer(&self, theme_context: &ThemeContext) -> Result<Arc<dyn TemplateRenderer>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 /// 模板渲染器trait
@@ -27,6 +42,8 @@ pub struct TemplateContext {
     pub model: HashMap<String, serde_json::Value>,
     /// Finder数据（在模板中可调用）
     pub finders: HashMap<String, serde_json::Value>,
+    /// 主题设置值（模板中的 `theme.config`）
+    pub theme_config: serde_json::Value,
 }
 
 impl TemplateContext {
@@ -34,6 +51,7 @@ impl TemplateContext {
         Self {
             model: HashMap::new(),
             finders: HashMap::new(),
+            theme_config: serde_json::Value::Object(Default::default()),
         }
     }
     
@@ -46,6 +64,26 @@ impl TemplateContext {
         self.finders = finders;
         self
     }
+    
+    pub fn with_theme_config(mut self, theme_config: serde_json::Value) -> Self {
+        self.theme_config = theme_config;
+        self
+    }
+    
+    /// 模板中可用的全部变量：模型数据、Finder数据和主题信息（名称和设置值）
+    ///
+    /// 各模板引擎使用相同的变量，主题切换模板引擎时只需改写模板语法
+    pub fn variables(&self, theme_name: &str) -> serde_json::Map<String, serde_json::Value> {
+        let mut variables = serde_json::Map::new();
+        for (key, value) in self.model.iter().chain(self.finders.iter()) {
+            variables.insert(key.clone(), value.clone());
+        }
+        variables.insert("theme".to_string(), serde_json::json!({
+            "name": theme_name,
+            "config": self.theme_config,
+        }));
+        variables
+    }
 }
 
 impl Default for TemplateContext {
@@ -61,10 +99,29 @@ impl TemplateEngineManager {
             .max_capacity(5)
             .build();
         
-        Self {
+        let manager = Self {
             engine_cache: cache,
             theme_root,
-        }
+            engines: RwLock::new(HashMap::new()),
+        };
+        manager.register_engine(Arc::new(TeraEngine));
+        manager.register_engine(Arc::new(HandlebarsEngine));
+        manager
+    }
+    
+    /// 注册模板引擎，同名引擎会被替换
+    pub fn register_engine(&self, engine: Arc<dyn TemplateEngine>) {
+        let mut engines = self.engines.write().unwrap();
+        engines.insert(engine.name().to_string(), engine);
+    }
+    
+    /// 获取主题使用的模板引擎
+    fn engine_for(&self, theme_context: &ThemeContext) -> Result<Arc<dyn TemplateEngine>, String> {
+        let name = theme_context.template_engine.as_deref().unwrap_or(DEFAULT_TEMPLATE_ENGINE);
+        let engines = self.engines.read().unwrap();
+        engines.get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown template engine: {}", name))
     }
     
     /// 获取模板引擎（从缓存或创建新的）
@@ -72,13 +129,14 @@ impl TemplateEngineManager {
         let cache_key = format!("{}:{}", theme_context.name, theme_context.active);
         
         let theme_ctx = theme_context.clone();
+        let engine = self.engine_for(theme_context);
         self.engine_cache.get_with(cache_key.clone(), async move {
-            // 创建新的模板引擎
-            match TeraTemplateEngine::new(theme_ctx) {
-                Ok(engine) => Arc::new(engine) as Arc<dyn TemplateRenderer>,
+            // 使用主题选择的模板引擎创建渲染器
+            match engine.and_then(|engine| engine.create_renderer(&theme_ctx).map_err(|e| e.to_string())) {
+                Ok(renderer) => renderer,
                 Err(e) => {
                     // 如果创建失败，返回一个错误渲染器
-                    Arc::new(ErrorTemplateEngine::new(e.to_string())) as Arc<dyn TemplateRenderer>
+                    Arc::new(ErrorTemplateEngine::new(e)) as Arc<dyn TemplateRenderer>
                 }
             }
         }).await
@@ -92,6 +150,19 @@ impl TemplateEngineManager {
     }
 }
 
+/// Tera模板引擎（默认）
+pub struct TeraEngine;
+
+impl TemplateEngine for TeraEngine {
+    fn name(&self) -> &str {
+        DEFAULT_TEMPLATE_ENGINE
+    }
+    
+    fn create_renderer(&self, theme_context: &ThemeContext) -> Result<Arc<dyn TemplateRenderer>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Arc::new(TeraTemplateEngine::new(theme_context.clone())?))
+    }
+}
+
 /// Tera模板引擎实现
 /// 使用Tera作为运行时模板引擎，支持动态加载主题模板
 struct TeraTemplateEngine {
@@ -125,20 +196,7 @@ impl TeraTemplateEngine {
 impl TemplateRenderer for TeraTemplateEngine {
     fn render(&self, template_name: &str, context: &TemplateContext) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         // 构建Tera上下文
-        let mut tera_context = Context::new();
-        
-        // 添加模型数据
-        for (key, value) in &context.model {
-            tera_context.insert(key, value);
-        }
-        
-        // 添加Finder数据
-        for (key, value) in &context.finders {
-            tera_context.insert(key, value);
-        }
-        
-        // 添加主题信息
-        tera_context.insert("theme", &self.theme_context.name);
+        let tera_context = Context::from_value(serde_json::Value::Object(context.variables(&self.theme_context.name)))?;
         
         // 渲染模板
         let rendered = self.tera.render(template_name, &tera_context)?;
@@ -146,7 +204,7 @@ impl TemplateRenderer for TeraTemplateEngine {
     }
 }
 
-/// 错误模板引擎（当无法创建Tera引擎时使用）
+/// 错误模板引擎（当无法创建模板渲染器时使用）
 struct ErrorTemplateEngine {
     error: String,
 }
@@ -46,6 +46,17 @@ pub trait WebSocketEndpoint: Send + Sync {
         mut sender: Box<dyn WebSocketSender>,
         mut receiver: Box<dyn WebSocketReceiver>,
     );
+    
+    /// 处理已认证用户的WebSocket连接
+    /// 需要区分用户的端点（如推送用户自己的通知）重写此方法，默认忽略用户
+    async fn handle_user_connection(
+        &self,
+        _username: &str,
+        sender: Box<dyn WebSocketSender>,
+        receiver: Box<dyn WebSocketReceiver>,
+    ) {
+        self.handle_connection(sender, receiver).await
+    }
 }
 
 /// WebSocket端点管理器
@@ -0,0 +1,84 @@
+//! 原生插件的API和ABI版本
+//! Rust没有稳定的ABI，原生插件（动态库）必须使用与宿主相同的插件API版本、rustc版本和目标平台编译，
+//! 加载动态库之前先检查描述符中声明的版本，避免不兼容的二进制导致宿主崩溃
+
+use crate::descriptor::PluginDescriptor;
+
+/// 宿主提供的插件API版本，Plugin trait或宿主接口不兼容变更时递增
+pub const HOST_API_VERSION: u32 = 1;
+
+/// 仍然支持的最低插件API版本
+pub const MIN_SUPPORTED_API_VERSION: u32 = 1;
+
+/// 原生插件与宿主不兼容时的错误信息前缀
+pub const PLUGIN_INCOMPATIBLE_ERROR: &str = "Incompatible plugin binary";
+
+/// 插件因二进制不兼容而无法加载的条件原因
+pub const INCOMPATIBLE_BINARY_REASON: &str = "IncompatibleBinary";
+
+/// 宿主的ABI指纹（rustc版本和目标平台），原生插件描述符的 `abi` 必须与之相同
+pub fn host_abi_fingerprint() -> String {
+    format!("{}; {}", env!("FLOW_RUSTC_VERSION"), env!("FLOW_TARGET"))
+}
+
+/// 检查原生插件与宿主是否兼容
+pub fn check_native_compatibility(descriptor: &PluginDescriptor) -> anyhow::Result<()> {
+    check_compatibility(descriptor, &host_abi_fingerprint())
+}
+
+fn check_compatibility(descriptor: &PluginDescriptor, host_abi: &str) -> anyhow::Result<()> {
+    let Some(api_version) = descriptor.api_version else {
+        anyhow::bail!("{}: plugin {} does not declare apiVersion", PLUGIN_INCOMPATIBLE_ERROR, descriptor.id);
+    };
+    if !(MIN_SUPPORTED_API_VERSION..=HOST_API_VERSION).contains(&api_version) {
+        anyhow::bail!(
+            "{}: plugin {} requires plugin API version {}, host supports {}..={}",
+            PLUGIN_INCOMPATIBLE_ERROR, descriptor.id, api_version, MIN_SUPPORTED_API_VERSION, HOST_API_VERSION
+        );
+    }
+    match descriptor.abi.as_deref() {
+        Some(abi) if abi == host_abi => Ok(()),
+        Some(abi) => anyhow::bail!(
+            "{}: plugin {} was built with {}, host was built with {}",
+            PLUGIN_INCOMPATIBLE_ERROR, descriptor.id, abi, host_abi
+        ),
+        None => anyhow::bail!("{}: plugin {} does not declare abi", PLUGIN_INCOMPATIBLE_ERROR, descriptor.id),
+    }
+}
+
+/// 错误是否表示插件二进制不兼容
+pub fn is_incompatible(error: &anyhow::Error) -> bool {
+    error.to_string().starts_with(PLUGIN_INCOMPATIBLE_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(api_version: Option<u32>, abi: Option<&str>) -> PluginDescriptor {
+        let mut descriptor = PluginDescriptor::from_yaml("id: native\nversion: 1.0.0\n").unwrap();
+        descriptor.api_version = api_version;
+        descriptor.abi = abi.map(str::to_string);
+        descriptor
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let host = "rustc 1.80.0 (051478957 2024-07-21); x86_64-unknown-linux-gnu";
+        assert!(check_compatibility(&descriptor(Some(HOST_API_VERSION), Some(host)), host).is_ok());
+
+        let err = check_compatibility(&descriptor(Some(HOST_API_VERSION + 1), Some(host)), host).unwrap_err();
+        assert!(is_incompatible(&err));
+        let err = check_compatibility(&descriptor(Some(HOST_API_VERSION), Some("rustc 1.79.0; x86_64-unknown-linux-gnu")), host).unwrap_err();
+        assert!(err.to_string().contains("rustc 1.79.0"));
+        assert!(is_incompatible(&check_compatibility(&descriptor(None, Some(host)), host).unwrap_err()));
+        assert!(is_incompatible(&check_compatibility(&descriptor(Some(HOST_API_VERSION), None), host).unwrap_err()));
+    }
+
+    #[test]
+    fn test_host_abi_fingerprint() {
+        let fingerprint = host_abi_fingerprint();
+        assert!(fingerprint.starts_with("rustc "));
+        assert!(fingerprint.ends_with(env!("FLOW_TARGET")));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::descriptor::PluginDescriptor;
+use flow_api::extension::GroupVersionKind;
+use serde::{Deserialize, Serialize};
+
+/// 所有扩展类型
+const ANY_KIND: &str = "*";
+
+/// 插件需要的能力，在描述符中声明，管理员批准后插件才能启动
+///
+/// 扩展类型写作 `group/Kind`（核心组为 `Kind`），支持 `group/*` 和 `*`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCapabilities {
+    /// 可读取的扩展类型
+    #[serde(default)]
+    pub read: Vec<String>,
+    /// 可创建、更新、删除的扩展类型（同时可读取）
+    #[serde(default)]
+    pub write: Vec<String>,
+    /// 是否访问网络
+    #[serde(default)]
+    pub network: bool,
+    /// 是否访问文件系统
+    #[serde(default)]
+    pub filesystem: bool,
+}
+
+/// 访问扩展对象的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// 扩展类型在能力声明中的写法
+pub fn kind_key(gvk: &GroupVersionKind) -> String {
+    if gvk.group.is_empty() {
+        gvk.kind.clone()
+    } else {
+        format!("{}/{}", gvk.group, gvk.kind)
+    }
+}
+
+fn matches(pattern: &str, gvk: &GroupVersionKind) -> bool {
+    if pattern == ANY_KIND {
+        return true;
+    }
+    match pattern.split_once('/') {
+        Some((group, kind)) => group == gvk.group && (kind == ANY_KIND || kind == gvk.kind),
+        None => gvk.group.is_empty() && pattern == gvk.kind,
+    }
+}
+
+impl PluginCapabilities {
+    /// 是否允许以指定方式访问扩展类型
+    pub fn allows(&self, gvk: &GroupVersionKind, access: Access) -> bool {
+        let writable = self.write.iter().any(|pattern| matches(pattern, gvk));
+        match access {
+            Access::Read => writable || self.read.iter().any(|pattern| matches(pattern, gvk)),
+            Access::Write => writable,
+        }
+    }
+
+    /// 逐项列出的能力，如 `read:content.halo.run/Post`、`network`
+    pub fn items(&self) -> Vec<String> {
+        let mut items: Vec<String> = self.read.iter().map(|kind| format!("read:{}", kind))
+            .chain(self.write.iter().map(|kind| format!("write:{}", kind)))
+            .collect();
+        if self.network {
+            items.push("network".to_string());
+        }
+        if self.filesystem {
+            items.push("filesystem".to_string());
+        }
+        items
+    }
+
+    /// 声明了但未被授予的能力
+    pub fn missing(&self, granted: &PluginCapabilities) -> Vec<String> {
+        let granted = granted.items();
+        self.items().into_iter().filter(|item| !granted.contains(item)).collect()
+    }
+
+    /// 插件在宿主接口中实际拥有的能力：声明的能力加上插件自身拥有的扩展类型
+    ///
+    /// `owns` 判断扩展类型是否已由该插件注册，与宿主或其他插件同名的定义不会带来写权限
+    pub fn effective(descriptor: &PluginDescriptor, owns: impl Fn(&GroupVersionKind) -> bool) -> Self {
+        let mut capabilities = descriptor.capabilities.clone();
+        capabilities.write.extend(descriptor.extensions.iter()
+            .map(|definition| definition.gvk())
+            .filter(|gvk| owns(gvk))
+            .map(|gvk| kind_key(&gvk)));
+        capabilities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let capabilities = PluginCapabilities {
+            read: vec!["content.halo.run/*".to_string(), "ConfigMap".to_string()],
+            write: vec!["metrics.halo.run/Counter".to_string()],
+            ..Default::default()
+        };
+        let post = GroupVersionKind::new("content.halo.run", "v1alpha1", "Post");
+        let config_map = GroupVersionKind::new("", "v1alpha1", "ConfigMap");
+        let counter = GroupVersionKind::new("metrics.halo.run", "v1alpha1", "Counter");
+        let user = GroupVersionKind::new("", "v1alpha1", "User");
+
+        assert!(capabilities.allows(&post, Access::Read));
+        assert!(!capabilities.allows(&post, Access::Write));
+        assert!(capabilities.allows(&config_map, Access::Read));
+        assert!(capabilities.allows(&counter, Access::Read));
+        assert!(capabilities.allows(&counter, Access::Write));
+        assert!(!capabilities.allows(&user, Access::Read));
+
+        let all = PluginCapabilities { write: vec!["*".to_string()], ..Default::default() };
+        assert!(all.allows(&user, Access::Write));
+    }
+
+    #[test]
+    fn test_effective_only_owned_kinds() {
+        let descriptor = PluginDescriptor::from_yaml(r#"
+id: notes
+version: 1.0.0
+extensions:
+  - {group: note.flow.run, version: v1alpha1, kind: Note}
+  - {group: content.halo.run, version: v1alpha1, kind: Post}
+"#).unwrap();
+        let note = GroupVersionKind::new("note.flow.run", "v1alpha1", "Note");
+        let post = GroupVersionKind::new("content.halo.run", "v1alpha1", "Post");
+
+        let capabilities = PluginCapabilities::effective(&descriptor, |gvk| gvk.group == "note.flow.run");
+        assert!(capabilities.allows(&note, Access::Write));
+        assert!(!capabilities.allows(&post, Access::Read));
+        assert!(PluginCapabilities::effective(&descriptor, |_| false).write.is_empty());
+    }
+
+    #[test]
+    fn test_missing() {
+        let requested = PluginCapabilities {
+            read: vec!["content.halo.run/Post".to_string()],
+            write: vec!["metrics.halo.run/Counter".to_string()],
+            network: true,
+            filesystem: false,
+        };
+        assert_eq!(requested.missing(&PluginCapabilities::default()), vec![
+            "read:content.halo.run/Post", "write:metrics.halo.run/Counter", "network",
+        ]);
+        let granted = PluginCapabilities { network: false, ..requested.clone() };
+        assert_eq!(requested.missing(&granted), vec!["network"]);
+        assert!(requested.missing(&requested).is_empty());
+        assert!(PluginCapabilities::default().missing(&PluginCapabilities::default()).is_empty());
+    }
+}
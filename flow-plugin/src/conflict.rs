@@ -0,0 +1,122 @@
+//! 插件注册冲突检测
+//! 插件启动前检查其扩展类型、HTTP路由前缀和Finder名称是否已被其他插件或宿主占用，
+//! 冲突时拒绝启动并在失败条件中记录占用方，而不是让后注册的插件静默覆盖
+
+use crate::descriptor::PluginDescriptor;
+
+/// 扩展类型已被占用
+pub const EXTENSION_CONFLICT_REASON: &str = "ExtensionConflict";
+/// HTTP路由前缀与其他插件重叠
+pub const ROUTE_CONFLICT_REASON: &str = "RouteConflict";
+/// Finder名称已被占用
+pub const FINDER_CONFLICT_REASON: &str = "FinderConflict";
+
+/// 一个注册冲突
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginConflict {
+    /// 机器可读的原因，如 `RouteConflict`
+    pub reason: &'static str,
+    /// 冲突的资源（扩展类型、路由前缀或Finder名称）
+    pub resource: String,
+    /// 占用该资源的一方，插件ID或 `host`
+    pub owner: String,
+}
+
+impl PluginConflict {
+    pub fn new(reason: &'static str, resource: impl Into<String>, owner: impl Into<String>) -> Self {
+        Self { reason, resource: resource.into(), owner: owner.into() }
+    }
+
+    pub fn message(&self) -> String {
+        let resource = match self.reason {
+            EXTENSION_CONFLICT_REASON => "extension kind",
+            ROUTE_CONFLICT_REASON => "route prefix",
+            _ => "finder",
+        };
+        format!("{} {} is already registered by {}", resource, self.resource, self.owner)
+    }
+}
+
+/// 是否为冲突原因
+pub fn is_conflict_reason(reason: &str) -> bool {
+    matches!(reason, EXTENSION_CONFLICT_REASON | ROUTE_CONFLICT_REASON | FINDER_CONFLICT_REASON)
+}
+
+/// 两个路由前缀是否重叠：相同，或一个是另一个的上级路径
+pub fn routes_overlap(a: &str, b: &str) -> bool {
+    let a = a.trim_end_matches('/');
+    let b = b.trim_end_matches('/');
+    let nested = |outer: &str, inner: &str| {
+        outer.is_empty() || inner.strip_prefix(outer).is_some_and(|rest| rest.starts_with('/'))
+    };
+    a == b || nested(a, b) || nested(b, a)
+}
+
+/// 检查插件与正在运行的其他插件之间的冲突
+pub fn find_conflicts(descriptor: &PluginDescriptor, running: &[PluginDescriptor]) -> Vec<PluginConflict> {
+    let mut conflicts = Vec::new();
+    for other in running.iter().filter(|other| other.id != descriptor.id) {
+        for definition in &descriptor.extensions {
+            let gvk = definition.gvk();
+            if other.extensions.iter().any(|d| d.gvk() == gvk) {
+                conflicts.push(PluginConflict::new(EXTENSION_CONFLICT_REASON, gvk.to_string(), &other.id));
+            }
+        }
+        for route in &descriptor.routes {
+            if let Some(existing) = other.routes.iter().find(|r| routes_overlap(route, r)) {
+                let resource = if existing == route { route.clone() } else { format!("{} (overlaps {})", route, existing) };
+                conflicts.push(PluginConflict::new(ROUTE_CONFLICT_REASON, resource, &other.id));
+            }
+        }
+        for name in &descriptor.finders {
+            if other.finders.contains(name) {
+                conflicts.push(PluginConflict::new(FINDER_CONFLICT_REASON, name, &other.id));
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(yaml: &str) -> PluginDescriptor {
+        PluginDescriptor::from_yaml(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_routes_overlap() {
+        assert!(routes_overlap("/apis/moments", "/apis/moments/"));
+        assert!(routes_overlap("/apis/moments", "/apis/moments/v1"));
+        assert!(routes_overlap("/apis/moments/v1", "/apis"));
+        assert!(!routes_overlap("/apis/moments", "/apis/moments-extra"));
+        assert!(!routes_overlap("/apis/links", "/apis/moments"));
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let moments = descriptor(r#"
+id: moments
+version: 1.0.0
+routes: ["/apis/moments"]
+finders: [momentFinder]
+extensions:
+  - {group: moment.halo.run, version: v1alpha1, kind: Moment}
+"#);
+        let copy = descriptor(r#"
+id: moments-copy
+version: 1.0.0
+routes: ["/apis/moments/v2", "/apis/copy"]
+finders: [momentFinder, copyFinder]
+extensions:
+  - {group: moment.halo.run, version: v1alpha1, kind: Moment}
+"#);
+        let conflicts = find_conflicts(&copy, &[moments.clone(), copy.clone()]);
+        let reasons: Vec<&str> = conflicts.iter().map(|c| c.reason).collect();
+        assert_eq!(reasons, vec![EXTENSION_CONFLICT_REASON, ROUTE_CONFLICT_REASON, FINDER_CONFLICT_REASON]);
+        assert!(conflicts.iter().all(|c| c.owner == "moments"));
+        assert_eq!(conflicts[2].message(), "finder momentFinder is already registered by moments");
+        assert!(find_conflicts(&moments, std::slice::from_ref(&moments)).is_empty());
+    }
+}
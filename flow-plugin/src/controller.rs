@@ -0,0 +1,327 @@
+//! 控制器运行时
+//! 控制器监听指定类型扩展对象的变更，将变更对象的名称加入工作队列，由调谐器（Reconciler）逐个处理。
+//! 每个控制器有独立的工作队列和工作任务，按注册者（插件ID）关闭
+
+use crate::plugin::Plugin;
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::GroupVersionKind;
+use flow_api::extension::watch::{ExtensionWatchSource, ExtensionWatcher, WatchEvent, WatchEventType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// 调谐失败后第一次重试的间隔，之后每次失败翻倍
+const ERROR_REQUEUE_BASE: Duration = Duration::from_secs(5);
+
+/// 调谐失败后重试的最大间隔
+const ERROR_REQUEUE_MAX: Duration = Duration::from_secs(300);
+
+/// 调谐请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconcileRequest {
+    /// 控制器监听的类型
+    pub gvk: GroupVersionKind,
+    /// 变更对象的名称，对象可能已被删除
+    pub name: String,
+}
+
+/// 调谐结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileResult {
+    /// 多少秒后重新调谐，None表示不需要
+    #[serde(default)]
+    pub requeue_after: Option<u64>,
+}
+
+/// 调谐器
+#[async_trait]
+pub trait Reconciler: Send + Sync {
+    /// 使对象的实际状态与期望状态一致，返回错误时稍后重试
+    async fn reconcile(&self, request: ReconcileRequest) -> Result<ReconcileResult>;
+}
+
+/// 将调谐请求转发给插件
+pub struct PluginReconciler(pub Arc<dyn Plugin>);
+
+#[async_trait]
+impl Reconciler for PluginReconciler {
+    async fn reconcile(&self, request: ReconcileRequest) -> Result<ReconcileResult> {
+        self.0.reconcile(&request).await
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: VecDeque<String>,
+    queued: HashSet<String>,
+    closed: bool,
+}
+
+/// 去重的工作队列，同一名称在队列中只出现一次
+#[derive(Default)]
+struct WorkQueue {
+    state: Mutex<QueueState>,
+    notify: Notify,
+}
+
+impl WorkQueue {
+    fn add(&self, name: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.closed || !state.queued.insert(name.to_string()) {
+            return;
+        }
+        state.pending.push_back(name.to_string());
+        self.notify.notify_one();
+    }
+
+    /// 延迟一段时间后加入队列
+    fn add_after(self: &Arc<Self>, name: String, delay: Duration) {
+        let queue = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            queue.add(&name);
+        });
+    }
+
+    /// 取出下一个名称，队列关闭后返回None
+    async fn next(&self) -> Option<String> {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.closed {
+                    return None;
+                }
+                if let Some(name) = state.pending.pop_front() {
+                    state.queued.remove(&name);
+                    return Some(name);
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// 关闭队列，丢弃尚未处理的名称
+    fn close(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.closed = true;
+        state.pending.clear();
+        state.queued.clear();
+        self.notify.notify_one();
+    }
+}
+
+struct Controller {
+    owner: String,
+    gvk: GroupVersionKind,
+    queue: Arc<WorkQueue>,
+    /// 控制器处理过的对象名称，删除事件没有类型信息，按名称分发
+    known: Arc<Mutex<HashSet<String>>>,
+    worker: JoinHandle<()>,
+}
+
+type Controllers = Arc<RwLock<Vec<Controller>>>;
+
+/// 将扩展对象变更分发到对应控制器的工作队列
+struct Dispatcher(Controllers);
+
+impl ExtensionWatcher for Dispatcher {
+    fn on_event(&self, event: &WatchEvent) {
+        let controllers = self.0.read().unwrap_or_else(|e| e.into_inner());
+        for controller in controllers.iter() {
+            let matched = match &event.gvk {
+                Some(gvk) => *gvk == controller.gvk,
+                None => controller.known.lock().unwrap_or_else(|e| e.into_inner()).contains(&event.name),
+            };
+            if !matched {
+                continue;
+            }
+            let mut known = controller.known.lock().unwrap_or_else(|e| e.into_inner());
+            if event.event_type == WatchEventType::Deleted {
+                known.remove(&event.name);
+            } else {
+                known.insert(event.name.clone());
+            }
+            controller.queue.add(&event.name);
+        }
+    }
+}
+
+/// 控制器管理器
+pub struct ControllerManager {
+    source: Arc<dyn ExtensionWatchSource>,
+    controllers: Controllers,
+    watch_id: u64,
+}
+
+impl ControllerManager {
+    /// 创建控制器管理器并开始监听扩展对象变更
+    pub fn new(source: Arc<dyn ExtensionWatchSource>) -> Self {
+        let controllers: Controllers = Arc::new(RwLock::new(Vec::new()));
+        let watch_id = source.watch(Arc::new(Dispatcher(controllers.clone())));
+        Self { source, controllers, watch_id }
+    }
+
+    /// 注册控制器，启动处理工作队列的任务
+    pub fn register(&self, owner: &str, gvk: GroupVersionKind, reconciler: Arc<dyn Reconciler>) {
+        let queue = Arc::new(WorkQueue::default());
+        let worker = tokio::spawn(run_worker(gvk.clone(), queue.clone(), reconciler));
+        self.controllers.write().unwrap_or_else(|e| e.into_inner()).push(Controller {
+            owner: owner.to_string(),
+            gvk,
+            queue,
+            known: Arc::new(Mutex::new(HashSet::new())),
+            worker,
+        });
+    }
+
+    /// 关闭注册者的所有控制器，等待正在进行的调谐完成，返回关闭的控制器数量
+    pub async fn shutdown(&self, owner: &str) -> usize {
+        let removed: Vec<Controller> = {
+            let mut controllers = self.controllers.write().unwrap_or_else(|e| e.into_inner());
+            let (removed, kept) = std::mem::take(&mut *controllers).into_iter()
+                .partition(|c| c.owner == owner);
+            *controllers = kept;
+            removed
+        };
+        for controller in &removed {
+            controller.queue.close();
+        }
+        let count = removed.len();
+        for controller in removed {
+            if let Err(e) = controller.worker.await {
+                tracing::warn!("Controller of {} for {} panicked: {}", owner, controller.gvk.to_string(), e);
+            }
+        }
+        count
+    }
+
+    /// 已注册的控制器（注册者，类型）
+    pub fn controllers(&self) -> Vec<(String, GroupVersionKind)> {
+        self.controllers.read().unwrap_or_else(|e| e.into_inner()).iter()
+            .map(|c| (c.owner.clone(), c.gvk.clone()))
+            .collect()
+    }
+}
+
+impl Drop for ControllerManager {
+    fn drop(&mut self) {
+        self.source.unwatch(self.watch_id);
+        for controller in self.controllers.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            controller.queue.close();
+        }
+    }
+}
+
+/// 依次处理工作队列中的对象，失败时按指数退避重试
+async fn run_worker(gvk: GroupVersionKind, queue: Arc<WorkQueue>, reconciler: Arc<dyn Reconciler>) {
+    let mut failures: HashMap<String, u32> = HashMap::new();
+    while let Some(name) = queue.next().await {
+        let request = ReconcileRequest { gvk: gvk.clone(), name: name.clone() };
+        match reconciler.reconcile(request).await {
+            Ok(result) => {
+                failures.remove(&name);
+                if let Some(seconds) = result.requeue_after {
+                    queue.add_after(name, Duration::from_secs(seconds));
+                }
+            }
+            Err(e) => {
+                let attempts = failures.entry(name.clone()).or_insert(0);
+                *attempts += 1;
+                let delay = error_backoff(*attempts);
+                tracing::warn!("Failed to reconcile {} {}, retrying in {:?}: {}", gvk.to_string(), name, delay, e);
+                queue.add_after(name, delay);
+            }
+        }
+    }
+}
+
+/// 第n次连续失败后的重试间隔
+fn error_backoff(attempts: u32) -> Duration {
+    ERROR_REQUEUE_BASE
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(ERROR_REQUEUE_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[derive(Default)]
+    struct Source(RwLock<Vec<Arc<dyn ExtensionWatcher>>>);
+
+    impl ExtensionWatchSource for Source {
+        fn watch(&self, watcher: Arc<dyn ExtensionWatcher>) -> u64 {
+            self.0.write().unwrap().push(watcher);
+            0
+        }
+
+        fn unwatch(&self, _id: u64) {
+            self.0.write().unwrap().clear();
+        }
+    }
+
+    impl Source {
+        fn emit(&self, event_type: WatchEventType, gvk: Option<GroupVersionKind>, name: &str) {
+            let event = WatchEvent { event_type, gvk, name: name.to_string() };
+            for watcher in self.0.read().unwrap().iter() {
+                watcher.on_event(&event);
+            }
+        }
+    }
+
+    struct ChannelReconciler(mpsc::UnboundedSender<String>);
+
+    #[async_trait]
+    impl Reconciler for ChannelReconciler {
+        async fn reconcile(&self, request: ReconcileRequest) -> Result<ReconcileResult> {
+            self.0.send(request.name)?;
+            Ok(ReconcileResult::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_and_shutdown() {
+        let source = Arc::new(Source::default());
+        let manager = ControllerManager::new(source.clone());
+        let moment = GroupVersionKind::new("moment.halo.run", "v1alpha1", "Moment");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        manager.register("moments", moment.clone(), Arc::new(ChannelReconciler(tx)));
+
+        source.emit(WatchEventType::Added, Some(GroupVersionKind::new("content.halo.run", "v1alpha1", "Post")), "hello");
+        source.emit(WatchEventType::Added, Some(moment.clone()), "moment-1");
+        assert_eq!(rx.recv().await.unwrap(), "moment-1");
+        // 删除事件没有类型，按控制器处理过的名称分发
+        source.emit(WatchEventType::Deleted, None, "hello");
+        source.emit(WatchEventType::Deleted, None, "moment-1");
+        assert_eq!(rx.recv().await.unwrap(), "moment-1");
+
+        assert_eq!(manager.shutdown("moments").await, 1);
+        assert!(manager.controllers().is_empty());
+        source.emit(WatchEventType::Updated, Some(moment), "moment-2");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_work_queue_dedup() {
+        let queue = WorkQueue::default();
+        queue.add("a");
+        queue.add("b");
+        queue.add("a");
+        assert_eq!(queue.next().await.as_deref(), Some("a"));
+        assert_eq!(queue.next().await.as_deref(), Some("b"));
+        queue.add("c");
+        queue.close();
+        queue.add("d");
+        assert_eq!(queue.next().await, None);
+
+        assert_eq!(error_backoff(1), Duration::from_secs(5));
+        assert_eq!(error_backoff(3), Duration::from_secs(20));
+        assert_eq!(error_backoff(20), ERROR_REQUEUE_MAX);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::descriptor::PluginDescriptor;
+use anyhow::{anyhow, Result};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 计算插件的启动顺序：依赖在前，依赖方在后
+///
+/// 未加载的依赖不影响排序（启动时再检查），存在循环依赖时返回错误
+pub fn startup_order<'a>(descriptors: impl IntoIterator<Item = &'a PluginDescriptor>) -> Result<Vec<String>> {
+    let descriptors: BTreeMap<&str, &PluginDescriptor> = descriptors.into_iter()
+        .map(|d| (d.id.as_str(), d))
+        .collect();
+    // 每个插件尚未排序的已加载依赖数量
+    let mut pending: BTreeMap<&str, usize> = descriptors.iter()
+        .map(|(id, d)| (*id, d.dependencies.keys().filter(|dep| descriptors.contains_key(dep.as_str())).count()))
+        .collect();
+    let mut ready: BTreeSet<&str> = pending.iter().filter(|(_, count)| **count == 0).map(|(id, _)| *id).collect();
+    let mut order = Vec::with_capacity(descriptors.len());
+    while let Some(id) = ready.pop_first() {
+        pending.remove(id);
+        order.push(id.to_string());
+        for (dependent, descriptor) in &descriptors {
+            if descriptor.dependencies.contains_key(id) {
+                if let Some(count) = pending.get_mut(dependent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.insert(dependent);
+                    }
+                }
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let cycle: Vec<&str> = pending.keys().copied().collect();
+        return Err(anyhow!("Circular plugin dependencies among: {}", cycle.join(", ")));
+    }
+    Ok(order)
+}
+
+/// 检查依赖的插件版本是否满足版本要求（SemVer范围，如 `>=1.0.0`、`*`）
+pub fn check_dependency_version(dependency: &str, requirement: &str, version: &str) -> Result<(), String> {
+    let requirement_range = VersionReq::parse(requirement.trim())
+        .map_err(|e| format!("Invalid version requirement {:?} of dependency {}: {}", requirement, dependency, e))?;
+    let version = Version::parse(version.trim())
+        .map_err(|e| format!("Invalid version {:?} of dependency {}: {}", version, dependency, e))?;
+    if requirement_range.matches(&version) {
+        Ok(())
+    } else {
+        Err(format!("Plugin requires {} {}, but the loaded version is {}", dependency, requirement, version))
+    }
+}
+
+/// 直接或间接依赖指定插件的插件ID，按停止顺序排列（最外层的依赖方在前）
+pub fn dependents<'a>(plugin_id: &str, descriptors: impl IntoIterator<Item = &'a PluginDescriptor>) -> Vec<String> {
+    let descriptors: Vec<&PluginDescriptor> = descriptors.into_iter().collect();
+    let mut found: Vec<String> = Vec::new();
+    let mut queue = vec![plugin_id.to_string()];
+    while let Some(id) = queue.pop() {
+        for descriptor in &descriptors {
+            if descriptor.dependencies.contains_key(&id) && descriptor.id != plugin_id && !found.contains(&descriptor.id) {
+                found.push(descriptor.id.clone());
+                queue.push(descriptor.id.clone());
+            }
+        }
+    }
+    // 按启动顺序的逆序停止，存在循环依赖时保持发现顺序
+    match startup_order(descriptors.into_iter().filter(|d| found.contains(&d.id))) {
+        Ok(mut order) => {
+            order.reverse();
+            order
+        }
+        Err(_) => found,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn descriptor(id: &str, dependencies: &[(&str, &str)]) -> PluginDescriptor {
+        PluginDescriptor {
+            id: id.to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            provider: None,
+            dependencies: dependencies.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>(),
+            plugin_class: None,
+            plugin_lib: None,
+            requires: "*".to_string(),
+            license: Vec::new(),
+            extensions: Vec::new(),
+            events: Vec::new(),
+            capabilities: Default::default(),
+            migration_level: 0,
+            finders: Vec::new(),
+            routes: Vec::new(),
+            reconcilers: Vec::new(),
+            api_version: None,
+            abi: None,
+        }
+    }
+
+    #[test]
+    fn test_startup_order() {
+        let plugins = [
+            descriptor("comment-widget", &[("editor", "*"), ("core-ui", ">=1.0.0")]),
+            descriptor("editor", &[("core-ui", "*")]),
+            descriptor("core-ui", &[]),
+            descriptor("search", &[("missing", "*")]),
+        ];
+        assert_eq!(startup_order(&plugins).unwrap(), vec!["core-ui", "editor", "comment-widget", "search"]);
+        assert_eq!(dependents("core-ui", &plugins), vec!["comment-widget", "editor"]);
+        assert!(dependents("search", &plugins).is_empty());
+
+        let cycle = [descriptor("a", &[("b", "*")]), descriptor("b", &[("a", "*")]), descriptor("c", &[])];
+        assert_eq!(startup_order(&cycle).unwrap_err().to_string(), "Circular plugin dependencies among: a, b");
+    }
+
+    #[test]
+    fn test_check_dependency_version() {
+        assert!(check_dependency_version("editor", "*", "0.1.0").is_ok());
+        assert!(check_dependency_version("editor", ">=1.2.0", "1.10.0").is_ok());
+        assert!(check_dependency_version("editor", "^2", "1.10.0").unwrap_err().contains("loaded version is 1.10.0"));
+        assert!(check_dependency_version("editor", "latest", "1.0.0").unwrap_err().starts_with("Invalid version requirement"));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::capability::PluginCapabilities;
+use flow_api::extension::GroupVersionKind;
+use flow_api::extension::scheme::{Scheme, SchemeIndex};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -18,7 +21,7 @@ pub struct PluginDescriptor {
     pub provider: Option<String>,
     
     /// 插件依赖（插件ID -> 版本要求）
-    #[serde(default)]
+    #[serde(default, alias = "pluginDependencies")]
     pub dependencies: HashMap<String, String>,
     
     /// 插件类名（Rust插件的入口点）
@@ -34,6 +37,75 @@ pub struct PluginDescriptor {
     /// 许可证
     #[serde(default)]
     pub license: Vec<String>,
+    
+    /// 插件注册的扩展类型，插件启动时注册到Scheme中，停止时移除
+    #[serde(default)]
+    pub extensions: Vec<ExtensionDefinition>,
+    
+    /// 插件订阅的事件类型（如 `PostPublished`，`*` 表示所有事件），插件启动后开始接收
+    #[serde(default)]
+    pub events: Vec<String>,
+    
+    /// 插件提供的模板Finder名称，插件启动后注册，主题模板中以该名称访问插件返回的数据
+    #[serde(default)]
+    pub finders: Vec<String>,
+    
+    /// 插件处理的HTTP路由前缀，与其他插件的前缀重叠时插件无法启动
+    #[serde(default)]
+    pub routes: Vec<String>,
+    
+    /// 插件调谐的扩展类型，插件启动后为每个类型注册控制器，对象变更时调用插件的调谐函数
+    #[serde(default)]
+    pub reconcilers: Vec<GroupVersionKind>,
+    
+    /// 插件需要的能力，管理员批准后插件才能启动
+    #[serde(default)]
+    pub capabilities: PluginCapabilities,
+    
+    /// 插件提供的数据迁移级别，启动时依次执行未应用的迁移（1..=该级别）
+    #[serde(default)]
+    pub migration_level: u32,
+    
+    /// 原生插件编译时使用的插件API版本，见 [`crate::abi::HOST_API_VERSION`]
+    #[serde(default, alias = "apiVersion")]
+    pub api_version: Option<u32>,
+    
+    /// 原生插件的ABI指纹（rustc版本和目标平台），见 [`crate::abi::host_abi_fingerprint`]
+    #[serde(default)]
+    pub abi: Option<String>,
+}
+
+/// 插件注册的扩展类型定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionDefinition {
+    pub group: String,
+    pub version: String,
+    pub kind: String,
+    /// 校验扩展对象的JSON Schema
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
+    /// 字段索引，可在字段选择器中使用
+    #[serde(default)]
+    pub indexes: Vec<SchemeIndex>,
+}
+
+impl ExtensionDefinition {
+    pub fn gvk(&self) -> GroupVersionKind {
+        GroupVersionKind::new(&self.group, &self.version, &self.kind)
+    }
+    
+    /// 转换为Scheme，类型名称记录注册该类型的插件
+    pub fn to_scheme(&self, plugin_id: &str) -> Scheme {
+        let mut scheme = Scheme::new(self.gvk(), scheme_owner(plugin_id));
+        scheme.json_schema = self.schema.clone();
+        scheme.indexes = self.indexes.clone();
+        scheme
+    }
+}
+
+/// 插件注册的Scheme的类型名称
+pub fn scheme_owner(plugin_id: &str) -> String {
+    format!("plugin:{}", plugin_id)
 }
 
 fn default_requires() -> String {
@@ -0,0 +1,186 @@
+//! 插件事件
+//! 插件生命周期事件和领域事件通过事件总线分发给订阅的插件
+
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// 插件已启动
+pub const PLUGIN_STARTED: &str = "PluginStarted";
+/// 插件已停止
+pub const PLUGIN_STOPPED: &str = "PluginStopped";
+/// 插件已卸载
+pub const PLUGIN_DELETED: &str = "PluginDeleted";
+/// 插件健康检查连续失败，已被标记为失败
+pub const PLUGIN_FAILED: &str = "PluginFailed";
+/// 文章已发布
+pub const POST_PUBLISHED: &str = "PostPublished";
+/// 评论已创建（不包括垃圾评论）
+pub const COMMENT_CREATED: &str = "CommentCreated";
+/// 用户已登录
+pub const USER_LOGGED_IN: &str = "UserLoggedIn";
+/// 用户已注册
+pub const USER_REGISTERED: &str = "UserRegistered";
+
+/// 订阅所有事件类型
+pub const ALL_EVENTS: &str = "*";
+
+/// 事件，序列化后的JSON即插件收到的事件内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// 事件类型，如 `PostPublished`
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// 事件数据
+    #[serde(default)]
+    pub data: Value,
+    /// 事件发生时间
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Event {
+    pub fn new(event_type: impl Into<String>, data: Value) -> Self {
+        Self {
+            event_type: event_type.into(),
+            data,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// 插件生命周期事件，数据包含插件ID和版本
+    pub fn plugin(event_type: &str, plugin_id: &str, version: &str) -> Self {
+        Self::new(event_type, serde_json::json!({"pluginId": plugin_id, "version": version}))
+    }
+}
+
+/// 事件监听器
+#[async_trait]
+pub trait EventListener: Send + Sync {
+    async fn on_event(&self, event: &Event) -> Result<()>;
+}
+
+struct Subscription {
+    id: u64,
+    /// 订阅者（插件ID），用于按插件取消订阅
+    owner: String,
+    event_types: Vec<String>,
+    listener: Arc<dyn EventListener>,
+}
+
+impl Subscription {
+    fn matches(&self, event_type: &str) -> bool {
+        self.event_types.iter().any(|t| t == event_type || t == ALL_EVENTS)
+    }
+}
+
+/// 事件总线
+/// 发布事件时异步通知订阅者，监听器失败只记录日志，不影响发布方
+#[derive(Default)]
+pub struct EventBus {
+    subscriptions: RwLock<Vec<Subscription>>,
+    next_id: AtomicU64,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅事件类型（`*` 表示所有事件），返回订阅ID
+    pub fn subscribe(&self, owner: &str, event_types: Vec<String>, listener: Arc<dyn EventListener>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut subscriptions = self.subscriptions.write().unwrap_or_else(|e| e.into_inner());
+        subscriptions.push(Subscription { id, owner: owner.to_string(), event_types, listener });
+        id
+    }
+
+    /// 取消订阅
+    pub fn unsubscribe(&self, id: u64) {
+        let mut subscriptions = self.subscriptions.write().unwrap_or_else(|e| e.into_inner());
+        subscriptions.retain(|s| s.id != id);
+    }
+
+    /// 取消订阅者的所有订阅
+    pub fn unsubscribe_owner(&self, owner: &str) {
+        let mut subscriptions = self.subscriptions.write().unwrap_or_else(|e| e.into_inner());
+        subscriptions.retain(|s| s.owner != owner);
+    }
+
+    /// 订阅了事件类型的监听器
+    fn listeners(&self, event_type: &str) -> Vec<(String, Arc<dyn EventListener>)> {
+        let subscriptions = self.subscriptions.read().unwrap_or_else(|e| e.into_inner());
+        subscriptions.iter()
+            .filter(|s| s.matches(event_type))
+            .map(|s| (s.owner.clone(), s.listener.clone()))
+            .collect()
+    }
+
+    /// 发布事件，每个监听器在独立的任务中处理，不等待处理完成
+    pub fn publish(&self, event: Event) {
+        let listeners = self.listeners(&event.event_type);
+        if listeners.is_empty() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!("Dropped event {}: no async runtime", event.event_type);
+            return;
+        };
+        let event = Arc::new(event);
+        for (owner, listener) in listeners {
+            let event = event.clone();
+            runtime.spawn(async move {
+                if let Err(e) = listener.on_event(&event).await {
+                    tracing::warn!("Subscriber {} failed to handle event {}: {}", owner, event.event_type, e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    struct ChannelListener(mpsc::UnboundedSender<String>);
+
+    #[async_trait]
+    impl EventListener for ChannelListener {
+        async fn on_event(&self, event: &Event) -> Result<()> {
+            self.0.send(event.event_type.clone())?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_to_subscribers() {
+        let bus = EventBus::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        bus.subscribe("analytics", vec![POST_PUBLISHED.to_string()], Arc::new(ChannelListener(tx.clone())));
+        let all = bus.subscribe("audit", vec![ALL_EVENTS.to_string()], Arc::new(ChannelListener(tx)));
+
+        bus.publish(Event::new(COMMENT_CREATED, Value::Null));
+        assert_eq!(rx.recv().await.unwrap(), COMMENT_CREATED);
+        bus.publish(Event::new(POST_PUBLISHED, Value::Null));
+        assert_eq!(rx.recv().await.unwrap(), POST_PUBLISHED);
+        assert_eq!(rx.recv().await.unwrap(), POST_PUBLISHED);
+
+        bus.unsubscribe(all);
+        bus.unsubscribe_owner("analytics");
+        bus.publish(Event::new(POST_PUBLISHED, Value::Null));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = Event::plugin(PLUGIN_STARTED, "search", "1.0.0");
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "PluginStarted");
+        assert_eq!(json["data"], serde_json::json!({"pluginId": "search", "version": "1.0.0"}));
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+    }
+}
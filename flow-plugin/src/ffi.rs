@@ -2,6 +2,9 @@
 //! 用于支持Java插件和Rust插件之间的互操作
 
 use anyhow::Result;
+use async_trait::async_trait;
+use crate::event::{Event, EventListener};
+use std::ffi::{c_char, c_void, CString};
 use std::path::Path;
 
 /// Java插件桥接器
@@ -56,3 +59,48 @@ impl Default for JavaPluginBridge {
     }
 }
 
+
+/// 事件回调接口版本，回调签名或事件JSON结构不兼容变更时递增
+pub const EVENT_CALLBACK_ABI_VERSION: u32 = 1;
+
+/// 原生插件的事件回调
+///
+/// - `event_type`: 事件类型（以NUL结尾的UTF-8字符串，如 `PostPublished`）
+/// - `event_json`: 完整事件的JSON（`{"type", "data", "timestamp"}`，以NUL结尾的UTF-8字符串）
+/// - `user_data`: 订阅时传入的指针，原样传回
+///
+/// 字符串只在回调期间有效，返回0表示处理成功
+pub type FlowEventCallback = extern "C" fn(event_type: *const c_char, event_json: *const c_char, user_data: *mut c_void) -> i32;
+
+/// 通过C回调接收事件的监听器，供原生插件订阅事件总线
+pub struct FfiEventListener {
+    callback: FlowEventCallback,
+    user_data: *mut c_void,
+}
+
+// 回调可能在任意线程上调用，由插件保证回调和user_data是线程安全的
+unsafe impl Send for FfiEventListener {}
+unsafe impl Sync for FfiEventListener {}
+
+impl FfiEventListener {
+    /// 创建回调监听器
+    ///
+    /// # Safety
+    /// 监听器存续期间 `callback` 必须有效，`user_data` 必须可以在任意线程上使用
+    pub unsafe fn new(callback: FlowEventCallback, user_data: *mut c_void) -> Self {
+        Self { callback, user_data }
+    }
+}
+
+#[async_trait]
+impl EventListener for FfiEventListener {
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        let event_type = CString::new(event.event_type.as_str())?;
+        let event_json = CString::new(serde_json::to_string(event)?)?;
+        let code = (self.callback)(event_type.as_ptr(), event_json.as_ptr(), self.user_data);
+        if code != 0 {
+            return Err(anyhow::anyhow!("Event callback returned error code {}", code));
+        }
+        Ok(())
+    }
+}
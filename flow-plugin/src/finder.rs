@@ -0,0 +1,76 @@
+//! 插件提供的模板Finder
+//! 插件在描述符中声明Finder名称，启动后注册到Finder注册表，主题模板中以Finder名称访问插件返回的数据
+
+use crate::plugin::Plugin;
+use async_trait::async_trait;
+use flow_api::theme::Finder;
+use std::sync::Arc;
+
+/// 将Finder的查询转发给插件
+pub struct PluginFinder {
+    name: String,
+    plugin: Arc<dyn Plugin>,
+}
+
+impl PluginFinder {
+    pub fn new(name: impl Into<String>, plugin: Arc<dyn Plugin>) -> Self {
+        Self { name: name.into(), plugin }
+    }
+}
+
+#[async_trait]
+impl Finder for PluginFinder {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn preload(&self) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        self.plugin.find(&self.name).await
+            .map_err(|e| format!("Plugin {} failed to find {}: {}", self.plugin.descriptor().id, self.name, e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::PluginDescriptor;
+    use anyhow::Result;
+
+    struct MomentsPlugin(PluginDescriptor);
+
+    #[async_trait]
+    impl Plugin for MomentsPlugin {
+        fn descriptor(&self) -> &PluginDescriptor {
+            &self.0
+        }
+
+        async fn start(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find(&self, finder: &str) -> Result<Option<serde_json::Value>> {
+            match finder {
+                "momentFinder" => Ok(Some(serde_json::json!([{"content": "hello"}]))),
+                _ => anyhow::bail!("unknown finder"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preload_from_plugin() {
+        let descriptor = PluginDescriptor::from_yaml("id: moments\nversion: 1.0.0\nfinders: [momentFinder]\n").unwrap();
+        assert_eq!(descriptor.finders, vec!["momentFinder"]);
+        let plugin: Arc<dyn Plugin> = Arc::new(MomentsPlugin(descriptor));
+
+        let finder = PluginFinder::new("momentFinder", plugin.clone());
+        assert_eq!(finder.name(), "momentFinder");
+        assert_eq!(finder.preload().await.unwrap(), Some(serde_json::json!([{"content": "hello"}])));
+
+        let err = PluginFinder::new("linkFinder", plugin).preload().await.unwrap_err();
+        assert!(err.to_string().contains("moments"));
+    }
+}
@@ -1,10 +1,34 @@
+pub mod abi;
+pub mod capability;
+pub mod conflict;
+pub mod controller;
 pub mod descriptor;
+pub mod dependency;
+pub mod event;
+pub mod finder;
+pub mod metrics;
+pub mod migration;
+pub mod probe;
 pub mod plugin;
 pub mod manager;
 pub mod loader;
+pub mod log;
 pub mod ffi;
+pub mod wasm;
+pub mod validation;
+pub mod watcher;
 
+pub use capability::PluginCapabilities;
 pub use descriptor::PluginDescriptor;
-pub use plugin::{Plugin, PluginWrapper, PluginState};
+pub use plugin::{Plugin, PluginWrapper, PluginState, PluginCondition, PluginHttpRequest, PluginHttpResponse};
 pub use manager::{PluginManager, DefaultPluginManager};
 pub use loader::{PluginLoader, DynamicLibraryLoader, DirectoryPluginLoader};
+pub use event::{Event, EventBus, EventListener};
+pub use finder::PluginFinder;
+pub use controller::{ControllerManager, Reconciler, ReconcileRequest, ReconcileResult};
+pub use log::{PluginLogBuffer, PluginLogEntry};
+pub use metrics::{MeteredPlugin, PluginMetrics, PluginStats};
+pub use migration::{MigrationStore, PluginMigrationState};
+pub use probe::{PluginProbe, ProbeState};
+pub use wasm::{WasmPlugin, WasmPluginLoader, ExtensionHost};
+pub use watcher::spawn_plugin_watcher;
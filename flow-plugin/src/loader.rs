@@ -1,7 +1,9 @@
+use crate::abi::check_native_compatibility;
 use crate::descriptor::PluginDescriptor;
 use crate::plugin::Plugin;
 use anyhow::Result;
-use std::path::Path;
+use crate::wasm::WasmPluginLoader;
+use std::path::{Path, PathBuf};
 use libloading::{Library, Symbol};
 
 /// 插件加载器trait
@@ -36,7 +38,9 @@ impl PluginLoader for DynamicLibraryLoader {
         Err(anyhow::anyhow!("Dynamic library descriptor loading not yet implemented"))
     }
     
-    fn load_plugin(&self, plugin_path: &Path, _descriptor: &PluginDescriptor) -> Result<Box<dyn Plugin>> {
+    fn load_plugin(&self, plugin_path: &Path, descriptor: &PluginDescriptor) -> Result<Box<dyn Plugin>> {
+        // 不兼容的动态库在加载或调用符号时可能直接导致宿主崩溃，必须在打开动态库之前检查
+        check_native_compatibility(descriptor)?;
         unsafe {
             // 加载动态库
             let lib = Library::new(plugin_path)?;
@@ -52,8 +56,39 @@ impl PluginLoader for DynamicLibraryLoader {
 }
 
 /// 目录插件加载器
-/// 用于开发模式，从目录加载插件
-pub struct DirectoryPluginLoader;
+/// 用于开发模式，从目录加载插件：描述符为目录下的plugin.yaml，
+/// 插件实例从描述符 `plugin_lib` 指定的构建产物（相对插件目录的 `.wasm` 或动态库）加载
+pub struct DirectoryPluginLoader {
+    wasm_loader: WasmPluginLoader,
+}
+
+impl DirectoryPluginLoader {
+    pub fn new() -> Self {
+        Self { wasm_loader: WasmPluginLoader::new() }
+    }
+    
+    /// 设置加载 `.wasm` 构建产物的加载器（如需提供宿主接口）
+    pub fn with_wasm_loader(mut self, wasm_loader: WasmPluginLoader) -> Self {
+        self.wasm_loader = wasm_loader;
+        self
+    }
+    
+    /// 插件目录的描述符文件
+    pub fn descriptor_path(plugin_path: &Path) -> PathBuf {
+        plugin_path.join("plugin.yaml")
+    }
+    
+    /// 插件的构建产物，描述符未声明 `plugin_lib` 时为None
+    pub fn build_output(plugin_path: &Path, descriptor: &PluginDescriptor) -> Option<PathBuf> {
+        descriptor.plugin_lib.as_deref().map(|lib| plugin_path.join(lib))
+    }
+}
+
+impl Default for DirectoryPluginLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl PluginLoader for DirectoryPluginLoader {
     fn is_applicable(&self, plugin_path: &Path) -> bool {
@@ -61,7 +96,7 @@ impl PluginLoader for DirectoryPluginLoader {
     }
     
     fn load_descriptor(&self, plugin_path: &Path) -> Result<PluginDescriptor> {
-        let descriptor_path = plugin_path.join("plugin.yaml");
+        let descriptor_path = Self::descriptor_path(plugin_path);
         
         // 使用阻塞方式读取（简化实现）
         let content = std::fs::read_to_string(&descriptor_path)
@@ -71,10 +106,17 @@ impl PluginLoader for DirectoryPluginLoader {
             .map_err(|e| anyhow::anyhow!("Failed to parse descriptor: {}", e))
     }
     
-    fn load_plugin(&self, _plugin_path: &Path, _descriptor: &PluginDescriptor) -> Result<Box<dyn Plugin>> {
-        // 目录插件通常用于开发模式，需要编译后加载
-        // 这里返回错误，表示需要先编译
-        Err(anyhow::anyhow!("Directory plugin loading requires compilation"))
+    fn load_plugin(&self, plugin_path: &Path, descriptor: &PluginDescriptor) -> Result<Box<dyn Plugin>> {
+        // 目录插件需要先编译，构建产物由plugin_lib指定
+        let output = Self::build_output(plugin_path, descriptor)
+            .ok_or_else(|| anyhow::anyhow!("Plugin {} does not declare plugin_lib", descriptor.id))?;
+        if !output.is_file() {
+            return Err(anyhow::anyhow!("Build output of plugin {} not found: {:?}", descriptor.id, output));
+        }
+        if self.wasm_loader.is_applicable(&output) {
+            self.wasm_loader.load_plugin(&output, descriptor)
+        } else {
+            DynamicLibraryLoader.load_plugin(&output, descriptor)
+        }
     }
 }
-
@@ -0,0 +1,116 @@
+//! 插件日志
+//! 保存插件代码输出的最近日志，并广播给正在查看日志的客户端
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+/// 每个插件默认保留的日志行数
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// 实时日志通道的容量，查看者处理不及时会丢失超出部分
+const LIVE_CHANNEL_CAPACITY: usize = 256;
+
+/// 一行插件日志
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginLogEntry {
+    pub plugin_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// 日志级别：ERROR、WARN、INFO、DEBUG、TRACE
+    pub level: String,
+    pub message: String,
+}
+
+/// 按插件保存最近日志的环形缓冲区
+pub struct PluginLogBuffer {
+    capacity: usize,
+    lines: RwLock<HashMap<String, VecDeque<PluginLogEntry>>>,
+    live: broadcast::Sender<PluginLogEntry>,
+}
+
+impl PluginLogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let (live, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Self { capacity: capacity.max(1), lines: RwLock::new(HashMap::new()), live }
+    }
+
+    /// 追加一行日志，超出容量时丢弃最早的日志
+    pub fn append(&self, plugin_id: &str, level: &str, message: impl Into<String>) {
+        let entry = PluginLogEntry {
+            plugin_id: plugin_id.to_string(),
+            timestamp: Utc::now(),
+            level: level.to_string(),
+            message: message.into(),
+        };
+        {
+            let mut lines = self.lines.write().unwrap_or_else(|e| e.into_inner());
+            let buffer = lines.entry(plugin_id.to_string()).or_default();
+            if buffer.len() == self.capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(entry.clone());
+        }
+        // 没有查看者时发送失败，忽略
+        let _ = self.live.send(entry);
+    }
+
+    /// 插件最近的日志，按时间顺序，最多 `limit` 行
+    pub fn recent(&self, plugin_id: &str, limit: Option<usize>) -> Vec<PluginLogEntry> {
+        let lines = self.lines.read().unwrap_or_else(|e| e.into_inner());
+        let Some(buffer) = lines.get(plugin_id) else {
+            return Vec::new();
+        };
+        let skip = limit.map(|limit| buffer.len().saturating_sub(limit)).unwrap_or(0);
+        buffer.iter().skip(skip).cloned().collect()
+    }
+
+    /// 订阅之后追加的所有插件日志
+    pub fn subscribe(&self) -> broadcast::Receiver<PluginLogEntry> {
+        self.live.subscribe()
+    }
+
+    /// 清除插件的日志，插件卸载时调用
+    pub fn clear(&self, plugin_id: &str) {
+        self.lines.write().unwrap_or_else(|e| e.into_inner()).remove(plugin_id);
+    }
+}
+
+impl Default for PluginLogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer() {
+        let buffer = PluginLogBuffer::new(3);
+        for i in 0..5 {
+            buffer.append("links", "INFO", format!("line {}", i));
+        }
+        buffer.append("moments", "WARN", "other");
+        let messages: Vec<String> = buffer.recent("links", None).into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(buffer.recent("links", Some(1))[0].message, "line 4");
+
+        buffer.clear("links");
+        assert!(buffer.recent("links", None).is_empty());
+        assert_eq!(buffer.recent("moments", None).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let buffer = PluginLogBuffer::default();
+        buffer.append("links", "INFO", "before");
+        let mut live = buffer.subscribe();
+        buffer.append("links", "ERROR", "after");
+        let entry = live.recv().await.unwrap();
+        assert_eq!((entry.plugin_id.as_str(), entry.level.as_str(), entry.message.as_str()), ("links", "ERROR", "after"));
+    }
+}
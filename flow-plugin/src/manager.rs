@@ -1,11 +1,36 @@
 use async_trait::async_trait;
-use crate::plugin::{Plugin, PluginWrapper, PluginState};
-use crate::descriptor::PluginDescriptor;
+use crate::capability::PluginCapabilities;
+use crate::conflict::{find_conflicts, is_conflict_reason, PluginConflict, EXTENSION_CONFLICT_REASON, FINDER_CONFLICT_REASON};
+use crate::dependency::{check_dependency_version, dependents, startup_order};
+use crate::abi::{is_incompatible, INCOMPATIBLE_BINARY_REASON};
+use crate::validation::validate_descriptor;
+use crate::plugin::{PluginWrapper, PluginState, PluginCondition};
+use crate::descriptor::{scheme_owner, PluginDescriptor};
+use crate::controller::{ControllerManager, PluginReconciler};
+use crate::finder::PluginFinder;
+use crate::event::{Event, EventBus, EventListener, PLUGIN_DELETED, PLUGIN_FAILED, PLUGIN_STARTED, PLUGIN_STOPPED};
+use crate::loader::PluginLoader;
+use crate::metrics::{MeteredPlugin, PluginMetrics, PluginStats};
+use crate::migration::{pending_migrations, upgraded_from, MigrationStore, PluginMigrationState};
+use crate::probe::{PluginProbe, DEFAULT_FAILURE_THRESHOLD, DEFAULT_PROBE_TIMEOUT};
+use crate::plugin::Plugin;
+use flow_api::extension::scheme::{Scheme, SchemeManager, SharedSchemeManager};
+use flow_api::extension::GroupVersionKind;
+use flow_api::theme::FinderRegistry;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::collections::HashMap;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 与扩展定义同组同名（不区分版本）且不属于该插件的Scheme
+///
+/// 宿主的内置类型在加载插件前注册，插件不能以任何版本注册同名类型来获得其写权限
+fn foreign_scheme<'a>(schemes: &'a dyn SchemeManager, gvk: &GroupVersionKind, owner: &str) -> Option<&'a Scheme> {
+    schemes.list().into_iter()
+        .find(|s| s.gvk.group == gvk.group && s.gvk.kind == gvk.kind && s.type_name != owner)
+}
 
 /// 插件管理器trait
 #[async_trait]
@@ -17,17 +42,57 @@ pub trait PluginManager: Send + Sync {
     async fn load_plugin(&self, plugin_path: PathBuf) -> Result<String>;
     
     /// 启动插件
+    /// 依赖的插件未加载、未启动或版本不满足要求，或声明的能力未被批准时拒绝启动
     /// 
     /// # 参数
     /// - `plugin_id`: 插件ID
     async fn start_plugin(&self, plugin_id: &str) -> Result<()>;
     
     /// 停止插件
+    /// 先停止直接或间接依赖该插件的已启动插件
     /// 
     /// # 参数
     /// - `plugin_id`: 插件ID
     async fn stop_plugin(&self, plugin_id: &str) -> Result<()>;
     
+    /// 从原路径重新加载插件
+    /// 插件已启动时先停止（连同依赖它的插件），重新加载后再启动，并重新应用最后的配置
+    /// 
+    /// # 参数
+    /// - `plugin_id`: 插件ID
+    async fn reload_plugin(&self, plugin_id: &str) -> Result<()>;
+    
+    /// 通知已启动的插件配置已变更，插件未加载或未启动时忽略
+    /// 配置会被保留，插件重新启动后重新应用
+    /// 
+    /// # 参数
+    /// - `plugin_id`: 插件ID
+    /// - `config`: 解析后的配置值
+    async fn notify_config_change(&self, plugin_id: &str, config: serde_json::Value) -> Result<()>;
+    
+    /// 按依赖顺序启动所有已加载的插件
+    /// 单个插件启动失败时只记录日志，依赖它的插件也不会启动
+    async fn start_plugins(&self) -> Result<()>;
+    
+    /// 按依赖顺序的逆序停止所有已启动的插件
+    async fn stop_plugins(&self) -> Result<()>;
+    
+    /// 记录管理员批准的插件能力，插件声明的能力全部被批准后才能启动
+    /// 
+    /// # 参数
+    /// - `plugin_id`: 插件ID
+    /// - `capabilities`: 批准的能力
+    async fn approve_capabilities(&self, plugin_id: &str, capabilities: PluginCapabilities);
+    
+    /// 获取已批准的插件能力，未批准过时返回None
+    async fn approved_capabilities(&self, plugin_id: &str) -> Option<PluginCapabilities>;
+    
+    /// 扫描插件根目录，加载尚未加载的插件包（可被加载器处理的文件，或包含 `plugin.yaml` 的目录）
+    /// 
+    /// # 返回
+    /// - 本次加载的插件ID，加载失败的插件包只记录日志
+    async fn scan_and_load(&self) -> Result<Vec<String>>;
+    
     /// 卸载插件
     /// 
     /// # 参数
@@ -48,6 +113,19 @@ pub trait PluginManager: Send + Sync {
     
     /// 获取已启动的插件列表
     async fn get_started_plugins(&self) -> Vec<Arc<PluginWrapper>>;
+    
+    /// 对所有已启动的插件执行一次健康检查，返回本次探测的插件及结果
+    /// 连续失败达到阈值的插件被标记为失败（依赖它的插件被停止），并发布 `PluginFailed` 事件
+    async fn probe_plugins(&self) -> Vec<(String, PluginProbe)>;
+    
+    /// 获取插件最近一次的探测结果，插件启动后尚未探测时返回None
+    async fn get_probe(&self, plugin_id: &str) -> Option<PluginProbe>;
+    
+    /// 获取插件的调用统计和内存占用，插件未加载时返回None
+    async fn get_stats(&self, plugin_id: &str) -> Option<PluginStats>;
+    
+    /// 获取所有已加载插件的调用统计，按插件ID排序
+    async fn get_all_stats(&self) -> Vec<PluginStats>;
 }
 
 /// 默认插件管理器实现
@@ -57,6 +135,73 @@ pub struct DefaultPluginManager {
     
     /// 插件根目录
     plugins_root: PathBuf,
+    
+    /// 注册插件扩展类型的Scheme管理器
+    scheme_manager: Option<SharedSchemeManager>,
+    
+    /// 分发生命周期事件和插件订阅事件的事件总线
+    event_bus: Option<Arc<EventBus>>,
+    
+    /// 插件加载器，按注册顺序使用第一个适用的加载器
+    loaders: Vec<Arc<dyn PluginLoader>>,
+    
+    /// 插件最后的配置：插件ID -> 配置值，插件重新启动后重新应用
+    configs: RwLock<HashMap<String, serde_json::Value>>,
+    
+    /// 管理员批准的插件能力：插件ID -> 能力
+    approvals: RwLock<HashMap<String, PluginCapabilities>>,
+    
+    /// 插件迁移状态存储，未设置时不执行升级钩子和数据迁移
+    migration_store: Option<Arc<dyn MigrationStore>>,
+    
+    /// 插件最近一次的探测结果：插件ID -> 探测结果，插件重新启动后清除
+    probes: RwLock<HashMap<String, PluginProbe>>,
+    
+    /// 单次健康检查的超时时间
+    probe_timeout: Duration,
+    
+    /// 连续失败多少次后将插件标记为失败
+    failure_threshold: u32,
+    
+    /// 注册插件Finder的注册表
+    finder_registry: Option<Arc<dyn FinderRegistry>>,
+    
+    /// 插件已注册的Finder：插件ID -> Finder名称
+    plugin_finders: RwLock<HashMap<String, Vec<String>>>,
+    
+    /// 运行插件调谐器的控制器管理器
+    controller_manager: Option<Arc<ControllerManager>>,
+    
+    /// 插件调用统计，插件卸载时清除
+    metrics: Arc<PluginMetrics>,
+}
+
+/// 将订阅的事件转发给插件
+struct PluginEventListener(Arc<dyn Plugin>);
+
+#[async_trait]
+impl EventListener for PluginEventListener {
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        self.0.on_event(event).await
+    }
+}
+
+/// 校验描述符并创建插件包装器
+///
+/// 描述符有问题时插件保留为失败状态，每个问题记录为一个条件；没有ID的插件无法管理，直接返回错误
+fn new_wrapper(descriptor: PluginDescriptor, plugin_path: &Path) -> Result<PluginWrapper> {
+    let violations = validate_descriptor(&descriptor);
+    if descriptor.id.trim().is_empty() {
+        let messages: Vec<String> = violations.into_iter().map(|v| v.message).collect();
+        return Err(anyhow::anyhow!("Invalid plugin descriptor in {:?}: {}", plugin_path, messages.join("; ")));
+    }
+    let mut wrapper = PluginWrapper::new(descriptor, plugin_path.to_string_lossy().to_string());
+    if !violations.is_empty() {
+        tracing::warn!("Plugin {} has an invalid descriptor", wrapper.plugin_id());
+        wrapper.state = PluginState::Failed;
+        wrapper.conditions.extend(violations.into_iter().map(|v| PluginCondition::failed(v.reason, v.message)));
+    }
+    Ok(wrapper)
 }
 
 impl DefaultPluginManager {
@@ -64,39 +209,119 @@ impl DefaultPluginManager {
         Self {
             plugins: Arc::new(RwLock::new(HashMap::new())),
             plugins_root,
+            scheme_manager: None,
+            event_bus: None,
+            loaders: Vec::new(),
+            configs: RwLock::new(HashMap::new()),
+            approvals: RwLock::new(HashMap::new()),
+            migration_store: None,
+            probes: RwLock::new(HashMap::new()),
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            finder_registry: None,
+            plugin_finders: RwLock::new(HashMap::new()),
+            controller_manager: None,
+            metrics: Arc::new(PluginMetrics::default()),
         }
     }
     
-    /// 扫描插件目录并加载所有插件
-    pub async fn scan_and_load(&self) -> Result<()> {
-        use tokio::fs;
-        
-        if !self.plugins_root.exists() {
-            fs::create_dir_all(&self.plugins_root).await?;
-            return Ok(());
+    /// 注册插件加载器，加载插件时创建插件实例；没有适用的加载器时只读取目录中的描述符
+    pub fn with_loader(mut self, loader: Arc<dyn PluginLoader>) -> Self {
+        self.loaders.push(loader);
+        self
+    }
+    
+    /// 设置事件总线，插件启动、停止、卸载时发布生命周期事件，启动后订阅描述符中声明的事件
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    /// 设置迁移状态存储，插件启动前执行升级钩子和未应用的数据迁移
+    pub fn with_migration_store(mut self, migration_store: Arc<dyn MigrationStore>) -> Self {
+        self.migration_store = Some(migration_store);
+        self
+    }
+    
+    /// 设置健康检查的超时时间和连续失败阈值（至少为1）
+    pub fn with_health_probe(mut self, probe_timeout: Duration, failure_threshold: u32) -> Self {
+        self.probe_timeout = probe_timeout;
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+    
+    /// 设置Finder注册表，插件启动后注册描述符中声明的Finder，停止时移除
+    pub fn with_finder_registry(mut self, finder_registry: Arc<dyn FinderRegistry>) -> Self {
+        self.finder_registry = Some(finder_registry);
+        self
+    }
+    
+    /// 设置控制器管理器，插件启动后为描述符中声明的类型注册控制器，停止前关闭
+    pub fn with_controller_manager(mut self, controller_manager: Arc<ControllerManager>) -> Self {
+        self.controller_manager = Some(controller_manager);
+        self
+    }
+    
+    /// 为插件声明的类型注册控制器
+    fn register_reconcilers(&self, descriptor: &PluginDescriptor, plugin: &Arc<dyn Plugin>) {
+        let Some(controller_manager) = &self.controller_manager else {
+            return;
+        };
+        for gvk in &descriptor.reconcilers {
+            controller_manager.register(&descriptor.id, gvk.clone(), Arc::new(PluginReconciler(plugin.clone())));
+        }
+    }
+    
+    /// 关闭插件的控制器，等待正在进行的调谐完成
+    async fn shutdown_reconcilers(&self, plugin_id: &str) {
+        if let Some(controller_manager) = &self.controller_manager {
+            controller_manager.shutdown(plugin_id).await;
         }
+    }
+    
+    fn publish(&self, event_type: &str, descriptor: &PluginDescriptor) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(Event::plugin(event_type, &descriptor.id, &descriptor.version));
+        }
+    }
+    
+    /// 设置Scheme管理器，插件启动时注册其扩展类型，停止时移除
+    pub fn with_scheme_manager(mut self, scheme_manager: SharedSchemeManager) -> Self {
+        self.scheme_manager = Some(scheme_manager);
+        self
+    }
+    
+    /// 读取插件描述符并创建插件包装器
+    async fn read_plugin(&self, plugin_path: &Path) -> Result<PluginWrapper> {
+        use tokio::fs;
         
-        let mut entries = fs::read_dir(&self.plugins_root).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            if path.is_dir() || (path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jar")) {
-                // 尝试加载插件
-                if let Err(e) = self.load_plugin(path).await {
-                    eprintln!("Failed to load plugin from {:?}: {}", entry.path(), e);
+        if let Some(loader) = self.loaders.iter().find(|l| l.is_applicable(plugin_path)).cloned() {
+            let path = plugin_path.to_path_buf();
+            // 加载器读取文件并编译插件，在阻塞线程中执行
+            let (descriptor, plugin) = tokio::task::spawn_blocking(move || -> Result<_> {
+                let descriptor = loader.load_descriptor(&path)?;
+                // 描述符无效时不加载插件实例
+                if !validate_descriptor(&descriptor).is_empty() {
+                    return Ok((descriptor, None));
                 }
+                let plugin = loader.load_plugin(&path, &descriptor);
+                Ok((descriptor, Some(plugin)))
+            }).await??;
+            let mut wrapper = new_wrapper(descriptor, plugin_path)?;
+            match plugin {
+                None => {}
+                Some(Ok(plugin)) => wrapper.plugin = Some(Arc::new(MeteredPlugin::new(Arc::from(plugin), self.metrics.clone()))),
+                // 不兼容的插件保留为失败状态，便于查看原因，但不能启动
+                Some(Err(e)) if is_incompatible(&e) => {
+                    tracing::warn!("Refused to load plugin {}: {}", wrapper.plugin_id(), e);
+                    wrapper.state = PluginState::Failed;
+                    wrapper.conditions.push(PluginCondition::failed(INCOMPATIBLE_BINARY_REASON, e.to_string()));
+                }
+                Some(Err(e)) => return Err(e),
             }
+            return Ok(wrapper);
         }
         
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl PluginManager for DefaultPluginManager {
-    async fn load_plugin(&self, plugin_path: PathBuf) -> Result<String> {
-        use tokio::fs;
-        use crate::loader::PluginLoader;
-        
         // 查找插件描述符文件
         let descriptor_path = if plugin_path.is_dir() {
             plugin_path.join("plugin.yaml")
@@ -113,56 +338,501 @@ impl PluginManager for DefaultPluginManager {
         let yaml_content = fs::read_to_string(&descriptor_path).await?;
         let descriptor = PluginDescriptor::from_yaml(&yaml_content)?;
         
-        let plugin_id = descriptor.id.clone();
-        
         // 创建插件包装器
-        let wrapper = Arc::new(PluginWrapper::new(
-            descriptor,
-            plugin_path.to_string_lossy().to_string(),
-        ));
+        new_wrapper(descriptor, plugin_path)
+    }
+    
+    /// 所有已加载插件的描述符
+    async fn descriptors(&self) -> Vec<PluginDescriptor> {
+        let plugins = self.plugins.read().await;
+        plugins.values().map(|w| w.descriptor.clone()).collect()
+    }
+    
+    /// 更新插件状态
+    async fn set_state(&self, plugin_id: &str, state: PluginState) {
+        let mut plugins = self.plugins.write().await;
+        if let Some(wrapper) = plugins.get_mut(plugin_id) {
+            let mut updated = wrapper.as_ref().clone();
+            updated.state = state;
+            *wrapper = Arc::new(updated);
+        }
+    }
+    
+    /// 检查插件依赖的插件均已启动且版本满足要求
+    async fn check_dependencies(&self, descriptor: &PluginDescriptor) -> Result<()> {
+        let plugins = self.plugins.read().await;
+        let mut dependencies: Vec<(&String, &String)> = descriptor.dependencies.iter().collect();
+        dependencies.sort();
+        for (dependency, requirement) in dependencies {
+            let wrapper = plugins.get(dependency).ok_or_else(|| {
+                anyhow::anyhow!("Dependency {} of plugin {} is not loaded", dependency, descriptor.id)
+            })?;
+            if wrapper.state != PluginState::Started {
+                return Err(anyhow::anyhow!("Dependency {} of plugin {} is not started", dependency, descriptor.id));
+            }
+            check_dependency_version(dependency, requirement, wrapper.version())
+                .map_err(|e| anyhow::anyhow!("Cannot start plugin {}: {}", descriptor.id, e))?;
+        }
+        Ok(())
+    }
+    
+    /// 检查插件声明的能力均已被批准
+    async fn check_capabilities(&self, descriptor: &PluginDescriptor) -> Result<()> {
+        let approvals = self.approvals.read().await;
+        let granted = approvals.get(&descriptor.id).cloned().unwrap_or_default();
+        let missing = descriptor.capabilities.missing(&granted);
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Plugin {} requires unapproved capabilities: {}", descriptor.id, missing.join(", ")
+            ));
+        }
+        Ok(())
+    }
+    
+    /// 版本变化时调用插件的升级钩子，然后依次执行未应用的迁移
+    /// 每个迁移成功后立即记录级别，失败时已应用的迁移不会重复执行；新版本号在插件启动成功后记录
+    async fn run_migrations(&self, descriptor: &PluginDescriptor, plugin: &Arc<dyn Plugin>) -> Result<()> {
+        let Some(store) = &self.migration_store else {
+            return Ok(());
+        };
+        let previous = store.load(&descriptor.id).await?;
+        if let Some(from_version) = upgraded_from(previous.as_ref(), descriptor) {
+            plugin.on_upgrade(from_version).await.map_err(|e| anyhow::anyhow!(
+                "Plugin {} failed to upgrade from {}: {}", descriptor.id, from_version, e
+            ))?;
+            tracing::info!("Upgraded plugin {} from {} to {}", descriptor.id, from_version, descriptor.version);
+        }
+        
+        let mut state = previous.clone().unwrap_or_default();
+        if state.level > descriptor.migration_level {
+            tracing::warn!(
+                "Plugin {} {} declares migration level {} but level {} is already applied",
+                descriptor.id, descriptor.version, descriptor.migration_level, state.level
+            );
+        }
+        for level in pending_migrations(previous.as_ref(), descriptor) {
+            plugin.migrate(level).await.map_err(|e| anyhow::anyhow!(
+                "Plugin {} failed to apply migration {}: {}", descriptor.id, level, e
+            ))?;
+            state.level = level;
+            store.save(&descriptor.id, &state).await?;
+            tracing::info!("Applied migration {} of plugin {}", level, descriptor.id);
+        }
+        Ok(())
+    }
+    
+    /// 插件启动成功后记录当前版本
+    async fn record_version(&self, descriptor: &PluginDescriptor) -> Result<()> {
+        let Some(store) = &self.migration_store else {
+            return Ok(());
+        };
+        let previous = store.load(&descriptor.id).await?;
+        let level = previous.as_ref().map(|state| state.level).unwrap_or(0);
+        let state = PluginMigrationState { version: descriptor.version.clone(), level };
+        if previous.as_ref() != Some(&state) {
+            store.save(&descriptor.id, &state).await?;
+        }
+        Ok(())
+    }
+    
+    /// 将健康检查连续失败的插件标记为失败
+    /// 依赖它的插件先被停止；插件可能已无响应，不调用插件的停止函数，只移除其扩展类型和事件订阅
+    async fn fail_plugin(&self, wrapper: &PluginWrapper, probe: &PluginProbe) {
+        let plugin_id = wrapper.plugin_id();
+        for dependent in dependents(plugin_id, &self.descriptors().await) {
+            if let Err(e) = self.stop_single(&dependent).await {
+                tracing::warn!("Failed to stop plugin {} depending on failed plugin {}: {}", dependent, plugin_id, e);
+            }
+        }
+        self.remove_schemes(&wrapper.descriptor);
+        self.remove_finders(plugin_id).await;
+        self.shutdown_reconcilers(plugin_id).await;
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.unsubscribe_owner(plugin_id);
+        }
+        self.set_state(plugin_id, PluginState::Failed).await;
+        tracing::error!(
+            "Plugin {} failed {} consecutive health checks, marked as failed: {}",
+            plugin_id, probe.consecutive_failures, probe.message.as_deref().unwrap_or_default()
+        );
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(Event::new(PLUGIN_FAILED, serde_json::json!({
+                "pluginId": plugin_id,
+                "version": wrapper.version(),
+                "failures": probe.consecutive_failures,
+                "message": probe.message,
+            })));
+        }
+    }
+    
+    /// 停止单个已启动的插件，不处理依赖它的插件
+    async fn stop_single(&self, plugin_id: &str) -> Result<()> {
+        let Some(wrapper) = self.get_plugin(plugin_id).await else {
+            return Ok(());
+        };
+        if wrapper.state != PluginState::Started {
+            return Ok(());
+        }
+        self.set_state(plugin_id, PluginState::Stopping).await;
+        // 插件停止后不能再处理调谐请求
+        self.shutdown_reconcilers(plugin_id).await;
+        let result = match &wrapper.plugin {
+            Some(plugin) => plugin.stop().await,
+            None => Ok(()),
+        };
+        // 停止失败的插件同样不再提供扩展类型、Finder和接收事件
+        self.remove_schemes(&wrapper.descriptor);
+        self.remove_finders(plugin_id).await;
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.unsubscribe_owner(plugin_id);
+        }
+        if let Err(e) = result {
+            self.set_state(plugin_id, PluginState::Failed).await;
+            return Err(e);
+        }
+        self.set_state(plugin_id, PluginState::Stopped).await;
+        self.publish(PLUGIN_STOPPED, &wrapper.descriptor);
+        Ok(())
+    }
+    
+    /// 注册插件声明的扩展类型，与其他来源注册的类型冲突时不注册任何类型
+    fn register_schemes(&self, descriptor: &PluginDescriptor) -> Result<()> {
+        let Some(scheme_manager) = &self.scheme_manager else {
+            return Ok(());
+        };
+        if descriptor.extensions.is_empty() {
+            return Ok(());
+        }
+        let owner = scheme_owner(&descriptor.id);
+        let mut schemes = scheme_manager.write().map_err(|_| anyhow::anyhow!("Scheme manager is poisoned"))?;
+        for definition in &descriptor.extensions {
+            let gvk = definition.gvk();
+            if let Some(existing) = foreign_scheme(&*schemes, &gvk, &owner) {
+                return Err(anyhow::anyhow!(
+                    "Extension kind {} of plugin {} is already registered by {}",
+                    gvk.to_string(), descriptor.id, existing.type_name
+                ));
+            }
+        }
+        for definition in &descriptor.extensions {
+            schemes.register(definition.to_scheme(&descriptor.id))
+                .map_err(|e| anyhow::anyhow!("Failed to register extension kind {}: {}", definition.gvk().to_string(), e))?;
+        }
+        Ok(())
+    }
+    
+    /// 检查插件的扩展类型、路由前缀和Finder是否已被正在运行的插件或宿主占用
+    async fn detect_conflicts(&self, descriptor: &PluginDescriptor) -> Vec<PluginConflict> {
+        let running: Vec<PluginDescriptor> = self.plugins.read().await.values()
+            .filter(|w| matches!(w.state, PluginState::Starting | PluginState::Started))
+            .map(|w| w.descriptor.clone())
+            .collect();
+        let mut conflicts = find_conflicts(descriptor, &running);
+        let reported = |conflicts: &[PluginConflict], resource: &str| conflicts.iter().any(|c| c.resource == resource);
+        
+        if let Some(scheme_manager) = &self.scheme_manager {
+            let owner = scheme_owner(&descriptor.id);
+            if let Ok(schemes) = scheme_manager.read() {
+                for definition in &descriptor.extensions {
+                    let gvk = definition.gvk().to_string();
+                    if let Some(existing) = foreign_scheme(&*schemes, &definition.gvk(), &owner) {
+                        if !reported(&conflicts, &gvk) {
+                            let holder = existing.type_name.strip_prefix("plugin:").unwrap_or("host").to_string();
+                            conflicts.push(PluginConflict::new(EXTENSION_CONFLICT_REASON, gvk, holder));
+                        }
+                    }
+                }
+            }
+        }
+        
+        if let Some(finder_registry) = &self.finder_registry {
+            let plugin_finders = self.plugin_finders.read().await;
+            for name in &descriptor.finders {
+                let holder = plugin_finders.iter()
+                    .find(|(_, names)| names.contains(name))
+                    .map(|(plugin_id, _)| plugin_id.clone());
+                if holder.as_deref() == Some(descriptor.id.as_str()) || reported(&conflicts, name) {
+                    continue;
+                }
+                if finder_registry.get(name).is_some() {
+                    let holder = holder.unwrap_or_else(|| "host".to_string());
+                    conflicts.push(PluginConflict::new(FINDER_CONFLICT_REASON, name, holder));
+                }
+            }
+        }
+        conflicts
+    }
+    
+    /// 用本次检测到的冲突替换插件之前记录的冲突条件，有冲突时插件为失败状态
+    async fn record_conflicts(&self, plugin_id: &str, conflicts: &[PluginConflict]) {
+        let mut plugins = self.plugins.write().await;
+        if let Some(wrapper) = plugins.get_mut(plugin_id) {
+            let mut updated = wrapper.as_ref().clone();
+            updated.conditions.retain(|c| !is_conflict_reason(&c.reason));
+            updated.conditions.extend(conflicts.iter().map(|c| PluginCondition::failed(c.reason, c.message())));
+            if !conflicts.is_empty() {
+                updated.state = PluginState::Failed;
+            }
+            *wrapper = Arc::new(updated);
+        }
+    }
+    
+    /// 注册插件声明的Finder
+    async fn register_finders(&self, descriptor: &PluginDescriptor, plugin: &Arc<dyn Plugin>) {
+        let Some(finder_registry) = &self.finder_registry else {
+            return;
+        };
+        if descriptor.finders.is_empty() {
+            return;
+        }
+        for name in &descriptor.finders {
+            finder_registry.register(name.clone(), Box::new(PluginFinder::new(name.clone(), plugin.clone())));
+        }
+        self.plugin_finders.write().await.insert(descriptor.id.clone(), descriptor.finders.clone());
+    }
+    
+    /// 移除插件注册的Finder
+    async fn remove_finders(&self, plugin_id: &str) {
+        let Some(finder_registry) = &self.finder_registry else {
+            return;
+        };
+        for name in self.plugin_finders.write().await.remove(plugin_id).unwrap_or_default() {
+            finder_registry.remove(&name);
+        }
+    }
+    
+    /// 移除插件注册的扩展类型
+    fn remove_schemes(&self, descriptor: &PluginDescriptor) {
+        let Some(scheme_manager) = &self.scheme_manager else {
+            return;
+        };
+        let owner = scheme_owner(&descriptor.id);
+        let Ok(mut schemes) = scheme_manager.write() else {
+            return;
+        };
+        for definition in &descriptor.extensions {
+            let gvk = definition.gvk();
+            if schemes.get(&gvk).is_some_and(|s| s.type_name == owner) {
+                schemes.remove(&gvk);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PluginManager for DefaultPluginManager {
+    async fn load_plugin(&self, plugin_path: PathBuf) -> Result<String> {
+        let wrapper = self.read_plugin(&plugin_path).await?;
+        let plugin_id = wrapper.descriptor.id.clone();
         
         // 存储插件
         let mut plugins = self.plugins.write().await;
-        plugins.insert(plugin_id.clone(), wrapper);
+        plugins.insert(plugin_id.clone(), Arc::new(wrapper));
         
         Ok(plugin_id)
     }
     
+    async fn scan_and_load(&self) -> Result<Vec<String>> {
+        if !self.plugins_root.exists() {
+            tokio::fs::create_dir_all(&self.plugins_root).await?;
+            return Ok(Vec::new());
+        }
+        
+        let loaded: Vec<PathBuf> = self.plugins.read().await.values()
+            .map(|wrapper| PathBuf::from(&wrapper.plugin_path))
+            .collect();
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.plugins_root).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            // 跳过隐藏文件和安装过程中的临时文件
+            if entry.file_name().to_string_lossy().starts_with('.') || loaded.contains(&path) {
+                continue;
+            }
+            let is_bundle = if path.is_dir() {
+                path.join("plugin.yaml").is_file()
+            } else {
+                self.loaders.iter().any(|loader| loader.is_applicable(&path))
+            };
+            if is_bundle {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        
+        let mut plugin_ids = Vec::new();
+        for path in paths {
+            match self.load_plugin(path.clone()).await {
+                Ok(plugin_id) => plugin_ids.push(plugin_id),
+                Err(e) => tracing::warn!("Failed to load plugin from {:?}: {}", path, e),
+            }
+        }
+        Ok(plugin_ids)
+    }
+    
     async fn start_plugin(&self, plugin_id: &str) -> Result<()> {
-        let plugins = self.plugins.read().await;
-        let wrapper = plugins.get(plugin_id)
+        let wrapper = self.get_plugin(plugin_id).await
             .ok_or_else(|| anyhow::anyhow!("Plugin not found: {}", plugin_id))?;
+        if wrapper.state == PluginState::Started {
+            return Ok(());
+        }
+        // 描述符无效或二进制不兼容的插件需要修复后重新加载；冲突在每次启动时重新检测
+        let failures: Vec<&str> = wrapper.failed_conditions()
+            .filter(|c| !is_conflict_reason(&c.reason))
+            .map(|c| c.message.as_str())
+            .collect();
+        if !failures.is_empty() {
+            anyhow::bail!("Plugin {} cannot be started: {}", plugin_id, failures.join("; "));
+        }
+        self.check_dependencies(&wrapper.descriptor).await?;
+        self.check_capabilities(&wrapper.descriptor).await?;
+        let conflicts = self.detect_conflicts(&wrapper.descriptor).await;
+        self.record_conflicts(plugin_id, &conflicts).await;
+        if !conflicts.is_empty() {
+            let messages: Vec<String> = conflicts.iter().map(|c| c.message()).collect();
+            tracing::warn!("Refused to start plugin {}: {}", plugin_id, messages.join("; "));
+            anyhow::bail!("Plugin {} conflicts with registered resources: {}", plugin_id, messages.join("; "));
+        }
         
-        // 更新状态为Starting
-        // 注意：由于Arc的不可变性，我们需要重新设计这部分
-        // 这里先简化实现
+        self.register_schemes(&wrapper.descriptor)?;
         
+        self.set_state(plugin_id, PluginState::Starting).await;
+        self.probes.write().await.remove(plugin_id);
+        if let Some(plugin) = &wrapper.plugin {
+            // 迁移可能需要读写插件自身的扩展类型，在注册Scheme之后执行
+            let result = match self.run_migrations(&wrapper.descriptor, plugin).await {
+                Ok(()) => plugin.start().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                self.remove_schemes(&wrapper.descriptor);
+                self.set_state(plugin_id, PluginState::Failed).await;
+                return Err(e);
+            }
+            if let Err(e) = self.record_version(&wrapper.descriptor).await {
+                tracing::warn!("Failed to record version of plugin {}: {}", plugin_id, e);
+            }
+        }
+        self.set_state(plugin_id, PluginState::Started).await;
         if let Some(plugin) = &wrapper.plugin {
-            plugin.start().await?;
+            self.register_finders(&wrapper.descriptor, plugin).await;
+            self.register_reconcilers(&wrapper.descriptor, plugin);
+        }
+        
+        if let (Some(event_bus), Some(plugin)) = (&self.event_bus, &wrapper.plugin) {
+            if !wrapper.descriptor.events.is_empty() {
+                let listener = Arc::new(PluginEventListener(plugin.clone()));
+                event_bus.subscribe(plugin_id, wrapper.descriptor.events.clone(), listener);
+            }
+        }
+        self.publish(PLUGIN_STARTED, &wrapper.descriptor);
+        
+        let config = self.configs.read().await.get(plugin_id).cloned();
+        if let (Some(plugin), Some(config)) = (&wrapper.plugin, config) {
+            if let Err(e) = plugin.on_config_change(config).await {
+                tracing::warn!("Plugin {} failed to apply saved config: {}", plugin_id, e);
+            }
         }
         
         Ok(())
     }
     
     async fn stop_plugin(&self, plugin_id: &str) -> Result<()> {
-        let plugins = self.plugins.read().await;
-        let wrapper = plugins.get(plugin_id)
+        if self.get_plugin(plugin_id).await.is_none() {
+            return Err(anyhow::anyhow!("Plugin not found: {}", plugin_id));
+        }
+        
+        // 依赖该插件的插件无法继续运行，先级联停止
+        for dependent in dependents(plugin_id, &self.descriptors().await) {
+            self.stop_single(&dependent).await?;
+        }
+        self.stop_single(plugin_id).await
+    }
+    
+    async fn reload_plugin(&self, plugin_id: &str) -> Result<()> {
+        let wrapper = self.get_plugin(plugin_id).await
             .ok_or_else(|| anyhow::anyhow!("Plugin not found: {}", plugin_id))?;
+        let was_started = wrapper.state == PluginState::Started;
+        // 停止前记录已启动的依赖方，重新启动插件后按启动顺序恢复
+        let mut started_dependents = Vec::new();
+        for dependent in dependents(plugin_id, &self.descriptors().await) {
+            if self.get_plugin(&dependent).await.is_some_and(|w| w.state == PluginState::Started) {
+                started_dependents.push(dependent);
+            }
+        }
+        started_dependents.reverse();
         
-        if let Some(plugin) = &wrapper.plugin {
-            plugin.stop().await?;
+        self.stop_plugin(plugin_id).await?;
+        let reloaded = self.read_plugin(Path::new(&wrapper.plugin_path)).await?;
+        if reloaded.descriptor.id != plugin_id {
+            return Err(anyhow::anyhow!(
+                "Plugin at {} changed its id from {} to {}", wrapper.plugin_path, plugin_id, reloaded.descriptor.id
+            ));
         }
+        self.plugins.write().await.insert(plugin_id.to_string(), Arc::new(reloaded));
         
+        if was_started {
+            self.start_plugin(plugin_id).await?;
+            for dependent in started_dependents {
+                if let Err(e) = self.start_plugin(&dependent).await {
+                    tracing::warn!("Failed to restart plugin {} after reloading {}: {}", dependent, plugin_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    async fn notify_config_change(&self, plugin_id: &str, config: serde_json::Value) -> Result<()> {
+        let Some(wrapper) = self.get_plugin(plugin_id).await else {
+            return Ok(());
+        };
+        self.configs.write().await.insert(plugin_id.to_string(), config.clone());
+        match &wrapper.plugin {
+            Some(plugin) if wrapper.state == PluginState::Started => plugin.on_config_change(config).await,
+            _ => Ok(()),
+        }
+    }
+    
+    async fn start_plugins(&self) -> Result<()> {
+        for plugin_id in startup_order(&self.descriptors().await)? {
+            if let Err(e) = self.start_plugin(&plugin_id).await {
+                tracing::warn!("Failed to start plugin {}: {}", plugin_id, e);
+            }
+        }
+        Ok(())
+    }
+    
+    async fn stop_plugins(&self) -> Result<()> {
+        let mut order = startup_order(&self.descriptors().await)?;
+        order.reverse();
+        for plugin_id in order {
+            if let Err(e) = self.stop_single(&plugin_id).await {
+                tracing::warn!("Failed to stop plugin {}: {}", plugin_id, e);
+            }
+        }
         Ok(())
     }
     
+    async fn approve_capabilities(&self, plugin_id: &str, capabilities: PluginCapabilities) {
+        self.approvals.write().await.insert(plugin_id.to_string(), capabilities);
+    }
+    
+    async fn approved_capabilities(&self, plugin_id: &str) -> Option<PluginCapabilities> {
+        self.approvals.read().await.get(plugin_id).cloned()
+    }
+    
     async fn unload_plugin(&self, plugin_id: &str) -> Result<()> {
         // 先停止插件
         self.stop_plugin(plugin_id).await?;
         
         // 从存储中移除
-        let mut plugins = self.plugins.write().await;
-        plugins.remove(plugin_id);
+        let removed = self.plugins.write().await.remove(plugin_id);
+        self.configs.write().await.remove(plugin_id);
+        self.probes.write().await.remove(plugin_id);
+        self.metrics.remove(plugin_id);
+        if let Some(wrapper) = removed {
+            self.publish(PLUGIN_DELETED, &wrapper.descriptor);
+        }
         
         Ok(())
     }
@@ -184,5 +854,83 @@ impl PluginManager for DefaultPluginManager {
             .cloned()
             .collect()
     }
+    
+    async fn probe_plugins(&self) -> Vec<(String, PluginProbe)> {
+        let mut results = Vec::new();
+        for wrapper in self.get_started_plugins().await {
+            let Some(plugin) = &wrapper.plugin else {
+                continue;
+            };
+            let plugin_id = wrapper.plugin_id().to_string();
+            let result = match tokio::time::timeout(self.probe_timeout, plugin.health_check()).await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err(format!("Health check timed out after {}s", self.probe_timeout.as_secs())),
+            };
+            let probe = {
+                let mut probes = self.probes.write().await;
+                let probe = PluginProbe::record(probes.get(&plugin_id), result, chrono::Utc::now());
+                probes.insert(plugin_id.clone(), probe.clone());
+                probe
+            };
+            if probe.consecutive_failures >= self.failure_threshold {
+                self.fail_plugin(&wrapper, &probe).await;
+            } else if let Some(message) = &probe.message {
+                tracing::warn!("Health check of plugin {} failed: {}", plugin_id, message);
+            }
+            results.push((plugin_id, probe));
+        }
+        results
+    }
+    
+    async fn get_probe(&self, plugin_id: &str) -> Option<PluginProbe> {
+        self.probes.read().await.get(plugin_id).cloned()
+    }
+    
+    async fn get_stats(&self, plugin_id: &str) -> Option<PluginStats> {
+        let wrapper = self.get_plugin(plugin_id).await?;
+        let memory_bytes = wrapper.plugin.as_ref().and_then(|plugin| plugin.memory_usage());
+        Some(self.metrics.snapshot(plugin_id, memory_bytes))
+    }
+    
+    async fn get_all_stats(&self) -> Vec<PluginStats> {
+        let mut stats: Vec<PluginStats> = self.get_plugins().await.iter()
+            .map(|wrapper| {
+                let memory_bytes = wrapper.plugin.as_ref().and_then(|plugin| plugin.memory_usage());
+                self.metrics.snapshot(wrapper.plugin_id(), memory_bytes)
+            })
+            .collect();
+        stats.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+        stats
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::scheme::DefaultSchemeManager;
+
+    #[tokio::test]
+    async fn test_plugin_declaring_host_kind_is_refused() {
+        let root = tempfile::tempdir().unwrap();
+        let plugin_dir = root.path().join("posts");
+        std::fs::create_dir(&plugin_dir).unwrap();
+        std::fs::write(
+            plugin_dir.join("plugin.yaml"),
+            "id: posts\nversion: 1.0.0\nextensions:\n  - {group: content.halo.run, version: v1beta1, kind: Post}\n",
+        ).unwrap();
+        let mut host = DefaultSchemeManager::new();
+        host.register(Scheme::new(GroupVersionKind::new("content.halo.run", "v1alpha1", "Post"), "Post")).unwrap();
+        let schemes: SharedSchemeManager = Arc::new(std::sync::RwLock::new(host));
+        let manager = DefaultPluginManager::new(root.path().to_path_buf()).with_scheme_manager(schemes.clone());
+
+        // 以其他版本声明宿主的内置类型同样被拒绝，插件不会获得该类型的写权限
+        let plugin_id = manager.load_plugin(plugin_dir).await.unwrap();
+        let error = manager.start_plugin(&plugin_id).await.unwrap_err();
+        assert!(error.to_string().contains("extension kind content.halo.run/v1beta1/Post is already registered by host"));
+        let wrapper = manager.get_plugin(&plugin_id).await.unwrap();
+        assert_eq!(wrapper.state, PluginState::Failed);
+        assert!(wrapper.failed_conditions().any(|c| c.reason == EXTENSION_CONFLICT_REASON));
+        assert_eq!(schemes.read().unwrap().list().len(), 1);
+    }
+}
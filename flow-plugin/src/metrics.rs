@@ -0,0 +1,243 @@
+//! 插件资源使用统计
+//! 记录每个插件各类调用的次数、错误数和耗时，WASM插件另外报告线性内存大小，
+//! 管理员可据此找出拖慢系统或频繁出错的插件
+
+use crate::controller::{ReconcileRequest, ReconcileResult};
+use crate::descriptor::PluginDescriptor;
+use crate::event::Event;
+use crate::plugin::{Plugin, PluginHttpRequest, PluginHttpResponse};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// 单类调用的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStats {
+    pub invocations: u64,
+    pub errors: u64,
+    /// 累计耗时（微秒）
+    pub total_micros: u64,
+    /// 最长一次耗时（微秒）
+    pub max_micros: u64,
+}
+
+impl OperationStats {
+    fn record(&mut self, elapsed: Duration, ok: bool) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.invocations += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_micros = self.total_micros.saturating_add(micros);
+        self.max_micros = self.max_micros.max(micros);
+    }
+}
+
+/// 插件的资源使用情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginStats {
+    pub plugin_id: String,
+    pub invocations: u64,
+    pub errors: u64,
+    /// 出错调用占比，没有调用时为0
+    pub error_rate: f64,
+    /// 平均耗时（微秒）
+    pub average_micros: u64,
+    /// WASM插件的线性内存大小（字节），原生插件为None
+    pub memory_bytes: Option<u64>,
+    /// 按调用类型（如 `http`、`event`）分别统计
+    pub operations: BTreeMap<String, OperationStats>,
+}
+
+impl PluginStats {
+    fn new(plugin_id: &str, operations: BTreeMap<String, OperationStats>, memory_bytes: Option<u64>) -> Self {
+        let invocations: u64 = operations.values().map(|o| o.invocations).sum();
+        let errors: u64 = operations.values().map(|o| o.errors).sum();
+        let total_micros: u64 = operations.values().map(|o| o.total_micros).fold(0, u64::saturating_add);
+        Self {
+            plugin_id: plugin_id.to_string(),
+            invocations,
+            errors,
+            error_rate: if invocations == 0 { 0.0 } else { errors as f64 / invocations as f64 },
+            average_micros: total_micros.checked_div(invocations).unwrap_or(0),
+            memory_bytes,
+            operations,
+        }
+    }
+}
+
+/// 插件调用统计：插件ID -> 调用类型 -> 统计
+#[derive(Default)]
+pub struct PluginMetrics {
+    stats: RwLock<HashMap<String, BTreeMap<String, OperationStats>>>,
+}
+
+impl PluginMetrics {
+    pub fn record(&self, plugin_id: &str, operation: &str, elapsed: Duration, ok: bool) {
+        let mut stats = self.stats.write().unwrap_or_else(|e| e.into_inner());
+        stats.entry(plugin_id.to_string()).or_default()
+            .entry(operation.to_string()).or_default()
+            .record(elapsed, ok);
+    }
+
+    /// 插件当前的统计，没有调用记录时各项为0
+    pub fn snapshot(&self, plugin_id: &str, memory_bytes: Option<u64>) -> PluginStats {
+        let stats = self.stats.read().unwrap_or_else(|e| e.into_inner());
+        PluginStats::new(plugin_id, stats.get(plugin_id).cloned().unwrap_or_default(), memory_bytes)
+    }
+
+    /// 清除插件的统计，插件卸载时调用
+    pub fn remove(&self, plugin_id: &str) {
+        self.stats.write().unwrap_or_else(|e| e.into_inner()).remove(plugin_id);
+    }
+}
+
+/// 以Prometheus文本格式输出插件统计
+pub fn render_prometheus(stats: &[PluginStats]) -> String {
+    type Metric = (&'static str, &'static str, &'static str, fn(&OperationStats) -> String);
+    let metrics: [Metric; 4] = [
+        ("flow_plugin_invocations_total", "counter", "Number of plugin invocations", |o| o.invocations.to_string()),
+        ("flow_plugin_errors_total", "counter", "Number of failed plugin invocations", |o| o.errors.to_string()),
+        ("flow_plugin_latency_seconds_sum", "counter", "Total time spent in plugin invocations", |o| seconds(o.total_micros)),
+        ("flow_plugin_latency_seconds_max", "gauge", "Longest plugin invocation", |o| seconds(o.max_micros)),
+    ];
+    let mut out = String::new();
+    for (name, kind, help, value) in metrics {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        for plugin in stats {
+            for (operation, operation_stats) in &plugin.operations {
+                let _ = writeln!(
+                    out, "{}{{plugin=\"{}\",operation=\"{}\"}} {}",
+                    name, escape_label(&plugin.plugin_id), escape_label(operation), value(operation_stats)
+                );
+            }
+        }
+    }
+    let _ = writeln!(out, "# HELP flow_plugin_memory_bytes Linear memory of WASM plugins\n# TYPE flow_plugin_memory_bytes gauge");
+    for plugin in stats {
+        if let Some(bytes) = plugin.memory_bytes {
+            let _ = writeln!(out, "flow_plugin_memory_bytes{{plugin=\"{}\"}} {}", escape_label(&plugin.plugin_id), bytes);
+        }
+    }
+    out
+}
+
+fn seconds(micros: u64) -> String {
+    (micros as f64 / 1_000_000.0).to_string()
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// 记录每次调用的插件包装，插件管理器加载的插件均经过它调用
+pub struct MeteredPlugin {
+    inner: Arc<dyn Plugin>,
+    metrics: Arc<PluginMetrics>,
+}
+
+impl MeteredPlugin {
+    pub fn new(inner: Arc<dyn Plugin>, metrics: Arc<PluginMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    async fn measure<T>(&self, operation: &str, call: impl Future<Output = Result<T>>) -> Result<T> {
+        let started = Instant::now();
+        let result = call.await;
+        self.metrics.record(&self.inner.descriptor().id, operation, started.elapsed(), result.is_ok());
+        result
+    }
+}
+
+#[async_trait]
+impl Plugin for MeteredPlugin {
+    fn descriptor(&self) -> &PluginDescriptor {
+        self.inner.descriptor()
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.measure("start", self.inner.start()).await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.measure("stop", self.inner.stop()).await
+    }
+
+    async fn on_config_change(&self, config: serde_json::Value) -> Result<()> {
+        self.measure("config", self.inner.on_config_change(config)).await
+    }
+
+    async fn handle_http(&self, request: PluginHttpRequest) -> Result<Option<PluginHttpResponse>> {
+        self.measure("http", self.inner.handle_http(request)).await
+    }
+
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        self.measure("event", self.inner.on_event(event)).await
+    }
+
+    async fn on_upgrade(&self, from_version: &str) -> Result<()> {
+        self.measure("upgrade", self.inner.on_upgrade(from_version)).await
+    }
+
+    async fn migrate(&self, level: u32) -> Result<()> {
+        self.measure("migrate", self.inner.migrate(level)).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.measure("health", self.inner.health_check()).await
+    }
+
+    async fn reconcile(&self, request: &ReconcileRequest) -> Result<ReconcileResult> {
+        self.measure("reconcile", self.inner.reconcile(request)).await
+    }
+
+    async fn find(&self, finder: &str) -> Result<Option<serde_json::Value>> {
+        self.measure("find", self.inner.find(finder)).await
+    }
+
+    fn memory_usage(&self) -> Option<u64> {
+        self.inner.memory_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let metrics = PluginMetrics::default();
+        metrics.record("links", "http", Duration::from_millis(30), true);
+        metrics.record("links", "http", Duration::from_millis(10), false);
+        metrics.record("links", "event", Duration::from_millis(20), true);
+
+        let stats = metrics.snapshot("links", Some(65536));
+        assert_eq!(stats.invocations, 3);
+        assert_eq!(stats.errors, 1);
+        assert!((stats.error_rate - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.average_micros, 20_000);
+        assert_eq!(stats.operations["http"].max_micros, 30_000);
+
+        metrics.remove("links");
+        let stats = metrics.snapshot("links", None);
+        assert_eq!((stats.invocations, stats.error_rate), (0, 0.0));
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = PluginMetrics::default();
+        metrics.record("links", "http", Duration::from_millis(1500), false);
+        let text = render_prometheus(&[metrics.snapshot("links", Some(1024))]);
+        assert!(text.contains("flow_plugin_invocations_total{plugin=\"links\",operation=\"http\"} 1\n"));
+        assert!(text.contains("flow_plugin_errors_total{plugin=\"links\",operation=\"http\"} 1\n"));
+        assert!(text.contains("flow_plugin_latency_seconds_max{plugin=\"links\",operation=\"http\"} 1.5\n"));
+        assert!(text.contains("flow_plugin_memory_bytes{plugin=\"links\"} 1024\n"));
+    }
+}
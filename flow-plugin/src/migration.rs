@@ -0,0 +1,71 @@
+//! 插件数据迁移
+//! 插件在描述符中声明迁移级别，启动时由插件管理器依次执行未应用的迁移并记录已应用的级别
+
+use crate::descriptor::PluginDescriptor;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// 插件已应用的迁移状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginMigrationState {
+    /// 最后成功启动的插件版本
+    pub version: String,
+    /// 已应用的迁移级别，0表示未应用任何迁移
+    #[serde(default)]
+    pub level: u32,
+}
+
+/// 插件迁移状态的持久化存储
+#[async_trait]
+pub trait MigrationStore: Send + Sync {
+    /// 读取插件的迁移状态，插件从未启动过时返回None
+    async fn load(&self, plugin_id: &str) -> Result<Option<PluginMigrationState>>;
+
+    /// 保存插件的迁移状态
+    async fn save(&self, plugin_id: &str, state: &PluginMigrationState) -> Result<()>;
+}
+
+/// 插件从哪个版本升级而来，首次安装或版本未变化时返回None
+pub fn upgraded_from<'a>(state: Option<&'a PluginMigrationState>, descriptor: &PluginDescriptor) -> Option<&'a str> {
+    state.map(|state| state.version.as_str())
+        .filter(|version| !version.is_empty() && *version != descriptor.version)
+}
+
+/// 需要依次执行的迁移级别
+///
+/// 已应用的级别高于描述符声明的级别（降级）时不执行迁移
+pub fn pending_migrations(state: Option<&PluginMigrationState>, descriptor: &PluginDescriptor) -> Vec<u32> {
+    let applied = state.map(|state| state.level).unwrap_or(0);
+    (applied + 1..=descriptor.migration_level).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(version: &str, migration_level: u32) -> PluginDescriptor {
+        let mut descriptor = PluginDescriptor::from_yaml(&format!("id: search\nversion: {}\n", version)).unwrap();
+        descriptor.migration_level = migration_level;
+        descriptor
+    }
+
+    #[test]
+    fn test_pending_migrations() {
+        let state = PluginMigrationState { version: "1.0.0".to_string(), level: 2 };
+        assert_eq!(pending_migrations(None, &descriptor("1.0.0", 2)), vec![1, 2]);
+        assert_eq!(pending_migrations(Some(&state), &descriptor("1.1.0", 4)), vec![3, 4]);
+        assert!(pending_migrations(Some(&state), &descriptor("1.0.0", 2)).is_empty());
+        assert!(pending_migrations(Some(&state), &descriptor("0.9.0", 1)).is_empty());
+        assert!(pending_migrations(None, &descriptor("1.0.0", 0)).is_empty());
+    }
+
+    #[test]
+    fn test_upgraded_from() {
+        let state = PluginMigrationState { version: "1.0.0".to_string(), level: 0 };
+        assert_eq!(upgraded_from(Some(&state), &descriptor("1.1.0", 0)), Some("1.0.0"));
+        assert_eq!(upgraded_from(Some(&state), &descriptor("1.0.0", 0)), None);
+        assert_eq!(upgraded_from(None, &descriptor("1.0.0", 0)), None);
+    }
+}
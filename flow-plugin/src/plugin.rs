@@ -1,6 +1,12 @@
 use async_trait::async_trait;
+use crate::controller::{ReconcileRequest, ReconcileResult};
 use crate::descriptor::PluginDescriptor;
+use crate::event::Event;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// 插件trait
 /// 所有插件必须实现此trait
@@ -16,6 +22,88 @@ pub trait Plugin: Send + Sync {
     /// 停止插件
     /// 在插件卸载前调用
     async fn stop(&self) -> Result<()>;
+
+    /// 插件配置变更时调用，参数为解析后的配置值（分组 -> 字段 -> 值）
+    async fn on_config_change(&self, _config: serde_json::Value) -> Result<()> {
+        Ok(())
+    }
+
+    /// 处理转发给插件的HTTP请求
+    /// 返回None表示插件不处理HTTP请求
+    async fn handle_http(&self, _request: PluginHttpRequest) -> Result<Option<PluginHttpResponse>> {
+        Ok(None)
+    }
+
+    /// 收到描述符中订阅的事件时调用
+    async fn on_event(&self, _event: &Event) -> Result<()> {
+        Ok(())
+    }
+
+    /// 插件从旧版本升级后、执行迁移和启动之前调用
+    async fn on_upgrade(&self, _from_version: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// 执行指定级别的数据迁移，按级别从小到大依次调用，成功后记录为已应用
+    async fn migrate(&self, _level: u32) -> Result<()> {
+        Ok(())
+    }
+
+    /// 健康检查，插件运行期间定期调用，返回错误表示插件不健康
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 调谐描述符中声明的类型的扩展对象，对象创建、更新、删除后调用
+    async fn reconcile(&self, _request: &ReconcileRequest) -> Result<ReconcileResult> {
+        Ok(ReconcileResult::default())
+    }
+
+    /// 查询描述符中声明的Finder的数据，渲染主题页面时调用，返回None表示没有数据
+    async fn find(&self, _finder: &str) -> Result<Option<serde_json::Value>> {
+        Ok(None)
+    }
+
+    /// 插件当前占用的内存（字节），无法统计时返回None
+    fn memory_usage(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// 转发给插件的HTTP请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginHttpRequest {
+    /// 请求方法
+    pub method: String,
+    /// 请求路径（去除插件路由前缀后的路径）
+    pub path: String,
+    /// 查询参数
+    #[serde(default)]
+    pub query: HashMap<String, String>,
+    /// 请求头
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 请求体
+    #[serde(default)]
+    pub body: String,
+}
+
+/// 插件返回的HTTP响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginHttpResponse {
+    /// 状态码
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// 响应头
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 响应体
+    #[serde(default)]
+    pub body: String,
+}
+
+fn default_status() -> u16 {
+    200
 }
 
 /// 插件状态
@@ -35,8 +123,35 @@ pub enum PluginState {
     Failed,
 }
 
+/// 插件状态条件，记录插件无法加载或启动的原因
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCondition {
+    /// 条件类型，如 `Failed`
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    /// 机器可读的原因，如 `IncompatibleBinary`
+    pub reason: String,
+    /// 详细信息
+    pub message: String,
+    pub last_transition_time: DateTime<Utc>,
+}
+
+impl PluginCondition {
+    /// 插件失败的条件
+    pub fn failed(reason: &str, message: impl Into<String>) -> Self {
+        Self {
+            condition_type: "Failed".to_string(),
+            reason: reason.to_string(),
+            message: message.into(),
+            last_transition_time: Utc::now(),
+        }
+    }
+}
+
 /// 插件包装器
 /// 包装插件实例和状态
+#[derive(Clone)]
 pub struct PluginWrapper {
     /// 插件描述符
     pub descriptor: PluginDescriptor,
@@ -45,10 +160,13 @@ pub struct PluginWrapper {
     pub state: PluginState,
     
     /// 插件实例（可选，因为可能使用动态库）
-    pub plugin: Option<Box<dyn Plugin>>,
+    pub plugin: Option<Arc<dyn Plugin>>,
     
     /// 插件路径
     pub plugin_path: String,
+    
+    /// 插件状态条件
+    pub conditions: Vec<PluginCondition>,
 }
 
 impl PluginWrapper {
@@ -58,6 +176,7 @@ impl PluginWrapper {
             state: PluginState::Created,
             plugin: None,
             plugin_path,
+            conditions: Vec::new(),
         }
     }
     
@@ -70,5 +189,10 @@ impl PluginWrapper {
     pub fn version(&self) -> &str {
         &self.descriptor.version
     }
+    
+    /// 插件失败的条件
+    pub fn failed_conditions(&self) -> impl Iterator<Item = &PluginCondition> {
+        self.conditions.iter().filter(|c| c.condition_type == "Failed")
+    }
 }
 
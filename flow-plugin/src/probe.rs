@@ -0,0 +1,100 @@
+//! 插件健康探测
+//! 定期调用运行中插件的健康检查，连续失败达到阈值后将插件标记为失败
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 单次健康检查的默认超时时间
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 连续失败多少次后将插件标记为失败
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// 探测结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProbeState {
+    Healthy,
+    Unhealthy,
+}
+
+impl ProbeState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProbeState::Healthy => "Healthy",
+            ProbeState::Unhealthy => "Unhealthy",
+        }
+    }
+}
+
+/// 插件最近一次探测的情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProbe {
+    pub state: ProbeState,
+    /// 最后探测时间
+    pub last_probe_time: DateTime<Utc>,
+    /// 最后一次探测成功的时间
+    pub last_success_time: Option<DateTime<Utc>>,
+    /// 连续失败次数，探测成功后清零
+    pub consecutive_failures: u32,
+    /// 最后一次失败的原因
+    pub message: Option<String>,
+}
+
+impl PluginProbe {
+    /// 在上一次探测的基础上记录本次探测结果
+    pub fn record(previous: Option<&PluginProbe>, result: Result<(), String>, now: DateTime<Utc>) -> Self {
+        match result {
+            Ok(()) => Self {
+                state: ProbeState::Healthy,
+                last_probe_time: now,
+                last_success_time: Some(now),
+                consecutive_failures: 0,
+                message: None,
+            },
+            Err(message) => Self {
+                state: ProbeState::Unhealthy,
+                last_probe_time: now,
+                last_success_time: previous.and_then(|probe| probe.last_success_time),
+                consecutive_failures: previous.map(|probe| probe.consecutive_failures).unwrap_or(0) + 1,
+                message: Some(message),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_failures() {
+        let start = Utc::now();
+        let healthy = PluginProbe::record(None, Ok(()), start);
+        assert_eq!(healthy.state, ProbeState::Healthy);
+        assert_eq!(healthy.last_success_time, Some(start));
+
+        let later = start + chrono::Duration::seconds(30);
+        let failed = PluginProbe::record(Some(&healthy), Err("timed out".to_string()), later);
+        let failed = PluginProbe::record(Some(&failed), Err("timed out".to_string()), later);
+        assert_eq!(failed.state, ProbeState::Unhealthy);
+        assert_eq!(failed.consecutive_failures, 2);
+        assert_eq!(failed.last_success_time, Some(start));
+        assert_eq!(failed.last_probe_time, later);
+        assert_eq!(failed.message.as_deref(), Some("timed out"));
+
+        let recovered = PluginProbe::record(Some(&failed), Ok(()), later);
+        assert_eq!(recovered.consecutive_failures, 0);
+        assert_eq!(recovered.message, None);
+    }
+
+    #[test]
+    fn test_probe_json() {
+        let probe = PluginProbe::record(None, Err("boom".to_string()), Utc::now());
+        let json = serde_json::to_value(&probe).unwrap();
+        assert_eq!(json["state"], "Unhealthy");
+        assert_eq!(json["consecutiveFailures"], 1);
+        assert_eq!(serde_json::from_value::<PluginProbe>(json).unwrap(), probe);
+    }
+}
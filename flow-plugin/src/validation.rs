@@ -0,0 +1,137 @@
+//! 插件描述符校验
+//! 加载插件时校验描述符，每个问题记录为一个失败条件，插件作者可以看到具体哪里有误
+
+use crate::descriptor::PluginDescriptor;
+use semver::{Version, VersionReq};
+
+/// 缺少必需字段
+pub const MISSING_FIELD_REASON: &str = "MissingField";
+/// 插件ID不符合命名规则
+pub const INVALID_ID_REASON: &str = "InvalidId";
+/// 插件版本不是SemVer格式
+pub const INVALID_VERSION_REASON: &str = "InvalidVersion";
+/// 宿主版本要求不是有效的SemVer范围
+pub const INVALID_REQUIRES_REASON: &str = "InvalidRequires";
+/// 许可证条目无效
+pub const INVALID_LICENSE_REASON: &str = "InvalidLicense";
+/// 依赖声明无效
+pub const INVALID_DEPENDENCY_REASON: &str = "InvalidDependency";
+/// HTTP路由前缀无效
+pub const INVALID_ROUTE_REASON: &str = "InvalidRoute";
+
+/// 插件ID的最大长度
+const MAX_ID_LENGTH: usize = 253;
+
+/// 描述符中的一个问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptorViolation {
+    /// 机器可读的原因，如 `InvalidVersion`
+    pub reason: &'static str,
+    pub message: String,
+}
+
+impl DescriptorViolation {
+    fn new(reason: &'static str, message: String) -> Self {
+        Self { reason, message }
+    }
+}
+
+/// 插件ID是否符合命名规则：小写字母、数字、`-` 和 `.`，以字母或数字开头和结尾
+pub fn is_valid_plugin_id(id: &str) -> bool {
+    let valid_char = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+    !id.is_empty()
+        && id.len() <= MAX_ID_LENGTH
+        && id.chars().all(|c| valid_char(c) || c == '-' || c == '.')
+        && id.starts_with(valid_char)
+        && id.ends_with(valid_char)
+}
+
+/// 校验描述符，返回发现的所有问题
+pub fn validate_descriptor(descriptor: &PluginDescriptor) -> Vec<DescriptorViolation> {
+    let mut violations = Vec::new();
+    if descriptor.id.trim().is_empty() {
+        violations.push(DescriptorViolation::new(MISSING_FIELD_REASON, "id is required".to_string()));
+    } else if !is_valid_plugin_id(&descriptor.id) {
+        violations.push(DescriptorViolation::new(INVALID_ID_REASON, format!(
+            "id {:?} must consist of lowercase letters, digits, '-' or '.', and start and end with a letter or digit",
+            descriptor.id
+        )));
+    }
+
+    if descriptor.version.trim().is_empty() {
+        violations.push(DescriptorViolation::new(MISSING_FIELD_REASON, "version is required".to_string()));
+    } else if let Err(e) = Version::parse(&descriptor.version) {
+        violations.push(DescriptorViolation::new(INVALID_VERSION_REASON, format!(
+            "version {:?} is not a valid SemVer version: {}", descriptor.version, e
+        )));
+    }
+
+    if let Err(e) = VersionReq::parse(descriptor.requires.trim()) {
+        violations.push(DescriptorViolation::new(INVALID_REQUIRES_REASON, format!(
+            "requires {:?} is not a valid version range: {}", descriptor.requires, e
+        )));
+    }
+
+    for (index, license) in descriptor.license.iter().enumerate() {
+        if license.trim().is_empty() {
+            violations.push(DescriptorViolation::new(INVALID_LICENSE_REASON, format!("license[{}] is empty", index)));
+        }
+    }
+
+    let mut dependencies: Vec<(&String, &String)> = descriptor.dependencies.iter().collect();
+    dependencies.sort();
+    for (dependency, requirement) in dependencies {
+        if *dependency == descriptor.id {
+            violations.push(DescriptorViolation::new(INVALID_DEPENDENCY_REASON, format!("plugin {} depends on itself", dependency)));
+        } else if !is_valid_plugin_id(dependency) {
+            violations.push(DescriptorViolation::new(INVALID_DEPENDENCY_REASON, format!("dependency id {:?} is invalid", dependency)));
+        }
+        if let Err(e) = VersionReq::parse(requirement.trim()) {
+            violations.push(DescriptorViolation::new(INVALID_DEPENDENCY_REASON, format!(
+                "version requirement {:?} of dependency {} is invalid: {}", requirement, dependency, e
+            )));
+        }
+    }
+
+    for route in &descriptor.routes {
+        // 根路径会与所有路由重叠
+        if !route.starts_with('/') || route.trim_end_matches('/').is_empty() {
+            violations.push(DescriptorViolation::new(INVALID_ROUTE_REASON, format!(
+                "route prefix {:?} must be an absolute path other than /", route
+            )));
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reasons(yaml: &str) -> Vec<&'static str> {
+        let descriptor = PluginDescriptor::from_yaml(yaml).unwrap();
+        validate_descriptor(&descriptor).into_iter().map(|v| v.reason).collect()
+    }
+
+    #[test]
+    fn test_valid_descriptor() {
+        assert!(reasons("id: links\nversion: 1.2.0\nrequires: '>=2.0.0'\nlicense: [GPL-3.0]\ndependencies:\n  comment-widget: '^1.0'\n").is_empty());
+        assert!(is_valid_plugin_id("plugin.halo.run"));
+        assert!(!is_valid_plugin_id("Links"));
+        assert!(!is_valid_plugin_id("-links"));
+    }
+
+    #[test]
+    fn test_violations() {
+        assert_eq!(reasons("id: ''\nversion: ''\n"), vec![MISSING_FIELD_REASON, MISSING_FIELD_REASON]);
+        assert_eq!(
+            reasons("id: My_Plugin\nversion: '1.0'\nrequires: '>>2'\nlicense: [' ']\n"),
+            vec![INVALID_ID_REASON, INVALID_VERSION_REASON, INVALID_REQUIRES_REASON, INVALID_LICENSE_REASON]
+        );
+        assert_eq!(
+            reasons("id: links\nversion: 1.0.0\ndependencies:\n  links: '*'\n  Other: 'not a range'\n"),
+            vec![INVALID_DEPENDENCY_REASON, INVALID_DEPENDENCY_REASON, INVALID_DEPENDENCY_REASON]
+        );
+        assert_eq!(reasons("id: links\nversion: 1.0.0\nroutes: [/apis/links, apis, /]\n"), vec![INVALID_ROUTE_REASON, INVALID_ROUTE_REASON]);
+    }
+}
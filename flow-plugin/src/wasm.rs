@@ -0,0 +1,701 @@
+use async_trait::async_trait;
+use crate::capability::{kind_key, Access, PluginCapabilities};
+use crate::controller::{ReconcileRequest, ReconcileResult};
+use crate::descriptor::{scheme_owner, PluginDescriptor};
+use crate::event::Event;
+use crate::loader::PluginLoader;
+use crate::log::PluginLogBuffer;
+use crate::plugin::{Plugin, PluginHttpRequest, PluginHttpResponse};
+use anyhow::{anyhow, bail, Result};
+use flow_api::extension::scheme::SharedSchemeManager;
+use flow_api::extension::GroupVersionKind;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use wasmtime::{AsContext, AsContextMut, Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap, TypedFunc};
+
+/// 嵌入插件描述符（YAML）的自定义段名称
+pub const DESCRIPTOR_SECTION: &str = "flow-plugin";
+
+/// 宿主函数所在的导入模块名称
+pub const HOST_MODULE: &str = "flow";
+
+/// 插件默认可使用的最大线性内存（64MiB）
+pub const DEFAULT_MAX_MEMORY: usize = 64 * 1024 * 1024;
+
+/// 插件单次传给宿主的数据（日志、扩展对象、返回值等）的最大长度（16MiB）
+pub const MAX_GUEST_PAYLOAD: usize = 16 * 1024 * 1024;
+
+/// 插件单次调用默认可消耗的燃料（约等于执行的指令数），耗尽时调用中断，防止死循环占用线程
+pub const DEFAULT_FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// 插件必须导出的线性内存
+const MEMORY_EXPORT: &str = "memory";
+/// 插件必须导出的内存分配函数：`flow_alloc(len: i32) -> i32`，宿主通过它向插件写入数据
+const ALLOC_EXPORT: &str = "flow_alloc";
+/// 可选的启动函数：`flow_start() -> i32`，返回0表示成功
+const START_EXPORT: &str = "flow_start";
+/// 可选的停止函数：`flow_stop() -> i32`，返回0表示成功
+const STOP_EXPORT: &str = "flow_stop";
+/// 可选的配置变更函数：`flow_config_change(ptr: i32, len: i32) -> i32`，参数为JSON配置值，返回0表示成功
+const CONFIG_CHANGE_EXPORT: &str = "flow_config_change";
+/// 可选的HTTP处理函数：`flow_handle_http(ptr: i32, len: i32) -> i64`
+const HTTP_EXPORT: &str = "flow_handle_http";
+/// 可选的事件处理函数：`flow_on_event(ptr: i32, len: i32) -> i32`，参数为JSON事件，返回0表示成功
+const EVENT_EXPORT: &str = "flow_on_event";
+/// 可选的升级函数：`flow_on_upgrade(ptr: i32, len: i32) -> i32`，参数为旧版本号，返回0表示成功
+const UPGRADE_EXPORT: &str = "flow_on_upgrade";
+/// 可选的迁移函数：`flow_migrate(level: i32) -> i32`，返回0表示成功
+const MIGRATE_EXPORT: &str = "flow_migrate";
+/// 可选的健康检查函数：`flow_health() -> i32`，返回0表示健康
+const HEALTH_EXPORT: &str = "flow_health";
+/// 可选的Finder函数：`flow_find(ptr: i32, len: i32) -> i64`，参数为Finder名称，返回JSON数据
+const FIND_EXPORT: &str = "flow_find";
+/// 可选的调谐函数：`flow_reconcile(ptr: i32, len: i32) -> i64`，参数为JSON调谐请求，返回JSON调谐结果
+const RECONCILE_EXPORT: &str = "flow_reconcile";
+
+/// 插件访问扩展对象的宿主接口，扩展对象以JSON表示
+///
+/// 插件调用在阻塞线程中执行，实现可以阻塞等待异步操作完成
+pub trait ExtensionHost: Send + Sync {
+    fn get(&self, gvk: &GroupVersionKind, name: &str) -> Result<Option<Value>>;
+
+    fn list(&self, gvk: &GroupVersionKind) -> Result<Vec<Value>>;
+
+    fn create(&self, gvk: &GroupVersionKind, extension: Value) -> Result<Value>;
+
+    fn update(&self, gvk: &GroupVersionKind, extension: Value) -> Result<Value>;
+
+    fn delete(&self, gvk: &GroupVersionKind, name: &str) -> Result<()>;
+}
+
+/// 将指针和长度打包为一个i64（高32位为指针，低32位为长度）
+fn pack(ptr: i32, len: i32) -> i64 {
+    ((ptr as u32 as i64) << 32) | (len as u32 as i64)
+}
+
+/// 拆分打包的指针和长度
+fn unpack(packed: i64) -> (i32, i32) {
+    ((packed as u64 >> 32) as u32 as i32, packed as u32 as i32)
+}
+
+/// 解析 `group/version/Kind` 形式的扩展类型，核心组可省略为 `version/Kind`
+fn parse_gvk(kind: &str) -> Result<GroupVersionKind> {
+    let parts: Vec<&str> = kind.split('/').collect();
+    match parts.as_slice() {
+        [group, version, kind] if !version.is_empty() && !kind.is_empty() => {
+            Ok(GroupVersionKind::new(*group, *version, *kind))
+        }
+        [version, kind] if !version.is_empty() && !kind.is_empty() => {
+            Ok(GroupVersionKind::new("", *version, *kind))
+        }
+        _ => Err(anyhow!("Invalid extension kind {:?}, expected group/version/Kind", kind)),
+    }
+}
+
+/// 从模块的自定义段中读取插件描述符
+pub fn descriptor_from_module(bytes: &[u8]) -> Result<Option<PluginDescriptor>> {
+    for payload in wasmparser::Parser::new(0).parse_all(bytes) {
+        if let wasmparser::Payload::CustomSection(section) = payload? {
+            if section.name() == DESCRIPTOR_SECTION {
+                let yaml = std::str::from_utf8(section.data())?;
+                return Ok(Some(PluginDescriptor::from_yaml(yaml)?));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 宿主函数的结果，以 `{"ok": ...}` 或 `{"error": "..."}` 的形式返回给插件
+fn envelope(result: Result<Value>) -> Vec<u8> {
+    let value = match result {
+        Ok(value) => serde_json::json!({ "ok": value }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    value.to_string().into_bytes()
+}
+
+/// 插件实例的宿主状态
+struct HostState {
+    plugin_id: String,
+    host: Option<Arc<dyn ExtensionHost>>,
+    /// 插件描述符，可以访问的扩展类型由声明的能力和插件已注册的扩展类型决定
+    descriptor: PluginDescriptor,
+    /// 已注册的扩展类型，插件只对自身注册的类型拥有写权限
+    schemes: Option<SharedSchemeManager>,
+    limits: StoreLimits,
+    /// 保存插件日志的缓冲区
+    logs: Option<Arc<PluginLogBuffer>>,
+}
+
+/// 读取插件内存中的数据，长度由插件提供，分配缓冲区前检查大小和范围
+fn read_guest(store: impl AsContext, memory: Memory, ptr: i32, len: i32) -> Result<Vec<u8>> {
+    let (ptr, len) = (usize::try_from(ptr)?, usize::try_from(len)?);
+    if len > MAX_GUEST_PAYLOAD {
+        bail!("Plugin payload of {} bytes exceeds the limit of {} bytes", len, MAX_GUEST_PAYLOAD);
+    }
+    if ptr.checked_add(len).is_none_or(|end| end > memory.data_size(&store)) {
+        bail!("Plugin payload at {} with {} bytes is out of bounds", ptr, len);
+    }
+    let mut buf = vec![0; len];
+    memory.read(&store, ptr, &mut buf)?;
+    Ok(buf)
+}
+
+/// 通过插件的分配函数申请内存并写入数据，内存的所有权转移给插件
+fn write_guest(mut store: impl AsContextMut, memory: Memory, alloc: TypedFunc<i32, i32>, bytes: &[u8]) -> Result<i64> {
+    let len = i32::try_from(bytes.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, usize::try_from(ptr)?, bytes)?;
+    Ok(pack(ptr, len))
+}
+
+fn caller_exports(caller: &mut Caller<'_, HostState>) -> Result<(Memory, TypedFunc<i32, i32>)> {
+    let memory = caller.get_export(MEMORY_EXPORT)
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| anyhow!("Plugin does not export {}", MEMORY_EXPORT))?;
+    let alloc = caller.get_export(ALLOC_EXPORT)
+        .and_then(Extern::into_func)
+        .ok_or_else(|| anyhow!("Plugin does not export {}", ALLOC_EXPORT))?
+        .typed::<i32, i32>(&*caller)?;
+    Ok((memory, alloc))
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String> {
+    let (memory, _) = caller_exports(caller)?;
+    Ok(String::from_utf8(read_guest(&*caller, memory, ptr, len)?)?)
+}
+
+/// 检查插件是否有权以指定方式访问扩展类型
+fn check_access(state: &HostState, gvk: &GroupVersionKind, access: Access) -> Result<()> {
+    let owner = scheme_owner(&state.plugin_id);
+    let owns = |gvk: &GroupVersionKind| state.schemes.as_ref()
+        .and_then(|schemes| schemes.read().ok()?.get(gvk).map(|scheme| scheme.type_name == owner))
+        .unwrap_or(false);
+    if PluginCapabilities::effective(&state.descriptor, owns).allows(gvk, access) {
+        return Ok(());
+    }
+    let verb = match access {
+        Access::Read => "read",
+        Access::Write => "write",
+    };
+    bail!("Plugin {} is not permitted to {} {}", state.plugin_id, verb, kind_key(gvk))
+}
+
+/// 检查访问权限后调用扩展对象宿主接口，并将结果写回插件内存
+fn call_host(
+    caller: &mut Caller<'_, HostState>,
+    kind: &str,
+    access: Access,
+    call: impl FnOnce(&dyn ExtensionHost, &GroupVersionKind) -> Result<Value>,
+) -> Result<i64> {
+    let result = match caller.data().host.clone() {
+        Some(host) => parse_gvk(kind)
+            .and_then(|gvk| check_access(caller.data(), &gvk, access).map(|_| gvk))
+            .and_then(|gvk| call(host.as_ref(), &gvk)),
+        None => Err(anyhow!("Extension host is not available")),
+    };
+    let (memory, alloc) = caller_exports(caller)?;
+    write_guest(caller, memory, alloc, &envelope(result))
+}
+
+/// 注册插件可导入的宿主函数（模块 `flow`）
+///
+/// 字符串和JSON参数以（指针，长度）传入；返回值为打包的指针和长度，指向JSON结果
+fn define_host_functions(linker: &mut Linker<HostState>) -> Result<()> {
+    linker.func_wrap(HOST_MODULE, "log", |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| -> Result<()> {
+        let message = read_string(&mut caller, ptr, len)?;
+        let plugin = &caller.data().plugin_id;
+        let level = match level {
+            0 => { tracing::error!(plugin = %plugin, "{}", message); "ERROR" }
+            1 => { tracing::warn!(plugin = %plugin, "{}", message); "WARN" }
+            2 => { tracing::info!(plugin = %plugin, "{}", message); "INFO" }
+            3 => { tracing::debug!(plugin = %plugin, "{}", message); "DEBUG" }
+            _ => { tracing::trace!(plugin = %plugin, "{}", message); "TRACE" }
+        };
+        if let Some(logs) = &caller.data().logs {
+            logs.append(plugin, level, message);
+        }
+        Ok(())
+    })?;
+    linker.func_wrap(HOST_MODULE, "extension_get", |mut caller: Caller<'_, HostState>, kind_ptr: i32, kind_len: i32, name_ptr: i32, name_len: i32| {
+        let kind = read_string(&mut caller, kind_ptr, kind_len)?;
+        let name = read_string(&mut caller, name_ptr, name_len)?;
+        call_host(&mut caller, &kind, Access::Read, |host, gvk| Ok(host.get(gvk, &name)?.unwrap_or(Value::Null)))
+    })?;
+    linker.func_wrap(HOST_MODULE, "extension_list", |mut caller: Caller<'_, HostState>, kind_ptr: i32, kind_len: i32| {
+        let kind = read_string(&mut caller, kind_ptr, kind_len)?;
+        call_host(&mut caller, &kind, Access::Read, |host, gvk| Ok(Value::Array(host.list(gvk)?)))
+    })?;
+    linker.func_wrap(HOST_MODULE, "extension_create", |mut caller: Caller<'_, HostState>, kind_ptr: i32, kind_len: i32, ptr: i32, len: i32| {
+        let kind = read_string(&mut caller, kind_ptr, kind_len)?;
+        let data = read_string(&mut caller, ptr, len)?;
+        call_host(&mut caller, &kind, Access::Write, |host, gvk| host.create(gvk, serde_json::from_str(&data)?))
+    })?;
+    linker.func_wrap(HOST_MODULE, "extension_update", |mut caller: Caller<'_, HostState>, kind_ptr: i32, kind_len: i32, ptr: i32, len: i32| {
+        let kind = read_string(&mut caller, kind_ptr, kind_len)?;
+        let data = read_string(&mut caller, ptr, len)?;
+        call_host(&mut caller, &kind, Access::Write, |host, gvk| host.update(gvk, serde_json::from_str(&data)?))
+    })?;
+    linker.func_wrap(HOST_MODULE, "extension_delete", |mut caller: Caller<'_, HostState>, kind_ptr: i32, kind_len: i32, name_ptr: i32, name_len: i32| {
+        let kind = read_string(&mut caller, kind_ptr, kind_len)?;
+        let name = read_string(&mut caller, name_ptr, name_len)?;
+        call_host(&mut caller, &kind, Access::Write, |host, gvk| host.delete(gvk, &name).map(|_| Value::Null))
+    })?;
+    Ok(())
+}
+
+/// 已实例化的WASM模块
+struct WasmInstance {
+    store: Store<HostState>,
+    instance: Instance,
+    /// 单次调用可消耗的燃料
+    fuel_per_call: u64,
+}
+
+impl WasmInstance {
+    /// 调用无参数的生命周期函数，插件未导出时忽略
+    fn call_lifecycle(&mut self, export: &str) -> Result<()> {
+        let Some(func) = self.instance.get_func(&mut self.store, export) else {
+            return Ok(());
+        };
+        let code = func.typed::<(), i32>(&self.store)?.call(&mut self.store, ())?;
+        if code != 0 {
+            bail!("Plugin {} {} returned error code {}", self.store.data().plugin_id, export, code);
+        }
+        Ok(())
+    }
+
+    /// 以整数参数调用返回状态码的插件函数，插件未导出时忽略
+    fn call_with_i32(&mut self, export: &str, arg: i32) -> Result<()> {
+        let Some(func) = self.instance.get_func(&mut self.store, export) else {
+            return Ok(());
+        };
+        let code = func.typed::<i32, i32>(&self.store)?.call(&mut self.store, arg)?;
+        if code != 0 {
+            bail!("Plugin {} {} returned error code {}", self.store.data().plugin_id, export, code);
+        }
+        Ok(())
+    }
+
+    /// 以JSON字节调用返回状态码的插件函数，插件未导出时忽略
+    fn call_with_input(&mut self, export: &str, input: &[u8]) -> Result<()> {
+        let Some(func) = self.instance.get_func(&mut self.store, export) else {
+            return Ok(());
+        };
+        let func = func.typed::<(i32, i32), i32>(&self.store)?;
+        let memory = self.instance.get_memory(&mut self.store, MEMORY_EXPORT)
+            .ok_or_else(|| anyhow!("Plugin does not export {}", MEMORY_EXPORT))?;
+        let alloc = self.instance.get_typed_func::<i32, i32>(&mut self.store, ALLOC_EXPORT)?;
+        let (ptr, len) = unpack(write_guest(&mut self.store, memory, alloc, input)?);
+        let code = func.call(&mut self.store, (ptr, len))?;
+        if code != 0 {
+            bail!("Plugin {} {} returned error code {}", self.store.data().plugin_id, export, code);
+        }
+        Ok(())
+    }
+
+    /// 线性内存大小（字节）
+    fn memory_size(&mut self) -> u64 {
+        self.instance.get_memory(&mut self.store, MEMORY_EXPORT)
+            .map(|memory| memory.data_size(&self.store) as u64)
+            .unwrap_or(0)
+    }
+
+    /// 以JSON字节调用插件函数，插件未导出时返回None
+    fn call_json(&mut self, export: &str, input: &[u8]) -> Result<Option<Vec<u8>>> {
+        let Some(func) = self.instance.get_func(&mut self.store, export) else {
+            return Ok(None);
+        };
+        let func = func.typed::<(i32, i32), i64>(&self.store)?;
+        let memory = self.instance.get_memory(&mut self.store, MEMORY_EXPORT)
+            .ok_or_else(|| anyhow!("Plugin does not export {}", MEMORY_EXPORT))?;
+        let alloc = self.instance.get_typed_func::<i32, i32>(&mut self.store, ALLOC_EXPORT)?;
+        let (ptr, len) = unpack(write_guest(&mut self.store, memory, alloc, input)?);
+        let (ptr, len) = unpack(func.call(&mut self.store, (ptr, len))?);
+        Ok(Some(read_guest(&self.store, memory, ptr, len)?))
+    }
+}
+
+/// WebAssembly插件
+/// 插件在沙箱中运行，只能通过宿主函数访问扩展对象
+pub struct WasmPlugin {
+    descriptor: PluginDescriptor,
+    instance: Arc<Mutex<WasmInstance>>,
+    /// 最近一次调用后的线性内存大小，调用期间读取时不需要等待实例锁
+    memory_bytes: Arc<AtomicU64>,
+}
+
+impl WasmPlugin {
+    /// 在阻塞线程中调用插件，宿主函数可以阻塞等待异步操作
+    async fn call<R: Send + 'static>(&self, f: impl FnOnce(&mut WasmInstance) -> Result<R> + Send + 'static) -> Result<R> {
+        let instance = self.instance.clone();
+        let memory_bytes = self.memory_bytes.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut instance = instance.lock().map_err(|_| anyhow!("Plugin instance is poisoned"))?;
+            let fuel = instance.fuel_per_call;
+            instance.store.set_fuel(fuel)?;
+            let result = f(&mut instance).map_err(|e| match e.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => anyhow!("Plugin {} exceeded its execution budget", instance.store.data().plugin_id),
+                _ => e,
+            });
+            memory_bytes.store(instance.memory_size(), Ordering::Relaxed);
+            result
+        }).await?
+    }
+}
+
+#[async_trait]
+impl Plugin for WasmPlugin {
+    fn descriptor(&self) -> &PluginDescriptor {
+        &self.descriptor
+    }
+
+    async fn start(&self) -> Result<()> {
+        self.call(|instance| instance.call_lifecycle(START_EXPORT)).await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.call(|instance| instance.call_lifecycle(STOP_EXPORT)).await
+    }
+
+    async fn on_config_change(&self, config: Value) -> Result<()> {
+        let input = serde_json::to_vec(&config)?;
+        self.call(move |instance| instance.call_with_input(CONFIG_CHANGE_EXPORT, &input)).await
+    }
+
+    async fn handle_http(&self, request: PluginHttpRequest) -> Result<Option<PluginHttpResponse>> {
+        let input = serde_json::to_vec(&request)?;
+        let Some(output) = self.call(move |instance| instance.call_json(HTTP_EXPORT, &input)).await? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&output)?))
+    }
+
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        let input = serde_json::to_vec(event)?;
+        self.call(move |instance| instance.call_with_input(EVENT_EXPORT, &input)).await
+    }
+
+    async fn on_upgrade(&self, from_version: &str) -> Result<()> {
+        let input = from_version.as_bytes().to_vec();
+        self.call(move |instance| instance.call_with_input(UPGRADE_EXPORT, &input)).await
+    }
+
+    async fn migrate(&self, level: u32) -> Result<()> {
+        let level = i32::try_from(level)?;
+        self.call(move |instance| instance.call_with_i32(MIGRATE_EXPORT, level)).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.call(|instance| instance.call_lifecycle(HEALTH_EXPORT)).await
+    }
+
+    async fn reconcile(&self, request: &ReconcileRequest) -> Result<ReconcileResult> {
+        let input = serde_json::to_vec(request)?;
+        let Some(output) = self.call(move |instance| instance.call_json(RECONCILE_EXPORT, &input)).await? else {
+            return Ok(ReconcileResult::default());
+        };
+        Ok(serde_json::from_slice(&output)?)
+    }
+
+    async fn find(&self, finder: &str) -> Result<Option<Value>> {
+        let input = finder.as_bytes().to_vec();
+        let Some(output) = self.call(move |instance| instance.call_json(FIND_EXPORT, &input)).await? else {
+            return Ok(None);
+        };
+        Ok(serde_json::from_slice(&output)?)
+    }
+
+    fn memory_usage(&self) -> Option<u64> {
+        Some(self.memory_bytes.load(Ordering::Relaxed))
+    }
+}
+
+/// WebAssembly插件加载器
+/// 用于加载 `.wasm` 插件，插件与平台无关
+///
+/// 描述符优先从模块的 `flow-plugin` 自定义段读取，其次读取同名的 `.yaml` 文件
+pub struct WasmPluginLoader {
+    engine: Engine,
+    host: Option<Arc<dyn ExtensionHost>>,
+    schemes: Option<SharedSchemeManager>,
+    max_memory: usize,
+    fuel_per_call: u64,
+    logs: Option<Arc<PluginLogBuffer>>,
+}
+
+impl WasmPluginLoader {
+    pub fn new() -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config).expect("Fuel metering is supported by the default engine configuration"),
+            host: None,
+            schemes: None,
+            max_memory: DEFAULT_MAX_MEMORY,
+            fuel_per_call: DEFAULT_FUEL_PER_CALL,
+            logs: None,
+        }
+    }
+
+    /// 设置保存插件日志的缓冲区
+    pub fn with_log_buffer(mut self, logs: Arc<PluginLogBuffer>) -> Self {
+        self.logs = Some(logs);
+        self
+    }
+
+    /// 设置插件访问扩展对象的宿主接口
+    pub fn with_host(mut self, host: Arc<dyn ExtensionHost>) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// 设置Scheme管理器，插件对自身已注册的扩展类型拥有写权限
+    pub fn with_scheme_manager(mut self, schemes: SharedSchemeManager) -> Self {
+        self.schemes = Some(schemes);
+        self
+    }
+
+    /// 设置单个插件可使用的最大线性内存（字节）
+    pub fn with_max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// 设置插件单次调用（包括实例化）可消耗的燃料
+    pub fn with_fuel_per_call(mut self, fuel_per_call: u64) -> Self {
+        self.fuel_per_call = fuel_per_call;
+        self
+    }
+
+    fn instantiate(&self, module: &Module, descriptor: &PluginDescriptor) -> Result<WasmPlugin> {
+        let mut linker = Linker::new(&self.engine);
+        define_host_functions(&mut linker)?;
+        let state = HostState {
+            plugin_id: descriptor.id.clone(),
+            host: self.host.clone(),
+            descriptor: descriptor.clone(),
+            schemes: self.schemes.clone(),
+            limits: StoreLimitsBuilder::new().memory_size(self.max_memory).build(),
+            logs: self.logs.clone(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.fuel_per_call)?;
+        let instance = linker.instantiate(&mut store, module)
+            .map_err(|e| anyhow!("Failed to instantiate plugin {}: {}", descriptor.id, e))?;
+        let mut instance = WasmInstance { store, instance, fuel_per_call: self.fuel_per_call };
+        let memory_bytes = Arc::new(AtomicU64::new(instance.memory_size()));
+        Ok(WasmPlugin {
+            descriptor: descriptor.clone(),
+            instance: Arc::new(Mutex::new(instance)),
+            memory_bytes,
+        })
+    }
+}
+
+impl Default for WasmPluginLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginLoader for WasmPluginLoader {
+    fn is_applicable(&self, plugin_path: &Path) -> bool {
+        plugin_path.is_file() && plugin_path.extension().is_some_and(|ext| ext == "wasm")
+    }
+
+    fn load_descriptor(&self, plugin_path: &Path) -> Result<PluginDescriptor> {
+        let bytes = std::fs::read(plugin_path)
+            .map_err(|e| anyhow!("Failed to read plugin: {}", e))?;
+        if let Some(descriptor) = descriptor_from_module(&bytes)? {
+            return Ok(descriptor);
+        }
+        let descriptor_path = plugin_path.with_extension("yaml");
+        let content = std::fs::read_to_string(&descriptor_path)
+            .map_err(|e| anyhow!("Failed to read descriptor {:?}: {}", descriptor_path, e))?;
+        PluginDescriptor::from_yaml(&content)
+            .map_err(|e| anyhow!("Failed to parse descriptor: {}", e))
+    }
+
+    fn load_plugin(&self, plugin_path: &Path, descriptor: &PluginDescriptor) -> Result<Box<dyn Plugin>> {
+        let module = Module::from_file(&self.engine, plugin_path)
+            .map_err(|e| anyhow!("Failed to compile plugin {}: {}", descriptor.id, e))?;
+        Ok(Box::new(self.instantiate(&module, descriptor)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// 简单的测试插件：使用递增分配器，`get_note` 读取名为hello的Note，HTTP请求返回固定响应
+    const GUEST: &str = r#"
+        (module
+          (import "flow" "extension_get" (func $get (param i32 i32 i32 i32) (result i64)))
+          (import "flow" "log" (func $log (param i32 i32 i32)))
+          (memory (export "memory") 1)
+          (global $heap (mut i32) (i32.const 1024))
+          (data (i32.const 0) "note.flow.run/v1alpha1/Note")
+          (data (i32.const 64) "hello")
+          (data (i32.const 128) "{\"status\":201,\"body\":\"created\"}")
+          (func (export "flow_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $heap))
+            (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+            (local.get $ptr))
+          (func (export "flow_start") (result i32)
+            (call $log (i32.const 2) (i32.const 64) (i32.const 5))
+            (i32.const 0))
+          (func (export "flow_stop") (result i32)
+            (i32.const 3))
+          (func (export "flow_config_change") (param i32 i32) (result i32)
+            (i32.ne (local.get 1) (i32.const 2)))
+          (func (export "get_note") (param i32 i32) (result i64)
+            (call $get (i32.const 0) (i32.const 27) (i32.const 64) (i32.const 5)))
+          (func (export "flow_handle_http") (param i32 i32) (result i64)
+            (i64.or (i64.shl (i64.const 128) (i64.const 32)) (i64.const 31)))
+          (@custom "flow-plugin" "id: hello\nversion: 1.0.0\ncapabilities:\n  read: [note.flow.run/Note]\n"))
+    "#;
+
+    struct MemoryHost(HashMap<String, Value>);
+
+    impl ExtensionHost for MemoryHost {
+        fn get(&self, gvk: &GroupVersionKind, name: &str) -> Result<Option<Value>> {
+            Ok(self.0.get(&format!("{}/{}", gvk.to_string(), name)).cloned())
+        }
+
+        fn list(&self, _gvk: &GroupVersionKind) -> Result<Vec<Value>> {
+            Ok(self.0.values().cloned().collect())
+        }
+
+        fn create(&self, _gvk: &GroupVersionKind, extension: Value) -> Result<Value> {
+            Ok(extension)
+        }
+
+        fn update(&self, _gvk: &GroupVersionKind, extension: Value) -> Result<Value> {
+            Ok(extension)
+        }
+
+        fn delete(&self, _gvk: &GroupVersionKind, _name: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_descriptor_and_kind() {
+        let bytes = wat::parse_str(GUEST).unwrap();
+        let descriptor = descriptor_from_module(&bytes).unwrap().unwrap();
+        assert_eq!(descriptor.id, "hello");
+        assert_eq!(descriptor.requires, "*");
+        assert!(descriptor_from_module(&wat::parse_str("(module)").unwrap()).unwrap().is_none());
+
+        assert_eq!(parse_gvk("note.flow.run/v1alpha1/Note").unwrap(), GroupVersionKind::new("note.flow.run", "v1alpha1", "Note"));
+        assert_eq!(parse_gvk("v1alpha1/ConfigMap").unwrap(), GroupVersionKind::new("", "v1alpha1", "ConfigMap"));
+        assert!(parse_gvk("Note").is_err());
+        assert_eq!(unpack(pack(1024, 27)), (1024, 27));
+    }
+
+    #[test]
+    fn test_read_guest_checks_bounds() {
+        let mut store = Store::new(&Engine::default(), ());
+        let memory = Memory::new(&mut store, wasmtime::MemoryType::new(1, None)).unwrap();
+        memory.write(&mut store, 0, b"hello").unwrap();
+
+        assert_eq!(read_guest(&store, memory, 0, 5).unwrap(), b"hello");
+        assert!(read_guest(&store, memory, 65530, 10).is_err());
+        assert!(read_guest(&store, memory, i32::MAX, i32::MAX).is_err());
+        assert!(read_guest(&store, memory, 0, -1).is_err());
+        let error = read_guest(&store, memory, 0, MAX_GUEST_PAYLOAD as i32 + 1).unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_calls() {
+        let host = MemoryHost(HashMap::from([(
+            "note.flow.run/v1alpha1/Note/hello".to_string(),
+            serde_json::json!({"metadata": {"name": "hello"}}),
+        )]));
+        let logs = Arc::new(PluginLogBuffer::default());
+        let loader = WasmPluginLoader::new().with_host(Arc::new(host)).with_log_buffer(logs.clone());
+        let bytes = wat::parse_str(GUEST).unwrap();
+        let descriptor = descriptor_from_module(&bytes).unwrap().unwrap();
+        let plugin = loader.instantiate(&Module::new(&loader.engine, &bytes).unwrap(), &descriptor).unwrap();
+
+        plugin.start().await.unwrap();
+        let lines = logs.recent("hello", None);
+        assert_eq!((lines[0].level.as_str(), lines[0].message.as_str()), ("INFO", "hello"));
+        assert!(plugin.stop().await.unwrap_err().to_string().contains("error code 3"));
+        plugin.on_config_change(serde_json::json!({})).await.unwrap();
+        assert!(plugin.on_config_change(serde_json::json!({"a": 1})).await.is_err());
+
+        let output = plugin.call(|instance| instance.call_json("get_note", b"")).await.unwrap().unwrap();
+        let output: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(output["ok"]["metadata"]["name"], "hello");
+
+        let response = plugin.handle_http(PluginHttpRequest::default()).await.unwrap().unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, "created");
+        assert_eq!(plugin.memory_usage(), Some(65536));
+
+        // 未声明的扩展类型拒绝访问
+        let mut denied = descriptor.clone();
+        denied.capabilities = PluginCapabilities::default();
+        let plugin = loader.instantiate(&Module::new(&loader.engine, &bytes).unwrap(), &denied).unwrap();
+        let output = plugin.call(|instance| instance.call_json("get_note", b"")).await.unwrap().unwrap();
+        let output: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(output["error"], "Plugin hello is not permitted to read note.flow.run/Note");
+
+        // 没有宿主接口时返回错误结果而不是中断插件
+        let loader = WasmPluginLoader::new();
+        let plugin = loader.instantiate(&Module::new(&loader.engine, &bytes).unwrap(), &descriptor).unwrap();
+        let output = plugin.call(|instance| instance.call_json("get_note", b"")).await.unwrap().unwrap();
+        let output: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(output["error"], "Extension host is not available");
+    }
+
+    #[tokio::test]
+    async fn test_directory_plugin_logs_to_buffer() {
+        use crate::loader::{DirectoryPluginLoader, PluginLoader};
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("plugin.yaml"), "id: hello\nversion: 1.0.0\nplugin_lib: hello.wasm\n").unwrap();
+        std::fs::write(dir.path().join("hello.wasm"), wat::parse_str(GUEST).unwrap()).unwrap();
+        let logs = Arc::new(PluginLogBuffer::default());
+        // 开发模式下目录插件的构建产物由带日志缓冲的加载器加载
+        let loader = DirectoryPluginLoader::new().with_wasm_loader(WasmPluginLoader::new().with_log_buffer(logs.clone()));
+        let descriptor = loader.load_descriptor(dir.path()).unwrap();
+        let plugin = loader.load_plugin(dir.path(), &descriptor).unwrap();
+
+        plugin.start().await.unwrap();
+
+        let lines = logs.recent("hello", None);
+        assert_eq!((lines[0].level.as_str(), lines[0].message.as_str()), ("INFO", "hello"));
+    }
+
+    #[tokio::test]
+    async fn test_infinite_loop_exhausts_fuel() {
+        const LOOP_GUEST: &str = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "flow_start") (result i32)
+                (loop (br 0))
+                (i32.const 0))
+              (func (export "flow_health") (result i32)
+                (i32.const 0))
+              (@custom "flow-plugin" "id: spin\nversion: 1.0.0\n"))
+        "#;
+        let loader = WasmPluginLoader::new().with_fuel_per_call(100_000);
+        let bytes = wat::parse_str(LOOP_GUEST).unwrap();
+        let descriptor = descriptor_from_module(&bytes).unwrap().unwrap();
+        let plugin = loader.instantiate(&Module::new(&loader.engine, &bytes).unwrap(), &descriptor).unwrap();
+
+        let error = plugin.start().await.unwrap_err();
+        assert_eq!(error.to_string(), "Plugin spin exceeded its execution budget");
+        // 每次调用重新分配燃料，中断后插件仍可调用
+        plugin.health_check().await.unwrap();
+    }
+}
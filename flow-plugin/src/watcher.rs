@@ -0,0 +1,117 @@
+use crate::loader::DirectoryPluginLoader;
+use crate::manager::PluginManager;
+use crate::plugin::PluginWrapper;
+use anyhow::Result;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 收到第一个变化后等待的时间，合并构建过程中产生的多个事件
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 变化时需要重新加载插件的文件：单文件插件本身，目录插件的描述符和构建产物
+pub fn watched_files(wrapper: &PluginWrapper) -> Vec<PathBuf> {
+    let plugin_path = Path::new(&wrapper.plugin_path);
+    if !plugin_path.is_dir() {
+        return vec![plugin_path.to_path_buf()];
+    }
+    let mut files = vec![DirectoryPluginLoader::descriptor_path(plugin_path)];
+    files.extend(DirectoryPluginLoader::build_output(plugin_path, &wrapper.descriptor));
+    files
+}
+
+/// 变化的文件所属的插件ID
+pub fn changed_plugin<'a>(watched: impl IntoIterator<Item = (&'a str, &'a [PathBuf])>, path: &Path) -> Option<String> {
+    watched.into_iter()
+        .find(|(_, files)| files.iter().any(|file| file == path))
+        .map(|(plugin_id, _)| plugin_id.to_string())
+}
+
+/// 监听插件目录（开发模式），已加载插件的构建产物或描述符变化时自动重新加载插件
+///
+/// 重新加载时先停止插件，重新读取后再启动，并保留插件的配置。
+/// 监听在后台任务中持续运行，无法监听目录时返回错误
+pub fn spawn_plugin_watcher(plugins_root: PathBuf, plugin_manager: Arc<dyn PluginManager>) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) => {
+            let _ = tx.send(event);
+        }
+        Err(e) => tracing::warn!("Plugin watcher error: {}", e),
+    })?;
+    std::fs::create_dir_all(&plugins_root)?;
+    watcher.watch(&plugins_root, RecursiveMode::Recursive)?;
+    tracing::info!("Watching plugin directory {:?} for changes", plugins_root);
+
+    tokio::spawn(async move {
+        // 监听器随任务一直存活
+        let _watcher = watcher;
+        while let Some(event) = rx.recv().await {
+            tokio::time::sleep(DEBOUNCE).await;
+            let mut events = vec![event];
+            while let Ok(event) = rx.try_recv() {
+                events.push(event);
+            }
+
+            // 事件中的路径为绝对路径，插件路径可能是相对路径
+            let watched: Vec<(String, Vec<PathBuf>)> = plugin_manager.get_plugins().await.iter()
+                .map(|wrapper| {
+                    let files = watched_files(wrapper).into_iter()
+                        .map(|file| std::path::absolute(&file).unwrap_or(file))
+                        .collect();
+                    (wrapper.plugin_id().to_string(), files)
+                })
+                .collect();
+            let changed: BTreeSet<String> = events.iter()
+                .filter(|event| matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)))
+                .flat_map(|event| event.paths.iter())
+                .filter_map(|path| changed_plugin(watched.iter().map(|(id, files)| (id.as_str(), files.as_slice())), path))
+                .collect();
+
+            for plugin_id in changed {
+                match plugin_manager.reload_plugin(&plugin_id).await {
+                    Ok(()) => tracing::info!("Plugin {} changed, reloaded", plugin_id),
+                    Err(e) => tracing::warn!("Failed to reload plugin {}: {}", plugin_id, e),
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::PluginDescriptor;
+
+    #[test]
+    fn test_watched_files() {
+        let dir = std::env::temp_dir();
+        let mut descriptor = PluginDescriptor::from_yaml("id: search\nversion: 1.0.0\n").unwrap();
+        let wrapper = PluginWrapper::new(descriptor.clone(), dir.to_string_lossy().to_string());
+        assert_eq!(watched_files(&wrapper), vec![dir.join("plugin.yaml")]);
+
+        descriptor.plugin_lib = Some("target/wasm32-unknown-unknown/debug/search.wasm".to_string());
+        let wrapper = PluginWrapper::new(descriptor.clone(), dir.to_string_lossy().to_string());
+        assert_eq!(watched_files(&wrapper), vec![
+            dir.join("plugin.yaml"),
+            dir.join("target/wasm32-unknown-unknown/debug/search.wasm"),
+        ]);
+
+        let wrapper = PluginWrapper::new(descriptor, "/plugins/search.wasm".to_string());
+        assert_eq!(watched_files(&wrapper), vec![PathBuf::from("/plugins/search.wasm")]);
+    }
+
+    #[test]
+    fn test_changed_plugin() {
+        let search = vec![PathBuf::from("/plugins/search/plugin.yaml"), PathBuf::from("/plugins/search/out.wasm")];
+        let editor = vec![PathBuf::from("/plugins/editor.wasm")];
+        let watched = [("search", search.as_slice()), ("editor", editor.as_slice())];
+        assert_eq!(changed_plugin(watched, Path::new("/plugins/search/out.wasm")), Some("search".to_string()));
+        assert_eq!(changed_plugin(watched, Path::new("/plugins/editor.wasm")), Some("editor".to_string()));
+        assert_eq!(changed_plugin(watched, Path::new("/plugins/search/src/lib.rs")), None);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::attachment::AttachmentService;
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{scan_all_pages, ListOptions};
+use flow_domain::attachment::Attachment;
+use flow_infra::attachment::ByteStream;
+use futures_util::StreamExt;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+/// 压缩包数据块大小，积累到该大小后发送给客户端
+const ARCHIVE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 打包下载的附件范围
+#[derive(Debug, Clone)]
+pub enum ArchiveSelection {
+    /// 分组内的所有附件
+    Group(String),
+    /// 指定的附件
+    Attachments(Vec<String>),
+}
+
+/// 生成压缩包内不重复的文件名，同名文件追加序号，如 `a (1).jpg`
+pub fn unique_entry_name(used: &mut HashSet<String>, name: &str) -> String {
+    // 去掉路径分隔符，避免解压到压缩包目录之外
+    let name = name.replace(['/', '\\'], "_");
+    let name = match name.trim_start_matches('.') {
+        "" => "file".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+    if used.insert(name.clone()) {
+        return name;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{}", extension)),
+        _ => (name.clone(), String::new()),
+    };
+    (1..)
+        .map(|index| format!("{} ({}){}", stem, index, extension))
+        .find(|candidate| used.insert(candidate.clone()))
+        .unwrap()
+}
+
+/// 将写入的数据按块发送到通道，供响应体以流的方式读取
+struct ChannelWriter {
+    sender: mpsc::Sender<io::Result<Vec<u8>>>,
+    buffer: Vec<u8>,
+}
+
+impl ChannelWriter {
+    fn new(sender: mpsc::Sender<io::Result<Vec<u8>>>) -> Self {
+        Self { sender, buffer: Vec::with_capacity(ARCHIVE_CHUNK_SIZE) }
+    }
+
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(ARCHIVE_CHUNK_SIZE));
+        // 客户端断开后接收端被丢弃，停止打包
+        self.sender.blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "archive receiver dropped"))
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= ARCHIVE_CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+/// 附件打包下载服务trait
+#[async_trait]
+pub trait AttachmentArchiveService: Send + Sync {
+    /// 将附件打包为zip，以流的方式边读取附件边生成，不在内存中缓存整个压缩包
+    ///
+    /// 打包过程中读取附件失败时流以错误结束
+    async fn archive(&self, selection: ArchiveSelection) -> Result<ByteStream>;
+}
+
+/// 默认附件打包下载服务实现
+pub struct DefaultAttachmentArchiveService {
+    attachment_service: Arc<dyn AttachmentService>,
+}
+
+impl DefaultAttachmentArchiveService {
+    pub fn new(attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self { attachment_service }
+    }
+
+    async fn resolve(&self, selection: ArchiveSelection) -> Result<Vec<Attachment>> {
+        match selection {
+            ArchiveSelection::Group(group_name) => {
+                // 逐页列出全部附件，避免分组附件较多时压缩包缺少文件
+                let attachments = scan_all_pages(ListOptions::default(), |options| self.attachment_service.list(options)).await?;
+                Ok(attachments.into_iter()
+                    .filter(|a| a.spec.group_name.as_deref() == Some(group_name.as_str()))
+                    .collect())
+            }
+            ArchiveSelection::Attachments(names) => {
+                let mut attachments = Vec::new();
+                for name in names {
+                    match self.attachment_service.get(&name).await? {
+                        Some(attachment) => attachments.push(attachment),
+                        None => tracing::debug!("Attachment {} not found, skip archiving it", name),
+                    }
+                }
+                Ok(attachments)
+            }
+        }
+    }
+}
+
+/// 在阻塞线程中逐个读取附件并写入zip
+fn write_archive(
+    attachment_service: Arc<dyn AttachmentService>,
+    attachments: Vec<Attachment>,
+    writer: ChannelWriter,
+    handle: tokio::runtime::Handle,
+) -> Result<()> {
+    let mut zip = ZipWriter::new_stream(writer);
+    let mut used_names = HashSet::new();
+    for attachment in attachments {
+        let name = attachment.metadata.name.as_str();
+        let Some((_, mut stream)) = handle.block_on(attachment_service.read_content(name))? else {
+            tracing::warn!("Content of attachment {} not found, skip archiving it", name);
+            continue;
+        };
+        let display_name = attachment.spec.display_name.as_deref().unwrap_or(name);
+        // 图片和视频大多已经压缩过，直接存储
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(attachment.spec.size.unwrap_or(0) >= u32::MAX as u64);
+        zip.start_file(unique_entry_name(&mut used_names, display_name), options)?;
+        while let Some(chunk) = handle.block_on(stream.next()) {
+            zip.write_all(&chunk?)?;
+        }
+    }
+    zip.finish()?.into_inner().flush()?;
+    Ok(())
+}
+
+#[async_trait]
+impl AttachmentArchiveService for DefaultAttachmentArchiveService {
+    async fn archive(&self, selection: ArchiveSelection) -> Result<ByteStream> {
+        let attachments = self.resolve(selection).await?;
+        let (sender, receiver) = mpsc::channel(4);
+        let writer = ChannelWriter::new(sender.clone());
+        let attachment_service = self.attachment_service.clone();
+        let handle = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = write_archive(attachment_service, attachments, writer, handle) {
+                tracing::warn!("Failed to archive attachments: {}", e);
+                let _ = sender.blocking_send(Err(io::Error::other(e.to_string())));
+            }
+        });
+
+        let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|chunk| (chunk, receiver))
+        });
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_entry_name() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_entry_name(&mut used, "a.jpg"), "a.jpg");
+        assert_eq!(unique_entry_name(&mut used, "a.jpg"), "a (1).jpg");
+        assert_eq!(unique_entry_name(&mut used, "a.jpg"), "a (2).jpg");
+        assert_eq!(unique_entry_name(&mut used, "../etc/passwd"), "_etc_passwd");
+        assert_eq!(unique_entry_name(&mut used, ".."), "file");
+        assert_eq!(unique_entry_name(&mut used, "README"), "README");
+        assert_eq!(unique_entry_name(&mut used, "README"), "README (1)");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_group_beyond_first_page() {
+        use crate::attachment::DefaultAttachmentService;
+        use flow_api::extension::{ExtensionClient, Metadata, SCAN_PAGE_SIZE};
+        use flow_domain::attachment::AttachmentSpec;
+        use flow_infra::extension::ReactiveExtensionClient;
+
+        let dir = tempfile::tempdir().unwrap();
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        let attachment_service = DefaultAttachmentService::new(
+            client.clone(),
+            Arc::new(flow_infra::attachment::LocalAttachmentStorage::new(dir.path().to_path_buf())),
+            Arc::new(crate::attachment::thumbnail::DefaultThumbnailService::new(dir.path().join("thumbnails"), 0.8)),
+            dir.path().join("upload"),
+            "http://localhost".to_string(),
+        );
+        for i in 0..SCAN_PAGE_SIZE + 2 {
+            let group_name = if i < SCAN_PAGE_SIZE { "other" } else { "photos" };
+            client.create(Attachment {
+                metadata: Metadata::new(format!("attachment-{}", i)),
+                spec: AttachmentSpec {
+                    display_name: None,
+                    group_name: Some(group_name.to_string()),
+                    policy_name: None,
+                    owner_name: None,
+                    media_type: None,
+                    size: None,
+                    tags: None,
+                },
+                status: None,
+            }).await.unwrap();
+        }
+        let service = DefaultAttachmentArchiveService::new(Arc::new(attachment_service));
+
+        let attachments = service.resolve(ArchiveSelection::Group("photos".to_string())).await.unwrap();
+
+        let names: Vec<_> = attachments.iter().map(|a| a.metadata.name.as_str()).collect();
+        assert_eq!(names, ["attachment-1000", "attachment-1001"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_zip_through_channel() {
+        let (sender, mut receiver) = mpsc::channel(4);
+        let writer = ChannelWriter::new(sender);
+        let content = vec![7u8; ARCHIVE_CHUNK_SIZE * 3];
+        let expected = content.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut zip = ZipWriter::new_stream(writer);
+            zip.start_file("a.bin", SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored)).unwrap();
+            zip.write_all(&content).unwrap();
+            zip.finish().unwrap().into_inner().flush().unwrap();
+        });
+
+        let mut archive = Vec::new();
+        while let Some(chunk) = receiver.recv().await {
+            archive.extend(chunk.unwrap());
+        }
+        let mut archive = zip::ZipArchive::new(io::Cursor::new(archive)).unwrap();
+        let mut entry = archive.by_name("a.bin").unwrap();
+        let mut read = Vec::new();
+        io::Read::read_to_end(&mut entry, &mut read).unwrap();
+        assert_eq!(read, expected);
+    }
+}
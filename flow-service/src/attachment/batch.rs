@@ -0,0 +1,255 @@
+use crate::attachment::{AttachmentReferenceService, AttachmentService, QuotaService};
+use crate::attachment::reference::ATTACHMENT_REFERENCED_ERROR;
+use crate::content::CoverService;
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::ExtensionClient;
+use flow_domain::attachment::{Attachment, Group};
+use flow_domain::security::User;
+use flow_infra::extension::ReactiveExtensionClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 单次批量操作最多处理的附件数量
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// 批量操作的目标分组或用户不存在时的错误信息前缀
+pub const BATCH_TARGET_NOT_FOUND_ERROR: &str = "Batch target not found";
+
+/// 附件不存在时记录的失败原因
+const ATTACHMENT_NOT_FOUND: &str = "Attachment not found";
+
+/// 修改附件标签的方式：先用set替换（为空时保留原标签），再删除remove中的标签，最后追加add中的标签
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TagUpdate {
+    #[serde(default)]
+    pub set: Option<Vec<String>>,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl TagUpdate {
+    /// 计算修改后的标签，去除空白和重复的标签并保持原有顺序，没有标签时返回None
+    pub fn apply(&self, tags: Option<&[String]>) -> Option<Vec<String>> {
+        let base = self.set.as_deref().or(tags).unwrap_or_default();
+        let mut seen = HashSet::new();
+        let result: Vec<String> = base.iter()
+            .filter(|tag| !self.remove.contains(tag))
+            .chain(self.add.iter())
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty() && seen.insert(tag.to_string()))
+            .map(str::to_string)
+            .collect();
+        (!result.is_empty()).then_some(result)
+    }
+}
+
+/// 批量操作
+#[derive(Debug, Clone)]
+pub enum BatchOperation {
+    /// 删除附件，force为false时跳过仍被引用的附件
+    Delete { force: bool },
+    /// 移动到分组，为空表示未分组
+    MoveToGroup { group_name: Option<String> },
+    /// 修改标签
+    Retag(TagUpdate),
+    /// 修改上传者
+    ChangeOwner { owner_name: String },
+}
+
+/// 处理失败的附件
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// 批量操作结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    /// 处理成功的附件
+    pub succeeded: Vec<String>,
+    /// 处理失败的附件
+    pub failed: Vec<BatchFailure>,
+}
+
+/// 附件批量操作服务trait
+#[async_trait]
+pub trait AttachmentBatchService: Send + Sync {
+    /// 对附件逐个执行操作，单个附件失败不影响其他附件，失败原因记录在结果中
+    async fn apply(&self, attachment_names: &[String], operation: BatchOperation) -> Result<BatchReport>;
+}
+
+/// 默认附件批量操作服务实现
+pub struct DefaultAttachmentBatchService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    attachment_service: Arc<dyn AttachmentService>,
+    reference_service: Arc<dyn AttachmentReferenceService>,
+    cover_service: Option<Arc<dyn CoverService>>,
+    quota_service: Option<Arc<dyn QuotaService>>,
+}
+
+impl DefaultAttachmentBatchService {
+    pub fn new(
+        extension_client: Arc<ReactiveExtensionClient>,
+        attachment_service: Arc<dyn AttachmentService>,
+        reference_service: Arc<dyn AttachmentReferenceService>,
+    ) -> Self {
+        Self {
+            extension_client,
+            attachment_service,
+            reference_service,
+            cover_service: None,
+            quota_service: None,
+        }
+    }
+
+    /// 设置封面服务，删除附件后清除引用该附件的文章封面
+    pub fn with_cover_service(mut self, cover_service: Arc<dyn CoverService>) -> Self {
+        self.cover_service = Some(cover_service);
+        self
+    }
+
+    /// 设置配额服务，修改上传者时检查并转移用户的存储用量
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn QuotaService>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+
+    /// 检查目标分组或用户是否存在
+    async fn check_target(&self, operation: &BatchOperation) -> Result<()> {
+        match operation {
+            BatchOperation::MoveToGroup { group_name: Some(group_name) } => {
+                let group = self.extension_client.fetch::<Group>(group_name).await
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch group {}: {}", group_name, e))?;
+                if group.is_none() {
+                    anyhow::bail!("{}: group {}", BATCH_TARGET_NOT_FOUND_ERROR, group_name);
+                }
+            }
+            BatchOperation::ChangeOwner { owner_name } => {
+                let user = self.extension_client.fetch::<User>(owner_name).await
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch user {}: {}", owner_name, e))?;
+                if user.is_none() {
+                    anyhow::bail!("{}: user {}", BATCH_TARGET_NOT_FOUND_ERROR, owner_name);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn apply_one(&self, attachment: Attachment, operation: &BatchOperation) -> Result<()> {
+        let name = attachment.metadata.name.clone();
+        match operation {
+            BatchOperation::Delete { force } => {
+                let used_by = self.reference_service.used_by(&attachment).await?;
+                if !used_by.is_empty() && !force {
+                    anyhow::bail!(
+                        "{}: used by {}",
+                        ATTACHMENT_REFERENCED_ERROR,
+                        used_by.iter().map(|r| format!("{}/{}", r.kind, r.name)).collect::<Vec<_>>().join(", ")
+                    );
+                }
+                self.attachment_service.delete(&name).await?;
+                if let Some(cover_service) = &self.cover_service {
+                    if let Err(e) = cover_service.release_attachment(&attachment).await {
+                        tracing::warn!("Failed to release covers referencing attachment {}: {}", name, e);
+                    }
+                }
+            }
+            BatchOperation::MoveToGroup { group_name } => {
+                // 存储策略不变，文件保留在原位置，只修改分组并校验分组的上传限制和配额
+                let migrated = self.attachment_service
+                    .migrate(&name, attachment.spec.policy_name.clone(), group_name.clone())
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!(ATTACHMENT_NOT_FOUND))?;
+                self.attachment_service.delete_previous_file(&migrated).await?;
+            }
+            BatchOperation::Retag(update) => {
+                let tags = update.apply(attachment.spec.tags.as_deref());
+                if tags != attachment.spec.tags {
+                    let mut attachment = attachment;
+                    attachment.spec.tags = tags;
+                    self.attachment_service.update(attachment).await?;
+                }
+            }
+            BatchOperation::ChangeOwner { owner_name } => {
+                let previous_owner = attachment.spec.owner_name.clone();
+                if previous_owner.as_deref() == Some(owner_name.as_str()) {
+                    return Ok(());
+                }
+                let size = attachment.spec.size.unwrap_or(0);
+                if let Some(quota_service) = &self.quota_service {
+                    quota_service.check(Some(owner_name), None, size).await?;
+                }
+                let mut attachment = attachment;
+                attachment.spec.owner_name = Some(owner_name.clone());
+                self.attachment_service.update(attachment).await?;
+                // 转移用户的存储用量，失败不影响修改结果
+                if let Some(quota_service) = &self.quota_service {
+                    let size = size as i64;
+                    if let Err(e) = async {
+                        quota_service.record(previous_owner.as_deref(), None, -size, -1).await?;
+                        quota_service.record(Some(owner_name), None, size, 1).await
+                    }.await {
+                        tracing::warn!("Failed to record storage usage of attachment {}: {}", name, e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AttachmentBatchService for DefaultAttachmentBatchService {
+    async fn apply(&self, attachment_names: &[String], operation: BatchOperation) -> Result<BatchReport> {
+        self.check_target(&operation).await?;
+        let mut report = BatchReport::default();
+        let mut seen = HashSet::new();
+        for name in attachment_names.iter().filter(|name| seen.insert(name.as_str())) {
+            let result = match self.attachment_service.get(name).await {
+                Ok(Some(attachment)) => self.apply_one(attachment, &operation).await,
+                Ok(None) => Err(anyhow::anyhow!(ATTACHMENT_NOT_FOUND)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => report.succeeded.push(name.clone()),
+                Err(e) => {
+                    tracing::warn!("Batch operation {:?} failed on attachment {}: {}", operation, name, e);
+                    report.failed.push(BatchFailure { name: name.clone(), error: e.to_string() });
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tag_update_add_and_remove() {
+        let update = TagUpdate { set: None, add: tags(&["new", " a ", ""]), remove: tags(&["b"]) };
+        assert_eq!(update.apply(Some(&tags(&["a", "b", "c"]))), Some(tags(&["a", "c", "new"])));
+        assert_eq!(update.apply(None), Some(tags(&["new", "a"])));
+
+        let clear = TagUpdate { remove: tags(&["a"]), ..Default::default() };
+        assert_eq!(clear.apply(Some(&tags(&["a"]))), None);
+    }
+
+    #[test]
+    fn test_tag_update_set_replaces_existing() {
+        let update = TagUpdate { set: Some(tags(&["x", "x", "y"])), add: tags(&["z"]), remove: tags(&["y"]) };
+        assert_eq!(update.apply(Some(&tags(&["a"]))), Some(tags(&["x", "z"])));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::attachment::CONTENT_HASH_ANNO;
+use flow_domain::attachment::Attachment;
+
+/// 下载请求的Range解析结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// 返回整个文件（没有Range、格式无法识别或请求了多段时忽略Range）
+    Full,
+    /// 返回从start开始的len个字节
+    Partial { start: u64, len: u64 },
+    /// 请求的范围超出文件大小
+    Unsatisfiable,
+}
+
+/// 解析Range请求头，只支持单段的 `bytes=start-end`、`bytes=start-` 和 `bytes=-suffix`
+pub fn parse_range(header: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        // 后缀范围：最后suffix个字节
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if size == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => {
+                let len = suffix.min(size);
+                ByteRange::Partial { start: size - len, len }
+            }
+            Err(_) => ByteRange::Full,
+        };
+    }
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = match end {
+        "" => size.saturating_sub(1),
+        end => match end.parse::<u64>() {
+            Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+            _ => return ByteRange::Full,
+        },
+    };
+    if start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial { start, len: end - start + 1 }
+}
+
+/// 附件内容的ETag：有内容摘要时使用摘要，否则使用名称和大小生成弱ETag
+pub fn entity_tag(attachment: &Attachment) -> String {
+    let hash = attachment.metadata.annotations.as_ref()
+        .and_then(|a| a.get(CONTENT_HASH_ANNO));
+    match hash {
+        Some(hash) => format!("\"{}\"", hash),
+        None => format!("W/\"{}-{}\"", attachment.metadata.name, attachment.spec.size.unwrap_or(0)),
+    }
+}
+
+/// If-None-Match请求头是否匹配ETag（弱比较）
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match.split(',').any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), ByteRange::Partial { start: 0, len: 10 });
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial { start: 90, len: 10 });
+        assert_eq!(parse_range(Some("bytes=90-200"), 100), ByteRange::Partial { start: 90, len: 10 });
+        assert_eq!(parse_range(Some("bytes=-30"), 100), ByteRange::Partial { start: 70, len: 30 });
+        assert_eq!(parse_range(Some("bytes=-300"), 100), ByteRange::Partial { start: 0, len: 100 });
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=9-1"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("\"abc\"", "\"abc\""));
+        assert!(etag_matches("W/\"abc\", \"def\"", "\"def\""));
+        assert!(etag_matches("\"abc\"", "W/\"abc\""));
+        assert!(etag_matches("*", "\"abc\""));
+        assert!(!etag_matches("\"abc\"", "\"abd\""));
+    }
+}
@@ -45,6 +45,7 @@ impl GroupService for DefaultGroupService {
         group_with_status.status = Some(GroupStatus {
             update_timestamp: Some(Utc::now()),
             total_attachments: Some(0),
+            total_size: Some(0),
         });
         
         self.client.create(group_with_status).await
@@ -57,6 +58,7 @@ impl GroupService for DefaultGroupService {
             group.status = Some(GroupStatus {
                 update_timestamp: Some(Utc::now()),
                 total_attachments: Some(0),
+                total_size: Some(0),
             });
         } else {
             // 更新时间戳
@@ -95,6 +97,7 @@ impl GroupService for DefaultGroupService {
         
         let attachments = self.attachment_service.list(options).await?;
         let count = attachments.len() as u64;
+        let total_size = attachments.iter().map(|a| a.spec.size.unwrap_or(0)).sum();
         
         // 更新分组状态
         let mut group = self.get(name).await?
@@ -103,6 +106,7 @@ impl GroupService for DefaultGroupService {
         group.status = Some(GroupStatus {
             update_timestamp: Some(Utc::now()),
             total_attachments: Some(count),
+            total_size: Some(total_size),
         });
         
         self.update(group).await
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use exif::{Field, In, Tag, Value};
+use img_parts::{Bytes, DynImage, ImageEXIF};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// 图片宽度（像素，已按EXIF方向校正）的注解
+pub const IMAGE_WIDTH_ANNO: &str = "storage.halo.run/image-width";
+
+/// 图片高度（像素，已按EXIF方向校正）的注解
+pub const IMAGE_HEIGHT_ANNO: &str = "storage.halo.run/image-height";
+
+/// 拍摄设备（厂商和型号）的注解
+pub const CAMERA_ANNO: &str = "storage.halo.run/camera";
+
+/// 拍摄时间的注解，格式为 2024-01-02T15:04:05
+pub const TAKEN_AT_ANNO: &str = "storage.halo.run/taken-at";
+
+/// 拍摄位置的注解，格式为 纬度,经度
+pub const GPS_ANNO: &str = "storage.halo.run/gps";
+
+/// 存储策略配置中控制是否去除EXIF敏感信息的键
+pub const STRIP_EXIF_SETTING: &str = "stripExif";
+
+/// 从图片中解析出的元数据
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub camera: Option<String>,
+    pub taken_at: Option<NaiveDateTime>,
+    /// (纬度, 经度)
+    pub gps: Option<(f64, f64)>,
+}
+
+impl ImageMetadata {
+    /// 转换为附件注解，include_gps为false时不包含拍摄位置
+    pub fn to_annotations(&self, include_gps: bool) -> HashMap<String, String> {
+        let mut annotations = HashMap::new();
+        if let Some(width) = self.width {
+            annotations.insert(IMAGE_WIDTH_ANNO.to_string(), width.to_string());
+        }
+        if let Some(height) = self.height {
+            annotations.insert(IMAGE_HEIGHT_ANNO.to_string(), height.to_string());
+        }
+        if let Some(camera) = &self.camera {
+            annotations.insert(CAMERA_ANNO.to_string(), camera.clone());
+        }
+        if let Some(taken_at) = self.taken_at {
+            annotations.insert(TAKEN_AT_ANNO.to_string(), taken_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+        }
+        if let (true, Some((latitude, longitude))) = (include_gps, self.gps) {
+            annotations.insert(GPS_ANNO.to_string(), format!("{:.6},{:.6}", latitude, longitude));
+        }
+        annotations
+    }
+}
+
+fn ascii_field(exif: &exif::Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => {
+            let value = String::from_utf8_lossy(values.first()?);
+            let value = value.trim_end_matches('\0').trim();
+            (!value.is_empty()).then(|| value.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// 度分秒形式的GPS坐标转换为十进制，南纬和西经为负数
+fn gps_coordinate(exif: &exif::Exif, tag: Tag, ref_tag: Tag) -> Option<f64> {
+    let Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    let degrees = parts.iter()
+        .take(3)
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(part, divisor)| part.to_f64() / divisor)
+        .sum::<f64>();
+    if !degrees.is_finite() {
+        return None;
+    }
+    match ascii_field(exif, ref_tag).as_deref() {
+        Some("S") | Some("W") => Some(-degrees),
+        _ => Some(degrees),
+    }
+}
+
+fn camera(exif: &exif::Exif) -> Option<String> {
+    let make = ascii_field(exif, Tag::Make);
+    let model = ascii_field(exif, Tag::Model);
+    match (make, model) {
+        // 型号中通常已包含厂商名称
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    }
+}
+
+fn orientation(exif: &exif::Exif) -> Option<u32> {
+    exif.get_field(Tag::Orientation, In::PRIMARY)?.value.get_uint(0)
+}
+
+/// 解析图片的尺寸和EXIF信息，无法识别的图片返回None
+pub fn extract_image_metadata(content: &[u8]) -> Option<ImageMetadata> {
+    let (width, height) = image::ImageReader::new(Cursor::new(content))
+        .with_guessed_format().ok()?
+        .into_dimensions().ok()?;
+    let mut metadata = ImageMetadata {
+        width: Some(width),
+        height: Some(height),
+        ..Default::default()
+    };
+
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(content)) else {
+        return Some(metadata);
+    };
+    // 方向为5~8时图片需要旋转90度显示，宽高互换
+    if matches!(orientation(&exif), Some(5..=8)) {
+        metadata.width = Some(height);
+        metadata.height = Some(width);
+    }
+    metadata.camera = camera(&exif);
+    metadata.taken_at = ascii_field(&exif, Tag::DateTimeOriginal)
+        .or_else(|| ascii_field(&exif, Tag::DateTime))
+        .and_then(|value| NaiveDateTime::parse_from_str(&value, "%Y:%m:%d %H:%M:%S").ok());
+    metadata.gps = gps_coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef)
+        .zip(gps_coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef));
+    Some(metadata)
+}
+
+/// 去除图片EXIF中的位置、设备等可识别信息，只保留方向
+///
+/// 图片格式不支持或没有EXIF时返回None，表示无需修改
+pub fn strip_identifying_exif(content: &[u8]) -> Result<Option<Vec<u8>>> {
+    let Some(mut image) = DynImage::from_bytes(Bytes::copy_from_slice(content))? else {
+        return Ok(None);
+    };
+    let Some(raw_exif) = image.exif() else {
+        return Ok(None);
+    };
+
+    // 保留方向，避免去除后图片显示方向错误
+    let orientation = exif::Reader::new().read_raw(raw_exif.to_vec()).ok()
+        .and_then(|exif| exif.get_field(Tag::Orientation, In::PRIMARY).cloned())
+        .filter(|field: &Field| field.value.get_uint(0).is_some_and(|o| o != 1));
+    let stripped = match &orientation {
+        Some(field) => {
+            let mut writer = exif::experimental::Writer::new();
+            writer.push_field(field);
+            let mut buf = Cursor::new(Vec::new());
+            writer.write(&mut buf, false)
+                .map_err(|e| anyhow::anyhow!("Failed to write EXIF: {}", e))?;
+            Some(Bytes::from(buf.into_inner()))
+        }
+        None => None,
+    };
+    image.set_exif(stripped);
+
+    let mut output = Vec::with_capacity(content.len());
+    image.encoder().write_to(&mut output)?;
+    Ok(Some(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::Rational;
+    use image::{DynamicImage, ImageFormat};
+
+    fn field(tag: Tag, value: Value) -> Field {
+        Field { tag, ifd_num: In::PRIMARY, value }
+    }
+
+    fn ascii(value: &str) -> Value {
+        Value::Ascii(vec![value.as_bytes().to_vec()])
+    }
+
+    fn dms(degrees: u32, minutes: u32, seconds: u32) -> Value {
+        Value::Rational(vec![
+            Rational { num: degrees, denom: 1 },
+            Rational { num: minutes, denom: 1 },
+            Rational { num: seconds, denom: 1 },
+        ])
+    }
+
+    /// 生成带有相机、时间、位置和方向信息的JPEG
+    fn jpeg_with_exif() -> Vec<u8> {
+        let mut jpeg = Cursor::new(Vec::new());
+        DynamicImage::new_rgb8(8, 4).write_to(&mut jpeg, ImageFormat::Jpeg).unwrap();
+
+        let fields = [
+            field(Tag::Make, ascii("Canon")),
+            field(Tag::Model, ascii("Canon EOS R5")),
+            field(Tag::Orientation, Value::Short(vec![6])),
+            field(Tag::DateTimeOriginal, ascii("2024:01:02 15:04:05")),
+            field(Tag::GPSLatitudeRef, ascii("N")),
+            field(Tag::GPSLatitude, dms(31, 30, 0)),
+            field(Tag::GPSLongitudeRef, ascii("W")),
+            field(Tag::GPSLongitude, dms(121, 15, 36)),
+        ];
+        let mut writer = exif::experimental::Writer::new();
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut raw_exif = Cursor::new(Vec::new());
+        writer.write(&mut raw_exif, false).unwrap();
+
+        let mut image = DynImage::from_bytes(Bytes::from(jpeg.into_inner())).unwrap().unwrap();
+        image.set_exif(Some(Bytes::from(raw_exif.into_inner())));
+        let mut output = Vec::new();
+        image.encoder().write_to(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_extract_image_metadata() {
+        let metadata = extract_image_metadata(&jpeg_with_exif()).unwrap();
+        assert_eq!((metadata.width, metadata.height), (Some(4), Some(8)));
+        assert_eq!(metadata.camera.as_deref(), Some("Canon EOS R5"));
+        let annotations = metadata.to_annotations(true);
+        assert_eq!(annotations[TAKEN_AT_ANNO], "2024-01-02T15:04:05");
+        assert_eq!(annotations[GPS_ANNO], "31.500000,-121.260000");
+        assert!(!metadata.to_annotations(false).contains_key(GPS_ANNO));
+
+        assert!(extract_image_metadata(b"not an image").is_none());
+    }
+
+    #[test]
+    fn test_strip_identifying_exif() {
+        let stripped = strip_identifying_exif(&jpeg_with_exif()).unwrap().unwrap();
+        let metadata = extract_image_metadata(&stripped).unwrap();
+        assert_eq!(metadata.camera, None);
+        assert_eq!(metadata.taken_at, None);
+        assert_eq!(metadata.gps, None);
+        // 方向保留
+        assert_eq!((metadata.width, metadata.height), (Some(4), Some(8)));
+
+        assert!(strip_identifying_exif(&stripped).unwrap().is_some());
+        assert!(strip_identifying_exif(b"not an image").unwrap().is_none());
+    }
+}
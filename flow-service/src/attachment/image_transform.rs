@@ -0,0 +1,271 @@
+use crate::attachment::AttachmentService;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use image::{DynamicImage, ImageFormat, imageops::FilterType};
+use serde::Deserialize;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 处理后图片允许的最大宽高
+pub const MAX_TRANSFORM_DIMENSION: u32 = 4096;
+
+/// 图片处理参数不合法
+pub const INVALID_TRANSFORM_ERROR: &str = "Invalid image transform";
+
+/// 附件不是可处理的图片
+pub const NOT_AN_IMAGE_ERROR: &str = "Attachment is not an image";
+
+/// 缩放方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFit {
+    /// 等比缩放后居中裁剪，填满目标尺寸
+    Cover,
+    /// 等比缩放到目标尺寸以内，不放大
+    #[default]
+    Contain,
+    /// 拉伸到目标尺寸
+    Fill,
+}
+
+impl ImageFit {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImageFit::Cover => "cover",
+            ImageFit::Contain => "contain",
+            ImageFit::Fill => "fill",
+        }
+    }
+}
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl OutputFormat {
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "image/jpeg" => Some(OutputFormat::Jpeg),
+            "image/png" => Some(OutputFormat::Png),
+            "image/webp" => Some(OutputFormat::Webp),
+            _ => None,
+        }
+    }
+
+    fn image_format(&self) -> ImageFormat {
+        match self {
+            OutputFormat::Jpeg => ImageFormat::Jpeg,
+            OutputFormat::Png => ImageFormat::Png,
+            OutputFormat::Webp => ImageFormat::WebP,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+        }
+    }
+
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Png => "image/png",
+            OutputFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// 图片处理参数（?w=600&h=400&fit=cover&format=webp）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ImageTransform {
+    #[serde(rename = "w")]
+    pub width: Option<u32>,
+    #[serde(rename = "h")]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fit: ImageFit,
+    pub format: Option<OutputFormat>,
+}
+
+impl ImageTransform {
+    pub fn validate(&self) -> Result<()> {
+        for dimension in [self.width, self.height].into_iter().flatten() {
+            if dimension == 0 || dimension > MAX_TRANSFORM_DIMENSION {
+                anyhow::bail!("{}: dimension must be between 1 and {}", INVALID_TRANSFORM_ERROR, MAX_TRANSFORM_DIMENSION);
+            }
+        }
+        Ok(())
+    }
+
+    /// 缓存文件名，同一附件的相同参数共用一个缓存
+    pub fn cache_file_name(&self, attachment_name: &str, format: OutputFormat) -> String {
+        let dimension = |d: Option<u32>| d.map(|d| d.to_string()).unwrap_or_else(|| "auto".to_string());
+        format!(
+            "{}_{}x{}_{}.{}",
+            attachment_name, dimension(self.width), dimension(self.height), self.fit.as_str(), format.extension()
+        )
+    }
+
+    /// 缩放的目标尺寸，只指定宽或高时按原图比例计算另一边，计算出的边不超过 [`MAX_TRANSFORM_DIMENSION`]
+    pub fn target_size(&self, source_width: u32, source_height: u32) -> Option<(u32, u32)> {
+        let clamp = |value: u32| value.min(MAX_TRANSFORM_DIMENSION);
+        match (self.width, self.height) {
+            (None, None) => None,
+            (Some(w), Some(h)) => Some((w, h)),
+            (Some(w), None) => Some((w, clamp(scale(source_height, w, source_width)))),
+            (None, Some(h)) => Some((clamp(scale(source_width, h, source_height)), h)),
+        }
+    }
+
+    /// 按参数缩放图片
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let (source_width, source_height) = (image.width(), image.height());
+        let Some((width, height)) = self.target_size(source_width, source_height) else {
+            return image;
+        };
+        match self.fit {
+            ImageFit::Cover => image.resize_to_fill(width, height, FilterType::Lanczos3),
+            ImageFit::Fill => image.resize_exact(width, height, FilterType::Lanczos3),
+            ImageFit::Contain if width >= source_width && height >= source_height => image,
+            ImageFit::Contain => image.resize(width, height, FilterType::Lanczos3),
+        }
+    }
+}
+
+fn scale(value: u32, target: u32, source: u32) -> u32 {
+    ((value as u64 * target as u64) / source.max(1) as u64).clamp(1, u32::MAX as u64) as u32
+}
+
+/// 处理后的图片
+pub struct TransformedImage {
+    pub content: Vec<u8>,
+    pub media_type: &'static str,
+}
+
+/// 图片处理服务trait
+#[async_trait]
+pub trait ImageTransformService: Send + Sync {
+    /// 按参数处理附件图片，附件不存在时返回None
+    async fn transform(&self, name: &str, transform: &ImageTransform) -> Result<Option<TransformedImage>>;
+}
+
+/// 默认图片处理服务实现，处理结果缓存在磁盘上
+pub struct DefaultImageTransformService {
+    attachment_service: Arc<dyn AttachmentService>,
+    cache_dir: PathBuf,
+}
+
+impl DefaultImageTransformService {
+    pub fn new(attachment_service: Arc<dyn AttachmentService>, cache_dir: PathBuf) -> Self {
+        Self { attachment_service, cache_dir }
+    }
+}
+
+#[async_trait]
+impl ImageTransformService for DefaultImageTransformService {
+    async fn transform(&self, name: &str, transform: &ImageTransform) -> Result<Option<TransformedImage>> {
+        transform.validate()?;
+        let Some(attachment) = self.attachment_service.get(name).await? else {
+            return Ok(None);
+        };
+        let media_type = attachment.spec.media_type.as_deref().unwrap_or_default();
+        let Some(source_format) = OutputFormat::from_media_type(media_type) else {
+            anyhow::bail!("{}: {}", NOT_AN_IMAGE_ERROR, media_type);
+        };
+        let format = transform.format.unwrap_or(source_format);
+
+        // 1. 命中缓存直接返回
+        let cache_path = self.cache_dir.join(transform.cache_file_name(&attachment.metadata.name, format));
+        if let Ok(content) = tokio::fs::read(&cache_path).await {
+            return Ok(Some(TransformedImage { content, media_type: format.media_type() }));
+        }
+
+        // 2. 读取原图
+        let Some((_, stream)) = self.attachment_service.read_content(name).await? else {
+            return Ok(None);
+        };
+        let source: Vec<u8> = stream.try_concat().await?;
+
+        // 3. 解码、缩放和编码比较耗时，放到阻塞线程中执行
+        let transform = transform.clone();
+        let content = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            let image = image::load_from_memory(&source)
+                .map_err(|e| anyhow::anyhow!("Failed to decode image: {}", e))?;
+            let image = transform.apply(image);
+            // JPEG不支持透明通道
+            let image = if format == OutputFormat::Jpeg {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            } else {
+                image
+            };
+            let mut content = Cursor::new(Vec::new());
+            image.write_to(&mut content, format.image_format())
+                .map_err(|e| anyhow::anyhow!("Failed to encode image: {}", e))?;
+            Ok(content.into_inner())
+        }).await??;
+
+        // 4. 写入缓存，失败不影响本次返回
+        if let Err(e) = async {
+            tokio::fs::create_dir_all(&self.cache_dir).await?;
+            tokio::fs::write(&cache_path, &content).await
+        }.await {
+            tracing::warn!("Failed to cache transformed image {}: {}", cache_path.display(), e);
+        }
+        Ok(Some(TransformedImage { content, media_type: format.media_type() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(width: Option<u32>, height: Option<u32>, fit: ImageFit) -> ImageTransform {
+        ImageTransform { width, height, fit, format: None }
+    }
+
+    #[test]
+    fn test_apply() {
+        let image = DynamicImage::new_rgb8(800, 400);
+        let resized = transform(Some(200), None, ImageFit::Contain).apply(image.clone());
+        assert_eq!((resized.width(), resized.height()), (200, 100));
+
+        let resized = transform(Some(300), Some(300), ImageFit::Cover).apply(image.clone());
+        assert_eq!((resized.width(), resized.height()), (300, 300));
+
+        let resized = transform(Some(300), Some(300), ImageFit::Fill).apply(image.clone());
+        assert_eq!((resized.width(), resized.height()), (300, 300));
+
+        // contain不放大
+        let resized = transform(Some(1600), None, ImageFit::Contain).apply(image);
+        assert_eq!((resized.width(), resized.height()), (800, 400));
+    }
+
+    #[test]
+    fn test_target_size_extreme_aspect_ratio() {
+        let max = MAX_TRANSFORM_DIMENSION;
+        assert_eq!(transform(Some(max), None, ImageFit::Fill).target_size(1, 10000), Some((max, max)));
+        assert_eq!(transform(None, Some(max), ImageFit::Cover).target_size(10000, 1), Some((max, max)));
+        assert_eq!(transform(Some(max), None, ImageFit::Fill).target_size(u32::MAX, 1), Some((max, 1)));
+        assert_eq!(transform(Some(200), None, ImageFit::Contain).target_size(800, 400), Some((200, 100)));
+        assert_eq!(transform(None, None, ImageFit::Contain).target_size(800, 400), None);
+    }
+
+    #[test]
+    fn test_validate_and_cache_name() {
+        assert!(transform(Some(0), None, ImageFit::Contain).validate().is_err());
+        assert!(transform(None, Some(MAX_TRANSFORM_DIMENSION + 1), ImageFit::Contain).validate().is_err());
+        let t = transform(Some(600), None, ImageFit::Cover);
+        assert!(t.validate().is_ok());
+        assert_eq!(t.cache_file_name("a", OutputFormat::Webp), "a_600xauto_cover.webp");
+    }
+}
@@ -0,0 +1,301 @@
+use crate::attachment::AttachmentService;
+use crate::content::{ContentRequest, PostRequest, PostService};
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions};
+use flow_domain::attachment::Attachment;
+use flow_domain::content::Post;
+use flow_infra::extension::ReactiveExtensionClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 迁移后的附件
+#[derive(Debug, Clone)]
+pub struct MigratedAttachment {
+    /// 迁移前的附件
+    pub previous: Attachment,
+    /// 迁移后的附件
+    pub current: Attachment,
+    /// 文件是否复制到了新的存储（两个策略都使用本地存储时文件位置不变）
+    pub file_moved: bool,
+}
+
+impl MigratedAttachment {
+    /// 迁移前后的访问地址对应关系，新存储没有对应尺寸的缩略图时指向原图
+    pub fn url_replacements(&self) -> Vec<(String, String)> {
+        let permalink = |a: &Attachment| a.status.as_ref().and_then(|s| s.permalink.clone());
+        let (Some(old_permalink), Some(new_permalink)) = (permalink(&self.previous), permalink(&self.current)) else {
+            return Vec::new();
+        };
+        let new_thumbnails = self.current.status.as_ref().and_then(|s| s.thumbnails.clone()).unwrap_or_default();
+        let mut replacements: Vec<(String, String)> = self.previous.status.as_ref()
+            .and_then(|s| s.thumbnails.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, old_url)| {
+                let new_url = new_thumbnails.get(&key).cloned().unwrap_or_else(|| new_permalink.clone());
+                (old_url, new_url)
+            })
+            .collect();
+        replacements.push((old_permalink, new_permalink));
+        replacements.retain(|(old, new)| !old.is_empty() && old != new);
+        replacements
+    }
+}
+
+/// 按对应关系替换文本中的地址，没有变化时返回None
+///
+/// 先替换较长的地址，避免一个地址是另一个地址的前缀时替换错误
+pub fn rewrite_urls(text: &str, replacements: &[(String, String)]) -> Option<String> {
+    let mut replacements: Vec<&(String, String)> = replacements.iter()
+        .filter(|(old, _)| text.contains(old.as_str()))
+        .collect();
+    if replacements.is_empty() {
+        return None;
+    }
+    replacements.sort_by_key(|(old, _)| std::cmp::Reverse(old.len()));
+    // 先替换为占位符，避免新地址中包含其他旧地址时被重复替换
+    let mut rewritten = text.to_string();
+    for (index, (old, _)) in replacements.iter().enumerate() {
+        rewritten = rewritten.replace(old.as_str(), &format!("\u{0}{}\u{0}", index));
+    }
+    for (index, (_, new)) in replacements.iter().enumerate() {
+        rewritten = rewritten.replace(&format!("\u{0}{}\u{0}", index), new);
+    }
+    Some(rewritten)
+}
+
+/// 附件迁移请求
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRequest {
+    /// 要迁移的附件名称
+    pub attachment_names: Vec<String>,
+    /// 目标存储策略，为空表示默认的本地存储
+    pub target_policy: Option<String>,
+    /// 目标分组，为空表示未分组
+    pub target_group: Option<String>,
+    /// 是否同时替换文章封面和内容中引用的附件地址
+    #[serde(default)]
+    pub rewrite_references: bool,
+}
+
+/// 迁移失败的附件
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// 附件迁移结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// 迁移成功的附件
+    pub migrated: Vec<String>,
+    /// 迁移失败的附件
+    pub failed: Vec<MigrationFailure>,
+    /// 替换了附件地址的文章
+    pub rewritten_posts: Vec<String>,
+    /// 已发布内容仍引用原地址（文章有未发布的修改），因此保留了原文件的附件
+    pub retained: Vec<String>,
+}
+
+/// 附件迁移服务trait
+#[async_trait]
+pub trait AttachmentMigrationService: Send + Sync {
+    /// 将附件迁移到目标存储策略和分组，全部处理完成后删除原存储中的文件
+    async fn migrate(&self, request: MigrationRequest) -> Result<MigrationReport>;
+}
+
+/// 默认附件迁移服务实现
+pub struct DefaultAttachmentMigrationService {
+    client: Arc<ReactiveExtensionClient>,
+    attachment_service: Arc<dyn AttachmentService>,
+    post_service: Arc<dyn PostService>,
+}
+
+impl DefaultAttachmentMigrationService {
+    pub fn new(
+        client: Arc<ReactiveExtensionClient>,
+        attachment_service: Arc<dyn AttachmentService>,
+        post_service: Arc<dyn PostService>,
+    ) -> Self {
+        Self { client, attachment_service, post_service }
+    }
+
+    /// 替换文章封面和head内容中的地址，head即为发布内容时重新发布
+    ///
+    /// 返回是否修改了文章，以及已发布内容中仍引用的原地址
+    async fn rewrite_post(&self, mut post: Post, replacements: &[(String, String)]) -> Result<(bool, Vec<String>)> {
+        let name = post.metadata.name.clone();
+        let published = post.spec.publish.unwrap_or(false) && post.spec.release_snapshot.is_some();
+        let in_sync = published && post.spec.release_snapshot == post.spec.head_snapshot;
+
+        let mut changed = false;
+        if let Some(cover) = post.spec.cover.as_deref().and_then(|c| rewrite_urls(c, replacements)) {
+            post.spec.cover = Some(cover);
+            changed = true;
+        }
+        let content = match post.spec.head_snapshot {
+            Some(_) => {
+                let head = self.post_service.get_head_content(&name).await
+                    .map_err(|e| anyhow::anyhow!("Failed to get content of post {}: {}", name, e))?;
+                let raw = rewrite_urls(&head.raw, replacements);
+                let rendered = rewrite_urls(&head.content, replacements);
+                (raw.is_some() || rendered.is_some()).then(|| ContentRequest {
+                    raw: raw.unwrap_or(head.raw),
+                    content: rendered.unwrap_or(head.content),
+                    raw_type: head.raw_type,
+                })
+            }
+            None => None,
+        };
+        let content_changed = content.is_some();
+
+        if changed || content_changed {
+            let post = self.post_service.update_post(PostRequest { post, content }).await
+                .map_err(|e| anyhow::anyhow!("Failed to update post {}: {}", name, e))?;
+            if content_changed && in_sync {
+                self.post_service.publish(post).await
+                    .map_err(|e| anyhow::anyhow!("Failed to republish post {}: {}", name, e))?;
+            }
+        }
+
+        // 有未发布修改的文章不能直接重新发布，记录已发布内容中仍引用的原地址
+        let mut stale = Vec::new();
+        if published && !in_sync {
+            let release = self.post_service.get_release_content(&name).await
+                .map_err(|e| anyhow::anyhow!("Failed to get released content of post {}: {}", name, e))?;
+            stale.extend(replacements.iter()
+                .filter(|(old, _)| release.raw.contains(old.as_str()) || release.content.contains(old.as_str()))
+                .map(|(old, _)| old.clone()));
+        }
+        Ok((changed || content_changed, stale))
+    }
+}
+
+#[async_trait]
+impl AttachmentMigrationService for DefaultAttachmentMigrationService {
+    async fn migrate(&self, request: MigrationRequest) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+
+        // 1. 复制文件到目标存储并更新附件，此时原文件仍然保留
+        let mut migrated = Vec::new();
+        for name in &request.attachment_names {
+            match self.attachment_service.migrate(name, request.target_policy.clone(), request.target_group.clone()).await {
+                Ok(Some(attachment)) => migrated.push(attachment),
+                Ok(None) => report.failed.push(MigrationFailure { name: name.clone(), error: "Attachment not found".to_string() }),
+                Err(e) => {
+                    tracing::warn!("Failed to migrate attachment {}: {}", name, e);
+                    report.failed.push(MigrationFailure { name: name.clone(), error: e.to_string() });
+                }
+            }
+        }
+
+        // 2. 替换文章中引用的地址
+        let replacements: Vec<(String, String)> = migrated.iter()
+            .flat_map(MigratedAttachment::url_replacements)
+            .collect();
+        let mut stale_urls = HashSet::new();
+        if request.rewrite_references && !replacements.is_empty() {
+            let posts = self.client.list_all::<Post>(ListOptions::default()).await
+                .map_err(|e| anyhow::anyhow!("Failed to list posts: {}", e))?;
+            for post in posts {
+                let name = post.metadata.name.clone();
+                match self.rewrite_post(post, &replacements).await {
+                    Ok((rewritten, stale)) => {
+                        if rewritten {
+                            report.rewritten_posts.push(name);
+                        }
+                        stale_urls.extend(stale);
+                    }
+                    Err(e) => {
+                        // 无法确认文章是否仍引用原地址，保留所有原文件
+                        tracing::warn!("Failed to rewrite attachment references in post {}: {}", name, e);
+                        stale_urls.extend(replacements.iter().map(|(old, _)| old.clone()));
+                    }
+                }
+            }
+        }
+
+        // 3. 删除原存储中的文件，已发布内容仍引用的文件保留
+        for attachment in migrated {
+            let name = attachment.current.metadata.name.clone();
+            if attachment.url_replacements().iter().any(|(old, _)| stale_urls.contains(old)) {
+                report.retained.push(name);
+                continue;
+            }
+            if let Err(e) = self.attachment_service.delete_previous_file(&attachment).await {
+                tracing::warn!("Failed to delete original file of migrated attachment {}: {}", name, e);
+                report.retained.push(name.clone());
+            }
+            report.migrated.push(name);
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::attachment::{AttachmentSpec, AttachmentStatus};
+    use std::collections::HashMap;
+
+    fn attachment(permalink: &str, thumbnails: &[(&str, &str)]) -> Attachment {
+        Attachment {
+            metadata: Metadata::new("a".to_string()),
+            spec: AttachmentSpec {
+                display_name: None,
+                group_name: None,
+                policy_name: None,
+                owner_name: None,
+                media_type: None,
+                size: None,
+                tags: None,
+            },
+            status: Some(AttachmentStatus {
+                permalink: Some(permalink.to_string()),
+                thumbnails: (!thumbnails.is_empty()).then(|| thumbnails.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>()),
+                used_by: None,
+                blurhash: None,
+                dominant_color: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_url_replacements_and_rewrite() {
+        let migrated = MigratedAttachment {
+            previous: attachment("http://x/upload/a.png", &[("M", "http://x/upload/thumbnails/a_M.png")]),
+            current: attachment("https://cdn/a.png", &[]),
+            file_moved: true,
+        };
+        let replacements = migrated.url_replacements();
+        assert_eq!(replacements.len(), 2);
+
+        let text = "![](http://x/upload/a.png) <img src=\"http://x/upload/thumbnails/a_M.png\">";
+        assert_eq!(
+            rewrite_urls(text, &replacements).unwrap(),
+            "![](https://cdn/a.png) <img src=\"https://cdn/a.png\">"
+        );
+        assert!(rewrite_urls("no attachments", &replacements).is_none());
+    }
+
+    #[test]
+    fn test_rewrite_urls_prefers_longer_and_no_chaining() {
+        let replacements = vec![
+            ("http://x/a".to_string(), "http://x/b".to_string()),
+            ("http://x/a/long".to_string(), "http://y/long".to_string()),
+            ("http://x/b".to_string(), "http://x/c".to_string()),
+        ];
+        assert_eq!(
+            rewrite_urls("http://x/a/long http://x/a http://x/b", &replacements).unwrap(),
+            "http://y/long http://x/b http://x/c"
+        );
+    }
+}
@@ -3,31 +3,105 @@ pub mod policy_service;
 pub mod group_service;
 pub mod policy_template_service;
 pub mod shared_url;
+pub mod storage_resolver;
+pub mod upload_session;
+pub mod image_transform;
+pub mod quota_service;
+pub mod upload_scanner;
+pub mod image_metadata;
+pub mod video;
+pub mod migration;
+pub mod archive;
+pub mod reference;
+pub mod remote_import;
+pub mod thumbnail_job;
+pub mod spool;
+pub mod download;
+pub mod upload_policy;
+pub mod text_extract;
+pub mod placeholder;
+pub mod batch;
+pub mod search_indexing;
 
 pub use policy_service::{PolicyService, DefaultPolicyService};
 pub use group_service::{GroupService, DefaultGroupService};
 pub use policy_template_service::{PolicyTemplateService, DefaultPolicyTemplateService};
 pub use shared_url::{SharedUrlService, DefaultSharedUrlService, SharedUrl};
+pub use storage_resolver::{PolicyStorageResolver, PluginStorageCleanup, StorageFactory, StorageTemplateInfo, STORAGE_TEMPLATE_INVALID_ERROR};
+pub use upload_session::{UploadSessionService, DefaultUploadSessionService, UploadSession, NewUploadSession};
+pub use image_transform::{ImageTransformService, DefaultImageTransformService, ImageTransform};
+pub use quota_service::{QuotaService, DefaultQuotaService, UserStorageUsage};
+pub use upload_scanner::{UploadScanner, ClamAvScanner, ClamAvConfig, InfectedFileAction, ScanVerdict};
+pub use video::{VideoProcessor, VideoMetadata, FfmpegConfig};
+#[cfg(feature = "video-thumbnails")]
+pub use video::FfmpegVideoProcessor;
+pub use archive::{AttachmentArchiveService, DefaultAttachmentArchiveService, ArchiveSelection};
+pub use remote_import::{RemoteImportService, DefaultRemoteImportService, RemoteImportConfig, RemoteImportRequest, RemoteFetcher};
+pub use reference::{AttachmentReferenceService, DefaultAttachmentReferenceService};
+pub use thumbnail_job::{ThumbnailJobService, DefaultThumbnailJobService, ThumbnailJobProgress, ThumbnailRegeneration};
+pub use spool::SpooledFile;
+pub use upload_policy::{UploadRestrictions, UploadValidationError, UploadViolation};
+pub use download::{ByteRange, parse_range, entity_tag, etag_matches};
+pub use text_extract::{TextExtractor, PdftotextExtractor};
+pub use search_indexing::SearchIndexingAttachmentService;
+pub use batch::{AttachmentBatchService, DefaultAttachmentBatchService, BatchOperation, BatchReport, TagUpdate};
+pub use migration::{AttachmentMigrationService, DefaultAttachmentMigrationService, MigratedAttachment, MigrationRequest, MigrationReport};
 
-use flow_domain::attachment::{Attachment, AttachmentSpec, AttachmentStatus, ThumbnailSize};
-use flow_api::extension::{ExtensionClient, ListOptions, Metadata};
+use flow_domain::attachment::{Attachment, AttachmentSpec, AttachmentStatus, Group, ThumbnailFormat, ThumbnailSize};
+use flow_api::extension::{scan_all_pages, ExtensionClient, ListOptions, Metadata};
 use flow_infra::extension::ReactiveExtensionClient;
-use flow_infra::attachment::AttachmentStorage;
+use flow_infra::attachment::{AttachmentStorage, ByteStream};
 use crate::attachment::thumbnail::ThumbnailService;
+use crate::attachment::upload_scanner::{INFECTED_FILE_ERROR, REASON_ATTACHMENT_INFECTED};
+use crate::attachment::image_metadata::{extract_image_metadata, strip_identifying_exif, STRIP_EXIF_SETTING, IMAGE_HEIGHT_ANNO, IMAGE_WIDTH_ANNO};
+use crate::attachment::video::VIDEO_DURATION_ANNO;
+use crate::attachment::placeholder::compute_placeholder;
+use crate::attachment::upload_policy::{sniff_media_type, SNIFF_LENGTH};
+use crate::notification::NotificationCenter;
+use flow_domain::notification::{Reason, ReasonSpec, ReasonSubject};
 use async_trait::async_trait;
 use std::sync::Arc;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use anyhow::Result;
 use uuid::Uuid;
 use std::collections::HashMap;
 
+/// 保存在远程存储中的附件对象Key的注解
+pub const OBJECT_KEY_ANNO: &str = "storage.halo.run/object-key";
+
+/// 附件内容SHA-256摘要的注解，相同内容的附件共用同一份文件
+pub const CONTENT_HASH_ANNO: &str = "storage.halo.run/content-hash";
+
+/// 生成缩略图时缩略图配置签名的注解，与当前配置不同时需要重新生成
+pub const THUMBNAIL_SIGNATURE_ANNO: &str = "storage.halo.run/thumbnail-signature";
+
+/// 附件替代文本的注解，用于图片的alt属性和搜索
+pub const ALT_TEXT_ANNO: &str = "storage.halo.run/alt";
+
+/// 存储策略配置中是否允许匿名访问附件内容的键，为true时附件内容代理和图片缩放接口不要求登录
+pub const PUBLIC_ACCESS_SETTING: &str = "publicAccess";
+
+/// 存储策略配置是否允许匿名访问附件内容，未配置时不允许
+pub fn public_access_enabled(settings: Option<&serde_json::Value>) -> bool {
+    settings.and_then(|settings| settings.get(PUBLIC_ACCESS_SETTING)?.as_bool()).unwrap_or(false)
+}
+
+/// 计算附件内容的SHA-256摘要（十六进制）
+pub fn content_hash(content: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(content))
+}
+
 /// Attachment服务trait
 #[async_trait]
 pub trait AttachmentService: Send + Sync {
-    /// 上传附件
+    /// 上传附件，内容以流的方式写入临时文件后保存，超过最大文件大小时返回错误
+    ///
+    /// 附件的媒体类型按文件内容识别，media_type只作参考；不符合存储策略或分组的限制时返回UploadValidationError
     async fn upload(
         &self,
-        file_content: Vec<u8>,
+        file_content: ByteStream,
         filename: String,
         media_type: Option<String>,
         owner_name: Option<String>,
@@ -35,6 +109,11 @@ pub trait AttachmentService: Send + Sync {
         group_name: Option<String>,
     ) -> Result<Attachment>;
     
+    /// 将上传内容暂存到临时文件，超过最大文件大小时返回错误
+    ///
+    /// 用于需要先接收完整内容才能确定上传参数的场景，如表单中文件字段位于其他字段之前
+    async fn spool(&self, content: ByteStream) -> Result<SpooledFile>;
+    
     /// 删除附件
     async fn delete(&self, name: &str) -> Result<()>;
     
@@ -46,6 +125,27 @@ pub trait AttachmentService: Send + Sync {
     
     /// 更新附件
     async fn update(&self, attachment: Attachment) -> Result<Attachment>;
+    
+    /// 生成附件的签名临时访问链接，附件所在存储不支持签名时返回None
+    async fn signed_url(&self, name: &str, expires_in: Duration) -> Result<Option<String>>;
+    
+    /// 以流的方式读取附件内容，附件不存在时返回None
+    async fn read_content(&self, name: &str) -> Result<Option<(Attachment, ByteStream)>>;
+    
+    /// 以流的方式读取附件从start开始的len个字节，附件不存在时返回None
+    async fn read_content_range(&self, name: &str, start: u64, len: u64) -> Result<Option<(Attachment, ByteStream)>>;
+    
+    /// 将附件迁移到目标存储策略和分组，文件复制到目标存储后更新附件，原文件保留，附件不存在时返回None
+    async fn migrate(&self, name: &str, target_policy: Option<String>, target_group: Option<String>) -> Result<Option<MigratedAttachment>>;
+    
+    /// 删除迁移前保存在原存储中的文件
+    async fn delete_previous_file(&self, migrated: &MigratedAttachment) -> Result<()>;
+    
+    /// 重新生成本地附件的缩略图，force为false时跳过缩略图配置未变化且文件齐全的附件，附件不存在时返回None
+    async fn regenerate_thumbnails(&self, name: &str, force: bool) -> Result<Option<ThumbnailRegeneration>>;
+    
+    /// 附件所在存储策略是否允许匿名访问附件内容，见 [`PUBLIC_ACCESS_SETTING`]
+    async fn is_public(&self, attachment: &Attachment) -> Result<bool>;
 }
 
 /// 默认Attachment服务实现
@@ -55,6 +155,13 @@ pub struct DefaultAttachmentService {
     thumbnail_service: Arc<dyn ThumbnailService>,
     upload_path: PathBuf,
     base_url: String,
+    storage_resolver: Option<Arc<PolicyStorageResolver>>,
+    quota_service: Option<Arc<dyn QuotaService>>,
+    upload_scanner: Option<(Arc<dyn UploadScanner>, InfectedFileAction)>,
+    notification_center: Option<Arc<dyn NotificationCenter>>,
+    video_processor: Option<Arc<dyn VideoProcessor>>,
+    spool_dir: PathBuf,
+    max_file_size: Option<u64>,
 }
 
 impl DefaultAttachmentService {
@@ -65,13 +172,389 @@ impl DefaultAttachmentService {
         upload_path: PathBuf,
         base_url: String,
     ) -> Self {
+        // 上传内容暂存在附件目录的tmp子目录，与附件位于同一文件系统
+        let spool_dir = upload_path.parent()
+            .map(|root| root.join("tmp"))
+            .unwrap_or_else(|| upload_path.join(".tmp"));
         Self {
             extension_client,
             storage,
             thumbnail_service,
             upload_path,
             base_url,
+            storage_resolver: None,
+            quota_service: None,
+            upload_scanner: None,
+            notification_center: None,
+            video_processor: None,
+            spool_dir,
+            max_file_size: None,
+        }
+    }
+    
+    /// 设置存储策略解析器，附件按策略保存到对应的存储（如对象存储）
+    pub fn with_storage_resolver(mut self, storage_resolver: Arc<PolicyStorageResolver>) -> Self {
+        self.storage_resolver = Some(storage_resolver);
+        self
+    }
+    
+    /// 设置存储配额服务，上传前检查用户和分组的配额并记录用量
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn QuotaService>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+    
+    /// 设置上传文件扫描器，保存文件前扫描，感染的文件按action拒绝或隔离
+    pub fn with_upload_scanner(mut self, scanner: Arc<dyn UploadScanner>, action: InfectedFileAction) -> Self {
+        self.upload_scanner = Some((scanner, action));
+        self
+    }
+    
+    /// 设置通知中心，检测到感染文件时发出通知
+    pub fn with_notification_center(mut self, notification_center: Arc<dyn NotificationCenter>) -> Self {
+        self.notification_center = Some(notification_center);
+        self
+    }
+    
+    /// 设置视频处理器，上传视频时截取封面生成缩略图并记录时长和分辨率
+    pub fn with_video_processor(mut self, video_processor: Arc<dyn VideoProcessor>) -> Self {
+        self.video_processor = Some(video_processor);
+        self
+    }
+    
+    /// 设置上传文件的最大大小，超过时中止接收并返回错误
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = Some(max_file_size);
+        self
+    }
+    
+    /// 为本地图片生成所有尺寸和格式的缩略图，返回缩略图键到访问地址的映射
+    fn generate_thumbnails(&self, source_path: &Path) -> HashMap<String, String> {
+        let mut thumbnails = HashMap::new();
+        let thumbnail_url = |thumbnail_path: &Path| format!("{}/upload/thumbnails/{}",
+            self.base_url.trim_end_matches('/'),
+            thumbnail_path.file_name().unwrap().to_string_lossy());
+        for size in [ThumbnailSize::Xl, ThumbnailSize::L, ThumbnailSize::M, ThumbnailSize::S] {
+            if let Ok(thumbnail_path) = self.thumbnail_service.generate_thumbnail(source_path, size) {
+                thumbnails.insert(size.as_str().to_string(), thumbnail_url(&thumbnail_path));
+            }
+            // 额外生成WebP/AVIF等格式，键为 尺寸.格式
+            for format in self.thumbnail_service.formats() {
+                match self.thumbnail_service.generate_thumbnail_as(source_path, size, format) {
+                    Ok(thumbnail_path) => {
+                        thumbnails.insert(ThumbnailFormat::thumbnail_key(size, Some(format)), thumbnail_url(&thumbnail_path));
+                    }
+                    Err(e) => tracing::warn!("Failed to generate {} thumbnail: {}", format.as_str(), e),
+                }
+            }
+        }
+        thumbnails
+    }
+    
+    /// 解析本地视频的时长和分辨率，并截取封面帧生成缩略图，失败时只记录日志
+    async fn process_video(&self, video_processor: &dyn VideoProcessor, source_path: &Path, file_id: &str)
+        -> (HashMap<String, String>, HashMap<String, String>) {
+        let metadata = video_processor.probe(source_path).await
+            .inspect_err(|e| tracing::warn!("Failed to probe video {}: {}", source_path.display(), e))
+            .unwrap_or_default();
+        
+        // 封面帧只是生成缩略图的中间文件，生成后删除
+        let poster_path = self.upload_path.join(format!("{}_poster.jpg", file_id));
+        let thumbnails = match video_processor.poster_frame(source_path, &poster_path, metadata.duration).await {
+            Ok(()) => self.generate_thumbnails(&poster_path),
+            Err(e) => {
+                tracing::warn!("Failed to extract poster frame from {}: {}", source_path.display(), e);
+                HashMap::new()
+            }
+        };
+        if let Err(e) = tokio::fs::remove_file(&poster_path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove poster frame {}: {}", poster_path.display(), e);
+            }
+        }
+        (metadata.to_annotations(), thumbnails)
+    }
+    
+    /// 扫描上传的文件，感染时记录审计日志、发出通知，并按配置隔离文件后返回错误
+    async fn scan_upload(&self, file_id: &str, filename: &str, owner_name: Option<&str>, file: &SpooledFile) -> Result<()> {
+        let Some((scanner, action)) = &self.upload_scanner else {
+            return Ok(());
+        };
+        let ScanVerdict::Infected(signature) = scanner.scan(file.path()).await? else {
+            return Ok(());
+        };
+        
+        let owner = owner_name.unwrap_or("anonymous");
+        let quarantined = match action {
+            InfectedFileAction::Reject => None,
+            InfectedFileAction::Quarantine(dir) => {
+                let path = dir.join(format!("{}_{}", file_id, Path::new(filename).file_name().map(|f| f.to_string_lossy()).unwrap_or_default()));
+                tokio::fs::create_dir_all(dir).await?;
+                tokio::fs::copy(file.path(), &path).await?;
+                Some(path)
+            }
+        };
+        tracing::warn!(
+            target: "audit",
+            scanner = scanner.name(),
+            owner,
+            filename,
+            signature = signature.as_str(),
+            quarantined = quarantined.as_ref().map(|p| p.display().to_string()),
+            "Infected upload rejected"
+        );
+        
+        if let Some(center) = &self.notification_center {
+            let mut attributes = HashMap::from([
+                ("filename".to_string(), filename.to_string()),
+                ("signature".to_string(), signature.clone()),
+                ("scanner".to_string(), scanner.name().to_string()),
+            ]);
+            if let Some(path) = &quarantined {
+                attributes.insert("quarantinePath".to_string(), path.display().to_string());
+            }
+            let reason = Reason {
+                metadata: Metadata::new(Uuid::new_v4().to_string()),
+                spec: ReasonSpec {
+                    reason_type: REASON_ATTACHMENT_INFECTED.to_string(),
+                    subject: ReasonSubject {
+                        api_version: "storage.halo.run/v1alpha1".to_string(),
+                        kind: "Attachment".to_string(),
+                        name: file_id.to_string(),
+                        title: filename.to_string(),
+                        url: None,
+                    },
+                    author: owner.to_string(),
+                    attributes: Some(attributes),
+                },
+            };
+            if let Err(e) = center.notify(reason).await {
+                tracing::warn!("Failed to notify infected upload {}: {}", filename, e);
+            }
+        }
+        anyhow::bail!("{}: {} ({})", INFECTED_FILE_ERROR, filename, signature)
+    }
+    
+    /// 获取存储策略对应的存储
+    async fn storage_for(&self, policy_name: Option<&str>) -> Result<Arc<dyn AttachmentStorage>> {
+        match &self.storage_resolver {
+            Some(resolver) => resolver.resolve(policy_name).await,
+            None => Ok(self.storage.clone()),
+        }
+    }
+    
+    /// 存储策略是否配置了去除图片EXIF中的可识别信息
+    async fn strip_exif_enabled(&self, policy_name: Option<&str>) -> Result<bool> {
+        let Some(resolver) = &self.storage_resolver else {
+            return Ok(false);
+        };
+        Ok(resolver.policy_settings(policy_name).await?
+            .and_then(|settings| settings.get(STRIP_EXIF_SETTING)?.as_bool())
+            .unwrap_or(false))
+    }
+    
+    /// 存储策略配置的上传限制，以及分组是否只允许上传图片
+    async fn upload_restrictions(&self, policy_name: Option<&str>, group_name: Option<&str>) -> Result<UploadRestrictions> {
+        let mut restrictions = match &self.storage_resolver {
+            Some(resolver) => resolver.policy_settings(policy_name).await?
+                .map(|settings| UploadRestrictions::from_settings(&settings))
+                .unwrap_or_default(),
+            None => UploadRestrictions::default(),
+        };
+        if let Some(group_name) = group_name.filter(|g| !g.is_empty()) {
+            let group: Option<Group> = self.extension_client.fetch(group_name).await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch group {}: {}", group_name, e))?;
+            restrictions.image_only = group.is_some_and(|g| g.spec.image_only);
+        }
+        Ok(restrictions)
+    }
+    
+    /// 附件保存在远程存储中的对象Key
+    fn object_key(attachment: &Attachment) -> Option<&str> {
+        attachment.metadata.annotations.as_ref()?
+            .get(OBJECT_KEY_ANNO)
+            .map(String::as_str)
+    }
+    
+    fn content_hash_of(attachment: &Attachment) -> Option<&str> {
+        attachment.metadata.annotations.as_ref()?
+            .get(CONTENT_HASH_ANNO)
+            .map(String::as_str)
+    }
+    
+    /// 由本站代理访问附件内容的地址
+    fn content_url(&self, name: &str) -> String {
+        format!("{}/api/v1alpha1/attachments/{}/content", self.base_url.trim_end_matches('/'), name)
+    }
+    
+    /// 复用内容相同的已有附件保存的文件和缩略图，返回permalink和缩略图
+    fn reuse_file(&self, existing: &Attachment, name: &str, annotations: &mut HashMap<String, String>) -> (String, HashMap<String, String>) {
+        if let Some(object_key) = Self::object_key(existing) {
+            annotations.insert(OBJECT_KEY_ANNO.to_string(), object_key.to_string());
+        }
+        // 视频信息只在首次保存时解析
+        for key in [IMAGE_WIDTH_ANNO, IMAGE_HEIGHT_ANNO, VIDEO_DURATION_ANNO] {
+            if let Some(value) = existing.metadata.annotations.as_ref().and_then(|a| a.get(key)) {
+                annotations.entry(key.to_string()).or_insert_with(|| value.clone());
+            }
+        }
+        let status = existing.status.clone();
+        let thumbnails = status.as_ref().and_then(|s| s.thumbnails.clone()).unwrap_or_default();
+        let permalink = status.and_then(|s| s.permalink).unwrap_or_default();
+        // 代理地址包含附件名称，需要指向新附件
+        let permalink = if permalink == self.content_url(&existing.metadata.name) {
+            self.content_url(name)
+        } else {
+            permalink
+        };
+        (permalink, thumbnails)
+    }
+    
+    /// 将文件保存到存储中，本地存储同时生成缩略图，返回permalink和缩略图
+    async fn save_file(
+        &self,
+        storage: &dyn AttachmentStorage,
+        file: &SpooledFile,
+        stored_filename: &str,
+        media_type: Option<&str>,
+        name: &str,
+        annotations: &mut HashMap<String, String>,
+    ) -> Result<(String, HashMap<String, String>)> {
+        let mut thumbnails = HashMap::new();
+        if !storage.is_local() {
+            // 远程存储使用相对路径作为对象Key，不生成本地缩略图；存储没有公开链接时由本站代理访问
+            storage.save(file.open().await?, file.size(), Path::new(stored_filename)).await?;
+            annotations.insert(OBJECT_KEY_ANNO.to_string(), stored_filename.to_string());
+            let permalink = storage.permalink(Path::new(stored_filename))
+                .unwrap_or_else(|| self.content_url(name));
+            return Ok((permalink, thumbnails));
+        }
+        
+        // 保存文件到本地存储位置
+        let stored_path = self.upload_path.join(stored_filename);
+        storage.save(file.open().await?, file.size(), &stored_path).await?;
+        
+        if let Some(mime_type) = media_type {
+            thumbnails = self.generate_file_thumbnails(&stored_path, mime_type, name, annotations).await;
+        }
+        
+        let permalink = format!("{}/upload/{}", self.base_url.trim_end_matches('/'), stored_filename);
+        Ok((permalink, thumbnails))
+    }
+    
+    /// 为本地文件生成缩略图（图片直接缩放，视频截取封面帧后缩放），并记录生成时的缩略图配置
+    async fn generate_file_thumbnails(
+        &self,
+        stored_path: &Path,
+        media_type: &str,
+        name: &str,
+        annotations: &mut HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let thumbnails = if self.thumbnail_service.is_image(media_type) {
+            self.generate_thumbnails(stored_path)
+        } else if let (true, Some(video_processor)) = (media_type.starts_with("video/"), &self.video_processor) {
+            let (video_annotations, video_thumbnails) =
+                self.process_video(video_processor.as_ref(), stored_path, name).await;
+            annotations.extend(video_annotations);
+            video_thumbnails
+        } else {
+            return HashMap::new();
+        };
+        annotations.insert(THUMBNAIL_SIGNATURE_ANNO.to_string(), self.thumbnail_service.signature());
+        thumbnails
+    }
+    
+    /// 本地缩略图访问地址对应的文件路径
+    fn thumbnail_path_of(&self, thumbnail_url: &str) -> Option<PathBuf> {
+        let prefix = format!("{}/upload/thumbnails/", self.base_url.trim_end_matches('/'));
+        thumbnail_url.strip_prefix(&prefix)
+            .map(|relative_path| self.upload_path.join("thumbnails").join(relative_path))
+    }
+    
+    /// 删除本地存储中的缩略图文件
+    async fn delete_thumbnail_files(&self, thumbnails: &HashMap<String, String>) -> Result<()> {
+        for thumbnail_url in thumbnails.values() {
+            if let Some(thumb_path) = self.thumbnail_path_of(thumbnail_url) {
+                if self.storage.exists(&thumb_path).await {
+                    self.storage.delete(&thumb_path).await
+                        .map_err(|e| anyhow::anyhow!("Failed to delete thumbnail: {}", e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// 附件在存储中的文件名：远程存储为对象Key，本地存储为upload目录下的相对路径
+    fn stored_filename_of(&self, attachment: &Attachment) -> Option<String> {
+        if let Some(object_key) = Self::object_key(attachment) {
+            return Some(object_key.to_string());
+        }
+        let prefix = format!("{}/upload/", self.base_url.trim_end_matches('/'));
+        attachment.status.as_ref()?
+            .permalink.as_deref()?
+            .strip_prefix(&prefix)
+            .map(str::to_string)
+    }
+    
+    /// 附件内容所在的存储和路径，本地附件从permalink中提取文件路径
+    async fn content_location(&self, attachment: &Attachment) -> Result<Option<(Arc<dyn AttachmentStorage>, PathBuf)>> {
+        if let Some(object_key) = Self::object_key(attachment) {
+            let storage = self.storage_for(attachment.spec.policy_name.as_deref()).await?;
+            return Ok(Some((storage, PathBuf::from(object_key))));
+        }
+        let prefix = format!("{}/upload/", self.base_url.trim_end_matches('/'));
+        let relative_path = attachment.status.as_ref()
+            .and_then(|s| s.permalink.as_deref())
+            .and_then(|p| p.strip_prefix(&prefix));
+        Ok(relative_path.map(|relative_path| (self.storage.clone(), self.upload_path.join(relative_path))))
+    }
+    
+    /// 删除附件保存的文件和缩略图，文件仍被内容相同的其他附件引用时跳过
+    async fn delete_file(&self, attachment: &Attachment) -> Result<()> {
+        let name = attachment.metadata.name.as_str();
+        let referenced = match Self::content_hash_of(attachment) {
+            Some(hash) => !self.find_by_content_hash(hash, attachment.spec.policy_name.as_deref(), Some(name)).await?.is_empty(),
+            None => false,
+        };
+        
+        // 远程存储中的附件按对象Key删除
+        if referenced {
+            tracing::debug!("File of attachment {} is still referenced, skip deleting it", name);
+        } else if let Some(object_key) = Self::object_key(attachment) {
+            let storage = self.storage_for(attachment.spec.policy_name.as_deref()).await?;
+            storage.delete(Path::new(object_key)).await
+                .map_err(|e| anyhow::anyhow!("Failed to delete file: {}", e))?;
+        } else if let Some(ref status) = attachment.status {
+            // 从permalink中提取本地文件路径
+            if let Some(ref permalink) = status.permalink {
+                // 解析permalink获取文件路径
+                if let Some(relative_path) = permalink.strip_prefix(&format!("{}/upload/", self.base_url.trim_end_matches('/'))) {
+                    let file_path = self.upload_path.join(relative_path);
+                    
+                    // 删除文件
+                    if self.storage.exists(&file_path).await {
+                        self.storage.delete(&file_path).await
+                            .map_err(|e| anyhow::anyhow!("Failed to delete file: {}", e))?;
+                    }
+                    
+                    // 删除缩略图
+                    if let Some(ref thumbnails) = status.thumbnails {
+                        self.delete_thumbnail_files(thumbnails).await?;
+                    }
+                }
+            }
         }
+        Ok(())
+    }
+    
+    /// 查找同一存储策略下内容相同的其他附件（即引用同一份文件的附件）
+    async fn find_by_content_hash(&self, hash: &str, policy_name: Option<&str>, exclude: Option<&str>) -> Result<Vec<Attachment>> {
+        let attachments = scan_all_pages(ListOptions::default(), |options| self.list(options)).await?;
+        Ok(attachments.into_iter()
+            .filter(|a| Self::content_hash_of(a) == Some(hash))
+            .filter(|a| a.spec.policy_name.as_deref() == policy_name)
+            .filter(|a| exclude != Some(a.metadata.name.as_str()))
+            .collect())
     }
 }
 
@@ -79,7 +562,7 @@ impl DefaultAttachmentService {
 impl AttachmentService for DefaultAttachmentService {
     async fn upload(
         &self,
-        file_content: Vec<u8>,
+        file_content: ByteStream,
         filename: String,
         media_type: Option<String>,
         owner_name: Option<String>,
@@ -94,47 +577,82 @@ impl AttachmentService for DefaultAttachmentService {
             .and_then(|e| e.to_str())
             .unwrap_or("bin");
         let stored_filename = format!("{}.{}", file_id, file_ext);
-        let stored_path = self.upload_path.join(&stored_filename);
+        let mut file = self.spool(file_content).await?;
         
-        // 2. 保存文件到存储位置
-        self.storage.save(&file_content, &stored_path)?;
-        
-        // 3. 生成permalink
-        let permalink = format!("{}/upload/{}", self.base_url.trim_end_matches('/'), stored_filename);
+        // 按文件内容识别媒体类型，不信任客户端提供的类型，并校验存储策略和分组的上传限制
+        let detected_media_type = sniff_media_type(&file.head(SNIFF_LENGTH).await?, &filename);
+        if media_type.as_deref().is_some_and(|t| t != detected_media_type) {
+            tracing::debug!("Media type of {} is {} rather than declared {:?}", filename, detected_media_type, media_type);
+        }
+        let media_type = Some(detected_media_type);
+        self.upload_restrictions(policy_name.as_deref(), group_name.as_deref()).await?
+            .validate(&filename, file.size(), media_type.as_deref().unwrap_or_default())?;
+        if let Some(quota_service) = &self.quota_service {
+            quota_service.check(owner_name.as_deref(), group_name.as_deref(), file.size()).await?;
+        }
+        self.scan_upload(&file_id.to_string(), &filename, owner_name.as_deref(), &file).await?;
+        let storage = self.storage_for(policy_name.as_deref()).await?;
         
-        // 4. 生成缩略图（如果是图片）
-        let mut thumbnails = HashMap::new();
-        if let Some(ref mime_type) = media_type {
-            if self.thumbnail_service.is_image(mime_type) {
-                // 生成所有尺寸的缩略图
-                for size in [ThumbnailSize::Xl, ThumbnailSize::L, ThumbnailSize::M, ThumbnailSize::S] {
-                    if let Ok(thumbnail_path) = self.thumbnail_service.generate_thumbnail(&stored_path, size) {
-                        // 生成缩略图URL
-                        let thumbnail_url = format!("{}/upload/thumbnails/{}", 
-                            self.base_url.trim_end_matches('/'),
-                            thumbnail_path.file_name().unwrap().to_string_lossy());
-                        thumbnails.insert(size.as_str().to_string(), thumbnail_url);
-                    }
+        // 解析图片尺寸和EXIF信息，策略要求时去除位置、设备等可识别信息后再保存
+        let mut image_annotations = HashMap::new();
+        let mut placeholder = None;
+        if media_type.as_deref().is_some_and(|t| t.starts_with("image/")) {
+            let strip_exif = self.strip_exif_enabled(policy_name.as_deref()).await?;
+            let image_content = file.read().await?;
+            if let Some(image_metadata) = extract_image_metadata(&image_content) {
+                image_annotations = image_metadata.to_annotations(!strip_exif);
+            }
+            if strip_exif {
+                if let Some(stripped) = strip_identifying_exif(&image_content)
+                    .map_err(|e| anyhow::anyhow!("Failed to strip EXIF from {}: {}", filename, e))? {
+                    file.replace(&stripped).await?;
                 }
             }
+            
+            // 计算BlurHash和主色调，无法解码的图片（如SVG）不生成占位信息
+            let path = file.path().to_path_buf();
+            placeholder = match tokio::task::spawn_blocking(move || compute_placeholder(&path)).await? {
+                Ok(placeholder) => Some(placeholder),
+                Err(e) => {
+                    tracing::debug!("Skipped placeholder of {}: {}", filename, e);
+                    None
+                }
+            };
         }
         
-        // 5. 创建Attachment Extension
-        let metadata = Metadata::new(file_id.to_string());
+        let mut metadata = Metadata::new(file_id.to_string());
+        let hash = file.hash().to_string();
+        let mut annotations = HashMap::from([(CONTENT_HASH_ANNO.to_string(), hash.clone())]);
+        annotations.extend(image_annotations);
+        
+        // 2. 同一策略下已有相同内容的附件时复用已保存的文件和缩略图，否则保存文件
+        let duplicate = self.find_by_content_hash(&hash, policy_name.as_deref(), None).await?
+            .into_iter()
+            .find(|a| a.status.as_ref().is_some_and(|s| s.permalink.is_some()));
         
+        let (permalink, thumbnails) = match duplicate {
+            Some(existing) => self.reuse_file(&existing, &file_id.to_string(), &mut annotations),
+            None => self.save_file(storage.as_ref(), &file, &stored_filename, media_type.as_deref(), &file_id.to_string(), &mut annotations).await?,
+        };
+        
+        // 3. 创建Attachment Extension
+        metadata.annotations = Some(annotations);
         let spec = AttachmentSpec {
             display_name: Some(filename.clone()),
             group_name,
             policy_name,
             owner_name,
             media_type,
-            size: Some(file_content.len() as u64),
+            size: Some(file.size()),
             tags: None,
         };
         
         let status = AttachmentStatus {
             permalink: Some(permalink),
             thumbnails: if thumbnails.is_empty() { None } else { Some(thumbnails) },
+            used_by: None,
+            blurhash: placeholder.as_ref().map(|p| p.blurhash.clone()),
+            dominant_color: placeholder.map(|p| p.dominant_color),
         };
         
         let attachment = Attachment {
@@ -143,52 +661,54 @@ impl AttachmentService for DefaultAttachmentService {
             status: Some(status),
         };
         
-        // 6. 保存Attachment Extension
+        // 4. 保存Attachment Extension
         self.extension_client.create(attachment.clone()).await
             .map_err(|e| anyhow::anyhow!("Failed to create attachment extension: {}", e))?;
         
+        // 5. 记录存储用量，失败不影响上传结果
+        if let Some(quota_service) = &self.quota_service {
+            let spec = &attachment.spec;
+            let size = spec.size.unwrap_or(0) as i64;
+            if let Err(e) = async {
+                quota_service.record(spec.owner_name.as_deref(), spec.group_name.as_deref(), size, 1).await?;
+                quota_service.record_policy(spec.policy_name.as_deref(), size, 1).await
+            }.await {
+                tracing::warn!("Failed to record storage usage of attachment {}: {}", attachment.metadata.name, e);
+            }
+        }
+        
         Ok(attachment)
     }
     
+    async fn spool(&self, content: ByteStream) -> Result<SpooledFile> {
+        SpooledFile::write(&self.spool_dir, content, self.max_file_size).await
+    }
+    
     async fn delete(&self, name: &str) -> Result<()> {
         // 1. 获取Attachment以获取文件路径
         let attachment = self.extension_client.fetch::<Attachment>(name).await
             .map_err(|e| anyhow::anyhow!("Failed to fetch attachment: {}", e))?
             .ok_or_else(|| anyhow::anyhow!("Attachment not found: {}", name))?;
         
-        // 2. 从permalink中提取文件路径
-        if let Some(ref status) = attachment.status {
-            if let Some(ref permalink) = status.permalink {
-                // 解析permalink获取文件路径
-                if let Some(relative_path) = permalink.strip_prefix(&format!("{}/upload/", self.base_url.trim_end_matches('/'))) {
-                    let file_path = self.upload_path.join(relative_path);
-                    
-                    // 删除文件
-                    if self.storage.exists(&file_path) {
-                        self.storage.delete(&file_path)
-                            .map_err(|e| anyhow::anyhow!("Failed to delete file: {}", e))?;
-                    }
-                    
-                    // 删除缩略图
-                    if let Some(ref thumbnails) = status.thumbnails {
-                        for thumbnail_url in thumbnails.values() {
-                            if let Some(thumb_relative_path) = thumbnail_url.strip_prefix(&format!("{}/upload/thumbnails/", self.base_url.trim_end_matches('/'))) {
-                                let thumb_path = self.upload_path.join("thumbnails").join(thumb_relative_path);
-                                if self.storage.exists(&thumb_path) {
-                                    self.storage.delete(&thumb_path)
-                                        .map_err(|e| anyhow::anyhow!("Failed to delete thumbnail: {}", e))?;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        // 2. 删除文件和缩略图，文件仍被内容相同的其他附件引用时只删除附件本身
+        self.delete_file(&attachment).await?;
         
         // 3. 删除Attachment Extension
         self.extension_client.delete::<Attachment>(name).await
             .map_err(|e| anyhow::anyhow!("Failed to delete attachment extension: {}", e))?;
         
+        // 4. 扣减存储用量
+        if let Some(quota_service) = &self.quota_service {
+            let spec = &attachment.spec;
+            let size = spec.size.unwrap_or(0) as i64;
+            if let Err(e) = async {
+                quota_service.record(spec.owner_name.as_deref(), spec.group_name.as_deref(), -size, -1).await?;
+                quota_service.record_policy(spec.policy_name.as_deref(), -size, -1).await
+            }.await {
+                tracing::warn!("Failed to record storage usage of attachment {}: {}", name, e);
+            }
+        }
+        
         Ok(())
     }
     
@@ -207,5 +727,247 @@ impl AttachmentService for DefaultAttachmentService {
         self.extension_client.update(attachment).await
             .map_err(|e| anyhow::anyhow!("Failed to update attachment: {}", e))
     }
+    
+    async fn signed_url(&self, name: &str, expires_in: Duration) -> Result<Option<String>> {
+        let attachment = self.get(name).await?
+            .ok_or_else(|| anyhow::anyhow!("Attachment not found: {}", name))?;
+        let Some(object_key) = Self::object_key(&attachment) else {
+            return Ok(None);
+        };
+        let storage = self.storage_for(attachment.spec.policy_name.as_deref()).await?;
+        storage.signed_url(Path::new(object_key), expires_in).await
+    }
+    
+    async fn read_content(&self, name: &str) -> Result<Option<(Attachment, ByteStream)>> {
+        let Some(attachment) = self.get(name).await? else {
+            return Ok(None);
+        };
+        let Some((storage, path)) = self.content_location(&attachment).await? else {
+            return Ok(None);
+        };
+        let stream = storage.read_stream(&path).await?;
+        Ok(Some((attachment, stream)))
+    }
+    
+    async fn read_content_range(&self, name: &str, start: u64, len: u64) -> Result<Option<(Attachment, ByteStream)>> {
+        let Some(attachment) = self.get(name).await? else {
+            return Ok(None);
+        };
+        let Some((storage, path)) = self.content_location(&attachment).await? else {
+            return Ok(None);
+        };
+        let stream = storage.read_range(&path, start, len).await?;
+        Ok(Some((attachment, stream)))
+    }
+    
+    async fn migrate(&self, name: &str, target_policy: Option<String>, target_group: Option<String>) -> Result<Option<MigratedAttachment>> {
+        let Some(previous) = self.get(name).await? else {
+            return Ok(None);
+        };
+        let target_policy = target_policy.filter(|p| !p.is_empty());
+        let target_group = target_group.filter(|g| !g.is_empty());
+        let source_policy = previous.spec.policy_name.clone().filter(|p| !p.is_empty());
+        let source_group = previous.spec.group_name.clone().filter(|g| !g.is_empty());
+        let size = previous.spec.size.unwrap_or(0);
+        let group_changed = source_group != target_group;
+        if let (true, Some(quota_service)) = (group_changed, &self.quota_service) {
+            quota_service.check(None, target_group.as_deref(), size).await?;
+        }
+        if group_changed || source_policy != target_policy {
+            let filename = previous.spec.display_name.as_deref().unwrap_or(name);
+            let media_type = previous.spec.media_type.as_deref().unwrap_or_default();
+            self.upload_restrictions(target_policy.as_deref(), target_group.as_deref()).await?
+                .validate(filename, size, media_type)?;
+        }
+        
+        let mut attachment = previous.clone();
+        attachment.spec.group_name = target_group.clone();
+        attachment.spec.policy_name = target_policy.clone();
+        let mut file_moved = false;
+        if source_policy != target_policy {
+            let source = self.storage_for(source_policy.as_deref()).await?;
+            let target = self.storage_for(target_policy.as_deref()).await?;
+            // 两个策略都使用本地存储时文件位置不变，只修改附件的策略
+            if !(source.is_local() && target.is_local()) {
+                let Some((_, stream)) = self.read_content(name).await? else {
+                    anyhow::bail!("File of attachment {} not found", name);
+                };
+                let file = SpooledFile::write(&self.spool_dir, stream, None).await?;
+                let hash = file.hash().to_string();
+                let stored_filename = self.stored_filename_of(&previous)
+                    .unwrap_or_else(|| format!("{}.bin", name));
+                
+                let mut annotations = previous.metadata.annotations.clone().unwrap_or_default();
+                annotations.remove(OBJECT_KEY_ANNO);
+                annotations.insert(CONTENT_HASH_ANNO.to_string(), hash.clone());
+                let duplicate = self.find_by_content_hash(&hash, target_policy.as_deref(), Some(name)).await?
+                    .into_iter()
+                    .find(|a| a.status.as_ref().is_some_and(|s| s.permalink.is_some()));
+                let (permalink, thumbnails) = match duplicate {
+                    Some(existing) => self.reuse_file(&existing, name, &mut annotations),
+                    None => self.save_file(target.as_ref(), &file, &stored_filename, previous.spec.media_type.as_deref(), name, &mut annotations).await?,
+                };
+                attachment.metadata.annotations = Some(annotations);
+                attachment.status = Some(AttachmentStatus {
+                    permalink: Some(permalink),
+                    thumbnails: if thumbnails.is_empty() { None } else { Some(thumbnails) },
+                    used_by: previous.status.as_ref().and_then(|s| s.used_by.clone()),
+                    blurhash: previous.status.as_ref().and_then(|s| s.blurhash.clone()),
+                    dominant_color: previous.status.as_ref().and_then(|s| s.dominant_color.clone()),
+                });
+                file_moved = true;
+            }
+        }
+        
+        let current = match self.update(attachment.clone()).await {
+            Ok(current) => current,
+            Err(e) => {
+                // 附件更新失败时删除已复制到目标存储的文件
+                if file_moved {
+                    if let Err(e) = self.delete_file(&attachment).await {
+                        tracing::warn!("Failed to clean up copied file of attachment {}: {}", name, e);
+                    }
+                }
+                return Err(e);
+            }
+        };
+        
+        // 分组或存储策略变化时转移对应的存储用量
+        if let Some(quota_service) = &self.quota_service {
+            let size = size as i64;
+            if let Err(e) = async {
+                if group_changed {
+                    quota_service.record(None, source_group.as_deref(), -size, -1).await?;
+                    quota_service.record(None, target_group.as_deref(), size, 1).await?;
+                }
+                if source_policy != target_policy {
+                    quota_service.record_policy(source_policy.as_deref(), -size, -1).await?;
+                    quota_service.record_policy(target_policy.as_deref(), size, 1).await?;
+                }
+                Ok::<_, anyhow::Error>(())
+            }.await {
+                tracing::warn!("Failed to record storage usage of attachment {}: {}", name, e);
+            }
+        }
+        Ok(Some(MigratedAttachment { previous, current, file_moved }))
+    }
+    
+    async fn delete_previous_file(&self, migrated: &MigratedAttachment) -> Result<()> {
+        if !migrated.file_moved {
+            return Ok(());
+        }
+        self.delete_file(&migrated.previous).await
+    }
+    
+    async fn regenerate_thumbnails(&self, name: &str, force: bool) -> Result<Option<ThumbnailRegeneration>> {
+        let Some(mut attachment) = self.get(name).await? else {
+            return Ok(None);
+        };
+        
+        // 1. 只有本地存储的图片和视频（配置了视频处理器）有缩略图
+        let storage = self.storage_for(attachment.spec.policy_name.as_deref()).await?;
+        let media_type = attachment.spec.media_type.clone().unwrap_or_default();
+        let supported = self.thumbnail_service.is_image(&media_type)
+            || (media_type.starts_with("video/") && self.video_processor.is_some());
+        let stored_filename = self.stored_filename_of(&attachment);
+        let (true, true, Some(stored_filename)) = (supported, storage.is_local() && Self::object_key(&attachment).is_none(), stored_filename) else {
+            return Ok(Some(ThumbnailRegeneration::Unsupported));
+        };
+        let stored_path = self.upload_path.join(&stored_filename);
+        if !self.storage.exists(&stored_path).await {
+            anyhow::bail!("File of attachment {} not found: {}", name, stored_path.display());
+        }
+        
+        // 2. 缩略图配置未变化且文件齐全时跳过
+        let previous = attachment.status.as_ref().and_then(|s| s.thumbnails.clone()).unwrap_or_default();
+        let mut annotations = attachment.metadata.annotations.clone().unwrap_or_default();
+        let current = !previous.is_empty()
+            && annotations.get(THUMBNAIL_SIGNATURE_ANNO) == Some(&self.thumbnail_service.signature())
+            && previous.values().all(|url| self.thumbnail_path_of(url).is_some_and(|path| path.exists()));
+        if current && !force {
+            return Ok(Some(ThumbnailRegeneration::Skipped));
+        }
+        
+        // 3. 删除旧的缩略图后重新生成，已存在的缩略图文件不会被覆盖
+        self.delete_thumbnail_files(&previous).await?;
+        let thumbnails = self.generate_file_thumbnails(&stored_path, &media_type, name, &mut annotations).await;
+        attachment.metadata.annotations = Some(annotations);
+        if let Some(status) = attachment.status.as_mut() {
+            status.thumbnails = if thumbnails.is_empty() { None } else { Some(thumbnails) };
+        }
+        self.update(attachment).await?;
+        Ok(Some(ThumbnailRegeneration::Regenerated))
+    }
+    
+    async fn is_public(&self, attachment: &Attachment) -> Result<bool> {
+        let Some(resolver) = &self.storage_resolver else {
+            return Ok(false);
+        };
+        let settings = resolver.policy_settings(attachment.spec.policy_name.as_deref()).await?;
+        Ok(public_access_enabled(settings.as_ref()))
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    fn attachment(name: &str, hash: &str, permalink: &str) -> Attachment {
+        let mut metadata = Metadata::new(name);
+        metadata.annotations = Some(HashMap::from([(CONTENT_HASH_ANNO.to_string(), hash.to_string())]));
+        Attachment {
+            metadata,
+            spec: AttachmentSpec {
+                display_name: None,
+                group_name: None,
+                policy_name: None,
+                owner_name: None,
+                media_type: None,
+                size: None,
+                tags: None,
+            },
+            status: Some(AttachmentStatus {
+                permalink: Some(permalink.to_string()),
+                thumbnails: None,
+                used_by: None,
+                blurhash: None,
+                dominant_color: None,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_file_keeps_file_referenced_beyond_first_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let upload_path = dir.path().join("upload");
+        std::fs::create_dir_all(&upload_path).unwrap();
+        std::fs::write(upload_path.join("shared.png"), b"png").unwrap();
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        let service = crate::testing::attachment_service(client.clone(), dir.path());
+        // 引用同一文件的另一个附件排在第一页之后
+        for i in 0..flow_api::extension::SCAN_PAGE_SIZE {
+            client.create(attachment(&format!("other-{}", i), "other", "http://localhost/upload/other.png")).await.unwrap();
+        }
+        let deleted = attachment("deleted", "shared", "http://localhost/upload/shared.png");
+        client.create(deleted.clone()).await.unwrap();
+        client.create(attachment("duplicate", "shared", "http://localhost/upload/shared.png")).await.unwrap();
+
+        service.delete_file(&deleted).await.unwrap();
+
+        assert!(upload_path.join("shared.png").exists());
+    }
+
+    #[test]
+    fn test_public_access_enabled() {
+        assert!(public_access_enabled(Some(&serde_json::json!({"publicAccess": true}))));
+        assert!(!public_access_enabled(Some(&serde_json::json!({"publicAccess": false}))));
+        assert!(!public_access_enabled(Some(&serde_json::json!({"stripExif": true}))));
+        assert!(!public_access_enabled(None));
+    }
+}
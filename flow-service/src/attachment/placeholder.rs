@@ -0,0 +1,101 @@
+use anyhow::Result;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use image::imageops::FilterType;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 计算占位图前先缩小图片的最大边长，BlurHash只保留低频信息，缩小后结果基本不变
+const SAMPLE_SIZE: u32 = 64;
+
+/// BlurHash长边方向的分量数，短边按比例减少
+const MAX_COMPONENTS: u32 = 4;
+
+/// 透明度低于该值的像素不参与主色调统计
+const OPAQUE_ALPHA: u8 = 128;
+
+/// 图片加载前显示的占位信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImagePlaceholder {
+    pub blurhash: String,
+    /// 主色调，格式为 #rrggbb
+    pub dominant_color: String,
+}
+
+/// 读取图片文件并计算占位信息，按EXIF方向校正后计算
+pub fn compute_placeholder(path: &Path) -> Result<ImagePlaceholder> {
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    placeholder_for(&image)
+}
+
+/// 计算图片的BlurHash和主色调
+pub fn placeholder_for(image: &DynamicImage) -> Result<ImagePlaceholder> {
+    let sample = image.resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle).to_rgba8();
+    let (width, height) = sample.dimensions();
+    let (components_x, components_y) = if width >= height {
+        (MAX_COMPONENTS, (MAX_COMPONENTS * height / width).clamp(1, MAX_COMPONENTS))
+    } else {
+        ((MAX_COMPONENTS * width / height).clamp(1, MAX_COMPONENTS), MAX_COMPONENTS)
+    };
+    let blurhash = blurhash::encode(components_x, components_y, width, height, sample.as_raw())
+        .map_err(|e| anyhow::anyhow!("Failed to encode blurhash: {}", e))?;
+    Ok(ImagePlaceholder {
+        blurhash,
+        dominant_color: dominant_color(sample.as_raw()),
+    })
+}
+
+/// 将颜色量化到每通道16级后取像素最多的一组，返回该组像素的平均颜色
+///
+/// 完全透明的图片返回白色
+fn dominant_color(rgba: &[u8]) -> String {
+    let mut buckets: HashMap<u16, (u32, [u32; 3])> = HashMap::new();
+    for pixel in rgba.chunks_exact(4).filter(|p| p[3] >= OPAQUE_ALPHA) {
+        let key = (pixel[0] as u16 >> 4) << 8 | (pixel[1] as u16 >> 4) << 4 | pixel[2] as u16 >> 4;
+        let (count, sum) = buckets.entry(key).or_default();
+        *count += 1;
+        for channel in 0..3 {
+            sum[channel] += pixel[channel] as u32;
+        }
+    }
+    // 像素数相同时取key较小的一组，保证结果稳定
+    let color = buckets.into_iter()
+        .max_by(|(a_key, (a, _)), (b_key, (b, _))| a.cmp(b).then(b_key.cmp(a_key)))
+        .map(|(_, (count, sum))| sum.map(|s| (s / count) as u8))
+        .unwrap_or([255, 255, 255]);
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_dominant_color() {
+        let mut rgba = Vec::new();
+        for _ in 0..3 {
+            rgba.extend_from_slice(&[200, 16, 32, 255]);
+        }
+        rgba.extend_from_slice(&[0, 0, 255, 255]);
+        rgba.extend_from_slice(&[0, 255, 0, 0]);
+        assert_eq!(dominant_color(&rgba), "#c81020");
+        assert_eq!(dominant_color(&[0, 0, 0, 0]), "#ffffff");
+    }
+
+    #[test]
+    fn test_placeholder_for_image() {
+        let image = RgbaImage::from_fn(120, 60, |x, _| {
+            if x < 90 { Rgba([10, 120, 200, 255]) } else { Rgba([250, 250, 250, 255]) }
+        });
+        let placeholder = placeholder_for(&DynamicImage::ImageRgba8(image)).unwrap();
+        assert_eq!(placeholder.dominant_color, "#0a78c8");
+        // 4x2个分量的BlurHash长度为 4 + 2 * 8 = 20
+        assert_eq!(placeholder.blurhash.len(), 20);
+        assert!(blurhash::decode(&placeholder.blurhash, 8, 4, 1.0).is_ok());
+    }
+}
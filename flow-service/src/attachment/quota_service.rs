@@ -0,0 +1,456 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use flow_api::extension::{ExtensionClient, ListOptions};
+use flow_api::extension::Metadata;
+use flow_domain::attachment::{Attachment, Group, GroupStatus, Policy, PolicyStatus, StorageQuota, StorageUsage};
+use flow_domain::security::User;
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Result;
+
+/// 超出存储配额
+pub const QUOTA_EXCEEDED_ERROR: &str = "Storage quota exceeded";
+
+/// 记录默认本地存储（未指定存储策略）用量的ConfigMap名称
+pub const DEFAULT_POLICY_USAGE_CONFIG_MAP: &str = "attachment-default-policy-usage";
+
+const USED_BYTES_KEY: &str = "usedBytes";
+const FILE_COUNT_KEY: &str = "fileCount";
+
+/// 按策略、分组或用户统计的存储用量，name为空表示默认本地存储或未分组
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedStorageUsage {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    #[serde(flatten)]
+    pub usage: StorageUsage,
+}
+
+/// 存储用量统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStats {
+    pub total: StorageUsage,
+    pub policies: Vec<NamedStorageUsage>,
+    pub groups: Vec<NamedStorageUsage>,
+    /// 有附件的用户
+    pub owners: Vec<NamedStorageUsage>,
+}
+
+/// 用户的存储用量和配额
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStorageUsage {
+    pub usage: StorageUsage,
+    pub quota: Option<StorageQuota>,
+}
+
+/// 检查再上传added_bytes字节的文件是否超出配额，超出时返回原因
+pub fn quota_violation(quota: &StorageQuota, usage: &StorageUsage, added_bytes: u64) -> Option<String> {
+    if let Some(max_files) = quota.max_files {
+        if usage.file_count + 1 > max_files {
+            return Some(format!("at most {} files allowed, {} already stored", max_files, usage.file_count));
+        }
+    }
+    if let Some(max_bytes) = quota.max_bytes {
+        if usage.used_bytes + added_bytes > max_bytes {
+            return Some(format!("at most {} bytes allowed, {} used, {} requested", max_bytes, usage.used_bytes, added_bytes));
+        }
+    }
+    None
+}
+
+/// 累加附件的大小和数量
+fn total_usage<'a>(attachments: impl Iterator<Item = &'a Attachment>) -> StorageUsage {
+    attachments.fold(StorageUsage::default(), |usage, a| StorageUsage {
+        used_bytes: usage.used_bytes + a.spec.size.unwrap_or(0),
+        file_count: usage.file_count + 1,
+    })
+}
+
+/// 总用量减去各部分用量后剩余的用量（如未分组的附件）
+fn remainder(total: StorageUsage, parts: &[StorageUsage]) -> StorageUsage {
+    parts.iter().fold(total, |rest, part| StorageUsage {
+        used_bytes: rest.used_bytes.saturating_sub(part.used_bytes),
+        file_count: rest.file_count.saturating_sub(part.file_count),
+    })
+}
+
+fn usage_from_config_map(config_map: &ConfigMap) -> Option<StorageUsage> {
+    let data = config_map.data.as_ref()?;
+    Some(StorageUsage {
+        used_bytes: data.get(USED_BYTES_KEY)?.parse().ok()?,
+        file_count: data.get(FILE_COUNT_KEY)?.parse().ok()?,
+    })
+}
+
+fn apply_delta(usage: StorageUsage, delta_bytes: i64, delta_files: i64) -> StorageUsage {
+    StorageUsage {
+        used_bytes: usage.used_bytes.saturating_add_signed(delta_bytes),
+        file_count: usage.file_count.saturating_add_signed(delta_files),
+    }
+}
+
+/// 存储配额服务trait
+#[async_trait]
+pub trait QuotaService: Send + Sync {
+    /// 检查用户和分组是否还能保存size字节的文件，超出配额时返回以QUOTA_EXCEEDED_ERROR开头的错误
+    async fn check(&self, owner_name: Option<&str>, group_name: Option<&str>, size: u64) -> Result<()>;
+
+    /// 附件创建或删除后增量更新用户和分组的用量
+    async fn record(&self, owner_name: Option<&str>, group_name: Option<&str>, delta_bytes: i64, delta_files: i64) -> Result<()>;
+
+    /// 获取用户的存储用量
+    async fn user_usage(&self, username: &str) -> Result<UserStorageUsage>;
+
+    /// 附件创建、删除或迁移后增量更新存储策略的用量，policy_name为空表示默认本地存储
+    async fn record_policy(&self, policy_name: Option<&str>, delta_bytes: i64, delta_files: i64) -> Result<()>;
+
+    /// 按存储策略、分组和用户统计存储用量，使用增量维护的用量，只有尚未记录时才扫描附件
+    async fn storage_stats(&self) -> Result<StorageStats>;
+}
+
+/// 默认存储配额服务实现
+///
+/// 用户用量记录在UserStatus.attachmentUsage，分组用量记录在GroupStatus，存储策略用量记录在PolicyStatus，
+/// 默认本地存储的用量记录在DEFAULT_POLICY_USAGE_CONFIG_MAP；
+/// 尚未记录用量时扫描一次附件初始化，之后在上传和删除时增量更新
+pub struct DefaultQuotaService {
+    client: Arc<ReactiveExtensionClient>,
+}
+
+impl DefaultQuotaService {
+    pub fn new(client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { client }
+    }
+
+    async fn list_all<E: flow_api::extension::Extension + for<'de> serde::Deserialize<'de>>(&self) -> Result<Vec<E>> {
+        self.client.list_all::<E>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list extensions: {}", e))
+    }
+
+    async fn scan_usage(&self, matches: impl Fn(&Attachment) -> bool + Send) -> Result<StorageUsage> {
+        let attachments = self.list_all::<Attachment>().await?;
+        Ok(total_usage(attachments.iter().filter(|a| matches(a))))
+    }
+
+    async fn fetch_user(&self, username: &str) -> Result<Option<User>> {
+        self.client.fetch::<User>(username).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch user {}: {}", username, e))
+    }
+
+    async fn fetch_group(&self, group_name: &str) -> Result<Option<Group>> {
+        self.client.fetch::<Group>(group_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch group {}: {}", group_name, e))
+    }
+
+    /// 用户当前用量，返回值的第二项表示是否为扫描得到（尚未记录）
+    async fn usage_of_user(&self, user: &User) -> Result<(StorageUsage, bool)> {
+        if let Some(usage) = user.status.as_ref().and_then(|s| s.attachment_usage) {
+            return Ok((usage, false));
+        }
+        let username = user.metadata.name.clone();
+        let usage = self.scan_usage(move |a| a.spec.owner_name.as_deref() == Some(username.as_str())).await?;
+        Ok((usage, true))
+    }
+
+    /// 分组当前用量，返回值的第二项表示是否为扫描得到（尚未记录）
+    async fn usage_of_group(&self, group: &Group) -> Result<(StorageUsage, bool)> {
+        let status = group.status.as_ref();
+        if let (Some(total_size), Some(total_attachments)) = (status.and_then(|s| s.total_size), status.and_then(|s| s.total_attachments)) {
+            return Ok((StorageUsage { used_bytes: total_size, file_count: total_attachments }, false));
+        }
+        let group_name = group.metadata.name.clone();
+        let usage = self.scan_usage(move |a| a.spec.group_name.as_deref() == Some(group_name.as_str())).await?;
+        Ok((usage, true))
+    }
+
+    /// 存储策略当前用量，返回值的第二项表示是否为扫描得到（尚未记录）
+    async fn usage_of_policy(&self, policy: &Policy) -> Result<(StorageUsage, bool)> {
+        let status = policy.status.as_ref();
+        if let (Some(total_size), Some(total_attachments)) = (status.and_then(|s| s.total_size), status.and_then(|s| s.total_attachments)) {
+            return Ok((StorageUsage { used_bytes: total_size, file_count: total_attachments }, false));
+        }
+        let policy_name = policy.metadata.name.clone();
+        let usage = self.scan_usage(move |a| a.spec.policy_name.as_deref() == Some(policy_name.as_str())).await?;
+        Ok((usage, true))
+    }
+
+    async fn fetch_default_policy_usage(&self) -> Result<Option<ConfigMap>> {
+        self.client.fetch::<ConfigMap>(DEFAULT_POLICY_USAGE_CONFIG_MAP).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch ConfigMap {}: {}", DEFAULT_POLICY_USAGE_CONFIG_MAP, e))
+    }
+}
+
+/// 附件是否保存在默认本地存储
+fn uses_default_policy(attachment: &Attachment) -> bool {
+    attachment.spec.policy_name.as_deref().is_none_or(str::is_empty)
+}
+
+#[async_trait]
+impl QuotaService for DefaultQuotaService {
+    async fn check(&self, owner_name: Option<&str>, group_name: Option<&str>, size: u64) -> Result<()> {
+        if let Some(user) = owner_name.filter(|o| !o.is_empty()) {
+            if let Some(user) = self.fetch_user(user).await? {
+                if let Some(quota) = &user.spec.attachment_quota {
+                    let (usage, _) = self.usage_of_user(&user).await?;
+                    if let Some(reason) = quota_violation(quota, &usage, size) {
+                        anyhow::bail!("{} for user {}: {}", QUOTA_EXCEEDED_ERROR, user.metadata.name, reason);
+                    }
+                }
+            }
+        }
+        if let Some(group) = group_name.filter(|g| !g.is_empty()) {
+            if let Some(group) = self.fetch_group(group).await? {
+                if let Some(quota) = &group.spec.quota {
+                    let (usage, _) = self.usage_of_group(&group).await?;
+                    if let Some(reason) = quota_violation(quota, &usage, size) {
+                        anyhow::bail!("{} for group {}: {}", QUOTA_EXCEEDED_ERROR, group.metadata.name, reason);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn record(&self, owner_name: Option<&str>, group_name: Option<&str>, delta_bytes: i64, delta_files: i64) -> Result<()> {
+        if let Some(user) = owner_name.filter(|o| !o.is_empty()) {
+            if let Some(mut user) = self.fetch_user(user).await? {
+                // 扫描结果已包含本次变化
+                let (usage, scanned) = self.usage_of_user(&user).await?;
+                let usage = if scanned { usage } else { apply_delta(usage, delta_bytes, delta_files) };
+                user.status.get_or_insert_with(Default::default).attachment_usage = Some(usage);
+                self.client.update(user).await
+                    .map_err(|e| anyhow::anyhow!("Failed to update user usage: {}", e))?;
+            }
+        }
+        if let Some(group) = group_name.filter(|g| !g.is_empty()) {
+            if let Some(mut group) = self.fetch_group(group).await? {
+                let (usage, scanned) = self.usage_of_group(&group).await?;
+                let usage = if scanned { usage } else { apply_delta(usage, delta_bytes, delta_files) };
+                group.status = Some(GroupStatus {
+                    update_timestamp: Some(Utc::now()),
+                    total_attachments: Some(usage.file_count),
+                    total_size: Some(usage.used_bytes),
+                });
+                self.client.update(group).await
+                    .map_err(|e| anyhow::anyhow!("Failed to update group usage: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn user_usage(&self, username: &str) -> Result<UserStorageUsage> {
+        let user = self.fetch_user(username).await?
+            .ok_or_else(|| anyhow::anyhow!("User not found: {}", username))?;
+        let (usage, _) = self.usage_of_user(&user).await?;
+        Ok(UserStorageUsage {
+            usage,
+            quota: user.spec.attachment_quota,
+        })
+    }
+
+    async fn record_policy(&self, policy_name: Option<&str>, delta_bytes: i64, delta_files: i64) -> Result<()> {
+        if let Some(policy_name) = policy_name.filter(|p| !p.is_empty()) {
+            if let Some(mut policy) = self.client.fetch::<Policy>(policy_name).await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch policy {}: {}", policy_name, e))? {
+                let (usage, scanned) = self.usage_of_policy(&policy).await?;
+                let usage = if scanned { usage } else { apply_delta(usage, delta_bytes, delta_files) };
+                policy.status = Some(PolicyStatus {
+                    total_attachments: Some(usage.file_count),
+                    total_size: Some(usage.used_bytes),
+                });
+                self.client.update(policy).await
+                    .map_err(|e| anyhow::anyhow!("Failed to update policy usage: {}", e))?;
+            }
+            return Ok(());
+        }
+
+        let config_map = self.fetch_default_policy_usage().await?;
+        let usage = match config_map.as_ref().and_then(usage_from_config_map) {
+            Some(usage) => apply_delta(usage, delta_bytes, delta_files),
+            None => self.scan_usage(uses_default_policy).await?,
+        };
+        let data = HashMap::from([
+            (USED_BYTES_KEY.to_string(), usage.used_bytes.to_string()),
+            (FILE_COUNT_KEY.to_string(), usage.file_count.to_string()),
+        ]);
+        let result = match config_map {
+            Some(mut config_map) => {
+                config_map.data = Some(data);
+                self.client.update(config_map).await
+            }
+            None => self.client.create(ConfigMap {
+                metadata: Metadata::new(DEFAULT_POLICY_USAGE_CONFIG_MAP.to_string()),
+                data: Some(data),
+            }).await,
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to update default policy usage: {}", e))?;
+        Ok(())
+    }
+
+    async fn storage_stats(&self) -> Result<StorageStats> {
+        let policies = self.list_all::<Policy>().await?;
+        let groups = self.list_all::<Group>().await?;
+        let users = self.list_all::<User>().await?;
+        let default_usage = self.fetch_default_policy_usage().await?
+            .as_ref()
+            .and_then(usage_from_config_map);
+
+        // 有尚未记录用量的对象时扫描一次附件，统一计算
+        let recorded = |total_size: Option<u64>, total_attachments: Option<u64>| {
+            total_size.zip(total_attachments)
+                .map(|(used_bytes, file_count)| StorageUsage { used_bytes, file_count })
+        };
+        let policy_usage = |p: &Policy| recorded(p.status.as_ref().and_then(|s| s.total_size), p.status.as_ref().and_then(|s| s.total_attachments));
+        let group_usage = |g: &Group| recorded(g.status.as_ref().and_then(|s| s.total_size), g.status.as_ref().and_then(|s| s.total_attachments));
+        let user_usage = |u: &User| u.status.as_ref().and_then(|s| s.attachment_usage);
+        let needs_scan = default_usage.is_none()
+            || policies.iter().any(|p| policy_usage(p).is_none())
+            || groups.iter().any(|g| group_usage(g).is_none())
+            || users.iter().any(|u| user_usage(u).is_none());
+        let attachments = if needs_scan { self.list_all::<Attachment>().await? } else { Vec::new() };
+        let scanned = |matches: &dyn Fn(&Attachment) -> bool| total_usage(attachments.iter().filter(|a| matches(a)));
+
+        let mut policy_stats = vec![NamedStorageUsage {
+            name: None,
+            display_name: None,
+            usage: default_usage.unwrap_or_else(|| scanned(&uses_default_policy)),
+        }];
+        policy_stats.extend(policies.iter().map(|p| NamedStorageUsage {
+            name: Some(p.metadata.name.clone()),
+            display_name: Some(p.spec.display_name.clone()),
+            usage: policy_usage(p).unwrap_or_else(|| {
+                scanned(&|a| a.spec.policy_name.as_deref() == Some(p.metadata.name.as_str()))
+            }),
+        }));
+        let total = policy_stats.iter().fold(StorageUsage::default(), |total, p| StorageUsage {
+            used_bytes: total.used_bytes + p.usage.used_bytes,
+            file_count: total.file_count + p.usage.file_count,
+        });
+
+        let mut group_stats: Vec<NamedStorageUsage> = groups.iter().map(|g| NamedStorageUsage {
+            name: Some(g.metadata.name.clone()),
+            display_name: Some(g.spec.display_name.clone()),
+            usage: group_usage(g).unwrap_or_else(|| {
+                scanned(&|a| a.spec.group_name.as_deref() == Some(g.metadata.name.as_str()))
+            }),
+        }).collect();
+        let grouped: Vec<StorageUsage> = group_stats.iter().map(|g| g.usage).collect();
+        group_stats.insert(0, NamedStorageUsage {
+            name: None,
+            display_name: None,
+            usage: remainder(total, &grouped),
+        });
+
+        let owner_stats = users.iter()
+            .map(|u| NamedStorageUsage {
+                name: Some(u.metadata.name.clone()),
+                display_name: Some(u.spec.display_name.clone()),
+                usage: user_usage(u).unwrap_or_else(|| {
+                    scanned(&|a| a.spec.owner_name.as_deref() == Some(u.metadata.name.as_str()))
+                }),
+            })
+            .filter(|u| u.usage.file_count > 0)
+            .collect();
+
+        Ok(StorageStats {
+            total,
+            policies: policy_stats,
+            groups: group_stats,
+            owners: owner_stats,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::SCAN_PAGE_SIZE;
+    use flow_domain::attachment::AttachmentSpec;
+
+    fn attachment(name: &str, owner_name: &str, size: u64) -> Attachment {
+        Attachment {
+            metadata: Metadata::new(name),
+            spec: AttachmentSpec {
+                display_name: None,
+                group_name: None,
+                policy_name: None,
+                owner_name: Some(owner_name.to_string()),
+                media_type: None,
+                size: Some(size),
+                tags: None,
+            },
+            status: None,
+        }
+    }
+
+    async fn service_with(attachments: impl IntoIterator<Item = Attachment>) -> DefaultQuotaService {
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        for attachment in attachments {
+            client.create(attachment).await.unwrap();
+        }
+        DefaultQuotaService::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_scan_usage_counts_every_page() {
+        let others = (0..SCAN_PAGE_SIZE).map(|i| attachment(&format!("other-{}", i), "bob", 1));
+        let service = service_with(others.chain([
+            attachment("first", "alice", 100),
+            attachment("second", "alice", 200),
+        ])).await;
+
+        let usage = service.scan_usage(|a| a.spec.owner_name.as_deref() == Some("alice")).await.unwrap();
+
+        assert_eq!(usage, StorageUsage { used_bytes: 300, file_count: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_record_default_policy_scans_every_page() {
+        let attachments = (0..=SCAN_PAGE_SIZE).map(|i| attachment(&format!("attachment-{}", i), "alice", 2));
+        let service = service_with(attachments).await;
+
+        // 尚未记录默认本地存储的用量时扫描全部附件初始化，扫描结果已包含本次变化
+        service.record_policy(None, 2, 1).await.unwrap();
+
+        let config_map = service.fetch_default_policy_usage().await.unwrap().unwrap();
+        let file_count = SCAN_PAGE_SIZE as u64 + 1;
+        assert_eq!(usage_from_config_map(&config_map), Some(StorageUsage { used_bytes: file_count * 2, file_count }));
+    }
+
+    #[test]
+    fn test_quota_violation() {
+        let usage = StorageUsage { used_bytes: 900, file_count: 9 };
+        let quota = StorageQuota { max_bytes: Some(1000), max_files: Some(10) };
+        assert!(quota_violation(&quota, &usage, 100).is_none());
+        assert!(quota_violation(&quota, &usage, 101).is_some());
+
+        let full = StorageUsage { used_bytes: 0, file_count: 10 };
+        assert!(quota_violation(&quota, &full, 0).is_some());
+        assert!(quota_violation(&StorageQuota::default(), &full, u32::MAX as u64).is_none());
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let usage = StorageUsage { used_bytes: 100, file_count: 1 };
+        assert_eq!(apply_delta(usage, -200, -2), StorageUsage::default());
+        assert_eq!(apply_delta(usage, 50, 1), StorageUsage { used_bytes: 150, file_count: 2 });
+    }
+
+    #[test]
+    fn test_remainder() {
+        let total = StorageUsage { used_bytes: 1000, file_count: 10 };
+        let parts = [
+            StorageUsage { used_bytes: 300, file_count: 3 },
+            StorageUsage { used_bytes: 200, file_count: 4 },
+        ];
+        assert_eq!(remainder(total, &parts), StorageUsage { used_bytes: 500, file_count: 3 });
+        // 增量用量与实际不一致时不会溢出
+        assert_eq!(remainder(StorageUsage::default(), &parts), StorageUsage::default());
+    }
+}
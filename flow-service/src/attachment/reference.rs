@@ -0,0 +1,208 @@
+use crate::attachment::AttachmentService;
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{scan_all_pages, ExtensionClient, ListOptions};
+use flow_domain::attachment::{Attachment, AttachmentReference};
+use flow_domain::theme::Theme;
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use std::sync::Arc;
+
+/// 文章引用类型
+pub const POST_REFERENCE_KIND: &str = "Post";
+
+/// 单页引用类型
+pub const SINGLE_PAGE_REFERENCE_KIND: &str = "SinglePage";
+
+/// 主题引用类型
+pub const THEME_REFERENCE_KIND: &str = "Theme";
+
+/// 附件仍被引用、拒绝删除时的错误信息前缀
+pub const ATTACHMENT_REFERENCED_ERROR: &str = "Attachment is still referenced";
+
+/// 附件可能被引用的地址：permalink和缩略图链接，绝对地址同时匹配其路径部分
+pub fn attachment_urls(attachment: &Attachment) -> Vec<String> {
+    let Some(status) = attachment.status.as_ref() else {
+        return Vec::new();
+    };
+    let mut urls: Vec<String> = status.permalink.iter()
+        .chain(status.thumbnails.iter().flat_map(|t| t.values()))
+        .filter(|url| !url.is_empty())
+        .cloned()
+        .collect();
+    let paths: Vec<String> = urls.iter()
+        .filter_map(|url| {
+            let rest = ["http://", "https://", "//"].iter().find_map(|scheme| url.strip_prefix(scheme))?;
+            let path = &rest[rest.find('/')?..];
+            (path.len() > 1).then(|| path.to_string())
+        })
+        .collect();
+    urls.extend(paths);
+    urls.sort();
+    urls.dedup();
+    urls
+}
+
+/// 内容中是否引用了任意一个地址
+pub fn is_referenced(texts: &[String], urls: &[String]) -> bool {
+    texts.iter().any(|text| urls.iter().any(|url| text.contains(url.as_str())))
+}
+
+/// 更新附件的引用列表，返回列表是否变化
+pub fn apply_reference(used_by: &mut Vec<AttachmentReference>, reference: &AttachmentReference, referenced: bool) -> bool {
+    let position = used_by.iter().position(|r| r.is_same_subject(reference));
+    match (position, referenced) {
+        (Some(index), true) if used_by[index] != *reference => {
+            used_by[index] = reference.clone();
+            true
+        }
+        (None, true) => {
+            used_by.push(reference.clone());
+            true
+        }
+        (Some(index), false) => {
+            used_by.remove(index);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 附件引用跟踪服务trait
+#[async_trait]
+pub trait AttachmentReferenceService: Send + Sync {
+    /// 扫描引用方的已发布内容，更新所有附件的引用列表
+    ///
+    /// texts为空表示不再引用任何附件（取消发布、删除）
+    async fn update_references(&self, reference: AttachmentReference, texts: Vec<String>) -> Result<()>;
+
+    /// 获取引用附件的文章、页面和主题
+    ///
+    /// 文章和页面使用发布时记录的引用；主题设置没有发布流程，实时扫描
+    async fn used_by(&self, attachment: &Attachment) -> Result<Vec<AttachmentReference>>;
+}
+
+/// 默认附件引用跟踪服务实现
+pub struct DefaultAttachmentReferenceService {
+    client: Arc<ReactiveExtensionClient>,
+    attachment_service: Arc<dyn AttachmentService>,
+}
+
+impl DefaultAttachmentReferenceService {
+    pub fn new(client: Arc<ReactiveExtensionClient>, attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self { client, attachment_service }
+    }
+
+    /// 扫描主题设置中引用附件的主题
+    async fn theme_references(&self, urls: &[String]) -> Result<Vec<AttachmentReference>> {
+        let themes = self.client.list_all::<Theme>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list themes: {}", e))?;
+        let mut references = Vec::new();
+        for theme in themes {
+            let Some(config_map_name) = theme.spec.config_map_name.as_deref() else {
+                continue;
+            };
+            let config_map: Option<ConfigMap> = self.client.fetch(config_map_name).await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch ConfigMap {}: {}", config_map_name, e))?;
+            let values: Vec<String> = config_map
+                .and_then(|c| c.data)
+                .map(|data| data.into_values().collect())
+                .unwrap_or_default();
+            if is_referenced(&values, urls) {
+                references.push(AttachmentReference::new(
+                    THEME_REFERENCE_KIND,
+                    &theme.metadata.name,
+                    Some(theme.spec.display_name.clone()),
+                ));
+            }
+        }
+        Ok(references)
+    }
+}
+
+#[async_trait]
+impl AttachmentReferenceService for DefaultAttachmentReferenceService {
+    async fn update_references(&self, reference: AttachmentReference, texts: Vec<String>) -> Result<()> {
+        let attachments = scan_all_pages(ListOptions::default(), |options| self.attachment_service.list(options)).await?;
+        for mut attachment in attachments {
+            let referenced = !texts.is_empty() && is_referenced(&texts, &attachment_urls(&attachment));
+            let Some(status) = attachment.status.as_mut() else {
+                continue;
+            };
+            let mut used_by = status.used_by.take().unwrap_or_default();
+            let changed = apply_reference(&mut used_by, &reference, referenced);
+            if !changed {
+                continue;
+            }
+            status.used_by = (!used_by.is_empty()).then_some(used_by);
+            let name = attachment.metadata.name.clone();
+            if let Err(e) = self.attachment_service.update(attachment).await {
+                tracing::warn!("Failed to update references of attachment {}: {}", name, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn used_by(&self, attachment: &Attachment) -> Result<Vec<AttachmentReference>> {
+        let mut references = attachment.status.as_ref()
+            .and_then(|s| s.used_by.clone())
+            .unwrap_or_default();
+        let urls = attachment_urls(attachment);
+        if !urls.is_empty() {
+            references.extend(self.theme_references(&urls).await?);
+        }
+        Ok(references)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::attachment::{AttachmentSpec, AttachmentStatus};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_attachment_urls_match_relative_references() {
+        let attachment = Attachment {
+            metadata: Metadata::new("a".to_string()),
+            spec: AttachmentSpec {
+                display_name: None,
+                group_name: None,
+                policy_name: None,
+                owner_name: None,
+                media_type: None,
+                size: None,
+                tags: None,
+            },
+            status: Some(AttachmentStatus {
+                permalink: Some("http://x/upload/a.png".to_string()),
+                thumbnails: Some(HashMap::from([("M".to_string(), "/upload/thumbnails/a_M.png".to_string())])),
+                used_by: None,
+                blurhash: None,
+                dominant_color: None,
+            }),
+        };
+        let urls = attachment_urls(&attachment);
+        assert_eq!(urls, vec!["/upload/a.png", "/upload/thumbnails/a_M.png", "http://x/upload/a.png"]);
+        assert!(is_referenced(&["<img src=\"/upload/a.png\">".to_string()], &urls));
+        assert!(!is_referenced(&["<img src=\"/upload/b.png\">".to_string()], &urls));
+    }
+
+    #[test]
+    fn test_apply_reference() {
+        let mut used_by = Vec::new();
+        let post = AttachmentReference::new(POST_REFERENCE_KIND, "p", Some("Title".to_string()));
+        assert!(apply_reference(&mut used_by, &post, true));
+        assert!(!apply_reference(&mut used_by, &post, true));
+
+        let renamed = AttachmentReference::new(POST_REFERENCE_KIND, "p", Some("New title".to_string()));
+        assert!(apply_reference(&mut used_by, &renamed, true));
+        assert_eq!(used_by, vec![renamed.clone()]);
+
+        let page = AttachmentReference::new(SINGLE_PAGE_REFERENCE_KIND, "p", None);
+        assert!(!apply_reference(&mut used_by, &page, false));
+        assert!(apply_reference(&mut used_by, &renamed, false));
+        assert!(used_by.is_empty());
+    }
+}
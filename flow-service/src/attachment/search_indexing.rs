@@ -0,0 +1,216 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::ListOptions;
+use flow_domain::attachment::Attachment;
+use flow_infra::attachment::ByteStream;
+use crate::attachment::{
+    AttachmentService, MigratedAttachment, SpooledFile, TextExtractor, ThumbnailRegeneration, ALT_TEXT_ANNO,
+};
+use crate::search::{DocumentConverter, SearchService};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// 带搜索索引的Attachment服务包装器
+/// 在附件上传/更新/迁移/删除时自动更新搜索索引，配置了文本提取器时同时索引文件中的文本
+pub struct SearchIndexingAttachmentService {
+    inner: Arc<dyn AttachmentService>,
+    search_service: Arc<dyn SearchService>,
+    text_extractor: Option<Arc<dyn TextExtractor>>,
+}
+
+impl SearchIndexingAttachmentService {
+    pub fn new(inner: Arc<dyn AttachmentService>, search_service: Arc<dyn SearchService>) -> Self {
+        Self { inner, search_service, text_extractor: None }
+    }
+
+    /// 设置文本提取器，用于索引PDF等文档的正文
+    pub fn with_text_extractor(mut self, text_extractor: Arc<dyn TextExtractor>) -> Self {
+        self.text_extractor = Some(text_extractor);
+        self
+    }
+
+    /// 更新附件的搜索索引，失败时只记录日志
+    async fn update_search_index(&self, attachment: &Attachment) {
+        let extracted_text = self.extract_text(attachment).await;
+        let halo_doc = DocumentConverter::convert_attachment(attachment, extracted_text.as_deref());
+        if let Err(e) = self.search_service.add_or_update(vec![halo_doc]).await {
+            warn!("Failed to update attachment in search index: {}", e);
+        } else {
+            debug!("Updated attachment {} in search index", attachment.metadata.name);
+        }
+    }
+
+    /// 提取附件文件中的文本，不支持该媒体类型或提取失败时返回None
+    async fn extract_text(&self, attachment: &Attachment) -> Option<String> {
+        let extractor = self.text_extractor.as_ref()?;
+        let media_type = attachment.spec.media_type.as_deref()?;
+        if !extractor.supports(media_type) {
+            return None;
+        }
+        let name = &attachment.metadata.name;
+        let result: Result<Option<String>> = async {
+            let Some((_, content)) = self.inner.read_content(name).await? else {
+                return Ok(None);
+            };
+            let spooled = self.inner.spool(content).await?;
+            Ok(Some(extractor.extract(spooled.path()).await?))
+        }.await;
+        match result {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to extract text from attachment {}: {}", name, e);
+                None
+            }
+        }
+    }
+}
+
+/// 更新前后影响搜索文档的字段是否发生变化
+fn indexed_fields_changed(previous: &Attachment, current: &Attachment) -> bool {
+    let alt = |a: &Attachment| a.metadata.annotations.as_ref().and_then(|a| a.get(ALT_TEXT_ANNO)).cloned();
+    let permalink = |a: &Attachment| a.status.as_ref().and_then(|s| s.permalink.clone());
+    previous.spec.display_name != current.spec.display_name
+        || previous.spec.tags != current.spec.tags
+        || previous.spec.media_type != current.spec.media_type
+        || previous.spec.owner_name != current.spec.owner_name
+        || alt(previous) != alt(current)
+        || permalink(previous) != permalink(current)
+}
+
+#[async_trait]
+impl AttachmentService for SearchIndexingAttachmentService {
+    async fn upload(
+        &self,
+        file_content: ByteStream,
+        filename: String,
+        media_type: Option<String>,
+        owner_name: Option<String>,
+        policy_name: Option<String>,
+        group_name: Option<String>,
+    ) -> Result<Attachment> {
+        let attachment = self.inner
+            .upload(file_content, filename, media_type, owner_name, policy_name, group_name)
+            .await?;
+        self.update_search_index(&attachment).await;
+        Ok(attachment)
+    }
+
+    async fn spool(&self, content: ByteStream) -> Result<SpooledFile> {
+        self.inner.spool(content).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<()> {
+        self.inner.delete(name).await?;
+        let doc_id = DocumentConverter::attachment_doc_id(name);
+        if let Err(e) = self.search_service.delete_document(vec![doc_id]).await {
+            warn!("Failed to delete attachment from search index: {}", e);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Attachment>> {
+        self.inner.get(name).await
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<Vec<Attachment>> {
+        self.inner.list(options).await
+    }
+
+    async fn update(&self, attachment: Attachment) -> Result<Attachment> {
+        // 引用扫描等只修改状态的更新不需要重新提取文本
+        let previous = self.inner.get(&attachment.metadata.name).await?;
+        let updated = self.inner.update(attachment).await?;
+        if previous.is_none_or(|previous| indexed_fields_changed(&previous, &updated)) {
+            self.update_search_index(&updated).await;
+        }
+        Ok(updated)
+    }
+
+    async fn signed_url(&self, name: &str, expires_in: Duration) -> Result<Option<String>> {
+        self.inner.signed_url(name, expires_in).await
+    }
+
+    async fn read_content(&self, name: &str) -> Result<Option<(Attachment, ByteStream)>> {
+        self.inner.read_content(name).await
+    }
+
+    async fn read_content_range(&self, name: &str, start: u64, len: u64) -> Result<Option<(Attachment, ByteStream)>> {
+        self.inner.read_content_range(name, start, len).await
+    }
+
+    async fn migrate(&self, name: &str, target_policy: Option<String>, target_group: Option<String>) -> Result<Option<MigratedAttachment>> {
+        let migrated = self.inner.migrate(name, target_policy, target_group).await?;
+        if let Some(migrated) = &migrated {
+            self.update_search_index(&migrated.current).await;
+        }
+        Ok(migrated)
+    }
+
+    async fn delete_previous_file(&self, migrated: &MigratedAttachment) -> Result<()> {
+        self.inner.delete_previous_file(migrated).await
+    }
+
+    async fn regenerate_thumbnails(&self, name: &str, force: bool) -> Result<Option<ThumbnailRegeneration>> {
+        self.inner.regenerate_thumbnails(name, force).await
+    }
+
+    async fn is_public(&self, attachment: &Attachment) -> Result<bool> {
+        self.inner.is_public(attachment).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::attachment::{AttachmentSpec, AttachmentStatus};
+    use std::collections::HashMap;
+
+    fn attachment() -> Attachment {
+        Attachment {
+            metadata: Metadata::new("report"),
+            spec: AttachmentSpec {
+                display_name: Some("年度报告.pdf".to_string()),
+                group_name: None,
+                policy_name: None,
+                owner_name: Some("admin".to_string()),
+                media_type: Some("application/pdf".to_string()),
+                size: Some(1024),
+                tags: Some(vec!["报告".to_string()]),
+            },
+            status: Some(AttachmentStatus {
+                permalink: Some("/upload/report.pdf".to_string()),
+                thumbnails: None,
+                used_by: None,
+                blurhash: None,
+                dominant_color: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_indexed_fields_changed() {
+        let previous = attachment();
+        let mut current = attachment();
+        current.status.as_mut().unwrap().used_by = Some(Vec::new());
+        assert!(!indexed_fields_changed(&previous, &current));
+
+        current.metadata.annotations = Some(HashMap::from([(ALT_TEXT_ANNO.to_string(), "封面".to_string())]));
+        assert!(indexed_fields_changed(&previous, &current));
+    }
+
+    #[test]
+    fn test_convert_attachment() {
+        let mut attachment = attachment();
+        attachment.metadata.annotations = Some(HashMap::from([(ALT_TEXT_ANNO.to_string(), "年度财务".to_string())]));
+        let doc = DocumentConverter::convert_attachment(&attachment, Some("营业收入"));
+        assert_eq!(doc.id, DocumentConverter::attachment_doc_id("report"));
+        assert_eq!(doc.title, "年度报告.pdf");
+        assert_eq!(doc.description.as_deref(), Some("年度财务"));
+        assert_eq!(doc.content, "application/pdf\n营业收入");
+        assert_eq!(doc.tags, Some(vec!["报告".to_string()]));
+        assert_eq!(doc.owner_name, "admin");
+        assert_eq!(doc.permalink, "/upload/report.pdf");
+    }
+}
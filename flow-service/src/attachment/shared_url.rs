@@ -1,11 +1,27 @@
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flow_infra::cache::Cache;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::Sha256;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
 use uuid::Uuid;
 use anyhow::Result;
 
+/// 已撤销的共享链接的缓存键前缀
+const REVOKED_KEY_PREFIX: &str = "attachment:shared-url:revoked:";
+
+/// 共享链接下载次数的缓存键前缀
+const DOWNLOADS_KEY_PREFIX: &str = "attachment:shared-url:downloads:";
+
+/// 附件已生成的共享链接列表的缓存键前缀
+const ISSUED_KEY_PREFIX: &str = "attachment:shared-url:issued:";
+
+/// 未配置缓存时无法使用的功能
+pub const SHARED_URL_CACHE_REQUIRED_ERROR: &str = "Shared URL cache not configured";
+
 /// 共享URL信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SharedUrl {
@@ -17,174 +33,273 @@ pub struct SharedUrl {
     pub expires_at: DateTime<Utc>,
     /// 创建时间
     pub created_at: DateTime<Utc>,
+    /// 最大下载次数，为空表示不限制
+    #[serde(default)]
+    pub max_downloads: Option<u32>,
+}
+
+/// 签名token中携带的信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SharedUrlClaims {
+    #[serde(rename = "a")]
+    attachment_name: String,
+    /// 过期时间（Unix时间戳，秒）
+    #[serde(rename = "e")]
+    expires_at: i64,
+    #[serde(rename = "m", default, skip_serializing_if = "Option::is_none")]
+    max_downloads: Option<u32>,
+    /// 唯一标识，用于撤销和统计下载次数
+    #[serde(rename = "n")]
+    id: String,
+    /// 创建时间（Unix时间戳，秒）
+    #[serde(rename = "c")]
+    created_at: i64,
+}
+
+impl SharedUrlClaims {
+    fn to_shared_url(&self, token: String) -> SharedUrl {
+        SharedUrl {
+            token,
+            attachment_name: self.attachment_name.clone(),
+            expires_at: DateTime::from_timestamp(self.expires_at, 0).unwrap_or_default(),
+            created_at: DateTime::from_timestamp(self.created_at, 0).unwrap_or_default(),
+            max_downloads: self.max_downloads,
+        }
+    }
+
+    /// 距离过期的秒数，已过期时返回0
+    fn remaining_seconds(&self) -> u64 {
+        (self.expires_at - Utc::now().timestamp()).max(0) as u64
+    }
+}
+
+fn mac(secret: &[u8], payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// 签名生成token：`base64url(claims).base64url(HMAC-SHA256)`
+fn sign_claims(secret: &[u8], claims: &SharedUrlClaims) -> Result<String> {
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?);
+    let signature = URL_SAFE_NO_PAD.encode(mac(secret, &payload).finalize().into_bytes());
+    Ok(format!("{}.{}", payload, signature))
+}
+
+/// 校验token签名并解析，签名不正确或格式错误时返回None（不检查是否过期）
+fn verify_token(secret: &[u8], token: &str) -> Option<SharedUrlClaims> {
+    let (payload, signature) = token.split_once('.')?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    mac(secret, payload).verify_slice(&signature).ok()?;
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()
 }
 
 /// 共享URL服务trait
+#[async_trait]
 pub trait SharedUrlService: Send + Sync {
     /// 生成共享URL
-    fn generate_shared_url(&self, attachment_name: &str, expires_in_hours: Option<u32>) -> Result<SharedUrl>;
-    
-    /// 验证共享URL token
-    fn validate_token(&self, token: &str) -> Result<Option<String>>;
-    
+    async fn generate_shared_url(&self, attachment_name: &str, expires_in_hours: Option<u32>, max_downloads: Option<u32>) -> Result<SharedUrl>;
+
+    /// 验证共享URL token并计一次下载，token无效、已过期、已撤销或超出下载次数时返回None
+    async fn validate_token(&self, token: &str) -> Result<Option<String>>;
+
     /// 删除共享URL
-    fn revoke_shared_url(&self, token: &str) -> Result<()>;
-    
+    async fn revoke_shared_url(&self, token: &str) -> Result<()>;
+
     /// 获取附件的所有共享URL
-    fn get_shared_urls(&self, attachment_name: &str) -> Result<Vec<SharedUrl>>;
+    async fn get_shared_urls(&self, attachment_name: &str) -> Result<Vec<SharedUrl>>;
 }
 
-/// 默认共享URL服务实现（内存存储）
+/// 默认共享URL服务实现
+///
+/// 共享链接是HMAC签名的无状态token，重启和多实例部署后仍然有效；
+/// 撤销、下载次数限制和链接列表保存在缓存（Redis）中，未配置缓存时不支持这些功能
 pub struct DefaultSharedUrlService {
-    /// 存储共享URL（token -> SharedUrl）
-    urls: Arc<RwLock<HashMap<String, SharedUrl>>>,
-    /// 存储附件的共享URL列表（attachment_name -> Vec<token>）
-    attachment_urls: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    secret: Vec<u8>,
+    cache: Option<Arc<dyn Cache>>,
 }
 
 impl DefaultSharedUrlService {
-    pub fn new() -> Self {
+    pub fn new(secret: &str) -> Self {
         Self {
-            urls: Arc::new(RwLock::new(HashMap::new())),
-            attachment_urls: Arc::new(RwLock::new(HashMap::new())),
+            secret: secret.as_bytes().to_vec(),
+            cache: None,
         }
     }
-    
-    /// 清理过期的共享URL
-    async fn cleanup_expired(&self) {
-        let mut urls = self.urls.write().await;
-        let mut attachment_urls = self.attachment_urls.write().await;
-        
-        let now = Utc::now();
-        let expired_tokens: Vec<String> = urls.iter()
-            .filter(|(_, url)| url.expires_at < now)
-            .map(|(token, _)| token.clone())
-            .collect();
-        
-        for token in expired_tokens {
-            if let Some(url) = urls.remove(&token) {
-                // 从附件的URL列表中移除
-                if let Some(tokens) = attachment_urls.get_mut(&url.attachment_name) {
-                    tokens.retain(|t| t != &token);
-                    if tokens.is_empty() {
-                        attachment_urls.remove(&url.attachment_name);
-                    }
-                }
-            }
-        }
+
+    /// 设置缓存，用于撤销链接、限制下载次数和列出链接
+    pub fn with_cache(mut self, cache: Arc<dyn Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    fn require_cache(&self) -> Result<&Arc<dyn Cache>> {
+        self.cache.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{}: revocation and download limits require Redis", SHARED_URL_CACHE_REQUIRED_ERROR))
+    }
+
+    /// 读取附件已生成且未过期的共享链接
+    async fn issued_tokens(&self, cache: &Arc<dyn Cache>, attachment_name: &str) -> Result<Vec<String>> {
+        let issued = cache.get(&format!("{}{}", ISSUED_KEY_PREFIX, attachment_name)).await
+            .map_err(|e| anyhow::anyhow!("Failed to read shared URLs: {}", e))?;
+        let tokens: Vec<String> = issued
+            .and_then(|issued| serde_json::from_str(&issued).ok())
+            .unwrap_or_default();
+        Ok(tokens.into_iter()
+            .filter(|token| verify_token(&self.secret, token).is_some_and(|c| c.remaining_seconds() > 0))
+            .collect())
+    }
+
+    async fn save_issued_tokens(&self, cache: &Arc<dyn Cache>, attachment_name: &str, tokens: &[String]) -> Result<()> {
+        let key = format!("{}{}", ISSUED_KEY_PREFIX, attachment_name);
+        let ttl = tokens.iter()
+            .filter_map(|token| verify_token(&self.secret, token))
+            .map(|claims| claims.remaining_seconds())
+            .max();
+        let result = match ttl {
+            Some(ttl) => cache.set(&key, &serde_json::to_string(tokens)?, Some(ttl.max(1))).await,
+            None => cache.delete(&key).await,
+        };
+        result.map_err(|e| anyhow::anyhow!("Failed to save shared URLs: {}", e))
     }
-}
 
-impl Default for DefaultSharedUrlService {
-    fn default() -> Self {
-        Self::new()
+    async fn is_revoked(&self, cache: &Arc<dyn Cache>, claims: &SharedUrlClaims) -> Result<bool> {
+        let revoked = cache.get(&format!("{}{}", REVOKED_KEY_PREFIX, claims.id)).await
+            .map_err(|e| anyhow::anyhow!("Failed to check shared URL revocation: {}", e))?;
+        Ok(revoked.is_some())
     }
 }
 
+#[async_trait]
 impl SharedUrlService for DefaultSharedUrlService {
-    fn generate_shared_url(&self, attachment_name: &str, expires_in_hours: Option<u32>) -> Result<SharedUrl> {
-        let token = Uuid::new_v4().to_string();
+    async fn generate_shared_url(&self, attachment_name: &str, expires_in_hours: Option<u32>, max_downloads: Option<u32>) -> Result<SharedUrl> {
+        if max_downloads.is_some() {
+            self.require_cache()?;
+        }
         let now = Utc::now();
-        let expires_in = Duration::hours(expires_in_hours.unwrap_or(24) as i64);
-        let expires_at = now + expires_in;
-        
-        let shared_url = SharedUrl {
-            token: token.clone(),
+        let expires_at = now + Duration::hours(expires_in_hours.unwrap_or(24) as i64);
+        let claims = SharedUrlClaims {
             attachment_name: attachment_name.to_string(),
-            expires_at,
-            created_at: now,
+            expires_at: expires_at.timestamp(),
+            max_downloads,
+            id: Uuid::new_v4().simple().to_string(),
+            created_at: now.timestamp(),
         };
-        
-        // 使用tokio运行时同步执行
-        let rt = tokio::runtime::Handle::try_current()
-            .map_err(|_| anyhow::anyhow!("No tokio runtime available"))?;
-        
-        rt.block_on(async {
-            let mut urls = self.urls.write().await;
-            let mut attachment_urls = self.attachment_urls.write().await;
-            
-            urls.insert(token.clone(), shared_url.clone());
-            attachment_urls
-                .entry(attachment_name.to_string())
-                .or_insert_with(Vec::new)
-                .push(token);
-            
-            // 清理过期URL
-            self.cleanup_expired().await;
-        });
-        
-        Ok(shared_url)
-    }
-    
-    fn validate_token(&self, token: &str) -> Result<Option<String>> {
-        let rt = tokio::runtime::Handle::try_current()
-            .map_err(|_| anyhow::anyhow!("No tokio runtime available"))?;
-        
-        rt.block_on(async {
-            let mut urls = self.urls.write().await;
-            
-            // 清理过期URL
-            self.cleanup_expired().await;
-            
-            if let Some(url) = urls.get(token) {
-                if url.expires_at > Utc::now() {
-                    Ok(Some(url.attachment_name.clone()))
-                } else {
-                    // 已过期，删除
-                    urls.remove(token);
-                    Ok(None)
-                }
-            } else {
-                Ok(None)
+        let token = sign_claims(&self.secret, &claims)?;
+
+        // 记录到附件的链接列表，失败不影响链接使用
+        if let Some(cache) = &self.cache {
+            if let Err(e) = async {
+                let mut tokens = self.issued_tokens(cache, attachment_name).await?;
+                tokens.push(token.clone());
+                self.save_issued_tokens(cache, attachment_name, &tokens).await
+            }.await {
+                tracing::warn!("Failed to record shared URL of attachment {}: {}", attachment_name, e);
             }
-        })
+        }
+        Ok(claims.to_shared_url(token))
     }
-    
-    fn revoke_shared_url(&self, token: &str) -> Result<()> {
-        let rt = tokio::runtime::Handle::try_current()
-            .map_err(|_| anyhow::anyhow!("No tokio runtime available"))?;
-        
-        rt.block_on(async {
-            let mut urls = self.urls.write().await;
-            let mut attachment_urls = self.attachment_urls.write().await;
-            
-            if let Some(url) = urls.remove(token) {
-                // 从附件的URL列表中移除
-                if let Some(tokens) = attachment_urls.get_mut(&url.attachment_name) {
-                    tokens.retain(|t| t != token);
-                    if tokens.is_empty() {
-                        attachment_urls.remove(&url.attachment_name);
-                    }
-                }
+
+    async fn validate_token(&self, token: &str) -> Result<Option<String>> {
+        let Some(claims) = verify_token(&self.secret, token) else {
+            return Ok(None);
+        };
+        let remaining = claims.remaining_seconds();
+        if remaining == 0 {
+            return Ok(None);
+        }
+        let Some(cache) = &self.cache else {
+            // 未配置缓存时无法统计下载次数，限制了次数的链接视为无效
+            return Ok(claims.max_downloads.is_none().then_some(claims.attachment_name));
+        };
+        if self.is_revoked(cache, &claims).await? {
+            return Ok(None);
+        }
+        if let Some(max_downloads) = claims.max_downloads {
+            let downloads = cache.incr(&format!("{}{}", DOWNLOADS_KEY_PREFIX, claims.id), Some(remaining)).await
+                .map_err(|e| anyhow::anyhow!("Failed to count shared URL downloads: {}", e))?;
+            if downloads > max_downloads as i64 {
+                return Ok(None);
             }
-        });
-        
-        Ok(())
+        }
+        Ok(Some(claims.attachment_name))
+    }
+
+    async fn revoke_shared_url(&self, token: &str) -> Result<()> {
+        let cache = self.require_cache()?;
+        let Some(claims) = verify_token(&self.secret, token) else {
+            return Ok(());
+        };
+        let remaining = claims.remaining_seconds();
+        if remaining == 0 {
+            return Ok(());
+        }
+        // 撤销记录保留到链接过期
+        cache.set(&format!("{}{}", REVOKED_KEY_PREFIX, claims.id), "1", Some(remaining)).await
+            .map_err(|e| anyhow::anyhow!("Failed to revoke shared URL: {}", e))?;
+
+        let mut tokens = self.issued_tokens(cache, &claims.attachment_name).await?;
+        tokens.retain(|t| t != token);
+        self.save_issued_tokens(cache, &claims.attachment_name, &tokens).await
     }
-    
-    fn get_shared_urls(&self, attachment_name: &str) -> Result<Vec<SharedUrl>> {
-        let rt = tokio::runtime::Handle::try_current()
-            .map_err(|_| anyhow::anyhow!("No tokio runtime available"))?;
-        
-        rt.block_on(async {
-            let urls = self.urls.read().await;
-            let attachment_urls = self.attachment_urls.read().await;
-            
-            if let Some(tokens) = attachment_urls.get(attachment_name) {
-                let mut result = Vec::new();
-                for token in tokens {
-                    if let Some(url) = urls.get(token) {
-                        // 只返回未过期的URL
-                        if url.expires_at > Utc::now() {
-                            result.push(url.clone());
-                        }
-                    }
-                }
-                Ok(result)
-            } else {
-                Ok(Vec::new())
+
+    async fn get_shared_urls(&self, attachment_name: &str) -> Result<Vec<SharedUrl>> {
+        let Some(cache) = &self.cache else {
+            return Ok(Vec::new());
+        };
+        let mut urls = Vec::new();
+        for token in self.issued_tokens(cache, attachment_name).await? {
+            let Some(claims) = verify_token(&self.secret, &token) else {
+                continue;
+            };
+            if !self.is_revoked(cache, &claims).await? {
+                urls.push(claims.to_shared_url(token));
             }
-        })
+        }
+        Ok(urls)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(expires_at: i64) -> SharedUrlClaims {
+        SharedUrlClaims {
+            attachment_name: "attachment-1".to_string(),
+            expires_at,
+            max_downloads: Some(3),
+            id: "abc".to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_token() {
+        let claims = claims(4_102_444_800);
+        let token = sign_claims(b"secret", &claims).unwrap();
+        assert_eq!(verify_token(b"secret", &token), Some(claims));
+        assert_eq!(verify_token(b"other-secret", &token), None);
+
+        // 篡改内容后签名不匹配
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(br#"{"a":"attachment-2","e":4102444800,"n":"abc","c":0}"#);
+        assert_eq!(verify_token(b"secret", &format!("{}.{}", forged, signature)), None);
+        assert_eq!(verify_token(b"secret", "not-a-token"), None);
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_without_cache() {
+        let service = DefaultSharedUrlService::new("secret");
+        let shared_url = service.generate_shared_url("attachment-1", Some(1), None).await.unwrap();
+        assert_eq!(service.validate_token(&shared_url.token).await.unwrap().as_deref(), Some("attachment-1"));
+
+        // 另一个实例使用相同密钥也能校验
+        let other = DefaultSharedUrlService::new("secret");
+        assert!(other.validate_token(&shared_url.token).await.unwrap().is_some());
+
+        let expired = sign_claims(b"secret", &SharedUrlClaims { max_downloads: None, ..claims(1) }).unwrap();
+        assert!(service.validate_token(&expired).await.unwrap().is_none());
+        assert!(service.generate_shared_url("attachment-1", None, Some(1)).await.is_err());
+    }
+}
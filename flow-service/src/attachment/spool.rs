@@ -0,0 +1,135 @@
+use anyhow::Result;
+use flow_infra::attachment::{ByteStream, file_stream};
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+/// 上传内容超过最大文件大小时的错误信息前缀
+pub const FILE_TOO_LARGE_ERROR: &str = "File is too large";
+
+/// 暂存在临时目录中的上传内容，边接收边计算摘要，不在内存中缓存整个文件
+///
+/// 丢弃时删除临时文件
+pub struct SpooledFile {
+    path: PathBuf,
+    size: u64,
+    hash: String,
+}
+
+impl SpooledFile {
+    /// 将字节流写入临时目录，超过max_size时中止并返回错误
+    pub async fn write(dir: &Path, mut content: ByteStream, max_size: Option<u64>) -> Result<Self> {
+        tokio::fs::create_dir_all(dir).await?;
+        let mut spooled = Self {
+            path: dir.join(Uuid::new_v4().to_string()),
+            size: 0,
+            hash: String::new(),
+        };
+        let mut file = tokio::fs::File::create(&spooled.path).await?;
+        let mut hasher = Sha256::new();
+        while let Some(chunk) = content.next().await {
+            let chunk = chunk?;
+            spooled.size += chunk.len() as u64;
+            if let Some(max_size) = max_size.filter(|max_size| spooled.size > *max_size) {
+                anyhow::bail!("{}: exceeds {} bytes", FILE_TOO_LARGE_ERROR, max_size);
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        spooled.hash = hex::encode(hasher.finalize());
+        Ok(spooled)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// 内容的SHA-256摘要（十六进制）
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// 以流的方式读取暂存的内容
+    pub async fn open(&self) -> Result<ByteStream> {
+        Ok(file_stream(&self.path, 0, None).await?)
+    }
+
+    /// 转换为字节流，临时文件在字节流丢弃后删除
+    pub async fn into_stream(self) -> Result<ByteStream> {
+        let stream = file_stream(&self.path, 0, None).await?;
+        Ok(stream.map(move |chunk| {
+            let _spooled = &self;
+            chunk
+        }).boxed())
+    }
+
+    /// 将暂存的内容读入内存，只用于图片等需要整体解析的小文件
+    pub async fn read(&self) -> Result<Vec<u8>> {
+        Ok(tokio::fs::read(&self.path).await?)
+    }
+
+    /// 读取暂存内容开头的最多len个字节，用于识别文件类型
+    pub async fn head(&self, len: usize) -> Result<Vec<u8>> {
+        let file = tokio::fs::File::open(&self.path).await?;
+        let mut head = Vec::with_capacity(len.min(self.size as usize));
+        file.take(len as u64).read_to_end(&mut head).await?;
+        Ok(head)
+    }
+
+    /// 替换暂存的内容（如去除EXIF后的图片）
+    pub async fn replace(&mut self, content: &[u8]) -> Result<()> {
+        tokio::fs::write(&self.path, content).await?;
+        self.size = content.len() as u64;
+        self.hash = crate::attachment::content_hash(content);
+        Ok(())
+    }
+}
+
+impl Drop for SpooledFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove spooled upload {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attachment::content_hash;
+
+    fn chunks(parts: &[&[u8]]) -> ByteStream {
+        let parts: Vec<std::io::Result<Vec<u8>>> = parts.iter().map(|p| Ok(p.to_vec())).collect();
+        futures_util::stream::iter(parts).boxed()
+    }
+
+    #[tokio::test]
+    async fn test_spool_hashes_and_removes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let spooled = SpooledFile::write(dir.path(), chunks(&[b"ab", b"c"]), Some(3)).await.unwrap();
+        assert_eq!(spooled.size(), 3);
+        assert_eq!(spooled.hash(), content_hash(b"abc"));
+        assert_eq!(spooled.read().await.unwrap(), b"abc");
+
+        let path = spooled.path().to_path_buf();
+        drop(spooled);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_spool_rejects_oversized_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = SpooledFile::write(dir.path(), chunks(&[b"ab", b"cd"]), Some(3)).await;
+        assert!(result.err().unwrap().to_string().starts_with(FILE_TOO_LARGE_ERROR));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use flow_api::extension::ExtensionClient;
+use flow_domain::attachment::{Policy, PolicyTemplate};
+use flow_domain::setting::Setting;
+use flow_infra::attachment::{AttachmentStorage, AliyunOssStorage, AliyunOssConfig, GcsStorage, GcsConfig, WebDavStorage, WebDavConfig};
+use flow_infra::attachment::aliyun_oss::ALIYUN_OSS_TEMPLATE;
+use flow_infra::attachment::gcs::GCS_TEMPLATE;
+use flow_infra::attachment::webdav::WEBDAV_TEMPLATE;
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use flow_plugin::event::{Event, EventListener, PLUGIN_DELETED, PLUGIN_FAILED, PLUGIN_STOPPED};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 存储策略ConfigMap中保存配置的键
+pub const POLICY_CONFIG_KEY: &str = "default";
+
+/// 根据存储策略配置创建存储实现
+pub type StorageFactory = Arc<dyn Fn(serde_json::Value) -> Result<Arc<dyn AttachmentStorage>> + Send + Sync>;
+
+/// 已创建的存储及创建时使用的配置
+type CachedStorage = (serde_json::Value, Arc<dyn AttachmentStorage>);
+
+/// 插件注册存储模板无效时的错误信息前缀
+pub const STORAGE_TEMPLATE_INVALID_ERROR: &str = "Invalid storage template";
+
+/// 可用的存储模板
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageTemplateInfo {
+    /// 模板名称（Policy的templateName）
+    pub name: String,
+    /// 注册模板的插件ID，内置模板为None
+    pub owner: Option<String>,
+}
+
+/// 按附件存储策略（Policy）解析对应的存储实现
+///
+/// 策略的templateName决定存储类型，configMapName指向的ConfigMap保存该策略的配置；
+/// 未指定策略、策略不存在或模板没有注册存储工厂时使用默认的本地存储
+pub struct PolicyStorageResolver {
+    client: Arc<ReactiveExtensionClient>,
+    default_storage: Arc<dyn AttachmentStorage>,
+    factories: RwLock<HashMap<String, StorageFactory>>,
+    /// 已创建的存储，配置变化时重新创建
+    cache: RwLock<HashMap<String, CachedStorage>>,
+    /// 插件注册的模板：模板名称 -> 插件ID
+    plugin_templates: RwLock<HashMap<String, String>>,
+}
+
+impl PolicyStorageResolver {
+    /// 创建包含内置存储（阿里云OSS、Google Cloud Storage、WebDAV）的解析器
+    pub fn new(client: Arc<ReactiveExtensionClient>, default_storage: Arc<dyn AttachmentStorage>) -> Self {
+        let resolver = Self {
+            client,
+            default_storage,
+            factories: RwLock::new(HashMap::new()),
+            cache: RwLock::new(HashMap::new()),
+            plugin_templates: RwLock::new(HashMap::new()),
+        };
+        resolver.register_factory(ALIYUN_OSS_TEMPLATE, Arc::new(|config| {
+            let config: AliyunOssConfig = serde_json::from_value(config)?;
+            Ok(Arc::new(AliyunOssStorage::new(config)) as Arc<dyn AttachmentStorage>)
+        }));
+        resolver.register_factory(GCS_TEMPLATE, Arc::new(|config| {
+            let config: GcsConfig = serde_json::from_value(config)?;
+            Ok(Arc::new(GcsStorage::new(config)?) as Arc<dyn AttachmentStorage>)
+        }));
+        resolver.register_factory(WEBDAV_TEMPLATE, Arc::new(|config| {
+            let config: WebDavConfig = serde_json::from_value(config)?;
+            Ok(Arc::new(WebDavStorage::new(config)) as Arc<dyn AttachmentStorage>)
+        }));
+        resolver
+    }
+
+    /// 注册存储工厂，同名模板的工厂会被替换
+    pub fn register_factory(&self, template_name: &str, factory: StorageFactory) {
+        self.factories.write().unwrap().insert(template_name.to_string(), factory);
+        self.cache.write().unwrap().clear();
+    }
+
+    /// 注册插件提供的存储
+    ///
+    /// 保存策略模板及其设置表单（用户创建该类型的存储策略时填写），并注册创建存储的工厂。
+    /// 模板引用的设置必须随模板一起提供；内置模板或其他插件的模板不能被覆盖
+    pub async fn register_plugin_storage(
+        &self,
+        plugin_id: &str,
+        template: PolicyTemplate,
+        setting: Setting,
+        factory: StorageFactory,
+    ) -> Result<()> {
+        let template_name = template.metadata.name.clone();
+        validate_plugin_template(&template, &setting)?;
+        let owner = self.plugin_templates.read().unwrap().get(&template_name).cloned();
+        match owner {
+            Some(owner) if owner != plugin_id => anyhow::bail!(
+                "{}: template {} is already registered by plugin {}", STORAGE_TEMPLATE_INVALID_ERROR, template_name, owner
+            ),
+            None if self.factories.read().unwrap().contains_key(&template_name) => anyhow::bail!(
+                "{}: template {} is built in", STORAGE_TEMPLATE_INVALID_ERROR, template_name
+            ),
+            _ => {}
+        }
+
+        self.client.update(setting).await
+            .map_err(|e| anyhow::anyhow!("Failed to save setting of template {}: {}", template_name, e))?;
+        self.client.update(template).await
+            .map_err(|e| anyhow::anyhow!("Failed to save policy template {}: {}", template_name, e))?;
+        self.plugin_templates.write().unwrap().insert(template_name.clone(), plugin_id.to_string());
+        self.register_factory(&template_name, factory);
+        tracing::info!("Registered attachment storage {} of plugin {}", template_name, plugin_id);
+        Ok(())
+    }
+
+    /// 移除插件注册的所有存储，返回移除的模板名称
+    ///
+    /// 策略模板及其设置保留，插件重新启动后使用该模板的存储策略恢复可用
+    pub fn unregister_plugin_storages(&self, plugin_id: &str) -> Vec<String> {
+        let mut plugin_templates = self.plugin_templates.write().unwrap();
+        let removed: Vec<String> = plugin_templates.iter()
+            .filter(|(_, owner)| owner.as_str() == plugin_id)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if removed.is_empty() {
+            return removed;
+        }
+        plugin_templates.retain(|_, owner| owner != plugin_id);
+        let mut factories = self.factories.write().unwrap();
+        for name in &removed {
+            factories.remove(name);
+        }
+        self.cache.write().unwrap().clear();
+        removed
+    }
+
+    /// 所有可用的存储模板
+    pub fn templates(&self) -> Vec<StorageTemplateInfo> {
+        let plugin_templates = self.plugin_templates.read().unwrap();
+        let mut templates: Vec<StorageTemplateInfo> = self.factories.read().unwrap().keys()
+            .map(|name| StorageTemplateInfo {
+                name: name.clone(),
+                owner: plugin_templates.get(name).cloned(),
+            })
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    /// 默认的本地存储
+    pub fn default_storage(&self) -> Arc<dyn AttachmentStorage> {
+        self.default_storage.clone()
+    }
+
+    /// 解析存储策略对应的存储实现
+    pub async fn resolve(&self, policy_name: Option<&str>) -> Result<Arc<dyn AttachmentStorage>> {
+        let Some(policy_name) = policy_name.filter(|p| !p.is_empty()) else {
+            return Ok(self.default_storage());
+        };
+        let Some(policy) = self.client.fetch::<Policy>(policy_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch policy {}: {}", policy_name, e))? else {
+            tracing::warn!("Attachment policy {} not found, using local storage", policy_name);
+            return Ok(self.default_storage());
+        };
+        let Some(factory) = self.factories.read().unwrap().get(&policy.spec.template_name).cloned() else {
+            return Ok(self.default_storage());
+        };
+
+        let config = self.policy_config(&policy).await?;
+        if let Some((cached_config, storage)) = self.cache.read().unwrap().get(policy_name) {
+            if *cached_config == config {
+                return Ok(storage.clone());
+            }
+        }
+        let storage = factory(config.clone())
+            .map_err(|e| anyhow::anyhow!("Invalid configuration of policy {}: {}", policy_name, e))?;
+        self.cache.write().unwrap().insert(policy_name.to_string(), (config, storage.clone()));
+        Ok(storage)
+    }
+
+    /// 读取存储策略的配置，未指定策略、策略不存在或没有配置时返回None
+    pub async fn policy_settings(&self, policy_name: Option<&str>) -> Result<Option<serde_json::Value>> {
+        let Some(policy_name) = policy_name.filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        let Some(policy) = self.client.fetch::<Policy>(policy_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch policy {}: {}", policy_name, e))? else {
+            return Ok(None);
+        };
+        if policy.spec.config_map_name.is_none() {
+            return Ok(None);
+        }
+        self.policy_config(&policy).await.map(Some)
+    }
+
+    /// 读取策略ConfigMap中的配置
+    async fn policy_config(&self, policy: &Policy) -> Result<serde_json::Value> {
+        let config_map_name = policy.spec.config_map_name.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Policy {} has no config map", policy.metadata.name))?;
+        let config_map: ConfigMap = self.client.fetch(config_map_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map {}: {}", config_map_name, e))?
+            .ok_or_else(|| anyhow::anyhow!("Config map {} not found", config_map_name))?;
+        let config_json = config_map.data
+            .and_then(|mut data| data.remove(POLICY_CONFIG_KEY))
+            .ok_or_else(|| anyhow::anyhow!("Config map {} has no {} entry", config_map_name, POLICY_CONFIG_KEY))?;
+        serde_json::from_str(&config_json)
+            .map_err(|e| anyhow::anyhow!("Failed to parse config map {}: {}", config_map_name, e))
+    }
+}
+
+/// 插件停止、卸载或失败时移除其注册的存储
+pub struct PluginStorageCleanup {
+    resolver: Arc<PolicyStorageResolver>,
+}
+
+impl PluginStorageCleanup {
+    /// 订阅时使用的订阅者名称
+    pub const OWNER: &'static str = "attachment-storage";
+
+    pub fn new(resolver: Arc<PolicyStorageResolver>) -> Self {
+        Self { resolver }
+    }
+
+    /// 需要订阅的插件生命周期事件
+    pub fn event_types() -> Vec<String> {
+        vec![PLUGIN_STOPPED.to_string(), PLUGIN_DELETED.to_string(), PLUGIN_FAILED.to_string()]
+    }
+}
+
+#[async_trait]
+impl EventListener for PluginStorageCleanup {
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        let Some(plugin_id) = event.data.get("pluginId").and_then(|id| id.as_str()) else {
+            return Ok(());
+        };
+        let removed = self.resolver.unregister_plugin_storages(plugin_id);
+        if !removed.is_empty() {
+            tracing::info!("Removed attachment storages {} of plugin {}", removed.join(", "), plugin_id);
+        }
+        Ok(())
+    }
+}
+
+/// 检查插件提供的模板与设置是否匹配
+fn validate_plugin_template(template: &PolicyTemplate, setting: &Setting) -> Result<()> {
+    let template_name = &template.metadata.name;
+    if template_name.is_empty() {
+        anyhow::bail!("{}: template name is empty", STORAGE_TEMPLATE_INVALID_ERROR);
+    }
+    let Some(spec) = &template.spec else {
+        anyhow::bail!("{}: template {} has no spec", STORAGE_TEMPLATE_INVALID_ERROR, template_name);
+    };
+    if spec.setting_name != setting.metadata.name {
+        anyhow::bail!(
+            "{}: template {} references setting {}, but setting {} was provided",
+            STORAGE_TEMPLATE_INVALID_ERROR, template_name, spec.setting_name, setting.metadata.name
+        );
+    }
+    // 扩展对象按名称存储，设置与模板同名会互相覆盖
+    if setting.metadata.name == *template_name {
+        anyhow::bail!("{}: setting of template {} must have a different name", STORAGE_TEMPLATE_INVALID_ERROR, template_name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::attachment::PolicyTemplateSpec;
+    use flow_domain::setting::SettingSpec;
+
+    fn template(name: &str, setting_name: &str) -> PolicyTemplate {
+        PolicyTemplate {
+            metadata: Metadata::new(name.to_string()),
+            spec: Some(PolicyTemplateSpec {
+                display_name: Some("S3".to_string()),
+                setting_name: setting_name.to_string(),
+            }),
+        }
+    }
+
+    fn setting(name: &str) -> Setting {
+        Setting {
+            metadata: Metadata::new(name.to_string()),
+            spec: SettingSpec::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate_plugin_template() {
+        assert!(validate_plugin_template(&template("s3os", "s3os-policy-template-setting"), &setting("s3os-policy-template-setting")).is_ok());
+
+        let err = validate_plugin_template(&template("s3os", "s3os-setting"), &setting("other")).unwrap_err();
+        assert!(err.to_string().starts_with(STORAGE_TEMPLATE_INVALID_ERROR));
+        let err = validate_plugin_template(&template("s3os", "s3os"), &setting("s3os")).unwrap_err();
+        assert!(err.to_string().starts_with(STORAGE_TEMPLATE_INVALID_ERROR));
+        let mut no_spec = template("s3os", "s3os-setting");
+        no_spec.spec = None;
+        assert!(validate_plugin_template(&no_spec, &setting("s3os-setting")).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+
+/// 提取的文本保留的最大字符数，避免大文档撑大搜索索引
+pub const MAX_EXTRACTED_TEXT_CHARS: usize = 100_000;
+
+/// 从附件文件中提取文本的trait，用于建立搜索索引
+#[async_trait]
+pub trait TextExtractor: Send + Sync {
+    /// 是否支持提取该媒体类型的文件
+    fn supports(&self, media_type: &str) -> bool;
+
+    /// 提取文件中的文本
+    async fn extract(&self, path: &Path) -> Result<String>;
+}
+
+/// 调用pdftotext命令提取PDF中的文本
+pub struct PdftotextExtractor {
+    program: String,
+}
+
+impl PdftotextExtractor {
+    /// program为pdftotext可执行文件路径
+    pub fn new(program: String) -> Self {
+        Self { program }
+    }
+}
+
+#[async_trait]
+impl TextExtractor for PdftotextExtractor {
+    fn supports(&self, media_type: &str) -> bool {
+        media_type == "application/pdf"
+    }
+
+    async fn extract(&self, path: &Path) -> Result<String> {
+        let output = tokio::process::Command::new(&self.program)
+            .args(["-q".as_ref(), "-enc".as_ref(), "UTF-8".as_ref(), path.as_os_str(), "-".as_ref()])
+            .kill_on_drop(true)
+            .output().await
+            .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", self.program, e))?;
+        if !output.status.success() {
+            anyhow::bail!("{} exited with {}: {}", self.program, output.status, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(normalize_text(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// 合并连续的空白字符，并截断到MAX_EXTRACTED_TEXT_CHARS个字符
+pub fn normalize_text(text: &str) -> String {
+    let mut normalized = String::new();
+    let mut chars = 0;
+    for word in text.split_whitespace() {
+        if chars >= MAX_EXTRACTED_TEXT_CHARS {
+            break;
+        }
+        if chars > 0 {
+            normalized.push(' ');
+            chars += 1;
+        }
+        normalized.push_str(word);
+        chars += word.chars().count();
+    }
+    match normalized.char_indices().nth(MAX_EXTRACTED_TEXT_CHARS) {
+        Some((end, _)) => normalized[..end].to_string(),
+        None => normalized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("  第一页\n\n\x0cSecond\tpage  "), "第一页 Second page");
+        assert_eq!(normalize_text(""), "");
+
+        let long = "字".repeat(MAX_EXTRACTED_TEXT_CHARS + 10);
+        assert_eq!(normalize_text(&long).chars().count(), MAX_EXTRACTED_TEXT_CHARS);
+    }
+}
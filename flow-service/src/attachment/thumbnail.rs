@@ -1,9 +1,13 @@
-use flow_domain::attachment::ThumbnailSize;
+use flow_domain::attachment::{ThumbnailFormat, ThumbnailSize};
 use std::path::{Path, PathBuf};
 use anyhow::Result;
-use image::{ImageReader, imageops::FilterType};
+use image::{DynamicImage, ImageReader, imageops::FilterType};
+use image::codecs::avif::AvifEncoder;
 use std::fs;
 
+/// AVIF编码速度（1-10，越大越快、压缩率越低）
+const AVIF_ENCODE_SPEED: u8 = 8;
+
 /// 缩略图服务trait
 pub trait ThumbnailService: Send + Sync {
     /// 生成缩略图
@@ -12,8 +16,21 @@ pub trait ThumbnailService: Send + Sync {
     /// 获取缩略图路径（如果存在）
     fn get_thumbnail_path(&self, source_path: &Path, size: ThumbnailSize) -> Option<PathBuf>;
     
+    /// 生成指定格式的缩略图
+    fn generate_thumbnail_as(&self, source_path: &Path, size: ThumbnailSize, format: ThumbnailFormat) -> Result<PathBuf>;
+    
+    /// 除原格式外需要额外生成的缩略图格式
+    fn formats(&self) -> Vec<ThumbnailFormat> {
+        Vec::new()
+    }
+    
     /// 检查是否为图片文件
     fn is_image(&self, media_type: &str) -> bool;
+    
+    /// 当前缩略图配置（尺寸、质量、格式）的签名，配置变化后需要重新生成已有的缩略图
+    fn signature(&self) -> String {
+        String::new()
+    }
 }
 
 /// 默认缩略图服务实现
@@ -21,6 +38,7 @@ pub trait ThumbnailService: Send + Sync {
 pub struct DefaultThumbnailService {
     thumbnail_dir: PathBuf,
     quality: f32,
+    formats: Vec<ThumbnailFormat>,
 }
 
 impl DefaultThumbnailService {
@@ -28,8 +46,55 @@ impl DefaultThumbnailService {
         Self {
             thumbnail_dir,
             quality: quality.max(0.0).min(1.0),
+            formats: vec![ThumbnailFormat::Webp],
         }
     }
+    
+    /// 设置额外生成的缩略图格式（默认只生成WebP）
+    pub fn with_formats(mut self, formats: Vec<ThumbnailFormat>) -> Self {
+        self.formats = formats;
+        self
+    }
+    
+    /// 加载原图并缩放到指定尺寸，原图小于目标尺寸时保持原图大小
+    fn resize(source_path: &Path, size: ThumbnailSize) -> Result<DynamicImage> {
+        if !source_path.exists() {
+            anyhow::bail!("Source file does not exist: {}", source_path.display());
+        }
+        let img = ImageReader::open(source_path)?
+            .decode()
+            .map_err(|e| anyhow::anyhow!("Failed to decode image: {}", e))?;
+        let width = size.width();
+        Ok(if img.width() > width {
+            img.resize(width, (img.height() as f32 * (width as f32 / img.width() as f32)) as u32, FilterType::Lanczos3)
+        } else {
+            img
+        })
+    }
+    
+    fn thumbnail_path_as(&self, source_path: &Path, size: ThumbnailSize, format: ThumbnailFormat) -> Option<PathBuf> {
+        let stem = source_path.file_stem()?;
+        let filename = format!("{}_{}.{}", stem.to_string_lossy(), size.as_str(), format.as_str());
+        Some(self.thumbnail_dir.join(filename))
+    }
+}
+
+/// 根据Accept请求头从可用的缩略图格式中选择压缩率最高的格式，都不接受时返回None（使用原格式）
+///
+/// 只认可明确列出的媒体类型，`*/*` 和 `image/*` 不视为支持
+pub fn negotiate_thumbnail_format(accept: &str, available: &[ThumbnailFormat]) -> Option<ThumbnailFormat> {
+    let accepted: Vec<&str> = accept.split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media_type = parts.next()?;
+            let rejected = parts.any(|param| {
+                param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+            });
+            (!rejected).then_some(media_type)
+        })
+        .collect();
+    ThumbnailFormat::ALL.into_iter()
+        .find(|format| available.contains(format) && accepted.contains(&format.media_type()))
 }
 
 impl ThumbnailService for DefaultThumbnailService {
@@ -53,21 +118,10 @@ impl ThumbnailService for DefaultThumbnailService {
             fs::create_dir_all(parent)?;
         }
         
-        // 5. 使用image crate加载图片
-        let img = ImageReader::open(source_path)?
-            .decode()
-            .map_err(|e| anyhow::anyhow!("Failed to decode image: {}", e))?;
-        
-        // 6. 调整图片大小
-        let width = size.width();
-        let resized = if img.width() > width {
-            img.resize(width, (img.height() as f32 * (width as f32 / img.width() as f32)) as u32, FilterType::Lanczos3)
-        } else {
-            // 如果原图小于目标尺寸，保持原图大小
-            img
-        };
+        // 5. 加载图片并调整大小
+        let resized = Self::resize(source_path, size)?;
         
-        // 7. 保存缩略图
+        // 6. 保存缩略图
         resized.save_with_format(&thumbnail_path, image::ImageFormat::Jpeg)
             .map_err(|e| anyhow::anyhow!("Failed to save thumbnail: {}", e))?;
         
@@ -85,5 +139,58 @@ impl ThumbnailService for DefaultThumbnailService {
     fn is_image(&self, media_type: &str) -> bool {
         media_type.starts_with("image/")
     }
+    
+    fn generate_thumbnail_as(&self, source_path: &Path, size: ThumbnailSize, format: ThumbnailFormat) -> Result<PathBuf> {
+        let thumbnail_path = self.thumbnail_path_as(source_path, size, format)
+            .ok_or_else(|| anyhow::anyhow!("Failed to generate thumbnail path"))?;
+        if thumbnail_path.exists() {
+            return Ok(thumbnail_path);
+        }
+        if let Some(parent) = thumbnail_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let resized = Self::resize(source_path, size)?;
+        match format {
+            // image crate的WebP编码器只支持无损压缩
+            ThumbnailFormat::Webp => resized.save_with_format(&thumbnail_path, image::ImageFormat::WebP)
+                .map_err(|e| anyhow::anyhow!("Failed to save thumbnail: {}", e))?,
+            ThumbnailFormat::Avif => {
+                let file = fs::File::create(&thumbnail_path)?;
+                let quality = (self.quality * 100.0) as u8;
+                resized.write_with_encoder(AvifEncoder::new_with_speed_quality(file, AVIF_ENCODE_SPEED, quality))
+                    .map_err(|e| anyhow::anyhow!("Failed to save thumbnail: {}", e))?;
+            }
+        }
+        Ok(thumbnail_path)
+    }
+    
+    fn formats(&self) -> Vec<ThumbnailFormat> {
+        self.formats.clone()
+    }
+    
+    fn signature(&self) -> String {
+        let sizes: Vec<String> = [ThumbnailSize::Xl, ThumbnailSize::L, ThumbnailSize::M, ThumbnailSize::S]
+            .iter()
+            .map(|size| format!("{}:{}", size.as_str(), size.width()))
+            .collect();
+        let formats: Vec<&str> = self.formats.iter().map(|format| format.as_str()).collect();
+        format!("sizes={};quality={};formats={}", sizes.join(","), self.quality, formats.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_thumbnail_format() {
+        let all = [ThumbnailFormat::Webp, ThumbnailFormat::Avif];
+        let chrome = "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8";
+        assert_eq!(negotiate_thumbnail_format(chrome, &all), Some(ThumbnailFormat::Avif));
+        assert_eq!(negotiate_thumbnail_format(chrome, &[ThumbnailFormat::Webp]), Some(ThumbnailFormat::Webp));
+        assert_eq!(negotiate_thumbnail_format("image/avif;q=0, image/webp", &all), Some(ThumbnailFormat::Webp));
+        assert_eq!(negotiate_thumbnail_format("image/*,*/*", &all), None);
+    }
 }
 
@@ -0,0 +1,208 @@
+use crate::attachment::AttachmentService;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flow_api::extension::{scan_all_pages, ListOptions};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 任务正在运行、不能重复启动时的错误信息前缀
+pub const THUMBNAIL_JOB_RUNNING_ERROR: &str = "Thumbnail regeneration is already running";
+
+/// 单个附件的缩略图重新生成结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailRegeneration {
+    /// 已重新生成
+    Regenerated,
+    /// 缩略图配置未变化且文件齐全，已跳过
+    Skipped,
+    /// 不支持生成缩略图（远程存储、非图片等）
+    Unsupported,
+}
+
+/// 缩略图重新生成任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ThumbnailJobState {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// 重新生成失败的附件
+#[derive(Debug, Clone, Serialize)]
+pub struct ThumbnailJobFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// 缩略图重新生成任务进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailJobProgress {
+    pub state: ThumbnailJobState,
+    /// 是否重新生成所有附件（包括缩略图已是最新的附件）
+    pub force: bool,
+    /// 附件总数
+    pub total: usize,
+    /// 已处理的附件数量
+    pub processed: usize,
+    pub regenerated: usize,
+    pub skipped: usize,
+    pub unsupported: usize,
+    pub failed: Vec<ThumbnailJobFailure>,
+    /// 任务失败的原因（如列出附件失败）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl ThumbnailJobProgress {
+    fn new(force: bool) -> Self {
+        Self {
+            state: ThumbnailJobState::Running,
+            force,
+            total: 0,
+            processed: 0,
+            regenerated: 0,
+            skipped: 0,
+            unsupported: 0,
+            failed: Vec::new(),
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    /// 记录单个附件的处理结果
+    fn record(&mut self, name: &str, result: &Result<Option<ThumbnailRegeneration>>) {
+        self.processed += 1;
+        match result {
+            Ok(Some(ThumbnailRegeneration::Regenerated)) => self.regenerated += 1,
+            Ok(Some(ThumbnailRegeneration::Skipped)) => self.skipped += 1,
+            // 列出后被删除的附件不再需要缩略图
+            Ok(Some(ThumbnailRegeneration::Unsupported)) | Ok(None) => self.unsupported += 1,
+            Err(e) => self.failed.push(ThumbnailJobFailure { name: name.to_string(), error: e.to_string() }),
+        }
+    }
+
+    fn finish(&mut self, error: Option<String>) {
+        self.state = if error.is_some() { ThumbnailJobState::Failed } else { ThumbnailJobState::Completed };
+        self.error = error;
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+/// 缩略图重新生成任务服务trait
+#[async_trait]
+pub trait ThumbnailJobService: Send + Sync {
+    /// 在后台启动重新生成所有附件缩略图的任务，返回初始进度
+    ///
+    /// force为false时跳过缩略图已是最新的附件；已有任务运行时返回错误
+    async fn start(&self, force: bool) -> Result<ThumbnailJobProgress>;
+
+    /// 获取当前或最近一次任务的进度
+    async fn progress(&self) -> Option<ThumbnailJobProgress>;
+}
+
+/// 默认缩略图重新生成任务服务实现
+pub struct DefaultThumbnailJobService {
+    attachment_service: Arc<dyn AttachmentService>,
+    progress: Arc<RwLock<Option<ThumbnailJobProgress>>>,
+}
+
+impl DefaultThumbnailJobService {
+    pub fn new(attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self {
+            attachment_service,
+            progress: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+/// 逐个重新生成附件的缩略图并更新进度
+async fn run_job(
+    attachment_service: Arc<dyn AttachmentService>,
+    progress: Arc<RwLock<Option<ThumbnailJobProgress>>>,
+    force: bool,
+) -> Result<()> {
+    let names: Vec<String> = scan_all_pages(ListOptions::default(), |options| attachment_service.list(options)).await?
+        .into_iter()
+        .map(|a| a.metadata.name)
+        .collect();
+    if let Some(progress) = progress.write().await.as_mut() {
+        progress.total = names.len();
+    }
+
+    for name in names {
+        let result = attachment_service.regenerate_thumbnails(&name, force).await;
+        if let Err(e) = &result {
+            tracing::warn!("Failed to regenerate thumbnails of attachment {}: {}", name, e);
+        }
+        if let Some(progress) = progress.write().await.as_mut() {
+            progress.record(&name, &result);
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ThumbnailJobService for DefaultThumbnailJobService {
+    async fn start(&self, force: bool) -> Result<ThumbnailJobProgress> {
+        let initial = {
+            let mut progress = self.progress.write().await;
+            if progress.as_ref().is_some_and(|p| p.state == ThumbnailJobState::Running) {
+                anyhow::bail!(THUMBNAIL_JOB_RUNNING_ERROR);
+            }
+            let initial = ThumbnailJobProgress::new(force);
+            *progress = Some(initial.clone());
+            initial
+        };
+
+        let attachment_service = self.attachment_service.clone();
+        let progress = self.progress.clone();
+        tokio::spawn(async move {
+            let error = run_job(attachment_service, progress.clone(), force).await.err();
+            if let Some(e) = &error {
+                tracing::warn!("Thumbnail regeneration failed: {}", e);
+            }
+            if let Some(progress) = progress.write().await.as_mut() {
+                progress.finish(error.map(|e| e.to_string()));
+                tracing::info!(
+                    "Thumbnail regeneration finished: {} regenerated, {} skipped, {} failed",
+                    progress.regenerated,
+                    progress.skipped,
+                    progress.failed.len()
+                );
+            }
+        });
+        Ok(initial)
+    }
+
+    async fn progress(&self) -> Option<ThumbnailJobProgress> {
+        self.progress.read().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_record() {
+        let mut progress = ThumbnailJobProgress::new(false);
+        progress.record("a", &Ok(Some(ThumbnailRegeneration::Regenerated)));
+        progress.record("b", &Ok(Some(ThumbnailRegeneration::Skipped)));
+        progress.record("c", &Ok(None));
+        progress.record("d", &Err(anyhow::anyhow!("broken image")));
+        assert_eq!((progress.processed, progress.regenerated, progress.skipped, progress.unsupported), (4, 1, 1, 1));
+        assert_eq!(progress.failed[0].name, "d");
+
+        progress.finish(None);
+        assert_eq!(progress.state, ThumbnailJobState::Completed);
+        assert!(progress.finished_at.is_some());
+    }
+}
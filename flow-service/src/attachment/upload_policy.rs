@@ -0,0 +1,233 @@
+use crate::attachment::remote_import::media_type_allowed;
+use serde::Serialize;
+
+/// 上传校验失败错误信息的前缀
+pub const UPLOAD_VALIDATION_ERROR: &str = "Upload validation failed";
+
+/// 存储策略配置中单个文件最大大小的键，值为字节数或带单位的字符串（如 "10MB"），0表示不限制
+pub const MAX_FILE_SIZE_SETTING: &str = "maxFileSize";
+
+/// 存储策略配置中允许的媒体类型的键，支持 `image/*` 形式的通配
+pub const ALLOWED_MEDIA_TYPES_SETTING: &str = "allowedMediaTypes";
+
+/// 存储策略配置中允许的扩展名的键
+pub const ALLOWED_EXTENSIONS_SETTING: &str = "allowedExtensions";
+
+/// 识别文件类型时读取的文件头长度
+pub const SNIFF_LENGTH: usize = 8192;
+
+/// 无法识别的文件内容的媒体类型
+const UNKNOWN_MEDIA_TYPE: &str = "application/octet-stream";
+
+/// 上传校验错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UploadViolationCode {
+    FileTooLarge,
+    MediaTypeNotAllowed,
+    ExtensionNotAllowed,
+    NotAnImage,
+}
+
+/// 单条上传校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadViolation {
+    pub code: UploadViolationCode,
+    pub message: String,
+}
+
+/// 上传校验失败时返回的聚合错误
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadValidationError {
+    /// 按文件内容识别出的媒体类型
+    pub detected_media_type: String,
+    pub violations: Vec<UploadViolation>,
+}
+
+impl UploadValidationError {
+    /// 是否因文件过大被拒绝
+    pub fn is_too_large(&self) -> bool {
+        self.violations.iter().any(|v| v.code == UploadViolationCode::FileTooLarge)
+    }
+}
+
+impl std::fmt::Display for UploadValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<&str> = self.violations.iter().map(|v| v.message.as_str()).collect();
+        write!(f, "{}: {}", UPLOAD_VALIDATION_ERROR, messages.join("; "))
+    }
+}
+
+impl std::error::Error for UploadValidationError {}
+
+/// 存储策略和分组对上传文件的限制
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadRestrictions {
+    pub max_file_size: Option<u64>,
+    /// 为空表示不限制
+    pub allowed_media_types: Vec<String>,
+    /// 为空表示不限制，不含点号、小写
+    pub allowed_extensions: Vec<String>,
+    pub image_only: bool,
+}
+
+impl UploadRestrictions {
+    /// 从存储策略配置中读取限制
+    pub fn from_settings(settings: &serde_json::Value) -> Self {
+        let strings = |key: &str| -> Vec<String> {
+            settings.get(key)
+                .and_then(|v| v.as_array())
+                .map(|values| values.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|v| v.trim().trim_start_matches('.').to_ascii_lowercase())
+                    .filter(|v| !v.is_empty())
+                    .collect())
+                .unwrap_or_default()
+        };
+        let max_file_size = settings.get(MAX_FILE_SIZE_SETTING).and_then(|v| match v {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => parse_size(s),
+            _ => None,
+        });
+        Self {
+            max_file_size: max_file_size.filter(|size| *size > 0),
+            allowed_media_types: strings(ALLOWED_MEDIA_TYPES_SETTING),
+            allowed_extensions: strings(ALLOWED_EXTENSIONS_SETTING),
+            image_only: false,
+        }
+    }
+
+    /// 校验上传的文件，media_type为按内容识别出的媒体类型
+    pub fn validate(&self, filename: &str, size: u64, media_type: &str) -> Result<(), UploadValidationError> {
+        let mut violations = Vec::new();
+        if let Some(max_file_size) = self.max_file_size.filter(|max| size > *max) {
+            violations.push(UploadViolation {
+                code: UploadViolationCode::FileTooLarge,
+                message: format!("File size {} exceeds the limit of {} bytes", size, max_file_size),
+            });
+        }
+        if !self.allowed_media_types.is_empty() && !media_type_allowed(media_type, &self.allowed_media_types) {
+            violations.push(UploadViolation {
+                code: UploadViolationCode::MediaTypeNotAllowed,
+                message: format!("Media type {} is not allowed, allowed: {}", media_type, self.allowed_media_types.join(", ")),
+            });
+        }
+        let extension = extension_of(filename);
+        if !self.allowed_extensions.is_empty() && !extension.as_ref().is_some_and(|e| self.allowed_extensions.contains(e)) {
+            violations.push(UploadViolation {
+                code: UploadViolationCode::ExtensionNotAllowed,
+                message: format!(
+                    "Extension {} is not allowed, allowed: {}",
+                    extension.as_deref().unwrap_or("(none)"),
+                    self.allowed_extensions.join(", ")
+                ),
+            });
+        }
+        if self.image_only && !media_type.starts_with("image/") {
+            violations.push(UploadViolation {
+                code: UploadViolationCode::NotAnImage,
+                message: format!("Only images can be uploaded to this group, got {}", media_type),
+            });
+        }
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(UploadValidationError { detected_media_type: media_type.to_string(), violations })
+    }
+}
+
+/// 解析带单位的文件大小，如 "512KB"、"10 MB"、"1GB"，不带单位时为字节数
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_ascii_uppercase();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier = match unit.trim() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// 文件扩展名（小写）
+fn extension_of(filename: &str) -> Option<String> {
+    std::path::Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// 按文件内容识别媒体类型，不信任客户端提供的类型
+///
+/// 二进制格式按文件头识别；内容为文本时识别SVG、HTML、XML，其他文本按扩展名推断；无法识别时返回 `application/octet-stream`
+pub fn sniff_media_type(head: &[u8], filename: &str) -> String {
+    let kind = infer::get(head);
+    if let Some(kind) = kind.filter(|k| k.matcher_type() != infer::MatcherType::Text) {
+        return kind.mime_type().to_string();
+    }
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        // 文件头可能在多字节字符中间截断
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+        Err(_) => return UNKNOWN_MEDIA_TYPE.to_string(),
+    };
+    if text.contains('\0') {
+        return UNKNOWN_MEDIA_TYPE.to_string();
+    }
+    // SVG同时是XML，按内容优先识别为图片
+    if text.contains("<svg") {
+        return "image/svg+xml".to_string();
+    }
+    if let Some(kind) = kind {
+        return kind.mime_type().to_string();
+    }
+    mime_guess::from_path(filename).first()
+        .map(|m| m.essence_str().to_string())
+        .filter(|m| m.starts_with("text/") || matches!(m.as_str(), "application/json" | "application/javascript"))
+        .unwrap_or_else(|| "text/plain".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_media_type_ignores_extension_for_binary() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D, b'I', b'H', b'D', b'R'];
+        assert_eq!(sniff_media_type(&png, "photo.txt"), "image/png");
+        assert_eq!(sniff_media_type(b"<html><script>alert(1)</script>", "photo.jpg"), "text/html");
+        assert_eq!(sniff_media_type(b"<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\"/>", "a.svg"), "image/svg+xml");
+        assert_eq!(sniff_media_type(b"body { color: red }", "style.css"), "text/css");
+        assert_eq!(sniff_media_type(&[0, 1, 2, 0xff, 0xfe], "a.jpg"), UNKNOWN_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn test_validate_restrictions() {
+        let settings = serde_json::json!({
+            "maxFileSize": "1KB",
+            "allowedMediaTypes": ["image/*", "application/pdf"],
+            "allowedExtensions": [".JPG", "png", "pdf"],
+        });
+        let restrictions = UploadRestrictions::from_settings(&settings);
+        assert_eq!(restrictions.max_file_size, Some(1024));
+        assert!(restrictions.validate("a.jpg", 100, "image/jpeg").is_ok());
+
+        let error = restrictions.validate("a.exe", 2048, "application/x-msdownload").unwrap_err();
+        let codes: Vec<_> = error.violations.iter().map(|v| v.code).collect();
+        assert_eq!(codes, vec![
+            UploadViolationCode::FileTooLarge,
+            UploadViolationCode::MediaTypeNotAllowed,
+            UploadViolationCode::ExtensionNotAllowed,
+        ]);
+        assert!(error.is_too_large());
+
+        let image_only = UploadRestrictions { image_only: true, ..Default::default() };
+        assert!(image_only.validate("a.pdf", 1, "application/pdf").is_err());
+        assert_eq!(parse_size("0"), Some(0));
+        assert_eq!(parse_size("10 mb"), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("ten"), None);
+    }
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 上传的文件被检测为感染病毒
+pub const INFECTED_FILE_ERROR: &str = "Infected file rejected";
+
+/// 附件被检测为感染病毒的通知原因
+pub const REASON_ATTACHMENT_INFECTED: &str = "attachment-infected";
+
+/// clamd INSTREAM每次发送的数据块大小
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 扫描结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// 感染，附带病毒特征名称
+    Infected(String),
+}
+
+/// 上传文件扫描器trait，由AttachmentService在保存文件前调用
+#[async_trait]
+pub trait UploadScanner: Send + Sync {
+    /// 扫描器名称
+    fn name(&self) -> &str;
+
+    /// 扫描暂存的上传文件
+    async fn scan(&self, path: &Path) -> Result<ScanVerdict>;
+}
+
+/// 检测到感染文件后的处理方式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InfectedFileAction {
+    /// 直接拒绝上传
+    #[default]
+    Reject,
+    /// 拒绝上传并将文件隔离到指定目录，供管理员复查
+    Quarantine(PathBuf),
+}
+
+/// ClamAV配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClamAvConfig {
+    /// clamd的TCP地址，如 127.0.0.1:3310
+    pub address: String,
+    /// 超时时间（秒）
+    #[serde(default = "default_clamd_timeout")]
+    pub timeout: u64,
+}
+
+fn default_clamd_timeout() -> u64 {
+    30
+}
+
+/// 解析clamd的扫描响应（如 `stream: OK`、`stream: Eicar-Signature FOUND`）
+pub fn parse_clamd_response(response: &str) -> Result<ScanVerdict> {
+    let response = response.trim_end_matches(['\0', '\n']).trim();
+    let result = response.strip_prefix("stream:").map(str::trim).unwrap_or(response);
+    if result == "OK" {
+        return Ok(ScanVerdict::Clean);
+    }
+    if let Some(signature) = result.strip_suffix("FOUND") {
+        return Ok(ScanVerdict::Infected(signature.trim().to_string()));
+    }
+    anyhow::bail!("Unexpected clamd response: {}", response)
+}
+
+/// 通过clamd TCP协议（INSTREAM）扫描文件的ClamAV扫描器
+pub struct ClamAvScanner {
+    config: ClamAvConfig,
+}
+
+impl ClamAvScanner {
+    pub fn new(config: ClamAvConfig) -> Self {
+        Self { config }
+    }
+
+    async fn instream(&self, path: &Path) -> Result<String> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut stream = TcpStream::connect(&self.config.address).await?;
+        stream.write_all(b"zINSTREAM\0").await?;
+        let mut chunk = vec![0u8; CLAMD_CHUNK_SIZE];
+        loop {
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            stream.write_all(&(read as u32).to_be_bytes()).await?;
+            stream.write_all(&chunk[..read]).await?;
+        }
+        // 长度为0的数据块表示结束
+        stream.write_all(&0u32.to_be_bytes()).await?;
+        stream.flush().await?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await?;
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+}
+
+#[async_trait]
+impl UploadScanner for ClamAvScanner {
+    fn name(&self) -> &str {
+        "clamav"
+    }
+
+    async fn scan(&self, path: &Path) -> Result<ScanVerdict> {
+        let response = tokio::time::timeout(Duration::from_secs(self.config.timeout), self.instream(path)).await
+            .map_err(|_| anyhow::anyhow!("Timed out scanning with clamd at {}", self.config.address))?
+            .map_err(|e| anyhow::anyhow!("Failed to scan with clamd at {}: {}", self.config.address, e))?;
+        parse_clamd_response(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clamd_response() {
+        assert_eq!(parse_clamd_response("stream: OK\0").unwrap(), ScanVerdict::Clean);
+        assert_eq!(
+            parse_clamd_response("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+            ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string())
+        );
+        assert!(parse_clamd_response("INSTREAM size limit exceeded. ERROR\0").is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::attachment::{AttachmentService, QuotaService};
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use chrono::{DateTime, Duration, Utc};
+use flow_infra::attachment::file_stream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// 支持的tus协议版本
+pub const TUS_VERSION: &str = "1.0.0";
+
+/// 默认允许的最大文件大小（2GiB）
+pub const DEFAULT_MAX_UPLOAD_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// 上传会话的默认有效期（小时）
+const SESSION_TTL_HOURS: i64 = 24;
+
+/// 写入位置与会话当前偏移量不一致
+pub const UPLOAD_OFFSET_MISMATCH_ERROR: &str = "Upload offset mismatch";
+
+/// 写入的数据超过了声明的文件大小
+pub const UPLOAD_LENGTH_EXCEEDED_ERROR: &str = "Upload length exceeded";
+
+/// 分片上传会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSession {
+    pub id: String,
+    pub filename: String,
+    pub media_type: Option<String>,
+    pub owner_name: Option<String>,
+    pub policy_name: Option<String>,
+    pub group_name: Option<String>,
+    /// 文件总大小
+    pub length: u64,
+    /// 已接收的字节数
+    pub offset: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 上传完成后生成的附件名称
+    pub attachment_name: Option<String>,
+}
+
+impl UploadSession {
+    pub fn is_complete(&self) -> bool {
+        self.offset >= self.length
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+}
+
+/// 创建上传会话的参数
+#[derive(Debug, Clone, Default)]
+pub struct NewUploadSession {
+    pub filename: String,
+    pub media_type: Option<String>,
+    pub owner_name: Option<String>,
+    pub policy_name: Option<String>,
+    pub group_name: Option<String>,
+    pub length: u64,
+}
+
+/// 解析tus的Upload-Metadata请求头（逗号分隔的 `key base64(value)` 键值对）
+pub fn parse_upload_metadata(header: &str) -> HashMap<String, String> {
+    header.split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.trim().splitn(2, ' ');
+            let key = parts.next().filter(|k| !k.is_empty())?;
+            let value = match parts.next() {
+                Some(encoded) => String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?,
+                None => String::new(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// 分片上传会话服务trait
+#[async_trait]
+pub trait UploadSessionService: Send + Sync {
+    /// 允许的最大文件大小
+    fn max_size(&self) -> u64;
+
+    /// 创建上传会话
+    async fn create(&self, session: NewUploadSession) -> Result<UploadSession>;
+
+    /// 获取上传会话，不存在或已过期时返回None
+    async fn get(&self, id: &str) -> Result<Option<UploadSession>>;
+
+    /// 从指定偏移量追加分片，接收完整后生成附件
+    async fn append(&self, id: &str, offset: u64, chunk: &[u8]) -> Result<UploadSession>;
+
+    /// 终止上传并删除已接收的数据
+    async fn terminate(&self, id: &str) -> Result<()>;
+}
+
+/// 默认分片上传会话服务实现
+///
+/// 会话信息和已接收的数据保存在临时目录（`{id}.json` 和 `{id}.part`），服务重启后仍可续传
+pub struct DefaultUploadSessionService {
+    attachment_service: Arc<dyn AttachmentService>,
+    session_dir: PathBuf,
+    max_size: u64,
+    quota_service: Option<Arc<dyn QuotaService>>,
+    /// 每个会话的写入锁，防止同一会话的分片并发写入
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl DefaultUploadSessionService {
+    pub fn new(attachment_service: Arc<dyn AttachmentService>, session_dir: PathBuf) -> Self {
+        Self {
+            attachment_service,
+            session_dir,
+            max_size: DEFAULT_MAX_UPLOAD_SIZE,
+            quota_service: None,
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 设置允许的最大文件大小
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// 设置存储配额服务，创建会话时提前检查配额
+    pub fn with_quota_service(mut self, quota_service: Arc<dyn QuotaService>) -> Self {
+        self.quota_service = Some(quota_service);
+        self
+    }
+
+    fn session_path(&self, id: &str) -> PathBuf {
+        self.session_dir.join(format!("{}.json", id))
+    }
+
+    fn data_path(&self, id: &str) -> PathBuf {
+        self.session_dir.join(format!("{}.part", id))
+    }
+
+    async fn lock(&self, id: &str) -> Arc<Mutex<()>> {
+        self.locks.lock().await.entry(id.to_string()).or_default().clone()
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<UploadSession>> {
+        // 会话ID由服务生成，拒绝可能越出临时目录的ID
+        if Uuid::parse_str(id).is_err() {
+            return Ok(None);
+        }
+        match tokio::fs::read(self.session_path(id)).await {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn store(&self, session: &UploadSession) -> Result<()> {
+        tokio::fs::write(self.session_path(&session.id), serde_json::to_vec(session)?).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<()> {
+        for path in [self.session_path(id), self.data_path(id)] {
+            if tokio::fs::try_exists(&path).await? {
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+        self.locks.lock().await.remove(id);
+        Ok(())
+    }
+
+    /// 清理过期的会话
+    async fn remove_expired(&self) -> Result<()> {
+        let mut entries = tokio::fs::read_dir(&self.session_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let expired = match self.load(&id).await {
+                Ok(Some(session)) => session.is_expired(),
+                Ok(None) => false,
+                Err(_) => true,
+            };
+            if expired {
+                self.remove(&id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 将接收完整的数据生成附件
+    async fn finish(&self, session: &mut UploadSession) -> Result<()> {
+        let content = file_stream(&self.data_path(&session.id), 0, None).await?;
+        let attachment = self.attachment_service.upload(
+            content,
+            session.filename.clone(),
+            session.media_type.clone(),
+            session.owner_name.clone(),
+            session.policy_name.clone(),
+            session.group_name.clone(),
+        ).await?;
+        session.attachment_name = Some(attachment.metadata.name);
+        tokio::fs::remove_file(self.data_path(&session.id)).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UploadSessionService for DefaultUploadSessionService {
+    fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    async fn create(&self, new_session: NewUploadSession) -> Result<UploadSession> {
+        if new_session.length > self.max_size {
+            anyhow::bail!("{}: {} > {}", UPLOAD_LENGTH_EXCEEDED_ERROR, new_session.length, self.max_size);
+        }
+        if let Some(quota_service) = &self.quota_service {
+            quota_service.check(new_session.owner_name.as_deref(), new_session.group_name.as_deref(), new_session.length).await?;
+        }
+        tokio::fs::create_dir_all(&self.session_dir).await?;
+        if let Err(e) = self.remove_expired().await {
+            tracing::warn!("Failed to remove expired upload sessions: {}", e);
+        }
+
+        let now = Utc::now();
+        let mut session = UploadSession {
+            id: Uuid::new_v4().to_string(),
+            filename: new_session.filename,
+            media_type: new_session.media_type,
+            owner_name: new_session.owner_name,
+            policy_name: new_session.policy_name,
+            group_name: new_session.group_name,
+            length: new_session.length,
+            offset: 0,
+            created_at: now,
+            expires_at: now + Duration::hours(SESSION_TTL_HOURS),
+            attachment_name: None,
+        };
+        tokio::fs::write(self.data_path(&session.id), b"").await?;
+        // 空文件无需分片，直接生成附件
+        if session.is_complete() {
+            self.finish(&mut session).await?;
+        }
+        self.store(&session).await?;
+        Ok(session)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<UploadSession>> {
+        Ok(self.load(id).await?.filter(|s| !s.is_expired()))
+    }
+
+    async fn append(&self, id: &str, offset: u64, chunk: &[u8]) -> Result<UploadSession> {
+        let lock = self.lock(id).await;
+        let _guard = lock.lock().await;
+
+        let mut session = self.get(id).await?
+            .ok_or_else(|| anyhow::anyhow!("Upload session not found: {}", id))?;
+        if offset != session.offset || session.is_complete() {
+            anyhow::bail!("{}: expected {}, got {}", UPLOAD_OFFSET_MISMATCH_ERROR, session.offset, offset);
+        }
+        if offset + chunk.len() as u64 > session.length {
+            anyhow::bail!("{}: {} > {}", UPLOAD_LENGTH_EXCEEDED_ERROR, offset + chunk.len() as u64, session.length);
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(self.data_path(id))
+            .await?;
+        // 上次写入中断时文件可能多于已记录的偏移量，截断后再追加
+        if file.metadata().await?.len() != session.offset {
+            file.set_len(session.offset).await?;
+        }
+        file.write_all(chunk).await?;
+        file.flush().await?;
+        session.offset += chunk.len() as u64;
+
+        if session.is_complete() {
+            self.finish(&mut session).await?;
+        }
+        self.store(&session).await?;
+        Ok(session)
+    }
+
+    async fn terminate(&self, id: &str) -> Result<()> {
+        let lock = self.lock(id).await;
+        let _guard = lock.lock().await;
+        if self.load(id).await?.is_none() {
+            anyhow::bail!("Upload session not found: {}", id);
+        }
+        self.remove(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_upload_metadata() {
+        let metadata = parse_upload_metadata("filename d29ybGRfZG9taW5hdGlvbl9wbGFuLnBkZg==,is_confidential, filetype YXBwbGljYXRpb24vcGRm");
+        assert_eq!(metadata.get("filename").map(String::as_str), Some("world_domination_plan.pdf"));
+        assert_eq!(metadata.get("filetype").map(String::as_str), Some("application/pdf"));
+        assert_eq!(metadata.get("is_confidential").map(String::as_str), Some(""));
+        assert!(parse_upload_metadata("").is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::attachment::image_metadata::{IMAGE_HEIGHT_ANNO, IMAGE_WIDTH_ANNO};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 视频时长（秒）的注解
+pub const VIDEO_DURATION_ANNO: &str = "storage.halo.run/video-duration";
+
+/// 视频的时长和分辨率
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// 时长（秒）
+    pub duration: Option<f64>,
+}
+
+impl VideoMetadata {
+    /// 转换为附件注解，宽高与图片使用相同的注解
+    pub fn to_annotations(&self) -> HashMap<String, String> {
+        let mut annotations = HashMap::new();
+        if let Some(width) = self.width {
+            annotations.insert(IMAGE_WIDTH_ANNO.to_string(), width.to_string());
+        }
+        if let Some(height) = self.height {
+            annotations.insert(IMAGE_HEIGHT_ANNO.to_string(), height.to_string());
+        }
+        if let Some(duration) = self.duration {
+            annotations.insert(VIDEO_DURATION_ANNO.to_string(), format!("{:.3}", duration));
+        }
+        annotations
+    }
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+}
+
+/// 解析 `ffprobe -of json -show_entries stream=width,height:format=duration` 的输出
+pub fn parse_ffprobe_output(output: &str) -> Result<VideoMetadata> {
+    let output: FfprobeOutput = serde_json::from_str(output)
+        .map_err(|e| anyhow::anyhow!("Failed to parse ffprobe output: {}", e))?;
+    let stream = output.streams.into_iter().find(|s| s.width.is_some() && s.height.is_some());
+    Ok(VideoMetadata {
+        width: stream.as_ref().and_then(|s| s.width),
+        height: stream.as_ref().and_then(|s| s.height),
+        duration: output.format
+            .and_then(|f| f.duration)
+            .and_then(|d| d.parse::<f64>().ok())
+            .filter(|d| d.is_finite() && *d >= 0.0),
+    })
+}
+
+/// 截取封面帧的时间点：默认取配置的时间点，视频较短时取中间位置
+pub fn poster_offset(poster_at: f64, duration: Option<f64>) -> f64 {
+    match duration {
+        Some(duration) if duration < poster_at * 2.0 => duration / 2.0,
+        _ => poster_at,
+    }
+}
+
+/// 视频处理trait，用于生成视频封面和解析视频信息
+#[async_trait]
+pub trait VideoProcessor: Send + Sync {
+    /// 解析视频的时长和分辨率
+    async fn probe(&self, source_path: &Path) -> Result<VideoMetadata>;
+
+    /// 截取一帧保存为JPEG封面
+    async fn poster_frame(&self, source_path: &Path, output_path: &Path, duration: Option<f64>) -> Result<()>;
+}
+
+/// ffmpeg配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegConfig {
+    /// ffmpeg可执行文件路径
+    #[serde(default = "default_ffmpeg")]
+    pub ffmpeg: String,
+    /// ffprobe可执行文件路径
+    #[serde(default = "default_ffprobe")]
+    pub ffprobe: String,
+    /// 截取封面帧的时间点（秒）
+    #[serde(default = "default_poster_at")]
+    pub poster_at: f64,
+}
+
+fn default_ffmpeg() -> String {
+    "ffmpeg".to_string()
+}
+
+fn default_ffprobe() -> String {
+    "ffprobe".to_string()
+}
+
+fn default_poster_at() -> f64 {
+    1.0
+}
+
+impl Default for FfmpegConfig {
+    fn default() -> Self {
+        Self {
+            ffmpeg: default_ffmpeg(),
+            ffprobe: default_ffprobe(),
+            poster_at: default_poster_at(),
+        }
+    }
+}
+
+/// 调用ffmpeg/ffprobe命令处理视频，需要启用video-thumbnails特性
+#[cfg(feature = "video-thumbnails")]
+pub struct FfmpegVideoProcessor {
+    config: FfmpegConfig,
+}
+
+#[cfg(feature = "video-thumbnails")]
+impl FfmpegVideoProcessor {
+    pub fn new(config: FfmpegConfig) -> Self {
+        Self { config }
+    }
+
+    async fn run(program: &str, args: &[&std::ffi::OsStr]) -> Result<Vec<u8>> {
+        let output = tokio::process::Command::new(program)
+            .args(args)
+            .kill_on_drop(true)
+            .output().await
+            .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e))?;
+        if !output.status.success() {
+            anyhow::bail!("{} exited with {}: {}", program, output.status, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(output.stdout)
+    }
+}
+
+#[cfg(feature = "video-thumbnails")]
+#[async_trait]
+impl VideoProcessor for FfmpegVideoProcessor {
+    async fn probe(&self, source_path: &Path) -> Result<VideoMetadata> {
+        let stdout = Self::run(&self.config.ffprobe, &[
+            "-v".as_ref(), "error".as_ref(),
+            "-select_streams".as_ref(), "v:0".as_ref(),
+            "-show_entries".as_ref(), "stream=width,height:format=duration".as_ref(),
+            "-of".as_ref(), "json".as_ref(),
+            source_path.as_os_str(),
+        ]).await?;
+        parse_ffprobe_output(&String::from_utf8_lossy(&stdout))
+    }
+
+    async fn poster_frame(&self, source_path: &Path, output_path: &Path, duration: Option<f64>) -> Result<()> {
+        let offset = poster_offset(self.config.poster_at, duration).to_string();
+        Self::run(&self.config.ffmpeg, &[
+            "-v".as_ref(), "error".as_ref(), "-y".as_ref(),
+            "-ss".as_ref(), offset.as_ref(),
+            "-i".as_ref(), source_path.as_os_str(),
+            "-frames:v".as_ref(), "1".as_ref(),
+            "-q:v".as_ref(), "2".as_ref(),
+            output_path.as_os_str(),
+        ]).await?;
+        if !output_path.exists() {
+            anyhow::bail!("ffmpeg did not produce a poster frame for {}", source_path.display());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffprobe_output() {
+        let output = r#"{"programs":[],"streams":[{"width":1920,"height":1080}],"format":{"duration":"12.345000"}}"#;
+        let metadata = parse_ffprobe_output(output).unwrap();
+        assert_eq!(metadata, VideoMetadata { width: Some(1920), height: Some(1080), duration: Some(12.345) });
+        let annotations = metadata.to_annotations();
+        assert_eq!(annotations[VIDEO_DURATION_ANNO], "12.345");
+        assert_eq!(annotations[IMAGE_WIDTH_ANNO], "1920");
+
+        let metadata = parse_ffprobe_output(r#"{"streams":[],"format":{"duration":"N/A"}}"#).unwrap();
+        assert_eq!(metadata, VideoMetadata::default());
+        assert!(parse_ffprobe_output("not json").is_err());
+    }
+
+    #[test]
+    fn test_poster_offset() {
+        assert_eq!(poster_offset(1.0, Some(60.0)), 1.0);
+        assert_eq!(poster_offset(1.0, Some(1.0)), 0.5);
+        assert_eq!(poster_offset(1.0, None), 1.0);
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Datelike, Utc};
+use flow_domain::content::{constant, Post};
+use serde::{Deserialize, Serialize};
+use crate::content::ListedPost;
+
+/// 每个月份默认返回的文章数量
+pub const DEFAULT_ARCHIVE_PAGE_SIZE: u32 = 10;
+
+/// 归档查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ArchiveQuery {
+    /// 只返回指定年份
+    pub year: Option<i32>,
+    /// 只返回指定月份（需同时指定年份）
+    pub month: Option<u32>,
+    /// 每个月份内文章的页码（从1开始）
+    pub page: Option<u32>,
+    /// 每个月份内文章的每页数量
+    pub size: Option<u32>,
+}
+
+/// 按年归档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveYear {
+    pub year: i32,
+    pub count: usize,
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// 按月归档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveMonth {
+    pub month: u32,
+    pub count: usize,
+    pub posts: Vec<ListedPost>,
+}
+
+/// 根据发布时间设置归档标签（年、月、日）
+pub fn apply_archive_labels(post: &mut Post, publish_time: DateTime<Utc>) {
+    let labels = post.metadata.labels.get_or_insert_with(Default::default);
+    labels.insert(constant::POST_ARCHIVE_YEAR_LABEL.to_string(), publish_time.year().to_string());
+    labels.insert(constant::POST_ARCHIVE_MONTH_LABEL.to_string(), format!("{:02}", publish_time.month()));
+    labels.insert(constant::POST_ARCHIVE_DAY_LABEL.to_string(), format!("{:02}", publish_time.day()));
+}
+
+/// 获取文章的归档年月，优先使用归档标签，缺失时使用发布时间
+pub fn archive_key(post: &Post) -> Option<(i32, u32)> {
+    let from_labels = post.metadata.labels.as_ref().and_then(|labels| {
+        let year = labels.get(constant::POST_ARCHIVE_YEAR_LABEL)?.parse().ok()?;
+        let month = labels.get(constant::POST_ARCHIVE_MONTH_LABEL)?.parse().ok()?;
+        Some((year, month))
+    });
+    from_labels.or_else(|| post.spec.publish_time.map(|t| (t.year(), t.month())))
+}
+
+/// 将文章按年月分组（均按时间倒序），每个月份内的文章按query分页
+pub fn group_archives(posts: Vec<Post>, query: &ArchiveQuery) -> Vec<ArchiveYear> {
+    let mut keyed: Vec<((i32, u32), Post)> = posts
+        .into_iter()
+        .filter_map(|post| archive_key(&post).map(|key| (key, post)))
+        .filter(|((year, month), _)| {
+            query.year.is_none_or(|y| y == *year) && query.month.is_none_or(|m| m == *month)
+        })
+        .collect();
+    keyed.sort_by(|(a_key, a), (b_key, b)| {
+        b_key.cmp(a_key).then_with(|| b.spec.publish_time.cmp(&a.spec.publish_time))
+    });
+
+    let page = query.page.unwrap_or(1).max(1) as usize;
+    let size = query.size.unwrap_or(DEFAULT_ARCHIVE_PAGE_SIZE).max(1) as usize;
+
+    let mut years: Vec<ArchiveYear> = Vec::new();
+    for ((year, month), post) in keyed {
+        if years.last().is_none_or(|y| y.year != year) {
+            years.push(ArchiveYear { year, count: 0, months: Vec::new() });
+        }
+        let archive_year = years.last_mut().expect("year bucket exists");
+        archive_year.count += 1;
+
+        if archive_year.months.last().is_none_or(|m| m.month != month) {
+            archive_year.months.push(ArchiveMonth { month, count: 0, posts: Vec::new() });
+        }
+        let archive_month = archive_year.months.last_mut().expect("month bucket exists");
+        let index = archive_month.count;
+        archive_month.count += 1;
+        if index >= (page - 1) * size && index < page * size {
+            archive_month.posts.push(ListedPost::new(post));
+        }
+    }
+    years
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::PostSpec;
+
+    fn post(name: &str, year: i32, month: u32, day: u32) -> Post {
+        let mut post = Post {
+            metadata: Metadata::new(name),
+            spec: serde_json::from_value::<PostSpec>(serde_json::json!({
+                "title": name,
+                "slug": name,
+            })).unwrap(),
+            status: None,
+        };
+        post.spec.publish_time = Some(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap());
+        post
+    }
+
+    #[test]
+    fn test_archive_labels() {
+        let mut p = post("a", 2024, 3, 5);
+        apply_archive_labels(&mut p, Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(archive_key(&p), Some((2023, 12)));
+        assert_eq!(p.metadata.labels.unwrap().get(constant::POST_ARCHIVE_MONTH_LABEL).unwrap(), "12");
+    }
+
+    #[test]
+    fn test_group_archives() {
+        let posts = vec![
+            post("a", 2023, 5, 1),
+            post("b", 2024, 1, 2),
+            post("c", 2024, 1, 20),
+            post("d", 2024, 3, 1),
+        ];
+        let years = group_archives(posts, &ArchiveQuery { size: Some(1), ..Default::default() });
+        assert_eq!(years.len(), 2);
+        assert_eq!((years[0].year, years[0].count), (2024, 3));
+        assert_eq!(years[0].months[0].month, 3);
+        let january = &years[0].months[1];
+        assert_eq!(january.count, 2);
+        assert_eq!(january.posts.len(), 1);
+        assert_eq!(january.posts[0].post.metadata.name, "c");
+    }
+
+    #[test]
+    fn test_group_archives_filter_by_year() {
+        let posts = vec![post("a", 2023, 5, 1), post("b", 2024, 1, 2)];
+        let years = group_archives(posts, &ArchiveQuery { year: Some(2023), ..Default::default() });
+        assert_eq!(years.len(), 1);
+        assert_eq!(years[0].months[0].posts[0].post.metadata.name, "a");
+    }
+}
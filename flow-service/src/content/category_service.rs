@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
-use flow_domain::content::Category;
+use flow_domain::content::{constant, Category, SubjectRef};
+use crate::content::{slug_redirect_service, SlugRedirectService};
 use std::sync::Arc;
+use tracing::warn;
 
 /// Category服务trait
 #[async_trait]
@@ -15,11 +17,21 @@ pub trait CategoryService: Send + Sync {
 
 pub struct DefaultCategoryService<C: ExtensionClient> {
     client: Arc<C>,
+    redirect_service: Option<Arc<dyn SlugRedirectService>>,
 }
 
 impl<C: ExtensionClient> DefaultCategoryService<C> {
     pub fn new(client: Arc<C>) -> Self {
-        Self { client }
+        Self {
+            client,
+            redirect_service: None,
+        }
+    }
+    
+    /// 设置重定向服务，slug变更时记录旧链接到新链接的301重定向
+    pub fn with_redirect_service(mut self, redirect_service: Arc<dyn SlugRedirectService>) -> Self {
+        self.redirect_service = Some(redirect_service);
+        self
     }
 }
 
@@ -29,8 +41,43 @@ impl<C: ExtensionClient> CategoryService for DefaultCategoryService<C> {
         self.client.create(category).await
     }
 
-    async fn update(&self, category: Category) -> Result<Category, Box<dyn std::error::Error + Send + Sync>> {
-        self.client.update(category).await
+    async fn update(&self, mut category: Category) -> Result<Category, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(redirect_service) = &self.redirect_service else {
+            return self.client.update(category).await;
+        };
+        
+        // slug变更时同步permalink并记录重定向
+        let old_category = self.client.fetch::<Category>(&category.metadata.name).await?;
+        let change = old_category
+            .filter(|old| old.spec.slug != category.spec.slug)
+            .map(|old| {
+                let old_path = old.status
+                    .and_then(|s| s.permalink)
+                    .unwrap_or_else(|| slug_redirect_service::category_permalink(&old.spec.slug));
+                (old_path, slug_redirect_service::category_permalink(&category.spec.slug))
+            });
+        
+        if let Some((_, new_path)) = &change {
+            let mut status = category.status_or_default();
+            status.permalink = Some(new_path.clone());
+            category.status = Some(status);
+        }
+        
+        let updated = self.client.update(category).await?;
+        
+        if let Some((old_path, new_path)) = change {
+            let subject_ref = SubjectRef {
+                group: constant::GROUP.to_string(),
+                version: constant::VERSION.to_string(),
+                kind: constant::CATEGORY_KIND.to_string(),
+                name: updated.metadata.name.clone(),
+            };
+            if let Err(e) = redirect_service.record(subject_ref, &old_path, &new_path).await {
+                warn!("Failed to record slug redirect for category {}: {}", updated.metadata.name, e);
+            }
+        }
+        
+        Ok(updated)
     }
 
     async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
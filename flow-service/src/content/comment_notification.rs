@@ -0,0 +1,125 @@
+use flow_api::extension::Metadata;
+use flow_domain::content::{constant, Comment, CommentOwner, SubjectRef};
+use flow_domain::notification::{InterestReason, InterestReasonSubject, Reason, ReasonSpec, ReasonSubject, Subscription};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 文章收到新评论
+pub const REASON_NEW_COMMENT_ON_POST: &str = "new-comment-on-post";
+
+/// 评论收到新回复
+pub const REASON_NEW_REPLY_ON_COMMENT: &str = "new-reply-on-comment";
+
+/// 评论所有者对应的订阅者名称（注册用户使用用户名，访客使用邮箱身份）
+pub fn comment_subscriber(owner: &CommentOwner) -> String {
+    if owner.kind == CommentOwner::KIND_EMAIL {
+        CommentOwner::owner_identity(&owner.kind, &owner.name)
+    } else {
+        owner.name.clone()
+    }
+}
+
+/// 评论是否为对另一条评论的回复（主题指向Comment）
+pub fn is_reply(comment: &Comment) -> bool {
+    let subject = &comment.spec.subject_ref;
+    subject.group == constant::GROUP && subject.kind == constant::COMMENT_KIND
+}
+
+/// 主题对应的Reason主题
+pub fn reason_subject(subject_ref: &SubjectRef, title: String, url: Option<String>) -> ReasonSubject {
+    ReasonSubject {
+        api_version: format!("{}/{}", subject_ref.group, subject_ref.version),
+        kind: subject_ref.kind.clone(),
+        name: subject_ref.name.clone(),
+        title,
+        url,
+    }
+}
+
+/// 构造评论产生的通知原因
+pub fn build_reason(reason_type: &str, subject: ReasonSubject, comment: &Comment) -> Reason {
+    let owner = &comment.spec.owner;
+    let mut attributes = HashMap::new();
+    attributes.insert("commentName".to_string(), comment.metadata.name.clone());
+    attributes.insert("commenter".to_string(), owner.display_name.clone().unwrap_or_else(|| owner.name.clone()));
+    attributes.insert("content".to_string(), comment.spec.raw.clone());
+    attributes.insert("subjectTitle".to_string(), subject.title.clone());
+
+    Reason {
+        metadata: Metadata::new(Uuid::new_v4().to_string()),
+        spec: ReasonSpec {
+            reason_type: reason_type.to_string(),
+            subject,
+            author: comment_subscriber(owner),
+            attributes: Some(attributes),
+        },
+    }
+}
+
+/// 对指定主题感兴趣的原因
+pub fn interest_reason(reason_type: &str, subject: &ReasonSubject) -> InterestReason {
+    InterestReason {
+        reason_type: reason_type.to_string(),
+        subject: Some(InterestReasonSubject {
+            name: Some(subject.name.clone()),
+            api_version: subject.api_version.clone(),
+            kind: subject.kind.clone(),
+        }),
+        expression: None,
+    }
+}
+
+/// 检查订阅者是否已订阅该原因
+pub fn is_subscribed(subscriptions: &[Subscription], subscriber: &str, interest: &InterestReason) -> bool {
+    subscriptions.iter().any(|s| {
+        s.spec.subscriber.name == subscriber
+            && s.spec.reason.reason_type == interest.reason_type
+            && s.spec.reason.subject.as_ref().map(|sub| (&sub.kind, &sub.name))
+                == interest.subject.as_ref().map(|sub| (&sub.kind, &sub.name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_domain::notification::{SubscriptionSpec, SubscriptionSubscriber};
+
+    fn owner(kind: &str, name: &str) -> CommentOwner {
+        CommentOwner {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            display_name: None,
+            annotations: None,
+        }
+    }
+
+    #[test]
+    fn test_comment_subscriber() {
+        assert_eq!(comment_subscriber(&owner("User", "admin")), "admin");
+        assert_eq!(comment_subscriber(&owner(CommentOwner::KIND_EMAIL, "a@b.com")), "Email#a@b.com");
+    }
+
+    #[test]
+    fn test_is_subscribed() {
+        let subject = ReasonSubject {
+            api_version: "content.halo.run/v1alpha1".to_string(),
+            kind: "Post".to_string(),
+            name: "post-1".to_string(),
+            title: "Hello".to_string(),
+            url: None,
+        };
+        let interest = interest_reason(REASON_NEW_COMMENT_ON_POST, &subject);
+        let subscriptions = vec![Subscription {
+            metadata: Metadata::new("s1"),
+            spec: SubscriptionSpec {
+                subscriber: SubscriptionSubscriber { name: "admin".to_string() },
+                unsubscribe_token: "token".to_string(),
+                reason: interest.clone(),
+                disabled: None,
+            },
+        }];
+        assert!(is_subscribed(&subscriptions, "admin", &interest));
+        assert!(!is_subscribed(&subscriptions, "guest", &interest));
+        assert!(!is_subscribed(&subscriptions, "admin", &interest_reason(REASON_NEW_REPLY_ON_COMMENT, &subject)));
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use flow_domain::content::{Comment, CommentStatus};
+use flow_infra::cache::Cache;
+use crate::content::CommentService;
+use std::sync::Arc;
+
+/// 支持的回应类型
+pub const REACTION_TYPES: &[&str] = &["like", "heart", "laugh", "hooray", "confused", "rocket"];
+
+/// 去重记录的有效期（1年）
+const DEDUP_TTL_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+/// 检查回应类型是否受支持
+pub fn is_supported_reaction(reaction: &str) -> bool {
+    REACTION_TYPES.contains(&reaction)
+}
+
+/// 调整回应计数，计数不会小于0，为0时移除该类型
+pub fn apply_reaction(status: &mut CommentStatus, reaction: &str, delta: i64) {
+    let reactions = status.reactions.get_or_insert_with(Default::default);
+    let count = reactions.entry(reaction.to_string()).or_insert(0);
+    *count = (*count + delta).max(0);
+    if *count == 0 {
+        reactions.remove(reaction);
+    }
+}
+
+/// 评论回应服务trait
+#[async_trait]
+pub trait CommentReactionService: Send + Sync {
+    /// 添加回应，同一身份（用户名或IP）重复回应不会重复计数
+    async fn react(&self, comment_name: &str, reaction: &str, identity: &str) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 取消回应，未回应过时不做修改
+    async fn unreact(&self, comment_name: &str, reaction: &str, identity: &str) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 默认评论回应服务实现（基于Redis去重）
+pub struct DefaultCommentReactionService {
+    comment_service: Arc<dyn CommentService>,
+    cache: Arc<dyn Cache>,
+    key_prefix: String,
+}
+
+impl DefaultCommentReactionService {
+    pub fn new(comment_service: Arc<dyn CommentService>, cache: Arc<dyn Cache>) -> Self {
+        Self {
+            comment_service,
+            cache,
+            key_prefix: "comment_reaction:".to_string(),
+        }
+    }
+
+    fn dedup_key(&self, comment_name: &str, reaction: &str, identity: &str) -> String {
+        format!("{}{}:{}:{}", self.key_prefix, comment_name, reaction, identity)
+    }
+
+    async fn get_comment(&self, comment_name: &str, reaction: &str) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
+        if !is_supported_reaction(reaction) {
+            return Err(format!("Unsupported reaction: {}", reaction).into());
+        }
+        self.comment_service.get(comment_name).await?
+            .ok_or_else(|| "Comment not found".into())
+    }
+
+    async fn update_count(&self, mut comment: Comment, reaction: &str, delta: i64) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
+        let mut status = comment.status_or_default();
+        apply_reaction(&mut status, reaction, delta);
+        comment.status = Some(status);
+        self.comment_service.update(comment).await
+    }
+}
+
+#[async_trait]
+impl CommentReactionService for DefaultCommentReactionService {
+    async fn react(&self, comment_name: &str, reaction: &str, identity: &str) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
+        let comment = self.get_comment(comment_name, reaction).await?;
+        let key = self.dedup_key(comment_name, reaction, identity);
+        if self.cache.get(&key).await?.is_some() {
+            return Ok(comment);
+        }
+
+        self.cache.set(&key, "1", Some(DEDUP_TTL_SECONDS)).await?;
+        self.update_count(comment, reaction, 1).await
+    }
+
+    async fn unreact(&self, comment_name: &str, reaction: &str, identity: &str) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
+        let comment = self.get_comment(comment_name, reaction).await?;
+        let key = self.dedup_key(comment_name, reaction, identity);
+        if self.cache.get(&key).await?.is_none() {
+            return Ok(comment);
+        }
+
+        self.cache.delete(&key).await?;
+        self.update_count(comment, reaction, -1).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_reaction() {
+        let mut status = CommentStatus::default();
+        apply_reaction(&mut status, "like", 1);
+        apply_reaction(&mut status, "like", 1);
+        apply_reaction(&mut status, "heart", 1);
+        let reactions = status.reactions.as_ref().unwrap();
+        assert_eq!(reactions.get("like"), Some(&2));
+        assert_eq!(reactions.get("heart"), Some(&1));
+
+        apply_reaction(&mut status, "heart", -1);
+        apply_reaction(&mut status, "heart", -1);
+        assert!(!status.reactions.as_ref().unwrap().contains_key("heart"));
+    }
+
+    #[test]
+    fn test_is_supported_reaction() {
+        assert!(is_supported_reaction("like"));
+        assert!(!is_supported_reaction("dislike"));
+    }
+}
@@ -1,8 +1,25 @@
 use async_trait::async_trait;
 use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
-use flow_domain::content::Comment;
+use flow_api::extension::query::Condition;
+use flow_domain::content::{constant, Comment, Post};
+use flow_domain::notification::{InterestReason, Subscription, SubscriptionSubscriber};
+use crate::content::SpamChecker;
+use crate::content::public_comment::{self, CursorPage, PublicComment, PublicCommentQuery, PublicReplyQuery};
+use crate::content::comment_notification::{
+    build_reason, interest_reason, is_reply, is_subscribed, reason_subject,
+    REASON_NEW_COMMENT_ON_POST, REASON_NEW_REPLY_ON_COMMENT,
+};
+use crate::content::slug_redirect_service::post_permalink;
+use crate::notification::NotificationCenter;
+use flow_plugin::event::{Event, EventBus, COMMENT_CREATED};
 use std::sync::Arc;
 
+/// 扫描垃圾评论时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 回复通知中被回复评论标题的最大长度
+const REPLY_SUBJECT_TITLE_LEN: usize = 50;
+
 /// Comment服务trait
 #[async_trait]
 pub trait CommentService: Send + Sync {
@@ -13,22 +30,175 @@ pub trait CommentService: Send + Sync {
     async fn list(&self, options: ListOptions) -> Result<ListResult<Comment>, Box<dyn std::error::Error + Send + Sync>>;
     async fn approve(&self, comment: Comment) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>>;
     async fn list_by_subject(&self, subject_ref: &flow_domain::content::SubjectRef) -> Result<Vec<Comment>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 列出被判定为垃圾评论的评论
+    async fn list_spam(&self) -> Result<Vec<Comment>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 批量审核垃圾评论：is_spam为false时恢复并批准评论，为true时确认并删除，返回处理的数量
+    async fn review_spam(&self, names: &[String], is_spam: bool) -> Result<usize, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 列出主题下对访客可见的评论（已批准、未隐藏），使用游标分页
+    async fn list_public(&self, query: &PublicCommentQuery) -> Result<CursorPage<PublicComment>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 列出评论下对访客可见的回复，评论本身不可见时返回None
+    async fn list_public_replies(&self, comment_name: &str, query: &PublicReplyQuery) -> Result<Option<CursorPage<PublicComment>>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 pub struct DefaultCommentService<C: ExtensionClient> {
     client: Arc<C>,
+    spam_checkers: Vec<Arc<dyn SpamChecker>>,
+    notification_center: Option<Arc<dyn NotificationCenter>>,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl<C: ExtensionClient> DefaultCommentService<C> {
     pub fn new(client: Arc<C>) -> Self {
-        Self { client }
+        Self { client, spam_checkers: Vec::new(), notification_center: None, event_bus: None }
+    }
+
+    /// 设置通知中心，创建评论后发送评论/回复通知并自动订阅
+    pub fn with_notification_center(mut self, notification_center: Arc<dyn NotificationCenter>) -> Self {
+        self.notification_center = Some(notification_center);
+        self
     }
+
+    /// 设置事件总线，创建非垃圾评论后发布 `CommentCreated` 事件
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 注册垃圾评论检测器（可注册多个，任一判定为垃圾即视为垃圾评论）
+    pub fn with_spam_checker(mut self, spam_checker: Arc<dyn SpamChecker>) -> Self {
+        self.spam_checkers.push(spam_checker);
+        self
+    }
+
+    /// 逐页列出subject_names中任一主题下已批准、未隐藏的评论
+    async fn list_visible(&self, group: &str, kind: &str, subject_names: Vec<String>) -> Result<Vec<Comment>, Box<dyn std::error::Error + Send + Sync>> {
+        if subject_names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let condition = visible_condition(group, kind, subject_names);
+        self.client.list_all::<Comment>(ListOptions { condition: Some(condition), ..Default::default() }).await
+    }
+
+    /// 依次调用检测器，检测失败时记录日志并视为正常评论
+    async fn detect_spam(&self, comment: &Comment) -> bool {
+        for checker in &self.spam_checkers {
+            match checker.is_spam(comment).await {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Spam checker {} failed for comment {}: {}", checker.name(), comment.metadata.name, e),
+            }
+        }
+        false
+    }
+
+    /// 订阅者尚未订阅该原因时创建订阅
+    async fn ensure_subscription(
+        &self,
+        center: &dyn NotificationCenter,
+        subscriber: String,
+        interest: InterestReason,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let subscriptions = self.client.list_all::<Subscription>(ListOptions {
+            condition: Some(Condition::Equal {
+                index_name: "spec.subscriber.name".to_string(),
+                value: serde_json::Value::String(subscriber.clone()),
+            }),
+            ..Default::default()
+        }).await?;
+        if !is_subscribed(&subscriptions, &subscriber, &interest) {
+            center.subscribe(SubscriptionSubscriber { name: subscriber }, interest).await?;
+        }
+        Ok(())
+    }
+
+    /// 发送新评论（文章作者）或新回复（被回复评论的作者）通知
+    ///
+    /// 文章作者在发布时自动订阅新评论，这里为更早发布的文章补充订阅；评论作者对回复的订阅在 `CommentCreated` 事件中创建
+    async fn notify_comment(&self, center: &dyn NotificationCenter, comment: &Comment) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let subject_ref = &comment.spec.subject_ref;
+        let (reason_type, subject) = if is_reply(comment) {
+            let Some(parent) = self.client.fetch::<Comment>(&subject_ref.name).await? else {
+                return Ok(());
+            };
+            let title: String = parent.spec.raw.chars().take(REPLY_SUBJECT_TITLE_LEN).collect();
+            (REASON_NEW_REPLY_ON_COMMENT, reason_subject(subject_ref, title, None))
+        } else if subject_ref.kind == constant::POST_KIND {
+            let Some(post) = self.client.fetch::<Post>(&subject_ref.name).await? else {
+                return Ok(());
+            };
+            let subject = reason_subject(subject_ref, post.spec.title.clone(), Some(post_permalink(&post.spec.slug)));
+            if let Some(owner) = post.spec.owner.clone() {
+                let interest = interest_reason(REASON_NEW_COMMENT_ON_POST, &subject);
+                self.ensure_subscription(center, owner, interest).await?;
+            }
+            (REASON_NEW_COMMENT_ON_POST, subject)
+        } else {
+            return Ok(());
+        };
+
+        let reason = build_reason(reason_type, subject, comment);
+        self.client.create(reason.clone()).await?;
+        center.notify(reason).await?;
+        Ok(())
+    }
+
+    /// 将人工审核结果反馈给检测器
+    async fn report_review(&self, comment: &Comment, is_spam: bool) {
+        for checker in &self.spam_checkers {
+            if let Err(e) = checker.report(comment, is_spam).await {
+                tracing::warn!("Failed to report review to spam checker {}: {}", checker.name(), e);
+            }
+        }
+    }
+}
+
+/// 主题下已批准、未隐藏评论的查询条件，垃圾评论由调用方过滤
+fn visible_condition(group: &str, kind: &str, subject_names: Vec<String>) -> Condition {
+    let equal = |index_name: &str, value: serde_json::Value| Condition::Equal {
+        index_name: index_name.to_string(),
+        value,
+    };
+    equal("spec.subjectRef.group", group.into())
+        .and(equal("spec.subjectRef.kind", kind.into()))
+        .and(Condition::In {
+            index_name: "spec.subjectRef.name".to_string(),
+            values: subject_names.into_iter().map(serde_json::Value::String).collect(),
+        })
+        .and(equal("spec.approved", true.into()))
+        .and(Condition::NotEqual {
+            index_name: "spec.hidden".to_string(),
+            value: true.into(),
+        })
 }
 
 #[async_trait]
 impl<C: ExtensionClient> CommentService for DefaultCommentService<C> {
-    async fn create(&self, comment: Comment) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
-        self.client.create(comment).await
+    async fn create(&self, mut comment: Comment) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
+        if self.detect_spam(&comment).await {
+            comment.spec.spam = Some(true);
+            comment.spec.approved = Some(false);
+            comment.spec.approved_time = None;
+        }
+        let created = self.client.create(comment).await?;
+
+        if let Some(center) = self.notification_center.as_deref().filter(|_| !created.is_spam()) {
+            if let Err(e) = self.notify_comment(center, &created).await {
+                tracing::warn!("Failed to emit notification for comment {}: {}", created.metadata.name, e);
+            }
+        }
+        if let Some(event_bus) = self.event_bus.as_deref().filter(|_| !created.is_spam()) {
+            event_bus.publish(Event::new(COMMENT_CREATED, serde_json::json!({
+                "name": created.metadata.name,
+                "subjectRef": created.spec.subject_ref,
+                "owner": created.spec.owner,
+                "approved": created.spec.approved.unwrap_or(false),
+            })));
+        }
+        Ok(created)
     }
 
     async fn update(&self, comment: Comment) -> Result<Comment, Box<dyn std::error::Error + Send + Sync>> {
@@ -67,5 +237,180 @@ impl<C: ExtensionClient> CommentService for DefaultCommentService<C> {
             .collect();
         Ok(comments)
     }
+
+    async fn list_spam(&self) -> Result<Vec<Comment>, Box<dyn std::error::Error + Send + Sync>> {
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        Ok(self.list(options).await?.items
+            .into_iter()
+            .filter(|c| c.is_spam())
+            .collect())
+    }
+
+    async fn review_spam(&self, names: &[String], is_spam: bool) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut reviewed = 0;
+        for name in names {
+            let Some(mut comment) = self.get(name).await? else {
+                continue;
+            };
+            if !comment.is_spam() {
+                continue;
+            }
+
+            self.report_review(&comment, is_spam).await;
+            if is_spam {
+                self.delete(name).await?;
+            } else {
+                comment.spec.spam = Some(false);
+                self.approve(comment).await?;
+            }
+            reviewed += 1;
+        }
+        Ok(reviewed)
+    }
+
+    async fn list_public(&self, query: &PublicCommentQuery) -> Result<CursorPage<PublicComment>, Box<dyn std::error::Error + Send + Sync>> {
+        let group = query.group.as_deref().unwrap_or(constant::GROUP);
+        let mut comments = self.list_visible(group, &query.kind, vec![query.name.clone()]).await?;
+        // 排序依赖回复数量，一并查询顶层评论的回复
+        let parents = comments.iter().map(|c| c.metadata.name.clone()).collect();
+        comments.extend(self.list_visible(constant::GROUP, constant::COMMENT_KIND, parents).await?);
+        Ok(public_comment::public_comments(&comments, query))
+    }
+
+    async fn list_public_replies(&self, comment_name: &str, query: &PublicReplyQuery) -> Result<Option<CursorPage<PublicComment>>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.get(comment_name).await? {
+            Some(comment) if public_comment::is_publicly_visible(&comment) => {
+                let replies = self.list_visible(constant::GROUP, constant::COMMENT_KIND, vec![comment_name.to_string()]).await?;
+                Ok(Some(public_comment::public_replies(&replies, comment_name, query)))
+            }
+            _ => Ok(None),
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MemoryClient;
+    use flow_api::extension::{Metadata, SCAN_PAGE_SIZE};
+    use flow_domain::notification::{InterestReasonSubject, Reason, SubscriptionSpec};
+    use std::sync::Mutex;
+
+    /// 记录订阅请求的通知中心
+    #[derive(Default)]
+    struct RecordingCenter(Mutex<Vec<InterestReason>>);
+
+    #[async_trait]
+    impl NotificationCenter for RecordingCenter {
+        async fn notify(&self, _reason: Reason) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn subscribe(&self, subscriber: SubscriptionSubscriber, interest_reason: InterestReason) -> anyhow::Result<Subscription> {
+            self.0.lock().unwrap().push(interest_reason.clone());
+            Ok(subscription(&subscriber.name, interest_reason))
+        }
+
+        async fn unsubscribe(&self, _subscriber: &SubscriptionSubscriber) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn unsubscribe_reason(&self, _subscriber: &SubscriptionSubscriber, _interest_reason: &InterestReason) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn subscription(subscriber: &str, reason: InterestReason) -> Subscription {
+        Subscription {
+            metadata: Metadata::new(uuid::Uuid::new_v4().to_string()),
+            spec: SubscriptionSpec {
+                subscriber: SubscriptionSubscriber { name: subscriber.to_string() },
+                unsubscribe_token: String::new(),
+                reason,
+                disabled: None,
+            },
+        }
+    }
+
+    fn post_interest(post_name: &str) -> InterestReason {
+        InterestReason {
+            reason_type: REASON_NEW_COMMENT_ON_POST.to_string(),
+            subject: Some(InterestReasonSubject {
+                name: Some(post_name.to_string()),
+                api_version: format!("{}/{}", constant::GROUP, constant::VERSION),
+                kind: constant::POST_KIND.to_string(),
+            }),
+            expression: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_subscription_checks_every_page() {
+        let client = Arc::new(MemoryClient::default());
+        for i in 0..=SCAN_PAGE_SIZE {
+            client.create(subscription("alice", post_interest(&format!("post-{}", i)))).await.unwrap();
+        }
+        let service = DefaultCommentService::new(client);
+        let center = RecordingCenter::default();
+
+        // 已有的订阅位于第二页，不应重复订阅
+        let existing = post_interest(&format!("post-{}", SCAN_PAGE_SIZE));
+        service.ensure_subscription(&center, "alice".to_string(), existing).await.unwrap();
+        assert!(center.0.lock().unwrap().is_empty());
+
+        service.ensure_subscription(&center, "alice".to_string(), post_interest("post-new")).await.unwrap();
+        assert_eq!(center.0.lock().unwrap().len(), 1);
+
+        // 其他订阅者的订阅不影响判断
+        service.ensure_subscription(&center, "bob".to_string(), post_interest("post-0")).await.unwrap();
+        assert_eq!(center.0.lock().unwrap().len(), 2);
+    }
+
+    fn comment(name: &str, kind: &str, subject: &str, approved: bool) -> Comment {
+        serde_json::from_value(serde_json::json!({
+            "metadata": { "name": name },
+            "spec": {
+                "subjectRef": { "group": constant::GROUP, "version": constant::VERSION, "kind": kind, "name": subject },
+                "raw": "hi",
+                "content": "<p>hi</p>",
+                "owner": { "kind": "Email", "name": "someone@example.com" },
+                "approved": approved,
+            },
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_public_beyond_first_page() {
+        let client = Arc::new(MemoryClient::default());
+        for i in 0..SCAN_PAGE_SIZE {
+            client.create(comment(&format!("other-{}", i), constant::POST_KIND, "newer-post", true)).await.unwrap();
+        }
+        // 较早主题的评论排在其他主题的评论之后
+        client.create(comment("c1", constant::POST_KIND, "older-post", true)).await.unwrap();
+        client.create(comment("c2", constant::POST_KIND, "older-post", false)).await.unwrap();
+        client.create(comment("r1", constant::COMMENT_KIND, "c1", true)).await.unwrap();
+        client.create(comment("r2", constant::COMMENT_KIND, "c1", false)).await.unwrap();
+        let service = DefaultCommentService::new(client);
+
+        let query = PublicCommentQuery {
+            group: None,
+            kind: constant::POST_KIND.to_string(),
+            name: "older-post".to_string(),
+            sort: None,
+            cursor: None,
+            size: None,
+            reply_size: None,
+        };
+        let page = service.list_public(&query).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].name, "c1");
+        assert_eq!(page.items[0].reply_count, 1);
+
+        let replies = service.list_public_replies("c1", &PublicReplyQuery::default()).await.unwrap().unwrap();
+        assert_eq!(replies.items.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["r1"]);
+        assert!(service.list_public_replies("c2", &PublicReplyQuery::default()).await.unwrap().is_none());
+    }
+}
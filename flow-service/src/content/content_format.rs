@@ -0,0 +1,175 @@
+use crate::content::ContentWrapper;
+use pulldown_cmark::{html, Options, Parser};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Markdown格式
+pub const RAW_TYPE_MARKDOWN: &str = "markdown";
+/// HTML格式
+pub const RAW_TYPE_HTML: &str = "html";
+/// 富文本编辑器格式（内容即HTML）
+pub const RAW_TYPE_RICHTEXT: &str = "richtext";
+
+/// 内容格式转换器，将原始内容转换为HTML
+pub trait ContentConverter: Send + Sync {
+    /// 支持的原始内容格式（rawType，不区分大小写）
+    fn raw_type(&self) -> &str;
+
+    /// 将原始内容转换为HTML
+    fn to_html(&self, raw: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Markdown转换器（支持表格、删除线、任务列表、脚注）
+pub struct MarkdownConverter;
+
+impl ContentConverter for MarkdownConverter {
+    fn raw_type(&self) -> &str {
+        RAW_TYPE_MARKDOWN
+    }
+
+    fn to_html(&self, raw: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_FOOTNOTES;
+        let mut output = String::with_capacity(raw.len() * 3 / 2);
+        html::push_html(&mut output, Parser::new_ext(raw, options));
+        Ok(output)
+    }
+}
+
+/// 原始内容已经是HTML的格式（html、richtext）
+pub struct HtmlConverter {
+    raw_type: &'static str,
+}
+
+impl HtmlConverter {
+    pub fn new(raw_type: &'static str) -> Self {
+        Self { raw_type }
+    }
+}
+
+impl ContentConverter for HtmlConverter {
+    fn raw_type(&self) -> &str {
+        self.raw_type
+    }
+
+    fn to_html(&self, raw: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(raw.to_string())
+    }
+}
+
+/// 内容格式转换器注册表，插件可在运行时注册额外的格式（如AsciiDoc）
+pub struct ContentConverterRegistry {
+    converters: RwLock<HashMap<String, Arc<dyn ContentConverter>>>,
+}
+
+impl Default for ContentConverterRegistry {
+    /// 创建包含内置格式（markdown、html、richtext）的注册表
+    fn default() -> Self {
+        let registry = Self::empty();
+        registry.register(Arc::new(MarkdownConverter));
+        registry.register(Arc::new(HtmlConverter::new(RAW_TYPE_HTML)));
+        registry.register(Arc::new(HtmlConverter::new(RAW_TYPE_RICHTEXT)));
+        registry
+    }
+}
+
+impl ContentConverterRegistry {
+    /// 创建空的注册表
+    pub fn empty() -> Self {
+        Self {
+            converters: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 注册转换器，同一格式后注册的覆盖先注册的
+    pub fn register(&self, converter: Arc<dyn ContentConverter>) {
+        let raw_type = converter.raw_type().to_lowercase();
+        self.converters.write().unwrap().insert(raw_type, converter);
+    }
+
+    /// 移除指定格式的转换器
+    pub fn unregister(&self, raw_type: &str) -> Option<Arc<dyn ContentConverter>> {
+        self.converters.write().unwrap().remove(&raw_type.to_lowercase())
+    }
+
+    /// 获取指定格式的转换器
+    pub fn get(&self, raw_type: &str) -> Option<Arc<dyn ContentConverter>> {
+        self.converters.read().unwrap().get(&raw_type.to_lowercase()).cloned()
+    }
+
+    /// 已注册的格式
+    pub fn raw_types(&self) -> Vec<String> {
+        let mut raw_types: Vec<String> = self.converters.read().unwrap().keys().cloned().collect();
+        raw_types.sort();
+        raw_types
+    }
+
+    /// 将原始内容转换为HTML
+    pub fn to_html(&self, raw_type: &str, raw: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let converter = self.get(raw_type)
+            .ok_or_else(|| format!("Unsupported content format: {}", raw_type))?;
+        converter.to_html(raw)
+    }
+
+    /// 内容未保存渲染结果时，根据rawType从原始内容生成HTML
+    pub fn render_content(&self, mut wrapper: ContentWrapper) -> ContentWrapper {
+        if !wrapper.content.trim().is_empty() || wrapper.raw.is_empty() {
+            return wrapper;
+        }
+        match self.to_html(&wrapper.raw_type, &wrapper.raw) {
+            Ok(html) => wrapper.content = html,
+            Err(e) => tracing::debug!("Failed to render snapshot {}: {}", wrapper.snapshot_name, e),
+        }
+        wrapper
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapper(raw_type: &str, raw: &str, content: &str) -> ContentWrapper {
+        ContentWrapper {
+            snapshot_name: "s1".to_string(),
+            raw: raw.to_string(),
+            content: content.to_string(),
+            raw_type: raw_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let registry = ContentConverterRegistry::default();
+        let rendered = registry.render_content(wrapper("Markdown", "# Title\n\n~~x~~", ""));
+        assert!(rendered.content.contains("<h1>Title</h1>"));
+        assert!(rendered.content.contains("<del>x</del>"));
+    }
+
+    #[test]
+    fn test_render_keeps_existing_content() {
+        let registry = ContentConverterRegistry::default();
+        let rendered = registry.render_content(wrapper("markdown", "# Title", "<p>saved</p>"));
+        assert_eq!(rendered.content, "<p>saved</p>");
+    }
+
+    #[test]
+    fn test_register_custom_format() {
+        struct UpperConverter;
+        impl ContentConverter for UpperConverter {
+            fn raw_type(&self) -> &str {
+                "asciidoc"
+            }
+            fn to_html(&self, raw: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+                Ok(format!("<div>{}</div>", raw.to_uppercase()))
+            }
+        }
+
+        let registry = ContentConverterRegistry::default();
+        assert!(registry.to_html("asciidoc", "a").is_err());
+        registry.register(Arc::new(UpperConverter));
+        assert_eq!(registry.to_html("AsciiDoc", "a").unwrap(), "<div>A</div>");
+        assert_eq!(registry.raw_types(), vec!["asciidoc", "html", "markdown", "richtext"]);
+    }
+}
@@ -0,0 +1,126 @@
+//! 内容统计工具，用于计算字数和预计阅读时间
+//! 中日韩（CJK）字符按单字计数，其他语言按空白和标点分隔的单词计数
+
+/// 中日韩文字每分钟阅读字数
+const CJK_CHARS_PER_MINUTE: u64 = 300;
+
+/// 拉丁文字每分钟阅读单词数
+const LATIN_WORDS_PER_MINUTE: u64 = 200;
+
+/// 内容统计结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContentStats {
+    /// 字数（CJK字符数 + 单词数）
+    pub word_count: u64,
+    /// 预计阅读时间（分钟）
+    pub reading_time: u32,
+}
+
+/// 计算HTML内容的统计信息
+pub fn compute(html: &str) -> ContentStats {
+    let text = strip_html(html);
+    let (cjk_chars, latin_words) = count_words(&text);
+    let word_count = cjk_chars + latin_words;
+    if word_count == 0 {
+        return ContentStats::default();
+    }
+
+    // 分别按两种阅读速度折算后向上取整，至少1分钟
+    let seconds = (cjk_chars * 60).div_ceil(CJK_CHARS_PER_MINUTE)
+        + (latin_words * 60).div_ceil(LATIN_WORDS_PER_MINUTE);
+    let reading_time = seconds.div_ceil(60).max(1) as u32;
+
+    ContentStats {
+        word_count,
+        reading_time,
+    }
+}
+
+/// 统计文本中的CJK字符数和非CJK单词数
+pub fn count_words(text: &str) -> (u64, u64) {
+    let mut cjk_chars = 0u64;
+    let mut latin_words = 0u64;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk_chars += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || c == '\'' || c == '_' {
+            if !in_word {
+                latin_words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+
+    (cjk_chars, latin_words)
+}
+
+/// 去除HTML标签，保留文本内容
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                // 标签视为分隔符，避免相邻段落的单词被合并
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// 判断字符是否为中日韩文字
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF      // CJK统一汉字
+        | 0x3400..=0x4DBF    // CJK扩展A
+        | 0x20000..=0x2A6DF  // CJK扩展B
+        | 0xF900..=0xFAFF    // CJK兼容汉字
+        | 0x3040..=0x309F    // 平假名
+        | 0x30A0..=0x30FF    // 片假名
+        | 0xAC00..=0xD7AF    // 韩文音节
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_words_mixed() {
+        let (cjk, latin) = count_words("Hello world，你好世界 Rust's great");
+        assert_eq!(cjk, 4);
+        assert_eq!(latin, 4);
+    }
+
+    #[test]
+    fn test_compute_strips_html() {
+        let stats = compute("<p>one two</p><p>three</p>");
+        assert_eq!(stats.word_count, 3);
+        assert_eq!(stats.reading_time, 1);
+    }
+
+    #[test]
+    fn test_compute_empty() {
+        assert_eq!(compute("<p></p>"), ContentStats::default());
+    }
+
+    #[test]
+    fn test_reading_time_rounds_up() {
+        let html = "字".repeat(301);
+        let stats = compute(&html);
+        assert_eq!(stats.word_count, 301);
+        assert_eq!(stats.reading_time, 2);
+    }
+}
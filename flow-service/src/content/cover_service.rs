@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions};
+use flow_domain::attachment::Attachment;
+use flow_domain::content::Post;
+use crate::attachment::AttachmentService;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 扫描附件、文章时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 封面引用的附件被删除时记录原封面地址的注解
+pub const MISSING_COVER_ANNO: &str = "content.halo.run/missing-cover";
+
+/// 校验封面错误信息的前缀
+pub const INVALID_COVER_ERROR: &str = "Invalid cover";
+
+/// 本站附件访问路径前缀
+const UPLOAD_PATH_PREFIX: &str = "/upload/";
+
+/// 检查封面是否为外部链接（指向本站附件路径的链接不算外部链接）
+pub fn is_external_url(cover: &str) -> bool {
+    let Some(rest) = ["http://", "https://", "//"].iter().find_map(|scheme| cover.strip_prefix(scheme)) else {
+        return false;
+    };
+    let path = rest.find('/').map(|i| &rest[i..]).unwrap_or("");
+    !path.starts_with(UPLOAD_PATH_PREFIX)
+}
+
+/// 查找封面引用的附件（匹配附件的permalink或名称）
+pub fn match_attachment<'a>(cover: &str, attachments: &'a [Attachment]) -> Option<&'a Attachment> {
+    attachments.iter().find(|attachment| {
+        attachment.metadata.name == cover
+            || attachment.status.as_ref()
+                .and_then(|s| s.permalink.as_deref())
+                .is_some_and(|permalink| permalink == cover)
+    })
+}
+
+/// 文章封面服务trait
+#[async_trait]
+pub trait CoverService: Send + Sync {
+    /// 校验封面：必须为已存在的附件或外部链接
+    async fn validate(&self, cover: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取封面的响应式缩略图链接（key为尺寸：XL, L, M, S），外部链接没有缩略图
+    async fn thumbnails(&self, covers: &[&str]) -> Result<HashMap<String, HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 附件删除后清除引用该附件的文章封面，并在注解中记录原封面，返回处理的文章数量
+    async fn release_attachment(&self, attachment: &Attachment) -> Result<usize, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 默认封面服务实现
+pub struct DefaultCoverService<C: ExtensionClient> {
+    client: Arc<C>,
+    attachment_service: Arc<dyn AttachmentService>,
+}
+
+impl<C: ExtensionClient> DefaultCoverService<C> {
+    pub fn new(client: Arc<C>, attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self { client, attachment_service }
+    }
+
+    async fn list_attachments(&self) -> Result<Vec<Attachment>, Box<dyn std::error::Error + Send + Sync>> {
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        Ok(self.attachment_service.list(options).await?)
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> CoverService for DefaultCoverService<C> {
+    async fn validate(&self, cover: Option<&str>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(cover) = cover.map(str::trim).filter(|c| !c.is_empty()) else {
+            return Ok(());
+        };
+
+        let attachments = self.list_attachments().await?;
+        if match_attachment(cover, &attachments).is_some() || is_external_url(cover) {
+            return Ok(());
+        }
+        Err(format!("{}: {} is neither an attachment nor an external URL", INVALID_COVER_ERROR, cover).into())
+    }
+
+    async fn thumbnails(&self, covers: &[&str]) -> Result<HashMap<String, HashMap<String, String>>, Box<dyn std::error::Error + Send + Sync>> {
+        if covers.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let attachments = self.list_attachments().await?;
+        Ok(covers.iter()
+            .filter_map(|cover| {
+                let thumbnails = match_attachment(cover, &attachments)?
+                    .status.as_ref()?
+                    .thumbnails.clone()?;
+                Some((cover.to_string(), thumbnails))
+            })
+            .collect())
+    }
+
+    async fn release_attachment(&self, attachment: &Attachment) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        let attachments = std::slice::from_ref(attachment);
+        let mut released = 0;
+        for mut post in self.client.list::<Post>(options).await?.items {
+            let Some(cover) = post.spec.cover.clone() else {
+                continue;
+            };
+            if match_attachment(&cover, attachments).is_none() {
+                continue;
+            }
+
+            post.metadata.annotations
+                .get_or_insert_with(Default::default)
+                .insert(MISSING_COVER_ANNO.to_string(), cover);
+            post.spec.cover = None;
+            self.client.update(post).await?;
+            released += 1;
+        }
+        Ok(released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::attachment::{AttachmentSpec, AttachmentStatus};
+
+    fn attachment(name: &str, permalink: &str) -> Attachment {
+        Attachment {
+            metadata: Metadata::new(name),
+            spec: AttachmentSpec {
+                display_name: None,
+                group_name: None,
+                policy_name: None,
+                owner_name: None,
+                media_type: Some("image/png".to_string()),
+                size: None,
+                tags: None,
+            },
+            status: Some(AttachmentStatus {
+                permalink: Some(permalink.to_string()),
+                thumbnails: None,
+                used_by: None,
+                blurhash: None,
+                dominant_color: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_match_attachment() {
+        let attachments = vec![attachment("a1", "http://localhost/upload/a1.png")];
+        assert!(match_attachment("http://localhost/upload/a1.png", &attachments).is_some());
+        assert!(match_attachment("a1", &attachments).is_some());
+        assert!(match_attachment("http://localhost/upload/a2.png", &attachments).is_none());
+    }
+
+    #[test]
+    fn test_is_external_url() {
+        assert!(is_external_url("https://cdn.example.com/a.png"));
+        assert!(!is_external_url("/upload/a.png"));
+        assert!(!is_external_url("http://localhost:8090/upload/missing.png"));
+    }
+}
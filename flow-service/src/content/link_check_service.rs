@@ -0,0 +1,414 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flow_api::extension::{scan_all_pages, ListOptions};
+use flow_domain::content::{constant, Post, SinglePage};
+use crate::attachment::AttachmentService;
+use crate::content::{CategoryService, PostQuery, PostService, SinglePageService, TagService};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+/// 后台检查任务的默认间隔（6小时）
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// 不参与检查的站内路径前缀（API、主题静态资源等）
+const IGNORED_PREFIXES: &[&str] = &["/api/", "/apis/", "/themes/", "/oauth2/", "/health"];
+
+/// 单个内容的失效链接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenLinkEntry {
+    /// 内容类型（Post或SinglePage）
+    pub kind: String,
+    /// 内容名称
+    pub name: String,
+    pub title: String,
+    /// 失效的链接
+    pub links: Vec<String>,
+}
+
+/// 失效链接检查报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheckReport {
+    #[serde(rename = "checkedAt")]
+    pub checked_at: DateTime<Utc>,
+    /// 检查的内容数量
+    #[serde(rename = "scannedCount")]
+    pub scanned_count: usize,
+    pub items: Vec<BrokenLinkEntry>,
+}
+
+/// 失效链接检查服务trait
+#[async_trait]
+pub trait LinkCheckService: Send + Sync {
+    /// 扫描所有已发布内容，标记失效链接并生成报告
+    async fn check_all(&self) -> Result<LinkCheckReport, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取最近一次检查的报告
+    async fn last_report(&self) -> Option<LinkCheckReport>;
+}
+
+/// 默认失效链接检查服务实现
+pub struct DefaultLinkCheckService {
+    post_service: Arc<dyn PostService>,
+    single_page_service: Arc<dyn SinglePageService>,
+    category_service: Arc<dyn CategoryService>,
+    tag_service: Arc<dyn TagService>,
+    attachment_service: Arc<dyn AttachmentService>,
+    /// 站点外部URL（用于识别绝对地址形式的站内链接）
+    external_url: Option<String>,
+    last_report: RwLock<Option<LinkCheckReport>>,
+}
+
+impl DefaultLinkCheckService {
+    pub fn new(
+        post_service: Arc<dyn PostService>,
+        single_page_service: Arc<dyn SinglePageService>,
+        category_service: Arc<dyn CategoryService>,
+        tag_service: Arc<dyn TagService>,
+        attachment_service: Arc<dyn AttachmentService>,
+        external_url: Option<String>,
+    ) -> Self {
+        Self {
+            post_service,
+            single_page_service,
+            category_service,
+            tag_service,
+            attachment_service,
+            external_url,
+            last_report: RwLock::new(None),
+        }
+    }
+
+    /// 收集所有有效的站内路径
+    async fn collect_live_targets(
+        &self,
+        posts: &[Post],
+        pages: &[SinglePage],
+    ) -> Result<HashSet<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut targets = HashSet::new();
+        targets.insert("/".to_string());
+
+        for post in posts {
+            targets.insert(format!("/archives/{}", post.spec.slug));
+            if let Some(permalink) = post.status.as_ref().and_then(|s| s.permalink.as_deref()) {
+                targets.insert(self.normalize(permalink).unwrap_or_else(|| permalink.to_string()));
+            }
+        }
+
+        for page in pages {
+            targets.insert(format!("/{}", page.spec.slug));
+            if let Some(permalink) = page.status.as_ref().and_then(|s| s.permalink.as_deref()) {
+                targets.insert(self.normalize(permalink).unwrap_or_else(|| permalink.to_string()));
+            }
+        }
+
+        let categories = scan_all_pages(ListOptions::default(), |options| async move {
+            self.category_service.list(options).await.map(|result| result.items)
+        }).await?;
+        for category in categories {
+            targets.insert(format!("/categories/{}", category.spec.slug));
+        }
+
+        let tags = scan_all_pages(ListOptions::default(), |options| async move {
+            self.tag_service.list(options).await.map(|result| result.items)
+        }).await?;
+        for tag in tags {
+            targets.insert(format!("/tags/{}", tag.spec.slug));
+        }
+
+        let attachments = scan_all_pages(ListOptions::default(), |options| self.attachment_service.list(options)).await
+            .map_err(|e| e.to_string())?;
+        for attachment in attachments {
+            let status = attachment.status.as_ref();
+            let urls = status.and_then(|s| s.permalink.clone()).into_iter()
+                .chain(status.and_then(|s| s.thumbnails.clone()).unwrap_or_default().into_values());
+            for url in urls {
+                if let Some(path) = self.normalize(&url) {
+                    targets.insert(path);
+                }
+            }
+        }
+
+        Ok(targets)
+    }
+
+    /// 将链接规范化为站内路径，站外链接返回None
+    fn normalize(&self, link: &str) -> Option<String> {
+        normalize_internal_link(link, self.external_url.as_deref())
+    }
+
+    /// 检查内容中的失效链接
+    fn find_broken(&self, html: &str, live_targets: &HashSet<String>) -> Vec<String> {
+        let mut broken = Vec::new();
+        for link in extract_links(html) {
+            let Some(path) = self.normalize(&link) else {
+                continue;
+            };
+            if IGNORED_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+                continue;
+            }
+            if !live_targets.contains(&path) && !broken.contains(&link) {
+                broken.push(link);
+            }
+        }
+        broken
+    }
+}
+
+/// 写入或清除失效链接注解，返回注解是否发生变化
+fn apply_broken_links_annotation(
+    annotations: &mut Option<HashMap<String, String>>,
+    broken: &[String],
+) -> bool {
+    let annotations = annotations.get_or_insert_with(HashMap::new);
+    if broken.is_empty() {
+        return annotations.remove(constant::BROKEN_LINKS_ANNO).is_some();
+    }
+    let value = serde_json::to_string(broken).unwrap_or_default();
+    annotations.insert(constant::BROKEN_LINKS_ANNO.to_string(), value.clone()) != Some(value)
+}
+
+#[async_trait]
+impl LinkCheckService for DefaultLinkCheckService {
+    async fn check_all(&self) -> Result<LinkCheckReport, Box<dyn std::error::Error + Send + Sync>> {
+        let posts: Vec<Post> = scan_all_pages(ListOptions::default(), |options| async move {
+            let query = PostQuery { page: options.page, size: options.size, ..Default::default() };
+            self.post_service.list_post(query).await.map(|result| result.items)
+        })
+            .await?
+            .into_iter()
+            .map(|listed| listed.post)
+            .filter(|post| post.is_published() && !post.is_deleted())
+            .collect();
+
+        let pages: Vec<SinglePage> = scan_all_pages(ListOptions::default(), |options| async move {
+            self.single_page_service.list(options).await.map(|result| result.items)
+        })
+            .await?
+            .into_iter()
+            .filter(|page| page.is_published() && !page.spec.deleted.unwrap_or(false))
+            .collect();
+
+        let live_targets = self.collect_live_targets(&posts, &pages).await?;
+        let mut items = Vec::new();
+
+        for mut post in posts.clone() {
+            let content = match self.post_service.get_release_content(&post.metadata.name).await {
+                Ok(content) => content,
+                Err(e) => {
+                    debug!("Skip link check for post {}: {}", post.metadata.name, e);
+                    continue;
+                }
+            };
+            let broken = self.find_broken(&content.content, &live_targets);
+            if !broken.is_empty() {
+                items.push(BrokenLinkEntry {
+                    kind: constant::POST_KIND.to_string(),
+                    name: post.metadata.name.clone(),
+                    title: post.spec.title.clone(),
+                    links: broken.clone(),
+                });
+            }
+            if apply_broken_links_annotation(&mut post.metadata.annotations, &broken) {
+                if let Err(e) = self.post_service.update_by(post).await {
+                    warn!("Failed to update broken links annotation: {}", e);
+                }
+            }
+        }
+
+        for mut page in pages.clone() {
+            let content = match self.single_page_service.get_release_content(&page.metadata.name).await {
+                Ok(content) => content,
+                Err(e) => {
+                    debug!("Skip link check for single page {}: {}", page.metadata.name, e);
+                    continue;
+                }
+            };
+            let broken = self.find_broken(&content.content, &live_targets);
+            if !broken.is_empty() {
+                items.push(BrokenLinkEntry {
+                    kind: constant::SINGLE_PAGE_KIND.to_string(),
+                    name: page.metadata.name.clone(),
+                    title: page.spec.title.clone(),
+                    links: broken.clone(),
+                });
+            }
+            if apply_broken_links_annotation(&mut page.metadata.annotations, &broken) {
+                if let Err(e) = self.single_page_service.update(page).await {
+                    warn!("Failed to update broken links annotation: {}", e);
+                }
+            }
+        }
+
+        let report = LinkCheckReport {
+            checked_at: Utc::now(),
+            scanned_count: posts.len() + pages.len(),
+            items,
+        };
+        *self.last_report.write().await = Some(report.clone());
+        Ok(report)
+    }
+
+    async fn last_report(&self) -> Option<LinkCheckReport> {
+        self.last_report.read().await.clone()
+    }
+}
+
+/// 启动后台失效链接检查任务
+pub fn spawn_link_check_job(
+    service: Arc<dyn LinkCheckService>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match service.check_all().await {
+                Ok(report) => info!(
+                    "Link check finished: {} contents scanned, {} with broken links",
+                    report.scanned_count,
+                    report.items.len()
+                ),
+                Err(e) => warn!("Link check failed: {}", e),
+            }
+        }
+    })
+}
+
+/// 从HTML中提取href和src属性的值
+pub fn extract_links(html: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    for attr in ["href=", "src="] {
+        let mut offset = 0;
+        while let Some(pos) = lower[offset..].find(attr) {
+            let start = offset + pos + attr.len();
+            offset = start;
+            // 属性名前必须是空白，避免匹配到data-href等
+            let before = lower[..start - attr.len()].chars().last();
+            if !before.map(|c| c.is_whitespace()).unwrap_or(false) {
+                continue;
+            }
+            let rest = &html[start..];
+            let value = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => rest[1..].split(quote).next(),
+                Some(_) => rest.split(|c: char| c.is_whitespace() || c == '>').next(),
+                None => None,
+            };
+            if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+                links.push(value.to_string());
+            }
+        }
+    }
+    links
+}
+
+/// 将链接规范化为站内路径（去除域名、查询参数、锚点和末尾斜杠）
+/// 站外链接或非HTTP链接返回None
+pub fn normalize_internal_link(link: &str, external_url: Option<&str>) -> Option<String> {
+    let path = if link.starts_with("//") {
+        return None;
+    } else if link.starts_with('/') {
+        link
+    } else if let Some(rest) = external_url
+        .map(|url| url.trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+        .and_then(|url| link.strip_prefix(url))
+    {
+        if rest.is_empty() { "/" } else if rest.starts_with('/') { rest } else { return None }
+    } else {
+        return None;
+    };
+
+    let path = path.split(['?', '#']).next().unwrap_or("/");
+    let trimmed = path.trim_end_matches('/');
+    Some(if trimmed.is_empty() { "/".to_string() } else { trimmed.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{DefaultCategoryService, DefaultPostService, DefaultSinglePageService, DefaultTagService};
+    use crate::testing::{attachment_service, MemoryClient, MemoryRepository};
+    use flow_api::extension::{ExtensionClient, Metadata, SCAN_PAGE_SIZE};
+    use flow_domain::content::Snapshot;
+    use flow_infra::extension::ReactiveExtensionClient;
+
+    fn published(name: &str) -> Metadata {
+        let mut metadata = Metadata::new(name);
+        metadata.labels = Some(HashMap::from([(constant::POST_PUBLISHED_LABEL.to_string(), "true".to_string())]));
+        metadata
+    }
+
+    #[tokio::test]
+    async fn test_check_all_scans_every_post_page() {
+        let client = Arc::new(MemoryClient::default());
+        for i in 0..=SCAN_PAGE_SIZE {
+            let spec = serde_json::from_value(serde_json::json!({"title": "post", "slug": format!("post-{}", i)})).unwrap();
+            client.create(Post { metadata: published(&format!("post-{}", i)), spec, status: None }).await.unwrap();
+        }
+
+        let mut snapshot: Snapshot = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "about-snapshot"},
+            "spec": {
+                "subjectRef": {"group": constant::GROUP, "version": constant::VERSION, "kind": constant::SINGLE_PAGE_KIND, "name": "about"},
+                "rawType": "html",
+                "owner": "admin",
+            },
+        })).unwrap();
+        snapshot.metadata.annotations = Some(HashMap::from([(constant::SNAPSHOT_KEEP_RAW_ANNO.to_string(), "true".to_string())]));
+        // 链接的文章位于第二页
+        snapshot.spec.content_patch = Some(format!(r#"<a href="/archives/post-{}">ok</a><a href="/archives/missing">x</a>"#, SCAN_PAGE_SIZE));
+        client.create(snapshot).await.unwrap();
+        let spec = serde_json::from_value(serde_json::json!({
+            "title": "About", "slug": "about", "releaseSnapshot": "about-snapshot", "baseSnapshot": "about-snapshot",
+        })).unwrap();
+        client.create(SinglePage { metadata: published("about"), spec, status: None }).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = DefaultLinkCheckService::new(
+            Arc::new(DefaultPostService::new(client.clone())),
+            Arc::new(DefaultSinglePageService::new(client.clone())),
+            Arc::new(DefaultCategoryService::new(client.clone())),
+            Arc::new(DefaultTagService::new(client.clone())),
+            // 没有任何附件
+            Arc::new(attachment_service(Arc::new(ReactiveExtensionClient::new(Arc::new(MemoryRepository::default()))), dir.path())),
+            None,
+        );
+        let report = service.check_all().await.unwrap();
+        assert_eq!(report.scanned_count, SCAN_PAGE_SIZE as usize + 2);
+        assert_eq!(report.items.len(), 1);
+        assert_eq!(report.items[0].name, "about");
+        assert_eq!(report.items[0].links, vec!["/archives/missing"]);
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let html = r#"<a href="/archives/hello">x</a><img src='/upload/a.png'><a data-href="/skip" href=https://example.com>y</a>"#;
+        let links = extract_links(html);
+        assert_eq!(links, vec!["/archives/hello", "https://example.com", "/upload/a.png"]);
+    }
+
+    #[test]
+    fn test_normalize_internal_link() {
+        let base = Some("https://blog.example.com/");
+        assert_eq!(normalize_internal_link("/archives/a/?x=1#top", base).as_deref(), Some("/archives/a"));
+        assert_eq!(normalize_internal_link("https://blog.example.com/tags/rust", base).as_deref(), Some("/tags/rust"));
+        assert_eq!(normalize_internal_link("https://blog.example.com", base).as_deref(), Some("/"));
+        assert_eq!(normalize_internal_link("https://other.com/a", base), None);
+        assert_eq!(normalize_internal_link("//cdn.example.com/a.js", base), None);
+        assert_eq!(normalize_internal_link("mailto:me@example.com", base), None);
+    }
+
+    #[test]
+    fn test_apply_broken_links_annotation() {
+        let mut annotations = None;
+        assert!(apply_broken_links_annotation(&mut annotations, &["/a".to_string()]));
+        assert!(!apply_broken_links_annotation(&mut annotations, &["/a".to_string()]));
+        assert!(apply_broken_links_annotation(&mut annotations, &[]));
+        assert!(!annotations.unwrap().contains_key(constant::BROKEN_LINKS_ANNO));
+    }
+}
@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_domain::content::{Link, LinkGroup};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 分组及其下的友情链接（group为None表示未分组的链接）
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedLinks {
+    pub group: Option<LinkGroup>,
+    pub links: Vec<Link>,
+}
+
+/// 将友情链接按分组归类，分组和链接均按priority、显示名称排序；
+/// 未分组或所属分组不存在的链接放在最后，没有链接时不返回未分组项
+pub fn group_links(mut groups: Vec<LinkGroup>, mut links: Vec<Link>) -> Vec<GroupedLinks> {
+    groups.sort_by(|a, b| {
+        a.spec.priority.cmp(&b.spec.priority)
+            .then_with(|| a.spec.display_name.cmp(&b.spec.display_name))
+    });
+    links.sort_by(|a, b| {
+        a.spec.priority.cmp(&b.spec.priority)
+            .then_with(|| a.spec.display_name.cmp(&b.spec.display_name))
+    });
+
+    let mut result: Vec<GroupedLinks> = groups.into_iter()
+        .map(|group| GroupedLinks { group: Some(group), links: Vec::new() })
+        .collect();
+    let mut ungrouped = Vec::new();
+    for link in links {
+        let target = link.spec.group_name.as_deref().and_then(|name| {
+            result.iter_mut().find(|g| g.group.as_ref().is_some_and(|group| group.metadata.name == name))
+        });
+        match target {
+            Some(grouped) => grouped.links.push(link),
+            None => ungrouped.push(link),
+        }
+    }
+    if !ungrouped.is_empty() {
+        result.push(GroupedLinks { group: None, links: ungrouped });
+    }
+    result
+}
+
+/// 友情链接服务trait
+#[async_trait]
+pub trait LinkService: Send + Sync {
+    async fn create(&self, link: Link) -> Result<Link, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update(&self, link: Link) -> Result<Link, Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get(&self, name: &str) -> Result<Option<Link>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Link>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn create_group(&self, group: LinkGroup) -> Result<LinkGroup, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update_group(&self, group: LinkGroup) -> Result<LinkGroup, Box<dyn std::error::Error + Send + Sync>>;
+    /// 删除分组，分组下的链接变为未分组
+    async fn delete_group(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get_group(&self, name: &str) -> Result<Option<LinkGroup>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list_groups(&self, options: ListOptions) -> Result<ListResult<LinkGroup>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 按分组列出所有友情链接
+    async fn list_grouped(&self) -> Result<Vec<GroupedLinks>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultLinkService<C: ExtensionClient> {
+    client: Arc<C>,
+}
+
+impl<C: ExtensionClient> DefaultLinkService<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+
+}
+
+#[async_trait]
+impl<C: ExtensionClient> LinkService for DefaultLinkService<C> {
+    async fn create(&self, link: Link) -> Result<Link, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.create(link).await
+    }
+
+    async fn update(&self, link: Link) -> Result<Link, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.update(link).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client.delete::<Link>(name).await
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Link>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Link>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn create_group(&self, group: LinkGroup) -> Result<LinkGroup, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.create(group).await
+    }
+
+    async fn update_group(&self, group: LinkGroup) -> Result<LinkGroup, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.update(group).await
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for mut link in self.client.list_all::<Link>(ListOptions::default()).await? {
+            if link.spec.group_name.as_deref() == Some(name) {
+                link.spec.group_name = None;
+                self.client.update(link).await?;
+            }
+        }
+        self.client.delete::<LinkGroup>(name).await
+    }
+
+    async fn get_group(&self, name: &str) -> Result<Option<LinkGroup>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list_groups(&self, options: ListOptions) -> Result<ListResult<LinkGroup>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn list_grouped(&self) -> Result<Vec<GroupedLinks>, Box<dyn std::error::Error + Send + Sync>> {
+        let groups = self.client.list_all::<LinkGroup>(ListOptions::default()).await?;
+        let links = self.client.list_all::<Link>(ListOptions::default()).await?;
+        Ok(group_links(groups, links))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{LinkGroupSpec, LinkSpec};
+
+    fn link(name: &str, priority: i32, group: Option<&str>) -> Link {
+        Link {
+            metadata: Metadata::new(name),
+            spec: LinkSpec {
+                url: format!("https://{}.example.com", name),
+                display_name: name.to_string(),
+                logo: None,
+                description: None,
+                priority,
+                group_name: group.map(str::to_string),
+            },
+        }
+    }
+
+    fn group(name: &str, priority: i32) -> LinkGroup {
+        LinkGroup {
+            metadata: Metadata::new(name),
+            spec: LinkGroupSpec { display_name: name.to_string(), priority },
+        }
+    }
+
+    #[test]
+    fn test_group_links() {
+        let grouped = group_links(
+            vec![group("friends", 1), group("tools", 0)],
+            vec![
+                link("b", 2, Some("friends")),
+                link("a", 1, Some("friends")),
+                link("c", 0, None),
+                link("d", 0, Some("removed")),
+            ],
+        );
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped[0].group.as_ref().unwrap().metadata.name, "tools");
+        let friends: Vec<&str> = grouped[1].links.iter().map(|l| l.metadata.name.as_str()).collect();
+        assert_eq!(friends, vec!["a", "b"]);
+        assert!(grouped[2].group.is_none());
+        assert_eq!(grouped[2].links.len(), 2);
+    }
+}
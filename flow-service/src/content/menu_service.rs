@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_domain::content::{Menu, MenuItem};
+use flow_infra::system_setting::SystemSettingService;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 菜单树节点
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuItemNode {
+    pub name: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    pub href: Option<String>,
+    pub target: Option<String>,
+    pub priority: i32,
+    pub children: Vec<MenuItemNode>,
+}
+
+/// 带菜单项树的菜单
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuTree {
+    pub menu: Menu,
+    #[serde(rename = "menuItems")]
+    pub menu_items: Vec<MenuItemNode>,
+}
+
+/// 根据菜单的顶级菜单项和各菜单项的children构建菜单树，同级按priority、显示名称排序；
+/// 缺失的菜单项会被忽略，重复引用（循环）的菜单项只展开一次
+pub fn build_menu_tree(menu: &Menu, items: &[MenuItem]) -> Vec<MenuItemNode> {
+    let by_name: HashMap<&str, &MenuItem> = items.iter()
+        .map(|item| (item.metadata.name.as_str(), item))
+        .collect();
+    let mut visited = HashSet::new();
+    let roots = menu.spec.menu_items.as_deref().unwrap_or_default();
+    build_nodes(roots, &by_name, &mut visited)
+}
+
+fn build_nodes<'a>(
+    names: &'a [String],
+    by_name: &HashMap<&str, &'a MenuItem>,
+    visited: &mut HashSet<&'a str>,
+) -> Vec<MenuItemNode> {
+    let mut items: Vec<&MenuItem> = names.iter()
+        .filter_map(|name| by_name.get(name.as_str()).copied())
+        .filter(|item| visited.insert(item.metadata.name.as_str()))
+        .collect();
+    items.sort_by(|a, b| {
+        a.spec.priority.cmp(&b.spec.priority)
+            .then_with(|| a.display_name().cmp(&b.display_name()))
+    });
+    items.into_iter()
+        .map(|item| MenuItemNode {
+            name: item.metadata.name.clone(),
+            display_name: item.display_name(),
+            href: item.href(),
+            target: item.spec.target.clone(),
+            priority: item.spec.priority,
+            children: build_nodes(item.spec.children.as_deref().unwrap_or_default(), by_name, visited),
+        })
+        .collect()
+}
+
+/// Menu服务trait
+#[async_trait]
+pub trait MenuService: Send + Sync {
+    async fn create(&self, menu: Menu) -> Result<Menu, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update(&self, menu: Menu) -> Result<Menu, Box<dyn std::error::Error + Send + Sync>>;
+    /// 删除菜单及其包含的所有菜单项
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get(&self, name: &str) -> Result<Option<Menu>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Menu>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn create_item(&self, item: MenuItem) -> Result<MenuItem, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update_item(&self, item: MenuItem) -> Result<MenuItem, Box<dyn std::error::Error + Send + Sync>>;
+    /// 删除菜单项，并从引用它的菜单和父菜单项中移除
+    async fn delete_item(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get_item(&self, name: &str) -> Result<Option<MenuItem>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list_items(&self, options: ListOptions) -> Result<ListResult<MenuItem>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取菜单及其菜单项树
+    async fn get_tree(&self, name: &str) -> Result<Option<MenuTree>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取主菜单（系统设置中的主菜单，未设置时使用第一个菜单）
+    async fn get_primary_tree(&self) -> Result<Option<MenuTree>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultMenuService<C: ExtensionClient> {
+    client: Arc<C>,
+    setting_service: Option<Arc<dyn SystemSettingService>>,
+}
+
+impl<C: ExtensionClient> DefaultMenuService<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, setting_service: None }
+    }
+
+    /// 设置系统设置服务，用于读取主菜单配置
+    pub fn with_setting_service(mut self, setting_service: Arc<dyn SystemSettingService>) -> Self {
+        self.setting_service = Some(setting_service);
+        self
+    }
+
+    /// 收集菜单包含的所有菜单项名称（含子孙菜单项）
+    fn collect_item_names(menu: &Menu, items: &[MenuItem]) -> HashSet<String> {
+        let by_name: HashMap<&str, &MenuItem> = items.iter()
+            .map(|item| (item.metadata.name.as_str(), item))
+            .collect();
+        let mut names = HashSet::new();
+        let mut pending: Vec<&str> = menu.spec.menu_items.iter().flatten().map(String::as_str).collect();
+        while let Some(name) = pending.pop() {
+            if !names.insert(name.to_string()) {
+                continue;
+            }
+            if let Some(item) = by_name.get(name) {
+                pending.extend(item.spec.children.iter().flatten().map(String::as_str));
+            }
+        }
+        names
+    }
+
+    async fn primary_menu_name(&self) -> Option<String> {
+        let setting_service = self.setting_service.as_ref()?;
+        match setting_service.get_menu_setting().await {
+            Ok(setting) => setting.and_then(|s| s.primary).filter(|p| !p.is_empty()),
+            Err(e) => {
+                tracing::warn!("Failed to get menu setting: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> MenuService for DefaultMenuService<C> {
+    async fn create(&self, menu: Menu) -> Result<Menu, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.create(menu).await
+    }
+
+    async fn update(&self, menu: Menu) -> Result<Menu, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.update(menu).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(menu) = self.client.fetch::<Menu>(name).await? {
+            let items = self.client.list_all::<MenuItem>(ListOptions::default()).await?;
+            for item_name in Self::collect_item_names(&menu, &items) {
+                if items.iter().any(|item| item.metadata.name == item_name) {
+                    self.client.delete::<MenuItem>(&item_name).await?;
+                }
+            }
+        }
+        self.client.delete::<Menu>(name).await
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Menu>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Menu>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn create_item(&self, item: MenuItem) -> Result<MenuItem, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.create(item).await
+    }
+
+    async fn update_item(&self, item: MenuItem) -> Result<MenuItem, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.update(item).await
+    }
+
+    async fn delete_item(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for mut menu in self.client.list_all::<Menu>(ListOptions::default()).await? {
+            if let Some(menu_items) = menu.spec.menu_items.as_mut().filter(|items| items.iter().any(|i| i == name)) {
+                menu_items.retain(|i| i != name);
+                self.client.update(menu).await?;
+            }
+        }
+        for mut parent in self.client.list_all::<MenuItem>(ListOptions::default()).await? {
+            if let Some(children) = parent.spec.children.as_mut().filter(|children| children.iter().any(|c| c == name)) {
+                children.retain(|c| c != name);
+                self.client.update(parent).await?;
+            }
+        }
+        self.client.delete::<MenuItem>(name).await
+    }
+
+    async fn get_item(&self, name: &str) -> Result<Option<MenuItem>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list_items(&self, options: ListOptions) -> Result<ListResult<MenuItem>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn get_tree(&self, name: &str) -> Result<Option<MenuTree>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(menu) = self.client.fetch::<Menu>(name).await? else {
+            return Ok(None);
+        };
+        let items = self.client.list_all::<MenuItem>(ListOptions::default()).await?;
+        let menu_items = build_menu_tree(&menu, &items);
+        Ok(Some(MenuTree { menu, menu_items }))
+    }
+
+    async fn get_primary_tree(&self) -> Result<Option<MenuTree>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(primary) = self.primary_menu_name().await {
+            if let Some(tree) = self.get_tree(&primary).await? {
+                return Ok(Some(tree));
+            }
+        }
+        let mut menus = self.client.list_all::<Menu>(ListOptions::default()).await?;
+        menus.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+        match menus.into_iter().next() {
+            Some(menu) => self.get_tree(&menu.metadata.name).await,
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{MenuItemSpec, MenuSpec};
+
+    fn item(name: &str, priority: i32, children: &[&str]) -> MenuItem {
+        MenuItem {
+            metadata: Metadata::new(name),
+            spec: MenuItemSpec {
+                display_name: Some(name.to_uppercase()),
+                href: Some(format!("/{}", name)),
+                target: None,
+                priority,
+                children: Some(children.iter().map(|c| c.to_string()).collect()),
+            },
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_build_menu_tree() {
+        let menu = Menu {
+            metadata: Metadata::new("primary"),
+            spec: MenuSpec {
+                display_name: "Primary".to_string(),
+                menu_items: Some(vec!["about".to_string(), "home".to_string(), "missing".to_string()]),
+            },
+        };
+        let items = vec![
+            item("home", 0, &[]),
+            item("about", 1, &["team", "about"]),
+            item("team", 0, &[]),
+        ];
+        let tree = build_menu_tree(&menu, &items);
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["home", "about"]);
+        assert_eq!(tree[1].children.len(), 1);
+        assert_eq!(tree[1].children[0].href.as_deref(), Some("/team"));
+        assert_eq!(tree[0].display_name, "HOME");
+    }
+}
@@ -3,17 +3,60 @@ pub mod single_page_service;
 pub mod comment_service;
 pub mod category_service;
 pub mod tag_service;
+pub mod series_service;
 pub mod snapshot_service;
 pub mod search_indexing_post_service;
 pub mod search_indexing_single_page_service;
+pub mod reference_tracking_post_service;
+pub mod reference_tracking_single_page_service;
+pub mod tag_counting_post_service;
 pub mod patch_utils;
+pub mod content_stats;
+pub mod link_check_service;
+pub mod translation;
+pub mod post_access_service;
+pub mod slug_redirect_service;
+pub mod comment_reaction_service;
+pub mod spam_checker;
+pub mod comment_notification;
+pub mod public_comment;
+pub mod archive;
+pub mod content_format;
+pub mod cover_service;
+pub mod page_tree;
+pub mod publish_validator;
+pub mod menu_service;
+pub mod link_service;
+pub mod moment_service;
+pub mod photo_service;
 
 pub use post_service::{PostService, DefaultPostService, PostRequest, PostQuery, ListedPost, ContentWrapper, ContentRequest};
 pub use single_page_service::{SinglePageService, DefaultSinglePageService};
+pub use page_tree::PageTreeNode;
 pub use comment_service::{CommentService, DefaultCommentService};
 pub use category_service::{CategoryService, DefaultCategoryService};
 pub use tag_service::{TagService, DefaultTagService};
+pub use menu_service::{MenuService, DefaultMenuService, MenuTree, MenuItemNode};
+pub use link_service::{LinkService, DefaultLinkService, GroupedLinks};
+pub use moment_service::{MomentService, DefaultMomentService, MomentQuery};
+pub use photo_service::{PhotoService, DefaultPhotoService, GroupedPhotos};
+pub use series_service::{SeriesService, DefaultSeriesService, SeriesNavigation};
 pub use snapshot_service::{SnapshotService, DefaultSnapshotService};
 pub use search_indexing_post_service::SearchIndexingPostService;
 pub use search_indexing_single_page_service::SearchIndexingSinglePageService;
+pub use reference_tracking_post_service::ReferenceTrackingPostService;
+pub use reference_tracking_single_page_service::ReferenceTrackingSinglePageService;
+pub use tag_counting_post_service::TagCountingPostService;
+pub use content_stats::ContentStats;
+pub use translation::HreflangLink;
+pub use cover_service::{CoverService, DefaultCoverService};
+pub use content_format::{ContentConverter, ContentConverterRegistry};
+pub use publish_validator::{PublishValidator, PublishValidatorRegistry, PublishValidationError, AttachmentReferenceValidator};
+pub use archive::{ArchiveQuery, ArchiveYear, ArchiveMonth};
+pub use post_access_service::{PostAccessService, DefaultPostAccessService};
+pub use slug_redirect_service::{SlugRedirectService, DefaultSlugRedirectService};
+pub use spam_checker::{SpamChecker, AkismetSpamChecker};
+pub use comment_reaction_service::{CommentReactionService, DefaultCommentReactionService};
+pub use public_comment::{PublicComment, PublicCommentQuery, PublicReplyQuery, CommentSort, CursorPage};
+pub use link_check_service::{LinkCheckService, DefaultLinkCheckService, LinkCheckReport, BrokenLinkEntry};
 
@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_domain::content::{Moment, VisibleEnum};
+use crate::content::ContentConverterRegistry;
+use crate::content::content_format::RAW_TYPE_MARKDOWN;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// 扫描瞬间时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 默认每页数量
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// 瞬间查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MomentQuery {
+    /// 只返回指定用户的瞬间
+    pub owner: Option<String>,
+    /// 只返回包含指定标签的瞬间
+    pub tag: Option<String>,
+    pub visible: Option<VisibleEnum>,
+    /// 内容关键词
+    pub keyword: Option<String>,
+    pub page: Option<u32>,
+    pub size: Option<u32>,
+}
+
+impl MomentQuery {
+    fn matches(&self, moment: &Moment) -> bool {
+        let spec = &moment.spec;
+        self.owner.as_deref().is_none_or(|owner| moment.is_owned_by(owner))
+            && self.tag.as_deref().is_none_or(|tag| spec.tags.iter().flatten().any(|t| t == tag))
+            && self.visible.is_none_or(|visible| spec.visible.unwrap_or(VisibleEnum::Public) == visible)
+            && self.keyword.as_deref().is_none_or(|keyword| spec.content.raw.contains(keyword))
+    }
+}
+
+/// 按查询条件过滤瞬间，按发布时间倒序分页
+pub fn query_moments(moments: Vec<Moment>, query: &MomentQuery) -> ListResult<Moment> {
+    let mut matched: Vec<Moment> = moments.into_iter().filter(|m| query.matches(m)).collect();
+    matched.sort_by(|a, b| {
+        b.spec.release_time.cmp(&a.spec.release_time)
+            .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+    });
+
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let total = matched.len() as u64;
+    let items = matched.into_iter()
+        .skip(((page - 1) * size) as usize)
+        .take(size as usize)
+        .collect();
+    ListResult::new(items, total, page, size)
+}
+
+/// 瞬间服务trait
+#[async_trait]
+pub trait MomentService: Send + Sync {
+    /// 创建瞬间（未指定时发布时间为当前时间，可见性为公开）
+    async fn create(&self, moment: Moment) -> Result<Moment, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update(&self, moment: Moment) -> Result<Moment, Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get(&self, name: &str) -> Result<Option<Moment>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list(&self, query: MomentQuery) -> Result<ListResult<Moment>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultMomentService<C: ExtensionClient> {
+    client: Arc<C>,
+    converter_registry: Option<Arc<ContentConverterRegistry>>,
+}
+
+impl<C: ExtensionClient> DefaultMomentService<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, converter_registry: None }
+    }
+
+    /// 设置内容格式注册表，未提供HTML时将Markdown原始内容渲染为HTML
+    pub fn with_converter_registry(mut self, converter_registry: Arc<ContentConverterRegistry>) -> Self {
+        self.converter_registry = Some(converter_registry);
+        self
+    }
+
+    fn render(&self, moment: &mut Moment) {
+        let content = &mut moment.spec.content;
+        if !content.html.trim().is_empty() || content.raw.is_empty() {
+            return;
+        }
+        let Some(registry) = &self.converter_registry else {
+            return;
+        };
+        match registry.to_html(RAW_TYPE_MARKDOWN, &content.raw) {
+            Ok(html) => content.html = html,
+            Err(e) => tracing::debug!("Failed to render moment {}: {}", moment.metadata.name, e),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> MomentService for DefaultMomentService<C> {
+    async fn create(&self, mut moment: Moment) -> Result<Moment, Box<dyn std::error::Error + Send + Sync>> {
+        moment.spec.release_time.get_or_insert_with(Utc::now);
+        moment.spec.visible.get_or_insert(VisibleEnum::Public);
+        self.render(&mut moment);
+        self.client.create(moment).await
+    }
+
+    async fn update(&self, mut moment: Moment) -> Result<Moment, Box<dyn std::error::Error + Send + Sync>> {
+        self.render(&mut moment);
+        self.client.update(moment).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client.delete::<Moment>(name).await
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Moment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list(&self, query: MomentQuery) -> Result<ListResult<Moment>, Box<dyn std::error::Error + Send + Sync>> {
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        let moments = self.client.list::<Moment>(options).await?.items;
+        Ok(query_moments(moments, &query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{MomentContent, MomentSpec};
+
+    fn moment(name: &str, owner: &str, day: u32, visible: VisibleEnum, tags: &[&str]) -> Moment {
+        Moment {
+            metadata: Metadata::new(name),
+            spec: MomentSpec {
+                content: MomentContent {
+                    raw: format!("moment {}", name),
+                    html: String::new(),
+                    medium: None,
+                },
+                release_time: Some(Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()),
+                visible: Some(visible),
+                owner: Some(owner.to_string()),
+                tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_query_moments() {
+        let moments = vec![
+            moment("a", "alice", 1, VisibleEnum::Public, &["life"]),
+            moment("b", "alice", 3, VisibleEnum::Private, &[]),
+            moment("c", "bob", 2, VisibleEnum::Public, &["life", "code"]),
+        ];
+        let result = query_moments(moments.clone(), &MomentQuery::default());
+        let names: Vec<&str> = result.items.iter().map(|m| m.metadata.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "c", "a"]);
+
+        let query = MomentQuery { visible: Some(VisibleEnum::Public), tag: Some("life".to_string()), size: Some(1), ..Default::default() };
+        let result = query_moments(moments.clone(), &query);
+        assert_eq!(result.total, 2);
+        assert_eq!(result.items[0].metadata.name, "c");
+
+        let query = MomentQuery { owner: Some("bob".to_string()), ..Default::default() };
+        assert_eq!(query_moments(moments, &query).total, 1);
+    }
+}
@@ -0,0 +1,152 @@
+use flow_domain::content::SinglePage;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// 页面树节点
+#[derive(Debug, Clone, Serialize)]
+pub struct PageTreeNode {
+    pub page: SinglePage,
+    /// 包含所有祖先页面slug的层级链接
+    pub permalink: String,
+    pub children: Vec<PageTreeNode>,
+}
+
+/// 生成页面的层级链接（如 /about/team），父页面缺失或存在循环引用时在该处截断
+pub fn nested_permalink(page: &SinglePage, pages: &HashMap<&str, &SinglePage>) -> String {
+    let mut slugs = vec![page.spec.slug.as_str()];
+    let mut visited = HashSet::from([page.metadata.name.as_str()]);
+    let mut current = page;
+    while let Some(parent) = current.spec.parent.as_deref().and_then(|name| pages.get(name)) {
+        if !visited.insert(parent.metadata.name.as_str()) {
+            break;
+        }
+        slugs.push(parent.spec.slug.as_str());
+        current = parent;
+    }
+    slugs.reverse();
+    format!("/{}", slugs.join("/"))
+}
+
+/// 检查将页面的父页面设置为parent后是否会形成循环
+pub fn creates_cycle(name: &str, parent: Option<&str>, pages: &HashMap<&str, &SinglePage>) -> bool {
+    let mut visited = HashSet::new();
+    let mut current = parent;
+    while let Some(ancestor) = current {
+        if ancestor == name {
+            return true;
+        }
+        if !visited.insert(ancestor) {
+            return false;
+        }
+        current = pages.get(ancestor).and_then(|p| p.spec.parent.as_deref());
+    }
+    false
+}
+
+/// 按父子关系构建页面树，同级按order、标题排序；父页面不在列表中的页面作为根节点
+pub fn build_tree(pages: Vec<SinglePage>) -> Vec<PageTreeNode> {
+    let by_name: HashMap<&str, &SinglePage> = pages.iter()
+        .map(|p| (p.metadata.name.as_str(), p))
+        .collect();
+    let permalinks: HashMap<String, String> = pages.iter()
+        .map(|p| (p.metadata.name.clone(), nested_permalink(p, &by_name)))
+        .collect();
+    let roots: Vec<String> = pages.iter()
+        .filter(|p| {
+            p.spec.parent.as_deref().is_none_or(|parent| {
+                !by_name.contains_key(parent) || creates_cycle(&p.metadata.name, Some(parent), &by_name)
+            })
+        })
+        .map(|p| p.metadata.name.clone())
+        .collect();
+
+    let mut children: HashMap<String, Vec<SinglePage>> = HashMap::new();
+    let mut nodes: HashMap<String, SinglePage> = HashMap::new();
+    for page in pages.iter().cloned() {
+        if !roots.contains(&page.metadata.name) {
+            if let Some(parent) = page.spec.parent.clone() {
+                children.entry(parent).or_default().push(page.clone());
+            }
+        }
+        nodes.insert(page.metadata.name.clone(), page);
+    }
+
+    let mut root_pages: Vec<SinglePage> = roots.iter().filter_map(|name| nodes.remove(name)).collect();
+    sort_siblings(&mut root_pages);
+    root_pages.into_iter()
+        .map(|page| to_node(page, &mut children, &permalinks))
+        .collect()
+}
+
+fn to_node(
+    page: SinglePage,
+    children: &mut HashMap<String, Vec<SinglePage>>,
+    permalinks: &HashMap<String, String>,
+) -> PageTreeNode {
+    let mut child_pages = children.remove(&page.metadata.name).unwrap_or_default();
+    sort_siblings(&mut child_pages);
+    let permalink = permalinks.get(&page.metadata.name).cloned().unwrap_or_default();
+    PageTreeNode {
+        children: child_pages.into_iter()
+            .map(|child| to_node(child, children, permalinks))
+            .collect(),
+        page,
+        permalink,
+    }
+}
+
+fn sort_siblings(pages: &mut [SinglePage]) {
+    pages.sort_by(|a, b| {
+        a.spec.order.unwrap_or(i32::MAX).cmp(&b.spec.order.unwrap_or(i32::MAX))
+            .then_with(|| a.spec.title.cmp(&b.spec.title))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::SinglePageSpec;
+
+    fn page(name: &str, slug: &str, parent: Option<&str>, order: Option<i32>) -> SinglePage {
+        let mut spec: SinglePageSpec = serde_json::from_value(serde_json::json!({
+            "title": name,
+            "slug": slug,
+        })).unwrap();
+        spec.parent = parent.map(str::to_string);
+        spec.order = order;
+        SinglePage { metadata: Metadata::new(name), spec, status: None }
+    }
+
+    #[test]
+    fn test_nested_permalink() {
+        let pages = [page("about", "about", None, None), page("team", "team", Some("about"), None)];
+        let by_name: HashMap<&str, &SinglePage> = pages.iter().map(|p| (p.metadata.name.as_str(), p)).collect();
+        assert_eq!(nested_permalink(&pages[1], &by_name), "/about/team");
+        assert_eq!(nested_permalink(&pages[0], &by_name), "/about");
+    }
+
+    #[test]
+    fn test_creates_cycle() {
+        let pages = [page("a", "a", None, None), page("b", "b", Some("a"), None)];
+        let by_name: HashMap<&str, &SinglePage> = pages.iter().map(|p| (p.metadata.name.as_str(), p)).collect();
+        assert!(creates_cycle("a", Some("b"), &by_name));
+        assert!(creates_cycle("a", Some("a"), &by_name));
+        assert!(!creates_cycle("b", Some("a"), &by_name));
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let tree = build_tree(vec![
+            page("team", "team", Some("about"), Some(2)),
+            page("history", "history", Some("about"), Some(1)),
+            page("about", "about", None, None),
+            page("orphan", "orphan", Some("missing"), None),
+        ]);
+        assert_eq!(tree.len(), 2);
+        let about = tree.iter().find(|n| n.page.metadata.name == "about").unwrap();
+        let children: Vec<&str> = about.children.iter().map(|n| n.page.metadata.name.as_str()).collect();
+        assert_eq!(children, vec!["history", "team"]);
+        assert_eq!(about.children[1].permalink, "/about/team");
+    }
+}
@@ -3,8 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Delta类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DeltaType {
     #[serde(rename = "DELETE")]
     Delete,
@@ -28,21 +27,29 @@ pub struct Delta {
 pub struct StringChunk {
     pub position: i32,
     pub lines: Vec<String>,
-    #[serde(rename = "changePosition")]
+    #[serde(rename = "changePosition", default)]
     pub change_position: Vec<i32>,
 }
 
+/// 超过该规模（原始行数×修订行数）时不再计算LCS，直接将差异部分整体替换
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// 按行分割内容，空字符串没有任何行；与join("\n")互逆，保证内容可以原样还原
+fn split_lines(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split('\n').map(|s| s.to_string()).collect()
+    }
+}
+
 /// 应用patch到原始内容
 pub fn apply_patch(original: &str, patch_json: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // 解析patch JSON
     let deltas: Vec<Delta> = serde_json::from_str(patch_json)?;
     
     // 将原始内容按行分割
-    let mut lines: Vec<String> = if original.is_empty() {
-        Vec::new()
-    } else {
-        original.lines().map(|s| s.to_string()).collect()
-    };
+    let mut lines = split_lines(original);
     
     // 按位置倒序排序deltas，从后往前应用，避免位置偏移问题
     let mut sorted_deltas = deltas;
@@ -50,36 +57,23 @@ pub fn apply_patch(original: &str, patch_json: &str) -> Result<String, Box<dyn s
     
     // 应用每个delta
     for delta in sorted_deltas {
+        let start = delta.source.position.max(0) as usize;
+        if start > lines.len() {
+            return Err(format!("Patch position {} is out of range", start).into());
+        }
+        let end = (start + delta.source.lines.len()).min(lines.len());
         match delta.delta_type {
             DeltaType::Delete => {
                 // 删除操作：删除source位置的lines
-                let start = delta.source.position as usize;
-                let end = (start + delta.source.lines.len()).min(lines.len());
-                if start < lines.len() {
-                    lines.drain(start..end);
-                }
+                lines.drain(start..end);
             }
             DeltaType::Insert => {
                 // 插入操作：在source位置插入target的lines
-                let pos = delta.source.position as usize;
-                if pos <= lines.len() {
-                    let insert_lines = delta.target.lines.clone();
-                    for (i, line) in insert_lines.iter().enumerate() {
-                        lines.insert(pos + i, line.clone());
-                    }
-                }
+                lines.splice(start..start, delta.target.lines);
             }
             DeltaType::Change => {
                 // 修改操作：替换source位置的lines为target的lines
-                let start = delta.source.position as usize;
-                let end = (start + delta.source.lines.len()).min(lines.len());
-                if start < lines.len() {
-                    lines.drain(start..end);
-                    let replace_lines = delta.target.lines.clone();
-                    for (i, line) in replace_lines.iter().enumerate() {
-                        lines.insert(start + i, line.clone());
-                    }
-                }
+                lines.splice(start..end, delta.target.lines);
             }
         }
     }
@@ -88,80 +82,110 @@ pub fn apply_patch(original: &str, patch_json: &str) -> Result<String, Box<dyn s
     Ok(lines.join("\n"))
 }
 
-/// 生成diff patch（简化版本，实际应该使用更复杂的diff算法）
-/// 这里提供一个基础实现，实际生产环境应该使用更成熟的diff库
+/// 逐行比较的操作
+enum LineOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// 计算两组行之间的编辑操作（基于最长公共子序列）
+fn line_ops(original: &[String], revised: &[String]) -> Vec<LineOp> {
+    // 公共前缀和后缀不参与LCS计算
+    let prefix = original.iter().zip(revised).take_while(|(a, b)| a == b).count();
+    let suffix = original[prefix..].iter().rev()
+        .zip(revised[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &original[prefix..original.len() - suffix];
+    let new = &revised[prefix..revised.len() - suffix];
+
+    let mut ops: Vec<LineOp> = (0..prefix).map(|_| LineOp::Equal).collect();
+    if old.len() * new.len() > MAX_LCS_CELLS {
+        ops.extend(old.iter().map(|_| LineOp::Delete));
+        ops.extend(new.iter().map(|_| LineOp::Insert));
+    } else {
+        // lcs[i][j]为old[i..]与new[j..]的最长公共子序列长度
+        let width = new.len() + 1;
+        let mut lcs = vec![0u32; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i * width + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                ops.push(LineOp::Equal);
+                i += 1;
+                j += 1;
+            } else if j < new.len() && (i == old.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j]) {
+                ops.push(LineOp::Insert);
+                j += 1;
+            } else {
+                ops.push(LineOp::Delete);
+                i += 1;
+            }
+        }
+    }
+    ops.extend((0..suffix).map(|_| LineOp::Equal));
+    ops
+}
+
+/// 生成diff patch，每段连续的差异生成一个Delta，位置均相对于原始内容
 pub fn diff_to_json_patch(original: &str, revised: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    // 简化实现：如果内容相同，返回空patch
+    // 内容相同时返回空patch
     if original == revised {
         return Ok("[]".to_string());
     }
     
-    // 简单的逐行diff实现
-    let original_lines: Vec<String> = if original.is_empty() {
-        Vec::new()
-    } else {
-        original.lines().map(|s| s.to_string()).collect()
-    };
-    
-    let revised_lines: Vec<String> = if revised.is_empty() {
-        Vec::new()
-    } else {
-        revised.lines().map(|s| s.to_string()).collect()
-    };
+    let original_lines = split_lines(original);
+    let revised_lines = split_lines(revised);
     
-    // 使用简单的LCS（最长公共子序列）算法
     let mut deltas = Vec::new();
-    let mut i = 0;
-    let mut j = 0;
-    
-    while i < original_lines.len() || j < revised_lines.len() {
-        if i < original_lines.len() && j < revised_lines.len() && original_lines[i] == revised_lines[j] {
-            // 行匹配，继续
+    let (mut i, mut j) = (0usize, 0usize);
+    let mut ops = line_ops(&original_lines, &revised_lines).into_iter().peekable();
+    while let Some(op) = ops.next() {
+        if let LineOp::Equal = op {
             i += 1;
             j += 1;
-        } else if j < revised_lines.len() && (i >= original_lines.len() || (i < original_lines.len() && j < revised_lines.len() && original_lines[i] != revised_lines[j])) {
-            // 需要插入
-            let mut insert_lines = Vec::new();
-            let start_j = j;
-            while j < revised_lines.len() && (i >= original_lines.len() || original_lines[i] != revised_lines[j]) {
-                insert_lines.push(revised_lines[j].clone());
-                j += 1;
-            }
-            deltas.push(Delta {
-                source: StringChunk {
-                    position: i as i32,
-                    lines: Vec::new(),
-                    change_position: Vec::new(),
-                },
-                target: StringChunk {
-                    position: i as i32,
-                    lines: insert_lines,
-                    change_position: (start_j..j).map(|x| x as i32).collect(),
-                },
-                delta_type: DeltaType::Insert,
-            });
-        } else if i < original_lines.len() {
-            // 需要删除
-            let mut delete_lines = Vec::new();
-            let start_i = i;
-            while i < original_lines.len() && (j >= revised_lines.len() || original_lines[i] != revised_lines[j]) {
-                delete_lines.push(original_lines[i].clone());
-                i += 1;
+            continue;
+        }
+        
+        // 收集一段连续的删除和插入
+        let (start_i, start_j) = (i, j);
+        let mut op = Some(op);
+        while let Some(current) = op {
+            match current {
+                LineOp::Delete => i += 1,
+                LineOp::Insert => j += 1,
+                LineOp::Equal => unreachable!(),
             }
-            deltas.push(Delta {
-                source: StringChunk {
-                    position: start_i as i32,
-                    lines: delete_lines,
-                    change_position: Vec::new(),
-                },
-                target: StringChunk {
-                    position: start_i as i32,
-                    lines: Vec::new(),
-                    change_position: Vec::new(),
-                },
-                delta_type: DeltaType::Delete,
-            });
+            op = ops.next_if(|next| !matches!(next, LineOp::Equal));
         }
+        
+        let delta_type = match (i > start_i, j > start_j) {
+            (true, true) => DeltaType::Change,
+            (true, false) => DeltaType::Delete,
+            _ => DeltaType::Insert,
+        };
+        deltas.push(Delta {
+            source: StringChunk {
+                position: start_i as i32,
+                lines: original_lines[start_i..i].to_vec(),
+                change_position: Vec::new(),
+            },
+            target: StringChunk {
+                position: start_j as i32,
+                lines: revised_lines[start_j..j].to_vec(),
+                change_position: Vec::new(),
+            },
+            delta_type,
+        });
     }
     
     serde_json::to_string(&deltas).map_err(|e| e.into())
@@ -178,5 +202,19 @@ mod tests {
         let result = apply_patch(original, patch).unwrap();
         assert_eq!(result, "line1\nline2_modified\nline3");
     }
-}
 
+    #[test]
+    fn test_diff_round_trip() {
+        let cases = [
+            ("", "hello"),
+            ("hello\n", ""),
+            ("a\nb\nc\nd", "a\nx\nc\nd\ne"),
+            ("a\nb\nc", "c\nb\na"),
+            ("title\n\nbody\n", "title\n\nnew body\nmore\n"),
+        ];
+        for (original, revised) in cases {
+            let patch = diff_to_json_patch(original, revised).unwrap();
+            assert_eq!(apply_patch(original, &patch).unwrap(), revised, "{:?} -> {:?}", original, revised);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_domain::attachment::{Attachment, ThumbnailSize};
+use flow_domain::content::{Photo, PhotoGroup};
+use crate::attachment::AttachmentService;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 照片引用的附件无效时错误信息的前缀
+pub const INVALID_ATTACHMENT_ERROR: &str = "Invalid attachment";
+
+/// 分组及其下的照片（group为None表示未分组的照片）
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedPhotos {
+    pub group: Option<PhotoGroup>,
+    pub photos: Vec<Photo>,
+}
+
+/// 将照片按分组归类，分组和照片均按priority、名称排序；
+/// 未分组或所属分组不存在的照片放在最后，没有照片时不返回未分组项
+pub fn group_photos(mut groups: Vec<PhotoGroup>, mut photos: Vec<Photo>) -> Vec<GroupedPhotos> {
+    groups.sort_by(|a, b| {
+        a.spec.priority.cmp(&b.spec.priority)
+            .then_with(|| a.spec.display_name.cmp(&b.spec.display_name))
+    });
+    sort_photos(&mut photos);
+
+    let mut result: Vec<GroupedPhotos> = groups.into_iter()
+        .map(|group| GroupedPhotos { group: Some(group), photos: Vec::new() })
+        .collect();
+    let mut ungrouped = Vec::new();
+    for photo in photos {
+        let target = photo.spec.group_name.as_deref().and_then(|name| {
+            result.iter_mut().find(|g| g.group.as_ref().is_some_and(|group| group.metadata.name == name))
+        });
+        match target {
+            Some(grouped) => grouped.photos.push(photo),
+            None => ungrouped.push(photo),
+        }
+    }
+    if !ungrouped.is_empty() {
+        result.push(GroupedPhotos { group: None, photos: ungrouped });
+    }
+    result
+}
+
+fn sort_photos(photos: &mut [Photo]) {
+    photos.sort_by(|a, b| {
+        a.spec.priority.cmp(&b.spec.priority)
+            .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+    });
+}
+
+/// 用附件信息填充照片的链接、缩略图和默认显示名称
+pub fn bind_attachment(photo: &mut Photo, attachment: &Attachment) {
+    let status = attachment.status.as_ref();
+    let url = status.and_then(|s| s.permalink.clone());
+    let cover = status
+        .and_then(|s| s.thumbnails.as_ref())
+        .and_then(|thumbnails| thumbnails.get(ThumbnailSize::M.as_str()).cloned())
+        .or_else(|| url.clone());
+    photo.spec.url = url;
+    photo.spec.cover = cover;
+    if photo.spec.display_name.trim().is_empty() {
+        photo.spec.display_name = attachment.spec.display_name.clone()
+            .unwrap_or_else(|| attachment.metadata.name.clone());
+    }
+}
+
+/// 图库服务trait
+#[async_trait]
+pub trait PhotoService: Send + Sync {
+    /// 创建照片，引用的附件必须存在
+    async fn create(&self, photo: Photo) -> Result<Photo, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update(&self, photo: Photo) -> Result<Photo, Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get(&self, name: &str) -> Result<Option<Photo>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Photo>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 列出分组下的照片（group为None时列出未分组的照片），按排序返回
+    async fn list_by_group(&self, group: Option<&str>) -> Result<Vec<Photo>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 按给定顺序重排分组下的照片，返回重排后的照片
+    async fn reorder(&self, group: &str, names: Vec<String>) -> Result<Vec<Photo>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn create_group(&self, group: PhotoGroup) -> Result<PhotoGroup, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update_group(&self, group: PhotoGroup) -> Result<PhotoGroup, Box<dyn std::error::Error + Send + Sync>>;
+    /// 删除分组及分组下的照片（引用的附件保留）
+    async fn delete_group(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get_group(&self, name: &str) -> Result<Option<PhotoGroup>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list_groups(&self, options: ListOptions) -> Result<ListResult<PhotoGroup>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 按分组列出所有照片
+    async fn list_grouped(&self) -> Result<Vec<GroupedPhotos>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultPhotoService<C: ExtensionClient> {
+    client: Arc<C>,
+    attachment_service: Arc<dyn AttachmentService>,
+}
+
+impl<C: ExtensionClient> DefaultPhotoService<C> {
+    pub fn new(client: Arc<C>, attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self { client, attachment_service }
+    }
+
+    async fn resolve_attachment(&self, photo: &mut Photo) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let name = photo.spec.attachment_name.trim();
+        if name.is_empty() {
+            return Err(format!("{}: attachment name is required", INVALID_ATTACHMENT_ERROR).into());
+        }
+        let attachment = self.attachment_service.get(name).await
+            .map_err(|e| format!("Failed to get attachment {}: {}", name, e))?
+            .ok_or_else(|| format!("{}: {} not found", INVALID_ATTACHMENT_ERROR, name))?;
+        bind_attachment(photo, &attachment);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> PhotoService for DefaultPhotoService<C> {
+    async fn create(&self, mut photo: Photo) -> Result<Photo, Box<dyn std::error::Error + Send + Sync>> {
+        self.resolve_attachment(&mut photo).await?;
+        self.client.create(photo).await
+    }
+
+    async fn update(&self, mut photo: Photo) -> Result<Photo, Box<dyn std::error::Error + Send + Sync>> {
+        self.resolve_attachment(&mut photo).await?;
+        self.client.update(photo).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client.delete::<Photo>(name).await
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Photo>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Photo>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn list_by_group(&self, group: Option<&str>) -> Result<Vec<Photo>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut photos: Vec<Photo> = self.client.list_all::<Photo>(ListOptions::default()).await?
+            .into_iter()
+            .filter(|photo| photo.spec.group_name.as_deref().filter(|g| !g.is_empty()) == group)
+            .collect();
+        sort_photos(&mut photos);
+        Ok(photos)
+    }
+
+    async fn reorder(&self, group: &str, names: Vec<String>) -> Result<Vec<Photo>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut photos = self.list_by_group(Some(group)).await?;
+        // 未在names中出现的照片保持原有相对顺序排在后面
+        photos.sort_by_key(|photo| names.iter().position(|n| *n == photo.metadata.name).unwrap_or(usize::MAX));
+        let mut reordered = Vec::with_capacity(photos.len());
+        for (index, mut photo) in photos.into_iter().enumerate() {
+            let priority = index as i32;
+            if photo.spec.priority != priority {
+                photo.spec.priority = priority;
+                photo = self.client.update(photo).await?;
+            }
+            reordered.push(photo);
+        }
+        Ok(reordered)
+    }
+
+    async fn create_group(&self, group: PhotoGroup) -> Result<PhotoGroup, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.create(group).await
+    }
+
+    async fn update_group(&self, group: PhotoGroup) -> Result<PhotoGroup, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.update(group).await
+    }
+
+    async fn delete_group(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for photo in self.list_by_group(Some(name)).await? {
+            self.client.delete::<Photo>(&photo.metadata.name).await?;
+        }
+        self.client.delete::<PhotoGroup>(name).await
+    }
+
+    async fn get_group(&self, name: &str) -> Result<Option<PhotoGroup>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list_groups(&self, options: ListOptions) -> Result<ListResult<PhotoGroup>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn list_grouped(&self) -> Result<Vec<GroupedPhotos>, Box<dyn std::error::Error + Send + Sync>> {
+        let groups = self.client.list_all::<PhotoGroup>(ListOptions::default()).await?;
+        let photos = self.client.list_all::<Photo>(ListOptions::default()).await?;
+        Ok(group_photos(groups, photos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{PhotoGroupSpec, PhotoSpec};
+
+    fn photo(name: &str, priority: i32, group: Option<&str>) -> Photo {
+        Photo {
+            metadata: Metadata::new(name),
+            spec: PhotoSpec {
+                attachment_name: format!("{}-attachment", name),
+                display_name: String::new(),
+                description: None,
+                url: None,
+                cover: None,
+                priority,
+                group_name: group.map(str::to_string),
+            },
+        }
+    }
+
+    #[test]
+    fn test_group_photos() {
+        let group = PhotoGroup {
+            metadata: Metadata::new("travel"),
+            spec: PhotoGroupSpec { display_name: "Travel".to_string(), description: None, priority: 0 },
+        };
+        let grouped = group_photos(vec![group], vec![
+            photo("b", 1, Some("travel")),
+            photo("a", 2, Some("travel")),
+            photo("c", 0, Some("removed")),
+        ]);
+        assert_eq!(grouped.len(), 2);
+        let names: Vec<&str> = grouped[0].photos.iter().map(|p| p.metadata.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a"]);
+        assert!(grouped[1].group.is_none());
+    }
+
+    #[test]
+    fn test_bind_attachment() {
+        let attachment: Attachment = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "a1" },
+            "spec": { "displayName": "sunset.jpg" },
+            "status": {
+                "permalink": "/upload/sunset.jpg",
+                "thumbnails": { "M": "/upload/thumbnails/w800/sunset.jpg" }
+            }
+        })).unwrap();
+        let mut p = photo("p1", 0, None);
+        bind_attachment(&mut p, &attachment);
+        assert_eq!(p.spec.url.as_deref(), Some("/upload/sunset.jpg"));
+        assert_eq!(p.spec.cover.as_deref(), Some("/upload/thumbnails/w800/sunset.jpg"));
+        assert_eq!(p.spec.display_name, "sunset.jpg");
+    }
+}
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use flow_domain::content::{Post, VisibleEnum};
+use flow_infra::security::JwtService;
+use crate::content::PostService;
+use crate::security::PasswordService;
+use std::sync::Arc;
+
+/// 解锁令牌的默认有效期（30分钟）
+pub const DEFAULT_UNLOCK_TTL_SECONDS: u64 = 30 * 60;
+
+/// 解锁令牌的签发者（与登录令牌区分，避免互相冒用）
+pub const UNLOCK_TOKEN_ISSUER: &str = "flow/post-unlock";
+
+/// 获取文章解锁Cookie的名称
+pub fn unlock_cookie_name(post_name: &str) -> String {
+    format!("FLOW_POST_UNLOCK_{}", post_name)
+}
+
+/// 解锁成功后写入的Set-Cookie值
+pub fn unlock_cookie(post_name: &str, token: &str, max_age: u64) -> String {
+    format!("{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}", unlock_cookie_name(post_name), token, max_age)
+}
+
+/// 从请求的Cookie头中读取文章的解锁令牌
+pub fn unlock_token_from_cookie(cookies: &str, post_name: &str) -> Option<String> {
+    let cookie_name = unlock_cookie_name(post_name);
+    cookies.split(';').find_map(|cookie| {
+        let (key, value) = cookie.trim().split_once('=')?;
+        (key.trim() == cookie_name).then(|| value.trim().to_string())
+    })
+}
+
+/// 加密文章访问服务trait
+#[async_trait]
+pub trait PostAccessService: Send + Sync {
+    /// 设置或清除文章访问密码，设置密码时文章可见性同时变为Private
+    async fn set_password(&self, post_name: &str, password: Option<&str>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 校验密码，成功时返回解锁令牌
+    async fn unlock(&self, post_name: &str, password: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 检查令牌是否可以访问文章（无需密码的文章始终可以访问）
+    fn can_access(&self, post: &Post, token: Option<&str>) -> bool;
+
+    /// 解锁令牌的有效期（秒）
+    fn unlock_ttl(&self) -> u64;
+}
+
+/// 默认加密文章访问服务实现
+pub struct DefaultPostAccessService {
+    post_service: Arc<dyn PostService>,
+    password_service: Arc<dyn PasswordService>,
+    /// 使用独立签发者的JWT服务签发解锁令牌
+    unlock_jwt_service: Arc<JwtService>,
+}
+
+impl DefaultPostAccessService {
+    pub fn new(
+        post_service: Arc<dyn PostService>,
+        password_service: Arc<dyn PasswordService>,
+        unlock_jwt_service: Arc<JwtService>,
+    ) -> Self {
+        Self {
+            post_service,
+            password_service,
+            unlock_jwt_service,
+        }
+    }
+}
+
+#[async_trait]
+impl PostAccessService for DefaultPostAccessService {
+    async fn set_password(&self, post_name: &str, password: Option<&str>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut post = self.post_service.get_by_username(post_name, "").await?
+            .ok_or("Post not found")?;
+
+        match password.filter(|p| !p.is_empty()) {
+            Some(password) => {
+                post.spec.password = Some(self.password_service.hash(password).await?);
+                post.spec.visible = Some(VisibleEnum::Private);
+            }
+            None => post.spec.password = None,
+        }
+
+        self.post_service.update_by(post).await
+    }
+
+    async fn unlock(&self, post_name: &str, password: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.post_service.get_by_username(post_name, "").await?
+            .ok_or("Post not found")?;
+
+        let Some(hash) = post.spec.password.as_deref().filter(|_| post.requires_password()) else {
+            return Err("Post is not password protected".into());
+        };
+
+        if !self.password_service.verify(password, hash).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(self.unlock_jwt_service.generate(post.metadata.name.clone())?))
+    }
+
+    fn can_access(&self, post: &Post, token: Option<&str>) -> bool {
+        if !post.requires_password() {
+            return true;
+        }
+        token
+            .and_then(|token| self.unlock_jwt_service.verify(token).ok())
+            .map(|claims| claims.sub == post.metadata.name)
+            .unwrap_or(false)
+    }
+
+    fn unlock_ttl(&self) -> u64 {
+        self.unlock_jwt_service.expiration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::DefaultPostService;
+    use crate::security::{DefaultPasswordService, PasswordAlgorithm};
+    use crate::testing::MemoryClient;
+    use flow_api::extension::{ExtensionClient, Metadata};
+    use flow_domain::content::PostSpec;
+
+    fn post(name: &str) -> Post {
+        Post {
+            metadata: Metadata::new(name),
+            spec: serde_json::from_value::<PostSpec>(serde_json::json!({"title": name, "slug": name})).unwrap(),
+            status: None,
+        }
+    }
+
+    async fn service() -> DefaultPostAccessService {
+        let client = Arc::new(MemoryClient::default());
+        client.create(post("locked")).await.unwrap();
+        let jwt_service = JwtService::new("secret", UNLOCK_TOKEN_ISSUER.to_string(), DEFAULT_UNLOCK_TTL_SECONDS).unwrap();
+        DefaultPostAccessService::new(
+            Arc::new(DefaultPostService::new(client)),
+            Arc::new(DefaultPasswordService::new(PasswordAlgorithm::Bcrypt).with_bcrypt_cost(4)),
+            Arc::new(jwt_service),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_unlock_cookie_grants_access() {
+        let service = service().await;
+        let locked = service.set_password("locked", Some("open-sesame")).await.unwrap();
+        assert!(locked.requires_password());
+        assert!(!service.can_access(&locked, None));
+
+        let token = service.unlock("locked", "open-sesame").await.unwrap().unwrap();
+        // 浏览器随后的请求带上Set-Cookie中的键值对
+        let set_cookie = unlock_cookie("locked", &token, service.unlock_ttl());
+        let (pair, _) = set_cookie.split_once(';').unwrap();
+        let cookies = format!("theme=dark; {}", pair);
+        let cookie_token = unlock_token_from_cookie(&cookies, "locked");
+        assert_eq!(cookie_token.as_deref(), Some(token.as_str()));
+        assert!(service.can_access(&locked, cookie_token.as_deref()));
+
+        // 令牌只对签发时的文章有效
+        let mut other = locked.clone();
+        other.metadata.name = "other".to_string();
+        assert!(!service.can_access(&other, cookie_token.as_deref()));
+        assert_eq!(unlock_token_from_cookie(&cookies, "other"), None);
+    }
+
+    #[tokio::test]
+    async fn test_unlock_with_wrong_password() {
+        let service = service().await;
+        assert!(service.unlock("locked", "open-sesame").await.is_err());
+
+        let locked = service.set_password("locked", Some("open-sesame")).await.unwrap();
+        assert_eq!(service.unlock("locked", "wrong").await.unwrap(), None);
+        assert!(!service.can_access(&locked, Some("not-a-token")));
+
+        let cleared = service.set_password("locked", None).await.unwrap();
+        assert!(!cleared.requires_password());
+        assert!(service.can_access(&cleared, None));
+    }
+}
@@ -1,12 +1,16 @@
 use async_trait::async_trait;
-use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_api::extension::{Extension, ExtensionClient, ListOptions, ListResult};
 use flow_api::extension::query::Condition;
-use flow_domain::content::{Post, PostPhase, Snapshot};
+use flow_domain::content::{Post, PostPhase, Snapshot, SubjectRef};
 use flow_domain::content::constant;
 use std::sync::Arc;
 use chrono::Utc;
 use serde_json::Value;
-use crate::content::patch_utils;
+use crate::content::{snapshot_service, content_stats, slug_redirect_service, SlugRedirectService};
+use crate::content::archive::{self, ArchiveQuery, ArchiveYear};
+use crate::content::{ContentConverterRegistry, CoverService, PublishValidatorRegistry};
+use tracing::{debug, warn};
+use flow_plugin::event::{Event, EventBus, POST_PUBLISHED};
 
 /// Post请求，包含Post和内容
 #[derive(Debug, Clone)]
@@ -28,10 +32,14 @@ pub struct ContentRequest {
 pub struct PostQuery {
     pub published: Option<bool>,
     pub owner: Option<String>,
+    /// 贡献者过滤（所有者或共同作者）
+    pub contributor: Option<String>,
     pub category: Option<String>,
     pub tag: Option<String>,
     pub keyword: Option<String>,
     pub visible: Option<flow_domain::content::VisibleEnum>,
+    /// 语言过滤（BCP 47语言标签）
+    pub language: Option<String>,
     pub page: Option<u32>,
     pub size: Option<u32>,
 }
@@ -101,6 +109,14 @@ impl PostQuery {
             });
         }
         
+        // 语言过滤
+        if let Some(ref language) = self.language {
+            condition = condition.and(Condition::Equal {
+                index_name: "spec.language".to_string(),
+                value: Value::String(language.clone()),
+            });
+        }
+        
         // 所有者过滤
         if let Some(ref owner) = self.owner {
             condition = condition.and(Condition::Equal {
@@ -109,6 +125,8 @@ impl PostQuery {
             });
         }
         
+        // 贡献者过滤（所有者或共同作者）没有对应的索引，由 `list_post` 在内存中过滤
+        
         // 设置查询条件
         if !matches!(condition, Condition::Empty) {
             options.condition = Some(condition);
@@ -126,7 +144,38 @@ impl PostQuery {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ListedPost {
     pub post: Post,
-    // 可以添加其他列表显示需要的字段
+    
+    /// 字数（来自PostStatus）
+    #[serde(rename = "wordCount")]
+    pub word_count: Option<u64>,
+    
+    /// 预计阅读时间（分钟，来自PostStatus）
+    #[serde(rename = "readingTime")]
+    pub reading_time: Option<u32>,
+    
+    /// 封面缩略图链接（key为尺寸：XL, L, M, S），仅封面为附件时存在
+    #[serde(rename = "coverThumbnails", default, skip_serializing_if = "Option::is_none")]
+    pub cover_thumbnails: Option<std::collections::HashMap<String, String>>,
+}
+
+impl ListedPost {
+    pub fn new(post: Post) -> Self {
+        let status = post.status.as_ref();
+        let word_count = status.and_then(|s| s.word_count);
+        let reading_time = status.and_then(|s| s.reading_time);
+        Self {
+            post,
+            word_count,
+            reading_time,
+            cover_thumbnails: None,
+        }
+    }
+    
+    /// 去掉文章的访问密码哈希，返回给客户端前调用
+    pub fn without_password(mut self) -> Self {
+        self.post = self.post.without_password();
+        self
+    }
 }
 
 /// 内容包装器
@@ -181,39 +230,268 @@ pub trait PostService: Send + Sync {
     
     /// 回收文章（移到回收站）
     async fn recycle(&self, post_name: &str, username: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 列出文章的所有语言版本（包含自身）
+    async fn list_translations(&self, post_name: &str) -> Result<Vec<Post>, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 根据所有者、共同作者和快照作者重新计算status.contributors
+    async fn sync_contributors(&self, post_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 设置共同作者（所有者会被自动排除）
+    async fn set_contributors(&self, post_name: &str, contributors: Vec<String>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 按年月归档已发布的公开文章
+    async fn list_archives(&self, query: ArchiveQuery) -> Result<Vec<ArchiveYear>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 为文章创建一个新的空快照
+fn new_snapshot(post: &Post, owner: &str) -> Snapshot {
+    let suffix: String = uuid::Uuid::new_v4().to_string().chars().take(8).collect();
+    Snapshot {
+        metadata: flow_api::extension::Metadata::new(format!("{}-snapshot-{}", post.metadata.name, suffix)),
+        spec: flow_domain::content::SnapshotSpec {
+            subject_ref: SubjectRef {
+                group: constant::GROUP.to_string(),
+                version: constant::VERSION.to_string(),
+                kind: constant::POST_KIND.to_string(),
+                name: post.metadata.name.clone(),
+            },
+            raw_type: String::new(),
+            raw_patch: None,
+            content_patch: None,
+            parent_snapshot_name: None,
+            last_modify_time: None,
+            owner: owner.to_string(),
+            contributors: None,
+        },
+    }
 }
 
 /// 默认Post服务实现
 pub struct DefaultPostService<C: ExtensionClient> {
     client: Arc<C>,
+    redirect_service: Option<Arc<dyn SlugRedirectService>>,
+    converter_registry: Option<Arc<ContentConverterRegistry>>,
+    cover_service: Option<Arc<dyn CoverService>>,
+    publish_validators: Option<Arc<PublishValidatorRegistry>>,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl<C: ExtensionClient> DefaultPostService<C> {
     pub fn new(client: Arc<C>) -> Self {
-        Self { client }
+        Self {
+            client,
+            redirect_service: None,
+            converter_registry: None,
+            cover_service: None,
+            publish_validators: None,
+            event_bus: None,
+        }
+    }
+    
+    /// 设置事件总线，文章发布后发布 `PostPublished` 事件
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+    
+    /// 设置发布校验器注册表，发布前校验失败时返回汇总的错误而不发布
+    pub fn with_publish_validators(mut self, publish_validators: Arc<PublishValidatorRegistry>) -> Self {
+        self.publish_validators = Some(publish_validators);
+        self
+    }
+    
+    /// 使用待发布的head快照内容运行发布校验器
+    async fn validate_publish(&self, post: &Post) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(validators) = &self.publish_validators else {
+            return Ok(());
+        };
+        let content = match (&post.spec.head_snapshot, &post.spec.base_snapshot) {
+            (Some(head_snapshot), Some(base_snapshot)) => {
+                match self.get_content(head_snapshot, Some(base_snapshot)).await {
+                    Ok(content) => Some(content),
+                    Err(e) => {
+                        debug!("Failed to load content of post {} for validation: {}", post.metadata.name, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        validators.validate(post, content.as_ref()).await?;
+        Ok(())
+    }
+    
+    /// 设置封面服务，保存文章前校验封面，列表中附加封面缩略图
+    pub fn with_cover_service(mut self, cover_service: Arc<dyn CoverService>) -> Self {
+        self.cover_service = Some(cover_service);
+        self
+    }
+    
+    async fn validate_cover(&self, post: &Post) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match &self.cover_service {
+            Some(cover_service) => cover_service.validate(post.spec.cover.as_deref()).await,
+            None => Ok(()),
+        }
+    }
+    
+    /// 为列表中的文章附加封面缩略图，失败时不影响列表结果
+    async fn attach_cover_thumbnails(&self, posts: &mut [ListedPost]) {
+        let Some(cover_service) = &self.cover_service else {
+            return;
+        };
+        let covers: Vec<&str> = posts.iter().filter_map(|p| p.post.spec.cover.as_deref()).collect();
+        let thumbnails = match cover_service.thumbnails(&covers).await {
+            Ok(thumbnails) => thumbnails,
+            Err(e) => {
+                warn!("Failed to resolve cover thumbnails: {}", e);
+                return;
+            }
+        };
+        for listed in posts.iter_mut() {
+            listed.cover_thumbnails = listed.post.spec.cover.as_ref()
+                .and_then(|cover| thumbnails.get(cover).cloned());
+        }
+    }
+
+    /// 设置内容格式注册表，内容未保存渲染结果时按rawType生成HTML
+    pub fn with_converter_registry(mut self, converter_registry: Arc<ContentConverterRegistry>) -> Self {
+        self.converter_registry = Some(converter_registry);
+        self
+    }
+    
+    fn render_content(&self, content: ContentWrapper) -> ContentWrapper {
+        match &self.converter_registry {
+            Some(registry) => registry.render_content(content),
+            None => content,
+        }
+    }
+    
+    /// 设置重定向服务，slug变更时记录旧链接到新链接的301重定向
+    pub fn with_redirect_service(mut self, redirect_service: Arc<dyn SlugRedirectService>) -> Self {
+        self.redirect_service = Some(redirect_service);
+        self
+    }
+    
+    /// 保存文章内容：首次保存时创建完整的基础快照，之后只保存相对基础快照的补丁；
+    /// head快照已发布时创建新快照，否则直接更新head快照
+    async fn save_content(&self, post: &mut Post, content: ContentRequest) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let owner = post.spec.owner.clone().unwrap_or_default();
+        let base_snapshot_name = post.spec.base_snapshot.clone();
+        let head_snapshot_name = post.spec.head_snapshot.clone();
+        let editable_head = head_snapshot_name.clone()
+            .filter(|head| post.spec.release_snapshot.as_ref() != Some(head));
+        
+        let mut snapshot = match &editable_head {
+            Some(head) => self.client.fetch::<Snapshot>(head).await?
+                .ok_or("Head snapshot not found")?,
+            None => {
+                let mut snapshot = new_snapshot(post, &owner);
+                snapshot.spec.parent_snapshot_name = head_snapshot_name;
+                snapshot
+            }
+        };
+        snapshot.spec.raw_type = content.raw_type;
+        snapshot.spec.raw_patch = Some(content.raw);
+        snapshot.spec.content_patch = Some(content.content);
+        snapshot.spec.last_modify_time = Some(Utc::now());
+        snapshot.add_contributor(owner);
+        
+        match base_snapshot_name.as_deref().filter(|base| *base != snapshot.metadata.name) {
+            Some(base_name) => {
+                let base = self.client.fetch::<Snapshot>(base_name).await?
+                    .ok_or("Base snapshot not found")?;
+                snapshot_service::to_delta(&mut snapshot, &base)?;
+            }
+            None => snapshot_service::mark_as_base(&mut snapshot),
+        }
+        
+        let saved = if editable_head.is_some() {
+            self.client.update(snapshot).await?
+        } else {
+            self.client.create(snapshot).await?
+        };
+        if post.spec.base_snapshot.is_none() {
+            post.spec.base_snapshot = Some(saved.metadata.name.clone());
+        }
+        post.spec.head_snapshot = Some(saved.metadata.name);
+        Ok(())
+    }
+    
+    /// 更新文章，slug变更时同步permalink并记录重定向
+    async fn update_tracking_slug(&self, mut post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(redirect_service) = &self.redirect_service else {
+            return self.client.update(post).await;
+        };
+        
+        let old_post = self.client.fetch::<Post>(&post.metadata.name).await?;
+        let change = old_post
+            .filter(|old| old.spec.slug != post.spec.slug)
+            .map(|old| {
+                let old_path = old.status
+                    .and_then(|s| s.permalink)
+                    .unwrap_or_else(|| slug_redirect_service::post_permalink(&old.spec.slug));
+                (old_path, slug_redirect_service::post_permalink(&post.spec.slug))
+            });
+        
+        if let Some((_, new_path)) = &change {
+            let mut status = post.status_or_default();
+            status.permalink = Some(new_path.clone());
+            post.status = Some(status);
+        }
+        
+        let updated = self.client.update(post).await?;
+        
+        if let Some((old_path, new_path)) = change {
+            let subject_ref = SubjectRef {
+                group: constant::GROUP.to_string(),
+                version: constant::VERSION.to_string(),
+                kind: constant::POST_KIND.to_string(),
+                name: updated.metadata.name.clone(),
+            };
+            if let Err(e) = redirect_service.record(subject_ref, &old_path, &new_path).await {
+                warn!("Failed to record slug redirect for post {}: {}", updated.metadata.name, e);
+            }
+        }
+        
+        Ok(updated)
     }
 }
 
 #[async_trait]
 impl<C: ExtensionClient> PostService for DefaultPostService<C> {
     async fn list_post(&self, query: PostQuery) -> Result<ListResult<ListedPost>, Box<dyn std::error::Error + Send + Sync>> {
-        let options = query.to_list_options();
-        let result = self.client.list::<Post>(options).await?;
+        let result = match query.contributor {
+            Some(ref contributor) => {
+                let mut options = query.to_list_options();
+                let page = options.page.take().unwrap_or(0);
+                let size = options.size.take().unwrap_or(10);
+                let posts: Vec<Post> = self.client.list_all::<Post>(options).await?
+                    .into_iter()
+                    .filter(|p| p.can_edit(contributor))
+                    .collect();
+                let total = posts.len() as u64;
+                let items = posts.into_iter()
+                    .skip(page as usize * size as usize)
+                    .take(size as usize)
+                    .collect();
+                ListResult::new(items, total, page, size)
+            }
+            None => self.client.list::<Post>(query.to_list_options()).await?,
+        };
         
-        let listed_posts: Vec<ListedPost> = result.items
+        let mut listed_posts: Vec<ListedPost> = result.items
             .into_iter()
-            .map(|post| ListedPost { post })
+            .map(ListedPost::new)
             .collect();
+        self.attach_cover_thumbnails(&mut listed_posts).await;
         
         Ok(ListResult::new(listed_posts, result.total, result.page, result.size))
     }
 
     async fn draft_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
-        // TODO: 实现草稿创建逻辑
-        // 1. 创建Post
-        // 2. 如果提供了内容，创建Snapshot
-        // 3. 更新Post的headSnapshot和baseSnapshot
         let mut post = request.post;
+        self.validate_cover(&post).await?;
         
         // 设置默认值
         if post.spec.deleted.is_none() {
@@ -234,30 +512,33 @@ impl<C: ExtensionClient> PostService for DefaultPostService<C> {
         if post.spec.priority.is_none() {
             post.spec.priority = Some(0);
         }
+        // 访问密码只能通过PostAccessService设置
+        post.spec.password = None;
         
-        // 创建Post
-        let created_post = self.client.create(post).await?;
-        
-        // TODO: 如果提供了内容，创建Snapshot
-        // if let Some(content) = request.content {
-        //     // 创建Snapshot逻辑
-        // }
+        // 如果提供了内容，创建基础快照
+        if let Some(content) = request.content {
+            self.save_content(&mut post, content).await?;
+        }
         
-        Ok(created_post)
+        // 创建Post
+        self.client.create(post).await
     }
 
     async fn update_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
-        // TODO: 实现更新逻辑
-        // 1. 获取现有Post
-        // 2. 更新Post字段
-        // 3. 如果提供了内容，创建新的Snapshot
-        // 4. 更新Post
-        let post = request.post;
-        self.client.update(post).await
+        let mut post = request.post;
+        self.validate_cover(&post).await?;
+        // 返回给客户端的文章不包含访问密码哈希，保留已设置的密码
+        post.spec.password = self.client.fetch::<Post>(&post.metadata.name).await?
+            .and_then(|old| old.spec.password);
+        // 如果提供了内容，以补丁形式保存到head快照
+        if let Some(content) = request.content {
+            self.save_content(&mut post, content).await?;
+        }
+        self.update_tracking_slug(post).await
     }
 
     async fn update_by(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
-        self.client.update(post).await
+        self.update_tracking_slug(post).await
     }
 
     async fn get_head_content(&self, post_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
@@ -296,50 +577,28 @@ impl<C: ExtensionClient> PostService for DefaultPostService<C> {
             return Err("The snapshot is not a base snapshot".into());
         }
         
-        // 如果snapshot_name等于base_snapshot_name，直接返回base snapshot的内容
-        if snapshot_name == base_snapshot_name {
-            let raw = base_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-            let content = base_snapshot.spec.content_patch.as_deref().unwrap_or("");
-            return Ok(ContentWrapper {
-                snapshot_name: base_snapshot.metadata.name.clone(),
-                raw: raw.to_string(),
-                content: content.to_string(),
-                raw_type: base_snapshot.spec.raw_type.clone(),
-            });
-        }
-        
-        // 获取patch snapshot
-        let patch_snapshot = self.client.fetch::<Snapshot>(snapshot_name).await?
-            .ok_or_else(|| "Snapshot not found")?;
-        
-        // 应用patch
-        let base_raw = base_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-        let base_content = base_snapshot.spec.content_patch.as_deref().unwrap_or("");
-        
-        let raw_patch = patch_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-        let content_patch = patch_snapshot.spec.content_patch.as_deref().unwrap_or("");
-        
-        let patched_raw = if raw_patch.is_empty() {
-            base_raw.to_string()
+        // 获取要读取的snapshot（基础快照本身保存完整内容）
+        let snapshot = if snapshot_name == base_snapshot_name {
+            base_snapshot.clone()
         } else {
-            patch_utils::apply_patch(base_raw, raw_patch)?
+            self.client.fetch::<Snapshot>(snapshot_name).await?
+                .ok_or_else(|| "Snapshot not found")?
         };
         
-        let patched_content = if content_patch.is_empty() {
-            base_content.to_string()
-        } else {
-            patch_utils::apply_patch(base_content, content_patch)?
-        };
-        
-        Ok(ContentWrapper {
-            snapshot_name: patch_snapshot.metadata.name.clone(),
-            raw: patched_raw,
-            content: patched_content,
-            raw_type: patch_snapshot.spec.raw_type.clone(),
-        })
+        // 基于基础快照应用补丁还原完整内容
+        let (raw, content) = snapshot_service::restore_content(&snapshot, &base_snapshot)?;
+        Ok(self.render_content(ContentWrapper {
+            snapshot_name: snapshot.metadata.name,
+            raw,
+            content,
+            raw_type: snapshot.spec.raw_type,
+        }))
     }
 
     async fn publish(&self, mut post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        // 发布前校验，未通过时不修改文章
+        self.validate_publish(&post).await?;
+        
         // 设置发布标签
         if post.metadata.labels.is_none() {
             post.metadata.labels = Some(std::collections::HashMap::new());
@@ -350,19 +609,52 @@ impl<C: ExtensionClient> PostService for DefaultPostService<C> {
         
         // 设置发布状态
         post.spec.publish = Some(true);
-        if post.spec.publish_time.is_none() {
-            post.spec.publish_time = Some(Utc::now());
+        let publish_time = *post.spec.publish_time.get_or_insert_with(Utc::now);
+        archive::apply_archive_labels(&mut post, publish_time);
+        
+        // 发布head snapshot
+        if let Some(ref head_snapshot) = post.spec.head_snapshot {
+            post.spec.release_snapshot = Some(head_snapshot.clone());
         }
         
+        // 计算发布内容的字数和阅读时间
+        let stats = match (&post.spec.release_snapshot, &post.spec.base_snapshot) {
+            (Some(release_snapshot), Some(base_snapshot)) => {
+                match self.get_content(release_snapshot, Some(base_snapshot)).await {
+                    Ok(content) => Some(content_stats::compute(&content.content)),
+                    Err(e) => {
+                        debug!("Failed to compute stats for post {}: {}", post.metadata.name, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        
         // 更新状态
         if post.status.is_none() {
             post.status = Some(Default::default());
         }
         if let Some(ref mut status) = post.status {
             status.phase = Some(PostPhase::Published);
+            if let Some(stats) = stats {
+                status.word_count = Some(stats.word_count);
+                status.reading_time = Some(stats.reading_time);
+            }
         }
         
-        self.client.update(post).await
+        let published = self.client.update(post).await?;
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(Event::new(POST_PUBLISHED, serde_json::json!({
+                "name": published.metadata.name,
+                "title": published.spec.title,
+                "slug": published.spec.slug,
+                "owner": published.spec.owner,
+                "publishTime": published.spec.publish_time,
+                "permalink": published.status.as_ref().and_then(|s| s.permalink.clone()),
+            })));
+        }
+        Ok(published)
     }
 
     async fn unpublish(&self, mut post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
@@ -475,5 +767,135 @@ impl<C: ExtensionClient> PostService for DefaultPostService<C> {
         
         self.client.update(post).await
     }
+
+    async fn list_translations(&self, post_name: &str) -> Result<Vec<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.client.fetch::<Post>(post_name).await?
+            .ok_or("Post not found")?;
+        let group = post.translation_group().to_string();
+        
+        let posts = self.client.list_all::<Post>(ListOptions::default()).await?;
+        
+        Ok(posts
+            .into_iter()
+            .filter(|p| !p.is_deleted() && p.translation_group() == group)
+            .collect())
+    }
+
+    async fn sync_contributors(&self, post_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut post = self.client.fetch::<Post>(post_name).await?
+            .ok_or("Post not found")?;
+        
+        let snapshots: Vec<Snapshot> = self.client.list_all::<Snapshot>(ListOptions::default()).await?
+            .into_iter()
+            .filter(|s| s.spec.subject_ref.kind == constant::POST_KIND && s.spec.subject_ref.name == post_name)
+            .collect();
+        let contributors = post.merge_contributors(
+            snapshots.iter().flat_map(|s| s.spec.contributors.iter().flatten())
+        );
+        
+        let mut status = post.status_or_default();
+        if status.contributors.as_ref() == Some(&contributors) {
+            return Ok(post);
+        }
+        status.contributors = Some(contributors);
+        post.status = Some(status);
+        self.client.update(post).await
+    }
+    
+    async fn set_contributors(&self, post_name: &str, contributors: Vec<String>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut post = self.client.fetch::<Post>(post_name).await?
+            .ok_or("Post not found")?;
+        
+        let mut coauthors: Vec<String> = Vec::new();
+        for name in contributors.into_iter().filter(|c| !c.is_empty() && !post.is_owned_by(c)) {
+            if !coauthors.contains(&name) {
+                coauthors.push(name);
+            }
+        }
+        post.spec.contributors = (!coauthors.is_empty()).then_some(coauthors);
+        self.client.update(post).await?;
+        
+        self.sync_contributors(post_name).await
+    }
+    
+    async fn list_archives(&self, query: ArchiveQuery) -> Result<Vec<ArchiveYear>, Box<dyn std::error::Error + Send + Sync>> {
+        let posts: Vec<Post> = self.client.list_all::<Post>(ListOptions::default()).await?
+            .into_iter()
+            .filter(|p| p.is_published() && !p.is_deleted() && p.is_public())
+            .collect();
+        Ok(archive::group_archives(posts, &query))
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MemoryClient;
+    use chrono::TimeZone;
+    use flow_api::extension::{Metadata, SCAN_PAGE_SIZE};
+    use flow_domain::content::PostSpec;
+
+    fn post(name: &str, owner: &str, coauthors: &[&str]) -> Post {
+        let mut spec: PostSpec = serde_json::from_value(serde_json::json!({"title": name, "slug": name})).unwrap();
+        spec.owner = Some(owner.to_string());
+        spec.contributors = (!coauthors.is_empty()).then(|| coauthors.iter().map(|c| c.to_string()).collect());
+        Post { metadata: Metadata::new(name), spec, status: None }
+    }
+
+    #[tokio::test]
+    async fn test_sync_contributors_scans_all_snapshot_pages() {
+        let client = Arc::new(MemoryClient::default());
+        let target = client.create(post("target", "alice", &["bob"])).await.unwrap();
+        let other = post("other", "alice", &[]);
+        // 目标文章的快照排在第一页之后
+        for _ in 0..SCAN_PAGE_SIZE {
+            client.create(new_snapshot(&other, "zed")).await.unwrap();
+        }
+        let mut snapshot = new_snapshot(&target, "carol");
+        snapshot.add_contributor("carol".to_string());
+        client.create(snapshot).await.unwrap();
+
+        let service = DefaultPostService::new(client.clone());
+        let synced = service.sync_contributors("target").await.unwrap();
+        assert_eq!(synced.status.unwrap().contributors.unwrap(), vec!["alice", "bob", "carol"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_post_by_contributor() {
+        let client = Arc::new(MemoryClient::default());
+        client.create(post("own", "alice", &[])).await.unwrap();
+        client.create(post("shared", "bob", &["alice"])).await.unwrap();
+        client.create(post("foreign", "bob", &["carol"])).await.unwrap();
+        client.create(post("another", "alice", &[])).await.unwrap();
+        let service = DefaultPostService::new(client);
+
+        let query = PostQuery { contributor: Some("alice".to_string()), page: Some(0), size: Some(2), ..Default::default() };
+        let first = service.list_post(query.clone()).await.unwrap();
+        assert_eq!(first.total, 3);
+        let names: Vec<&str> = first.items.iter().map(|p| p.post.metadata.name.as_str()).collect();
+        assert_eq!(names, vec!["own", "shared"]);
+
+        let second = service.list_post(PostQuery { page: Some(1), ..query }).await.unwrap();
+        let names: Vec<&str> = second.items.iter().map(|p| p.post.metadata.name.as_str()).collect();
+        assert_eq!(names, vec!["another"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_archives_scans_every_page() {
+        let client = Arc::new(MemoryClient::default());
+        for i in 0..=SCAN_PAGE_SIZE {
+            let mut post = post(&format!("post-{}", i), "alice", &[]);
+            // 最早的文章位于第二页
+            let year = if i == SCAN_PAGE_SIZE { 2001 } else { 2024 };
+            archive::apply_archive_labels(&mut post, Utc.with_ymd_and_hms(year, 3, 1, 0, 0, 0).unwrap());
+            post.metadata.labels.get_or_insert_with(Default::default)
+                .insert(constant::POST_PUBLISHED_LABEL.to_string(), "true".to_string());
+            client.create(post).await.unwrap();
+        }
+        let service = DefaultPostService::new(client);
+
+        let archives = service.list_archives(ArchiveQuery::default()).await.unwrap();
+        let counts: Vec<(i32, usize)> = archives.iter().map(|y| (y.year, y.count)).collect();
+        assert_eq!(counts, vec![(2024, SCAN_PAGE_SIZE as usize), (2001, 1)]);
+    }
+}
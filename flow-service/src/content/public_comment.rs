@@ -0,0 +1,344 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use flow_domain::content::{constant, Comment, CommentOwner};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 默认每页评论数量
+pub const DEFAULT_PAGE_SIZE: u32 = 10;
+
+/// 每页评论数量上限
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// 每条评论默认内嵌的回复数量
+pub const DEFAULT_REPLY_SIZE: u32 = 3;
+
+/// 评论排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommentSort {
+    /// 按创建时间倒序
+    #[default]
+    CreationTime,
+    /// 按表情回应数与回复数之和倒序
+    Popularity,
+}
+
+/// 公开评论查询参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublicCommentQuery {
+    /// 评论主题的group，默认为内容组
+    pub group: Option<String>,
+    pub kind: String,
+    pub name: String,
+    pub sort: Option<CommentSort>,
+    /// 上一页返回的nextCursor
+    pub cursor: Option<String>,
+    pub size: Option<u32>,
+    /// 每条评论内嵌的回复数量
+    #[serde(rename = "replySize")]
+    pub reply_size: Option<u32>,
+}
+
+/// 公开回复查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PublicReplyQuery {
+    pub cursor: Option<String>,
+    pub size: Option<u32>,
+}
+
+/// 评论者的公开信息（邮箱评论者不返回邮箱）
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicCommentOwner {
+    pub kind: String,
+    pub name: Option<String>,
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    /// 头像地址（注册用户评论者，由调用方补充）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+}
+
+impl From<&CommentOwner> for PublicCommentOwner {
+    fn from(owner: &CommentOwner) -> Self {
+        let name = (owner.kind != CommentOwner::KIND_EMAIL).then(|| owner.name.clone());
+        Self {
+            kind: owner.kind.clone(),
+            name,
+            display_name: owner.display_name.clone(),
+            avatar: None,
+        }
+    }
+}
+
+/// 评论的访客安全视图，不包含邮箱、IP、User-Agent等信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicComment {
+    pub name: String,
+    pub owner: PublicCommentOwner,
+    pub content: String,
+    #[serde(rename = "creationTime")]
+    pub creation_time: Option<DateTime<Utc>>,
+    pub top: bool,
+    pub reactions: HashMap<String, i64>,
+    #[serde(rename = "replyCount")]
+    pub reply_count: u64,
+    /// 最早的若干条回复，其余回复通过回复接口分页获取
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<PublicComment>,
+}
+
+impl PublicComment {
+    pub fn from_comment(comment: &Comment) -> Self {
+        let reactions = comment.status.as_ref()
+            .and_then(|s| s.reactions.clone())
+            .unwrap_or_default();
+        Self {
+            name: comment.metadata.name.clone(),
+            owner: PublicCommentOwner::from(&comment.spec.owner),
+            content: comment.spec.content.clone(),
+            creation_time: creation_time(comment),
+            top: comment.spec.top.unwrap_or(false),
+            reactions,
+            reply_count: 0,
+            replies: Vec::new(),
+        }
+    }
+}
+
+/// 游标分页结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    /// 下一页游标，没有更多数据时为None
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+}
+
+/// 评论是否对访客可见（已批准、未隐藏、非垃圾评论）
+pub fn is_publicly_visible(comment: &Comment) -> bool {
+    comment.spec.approved.unwrap_or(false)
+        && !comment.spec.hidden.unwrap_or(false)
+        && !comment.is_spam()
+}
+
+/// 评论的创建时间（优先使用用户定义的创建时间）
+pub fn creation_time(comment: &Comment) -> Option<DateTime<Utc>> {
+    comment.spec.creation_time.or(comment.metadata.creation_timestamp)
+}
+
+/// 评论热度：表情回应数与可见回复数之和
+pub fn popularity(comment: &Comment, reply_count: u64) -> i64 {
+    let reactions: i64 = comment.status.as_ref()
+        .and_then(|s| s.reactions.as_ref())
+        .map(|r| r.values().sum())
+        .unwrap_or(0);
+    reactions + reply_count as i64
+}
+
+/// 排序键，按ranks升序、名称升序排列；游标记录上一页最后一项的排序键
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CursorKey {
+    pub ranks: Vec<i64>,
+    pub name: String,
+}
+
+impl CursorKey {
+    /// 顶层评论的排序键：置顶优先，其次按排序方式倒序
+    pub fn for_comment(comment: &Comment, sort: CommentSort, reply_count: u64) -> Self {
+        let top = i64::from(comment.spec.top.unwrap_or(false));
+        let time = creation_time(comment).map(|t| t.timestamp_millis()).unwrap_or(0);
+        let ranks = match sort {
+            CommentSort::CreationTime => vec![-top, -time],
+            CommentSort::Popularity => vec![-top, -popularity(comment, reply_count), -time],
+        };
+        Self { ranks, name: comment.metadata.name.clone() }
+    }
+
+    /// 回复的排序键：按创建时间正序
+    pub fn for_reply(reply: &Comment) -> Self {
+        let time = creation_time(reply).map(|t| t.timestamp_millis()).unwrap_or(0);
+        Self { ranks: vec![time], name: reply.metadata.name.clone() }
+    }
+
+    pub fn encode(&self) -> String {
+        let ranks: Vec<String> = self.ranks.iter().map(i64::to_string).collect();
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", ranks.join("_"), self.name))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let (ranks, name) = decoded.split_once(':')?;
+        let ranks = ranks.split('_')
+            .map(|r| r.parse().ok())
+            .collect::<Option<Vec<i64>>>()?;
+        Some(Self { ranks, name: name.to_string() })
+    }
+}
+
+/// 按排序键排序并返回游标之后的一页；无效的游标从第一页开始
+pub fn paginate<T>(mut entries: Vec<(CursorKey, T)>, cursor: Option<&str>, size: u32) -> CursorPage<T> {
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let total = entries.len() as u64;
+    let after = cursor.and_then(CursorKey::decode);
+    let mut remaining: Vec<(CursorKey, T)> = entries.into_iter()
+        .filter(|(key, _)| after.as_ref().is_none_or(|after| key > after))
+        .collect();
+
+    let size = size.clamp(1, MAX_PAGE_SIZE) as usize;
+    let has_more = remaining.len() > size;
+    remaining.truncate(size);
+    let next_cursor = if has_more {
+        remaining.last().map(|(key, _)| key.encode())
+    } else {
+        None
+    };
+    CursorPage {
+        items: remaining.into_iter().map(|(_, item)| item).collect(),
+        total,
+        next_cursor,
+        has_more,
+    }
+}
+
+/// 评论下对访客可见的直接回复
+fn visible_replies<'a>(comments: &'a [Comment], parent: &str) -> Vec<&'a Comment> {
+    comments.iter()
+        .filter(|c| {
+            let subject = &c.spec.subject_ref;
+            subject.group == constant::GROUP && subject.kind == constant::COMMENT_KIND && subject.name == parent
+        })
+        .filter(|c| is_publicly_visible(c))
+        .collect()
+}
+
+/// 主题下访客可见的顶层评论及其排序键，每条评论内嵌最早的若干条回复
+fn public_comment_entries(comments: &[Comment], query: &PublicCommentQuery) -> Vec<(CursorKey, PublicComment)> {
+    let group = query.group.as_deref().unwrap_or(constant::GROUP);
+    let sort = query.sort.unwrap_or_default();
+    let reply_size = query.reply_size.unwrap_or(DEFAULT_REPLY_SIZE).min(MAX_PAGE_SIZE) as usize;
+
+    comments.iter()
+        .filter(|c| {
+            let subject = &c.spec.subject_ref;
+            subject.group == group && subject.kind == query.kind && subject.name == query.name
+        })
+        .filter(|c| is_publicly_visible(c))
+        .map(|comment| {
+            let mut replies = visible_replies(comments, &comment.metadata.name);
+            replies.sort_by_key(|r| CursorKey::for_reply(r));
+            let reply_count = replies.len() as u64;
+            let mut public = PublicComment::from_comment(comment);
+            public.reply_count = reply_count;
+            public.replies = replies.into_iter().take(reply_size).map(PublicComment::from_comment).collect();
+            (CursorKey::for_comment(comment, sort, reply_count), public)
+        })
+        .collect()
+}
+
+/// 从全部评论中构建主题下访客可见的顶层评论分页，每条评论内嵌最早的若干条回复
+pub fn public_comments(comments: &[Comment], query: &PublicCommentQuery) -> CursorPage<PublicComment> {
+    let entries = public_comment_entries(comments, query);
+    paginate(entries, query.cursor.as_deref(), query.size.unwrap_or(DEFAULT_PAGE_SIZE))
+}
+
+/// 按页码分页的顶层评论（用于服务端渲染的评论区），page从1开始，返回当前页评论和总数
+pub fn public_comments_by_page(comments: &[Comment], query: &PublicCommentQuery, page: u32) -> (Vec<PublicComment>, u64) {
+    let mut entries = public_comment_entries(comments, query);
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let total = entries.len() as u64;
+    let size = query.size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+    let skip = (page.saturating_sub(1) as usize).saturating_mul(size);
+    let items = entries.into_iter().skip(skip).take(size).map(|(_, comment)| comment).collect();
+    (items, total)
+}
+
+/// 从全部评论中构建评论下访客可见的回复分页，按创建时间正序
+pub fn public_replies(comments: &[Comment], parent: &str, query: &PublicReplyQuery) -> CursorPage<PublicComment> {
+    let entries = visible_replies(comments, parent).into_iter()
+        .map(|reply| (CursorKey::for_reply(reply), PublicComment::from_comment(reply)))
+        .collect();
+    paginate(entries, query.cursor.as_deref(), query.size.unwrap_or(DEFAULT_PAGE_SIZE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn comment(name: &str, minute: u32, top: bool, likes: i64) -> Comment {
+        let mut comment: Comment = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": name },
+            "spec": {
+                "subjectRef": { "group": "content.halo.run", "version": "v1alpha1", "kind": "Post", "name": "p1" },
+                "raw": "hi",
+                "content": "<p>hi</p>",
+                "owner": { "kind": "Email", "name": "someone@example.com", "displayName": "Someone" },
+                "ipAddress": "127.0.0.1",
+                "approved": true,
+                "top": top,
+            },
+            "status": { "reactions": { "like": likes } },
+        })).unwrap();
+        comment.spec.creation_time = Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, 0).unwrap());
+        comment
+    }
+
+    #[test]
+    fn test_projection_hides_private_fields() {
+        let value = serde_json::to_value(PublicComment::from_comment(&comment("c1", 0, false, 0))).unwrap();
+        let text = value.to_string();
+        assert!(!text.contains("someone@example.com"));
+        assert!(!text.contains("127.0.0.1"));
+        assert_eq!(value["owner"]["displayName"], "Someone");
+    }
+
+    #[test]
+    fn test_paginate_with_cursor() {
+        let comments = [
+            comment("a", 1, false, 5),
+            comment("b", 2, false, 0),
+            comment("c", 3, false, 1),
+            comment("d", 0, true, 0),
+        ];
+        let entries = |sort| comments.iter()
+            .map(|c| (CursorKey::for_comment(c, sort, 0), c.metadata.name.clone()))
+            .collect::<Vec<_>>();
+
+        let first = paginate(entries(CommentSort::CreationTime), None, 2);
+        assert_eq!(first.items, vec!["d", "c"]);
+        assert!(first.has_more);
+        let second = paginate(entries(CommentSort::CreationTime), first.next_cursor.as_deref(), 2);
+        assert_eq!(second.items, vec!["b", "a"]);
+        assert!(second.next_cursor.is_none());
+
+        let popular = paginate(entries(CommentSort::Popularity), None, 10);
+        assert_eq!(popular.items, vec!["d", "a", "c", "b"]);
+        assert_eq!(paginate(entries(CommentSort::Popularity), Some("not-a-cursor"), 10).items.len(), 4);
+    }
+
+    #[test]
+    fn test_public_comments_by_page() {
+        let mut hidden = comment("e", 4, false, 0);
+        hidden.spec.approved = Some(false);
+        let comments = [comment("a", 1, false, 0), comment("b", 2, false, 0), comment("c", 3, false, 0), hidden];
+        let query = PublicCommentQuery {
+            group: None,
+            kind: "Post".to_string(),
+            name: "p1".to_string(),
+            sort: None,
+            cursor: None,
+            size: Some(2),
+            reply_size: None,
+        };
+        let (first, total) = public_comments_by_page(&comments, &query, 1);
+        assert_eq!(total, 3);
+        assert_eq!(first.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["c", "b"]);
+        let (second, _) = public_comments_by_page(&comments, &query, 2);
+        assert_eq!(second.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        assert!(public_comments_by_page(&comments, &query, 3).0.is_empty());
+    }
+}
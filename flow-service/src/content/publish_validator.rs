@@ -0,0 +1,258 @@
+use async_trait::async_trait;
+use flow_api::extension::ListOptions;
+use flow_domain::content::Post;
+use crate::attachment::AttachmentService;
+use crate::content::ContentWrapper;
+use crate::content::cover_service::match_attachment;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+/// 发布校验失败错误信息的前缀
+pub const PUBLISH_VALIDATION_ERROR: &str = "Publish validation failed";
+
+/// 扫描附件时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 本站附件访问路径
+const UPLOAD_PATH: &str = "/upload/";
+
+/// 单条发布校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishViolation {
+    /// 产生该错误的校验器名称
+    pub validator: String,
+    pub message: String,
+}
+
+/// 发布校验失败时返回的聚合错误
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishValidationError {
+    pub violations: Vec<PublishViolation>,
+}
+
+impl std::fmt::Display for PublishValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<&str> = self.violations.iter().map(|v| v.message.as_str()).collect();
+        write!(f, "{}: {}", PUBLISH_VALIDATION_ERROR, messages.join("; "))
+    }
+}
+
+impl std::error::Error for PublishValidationError {}
+
+/// 发布前校验器，返回发现的所有问题（为空表示通过）
+#[async_trait]
+pub trait PublishValidator: Send + Sync {
+    /// 校验器名称
+    fn name(&self) -> &str;
+
+    /// 校验即将发布的文章及其内容（content为待发布快照的内容，获取失败时为None）
+    async fn validate(&self, post: &Post, content: Option<&ContentWrapper>) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 校验标题和别名不能为空
+pub struct RequiredFieldsValidator;
+
+#[async_trait]
+impl PublishValidator for RequiredFieldsValidator {
+    fn name(&self) -> &str {
+        "required-fields"
+    }
+
+    async fn validate(&self, post: &Post, _content: Option<&ContentWrapper>) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut problems = Vec::new();
+        if post.spec.title.trim().is_empty() {
+            problems.push("Title is required".to_string());
+        }
+        if post.spec.slug.trim().is_empty() {
+            problems.push("Slug is required".to_string());
+        }
+        Ok(problems)
+    }
+}
+
+/// 校验待发布内容不能为空
+pub struct NonEmptyContentValidator;
+
+#[async_trait]
+impl PublishValidator for NonEmptyContentValidator {
+    fn name(&self) -> &str {
+        "non-empty-content"
+    }
+
+    async fn validate(&self, _post: &Post, content: Option<&ContentWrapper>) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let is_empty = content.is_none_or(|c| c.raw.trim().is_empty() && c.content.trim().is_empty());
+        Ok(if is_empty { vec!["Content is empty".to_string()] } else { Vec::new() })
+    }
+}
+
+/// 提取HTML中src、href属性引用的本站附件链接
+pub fn extract_upload_urls(html: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    for attr in ["src=", "href="] {
+        let mut rest = html;
+        while let Some(pos) = rest.find(attr) {
+            rest = &rest[pos + attr.len()..];
+            let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                continue;
+            };
+            let value = &rest[1..];
+            let Some(end) = value.find(quote) else {
+                break;
+            };
+            let url = &value[..end];
+            if url.contains(UPLOAD_PATH) && !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+            rest = &value[end..];
+        }
+    }
+    urls
+}
+
+/// 校验内容和封面引用的本站附件均存在
+pub struct AttachmentReferenceValidator {
+    attachment_service: Arc<dyn AttachmentService>,
+}
+
+impl AttachmentReferenceValidator {
+    pub fn new(attachment_service: Arc<dyn AttachmentService>) -> Self {
+        Self { attachment_service }
+    }
+}
+
+#[async_trait]
+impl PublishValidator for AttachmentReferenceValidator {
+    fn name(&self) -> &str {
+        "attachment-references"
+    }
+
+    async fn validate(&self, post: &Post, content: Option<&ContentWrapper>) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut urls = content.map(|c| extract_upload_urls(&c.content)).unwrap_or_default();
+        if let Some(cover) = post.spec.cover.as_deref().filter(|c| c.contains(UPLOAD_PATH)) {
+            urls.push(cover.to_string());
+        }
+        if urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        let attachments = self.attachment_service.list(options).await?;
+        Ok(urls.into_iter()
+            .filter(|url| match_attachment(url, &attachments).is_none())
+            .map(|url| format!("Attachment not found: {}", url))
+            .collect())
+    }
+}
+
+/// 发布校验器注册表，插件可在运行时注册额外的校验器
+pub struct PublishValidatorRegistry {
+    validators: RwLock<Vec<Arc<dyn PublishValidator>>>,
+}
+
+impl Default for PublishValidatorRegistry {
+    /// 创建包含内置校验器（标题别名、内容非空）的注册表
+    fn default() -> Self {
+        let registry = Self::empty();
+        registry.register(Arc::new(RequiredFieldsValidator));
+        registry.register(Arc::new(NonEmptyContentValidator));
+        registry
+    }
+}
+
+impl PublishValidatorRegistry {
+    /// 创建空的注册表
+    pub fn empty() -> Self {
+        Self {
+            validators: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// 注册校验器，同名校验器会被替换
+    pub fn register(&self, validator: Arc<dyn PublishValidator>) {
+        let mut validators = self.validators.write().unwrap();
+        validators.retain(|v| v.name() != validator.name());
+        validators.push(validator);
+    }
+
+    /// 移除指定名称的校验器
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn PublishValidator>> {
+        let mut validators = self.validators.write().unwrap();
+        let index = validators.iter().position(|v| v.name() == name)?;
+        Some(validators.remove(index))
+    }
+
+    /// 已注册的校验器名称
+    pub fn names(&self) -> Vec<String> {
+        self.validators.read().unwrap().iter().map(|v| v.name().to_string()).collect()
+    }
+
+    /// 依次运行所有校验器并汇总问题，校验器自身出错也视为校验失败
+    pub async fn validate(&self, post: &Post, content: Option<&ContentWrapper>) -> Result<(), PublishValidationError> {
+        let validators = self.validators.read().unwrap().clone();
+        let mut violations = Vec::new();
+        for validator in validators {
+            let messages = match validator.validate(post, content).await {
+                Ok(messages) => messages,
+                Err(e) => vec![format!("Validator error: {}", e)],
+            };
+            violations.extend(messages.into_iter().map(|message| PublishViolation {
+                validator: validator.name().to_string(),
+                message,
+            }));
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PublishValidationError { violations })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::PostSpec;
+
+    fn post(title: &str, slug: &str) -> Post {
+        Post {
+            metadata: Metadata::new("p1"),
+            spec: serde_json::from_value::<PostSpec>(serde_json::json!({
+                "title": title,
+                "slug": slug,
+            })).unwrap(),
+            status: None,
+        }
+    }
+
+    fn content(raw: &str) -> ContentWrapper {
+        ContentWrapper {
+            snapshot_name: "s1".to_string(),
+            raw: raw.to_string(),
+            content: String::new(),
+            raw_type: "markdown".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extract_upload_urls() {
+        let html = r#"<img src="/upload/a.png"><a href='https://x.com/upload/b.pdf'>b</a><img src="https://cdn.com/c.png"><img src="/upload/a.png">"#;
+        assert_eq!(extract_upload_urls(html), vec!["/upload/a.png", "https://x.com/upload/b.pdf"]);
+    }
+
+    #[tokio::test]
+    async fn test_registry_aggregates_violations() {
+        let registry = PublishValidatorRegistry::default();
+        let err = registry.validate(&post("", " "), None).await.unwrap_err();
+        let validators: Vec<&str> = err.violations.iter().map(|v| v.validator.as_str()).collect();
+        assert_eq!(validators, vec!["required-fields", "required-fields", "non-empty-content"]);
+        assert!(err.to_string().starts_with(PUBLISH_VALIDATION_ERROR));
+
+        assert!(registry.validate(&post("Hello", "hello"), Some(&content("# Hi"))).await.is_ok());
+        registry.unregister("non-empty-content");
+        assert!(registry.validate(&post("Hello", "hello"), None).await.is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use flow_api::extension::ListResult;
+use flow_domain::attachment::AttachmentReference;
+use flow_domain::content::Post;
+use crate::attachment::AttachmentReferenceService;
+use crate::attachment::reference::POST_REFERENCE_KIND;
+use crate::content::{PostService, PostRequest, PostQuery, ListedPost, ContentWrapper, ArchiveQuery, ArchiveYear};
+use std::sync::Arc;
+use tracing::warn;
+
+/// 跟踪附件引用的Post服务包装器
+/// 在Post发布/取消发布/回收时扫描发布内容和封面，更新附件的引用列表
+pub struct ReferenceTrackingPostService {
+    inner: Arc<dyn PostService>,
+    reference_service: Arc<dyn AttachmentReferenceService>,
+}
+
+impl ReferenceTrackingPostService {
+    pub fn new(inner: Arc<dyn PostService>, reference_service: Arc<dyn AttachmentReferenceService>) -> Self {
+        Self { inner, reference_service }
+    }
+    
+    /// 更新Post引用的附件，未发布或已删除的Post不引用任何附件
+    async fn track_references(&self, post: &Post) {
+        let mut texts = Vec::new();
+        if post.is_published() && !post.is_deleted() {
+            texts.extend(post.spec.cover.clone());
+            match self.inner.get_release_content(&post.metadata.name).await {
+                Ok(content) => texts.extend([content.raw, content.content]),
+                Err(e) => {
+                    // 无法确认发布内容时保留原有的引用记录
+                    warn!("Failed to get release content of post {}: {}", post.metadata.name, e);
+                    return;
+                }
+            }
+        }
+        let reference = AttachmentReference::new(POST_REFERENCE_KIND, &post.metadata.name, Some(post.spec.title.clone()));
+        if let Err(e) = self.reference_service.update_references(reference, texts).await {
+            warn!("Failed to update attachment references of post {}: {}", post.metadata.name, e);
+        }
+    }
+}
+
+#[async_trait]
+impl PostService for ReferenceTrackingPostService {
+    async fn list_post(&self, query: PostQuery) -> Result<ListResult<ListedPost>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_post(query).await
+    }
+    
+    async fn draft_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.draft_post(request).await
+    }
+    
+    async fn update_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.inner.update_post(request).await?;
+        // 已发布文章的封面修改后立即生效
+        self.track_references(&post).await;
+        Ok(post)
+    }
+    
+    async fn update_by(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.update_by(post).await
+    }
+    
+    async fn get_head_content(&self, post_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_head_content(post_name).await
+    }
+    
+    async fn get_release_content(&self, post_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_release_content(post_name).await
+    }
+    
+    async fn get_content(&self, snapshot_name: &str, base_snapshot_name: Option<&str>) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_content(snapshot_name, base_snapshot_name).await
+    }
+    
+    async fn publish(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let published_post = self.inner.publish(post).await?;
+        // 发布后扫描发布内容
+        self.track_references(&published_post).await;
+        Ok(published_post)
+    }
+    
+    async fn unpublish(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let unpublished_post = self.inner.unpublish(post).await?;
+        // 取消发布后不再引用附件
+        self.track_references(&unpublished_post).await;
+        Ok(unpublished_post)
+    }
+    
+    async fn get_by_username(&self, post_name: &str, username: &str) -> Result<Option<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_by_username(post_name, username).await
+    }
+    
+    async fn revert_to_snapshot(&self, post_name: &str, snapshot_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.revert_to_snapshot(post_name, snapshot_name).await
+    }
+    
+    async fn delete_content(&self, post_name: &str, snapshot_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_content(post_name, snapshot_name).await
+    }
+    
+    async fn recycle(&self, post_name: &str, username: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.inner.recycle(post_name, username).await?;
+        // 回收后不再引用附件
+        self.track_references(&post).await;
+        Ok(post)
+    }
+    
+    async fn list_translations(&self, post_name: &str) -> Result<Vec<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_translations(post_name).await
+    }
+    
+    async fn sync_contributors(&self, post_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.sync_contributors(post_name).await
+    }
+    
+    async fn set_contributors(&self, post_name: &str, contributors: Vec<String>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_contributors(post_name, contributors).await
+    }
+    
+    async fn list_archives(&self, query: ArchiveQuery) -> Result<Vec<ArchiveYear>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_archives(query).await
+    }
+}
+
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use flow_api::extension::{ListOptions, ListResult};
+use flow_domain::attachment::AttachmentReference;
+use flow_domain::content::SinglePage;
+use crate::attachment::AttachmentReferenceService;
+use crate::attachment::reference::SINGLE_PAGE_REFERENCE_KIND;
+use crate::content::{SinglePageService, ContentWrapper, PageTreeNode};
+use std::sync::Arc;
+use tracing::warn;
+
+/// 跟踪附件引用的SinglePage服务包装器
+/// 在SinglePage发布/取消发布/删除时扫描发布内容和封面，更新附件的引用列表
+pub struct ReferenceTrackingSinglePageService {
+    inner: Arc<dyn SinglePageService>,
+    reference_service: Arc<dyn AttachmentReferenceService>,
+}
+
+impl ReferenceTrackingSinglePageService {
+    pub fn new(inner: Arc<dyn SinglePageService>, reference_service: Arc<dyn AttachmentReferenceService>) -> Self {
+        Self { inner, reference_service }
+    }
+    
+    /// 更新SinglePage引用的附件，未发布或已删除的SinglePage不引用任何附件
+    async fn track_references(&self, page: &SinglePage) {
+        let mut texts = Vec::new();
+        if page.is_published() && !page.spec.deleted.unwrap_or(false) {
+            texts.extend(page.spec.cover.clone());
+            match self.inner.get_release_content(&page.metadata.name).await {
+                Ok(content) => texts.extend([content.raw, content.content]),
+                Err(e) => {
+                    // 无法确认发布内容时保留原有的引用记录
+                    warn!("Failed to get release content of single page {}: {}", page.metadata.name, e);
+                    return;
+                }
+            }
+        }
+        self.update_references(&page.metadata.name, Some(page.spec.title.clone()), texts).await;
+    }
+
+    async fn update_references(&self, name: &str, title: Option<String>, texts: Vec<String>) {
+        let reference = AttachmentReference::new(SINGLE_PAGE_REFERENCE_KIND, name, title);
+        if let Err(e) = self.reference_service.update_references(reference, texts).await {
+            warn!("Failed to update attachment references of single page {}: {}", name, e);
+        }
+    }
+}
+
+#[async_trait]
+impl SinglePageService for ReferenceTrackingSinglePageService {
+    async fn create(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.create(page).await
+    }
+    
+    async fn update(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        let updated_page = self.inner.update(page).await?;
+        // 已发布页面的封面修改后立即生效
+        self.track_references(&updated_page).await;
+        Ok(updated_page)
+    }
+    
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete(name).await?;
+        // 删除后不再引用附件
+        self.update_references(name, None, Vec::new()).await;
+        Ok(())
+    }
+    
+    async fn get(&self, name: &str) -> Result<Option<SinglePage>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get(name).await
+    }
+    
+    async fn list(&self, options: ListOptions) -> Result<ListResult<SinglePage>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list(options).await
+    }
+    
+    async fn publish(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        let published_page = self.inner.publish(page).await?;
+        // 发布后扫描发布内容
+        self.track_references(&published_page).await;
+        Ok(published_page)
+    }
+    
+    async fn unpublish(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        let unpublished_page = self.inner.unpublish(page).await?;
+        // 取消发布后不再引用附件
+        self.track_references(&unpublished_page).await;
+        Ok(unpublished_page)
+    }
+    
+    async fn get_head_content(&self, page_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_head_content(page_name).await
+    }
+    
+    async fn get_release_content(&self, page_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_release_content(page_name).await
+    }
+    
+    async fn get_content(&self, snapshot_name: &str, base_snapshot_name: Option<&str>) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_content(snapshot_name, base_snapshot_name).await
+    }
+    
+    async fn list_tree(&self, published_only: bool) -> Result<Vec<PageTreeNode>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tree(published_only).await
+    }
+}
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use flow_api::extension::ListResult;
 use flow_domain::content::Post;
-use crate::content::{PostService, PostRequest, PostQuery, ListedPost, ContentWrapper};
+use crate::content::{PostService, PostRequest, PostQuery, ListedPost, ContentWrapper, ArchiveQuery, ArchiveYear};
 use crate::search::{SearchService, DocumentConverter};
 use std::sync::Arc;
 use tracing::{debug, warn};
@@ -131,5 +131,21 @@ impl PostService for SearchIndexingPostService {
         self.update_search_index(&post).await;
         Ok(post)
     }
+    
+    async fn list_translations(&self, post_name: &str) -> Result<Vec<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_translations(post_name).await
+    }
+    
+    async fn sync_contributors(&self, post_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.sync_contributors(post_name).await
+    }
+    
+    async fn set_contributors(&self, post_name: &str, contributors: Vec<String>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_contributors(post_name, contributors).await
+    }
+    
+    async fn list_archives(&self, query: ArchiveQuery) -> Result<Vec<ArchiveYear>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_archives(query).await
+    }
 }
 
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use flow_api::extension::{ListOptions, ListResult};
 use flow_domain::content::SinglePage;
-use crate::content::{SinglePageService, ContentWrapper};
+use crate::content::{SinglePageService, ContentWrapper, PageTreeNode};
 use crate::search::{SearchService, DocumentConverter};
 use std::sync::Arc;
 use tracing::{debug, warn};
@@ -127,5 +127,8 @@ impl SinglePageService for SearchIndexingSinglePageService {
     async fn get_content(&self, snapshot_name: &str, base_snapshot_name: Option<&str>) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
         self.inner.get_content(snapshot_name, base_snapshot_name).await
     }
+    
+    async fn list_tree(&self, published_only: bool) -> Result<Vec<PageTreeNode>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tree(published_only).await
+    }
 }
-
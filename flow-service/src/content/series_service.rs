@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_domain::content::Series;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 系列导航信息（用于主题端展示上一篇/下一篇）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesNavigation {
+    /// 系列名称
+    pub name: String,
+
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+
+    pub slug: String,
+
+    /// 当前文章在系列中的位置（从1开始）
+    pub index: usize,
+
+    /// 系列文章总数
+    pub total: usize,
+
+    /// 上一篇文章名称
+    pub previous: Option<String>,
+
+    /// 下一篇文章名称
+    pub next: Option<String>,
+}
+
+impl SeriesNavigation {
+    /// 根据系列和文章名称计算导航信息，文章不在系列中时返回None
+    pub fn compute(series: &Series, post_name: &str) -> Option<Self> {
+        let position = series.position_of(post_name)?;
+        let posts = &series.spec.posts;
+        Some(Self {
+            name: series.metadata.name.clone(),
+            display_name: series.spec.display_name.clone(),
+            slug: series.spec.slug.clone(),
+            index: position + 1,
+            total: posts.len(),
+            previous: position.checked_sub(1).map(|i| posts[i].clone()),
+            next: posts.get(position + 1).cloned(),
+        })
+    }
+}
+
+/// Series服务trait
+#[async_trait]
+pub trait SeriesService: Send + Sync {
+    async fn create(&self, series: Series) -> Result<Series, Box<dyn std::error::Error + Send + Sync>>;
+    async fn update(&self, series: Series) -> Result<Series, Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn get(&self, name: &str) -> Result<Option<Series>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Series>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 根据slug获取系列
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Series>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 获取文章所属系列的导航信息
+    async fn navigation_for_post(&self, post_name: &str) -> Result<Option<SeriesNavigation>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultSeriesService<C: ExtensionClient> {
+    client: Arc<C>,
+}
+
+impl<C: ExtensionClient> DefaultSeriesService<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+
+    /// 同步状态中的文章数量
+    fn refresh_status(series: &mut Series) {
+        let mut status = series.status_or_default();
+        status.post_count = Some(series.spec.posts.len() as i32);
+        series.status = Some(status);
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> SeriesService for DefaultSeriesService<C> {
+    async fn create(&self, mut series: Series) -> Result<Series, Box<dyn std::error::Error + Send + Sync>> {
+        Self::refresh_status(&mut series);
+        self.client.create(series).await
+    }
+
+    async fn update(&self, mut series: Series) -> Result<Series, Box<dyn std::error::Error + Send + Sync>> {
+        Self::refresh_status(&mut series);
+        self.client.update(series).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client.delete::<Series>(name).await
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Series>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.fetch(name).await
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Series>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Series>, Box<dyn std::error::Error + Send + Sync>> {
+        let series = self.client.list_all::<Series>(ListOptions::default()).await?;
+        Ok(series.into_iter().find(|s| s.spec.slug == slug))
+    }
+
+    async fn navigation_for_post(&self, post_name: &str) -> Result<Option<SeriesNavigation>, Box<dyn std::error::Error + Send + Sync>> {
+        let series = self.client.list_all::<Series>(ListOptions::default()).await?;
+        Ok(series
+            .iter()
+            .find_map(|series| SeriesNavigation::compute(series, post_name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::SeriesSpec;
+
+    fn series(posts: &[&str]) -> Series {
+        Series {
+            metadata: Metadata::new("rust-basics"),
+            spec: SeriesSpec {
+                display_name: "Rust Basics".to_string(),
+                slug: "rust-basics".to_string(),
+                description: None,
+                cover: None,
+                template: None,
+                posts: posts.iter().map(|p| p.to_string()).collect(),
+            },
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_navigation_middle() {
+        let nav = SeriesNavigation::compute(&series(&["a", "b", "c"]), "b").unwrap();
+        assert_eq!(nav.index, 2);
+        assert_eq!(nav.total, 3);
+        assert_eq!(nav.previous.as_deref(), Some("a"));
+        assert_eq!(nav.next.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_navigation_edges() {
+        let s = series(&["a", "b"]);
+        let first = SeriesNavigation::compute(&s, "a").unwrap();
+        assert!(first.previous.is_none());
+        let last = SeriesNavigation::compute(&s, "b").unwrap();
+        assert!(last.next.is_none());
+        assert!(SeriesNavigation::compute(&s, "x").is_none());
+    }
+}
@@ -1,9 +1,17 @@
 use async_trait::async_trait;
 use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
 use flow_domain::content::{SinglePage, Snapshot};
-use crate::content::{ContentWrapper, patch_utils};
+use crate::content::{ContentWrapper, ContentConverterRegistry, snapshot_service};
+use crate::content::page_tree::{self, PageTreeNode};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// 扫描页面层级时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 父页面校验错误信息的前缀
+pub const INVALID_PARENT_ERROR: &str = "Invalid parent page";
+
 /// SinglePage服务trait
 #[async_trait]
 pub trait SinglePageService: Send + Sync {
@@ -23,26 +31,106 @@ pub trait SinglePageService: Send + Sync {
     
     /// 获取指定快照的内容
     async fn get_content(&self, snapshot_name: &str, base_snapshot_name: Option<&str>) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 获取页面树（published_only为true时只包含已发布、未删除的页面）
+    async fn list_tree(&self, published_only: bool) -> Result<Vec<PageTreeNode>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 pub struct DefaultSinglePageService<C: ExtensionClient> {
     client: Arc<C>,
+    converter_registry: Option<Arc<ContentConverterRegistry>>,
 }
 
 impl<C: ExtensionClient> DefaultSinglePageService<C> {
     pub fn new(client: Arc<C>) -> Self {
-        Self { client }
+        Self { client, converter_registry: None }
+    }
+
+    /// 设置内容格式注册表，内容未保存渲染结果时按rawType生成HTML
+    pub fn with_converter_registry(mut self, converter_registry: Arc<ContentConverterRegistry>) -> Self {
+        self.converter_registry = Some(converter_registry);
+        self
+    }
+    
+    async fn list_all(&self) -> Result<Vec<SinglePage>, Box<dyn std::error::Error + Send + Sync>> {
+        let options = ListOptions {
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        Ok(self.client.list::<SinglePage>(options).await?.items)
+    }
+
+    /// 校验父页面存在且不会形成循环，并根据层级设置页面的permalink
+    async fn prepare_hierarchy(&self, page: &mut SinglePage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if page.spec.parent.as_deref().is_some_and(|p| p.trim().is_empty()) {
+            page.spec.parent = None;
+        }
+        let pages = self.list_all().await?;
+        let mut by_name: HashMap<&str, &SinglePage> = pages.iter()
+            .map(|p| (p.metadata.name.as_str(), p))
+            .collect();
+
+        if let Some(parent) = page.spec.parent.as_deref() {
+            if !by_name.contains_key(parent) {
+                return Err(format!("{}: {} does not exist", INVALID_PARENT_ERROR, parent).into());
+            }
+            if page_tree::creates_cycle(&page.metadata.name, Some(parent), &by_name) {
+                return Err(format!("{}: {} is a descendant of {}", INVALID_PARENT_ERROR, parent, page.metadata.name).into());
+            }
+        }
+
+        by_name.insert(page.metadata.name.as_str(), &*page);
+        let permalink = page_tree::nested_permalink(page, &by_name);
+        page.status.get_or_insert_with(Default::default).permalink = Some(permalink);
+        Ok(())
+    }
+
+    /// 父页面的slug或层级变化后，更新所有子孙页面的permalink
+    async fn refresh_descendant_permalinks(&self, root: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let pages = self.list_all().await?;
+        let by_name: HashMap<&str, &SinglePage> = pages.iter()
+            .map(|p| (p.metadata.name.as_str(), p))
+            .collect();
+
+        let mut updates = Vec::new();
+        for page in &pages {
+            if page.metadata.name == root
+                || !page_tree::creates_cycle(root, page.spec.parent.as_deref(), &by_name) {
+                continue;
+            }
+            let permalink = page_tree::nested_permalink(page, &by_name);
+            if page.status.as_ref().and_then(|s| s.permalink.as_deref()) != Some(permalink.as_str()) {
+                let mut page = page.clone();
+                page.status.get_or_insert_with(Default::default).permalink = Some(permalink);
+                updates.push(page);
+            }
+        }
+        for page in updates {
+            self.client.update(page).await?;
+        }
+        Ok(())
+    }
+
+    fn render_content(&self, content: ContentWrapper) -> ContentWrapper {
+        match &self.converter_registry {
+            Some(registry) => registry.render_content(content),
+            None => content,
+        }
     }
 }
 
 #[async_trait]
 impl<C: ExtensionClient> SinglePageService for DefaultSinglePageService<C> {
-    async fn create(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+    async fn create(&self, mut page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        self.prepare_hierarchy(&mut page).await?;
         self.client.create(page).await
     }
 
-    async fn update(&self, page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
-        self.client.update(page).await
+    async fn update(&self, mut page: SinglePage) -> Result<SinglePage, Box<dyn std::error::Error + Send + Sync>> {
+        self.prepare_hierarchy(&mut page).await?;
+        let updated = self.client.update(page).await?;
+        self.refresh_descendant_permalinks(&updated.metadata.name).await?;
+        Ok(updated)
     }
 
     async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -114,47 +202,29 @@ impl<C: ExtensionClient> SinglePageService for DefaultSinglePageService<C> {
             return Err("The snapshot is not a base snapshot".into());
         }
         
-        // 如果snapshot_name等于base_snapshot_name，直接返回base snapshot的内容
-        if snapshot_name == base_snapshot_name {
-            let raw = base_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-            let content = base_snapshot.spec.content_patch.as_deref().unwrap_or("");
-            return Ok(ContentWrapper {
-                snapshot_name: base_snapshot.metadata.name.clone(),
-                raw: raw.to_string(),
-                content: content.to_string(),
-                raw_type: base_snapshot.spec.raw_type.clone(),
-            });
-        }
-        
-        // 获取patch snapshot
-        let patch_snapshot = self.client.fetch::<Snapshot>(snapshot_name).await?
-            .ok_or_else(|| "Snapshot not found")?;
-        
-        // 应用patch
-        let base_raw = base_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-        let base_content = base_snapshot.spec.content_patch.as_deref().unwrap_or("");
-        
-        let raw_patch = patch_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-        let content_patch = patch_snapshot.spec.content_patch.as_deref().unwrap_or("");
-        
-        let patched_raw = if raw_patch.is_empty() {
-            base_raw.to_string()
-        } else {
-            patch_utils::apply_patch(base_raw, raw_patch)?
-        };
-        
-        let patched_content = if content_patch.is_empty() {
-            base_content.to_string()
+        // 获取要读取的snapshot（基础快照本身保存完整内容）
+        let snapshot = if snapshot_name == base_snapshot_name {
+            base_snapshot.clone()
         } else {
-            patch_utils::apply_patch(base_content, content_patch)?
+            self.client.fetch::<Snapshot>(snapshot_name).await?
+                .ok_or_else(|| "Snapshot not found")?
         };
         
-        Ok(ContentWrapper {
-            snapshot_name: patch_snapshot.metadata.name.clone(),
-            raw: patched_raw,
-            content: patched_content,
-            raw_type: patch_snapshot.spec.raw_type.clone(),
-        })
+        // 基于基础快照应用补丁还原完整内容
+        let (raw, content) = snapshot_service::restore_content(&snapshot, &base_snapshot)?;
+        Ok(self.render_content(ContentWrapper {
+            snapshot_name: snapshot.metadata.name,
+            raw,
+            content,
+            raw_type: snapshot.spec.raw_type,
+        }))
+    }
+    
+    async fn list_tree(&self, published_only: bool) -> Result<Vec<PageTreeNode>, Box<dyn std::error::Error + Send + Sync>> {
+        let pages = self.list_all().await?
+            .into_iter()
+            .filter(|p| !published_only || (p.is_published() && !p.spec.deleted.unwrap_or(false)))
+            .collect();
+        Ok(page_tree::build_tree(pages))
     }
 }
-
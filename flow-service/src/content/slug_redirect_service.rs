@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult, Metadata};
+use flow_api::extension::query::Condition;
+use flow_domain::content::{SlugRedirect, SlugRedirectSpec, SubjectRef};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 文章的默认访问路径
+pub fn post_permalink(slug: &str) -> String {
+    format!("/archives/{}", slug)
+}
+
+/// 分类的默认访问路径
+pub fn category_permalink(slug: &str) -> String {
+    format!("/categories/{}", slug)
+}
+
+/// 规范化路径（去除末尾斜杠），用于匹配重定向
+fn normalize_path(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() { "/" } else { trimmed }
+}
+
+/// 记录一次slug变更需要对重定向表做的修改
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RedirectChanges {
+    /// 需要删除的重定向（旧链接重新生效）
+    pub delete: Vec<String>,
+    /// 需要改为指向新链接的重定向（避免多次跳转）
+    pub retarget: Vec<String>,
+    /// 是否需要新建旧链接到新链接的重定向
+    pub create: bool,
+}
+
+/// 计算slug从old_path变为new_path时重定向表的修改
+pub fn plan_changes(existing: &[SlugRedirect], old_path: &str, new_path: &str) -> RedirectChanges {
+    let old_path = normalize_path(old_path);
+    let new_path = normalize_path(new_path);
+    let mut changes = RedirectChanges::default();
+    if old_path == new_path {
+        return changes;
+    }
+
+    let mut has_source = false;
+    for redirect in existing {
+        let source = normalize_path(&redirect.spec.source_path);
+        let target = normalize_path(&redirect.spec.target_path);
+        if source == new_path {
+            changes.delete.push(redirect.metadata.name.clone());
+        } else if source == old_path {
+            has_source = true;
+            if target != new_path {
+                changes.retarget.push(redirect.metadata.name.clone());
+            }
+        } else if target == old_path {
+            changes.retarget.push(redirect.metadata.name.clone());
+        }
+    }
+    changes.create = !has_source;
+    changes
+}
+
+/// Slug重定向服务trait
+#[async_trait]
+pub trait SlugRedirectService: Send + Sync {
+    /// 记录链接变更（old_path -> new_path）
+    async fn record(&self, subject_ref: SubjectRef, old_path: &str, new_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 查找旧链接对应的新链接
+    async fn resolve(&self, path: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<SlugRedirect>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 清理重定向记录，可按目标名称和创建时间过滤，返回删除的数量
+    async fn prune(&self, subject_name: Option<&str>, before: Option<DateTime<Utc>>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+pub struct DefaultSlugRedirectService<C: ExtensionClient> {
+    client: Arc<C>,
+}
+
+impl<C: ExtensionClient> DefaultSlugRedirectService<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+
+    /// 逐页列出满足条件的全部重定向
+    async fn list_all(&self, condition: Option<Condition>) -> Result<Vec<SlugRedirect>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list_all(ListOptions { condition, ..Default::default() }).await
+    }
+}
+
+#[async_trait]
+impl<C: ExtensionClient> SlugRedirectService for DefaultSlugRedirectService<C> {
+    async fn record(&self, subject_ref: SubjectRef, old_path: &str, new_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let existing = self.list_all(None).await?;
+        let changes = plan_changes(&existing, old_path, new_path);
+
+        for name in &changes.delete {
+            self.client.delete::<SlugRedirect>(name).await?;
+        }
+
+        for mut redirect in existing.into_iter().filter(|r| changes.retarget.contains(&r.metadata.name)) {
+            redirect.spec.target_path = new_path.to_string();
+            redirect.spec.subject_ref = subject_ref.clone();
+            self.client.update(redirect).await?;
+        }
+
+        if changes.create {
+            let redirect = SlugRedirect {
+                metadata: Metadata::new(Uuid::new_v4().to_string()),
+                spec: SlugRedirectSpec {
+                    source_path: normalize_path(old_path).to_string(),
+                    target_path: new_path.to_string(),
+                    subject_ref,
+                },
+            };
+            self.client.create(redirect).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn resolve(&self, path: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let path = normalize_path(path);
+        // 记录时旧链接已规范化，按旧链接字段查询
+        let condition = Condition::Equal {
+            index_name: "spec.sourcePath".to_string(),
+            value: serde_json::Value::String(path.to_string()),
+        };
+        Ok(self.list_all(Some(condition)).await?
+            .into_iter()
+            .find(|r| normalize_path(&r.spec.source_path) == path)
+            .map(|r| r.spec.target_path))
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<ListResult<SlugRedirect>, Box<dyn std::error::Error + Send + Sync>> {
+        self.client.list(options).await
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.client.delete::<SlugRedirect>(name).await
+    }
+
+    async fn prune(&self, subject_name: Option<&str>, before: Option<DateTime<Utc>>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut pruned = 0;
+        for redirect in self.list_all(None).await? {
+            let subject_matches = subject_name
+                .map(|name| redirect.spec.subject_ref.name == name)
+                .unwrap_or(true);
+            let time_matches = match (before, redirect.metadata.creation_timestamp) {
+                (Some(before), Some(created)) => created < before,
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            if subject_matches && time_matches {
+                self.client.delete::<SlugRedirect>(&redirect.metadata.name).await?;
+                pruned += 1;
+            }
+        }
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MemoryClient;
+    use flow_api::extension::SCAN_PAGE_SIZE;
+
+    fn redirect(name: &str, source: &str, target: &str) -> SlugRedirect {
+        SlugRedirect {
+            metadata: Metadata::new(name),
+            spec: SlugRedirectSpec {
+                source_path: source.to_string(),
+                target_path: target.to_string(),
+                subject_ref: SubjectRef {
+                    group: "content.halo.run".to_string(),
+                    version: "v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: "post-1".to_string(),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn test_plan_changes_new_redirect() {
+        let changes = plan_changes(&[], "/archives/a", "/archives/b");
+        assert!(changes.create);
+        assert!(changes.delete.is_empty());
+        assert!(changes.retarget.is_empty());
+    }
+
+    #[test]
+    fn test_plan_changes_collapses_chain() {
+        // a -> b 已存在，现在 b -> c：a应直接指向c
+        let existing = vec![redirect("r1", "/archives/a", "/archives/b")];
+        let changes = plan_changes(&existing, "/archives/b", "/archives/c");
+        assert_eq!(changes.retarget, vec!["r1".to_string()]);
+        assert!(changes.create);
+    }
+
+    #[test]
+    fn test_plan_changes_revert_slug() {
+        // a -> b 已存在，现在改回 b -> a：删除a的重定向，新建b -> a
+        let existing = vec![redirect("r1", "/archives/a", "/archives/b")];
+        let changes = plan_changes(&existing, "/archives/b/", "/archives/a");
+        assert_eq!(changes.delete, vec!["r1".to_string()]);
+        assert!(changes.create);
+    }
+
+    #[test]
+    fn test_plan_changes_unchanged() {
+        assert_eq!(plan_changes(&[], "/archives/a/", "/archives/a"), RedirectChanges::default());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_beyond_first_page() {
+        let client = Arc::new(MemoryClient::default());
+        for i in 0..=SCAN_PAGE_SIZE {
+            client.create(redirect(&format!("r{}", i), &format!("/archives/old-{}", i), &format!("/archives/new-{}", i))).await.unwrap();
+        }
+        let service = DefaultSlugRedirectService::new(client.clone());
+
+        let last = format!("/archives/old-{}/", SCAN_PAGE_SIZE);
+        assert_eq!(service.resolve(&last).await.unwrap(), Some(format!("/archives/new-{}", SCAN_PAGE_SIZE)));
+        assert_eq!(service.resolve("/archives/unknown").await.unwrap(), None);
+        assert_eq!(service.prune(Some("post-1"), None).await.unwrap(), SCAN_PAGE_SIZE as usize + 1);
+    }
+}
@@ -1,8 +1,52 @@
 use async_trait::async_trait;
 use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
-use flow_domain::content::Snapshot;
+use flow_domain::content::{constant, Snapshot};
+use crate::content::{patch_utils, ContentWrapper};
 use std::sync::Arc;
 
+/// 将快照标记为基础快照（完整保存内容）
+pub fn mark_as_base(snapshot: &mut Snapshot) {
+    snapshot.metadata.annotations
+        .get_or_insert_with(Default::default)
+        .insert(constant::SNAPSHOT_KEEP_RAW_ANNO.to_string(), "true".to_string());
+}
+
+/// 以基础快照为基准，将快照中的完整内容（rawPatch/contentPatch）转换为补丁
+pub fn to_delta(snapshot: &mut Snapshot, base: &Snapshot) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let base_raw = base.spec.raw_patch.as_deref().unwrap_or("");
+    let base_content = base.spec.content_patch.as_deref().unwrap_or("");
+    let raw = snapshot.spec.raw_patch.as_deref().unwrap_or("");
+    let content = snapshot.spec.content_patch.as_deref().unwrap_or("");
+    let raw_patch = patch_utils::diff_to_json_patch(base_raw, raw)?;
+    let content_patch = patch_utils::diff_to_json_patch(base_content, content)?;
+    snapshot.spec.raw_patch = Some(raw_patch);
+    snapshot.spec.content_patch = Some(content_patch);
+    if let Some(annotations) = snapshot.metadata.annotations.as_mut() {
+        annotations.remove(constant::SNAPSHOT_KEEP_RAW_ANNO);
+    }
+    Ok(())
+}
+
+/// 根据基础快照还原快照的完整内容，返回(raw, content)
+pub fn restore_content(snapshot: &Snapshot, base: &Snapshot) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+    let base_raw = base.spec.raw_patch.as_deref().unwrap_or("");
+    let base_content = base.spec.content_patch.as_deref().unwrap_or("");
+    if snapshot.metadata.name == base.metadata.name || snapshot.is_base_snapshot() {
+        let raw = snapshot.spec.raw_patch.as_deref().unwrap_or("");
+        let content = snapshot.spec.content_patch.as_deref().unwrap_or("");
+        return Ok((raw.to_string(), content.to_string()));
+    }
+
+    let apply = |base: &str, patch: Option<&str>| match patch.filter(|p| !p.is_empty()) {
+        Some(patch) => patch_utils::apply_patch(base, patch),
+        None => Ok(base.to_string()),
+    };
+    Ok((
+        apply(base_raw, snapshot.spec.raw_patch.as_deref())?,
+        apply(base_content, snapshot.spec.content_patch.as_deref())?,
+    ))
+}
+
 /// Snapshot服务trait
 #[async_trait]
 pub trait SnapshotService: Send + Sync {
@@ -12,6 +56,13 @@ pub trait SnapshotService: Send + Sync {
     async fn get(&self, name: &str) -> Result<Option<Snapshot>, Box<dyn std::error::Error + Send + Sync>>;
     async fn list(&self, options: ListOptions) -> Result<ListResult<Snapshot>, Box<dyn std::error::Error + Send + Sync>>;
     async fn list_by_subject(&self, subject_ref: &flow_domain::content::SubjectRef) -> Result<Vec<Snapshot>, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 保存快照的一个版本，快照的rawPatch/contentPatch中为完整内容：
+    /// 没有基础快照（或快照本身就是基础快照）时完整保存，否则只保存相对基础快照的补丁
+    async fn save_revision(&self, snapshot: Snapshot, base_snapshot_name: Option<&str>) -> Result<Snapshot, Box<dyn std::error::Error + Send + Sync>>;
+    
+    /// 获取快照还原后的完整内容
+    async fn get_content(&self, snapshot_name: &str, base_snapshot_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 pub struct DefaultSnapshotService<C: ExtensionClient> {
@@ -60,5 +111,88 @@ impl<C: ExtensionClient> SnapshotService for DefaultSnapshotService<C> {
             .collect();
         Ok(snapshots)
     }
+
+    async fn save_revision(&self, mut snapshot: Snapshot, base_snapshot_name: Option<&str>) -> Result<Snapshot, Box<dyn std::error::Error + Send + Sync>> {
+        match base_snapshot_name.filter(|base| *base != snapshot.metadata.name) {
+            Some(base_snapshot_name) => {
+                let base = self.client.fetch::<Snapshot>(base_snapshot_name).await?
+                    .ok_or("Base snapshot not found")?;
+                if !base.is_base_snapshot() {
+                    return Err("The snapshot is not a base snapshot".into());
+                }
+                to_delta(&mut snapshot, &base)?;
+            }
+            None => mark_as_base(&mut snapshot),
+        }
+        
+        if self.client.fetch::<Snapshot>(&snapshot.metadata.name).await?.is_some() {
+            self.client.update(snapshot).await
+        } else {
+            self.client.create(snapshot).await
+        }
+    }
+    
+    async fn get_content(&self, snapshot_name: &str, base_snapshot_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        let base = self.client.fetch::<Snapshot>(base_snapshot_name).await?
+            .ok_or("Base snapshot not found")?;
+        if !base.is_base_snapshot() {
+            return Err("The snapshot is not a base snapshot".into());
+        }
+        let snapshot = if snapshot_name == base_snapshot_name {
+            base.clone()
+        } else {
+            self.client.fetch::<Snapshot>(snapshot_name).await?
+                .ok_or("Snapshot not found")?
+        };
+        let (raw, content) = restore_content(&snapshot, &base)?;
+        Ok(ContentWrapper {
+            snapshot_name: snapshot.metadata.name,
+            raw,
+            content,
+            raw_type: snapshot.spec.raw_type,
+        })
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{SnapshotSpec, SubjectRef};
+
+    fn snapshot(name: &str, raw: &str, content: &str) -> Snapshot {
+        Snapshot {
+            metadata: Metadata::new(name),
+            spec: SnapshotSpec {
+                subject_ref: SubjectRef {
+                    group: constant::GROUP.to_string(),
+                    version: constant::VERSION.to_string(),
+                    kind: constant::POST_KIND.to_string(),
+                    name: "post-1".to_string(),
+                },
+                raw_type: "markdown".to_string(),
+                raw_patch: Some(raw.to_string()),
+                content_patch: Some(content.to_string()),
+                parent_snapshot_name: None,
+                last_modify_time: None,
+                owner: "admin".to_string(),
+                contributors: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let mut base = snapshot("base", "# Title\n\nfirst", "<h1>Title</h1>\n<p>first</p>");
+        mark_as_base(&mut base);
+        let mut revision = snapshot("rev", "# Title\n\nsecond\nthird", "<h1>Title</h1>\n<p>second</p>");
+        to_delta(&mut revision, &base).unwrap();
+
+        assert!(!revision.is_base_snapshot());
+        assert_ne!(revision.spec.raw_patch.as_deref(), Some("# Title\n\nsecond\nthird"));
+        let (raw, content) = restore_content(&revision, &base).unwrap();
+        assert_eq!(raw, "# Title\n\nsecond\nthird");
+        assert_eq!(content, "<h1>Title</h1>\n<p>second</p>");
+        assert_eq!(restore_content(&base, &base).unwrap().0, "# Title\n\nfirst");
+    }
+}
@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use flow_domain::content::Comment;
+use flow_infra::system_setting::{AkismetSetting, SystemSettingService};
+use std::sync::Arc;
+
+/// 垃圾评论检测器trait，由CommentService在保存评论前调用
+#[async_trait]
+pub trait SpamChecker: Send + Sync {
+    /// 检测器名称
+    fn name(&self) -> &str;
+
+    /// 检测评论是否为垃圾评论
+    async fn is_spam(&self, comment: &Comment) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// 反馈人工审核结果，用于改进检测（默认不做处理）
+    async fn report(&self, _comment: &Comment, _is_spam: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// 构造Akismet请求参数
+pub fn akismet_params(site_url: &str, comment: &Comment) -> Vec<(&'static str, String)> {
+    let spec = &comment.spec;
+    let mut params = vec![
+        ("blog", site_url.to_string()),
+        ("comment_type", "comment".to_string()),
+        ("comment_content", spec.raw.clone()),
+    ];
+    if let Some(ip) = &spec.ip_address {
+        params.push(("user_ip", ip.clone()));
+    }
+    if let Some(user_agent) = &spec.user_agent {
+        params.push(("user_agent", user_agent.clone()));
+    }
+    if let Some(display_name) = &spec.owner.display_name {
+        params.push(("comment_author", display_name.clone()));
+    }
+    if spec.owner.kind == flow_domain::content::CommentOwner::KIND_EMAIL {
+        params.push(("comment_author_email", spec.owner.name.clone()));
+    }
+    params
+}
+
+/// 解析Akismet comment-check响应（"true"为垃圾评论，"false"为正常评论）
+pub fn parse_akismet_response(body: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    match body.trim() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(format!("Unexpected Akismet response: {}", other).into()),
+    }
+}
+
+/// Akismet垃圾评论检测器，配置从系统设置的comment分组读取
+pub struct AkismetSpamChecker {
+    http_client: reqwest::Client,
+    setting_service: Arc<dyn SystemSettingService>,
+}
+
+impl AkismetSpamChecker {
+    pub fn new(setting_service: Arc<dyn SystemSettingService>) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            setting_service,
+        }
+    }
+
+    /// 获取启用的Akismet设置，未启用或未配置API Key时返回None
+    async fn enabled_setting(&self) -> Result<Option<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+        let setting = self.setting_service.get_comment_setting().await
+            .map_err(|e| e.to_string())?
+            .and_then(|s| s.akismet);
+        Ok(setting.and_then(|AkismetSetting { enabled, api_key, site_url }| {
+            let api_key = api_key.filter(|k| enabled && !k.is_empty())?;
+            Some((api_key, site_url.unwrap_or_default()))
+        }))
+    }
+
+    async fn call(&self, api_key: &str, method: &str, params: &[(&str, String)]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("https://{}.rest.akismet.com/1.1/{}", api_key, method);
+        let response = self.http_client.post(url).form(params).send().await?;
+        Ok(response.error_for_status()?.text().await?)
+    }
+}
+
+#[async_trait]
+impl SpamChecker for AkismetSpamChecker {
+    fn name(&self) -> &str {
+        "akismet"
+    }
+
+    async fn is_spam(&self, comment: &Comment) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let Some((api_key, site_url)) = self.enabled_setting().await? else {
+            return Ok(false);
+        };
+        let body = self.call(&api_key, "comment-check", &akismet_params(&site_url, comment)).await?;
+        parse_akismet_response(&body)
+    }
+
+    async fn report(&self, comment: &Comment, is_spam: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some((api_key, site_url)) = self.enabled_setting().await? else {
+            return Ok(());
+        };
+        let method = if is_spam { "submit-spam" } else { "submit-ham" };
+        self.call(&api_key, method, &akismet_params(&site_url, comment)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{CommentOwner, CommentSpec, SubjectRef};
+
+    fn comment() -> Comment {
+        Comment {
+            metadata: Metadata::new("c1"),
+            spec: CommentSpec {
+                subject_ref: SubjectRef {
+                    group: "content.halo.run".to_string(),
+                    version: "v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: "post-1".to_string(),
+                },
+                last_read_time: None,
+                raw: "buy now".to_string(),
+                content: "<p>buy now</p>".to_string(),
+                owner: CommentOwner {
+                    kind: CommentOwner::KIND_EMAIL.to_string(),
+                    name: "guest@example.com".to_string(),
+                    display_name: Some("Guest".to_string()),
+                    annotations: None,
+                },
+                user_agent: None,
+                ip_address: Some("127.0.0.1".to_string()),
+                approved_time: None,
+                creation_time: None,
+                priority: None,
+                top: None,
+                allow_notification: None,
+                approved: None,
+                hidden: None,
+                spam: None,
+            },
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_akismet_params() {
+        let params = akismet_params("https://example.com", &comment());
+        assert!(params.contains(&("blog", "https://example.com".to_string())));
+        assert!(params.contains(&("user_ip", "127.0.0.1".to_string())));
+        assert!(params.contains(&("comment_author_email", "guest@example.com".to_string())));
+        assert!(!params.iter().any(|(k, _)| *k == "user_agent"));
+    }
+
+    #[test]
+    fn test_parse_akismet_response() {
+        assert!(parse_akismet_response("true").unwrap());
+        assert!(!parse_akismet_response("false\n").unwrap());
+        assert!(parse_akismet_response("invalid").is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+use flow_api::extension::{scan_all_pages, ListOptions, ListResult};
+use flow_domain::content::{Post, TagStatus};
+use crate::content::{PostService, TagService, PostRequest, PostQuery, ListedPost, ContentWrapper, ArchiveQuery, ArchiveYear};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tracing::warn;
+
+/// 计算标签的文章数量：未删除的文章总数和已发布且公开的文章数量
+pub fn tag_post_counts<'a>(posts: impl IntoIterator<Item = &'a Post>) -> (i32, i32) {
+    posts.into_iter()
+        .filter(|post| !post.is_deleted())
+        .fold((0, 0), |(total, visible), post| {
+            let is_visible = post.is_published() && post.is_public();
+            (total + 1, visible + is_visible as i32)
+        })
+}
+
+/// 文章引用的标签名称
+fn post_tags(post: &Post) -> impl Iterator<Item = String> + '_ {
+    post.spec.tags.iter().flatten().cloned()
+}
+
+/// 维护标签文章数量的Post服务包装器
+/// 在Post保存/发布/取消发布/回收后重新统计受影响标签（修改前后引用的标签）的文章数量
+pub struct TagCountingPostService {
+    inner: Arc<dyn PostService>,
+    tag_service: Arc<dyn TagService>,
+}
+
+impl TagCountingPostService {
+    pub fn new(inner: Arc<dyn PostService>, tag_service: Arc<dyn TagService>) -> Self {
+        Self { inner, tag_service }
+    }
+
+    /// 修改前文章引用的标签，获取失败时视为没有
+    async fn previous_tags(&self, post_name: &str) -> BTreeSet<String> {
+        match self.inner.get_by_username(post_name, "").await {
+            Ok(Some(post)) => post_tags(&post).collect(),
+            _ => BTreeSet::new(),
+        }
+    }
+
+    /// 重新统计标签的文章数量，数量未变化时不更新标签
+    async fn refresh_counts(&self, tags: BTreeSet<String>) {
+        for name in tags {
+            if let Err(e) = self.refresh_count(&name).await {
+                warn!("Failed to refresh post count of tag {}: {}", name, e);
+            }
+        }
+    }
+
+    async fn refresh_count(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(mut tag) = self.tag_service.get(name).await? else {
+            return Ok(());
+        };
+        let posts = scan_all_pages(ListOptions::default(), |options| async move {
+            let query = PostQuery {
+                tag: Some(name.to_string()),
+                page: options.page,
+                size: options.size,
+                ..Default::default()
+            };
+            self.inner.list_post(query).await.map(|result| result.items)
+        }).await?;
+        let (post_count, visible_post_count) = tag_post_counts(posts.iter().map(|item| &item.post));
+        let status = tag.status.get_or_insert_with(TagStatus::default);
+        if status.post_count == Some(post_count) && status.visible_post_count == Some(visible_post_count) {
+            return Ok(());
+        }
+        status.post_count = Some(post_count);
+        status.visible_post_count = Some(visible_post_count);
+        self.tag_service.update(tag).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PostService for TagCountingPostService {
+    async fn list_post(&self, query: PostQuery) -> Result<ListResult<ListedPost>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_post(query).await
+    }
+
+    async fn draft_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.inner.draft_post(request).await?;
+        self.refresh_counts(post_tags(&post).collect()).await;
+        Ok(post)
+    }
+
+    async fn update_post(&self, request: PostRequest) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tags = self.previous_tags(&request.post.metadata.name).await;
+        let post = self.inner.update_post(request).await?;
+        tags.extend(post_tags(&post));
+        self.refresh_counts(tags).await;
+        Ok(post)
+    }
+
+    async fn update_by(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tags = self.previous_tags(&post.metadata.name).await;
+        let post = self.inner.update_by(post).await?;
+        tags.extend(post_tags(&post));
+        self.refresh_counts(tags).await;
+        Ok(post)
+    }
+
+    async fn get_head_content(&self, post_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_head_content(post_name).await
+    }
+
+    async fn get_release_content(&self, post_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_release_content(post_name).await
+    }
+
+    async fn get_content(&self, snapshot_name: &str, base_snapshot_name: Option<&str>) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_content(snapshot_name, base_snapshot_name).await
+    }
+
+    async fn publish(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tags = self.previous_tags(&post.metadata.name).await;
+        let published_post = self.inner.publish(post).await?;
+        tags.extend(post_tags(&published_post));
+        self.refresh_counts(tags).await;
+        Ok(published_post)
+    }
+
+    async fn unpublish(&self, post: Post) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tags = self.previous_tags(&post.metadata.name).await;
+        let unpublished_post = self.inner.unpublish(post).await?;
+        tags.extend(post_tags(&unpublished_post));
+        self.refresh_counts(tags).await;
+        Ok(unpublished_post)
+    }
+
+    async fn get_by_username(&self, post_name: &str, username: &str) -> Result<Option<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_by_username(post_name, username).await
+    }
+
+    async fn revert_to_snapshot(&self, post_name: &str, snapshot_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.revert_to_snapshot(post_name, snapshot_name).await
+    }
+
+    async fn delete_content(&self, post_name: &str, snapshot_name: &str) -> Result<ContentWrapper, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_content(post_name, snapshot_name).await
+    }
+
+    async fn recycle(&self, post_name: &str, username: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        let post = self.inner.recycle(post_name, username).await?;
+        // 回收的文章不再计入标签
+        self.refresh_counts(post_tags(&post).collect()).await;
+        Ok(post)
+    }
+
+    async fn list_translations(&self, post_name: &str) -> Result<Vec<Post>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_translations(post_name).await
+    }
+
+    async fn sync_contributors(&self, post_name: &str) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.sync_contributors(post_name).await
+    }
+
+    async fn set_contributors(&self, post_name: &str, contributors: Vec<String>) -> Result<Post, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_contributors(post_name, contributors).await
+    }
+
+    async fn list_archives(&self, query: ArchiveQuery) -> Result<Vec<ArchiveYear>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_archives(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{constant, PostSpec, VisibleEnum};
+    use std::collections::HashMap;
+
+    fn post(name: &str, published: bool, visible: VisibleEnum, deleted: bool) -> Post {
+        let mut post = Post {
+            metadata: Metadata::new(name),
+            spec: serde_json::from_value::<PostSpec>(serde_json::json!({
+                "title": name,
+                "slug": name,
+            })).unwrap(),
+            status: None,
+        };
+        post.metadata.labels = Some(HashMap::from([
+            (constant::POST_PUBLISHED_LABEL.to_string(), published.to_string()),
+        ]));
+        post.spec.visible = Some(visible);
+        post.spec.deleted = Some(deleted);
+        post
+    }
+
+    #[test]
+    fn test_tag_post_counts() {
+        let posts = [
+            post("a", true, VisibleEnum::Public, false),
+            post("b", true, VisibleEnum::Private, false),
+            post("c", false, VisibleEnum::Public, false),
+            post("d", true, VisibleEnum::Public, true),
+        ];
+        assert_eq!(tag_post_counts(&posts), (3, 1));
+        assert_eq!(tag_post_counts(&[]), (0, 0));
+    }
+}
@@ -0,0 +1,126 @@
+//! 多语言文章工具，用于生成主题端的hreflang数据
+
+use flow_domain::content::Post;
+use serde::{Deserialize, Serialize};
+
+/// hreflang中表示默认版本的语言标签
+pub const X_DEFAULT: &str = "x-default";
+
+/// 文章某个语言版本的链接（对应`<link rel="alternate" hreflang="..." href="...">`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HreflangLink {
+    /// 语言标签（或x-default）
+    pub lang: String,
+
+    pub href: String,
+
+    /// 文章名称
+    pub name: String,
+}
+
+/// 获取文章的访问链接（优先使用permalink）
+fn post_href(post: &Post) -> String {
+    post.status
+        .as_ref()
+        .and_then(|s| s.permalink.clone())
+        .unwrap_or_else(|| format!("/archives/{}", post.spec.slug))
+}
+
+/// 根据同一翻译组内的文章生成hreflang链接
+/// 只包含已发布且设置了语言的版本，来源文章同时作为x-default
+pub fn build_hreflang(translations: &[Post]) -> Vec<HreflangLink> {
+    let mut links: Vec<HreflangLink> = translations
+        .iter()
+        .filter(|post| post.is_published() && !post.is_deleted())
+        .filter_map(|post| {
+            let lang = post.spec.language.clone()?;
+            Some(HreflangLink {
+                lang,
+                href: post_href(post),
+                name: post.metadata.name.clone(),
+            })
+        })
+        .collect();
+    links.sort_by(|a, b| a.lang.cmp(&b.lang));
+
+    // 只有存在多个语言版本时才需要hreflang
+    if links.len() < 2 {
+        return Vec::new();
+    }
+
+    if let Some(source) = links
+        .iter()
+        .find(|link| translations.iter().any(|p| p.metadata.name == link.name && p.spec.translation_of.is_none()))
+        .cloned()
+    {
+        links.push(HreflangLink {
+            lang: X_DEFAULT.to_string(),
+            ..source
+        });
+    }
+
+    links
+}
+
+/// 从翻译组中查找指定语言的版本
+pub fn find_translation<'a>(translations: &'a [Post], language: &str) -> Option<&'a Post> {
+    translations.iter().find(|post| {
+        post.spec.language
+            .as_deref()
+            .map(|lang| lang.eq_ignore_ascii_case(language))
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_domain::content::constant;
+
+    fn post(name: &str, language: &str, translation_of: Option<&str>) -> Post {
+        let json = serde_json::json!({
+            "metadata": { "name": name },
+            "spec": {
+                "title": name,
+                "slug": name,
+                "language": language,
+                "translationOf": translation_of,
+            },
+            "status": null
+        });
+        let mut post: Post = serde_json::from_value(json).unwrap();
+        post.metadata.labels = Some(
+            [(constant::POST_PUBLISHED_LABEL.to_string(), "true".to_string())].into(),
+        );
+        post
+    }
+
+    #[test]
+    fn test_build_hreflang() {
+        let posts = vec![
+            post("hello", "zh-CN", None),
+            post("hello-en", "en", Some("hello")),
+        ];
+        let links = build_hreflang(&posts);
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].lang, "en");
+        assert_eq!(links[0].href, "/archives/hello-en");
+        assert_eq!(links[2].lang, X_DEFAULT);
+        assert_eq!(links[2].name, "hello");
+    }
+
+    #[test]
+    fn test_build_hreflang_single_language() {
+        assert!(build_hreflang(&[post("hello", "zh-CN", None)]).is_empty());
+    }
+
+    #[test]
+    fn test_find_translation() {
+        let posts = vec![
+            post("hello", "zh-CN", None),
+            post("hello-en", "en", Some("hello")),
+        ];
+        assert_eq!(find_translation(&posts, "EN").unwrap().metadata.name, "hello-en");
+        assert!(find_translation(&posts, "ja").is_none());
+    }
+}
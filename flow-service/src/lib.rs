@@ -5,6 +5,10 @@ pub mod theme;
 pub mod attachment;
 pub mod notification;
 pub mod migration;
+pub mod plugin;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use security::{
     UserService,
@@ -20,6 +24,7 @@ pub use content::{
     CommentService, DefaultCommentService,
     CategoryService, DefaultCategoryService,
     TagService, DefaultTagService,
+    SeriesService, DefaultSeriesService,
     SnapshotService, DefaultSnapshotService,
 };
 
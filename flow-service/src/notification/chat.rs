@@ -0,0 +1,272 @@
+use async_trait::async_trait;
+use crate::notification::{NotificationContext, NotificationMessage, NotificationSender};
+use anyhow::Result;
+use serde_json::{json, Value};
+
+/// Telegram机器人通知器名称
+pub const TELEGRAM_NOTIFIER: &str = "telegram";
+/// Slack Incoming Webhook通知器名称
+pub const SLACK_NOTIFIER: &str = "slack";
+/// Discord Webhook通知器名称
+pub const DISCORD_NOTIFIER: &str = "discord";
+
+/// 通知器缺少必需配置（如机器人令牌、Webhook地址）时的错误信息前缀
+pub const NOTIFIER_CONFIG_MISSING_ERROR: &str = "Notifier is not configured";
+
+/// 聊天平台推送失败时的错误信息前缀
+pub const CHAT_DELIVERY_ERROR: &str = "Failed to deliver chat notification";
+
+const TELEGRAM_API_BASE: &str = "https://api.telegram.org";
+
+/// Telegram单条消息的最大长度
+const TELEGRAM_MAX_TEXT: usize = 4096;
+/// Discord嵌入消息标题和描述的最大长度
+const DISCORD_MAX_TITLE: usize = 256;
+const DISCORD_MAX_DESCRIPTION: usize = 4096;
+
+/// 读取配置项，接收者配置优先于发送者配置
+fn config_value(context: &NotificationContext, key: &str) -> Option<String> {
+    [&context.receiver_config, &context.sender_config].into_iter()
+        .flatten()
+        .find_map(|config| config.get(key))
+        .and_then(|value| match value {
+            Value::String(s) => Some(s.trim().to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .filter(|value| !value.is_empty())
+}
+
+fn required_config(context: &NotificationContext, notifier: &str, key: &str) -> Result<String> {
+    config_value(context, key)
+        .ok_or_else(|| anyhow::anyhow!("{}: {} requires {}", NOTIFIER_CONFIG_MISSING_ERROR, notifier, key))
+}
+
+/// 按字符截断，超出时以省略号结尾
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn body(message: &NotificationMessage) -> &str {
+    message.payload.raw_body.as_deref().map(str::trim).unwrap_or_default()
+}
+
+/// Telegram HTML模式的消息：标题加粗，正文，主题链接
+pub fn format_telegram(message: &NotificationMessage) -> String {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let link = message.subject.url.as_ref().map(|url| {
+        format!("<a href=\"{}\">{}</a>", escape(url).replace('"', "&quot;"), escape(&message.subject.title))
+    });
+    let mut text = format!("<b>{}</b>", escape(&message.payload.title));
+    // 截断正文而不是整条消息，避免切断HTML标签
+    let reserved = text.chars().count() + link.as_ref().map(|l| l.chars().count() + 2).unwrap_or(0) + 2;
+    let body = body(message);
+    if !body.is_empty() {
+        text.push_str("\n\n");
+        let budget = TELEGRAM_MAX_TEXT.saturating_sub(reserved);
+        let mut used = 0;
+        for c in body.chars() {
+            let escaped = escape(c.encode_utf8(&mut [0; 4]));
+            let len = escaped.chars().count();
+            if used + len >= budget {
+                text.push('…');
+                break;
+            }
+            used += len;
+            text.push_str(&escaped);
+        }
+    }
+    if let Some(link) = link {
+        text.push_str("\n\n");
+        text.push_str(&link);
+    }
+    text
+}
+
+/// Slack mrkdwn格式的消息
+pub fn format_slack(message: &NotificationMessage) -> Value {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut text = format!("*{}*", escape(&message.payload.title));
+    let body = body(message);
+    if !body.is_empty() {
+        text.push('\n');
+        text.push_str(&escape(body));
+    }
+    if let Some(url) = &message.subject.url {
+        text.push_str(&format!("\n<{}|{}>", url, escape(&message.subject.title).replace('|', "¦")));
+    }
+    json!({ "text": text })
+}
+
+/// Discord嵌入消息
+pub fn format_discord(message: &NotificationMessage) -> Value {
+    let mut embed = json!({
+        "title": truncate(&message.payload.title, DISCORD_MAX_TITLE),
+        "description": truncate(body(message), DISCORD_MAX_DESCRIPTION),
+        "timestamp": message.timestamp.to_rfc3339(),
+    });
+    if let Some(url) = &message.subject.url {
+        embed["url"] = Value::String(url.clone());
+    }
+    json!({ "embeds": [embed] })
+}
+
+async fn post_json(client: &reqwest::Client, notifier: &str, url: &str, body: &Value) -> Result<()> {
+    let response = client.post(url).json(body).send().await
+        .map_err(|e| anyhow::anyhow!("{}: {}: {}", CHAT_DELIVERY_ERROR, notifier, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let detail = response.text().await.unwrap_or_default();
+        anyhow::bail!("{}: {} responded {}: {}", CHAT_DELIVERY_ERROR, notifier, status, truncate(&detail, 200));
+    }
+    Ok(())
+}
+
+/// Telegram机器人通知器
+///
+/// 发送者配置 `botToken` 为机器人令牌，接收者配置 `chatId` 为接收消息的会话
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    api_base: String,
+}
+
+impl TelegramNotifier {
+    pub fn new() -> Self {
+        Self { client: reqwest::Client::new(), api_base: TELEGRAM_API_BASE.to_string() }
+    }
+
+    /// 设置Bot API地址（自建Bot API服务器时使用）
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into();
+        self
+    }
+}
+
+impl Default for TelegramNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for TelegramNotifier {
+    async fn send_notification(&self, _notifier_extension_name: &str, context: NotificationContext) -> Result<()> {
+        let token = required_config(&context, TELEGRAM_NOTIFIER, "botToken")?;
+        let chat_id = required_config(&context, TELEGRAM_NOTIFIER, "chatId")?;
+        let url = format!("{}/bot{}/sendMessage", self.api_base.trim_end_matches('/'), token);
+        let body = json!({
+            "chat_id": chat_id,
+            "text": format_telegram(&context.message),
+            "parse_mode": "HTML",
+            "disable_web_page_preview": true,
+        });
+        post_json(&self.client, TELEGRAM_NOTIFIER, &url, &body).await
+    }
+}
+
+/// Slack Incoming Webhook通知器，配置 `webhookUrl`（接收者配置优先，其次为站点配置）
+#[derive(Default)]
+pub struct SlackNotifier {
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for SlackNotifier {
+    async fn send_notification(&self, _notifier_extension_name: &str, context: NotificationContext) -> Result<()> {
+        let url = required_config(&context, SLACK_NOTIFIER, "webhookUrl")?;
+        post_json(&self.client, SLACK_NOTIFIER, &url, &format_slack(&context.message)).await
+    }
+}
+
+/// Discord Webhook通知器，配置 `webhookUrl`（接收者配置优先，其次为站点配置）
+#[derive(Default)]
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for DiscordNotifier {
+    async fn send_notification(&self, _notifier_extension_name: &str, context: NotificationContext) -> Result<()> {
+        let url = required_config(&context, DISCORD_NOTIFIER, "webhookUrl")?;
+        post_json(&self.client, DISCORD_NOTIFIER, &url, &format_discord(&context.message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::{MessagePayload, NotificationSubject};
+    use std::collections::HashMap;
+
+    fn message(body: &str) -> NotificationMessage {
+        NotificationMessage {
+            payload: MessagePayload {
+                title: "New comment on <Hello>".to_string(),
+                raw_body: Some(body.to_string()),
+                html_body: None,
+                attributes: None,
+            },
+            subject: NotificationSubject {
+                api_version: "content.halo.run/v1alpha1".to_string(),
+                kind: "Post".to_string(),
+                name: "hello".to_string(),
+                title: "Hello & welcome".to_string(),
+                url: Some("https://example.com/archives/hello".to_string()),
+            },
+            recipient: "admin".to_string(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_channel_formats() {
+        assert_eq!(
+            format_telegram(&message("a < b")),
+            "<b>New comment on &lt;Hello&gt;</b>\n\na &lt; b\n\n<a href=\"https://example.com/archives/hello\">Hello &amp; welcome</a>"
+        );
+        assert!(format_telegram(&message(&"<".repeat(10_000))).chars().count() <= TELEGRAM_MAX_TEXT);
+        assert_eq!(
+            format_slack(&message("a < b"))["text"],
+            "*New comment on &lt;Hello&gt;*\na &lt; b\n<https://example.com/archives/hello|Hello &amp; welcome>"
+        );
+        let discord = format_discord(&message(&"x".repeat(5000)));
+        assert_eq!(discord["embeds"][0]["url"], "https://example.com/archives/hello");
+        assert_eq!(discord["embeds"][0]["description"].as_str().unwrap().chars().count(), DISCORD_MAX_DESCRIPTION);
+    }
+
+    #[test]
+    fn test_config_lookup() {
+        let mut context = NotificationContext {
+            message: message(""),
+            receiver_config: Some(HashMap::from([("chatId".to_string(), json!(42))])),
+            sender_config: Some(HashMap::from([
+                ("botToken".to_string(), json!("token")),
+                ("chatId".to_string(), json!("site")),
+                ("webhookUrl".to_string(), json!(" ")),
+            ])),
+        };
+        assert_eq!(config_value(&context, "chatId").as_deref(), Some("42"));
+        assert_eq!(config_value(&context, "botToken").as_deref(), Some("token"));
+        let err = required_config(&context, SLACK_NOTIFIER, "webhookUrl").unwrap_err();
+        assert!(err.to_string().starts_with(NOTIFIER_CONFIG_MISSING_ERROR));
+        context.receiver_config = None;
+        assert_eq!(config_value(&context, "chatId").as_deref(), Some("site"));
+    }
+}
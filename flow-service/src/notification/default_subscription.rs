@@ -0,0 +1,263 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, Metadata};
+use flow_domain::content::{constant, Comment, Post, SubjectRef};
+use flow_domain::notification::{InterestReason, Reason, ReasonSpec, ReasonSubject, Subscription, SubscriptionSubscriber};
+use flow_domain::security::RoleBinding;
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_plugin::event::{Event, EventListener, COMMENT_CREATED, PLUGIN_FAILED, POST_PUBLISHED, USER_REGISTERED};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::content::comment_notification::{
+    comment_subscriber, interest_reason, is_subscribed, reason_subject, REASON_NEW_COMMENT_ON_POST, REASON_NEW_REPLY_ON_COMMENT,
+};
+use crate::content::slug_redirect_service::post_permalink;
+use crate::notification::NotificationCenter;
+
+/// 新用户注册
+pub const REASON_NEW_USER_REGISTERED: &str = "new-user-registered";
+
+/// 系统故障（如插件健康检查连续失败）
+pub const REASON_SYSTEM_FAILURE: &str = "system-failure";
+
+/// 超级管理员角色，绑定该角色的用户自动订阅新用户和系统故障通知
+pub const SUPER_ROLE_NAME: &str = "super-role";
+
+/// 系统产生的原因的作者
+const SYSTEM_AUTHOR: &str = "system";
+
+/// 绑定了角色的用户名，按名称排序去重
+pub fn role_members(bindings: &[RoleBinding], role_name: &str) -> Vec<String> {
+    bindings.iter()
+        .filter(|binding| binding.role_ref.name == role_name)
+        .flat_map(|binding| binding.subjects.iter())
+        .filter(|subject| subject.is_user())
+        .map(|subject| subject.name.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// 对某种原因类型的所有主题感兴趣
+pub fn interest_all(reason_type: &str) -> InterestReason {
+    InterestReason {
+        reason_type: reason_type.to_string(),
+        subject: None,
+        expression: None,
+    }
+}
+
+fn event_str<'a>(event: &'a Event, key: &str) -> Option<&'a str> {
+    event.data.get(key).and_then(Value::as_str)
+}
+
+/// 用户注册事件产生的通知原因
+pub fn user_registered_reason(event: &Event) -> Option<Reason> {
+    let username = event_str(event, "username")?;
+    let display_name = event_str(event, "displayName").unwrap_or(username);
+    let attributes = HashMap::from([
+        ("username".to_string(), username.to_string()),
+        ("displayName".to_string(), display_name.to_string()),
+    ]);
+    Some(Reason {
+        metadata: Metadata::new(Uuid::new_v4().to_string()),
+        spec: ReasonSpec {
+            reason_type: REASON_NEW_USER_REGISTERED.to_string(),
+            subject: ReasonSubject {
+                api_version: "v1alpha1".to_string(),
+                kind: "User".to_string(),
+                name: username.to_string(),
+                title: display_name.to_string(),
+                url: None,
+            },
+            author: username.to_string(),
+            attributes: Some(attributes),
+        },
+    })
+}
+
+/// 插件失败事件产生的系统故障原因
+pub fn plugin_failed_reason(event: &Event) -> Option<Reason> {
+    let plugin_id = event_str(event, "pluginId")?;
+    let mut attributes = HashMap::from([("pluginId".to_string(), plugin_id.to_string())]);
+    if let Some(failures) = event.data.get("failures").and_then(Value::as_u64) {
+        attributes.insert("failures".to_string(), failures.to_string());
+    }
+    if let Some(message) = event_str(event, "message") {
+        attributes.insert("message".to_string(), message.to_string());
+    }
+    Some(Reason {
+        metadata: Metadata::new(Uuid::new_v4().to_string()),
+        spec: ReasonSpec {
+            reason_type: REASON_SYSTEM_FAILURE.to_string(),
+            subject: ReasonSubject {
+                api_version: "plugin.halo.run/v1alpha1".to_string(),
+                kind: "Plugin".to_string(),
+                name: plugin_id.to_string(),
+                title: plugin_id.to_string(),
+                url: None,
+            },
+            author: SYSTEM_AUTHOR.to_string(),
+            attributes: Some(attributes),
+        },
+    })
+}
+
+/// 根据领域事件自动创建默认订阅
+///
+/// - 文章发布：文章作者订阅文章的新评论
+/// - 评论创建：允许通知的评论作者订阅对该评论的回复
+/// - 用户注册、插件失败：超级管理员订阅新用户和系统故障通知，然后发送对应的通知
+///
+/// 每个订阅者的同一原因只订阅一次
+pub struct DefaultSubscriptionListener {
+    extension_client: Arc<ReactiveExtensionClient>,
+    notification_center: Arc<dyn NotificationCenter>,
+    /// 串行化查重和创建，避免并发事件重复订阅
+    lock: Mutex<()>,
+}
+
+impl DefaultSubscriptionListener {
+    /// 订阅时使用的订阅者名称
+    pub const OWNER: &'static str = "default-subscriptions";
+
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>, notification_center: Arc<dyn NotificationCenter>) -> Self {
+        Self { extension_client, notification_center, lock: Mutex::new(()) }
+    }
+
+    /// 需要订阅的事件
+    pub fn event_types() -> Vec<String> {
+        vec![
+            POST_PUBLISHED.to_string(),
+            COMMENT_CREATED.to_string(),
+            USER_REGISTERED.to_string(),
+            PLUGIN_FAILED.to_string(),
+        ]
+    }
+
+    /// 订阅者尚未订阅该原因时创建订阅
+    async fn ensure_subscription(&self, subscriber: &str, interest: InterestReason) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        // 扩展仓库不按条件过滤，读取全部订阅后按订阅者匹配
+        let existing = self.extension_client.list_all::<Subscription>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list subscriptions: {}", e))?;
+        if !is_subscribed(&existing, subscriber, &interest) {
+            self.notification_center.subscribe(SubscriptionSubscriber { name: subscriber.to_string() }, interest).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_post_published(&self, event: &Event) -> Result<()> {
+        let Some(name) = event_str(event, "name") else {
+            return Ok(());
+        };
+        let Some(post) = self.extension_client.fetch::<Post>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch post {}: {}", name, e))? else {
+            return Ok(());
+        };
+        let Some(owner) = post.spec.owner.as_deref() else {
+            return Ok(());
+        };
+        let subject_ref = SubjectRef {
+            group: constant::GROUP.to_string(),
+            version: constant::VERSION.to_string(),
+            kind: constant::POST_KIND.to_string(),
+            name: post.metadata.name.clone(),
+        };
+        let subject = reason_subject(&subject_ref, post.spec.title.clone(), Some(post_permalink(&post.spec.slug)));
+        self.ensure_subscription(owner, interest_reason(REASON_NEW_COMMENT_ON_POST, &subject)).await
+    }
+
+    async fn on_comment_created(&self, event: &Event) -> Result<()> {
+        let Some(name) = event_str(event, "name") else {
+            return Ok(());
+        };
+        let Some(comment) = self.extension_client.fetch::<Comment>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch comment {}: {}", name, e))? else {
+            return Ok(());
+        };
+        if !comment.spec.allow_notification.unwrap_or(true) {
+            return Ok(());
+        }
+        let own_ref = SubjectRef {
+            group: constant::GROUP.to_string(),
+            version: constant::VERSION.to_string(),
+            kind: constant::COMMENT_KIND.to_string(),
+            name: comment.metadata.name.clone(),
+        };
+        let interest = interest_reason(REASON_NEW_REPLY_ON_COMMENT, &reason_subject(&own_ref, String::new(), None));
+        self.ensure_subscription(&comment_subscriber(&comment.spec.owner), interest).await
+    }
+
+    /// 为超级管理员订阅原因类型，然后发送通知
+    async fn notify_admins(&self, reason: Reason) -> Result<()> {
+        let bindings = self.extension_client.list_all::<RoleBinding>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list role bindings: {}", e))?;
+        for admin in role_members(&bindings, SUPER_ROLE_NAME) {
+            self.ensure_subscription(&admin, interest_all(&reason.spec.reason_type)).await?;
+        }
+        self.extension_client.create(reason.clone()).await
+            .map_err(|e| anyhow::anyhow!("Failed to create reason: {}", e))?;
+        self.notification_center.notify(reason).await
+    }
+}
+
+#[async_trait]
+impl EventListener for DefaultSubscriptionListener {
+    async fn on_event(&self, event: &Event) -> Result<()> {
+        match event.event_type.as_str() {
+            POST_PUBLISHED => self.on_post_published(event).await,
+            COMMENT_CREATED => self.on_comment_created(event).await,
+            USER_REGISTERED => match user_registered_reason(event) {
+                Some(reason) => self.notify_admins(reason).await,
+                None => Ok(()),
+            },
+            PLUGIN_FAILED => match plugin_failed_reason(event) {
+                Some(reason) => self.notify_admins(reason).await,
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_members() {
+        let mut group = RoleBinding::create("bob", SUPER_ROLE_NAME);
+        group.subjects.push(flow_domain::security::Subject {
+            kind: "Group".to_string(),
+            name: "editors".to_string(),
+            api_group: String::new(),
+        });
+        let bindings = vec![
+            RoleBinding::create("alice", SUPER_ROLE_NAME),
+            RoleBinding::create("carol", "editor"),
+            group,
+            RoleBinding::create("alice", SUPER_ROLE_NAME),
+        ];
+        assert_eq!(role_members(&bindings, SUPER_ROLE_NAME), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_event_reasons() {
+        let event = Event::new(USER_REGISTERED, serde_json::json!({"username": "ryan", "displayName": "Ryan"}));
+        let reason = user_registered_reason(&event).unwrap();
+        assert_eq!(reason.spec.reason_type, REASON_NEW_USER_REGISTERED);
+        assert_eq!(reason.spec.subject.title, "Ryan");
+        assert_eq!(reason.spec.author, "ryan");
+
+        let event = Event::new(PLUGIN_FAILED, serde_json::json!({"pluginId": "search", "failures": 3, "message": "timeout"}));
+        let reason = plugin_failed_reason(&event).unwrap();
+        assert_eq!(reason.spec.reason_type, REASON_SYSTEM_FAILURE);
+        assert_eq!(reason.spec.subject.name, "search");
+        assert_eq!(reason.spec.attributes.unwrap()["failures"], "3");
+        assert!(plugin_failed_reason(&Event::new(PLUGIN_FAILED, Value::Null)).is_none());
+    }
+}
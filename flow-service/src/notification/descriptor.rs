@@ -0,0 +1,356 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, Metadata};
+use flow_domain::notification::{NotifierDescriptor, NotifierDescriptorSpec, NotifierSettingRef};
+use flow_domain::setting::{Setting, SettingForm, SettingSpec};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::notification::preference::user_preference_config_map;
+use crate::notification::{DISCORD_NOTIFIER, SLACK_NOTIFIER, TELEGRAM_NOTIFIER};
+
+/// 保存站点级发送者配置的ConfigMap，每个通知渠道一个键，值为JSON字符串
+pub const NOTIFIER_SENDER_CONFIG_MAP: &str = "notifier-sender-config";
+
+/// 找不到通知渠道描述时的错误信息前缀
+pub const NOTIFIER_DESCRIPTOR_NOT_FOUND_ERROR: &str = "Notifier descriptor not found";
+
+/// 通知器配置无效（渠道没有该设置或包含表单之外的字段）时的错误信息前缀
+pub const NOTIFIER_CONFIG_INVALID_ERROR: &str = "Invalid notifier config";
+
+/// 通知器配置
+pub type NotifierConfig = HashMap<String, Value>;
+
+/// 用户偏好ConfigMap中保存某个通知渠道接收者配置的键
+pub fn receiver_config_key(descriptor_name: &str) -> String {
+    format!("notifier.{}", descriptor_name)
+}
+
+/// 解析后的通知渠道：实际发送的通知器及其配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedNotifier {
+    pub notifier_ext_name: String,
+    pub sender_config: Option<NotifierConfig>,
+    pub receiver_config: Option<NotifierConfig>,
+}
+
+impl ResolvedNotifier {
+    /// 按通知器扩展名称直接发送，不带配置
+    pub fn direct(notifier_ext_name: &str) -> Self {
+        Self { notifier_ext_name: notifier_ext_name.to_string(), sender_config: None, receiver_config: None }
+    }
+}
+
+/// 解析通知渠道，没有渠道描述服务时按通知器名称直接发送
+pub async fn resolve_notifier(
+    descriptor_service: Option<&Arc<dyn NotifierDescriptorService>>,
+    name: &str,
+    username: &str,
+) -> Result<ResolvedNotifier> {
+    match descriptor_service {
+        Some(descriptor_service) => descriptor_service.resolve(name, username).await,
+        None => Ok(ResolvedNotifier::direct(name)),
+    }
+}
+
+/// 通知渠道描述服务trait
+#[async_trait]
+pub trait NotifierDescriptorService: Send + Sync {
+    /// 列出所有通知渠道
+    async fn list_descriptors(&self) -> Result<Vec<NotifierDescriptor>>;
+
+    /// 获取通知渠道
+    async fn get_descriptor(&self, name: &str) -> Result<Option<NotifierDescriptor>>;
+
+    /// 获取站点级发送者配置，未填写的字段使用表单默认值
+    async fn get_sender_config(&self, name: &str) -> Result<NotifierConfig>;
+
+    /// 保存站点级发送者配置
+    async fn update_sender_config(&self, name: &str, config: NotifierConfig) -> Result<NotifierConfig>;
+
+    /// 获取用户的接收者配置，未填写的字段使用表单默认值
+    async fn get_receiver_config(&self, name: &str, username: &str) -> Result<NotifierConfig>;
+
+    /// 保存用户的接收者配置
+    async fn update_receiver_config(&self, name: &str, username: &str, config: NotifierConfig) -> Result<NotifierConfig>;
+
+    /// 解析用户在通知偏好中选择的通知渠道
+    ///
+    /// 没有同名渠道描述时按通知器扩展名称直接发送，不带配置
+    async fn resolve(&self, name: &str, username: &str) -> Result<ResolvedNotifier>;
+}
+
+/// 默认通知渠道描述服务实现
+///
+/// 发送者配置保存在 `notifier-sender-config` ConfigMap中，接收者配置保存在用户偏好ConfigMap中
+pub struct DefaultNotifierDescriptorService {
+    extension_client: Arc<ReactiveExtensionClient>,
+}
+
+impl DefaultNotifierDescriptorService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client }
+    }
+
+    /// 保存内置通知渠道（Telegram、Slack、Discord）的描述和设置表单
+    pub async fn install_builtin(&self) -> Result<()> {
+        for (descriptor, setting) in builtin_descriptors() {
+            let name = descriptor.metadata.name.clone();
+            self.extension_client.update(setting).await
+                .map_err(|e| anyhow::anyhow!("Failed to save setting of notifier {}: {}", name, e))?;
+            self.extension_client.update(descriptor).await
+                .map_err(|e| anyhow::anyhow!("Failed to save notifier descriptor {}: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    async fn require_descriptor(&self, name: &str) -> Result<NotifierDescriptor> {
+        self.get_descriptor(name).await?
+            .ok_or_else(|| anyhow::anyhow!("{}: {}", NOTIFIER_DESCRIPTOR_NOT_FOUND_ERROR, name))
+    }
+
+    async fn fetch_form(&self, setting_ref: &NotifierSettingRef) -> Result<Option<SettingForm>> {
+        let setting: Option<Setting> = self.extension_client.fetch(&setting_ref.name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch setting {}: {}", setting_ref.name, e))?;
+        Ok(setting.and_then(|setting| setting.spec.forms.into_iter().find(|form| form.group == setting_ref.group)))
+    }
+
+    async fn fetch_config_map(&self, name: &str) -> Result<Option<ConfigMap>> {
+        self.extension_client.fetch(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch config map {}: {}", name, e))
+    }
+
+    async fn stored_config(&self, config_map: &str, key: &str) -> Result<NotifierConfig> {
+        let stored = self.fetch_config_map(config_map).await?
+            .and_then(|config_map| config_map.data)
+            .and_then(|mut data| data.remove(key));
+        match stored {
+            Some(stored) => Ok(serde_json::from_str(&stored)?),
+            None => Ok(NotifierConfig::new()),
+        }
+    }
+
+    async fn save_config(&self, config_map: &str, key: &str, config: &NotifierConfig) -> Result<()> {
+        let value = serde_json::to_string(config)?;
+        match self.fetch_config_map(config_map).await? {
+            Some(mut existing) => {
+                existing.data.get_or_insert_with(HashMap::new).insert(key.to_string(), value);
+                self.extension_client.update(existing).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(config_map.to_string()),
+                data: Some(HashMap::from([(key.to_string(), value)])),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save notifier config: {}", e))?;
+        Ok(())
+    }
+
+    /// 读取配置并补全表单默认值，渠道没有该设置时返回None
+    async fn config(
+        &self,
+        setting_ref: Option<&NotifierSettingRef>,
+        config_map: &str,
+        key: &str,
+    ) -> Result<Option<NotifierConfig>> {
+        let Some(setting_ref) = setting_ref else {
+            return Ok(None);
+        };
+        let form = self.fetch_form(setting_ref).await?;
+        let stored = self.stored_config(config_map, key).await?;
+        Ok(Some(with_form_defaults(form.as_ref(), stored)))
+    }
+
+    async fn update_config(
+        &self,
+        name: &str,
+        side: &str,
+        setting_ref: Option<&NotifierSettingRef>,
+        config_map: &str,
+        key: &str,
+        config: NotifierConfig,
+    ) -> Result<NotifierConfig> {
+        let Some(setting_ref) = setting_ref else {
+            anyhow::bail!("{}: notifier {} has no {} settings", NOTIFIER_CONFIG_INVALID_ERROR, name, side);
+        };
+        let form = self.fetch_form(setting_ref).await?;
+        if let Some(form) = &form {
+            let unknown = unknown_fields(form, &config);
+            if !unknown.is_empty() {
+                anyhow::bail!("{}: unknown fields {}", NOTIFIER_CONFIG_INVALID_ERROR, unknown.join(", "));
+            }
+        }
+        self.save_config(config_map, key, &config).await?;
+        Ok(with_form_defaults(form.as_ref(), config))
+    }
+}
+
+#[async_trait]
+impl NotifierDescriptorService for DefaultNotifierDescriptorService {
+    async fn list_descriptors(&self) -> Result<Vec<NotifierDescriptor>> {
+        self.extension_client.list_all::<NotifierDescriptor>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list notifier descriptors: {}", e))
+    }
+
+    async fn get_descriptor(&self, name: &str) -> Result<Option<NotifierDescriptor>> {
+        self.extension_client.fetch(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch notifier descriptor {}: {}", name, e))
+    }
+
+    async fn get_sender_config(&self, name: &str) -> Result<NotifierConfig> {
+        let descriptor = self.require_descriptor(name).await?;
+        let config = self.config(descriptor.spec.sender_setting_ref.as_ref(), NOTIFIER_SENDER_CONFIG_MAP, name).await?;
+        Ok(config.unwrap_or_default())
+    }
+
+    async fn update_sender_config(&self, name: &str, config: NotifierConfig) -> Result<NotifierConfig> {
+        let descriptor = self.require_descriptor(name).await?;
+        self.update_config(
+            name, "sender", descriptor.spec.sender_setting_ref.as_ref(), NOTIFIER_SENDER_CONFIG_MAP, name, config,
+        ).await
+    }
+
+    async fn get_receiver_config(&self, name: &str, username: &str) -> Result<NotifierConfig> {
+        let descriptor = self.require_descriptor(name).await?;
+        let config = self.config(
+            descriptor.spec.receiver_setting_ref.as_ref(), &user_preference_config_map(username), &receiver_config_key(name),
+        ).await?;
+        Ok(config.unwrap_or_default())
+    }
+
+    async fn update_receiver_config(&self, name: &str, username: &str, config: NotifierConfig) -> Result<NotifierConfig> {
+        let descriptor = self.require_descriptor(name).await?;
+        self.update_config(
+            name, "receiver", descriptor.spec.receiver_setting_ref.as_ref(),
+            &user_preference_config_map(username), &receiver_config_key(name), config,
+        ).await
+    }
+
+    async fn resolve(&self, name: &str, username: &str) -> Result<ResolvedNotifier> {
+        // 扩展对象按名称存储，同名的其他类型对象无法解析为渠道描述，同样按通知器名称处理
+        let Ok(Some(descriptor)) = self.get_descriptor(name).await else {
+            return Ok(ResolvedNotifier::direct(name));
+        };
+        let sender_config = self.config(
+            descriptor.spec.sender_setting_ref.as_ref(), NOTIFIER_SENDER_CONFIG_MAP, name,
+        ).await?;
+        let receiver_config = self.config(
+            descriptor.spec.receiver_setting_ref.as_ref(), &user_preference_config_map(username), &receiver_config_key(name),
+        ).await?;
+        Ok(ResolvedNotifier { notifier_ext_name: descriptor.spec.notifier_ext_name, sender_config, receiver_config })
+    }
+}
+
+/// 表单字段名称
+fn form_fields(form: &SettingForm) -> impl Iterator<Item = (&str, Option<&Value>)> {
+    form.form_schema.iter()
+        .filter_map(|field| field.get("name").and_then(Value::as_str).map(|name| (name, field.get("value"))))
+}
+
+/// 用表单默认值补全配置中未填写的字段
+fn with_form_defaults(form: Option<&SettingForm>, mut config: NotifierConfig) -> NotifierConfig {
+    if let Some(form) = form {
+        for (name, default) in form_fields(form) {
+            if let Some(default) = default {
+                config.entry(name.to_string()).or_insert_with(|| default.clone());
+            }
+        }
+    }
+    config
+}
+
+/// 配置中表单之外的字段
+fn unknown_fields(form: &SettingForm, config: &NotifierConfig) -> Vec<String> {
+    let known: Vec<&str> = form_fields(form).map(|(name, _)| name).collect();
+    let mut unknown: Vec<String> = config.keys().filter(|key| !known.contains(&key.as_str())).cloned().collect();
+    unknown.sort();
+    unknown
+}
+
+fn text_field(name: &str, label: &str, help: &str) -> Value {
+    json!({ "$formkit": "text", "name": name, "label": label, "help": help, "validation": "required" })
+}
+
+/// 内置通知渠道的描述和设置表单
+pub fn builtin_descriptors() -> Vec<(NotifierDescriptor, Setting)> {
+    let channel = |ext_name: &str, display_name: &str, description: &str, sender: Option<SettingForm>, receiver: Option<SettingForm>| {
+        let setting_name = format!("notifier-setting-for-{}", ext_name);
+        let setting_ref = |form: &Option<SettingForm>| form.as_ref().map(|form| NotifierSettingRef {
+            name: setting_name.clone(),
+            group: form.group.clone(),
+        });
+        let descriptor = NotifierDescriptor {
+            metadata: Metadata::new(format!("{}-notifier", ext_name)),
+            spec: NotifierDescriptorSpec {
+                display_name: display_name.to_string(),
+                description: Some(description.to_string()),
+                notifier_ext_name: ext_name.to_string(),
+                sender_setting_ref: setting_ref(&sender),
+                receiver_setting_ref: setting_ref(&receiver),
+            },
+        };
+        let setting = Setting {
+            metadata: Metadata::new(setting_name.clone()),
+            spec: SettingSpec { forms: sender.into_iter().chain(receiver).collect() },
+        };
+        (descriptor, setting)
+    };
+    let form = |group: &str, label: &str, fields: Vec<Value>| SettingForm {
+        group: group.to_string(),
+        label: Some(label.to_string()),
+        form_schema: fields,
+    };
+    vec![
+        channel(
+            TELEGRAM_NOTIFIER, "Telegram", "Send notifications through a Telegram bot",
+            Some(form("sender", "Bot", vec![text_field("botToken", "Bot token", "Token issued by @BotFather")])),
+            Some(form("receiver", "Chat", vec![text_field("chatId", "Chat ID", "Chat that receives the notifications")])),
+        ),
+        channel(
+            SLACK_NOTIFIER, "Slack", "Send notifications to a Slack incoming webhook",
+            None,
+            Some(form("receiver", "Webhook", vec![text_field("webhookUrl", "Webhook URL", "Slack incoming webhook URL")])),
+        ),
+        channel(
+            DISCORD_NOTIFIER, "Discord", "Send notifications to a Discord webhook",
+            None,
+            Some(form("receiver", "Webhook", vec![text_field("webhookUrl", "Webhook URL", "Discord channel webhook URL")])),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_descriptors() {
+        for (descriptor, setting) in builtin_descriptors() {
+            assert_ne!(descriptor.metadata.name, setting.metadata.name);
+            let refs = [&descriptor.spec.sender_setting_ref, &descriptor.spec.receiver_setting_ref];
+            for setting_ref in refs.into_iter().flatten() {
+                assert_eq!(setting_ref.name, setting.metadata.name);
+                assert!(setting.spec.forms.iter().any(|form| form.group == setting_ref.group));
+            }
+        }
+    }
+
+    #[test]
+    fn test_form_defaults_and_unknown_fields() {
+        let form = SettingForm {
+            group: "sender".to_string(),
+            label: None,
+            form_schema: vec![
+                json!({"$formkit": "text", "name": "botToken"}),
+                json!({"$formkit": "text", "name": "apiBase", "value": "https://api.telegram.org"}),
+            ],
+        };
+        let config = with_form_defaults(Some(&form), HashMap::from([("botToken".to_string(), json!("token"))]));
+        assert_eq!(config["apiBase"], "https://api.telegram.org");
+        assert_eq!(config["botToken"], "token");
+
+        let config = HashMap::from([("botToken".to_string(), json!("t")), ("chatId".to_string(), json!(1))]);
+        assert_eq!(unknown_fields(&form, &config), vec!["chatId"]);
+    }
+}
@@ -0,0 +1,299 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use crate::notification::descriptor::resolve_notifier;
+use crate::notification::{
+    MessagePayload, NotificationContext, NotificationMessage, NotificationPreferenceService, NotificationSender,
+    NotificationSubject, NotifierDescriptorService,
+};
+
+/// 保存待发送摘要的ConfigMap，每个用户一个键，值为JSON字符串
+pub const DIGEST_QUEUE_CONFIG_MAP: &str = "notification-digest-queue";
+
+/// 默认的摘要检查间隔
+pub const DEFAULT_DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// 摘要中的一条通知
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestItem {
+    /// 订阅者选择的通知渠道
+    pub notifier: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl DigestItem {
+    fn new(notifier: &str, message: &NotificationMessage) -> Self {
+        Self {
+            notifier: notifier.to_string(),
+            title: message.payload.title.clone(),
+            url: message.subject.url.clone(),
+            timestamp: message.timestamp,
+        }
+    }
+}
+
+/// 用户待发送的摘要，按加入顺序保存
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DigestQueue {
+    #[serde(default)]
+    pub items: Vec<DigestItem>,
+}
+
+impl DigestQueue {
+    /// 最早的一条通知加入后经过了窗口时长即到期，没有窗口（用户改回立即发送）时立即到期
+    pub fn is_due(&self, window: Option<chrono::Duration>, now: DateTime<Utc>) -> bool {
+        let Some(oldest) = self.items.iter().map(|item| item.timestamp).min() else {
+            return false;
+        };
+        window.map(|window| oldest + window <= now).unwrap_or(true)
+    }
+}
+
+/// 把多条通知合并为一条摘要消息
+pub fn summarize(recipient: &str, items: &[DigestItem], now: DateTime<Utc>) -> NotificationMessage {
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let title = if items.len() == 1 {
+        "1 new notification".to_string()
+    } else {
+        format!("{} new notifications", items.len())
+    };
+    let mut raw_body = String::new();
+    let mut html_body = String::from("<ul>");
+    for item in items {
+        raw_body.push_str(&format!("- {}", item.title));
+        if let Some(url) = &item.url {
+            raw_body.push_str(&format!(" ({})", url));
+        }
+        raw_body.push('\n');
+        match &item.url {
+            Some(url) => html_body.push_str(&format!("<li><a href=\"{}\">{}</a></li>", escape(url), escape(&item.title))),
+            None => html_body.push_str(&format!("<li>{}</li>", escape(&item.title))),
+        }
+    }
+    html_body.push_str("</ul>");
+    NotificationMessage {
+        payload: MessagePayload {
+            title,
+            raw_body: Some(raw_body.trim_end().to_string()),
+            html_body: Some(html_body),
+            attributes: None,
+        },
+        subject: NotificationSubject {
+            api_version: "notification.halo.run/v1alpha1".to_string(),
+            kind: "Notification".to_string(),
+            name: "digest".to_string(),
+            title: "Notification digest".to_string(),
+            url: None,
+        },
+        recipient: recipient.to_string(),
+        timestamp: now,
+    }
+}
+
+/// 通知摘要服务trait
+#[async_trait]
+pub trait NotificationDigestService: Send + Sync {
+    /// 把要通过通知渠道发送的消息加入接收者的摘要队列
+    async fn enqueue(&self, notifier: &str, message: &NotificationMessage) -> Result<()>;
+
+    /// 发送所有到期的摘要，每个用户的每个通知渠道一条消息，返回发送的摘要数量
+    async fn send_due(&self, now: DateTime<Utc>) -> Result<usize>;
+}
+
+/// 默认通知摘要服务实现，待发送的通知保存在 `notification-digest-queue` ConfigMap中
+pub struct DefaultNotificationDigestService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    preference_service: Arc<dyn NotificationPreferenceService>,
+    sender: Arc<dyn NotificationSender>,
+    descriptor_service: Option<Arc<dyn NotifierDescriptorService>>,
+    /// 串行化队列的读改写，避免加入和发送同时进行时丢失通知
+    lock: Mutex<()>,
+}
+
+impl DefaultNotificationDigestService {
+    pub fn new(
+        extension_client: Arc<ReactiveExtensionClient>,
+        preference_service: Arc<dyn NotificationPreferenceService>,
+        sender: Arc<dyn NotificationSender>,
+    ) -> Self {
+        Self { extension_client, preference_service, sender, descriptor_service: None, lock: Mutex::new(()) }
+    }
+
+    /// 设置通知渠道描述服务，按渠道描述解析通知器并填充发送者和接收者配置
+    pub fn with_descriptors(mut self, descriptor_service: Arc<dyn NotifierDescriptorService>) -> Self {
+        self.descriptor_service = Some(descriptor_service);
+        self
+    }
+
+    async fn fetch_queues(&self) -> Result<Option<ConfigMap>> {
+        self.extension_client.fetch(DIGEST_QUEUE_CONFIG_MAP).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch digest queue: {}", e))
+    }
+
+    async fn save_queues(&self, existing: Option<ConfigMap>, data: HashMap<String, String>) -> Result<()> {
+        match existing {
+            Some(mut config_map) => {
+                config_map.data = Some(data);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(DIGEST_QUEUE_CONFIG_MAP.to_string()),
+                data: Some(data),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save digest queue: {}", e))?;
+        Ok(())
+    }
+
+    /// 按通知渠道分别发送用户的摘要，发送失败只记录日志
+    async fn send_digest(&self, username: &str, queue: DigestQueue, now: DateTime<Utc>) -> usize {
+        let mut by_notifier: Vec<(String, Vec<DigestItem>)> = Vec::new();
+        for item in queue.items {
+            match by_notifier.iter_mut().find(|(notifier, _)| *notifier == item.notifier) {
+                Some((_, items)) => items.push(item),
+                None => by_notifier.push((item.notifier.clone(), vec![item])),
+            }
+        }
+        let mut sent = 0;
+        for (notifier, items) in by_notifier {
+            let resolved = match resolve_notifier(self.descriptor_service.as_ref(), &notifier, username).await {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    tracing::warn!("Failed to resolve notifier {} for {}: {}", notifier, username, e);
+                    continue;
+                }
+            };
+            let context = NotificationContext {
+                message: summarize(username, &items, now),
+                receiver_config: resolved.receiver_config,
+                sender_config: resolved.sender_config,
+            };
+            match self.sender.send_notification(&resolved.notifier_ext_name, context).await {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::warn!("Failed to send notification digest to {} via {}: {}", username, notifier, e),
+            }
+        }
+        sent
+    }
+}
+
+#[async_trait]
+impl NotificationDigestService for DefaultNotificationDigestService {
+    async fn enqueue(&self, notifier: &str, message: &NotificationMessage) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let existing = self.fetch_queues().await?;
+        let mut data = existing.as_ref().and_then(|config_map| config_map.data.clone()).unwrap_or_default();
+        let mut queue: DigestQueue = match data.get(&message.recipient) {
+            Some(stored) => serde_json::from_str(stored)?,
+            None => DigestQueue::default(),
+        };
+        queue.items.push(DigestItem::new(notifier, message));
+        data.insert(message.recipient.clone(), serde_json::to_string(&queue)?);
+        self.save_queues(existing, data).await
+    }
+
+    async fn send_due(&self, now: DateTime<Utc>) -> Result<usize> {
+        let _guard = self.lock.lock().await;
+        let existing = self.fetch_queues().await?;
+        let data = existing.as_ref().and_then(|config_map| config_map.data.clone()).unwrap_or_default();
+        let mut remaining = HashMap::new();
+        let mut sent = 0;
+        for (username, stored) in data {
+            let queue: DigestQueue = match serde_json::from_str(&stored) {
+                Ok(queue) => queue,
+                Err(e) => {
+                    tracing::warn!("Dropping invalid digest queue of {}: {}", username, e);
+                    continue;
+                }
+            };
+            let window = match self.preference_service.get_preference(&username).await {
+                Ok(preference) => preference.digest.window(),
+                Err(e) => {
+                    tracing::warn!("Failed to get notification preference of {}: {}", username, e);
+                    remaining.insert(username, stored);
+                    continue;
+                }
+            };
+            if queue.is_due(window, now) {
+                sent += self.send_digest(&username, queue, now).await;
+            } else if !queue.items.is_empty() {
+                remaining.insert(username, stored);
+            }
+        }
+        self.save_queues(existing, remaining).await?;
+        Ok(sent)
+    }
+}
+
+/// 启动后台摘要任务，定期发送到期的摘要
+pub fn spawn_digest_job(
+    service: Arc<dyn NotificationDigestService>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match service.send_due(Utc::now()).await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("Sent {} notification digests", sent),
+                Err(e) => tracing::warn!("Notification digest failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str, url: Option<&str>, timestamp: DateTime<Utc>) -> DigestItem {
+        DigestItem {
+            notifier: "telegram-notifier".to_string(),
+            title: title.to_string(),
+            url: url.map(str::to_string),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let queue = DigestQueue { items: vec![item("a", None, now - chrono::Duration::minutes(90)), item("b", None, now)] };
+        assert!(queue.is_due(Some(chrono::Duration::hours(1)), now));
+        assert!(!queue.is_due(Some(chrono::Duration::days(1)), now));
+        assert!(queue.is_due(None, now));
+        assert!(!DigestQueue::default().is_due(None, now));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let now = Utc::now();
+        let items = vec![
+            item("New comment on <Hello>", Some("https://example.com/archives/hello"), now),
+            item("New reply", None, now),
+        ];
+        let message = summarize("admin", &items, now);
+        assert_eq!(message.payload.title, "2 new notifications");
+        assert_eq!(
+            message.payload.raw_body.as_deref(),
+            Some("- New comment on <Hello> (https://example.com/archives/hello)\n- New reply")
+        );
+        assert_eq!(
+            message.payload.html_body.as_deref(),
+            Some("<ul><li><a href=\"https://example.com/archives/hello\">New comment on &lt;Hello&gt;</a></li><li>New reply</li></ul>")
+        );
+        assert_eq!(message.recipient, "admin");
+        assert_eq!(summarize("admin", &items[..1], now).payload.title, "1 new notification");
+    }
+}
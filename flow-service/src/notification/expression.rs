@@ -0,0 +1,141 @@
+use anyhow::Result;
+use evalexpr::{ContextWithMutableVariables, HashMapContext, Node, Value};
+use flow_domain::notification::Reason;
+
+/// 订阅表达式无效的错误前缀
+pub const SUBSCRIPTION_EXPRESSION_INVALID_ERROR: &str = "Invalid subscription expression";
+
+/// 把订阅表达式转换为evalexpr语法
+///
+/// 表达式沿用Halo的写法：字符串可以用单引号（`'admin'`），逻辑运算可以写作 `and`、`or`、`not`，字符串中的内容原样保留
+pub fn normalize_expression(expression: &str) -> String {
+    fn flush(word: &mut String, normalized: &mut String) {
+        match word.as_str() {
+            "and" => normalized.push_str("&&"),
+            "or" => normalized.push_str("||"),
+            "not" => normalized.push('!'),
+            _ => normalized.push_str(word),
+        }
+        word.clear();
+    }
+
+    let mut normalized = String::with_capacity(expression.len());
+    let mut word = String::new();
+    let mut chars = expression.chars();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            word.push(c);
+            continue;
+        }
+        flush(&mut word, &mut normalized);
+        if c != '\'' && c != '"' {
+            normalized.push(c);
+            continue;
+        }
+        // 字符串统一改为双引号，内容中的双引号需要转义
+        let quote = c;
+        normalized.push('"');
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    normalized.push('\\');
+                    if let Some(escaped) = chars.next() {
+                        normalized.push(escaped);
+                    }
+                }
+                _ if c == quote => break,
+                '"' => normalized.push_str("\\\""),
+                _ => normalized.push(c),
+            }
+        }
+        normalized.push('"');
+    }
+    flush(&mut word, &mut normalized);
+    normalized
+}
+
+/// 解析订阅表达式，用于在保存订阅前校验语法
+pub fn parse_expression(expression: &str) -> Result<Node> {
+    evalexpr::build_operator_tree(&normalize_expression(expression))
+        .map_err(|e| anyhow::anyhow!("{}: {}: {}", SUBSCRIPTION_EXPRESSION_INVALID_ERROR, expression, e))
+}
+
+/// 对原因计算订阅表达式
+///
+/// 可以使用的变量：`props.*`（原因的属性）、`subject.apiVersion`、`subject.kind`、`subject.name`、
+/// `subject.title`、`subject.url` 和 `author`。原因中不存在的变量为空值，与任何字符串都不相等
+pub fn evaluate_expression(expression: &str, reason: &Reason) -> Result<bool> {
+    let node = parse_expression(expression)?;
+    let subject = &reason.spec.subject;
+    let mut variables = vec![
+        ("subject.apiVersion".to_string(), subject.api_version.clone()),
+        ("subject.kind".to_string(), subject.kind.clone()),
+        ("subject.name".to_string(), subject.name.clone()),
+        ("subject.title".to_string(), subject.title.clone()),
+        ("author".to_string(), reason.spec.author.clone()),
+    ];
+    if let Some(url) = &subject.url {
+        variables.push(("subject.url".to_string(), url.clone()));
+    }
+    for (key, value) in reason.spec.attributes.iter().flat_map(|attributes| attributes.iter()) {
+        variables.push((format!("props.{}", key), value.clone()));
+    }
+
+    let mut context = HashMapContext::new();
+    for identifier in node.iter_read_variable_identifiers() {
+        if !variables.iter().any(|(name, _)| name == identifier) {
+            context.set_value(identifier.to_string(), Value::Empty)?;
+        }
+    }
+    for (name, value) in variables {
+        context.set_value(name, Value::String(value))?;
+    }
+    node.eval_boolean_with_context(&context)
+        .map_err(|e| anyhow::anyhow!("Failed to evaluate subscription expression '{}': {}", expression, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::notification::{ReasonSpec, ReasonSubject};
+    use std::collections::HashMap;
+
+    fn reason(owner: Option<&str>) -> Reason {
+        Reason {
+            metadata: Metadata::new("reason".to_string()),
+            spec: ReasonSpec {
+                reason_type: "new-comment-on-post".to_string(),
+                subject: ReasonSubject {
+                    api_version: "content.halo.run/v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: "hello".to_string(),
+                    title: "Hello".to_string(),
+                    url: None,
+                },
+                author: "guqing".to_string(),
+                attributes: owner.map(|owner| HashMap::from([("owner".to_string(), owner.to_string())])),
+            },
+        }
+    }
+
+    #[test]
+    fn test_normalize_expression() {
+        assert_eq!(
+            normalize_expression("props.owner == 'admin' and not (subject.kind == 'Post')"),
+            "props.owner == \"admin\" && ! (subject.kind == \"Post\")"
+        );
+        assert_eq!(normalize_expression("author == 'say \"hi\" or bye'"), "author == \"say \\\"hi\\\" or bye\"");
+    }
+
+    #[test]
+    fn test_evaluate_expression() {
+        let expression = "props.owner == 'admin' && subject.kind == 'Post'";
+        assert!(evaluate_expression(expression, &reason(Some("admin"))).unwrap());
+        assert!(!evaluate_expression(expression, &reason(Some("guqing"))).unwrap());
+        assert!(!evaluate_expression(expression, &reason(None)).unwrap());
+        assert!(evaluate_expression("props.owner != 'admin' or author == 'admin'", &reason(None)).unwrap());
+        let error = evaluate_expression("(props.owner == 'admin'", &reason(None)).unwrap_err();
+        assert!(error.to_string().starts_with(SUBSCRIPTION_EXPRESSION_INVALID_ERROR));
+    }
+}
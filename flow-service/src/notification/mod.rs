@@ -6,8 +6,44 @@ use serde_json::Value;
 
 pub mod notification_service;
 pub mod notification_center;
+pub mod notifier;
+pub mod chat;
+pub mod default_subscription;
+pub mod descriptor;
+pub mod digest;
+pub mod expression;
+pub mod preference;
+pub mod push;
+pub mod reason_type;
+pub mod retention;
+pub mod template;
+pub mod throttle;
 
 pub use notification_service::{NotificationService, DefaultNotificationService};
+pub use notifier::{NotifierRegistry, NOTIFIER_NOT_FOUND_ERROR};
+pub use chat::{TelegramNotifier, SlackNotifier, DiscordNotifier, TELEGRAM_NOTIFIER, SLACK_NOTIFIER, DISCORD_NOTIFIER};
+pub use descriptor::{
+    NotifierDescriptorService, DefaultNotifierDescriptorService, NotifierConfig, ResolvedNotifier,
+    NOTIFIER_DESCRIPTOR_NOT_FOUND_ERROR, NOTIFIER_CONFIG_INVALID_ERROR,
+};
+pub use digest::{NotificationDigestService, DefaultNotificationDigestService, spawn_digest_job, DEFAULT_DIGEST_CHECK_INTERVAL};
+pub use retention::{
+    NotificationRetentionService, DefaultNotificationRetentionService, NotificationRetentionConfig,
+    spawn_notification_cleanup_job,
+};
+pub use default_subscription::{
+    DefaultSubscriptionListener, REASON_NEW_USER_REGISTERED, REASON_SYSTEM_FAILURE, SUPER_ROLE_NAME,
+};
+pub use expression::SUBSCRIPTION_EXPRESSION_INVALID_ERROR;
+pub use reason_type::{
+    ReasonTypeService, DefaultReasonTypeService, REASON_TYPE_NOT_FOUND_ERROR, REASON_ATTRIBUTES_INVALID_ERROR,
+};
+pub use throttle::{NotificationThrottle, NotificationThrottleConfig};
+pub use push::{NotificationBroadcaster, NotificationPushEndpoint, NotificationPushMessage};
+pub use preference::{
+    NotificationPreferenceService, DefaultNotificationPreferenceService, NotificationPreference, NotifierSetting, DigestFrequency,
+    NOTIFICATION_PREFERENCE_INVALID_ERROR,
+};
 
 /// 通知上下文
 /// 包含发送通知所需的所有信息
@@ -1,6 +1,16 @@
 use async_trait::async_trait;
-use flow_domain::notification::{Reason, Subscription, SubscriptionSubscriber, InterestReason};
-use crate::notification::{NotificationService, NotificationSender, NotificationCenter};
+use flow_domain::notification::{Notification, Reason, Subscription, SubscriptionSubscriber, InterestReason};
+use flow_domain::security::User;
+use crate::notification::{
+    NotificationService, NotificationSender, NotificationCenter, NotificationPreferenceService, NotifierDescriptorService,
+    NotificationDigestService, NotificationPreference, ReasonTypeService,
+};
+use crate::notification::descriptor::resolve_notifier;
+use crate::notification::throttle::{aggregated_title, NotificationThrottle, ThrottleDecision};
+use crate::notification::expression::{evaluate_expression, parse_expression};
+use crate::notification::template::{
+    render_template, select_template, template_variables, RenderedNotification, DEFAULT_TEMPLATE_LANGUAGE,
+};
 use flow_api::extension::{ExtensionClient, ListOptions, query::Condition};
 use flow_infra::extension::ReactiveExtensionClient;
 use std::sync::Arc;
@@ -11,8 +21,20 @@ use uuid::Uuid;
 pub struct DefaultNotificationCenter {
     extension_client: Arc<ReactiveExtensionClient>,
     notification_service: Arc<dyn NotificationService>,
-    #[allow(dead_code)] // 保留用于未来扩展（邮件、短信等通知方式）
+    /// 按通知器名称发送站外通知（邮件、短信等）
     sender: Arc<dyn NotificationSender>,
+    /// 订阅者的通知偏好，决定每种原因类型通过哪些通知器发送；未设置时只创建站内通知
+    preference_service: Option<Arc<dyn NotificationPreferenceService>>,
+    /// 通知渠道描述，把偏好中的渠道解析为通知器及其发送者、接收者配置
+    descriptor_service: Option<Arc<dyn NotifierDescriptorService>>,
+    /// 合并站外通知的摘要服务，未设置时总是立即发送
+    digest_service: Option<Arc<dyn NotificationDigestService>>,
+    /// 站点外部访问地址，用于在模板中生成绝对地址
+    external_url: Option<String>,
+    /// 原因类型注册表，设置后只发送已注册且属性符合声明的原因
+    reason_type_service: Option<Arc<dyn ReasonTypeService>>,
+    /// 通知去重和限流，未设置时每个原因都单独通知
+    throttle: Option<Arc<NotificationThrottle>>,
 }
 
 impl DefaultNotificationCenter {
@@ -25,18 +47,134 @@ impl DefaultNotificationCenter {
             extension_client,
             notification_service,
             sender,
+            preference_service: None,
+            descriptor_service: None,
+            digest_service: None,
+            external_url: None,
+            reason_type_service: None,
+            throttle: None,
+        }
+    }
+    
+    /// 设置通知偏好服务，按订阅者选择的通知器发送站外通知
+    pub fn with_preferences(mut self, preference_service: Arc<dyn NotificationPreferenceService>) -> Self {
+        self.preference_service = Some(preference_service);
+        self
+    }
+    
+    /// 设置通知渠道描述服务，按渠道描述解析通知器并填充发送者和接收者配置
+    pub fn with_descriptors(mut self, descriptor_service: Arc<dyn NotifierDescriptorService>) -> Self {
+        self.descriptor_service = Some(descriptor_service);
+        self
+    }
+    
+    /// 设置摘要服务，选择按小时或按天接收的订阅者的站外通知合并为摘要发送
+    pub fn with_digests(mut self, digest_service: Arc<dyn NotificationDigestService>) -> Self {
+        self.digest_service = Some(digest_service);
+        self
+    }
+    
+    /// 设置站点外部访问地址，模板中的站内路径据此补全为绝对地址
+    pub fn with_external_url(mut self, external_url: Option<String>) -> Self {
+        self.external_url = external_url;
+        self
+    }
+    
+    /// 设置原因类型注册表，发送前按原因类型校验原因
+    pub fn with_reason_types(mut self, reason_type_service: Arc<dyn ReasonTypeService>) -> Self {
+        self.reason_type_service = Some(reason_type_service);
+        self
+    }
+    
+    /// 设置通知限流，窗口内同一接收者、原因类型和主题的通知合并为一条
+    pub fn with_throttle(mut self, throttle: Arc<NotificationThrottle>) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+    
+    /// 合并窗口内的重复通知
+    ///
+    /// 是重复通知时返回true：第一条站内通知仍未读时用新内容和合并计数更新它，已读或已删除时重新创建。
+    /// 不是重复通知时返回false，由调用方创建站内通知
+    async fn collapse_repeated(&self, subscriber: &str, reason: &Reason, notification: &Notification) -> bool {
+        let Some(throttle) = &self.throttle else {
+            return false;
+        };
+        let (count, first) = match throttle.check(subscriber, reason).await {
+            Ok(ThrottleDecision::First) => return false,
+            Ok(ThrottleDecision::Repeat { count, first }) => (count, first),
+            Err(e) => {
+                tracing::warn!("Notification throttle failed for {}: {}", subscriber, e);
+                return false;
+            }
+        };
+        let existing = match first {
+            Some(name) => self.notification_service.get(&name).await.unwrap_or_else(|e| {
+                tracing::warn!("Failed to fetch notification {}: {}", name, e);
+                None
+            }),
+            None => None,
+        };
+        match existing.filter(|existing| existing.spec.unread.unwrap_or(true)) {
+            Some(mut existing) => {
+                existing.spec.reason = notification.spec.reason.clone();
+                existing.spec.title = aggregated_title(&notification.spec.title, count);
+                existing.spec.raw_content = notification.spec.raw_content.clone();
+                existing.spec.html_content = notification.spec.html_content.clone();
+                if let Err(e) = self.notification_service.update(existing).await {
+                    tracing::warn!("Failed to update collapsed notification for {}: {}", subscriber, e);
+                }
+            }
+            None => self.create_notification(subscriber, reason, notification.clone()).await,
+        }
+        true
+    }
+    
+    /// 创建站内通知并记录为窗口内的第一条通知，失败只记录日志，继续处理其他订阅者
+    async fn create_notification(&self, subscriber: &str, reason: &Reason, notification: Notification) {
+        let created = match self.notification_service.create(notification).await {
+            Ok(created) => created,
+            Err(e) => {
+                tracing::warn!("Failed to create notification for subscriber {}: {}", subscriber, e);
+                return;
+            }
+        };
+        if let Some(throttle) = &self.throttle {
+            if let Err(e) = throttle.remember(subscriber, reason, &created.metadata.name).await {
+                tracing::warn!("Failed to remember notification {}: {}", created.metadata.name, e);
+            }
+        }
+    }
+    
+    /// 订阅者的通知偏好，未设置偏好服务或读取失败时为默认偏好（不发送站外通知）
+    async fn subscriber_preference(&self, subscriber: &str) -> NotificationPreference {
+        let Some(preference_service) = &self.preference_service else {
+            return NotificationPreference::default();
+        };
+        match preference_service.get_preference(subscriber).await {
+            Ok(preference) => preference,
+            Err(e) => {
+                tracing::warn!("Failed to get notification preference of {}: {}", subscriber, e);
+                NotificationPreference::default()
+            }
         }
     }
     
     /// 查找匹配的订阅
     async fn find_matching_subscriptions(&self, reason: &Reason) -> Result<Vec<Subscription>> {
-        let options = ListOptions::default();
-        // 查找所有订阅（后续可以优化为只查询匹配reason_type的订阅）
-        let result = self.extension_client.list::<Subscription>(options).await
+        // 逐页查询该reason_type的全部订阅
+        let options = ListOptions {
+            condition: Some(Condition::Equal {
+                index_name: "spec.reason.reasonType".to_string(),
+                value: serde_json::Value::String(reason.spec.reason_type.clone()),
+            }),
+            ..Default::default()
+        };
+        let subscriptions = self.extension_client.list_all::<Subscription>(options).await
             .map_err(|e| anyhow::anyhow!("Failed to list subscriptions: {}", e))?;
         
         // 过滤匹配的订阅
-        let matching: Vec<Subscription> = result.items.into_iter()
+        let matching: Vec<Subscription> = subscriptions.into_iter()
             .filter(|sub| self.matches_reason(sub, reason))
             .collect();
         
@@ -82,76 +220,32 @@ impl DefaultNotificationCenter {
         true
     }
     
-    /// 评估SpEL表达式
-    /// 表达式上下文包含：props（reason attributes）、subject、author
-    /// 注意：evalexpr不支持嵌套的HashMap，所以我们使用点号访问路径，如props.owner
+    /// 计算订阅表达式，表达式无效时视为不匹配
     fn evaluate_expression(&self, expression: &str, reason: &Reason) -> bool {
-        use evalexpr::{eval_boolean_with_context, Value, HashMapContext, ContextWithMutableVariables};
-        
-        // 创建表达式上下文
-        let mut context = HashMapContext::new();
-        
-        // 添加props（reason attributes）- 使用点号访问路径
-        if let Some(attributes) = &reason.spec.attributes {
-            for (key, value) in attributes {
-                let var_name = format!("props.{}", key);
-                context.set_value(var_name, Value::String(value.clone()))
-                    .unwrap_or_else(|_| {
-                        tracing::warn!("Failed to set props.{} in expression context", key);
-                    });
-            }
-        }
-        
-        // 添加subject属性 - 使用点号访问路径
-        context.set_value("subject.apiVersion".to_string(), Value::String(reason.spec.subject.api_version.clone()))
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to set subject.apiVersion in expression context");
-            });
-        context.set_value("subject.kind".to_string(), Value::String(reason.spec.subject.kind.clone()))
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to set subject.kind in expression context");
-            });
-        context.set_value("subject.name".to_string(), Value::String(reason.spec.subject.name.clone()))
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to set subject.name in expression context");
-            });
-        context.set_value("subject.title".to_string(), Value::String(reason.spec.subject.title.clone()))
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to set subject.title in expression context");
-            });
-        if let Some(url) = &reason.spec.subject.url {
-            context.set_value("subject.url".to_string(), Value::String(url.clone()))
-                .unwrap_or_else(|_| {
-                    tracing::warn!("Failed to set subject.url in expression context");
-                });
-        }
-        
-        // 添加author
-        context.set_value("author".to_string(), Value::String(reason.spec.author.clone()))
-            .unwrap_or_else(|_| {
-                tracing::warn!("Failed to set author in expression context");
-            });
-        
-        // 评估表达式
-        // 注意：evalexpr使用点号访问嵌套属性，如props.owner == "guqing"
-        match eval_boolean_with_context(expression, &context) {
-            Ok(result) => result,
+        evaluate_expression(expression, reason).unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            false
+        })
+    }
+    
+    /// 订阅者的首选语言，不是注册用户（如访客邮箱）或未设置时为默认语言
+    async fn subscriber_language(&self, subscriber: &str) -> String {
+        match self.extension_client.fetch::<User>(subscriber).await {
+            Ok(user) => user.and_then(|user| user.spec.language),
             Err(e) => {
-                tracing::warn!("Failed to evaluate expression '{}' for subscription: {}", expression, e);
-                false
+                tracing::warn!("Failed to fetch user {}: {}", subscriber, e);
+                None
             }
-        }
+        }.unwrap_or_else(|| DEFAULT_TEMPLATE_LANGUAGE.to_string())
     }
     
     /// 查找通知模板
-    /// 根据reason_type和language查找匹配的模板
+    /// 根据reason_type和language查找匹配的模板，没有该语言的模板时使用默认语言的模板
     async fn find_notification_template(
         &self,
         reason_type: &str,
         language: &str,
     ) -> Option<flow_domain::notification::NotificationTemplate> {
-        use flow_api::extension::query::Condition;
-        
         // 查找匹配reason_type的模板
         let options = ListOptions {
             condition: Some(Condition::Equal {
@@ -162,41 +256,45 @@ impl DefaultNotificationCenter {
         };
         
         match self.extension_client.list::<flow_domain::notification::NotificationTemplate>(options).await {
-            Ok(result) => {
-                // 过滤匹配语言的模板，选择最新的（按创建时间）
-                let mut matching: Vec<_> = result.items.into_iter()
-                    .filter(|t| {
-                        t.spec.as_ref()
-                            .and_then(|s| s.reason_selector.as_ref())
-                            .map(|rs| rs.language.as_str() == language || rs.language == "default")
-                            .unwrap_or(false)
-                    })
-                    .collect();
-                
-                // 按创建时间排序，选择最新的
-                matching.sort_by(|a, b| {
-                    let a_time = a.metadata.creation_timestamp.as_ref()
-                        .map(|ts| ts.timestamp())
-                        .unwrap_or(0);
-                    let b_time = b.metadata.creation_timestamp.as_ref()
-                        .map(|ts| ts.timestamp())
-                        .unwrap_or(0);
-                    b_time.cmp(&a_time)
-                });
-                
-                matching.into_iter().next()
-            }
+            Ok(result) => select_template(result.items, language),
             Err(e) => {
                 tracing::warn!("Failed to find notification template for reason_type {}: {}", reason_type, e);
                 None
             }
         }
     }
+    
+    /// 用模板渲染通知内容，没有模板或渲染失败时使用默认内容
+    fn render_notification(
+        &self,
+        template: Option<&flow_domain::notification::NotificationTemplate>,
+        reason: &Reason,
+        subscriber: &str,
+    ) -> RenderedNotification {
+        let content = template.and_then(|t| t.spec.as_ref()).and_then(|s| s.template.as_ref());
+        if let Some(content) = content {
+            let external_url = self.external_url.as_deref();
+            let variables = template_variables(reason, subscriber, external_url);
+            match render_template(content, &variables, external_url) {
+                Ok(rendered) => return rendered,
+                Err(e) => tracing::warn!("Failed to render notification template for {}: {}", reason.spec.reason_type, e),
+            }
+        }
+        RenderedNotification {
+            title: format!("Notification: {}", reason.spec.subject.title),
+            raw_body: format!("Reason: {}", reason.spec.reason_type),
+            html_body: format!("<p>Reason: {}</p>", reason.spec.reason_type),
+        }
+    }
 }
 
 #[async_trait]
 impl NotificationCenter for DefaultNotificationCenter {
     async fn notify(&self, reason: Reason) -> Result<()> {
+        if let Some(reason_type_service) = &self.reason_type_service {
+            reason_type_service.validate(&reason).await?;
+        }
+        
         // 1. 查找所有订阅该reason的Subscription
         let subscriptions = self.find_matching_subscriptions(&reason).await?;
         
@@ -209,61 +307,42 @@ impl NotificationCenter for DefaultNotificationCenter {
             
             let subscriber_name = &subscription.spec.subscriber.name;
             
-            // 查找通知模板
-            let template = self.find_notification_template(&reason.spec.reason_type, "default").await;
+            // 按订阅者的首选语言查找通知模板
+            let language = self.subscriber_language(subscriber_name).await;
+            let template = self.find_notification_template(&reason.spec.reason_type, &language).await;
             
             // 创建站内通知
-            use flow_domain::notification::{Notification, NotificationSpec};
+            use flow_domain::notification::NotificationSpec;
             use flow_api::extension::Metadata;
             
             // 使用模板渲染通知内容（如果找到模板）
-            let (title, raw_content, html_content) = if let Some(template) = template {
-                if let Some(template_content) = &template.spec.as_ref().and_then(|s| s.template.as_ref()) {
-                    // 使用模板内容
-                    (
-                        template_content.title.clone(),
-                        template_content.raw_body.clone().unwrap_or_else(|| "".to_string()),
-                        template_content.html_body.clone().unwrap_or_else(|| "".to_string()),
-                    )
-                } else {
-                    // 模板没有内容，使用默认
-                    (
-                        format!("Notification: {}", reason.spec.subject.title),
-                        format!("Reason: {}", reason.spec.reason_type),
-                        format!("<p>Reason: {}</p>", reason.spec.reason_type),
-                    )
-                }
-            } else {
-                // 没有找到模板，使用默认内容
-                (
-                    format!("Notification: {}", reason.spec.subject.title),
-                    format!("Reason: {}", reason.spec.reason_type),
-                    format!("<p>Reason: {}</p>", reason.spec.reason_type),
-                )
-            };
+            let rendered = self.render_notification(template.as_ref(), &reason, subscriber_name);
             
             let notification = Notification {
                 metadata: Metadata::new(Uuid::new_v4().to_string()),
                 spec: NotificationSpec {
                     recipient: subscriber_name.clone(),
                     reason: reason.metadata.name.clone(),
-                    title,
-                    raw_content,
-                    html_content,
+                    title: rendered.title,
+                    raw_content: rendered.raw_body,
+                    html_content: rendered.html_body,
                     unread: Some(true),
                     last_read_at: None,
                 },
             };
             
-            // 创建站内通知（忽略错误，继续处理其他订阅者）
-            if let Err(e) = self.notification_service.create(notification.clone()).await {
-                tracing::warn!("Failed to create notification for subscriber {}: {}", subscriber_name, e);
+            // 窗口内的重复通知合并到第一条站内通知中，不再发送即时站外通知
+            let throttled = self.collapse_repeated(subscriber_name, &reason, &notification).await;
+            if !throttled {
+                self.create_notification(subscriber_name, &reason, notification.clone()).await;
             }
             
-            // 使用NotificationSender发送其他类型的通知（邮件、短信等）
-            // 注意：目前InMemoryNotificationSender是占位实现，实际发送逻辑需要扩展
-            // 可以通过Extension系统注册不同的NotificationSender实现（如EmailNotificationSender、SmsNotificationSender）
-            // 然后根据subscription.spec.notifier来选择对应的发送器
+            // 通过订阅者在通知偏好中选择的通知器发送站外通知（邮件、短信等）
+            let preference = self.subscriber_preference(subscriber_name).await;
+            let notifiers = preference.notifiers(&reason.spec.reason_type);
+            if notifiers.is_empty() {
+                continue;
+            }
             let context = crate::notification::NotificationContext {
                 message: crate::notification::NotificationMessage {
                     payload: crate::notification::MessagePayload {
@@ -286,9 +365,35 @@ impl NotificationCenter for DefaultNotificationCenter {
                 sender_config: None,
             };
             
-            // 尝试发送通知（忽略错误，避免影响站内通知）
-            if let Err(e) = self.sender.send_notification("default", context).await {
-                tracing::debug!("Failed to send external notification for subscriber {}: {}", subscriber_name, e);
+            // 选择摘要的订阅者先加入摘要队列，由摘要任务定期合并发送
+            if let (Some(digest_service), Some(_)) = (&self.digest_service, preference.digest.window()) {
+                for notifier in notifiers {
+                    if let Err(e) = digest_service.enqueue(&notifier, &context.message).await {
+                        tracing::warn!("Failed to queue digest notification for {} via {}: {}", subscriber_name, notifier, e);
+                    }
+                }
+                continue;
+            }
+            
+            if throttled {
+                continue;
+            }
+            
+            // 发送失败只记录日志，不影响站内通知和其他通知器
+            for notifier in notifiers {
+                let resolved = match resolve_notifier(self.descriptor_service.as_ref(), &notifier, subscriber_name).await {
+                    Ok(resolved) => resolved,
+                    Err(e) => {
+                        tracing::warn!("Failed to resolve notifier {} for {}: {}", notifier, subscriber_name, e);
+                        continue;
+                    }
+                };
+                let mut context = context.clone();
+                context.sender_config = resolved.sender_config;
+                context.receiver_config = resolved.receiver_config;
+                if let Err(e) = self.sender.send_notification(&resolved.notifier_ext_name, context).await {
+                    tracing::warn!("Failed to send notification to {} via {}: {}", subscriber_name, notifier, e);
+                }
             }
         }
         
@@ -303,6 +408,11 @@ impl NotificationCenter for DefaultNotificationCenter {
         use flow_domain::notification::{Subscription, SubscriptionSpec};
         use flow_api::extension::Metadata;
         
+        // 保存前校验表达式，避免无效的订阅永远不匹配
+        if let Some(expression) = &interest_reason.expression {
+            parse_expression(expression)?;
+        }
+        
         let subscription = Subscription {
             metadata: Metadata::new(Uuid::new_v4().to_string()),
             spec: SubscriptionSpec {
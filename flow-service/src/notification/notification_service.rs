@@ -2,9 +2,11 @@ use async_trait::async_trait;
 use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
 use flow_domain::notification::Notification;
 use flow_infra::extension::ReactiveExtensionClient;
+use std::collections::HashSet;
 use std::sync::Arc;
 use anyhow::Result;
 use chrono::Utc;
+use crate::notification::push::{NotificationBroadcaster, NotificationPushMessage};
 
 /// Notification服务trait
 #[async_trait]
@@ -18,6 +20,9 @@ pub trait NotificationService: Send + Sync {
     /// 删除通知
     async fn delete(&self, name: &str) -> Result<()>;
     
+    /// 批量删除通知，不存在的通知被忽略，返回删除的数量
+    async fn delete_all(&self, names: &[String]) -> Result<usize>;
+    
     /// 获取通知
     async fn get(&self, name: &str) -> Result<Option<Notification>>;
     
@@ -37,29 +42,83 @@ pub trait NotificationService: Send + Sync {
 /// 默认Notification服务实现
 pub struct DefaultNotificationService {
     client: Arc<ReactiveExtensionClient>,
+    /// 通知创建和已读状态变化时推送给在线的接收者
+    broadcaster: Option<Arc<NotificationBroadcaster>>,
 }
 
 impl DefaultNotificationService {
     pub fn new(client: Arc<ReactiveExtensionClient>) -> Self {
-        Self { client }
+        Self { client, broadcaster: None }
+    }
+    
+    /// 设置推送广播，通知变化时实时推送给接收者
+    pub fn with_broadcaster(mut self, broadcaster: Arc<NotificationBroadcaster>) -> Self {
+        self.broadcaster = Some(broadcaster);
+        self
+    }
+    
+    /// 推送接收者最新的未读数量
+    async fn publish_unread_count(&self, recipient: &str) {
+        let Some(broadcaster) = &self.broadcaster else {
+            return;
+        };
+        match self.get_unread_count(recipient).await {
+            Ok(count) => broadcaster.publish(recipient, NotificationPushMessage::UnreadCount { count }),
+            Err(e) => tracing::warn!("Failed to get unread notification count of {}: {}", recipient, e),
+        }
     }
 }
 
 #[async_trait]
 impl NotificationService for DefaultNotificationService {
     async fn create(&self, notification: Notification) -> Result<Notification> {
-        self.client.create(notification).await
-            .map_err(|e| anyhow::anyhow!("Failed to create notification: {}", e))
+        let created = self.client.create(notification).await
+            .map_err(|e| anyhow::anyhow!("Failed to create notification: {}", e))?;
+        if let Some(broadcaster) = &self.broadcaster {
+            broadcaster.publish(&created.spec.recipient, NotificationPushMessage::Created { notification: Box::new(created.clone()) });
+            self.publish_unread_count(&created.spec.recipient).await;
+        }
+        Ok(created)
     }
 
     async fn update(&self, notification: Notification) -> Result<Notification> {
-        self.client.update(notification).await
-            .map_err(|e| anyhow::anyhow!("Failed to update notification: {}", e))
+        let updated = self.client.update(notification).await
+            .map_err(|e| anyhow::anyhow!("Failed to update notification: {}", e))?;
+        self.publish_unread_count(&updated.spec.recipient).await;
+        Ok(updated)
     }
 
     async fn delete(&self, name: &str) -> Result<()> {
+        // 删除前记录接收者，用于推送新的未读数量
+        let recipient = match &self.broadcaster {
+            Some(_) => self.get(name).await?.map(|notification| notification.spec.recipient),
+            None => None,
+        };
         self.client.delete::<Notification>(name).await
-            .map_err(|e| anyhow::anyhow!("Failed to delete notification: {}", e))
+            .map_err(|e| anyhow::anyhow!("Failed to delete notification: {}", e))?;
+        if let Some(recipient) = recipient {
+            self.publish_unread_count(&recipient).await;
+        }
+        Ok(())
+    }
+
+    async fn delete_all(&self, names: &[String]) -> Result<usize> {
+        let mut recipients = HashSet::new();
+        let mut deleted = 0;
+        for name in names {
+            let Some(notification) = self.get(name).await? else {
+                continue;
+            };
+            self.client.delete::<Notification>(name).await
+                .map_err(|e| anyhow::anyhow!("Failed to delete notification: {}", e))?;
+            recipients.insert(notification.spec.recipient);
+            deleted += 1;
+        }
+        // 全部删除后每个接收者只推送一次未读数量
+        for recipient in recipients {
+            self.publish_unread_count(&recipient).await;
+        }
+        Ok(deleted)
     }
 
     async fn get(&self, name: &str) -> Result<Option<Notification>> {
@@ -100,10 +159,12 @@ impl NotificationService for DefaultNotificationService {
             if notification.spec.unread.unwrap_or(true) {
                 notification.spec.unread = Some(false);
                 notification.spec.last_read_at = Some(now);
-                let _ = self.update(notification).await;
+                let _ = self.client.update(notification).await;
             }
         }
         
+        // 全部更新后只推送一次未读数量
+        self.publish_unread_count(recipient).await;
         Ok(())
     }
 
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use crate::notification::{NotificationContext, NotificationSender};
+use anyhow::Result;
+use std::sync::{Arc, RwLock};
+
+/// 找不到通知器时的错误信息前缀
+pub const NOTIFIER_NOT_FOUND_ERROR: &str = "Notifier not found";
+
+/// 通知器注册表
+///
+/// 插件按通知器扩展名称（如 `sms`、`dingtalk`）注册发送器，用户在通知偏好中按原因类型选择通知器。
+/// 注册表本身也是发送器，按名称分发给对应的通知器
+#[derive(Default)]
+pub struct NotifierRegistry {
+    notifiers: RwLock<Vec<(String, Arc<dyn NotificationSender>)>>,
+}
+
+impl NotifierRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册通知器，同名通知器会被替换
+    pub fn register(&self, name: &str, sender: Arc<dyn NotificationSender>) {
+        let mut notifiers = self.notifiers.write().unwrap();
+        notifiers.retain(|(n, _)| n != name);
+        notifiers.push((name.to_string(), sender));
+    }
+
+    /// 移除通知器
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut notifiers = self.notifiers.write().unwrap();
+        let before = notifiers.len();
+        notifiers.retain(|(n, _)| n != name);
+        notifiers.len() != before
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn NotificationSender>> {
+        self.notifiers.read().unwrap().iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, sender)| sender.clone())
+    }
+
+    /// 已注册的通知器名称
+    pub fn names(&self) -> Vec<String> {
+        self.notifiers.read().unwrap().iter().map(|(n, _)| n.clone()).collect()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for NotifierRegistry {
+    async fn send_notification(
+        &self,
+        notifier_extension_name: &str,
+        context: NotificationContext,
+    ) -> Result<()> {
+        let sender = self.get(notifier_extension_name)
+            .ok_or_else(|| anyhow::anyhow!("{}: {}", NOTIFIER_NOT_FOUND_ERROR, notifier_extension_name))?;
+        sender.send_notification(notifier_extension_name, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::{MessagePayload, NotificationMessage, NotificationSubject};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSender(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl NotificationSender for RecordingSender {
+        async fn send_notification(&self, notifier_extension_name: &str, context: NotificationContext) -> Result<()> {
+            self.0.lock().unwrap().push(format!("{}:{}", notifier_extension_name, context.message.recipient));
+            Ok(())
+        }
+    }
+
+    fn context(recipient: &str) -> NotificationContext {
+        NotificationContext {
+            message: NotificationMessage {
+                payload: MessagePayload { title: "New comment".to_string(), raw_body: None, html_body: None, attributes: None },
+                subject: NotificationSubject {
+                    api_version: "content.halo.run/v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: "hello".to_string(),
+                    title: "Hello".to_string(),
+                    url: None,
+                },
+                recipient: recipient.to_string(),
+                timestamp: chrono::Utc::now(),
+            },
+            receiver_config: None,
+            sender_config: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_by_name() {
+        let registry = NotifierRegistry::new();
+        let sms = Arc::new(RecordingSender::default());
+        registry.register("sms", sms.clone());
+        registry.register("dingtalk", Arc::new(RecordingSender::default()));
+        assert_eq!(registry.names(), vec!["sms", "dingtalk"]);
+
+        registry.send_notification("sms", context("admin")).await.unwrap();
+        assert_eq!(*sms.0.lock().unwrap(), vec!["sms:admin"]);
+
+        let err = registry.send_notification("wechat", context("admin")).await.unwrap_err();
+        assert!(err.to_string().starts_with(NOTIFIER_NOT_FOUND_ERROR));
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let registry = NotifierRegistry::new();
+        let first: Arc<dyn NotificationSender> = Arc::new(RecordingSender::default());
+        let second: Arc<dyn NotificationSender> = Arc::new(RecordingSender::default());
+        registry.register("sms", first);
+        registry.register("sms", second.clone());
+        assert_eq!(registry.names(), vec!["sms"]);
+        assert!(Arc::ptr_eq(&registry.get("sms").unwrap(), &second));
+        assert!(registry.unregister("sms"));
+        assert!(registry.get("sms").is_none());
+    }
+}
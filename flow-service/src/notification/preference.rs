@@ -0,0 +1,180 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::notification::{NotifierDescriptorService, NotifierRegistry};
+
+/// 通知偏好无效（选择了未注册的通知器）时的错误信息前缀
+pub const NOTIFICATION_PREFERENCE_INVALID_ERROR: &str = "Invalid notification preference";
+
+/// 用户偏好ConfigMap中保存通知偏好的键
+pub const NOTIFICATION_PREFERENCE_KEY: &str = "notification";
+
+/// 保存用户偏好的ConfigMap名称
+pub fn user_preference_config_map(username: &str) -> String {
+    format!("user-preferences-{}", username)
+}
+
+/// 用户的通知偏好：每种原因类型通过哪些通知器发送
+///
+/// 站内通知总是会创建，未设置的原因类型不通过其他通知器发送
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    /// 原因类型 -> 通知器设置
+    #[serde(default)]
+    pub reason_type_notifier: HashMap<String, NotifierSetting>,
+    /// 站外通知的发送频率，按小时或按天时合并为摘要发送
+    #[serde(default)]
+    pub digest: DigestFrequency,
+}
+
+/// 站外通知的发送频率
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    /// 每条通知立即发送
+    #[default]
+    Immediate,
+    /// 每小时发送一次摘要
+    Hourly,
+    /// 每天发送一次摘要
+    Daily,
+}
+
+impl DigestFrequency {
+    /// 摘要的合并窗口，立即发送时为None
+    pub fn window(self) -> Option<chrono::Duration> {
+        match self {
+            DigestFrequency::Immediate => None,
+            DigestFrequency::Hourly => Some(chrono::Duration::hours(1)),
+            DigestFrequency::Daily => Some(chrono::Duration::days(1)),
+        }
+    }
+}
+
+/// 原因类型的通知器设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotifierSetting {
+    /// 通知器扩展名称
+    #[serde(default)]
+    pub notifiers: Vec<String>,
+}
+
+impl NotificationPreference {
+    /// 原因类型选择的通知器
+    pub fn notifiers(&self, reason_type: &str) -> Vec<String> {
+        self.reason_type_notifier.get(reason_type)
+            .map(|setting| setting.notifiers.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 通知偏好服务trait
+#[async_trait]
+pub trait NotificationPreferenceService: Send + Sync {
+    /// 获取用户的通知偏好，未设置时返回默认偏好
+    async fn get_preference(&self, username: &str) -> Result<NotificationPreference>;
+
+    /// 保存用户的通知偏好，只能选择已注册的通知器或通知渠道
+    async fn update_preference(&self, username: &str, preference: NotificationPreference) -> Result<NotificationPreference>;
+}
+
+/// 默认通知偏好服务实现，偏好保存在用户偏好ConfigMap中
+pub struct DefaultNotificationPreferenceService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    notifier_registry: Arc<NotifierRegistry>,
+    descriptor_service: Option<Arc<dyn NotifierDescriptorService>>,
+}
+
+impl DefaultNotificationPreferenceService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>, notifier_registry: Arc<NotifierRegistry>) -> Self {
+        Self { extension_client, notifier_registry, descriptor_service: None }
+    }
+
+    /// 设置通知渠道描述服务，偏好中除通知器名称外也可以选择通知渠道
+    pub fn with_descriptors(mut self, descriptor_service: Arc<dyn NotifierDescriptorService>) -> Self {
+        self.descriptor_service = Some(descriptor_service);
+        self
+    }
+
+    /// 可选择的名称：已注册的通知器，以及通知器已注册的通知渠道
+    async fn selectable_notifiers(&self) -> Result<Vec<String>> {
+        let mut names = self.notifier_registry.names();
+        if let Some(descriptor_service) = &self.descriptor_service {
+            for descriptor in descriptor_service.list_descriptors().await? {
+                if self.notifier_registry.get(&descriptor.spec.notifier_ext_name).is_some() {
+                    names.push(descriptor.metadata.name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    async fn fetch_config_map(&self, username: &str) -> Result<Option<ConfigMap>> {
+        self.extension_client.fetch(&user_preference_config_map(username)).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch user preferences: {}", e))
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceService for DefaultNotificationPreferenceService {
+    async fn get_preference(&self, username: &str) -> Result<NotificationPreference> {
+        let preference = self.fetch_config_map(username).await?
+            .and_then(|config_map| config_map.data)
+            .and_then(|mut data| data.remove(NOTIFICATION_PREFERENCE_KEY));
+        match preference {
+            Some(preference) => Ok(serde_json::from_str(&preference)?),
+            None => Ok(NotificationPreference::default()),
+        }
+    }
+
+    async fn update_preference(&self, username: &str, preference: NotificationPreference) -> Result<NotificationPreference> {
+        let registered = self.selectable_notifiers().await?;
+        let mut unknown: Vec<&String> = preference.reason_type_notifier.values()
+            .flat_map(|setting| setting.notifiers.iter())
+            .filter(|name| !registered.contains(name))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort();
+            unknown.dedup();
+            let names: Vec<&str> = unknown.iter().map(|name| name.as_str()).collect();
+            anyhow::bail!("{}: unknown notifiers {}", NOTIFICATION_PREFERENCE_INVALID_ERROR, names.join(", "));
+        }
+
+        let value = serde_json::to_string(&preference)?;
+        match self.fetch_config_map(username).await? {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).insert(NOTIFICATION_PREFERENCE_KEY.to_string(), value);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(user_preference_config_map(username)),
+                data: Some(HashMap::from([(NOTIFICATION_PREFERENCE_KEY.to_string(), value)])),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save notification preference: {}", e))?;
+        Ok(preference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preference_notifiers() {
+        let preference: NotificationPreference = serde_json::from_str(
+            r#"{"reasonTypeNotifier": {"new-comment-on-post": {"notifiers": ["sms", "dingtalk"]}}}"#
+        ).unwrap();
+        assert_eq!(preference.notifiers("new-comment-on-post"), vec!["sms", "dingtalk"]);
+        assert!(preference.notifiers("new-reply-on-comment").is_empty());
+        assert!(NotificationPreference::default().notifiers("new-comment-on-post").is_empty());
+        assert_eq!(preference.digest, DigestFrequency::Immediate);
+        let preference: NotificationPreference = serde_json::from_str(r#"{"digest": "daily"}"#).unwrap();
+        assert_eq!(preference.digest.window(), Some(chrono::Duration::days(1)));
+    }
+}
@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use flow_api::extension::GroupVersionKind;
+use flow_domain::notification::Notification;
+use flow_infra::websocket::{WebSocketEndpoint, WebSocketMessage, WebSocketReceiver, WebSocketSender};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use crate::notification::NotificationService;
+
+/// 推送通道的容量，客户端处理不及时会丢失超出部分
+const PUSH_CHANNEL_CAPACITY: usize = 256;
+
+/// 推送给客户端的消息
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NotificationPushMessage {
+    /// 新的站内通知
+    #[serde(rename_all = "camelCase")]
+    Created { notification: Box<Notification> },
+    /// 未读数量变化
+    #[serde(rename_all = "camelCase")]
+    UnreadCount { count: u64 },
+}
+
+/// 站内通知变化的广播，通知服务发布，推送端点按接收者转发
+pub struct NotificationBroadcaster {
+    sender: broadcast::Sender<(String, NotificationPushMessage)>,
+}
+
+impl NotificationBroadcaster {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(PUSH_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// 发布发给接收者的消息
+    pub fn publish(&self, recipient: &str, message: NotificationPushMessage) {
+        // 没有连接的客户端时发送失败，忽略
+        let _ = self.sender.send((recipient.to_string(), message));
+    }
+
+    /// 订阅之后发布的所有消息
+    pub fn subscribe(&self) -> broadcast::Receiver<(String, NotificationPushMessage)> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for NotificationBroadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 实时推送站内通知的WebSocket端点
+///
+/// 路径为 `/apis/api.notification.halo.run/v1alpha1/notifications/-/stream`。连接后先发送当前未读数量，
+/// 之后当前用户每收到一条通知或未读数量变化时发送一条JSON消息（`type` 为 `created` 或 `unreadCount`）
+pub struct NotificationPushEndpoint {
+    broadcaster: Arc<NotificationBroadcaster>,
+    notification_service: Arc<dyn NotificationService>,
+}
+
+impl NotificationPushEndpoint {
+    pub fn new(broadcaster: Arc<NotificationBroadcaster>, notification_service: Arc<dyn NotificationService>) -> Self {
+        Self { broadcaster, notification_service }
+    }
+}
+
+async fn send_message(sender: &mut Box<dyn WebSocketSender>, message: &NotificationPushMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => sender.send(WebSocketMessage::Text(text)).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+#[async_trait]
+impl WebSocketEndpoint for NotificationPushEndpoint {
+    fn url_path(&self) -> &str {
+        "notifications/-/stream"
+    }
+
+    fn group_version(&self) -> GroupVersionKind {
+        GroupVersionKind::new("api.notification.halo.run", "v1alpha1", "Notification")
+    }
+
+    async fn handle_connection(
+        &self,
+        mut sender: Box<dyn WebSocketSender>,
+        _receiver: Box<dyn WebSocketReceiver>,
+    ) {
+        // 只推送给已认证用户
+        let _ = sender.send(WebSocketMessage::Close).await;
+    }
+
+    async fn handle_user_connection(
+        &self,
+        username: &str,
+        mut sender: Box<dyn WebSocketSender>,
+        mut receiver: Box<dyn WebSocketReceiver>,
+    ) {
+        // 先订阅再读取未读数量，避免两者之间的变化丢失
+        let mut live = self.broadcaster.subscribe();
+        match self.notification_service.get_unread_count(username).await {
+            Ok(count) => {
+                if !send_message(&mut sender, &NotificationPushMessage::UnreadCount { count }).await {
+                    return;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to get unread notification count of {}: {}", username, e),
+        }
+        loop {
+            tokio::select! {
+                message = receiver.recv() => match message {
+                    Some(Ok(WebSocketMessage::Ping(data))) => {
+                        if sender.send(WebSocketMessage::Pong(data)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(WebSocketMessage::Close)) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+                event = live.recv() => match event {
+                    Ok((recipient, message)) if recipient == username => {
+                        if !send_message(&mut sender, &message).await {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        // 丢失了消息，重新发送准确的未读数量
+                        tracing::debug!("Notification stream of {} skipped {} messages", username, skipped);
+                        if let Ok(count) = self.notification_service.get_unread_count(username).await {
+                            if !send_message(&mut sender, &NotificationPushMessage::UnreadCount { count }).await {
+                                break;
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_broadcast_messages() {
+        let broadcaster = NotificationBroadcaster::new();
+        broadcaster.publish("nobody", NotificationPushMessage::UnreadCount { count: 1 });
+        let mut live = broadcaster.subscribe();
+        broadcaster.publish("admin", NotificationPushMessage::UnreadCount { count: 3 });
+        let (recipient, message) = live.recv().await.unwrap();
+        assert_eq!(recipient, "admin");
+        assert_eq!(serde_json::to_value(&message).unwrap(), serde_json::json!({"type": "unreadCount", "count": 3}));
+    }
+}
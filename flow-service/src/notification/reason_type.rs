@@ -0,0 +1,213 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, ListOptions, Metadata};
+use flow_domain::notification::{NotificationTemplate, Reason, ReasonProperty, ReasonType, ReasonTypeSpec};
+use flow_infra::extension::ReactiveExtensionClient;
+use std::sync::Arc;
+use crate::content::comment_notification::{REASON_NEW_COMMENT_ON_POST, REASON_NEW_REPLY_ON_COMMENT};
+use crate::notification::default_subscription::{REASON_NEW_USER_REGISTERED, REASON_SYSTEM_FAILURE};
+use crate::notification::template::builtin_templates;
+
+/// 原因类型未注册时的错误信息前缀
+pub const REASON_TYPE_NOT_FOUND_ERROR: &str = "Reason type not found";
+
+/// 原因的属性与原因类型声明不符时的错误信息前缀
+pub const REASON_ATTRIBUTES_INVALID_ERROR: &str = "Invalid reason attributes";
+
+/// 按原因类型的属性声明校验原因的属性
+///
+/// 必需的属性必须存在，`number` 和 `boolean` 类型的属性值必须能解析为对应类型，不允许未声明的属性
+pub fn validate_attributes(reason_type: &ReasonType, reason: &Reason) -> Result<()> {
+    let name = &reason_type.metadata.name;
+    let attributes = reason.spec.attributes.as_ref();
+    let properties = &reason_type.spec.properties;
+    for property in properties {
+        let Some(value) = attributes.and_then(|attributes| attributes.get(&property.name)) else {
+            if property.optional {
+                continue;
+            }
+            anyhow::bail!("{}: {} requires attribute {}", REASON_ATTRIBUTES_INVALID_ERROR, name, property.name);
+        };
+        let valid = match property.property_type.as_str() {
+            "number" => value.parse::<f64>().is_ok(),
+            "boolean" => value.parse::<bool>().is_ok(),
+            _ => true,
+        };
+        if !valid {
+            anyhow::bail!(
+                "{}: attribute {} of {} must be a {}",
+                REASON_ATTRIBUTES_INVALID_ERROR, property.name, name, property.property_type
+            );
+        }
+    }
+    if let Some(unknown) = attributes.into_iter().flat_map(|attributes| attributes.keys())
+        .find(|key| !properties.iter().any(|property| property.name == **key)) {
+        anyhow::bail!("{}: {} does not declare attribute {}", REASON_ATTRIBUTES_INVALID_ERROR, name, unknown);
+    }
+    Ok(())
+}
+
+fn property(name: &str, property_type: &str, description: &str, optional: bool) -> ReasonProperty {
+    ReasonProperty {
+        name: name.to_string(),
+        property_type: property_type.to_string(),
+        description: Some(description.to_string()),
+        optional,
+    }
+}
+
+fn reason_type(name: &str, display_name: &str, description: &str, properties: Vec<ReasonProperty>) -> ReasonType {
+    ReasonType {
+        metadata: Metadata::new(name.to_string()),
+        spec: ReasonTypeSpec {
+            display_name: display_name.to_string(),
+            description: Some(description.to_string()),
+            properties,
+        },
+    }
+}
+
+/// 内置原因类型
+fn builtin_reason_types() -> Vec<ReasonType> {
+    let comment_properties = || vec![
+        property("commentName", "string", "Name of the comment", false),
+        property("commenter", "string", "Display name of the commenter", false),
+        property("content", "string", "Raw content of the comment", false),
+        property("subjectTitle", "string", "Title of the commented subject", false),
+    ];
+    vec![
+        reason_type(
+            REASON_NEW_COMMENT_ON_POST, "New comment on post",
+            "Someone commented on a post you wrote", comment_properties(),
+        ),
+        reason_type(
+            REASON_NEW_REPLY_ON_COMMENT, "New reply on comment",
+            "Someone replied to your comment", comment_properties(),
+        ),
+        reason_type(REASON_NEW_USER_REGISTERED, "New user registered", "A new user account was created", vec![
+            property("username", "string", "Username of the new user", false),
+            property("displayName", "string", "Display name of the new user", false),
+        ]),
+        reason_type(REASON_SYSTEM_FAILURE, "System failure", "A plugin failed its health checks", vec![
+            property("pluginId", "string", "ID of the failed plugin", false),
+            property("failures", "number", "Number of consecutive failed health checks", true),
+            property("message", "string", "Last health check error", true),
+        ]),
+    ]
+}
+
+/// 原因类型服务trait
+#[async_trait]
+pub trait ReasonTypeService: Send + Sync {
+    /// 列出所有原因类型，供通知偏好和模板编辑选择
+    async fn list_reason_types(&self) -> Result<Vec<ReasonType>>;
+
+    /// 获取原因类型
+    async fn get_reason_type(&self, name: &str) -> Result<Option<ReasonType>>;
+
+    /// 校验原因：原因类型必须已注册，属性必须符合声明
+    async fn validate(&self, reason: &Reason) -> Result<()>;
+}
+
+/// 默认原因类型服务实现
+pub struct DefaultReasonTypeService {
+    extension_client: Arc<ReactiveExtensionClient>,
+}
+
+impl DefaultReasonTypeService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client }
+    }
+
+    /// 保存内置原因类型（新评论、新回复、新用户注册、系统故障）及其模板
+    ///
+    /// 模板只在不存在时创建，保留管理员的修改
+    pub async fn install_builtin(&self) -> Result<()> {
+        for reason_type in builtin_reason_types() {
+            let name = reason_type.metadata.name.clone();
+            self.extension_client.update(reason_type).await
+                .map_err(|e| anyhow::anyhow!("Failed to save reason type {}: {}", name, e))?;
+        }
+        for template in builtin_templates() {
+            let name = template.metadata.name.clone();
+            let existing: Option<NotificationTemplate> = self.extension_client.fetch(&name).await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch notification template {}: {}", name, e))?;
+            if existing.is_none() {
+                self.extension_client.create(template).await
+                    .map_err(|e| anyhow::anyhow!("Failed to create notification template {}: {}", name, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReasonTypeService for DefaultReasonTypeService {
+    async fn list_reason_types(&self) -> Result<Vec<ReasonType>> {
+        self.extension_client.list_all::<ReasonType>(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list reason types: {}", e))
+    }
+
+    async fn get_reason_type(&self, name: &str) -> Result<Option<ReasonType>> {
+        self.extension_client.fetch(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch reason type {}: {}", name, e))
+    }
+
+    async fn validate(&self, reason: &Reason) -> Result<()> {
+        let reason_type = self.get_reason_type(&reason.spec.reason_type).await?
+            .ok_or_else(|| anyhow::anyhow!("{}: {}", REASON_TYPE_NOT_FOUND_ERROR, reason.spec.reason_type))?;
+        validate_attributes(&reason_type, reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_domain::notification::{ReasonSpec, ReasonSubject};
+    use std::collections::HashMap;
+
+    fn reason(attributes: &[(&str, &str)]) -> Reason {
+        Reason {
+            metadata: Metadata::new("reason".to_string()),
+            spec: ReasonSpec {
+                reason_type: REASON_SYSTEM_FAILURE.to_string(),
+                subject: ReasonSubject {
+                    api_version: "plugin.halo.run/v1alpha1".to_string(),
+                    kind: "Plugin".to_string(),
+                    name: "search".to_string(),
+                    title: "search".to_string(),
+                    url: None,
+                },
+                author: "system".to_string(),
+                attributes: Some(attributes.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>()),
+            },
+        }
+    }
+
+    fn system_failure() -> ReasonType {
+        builtin_reason_types().into_iter().find(|t| t.metadata.name == REASON_SYSTEM_FAILURE).unwrap()
+    }
+
+    #[test]
+    fn test_validate_attributes() {
+        let reason_type = system_failure();
+        assert!(validate_attributes(&reason_type, &reason(&[("pluginId", "search")])).is_ok());
+        assert!(validate_attributes(&reason_type, &reason(&[("pluginId", "search"), ("failures", "3")])).is_ok());
+        for attributes in [&[("failures", "3")][..], &[("pluginId", "search"), ("failures", "many")], &[("pluginId", "search"), ("extra", "x")]] {
+            let error = validate_attributes(&reason_type, &reason(attributes)).unwrap_err();
+            assert!(error.to_string().starts_with(REASON_ATTRIBUTES_INVALID_ERROR), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_builtin_reason_types_json() {
+        let json = serde_json::to_value(system_failure()).unwrap();
+        assert_eq!(json["spec"]["displayName"], "System failure");
+        assert_eq!(json["spec"]["properties"][1], serde_json::json!({
+            "name": "failures",
+            "type": "number",
+            "description": "Number of consecutive failed health checks",
+            "optional": true,
+        }));
+    }
+}
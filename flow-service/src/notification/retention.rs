@@ -0,0 +1,200 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flow_api::extension::{scan_all_pages, ListOptions};
+use flow_domain::notification::Notification;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::notification::NotificationService;
+
+/// 站内通知保留配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRetentionConfig {
+    /// 已读通知保留的天数，0表示不按时间清理
+    #[serde(default = "default_read_retention_days")]
+    pub read_retention_days: u64,
+    /// 每个用户最多保留的通知数量，超出时删除最早的通知，0表示不限制
+    #[serde(default = "default_max_per_user")]
+    pub max_per_user: usize,
+    /// 清理间隔（秒）
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_read_retention_days() -> u64 {
+    30
+}
+
+fn default_max_per_user() -> usize {
+    1000
+}
+
+fn default_interval() -> u64 {
+    3600
+}
+
+impl Default for NotificationRetentionConfig {
+    fn default() -> Self {
+        Self {
+            read_retention_days: default_read_retention_days(),
+            max_per_user: default_max_per_user(),
+            interval: default_interval(),
+        }
+    }
+}
+
+/// 按保留配置选出需要删除的通知名称
+///
+/// 已读时间（没有时为创建时间）早于保留期的已读通知过期；每个用户超出数量上限的部分按创建时间从早到晚删除
+pub fn select_expired(notifications: &[Notification], config: &NotificationRetentionConfig, now: DateTime<Utc>) -> Vec<String> {
+    let created = |notification: &Notification| notification.metadata.creation_timestamp.unwrap_or(now);
+    let mut expired = Vec::new();
+    let mut kept: HashMap<&str, Vec<&Notification>> = HashMap::new();
+    let cutoff = (config.read_retention_days > 0)
+        .then(|| now - chrono::Duration::days(config.read_retention_days as i64));
+    for notification in notifications {
+        let read = !notification.spec.unread.unwrap_or(true);
+        let read_at = notification.spec.last_read_at.unwrap_or_else(|| created(notification));
+        if read && cutoff.is_some_and(|cutoff| read_at < cutoff) {
+            expired.push(notification.metadata.name.clone());
+        } else {
+            kept.entry(notification.spec.recipient.as_str()).or_default().push(notification);
+        }
+    }
+    if config.max_per_user > 0 {
+        for mut notifications in kept.into_values() {
+            if notifications.len() > config.max_per_user {
+                notifications.sort_by_key(|notification| created(notification));
+                let excess = notifications.len() - config.max_per_user;
+                expired.extend(notifications.into_iter().take(excess).map(|n| n.metadata.name.clone()));
+            }
+        }
+    }
+    expired
+}
+
+/// 站内通知清理服务trait
+#[async_trait]
+pub trait NotificationRetentionService: Send + Sync {
+    /// 删除过期和超出数量上限的通知，返回删除的数量
+    async fn cleanup(&self) -> Result<usize>;
+}
+
+/// 默认站内通知清理服务实现
+pub struct DefaultNotificationRetentionService {
+    notification_service: Arc<dyn NotificationService>,
+    config: NotificationRetentionConfig,
+}
+
+impl DefaultNotificationRetentionService {
+    pub fn new(notification_service: Arc<dyn NotificationService>, config: NotificationRetentionConfig) -> Self {
+        Self { notification_service, config }
+    }
+}
+
+#[async_trait]
+impl NotificationRetentionService for DefaultNotificationRetentionService {
+    async fn cleanup(&self) -> Result<usize> {
+        let notifications = scan_all_pages(ListOptions::default(), |options| async move {
+            self.notification_service.list(options).await.map(|result| result.items)
+        }).await?;
+        let expired = select_expired(&notifications, &self.config, Utc::now());
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        self.notification_service.delete_all(&expired).await
+    }
+}
+
+/// 启动后台通知清理任务
+pub fn spawn_notification_cleanup_job(
+    service: Arc<dyn NotificationRetentionService>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match service.cleanup().await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} expired notifications", deleted),
+                Err(e) => tracing::warn!("Notification cleanup failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::notification::NotificationSpec;
+
+    fn notification(name: &str, recipient: &str, age_days: i64, read_days_ago: Option<i64>, now: DateTime<Utc>) -> Notification {
+        let mut metadata = Metadata::new(name.to_string());
+        metadata.creation_timestamp = Some(now - chrono::Duration::days(age_days));
+        Notification {
+            metadata,
+            spec: NotificationSpec {
+                recipient: recipient.to_string(),
+                reason: "reason".to_string(),
+                title: name.to_string(),
+                raw_content: String::new(),
+                html_content: String::new(),
+                unread: Some(read_days_ago.is_none()),
+                last_read_at: read_days_ago.map(|days| now - chrono::Duration::days(days)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_expire_read_notifications() {
+        let now = Utc::now();
+        let notifications = vec![
+            notification("old-read", "admin", 60, Some(40), now),
+            notification("recent-read", "admin", 60, Some(5), now),
+            notification("old-unread", "admin", 60, None, now),
+        ];
+        let config = NotificationRetentionConfig { max_per_user: 0, ..Default::default() };
+        assert_eq!(select_expired(&notifications, &config, now), vec!["old-read"]);
+        let config = NotificationRetentionConfig { read_retention_days: 0, max_per_user: 0, ..Default::default() };
+        assert!(select_expired(&notifications, &config, now).is_empty());
+    }
+
+    #[test]
+    fn test_cap_per_user() {
+        let now = Utc::now();
+        let notifications = vec![
+            notification("a1", "alice", 3, None, now),
+            notification("a2", "alice", 1, None, now),
+            notification("a3", "alice", 2, None, now),
+            notification("b1", "bob", 5, None, now),
+        ];
+        let config = NotificationRetentionConfig { read_retention_days: 0, max_per_user: 2, ..Default::default() };
+        assert_eq!(select_expired(&notifications, &config, now), vec!["a1"]);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_scans_all_pages() {
+        use crate::notification::DefaultNotificationService;
+        use flow_api::extension::ExtensionClient;
+        use flow_infra::extension::ReactiveExtensionClient;
+
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        let now = Utc::now();
+        // 超过默认分页大小的已读通知全部清理
+        for i in 0..15 {
+            client.create(notification(&format!("n{}", i), "admin", 60, Some(40), now)).await.unwrap();
+        }
+        let service = DefaultNotificationRetentionService::new(
+            Arc::new(DefaultNotificationService::new(client.clone())),
+            NotificationRetentionConfig::default(),
+        );
+
+        assert_eq!(service.cleanup().await.unwrap(), 15);
+        assert!(client.list::<Notification>(ListOptions::default()).await.unwrap().items.is_empty());
+    }
+}
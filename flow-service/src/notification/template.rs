@@ -0,0 +1,283 @@
+use anyhow::Result;
+use flow_api::extension::Metadata;
+use flow_domain::notification::{NotificationTemplate, NotificationTemplateSpec, Reason, ReasonSelector, TemplateContent};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use crate::content::comment_notification::{REASON_NEW_COMMENT_ON_POST, REASON_NEW_REPLY_ON_COMMENT};
+use crate::notification::default_subscription::{REASON_NEW_USER_REGISTERED, REASON_SYSTEM_FAILURE};
+
+/// 未指定语言的模板使用的语言
+pub const DEFAULT_TEMPLATE_LANGUAGE: &str = "default";
+
+/// 渲染后的通知内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedNotification {
+    pub title: String,
+    pub raw_body: String,
+    pub html_body: String,
+}
+
+/// 语言标签的主语言（`zh-CN`、`zh_CN` 的主语言为 `zh`），不区分大小写
+fn primary_language(language: &str) -> String {
+    language.split(['-', '_']).next().unwrap_or_default().to_lowercase()
+}
+
+/// 选择原因类型的模板：优先使用语言完全匹配的模板，其次为主语言相同的模板，最后为默认语言，同等条件下选择最新创建的
+pub fn select_template(templates: Vec<NotificationTemplate>, language: &str) -> Option<NotificationTemplate> {
+    let normalized = language.replace('_', "-");
+    let rank = |template: &NotificationTemplate| {
+        let template_language = template.spec.as_ref()
+            .and_then(|spec| spec.reason_selector.as_ref())
+            .map(|selector| selector.language.as_str())?;
+        if template_language == DEFAULT_TEMPLATE_LANGUAGE {
+            Some(2)
+        } else if template_language.replace('_', "-").eq_ignore_ascii_case(&normalized) {
+            Some(0)
+        } else if primary_language(template_language) == primary_language(language) {
+            Some(1)
+        } else {
+            None
+        }
+    };
+    let created = |template: &NotificationTemplate| {
+        template.metadata.creation_timestamp.as_ref().map(|ts| ts.timestamp()).unwrap_or(0)
+    };
+    templates.into_iter()
+        .filter_map(|template| rank(&template).map(|rank| (rank, template)))
+        .min_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| created(b).cmp(&created(a))))
+        .map(|(_, template)| template)
+}
+
+fn builtin_template(reason_type: &str, language: &str, title: &str, raw_body: &str, html_body: &str) -> NotificationTemplate {
+    NotificationTemplate {
+        metadata: Metadata::new(format!("template-{}-{}", reason_type, language.to_lowercase())),
+        spec: Some(NotificationTemplateSpec {
+            reason_selector: Some(ReasonSelector { reason_type: reason_type.to_string(), language: language.to_string() }),
+            template: Some(TemplateContent {
+                title: title.to_string(),
+                raw_body: Some(raw_body.to_string()),
+                html_body: Some(html_body.to_string()),
+            }),
+        }),
+    }
+}
+
+/// 内置原因类型的模板，包括默认（英文）和简体中文
+pub fn builtin_templates() -> Vec<NotificationTemplate> {
+    vec![
+        builtin_template(
+            REASON_NEW_COMMENT_ON_POST, DEFAULT_TEMPLATE_LANGUAGE,
+            "{{ props.commenter }} commented on \"{{ props.subjectTitle }}\"",
+            "{{ props.commenter }} commented on \"{{ props.subjectTitle }}\": {{ props.content }}",
+            "<p>{{ props.commenter }} commented on <a href=\"{{ subject.url }}\">{{ props.subjectTitle }}</a>:</p><p>{{ props.content }}</p>",
+        ),
+        builtin_template(
+            REASON_NEW_COMMENT_ON_POST, "zh-CN",
+            "{{ props.commenter }} 评论了《{{ props.subjectTitle }}》",
+            "{{ props.commenter }} 评论了《{{ props.subjectTitle }}》：{{ props.content }}",
+            "<p>{{ props.commenter }} 评论了<a href=\"{{ subject.url }}\">《{{ props.subjectTitle }}》</a>：</p><p>{{ props.content }}</p>",
+        ),
+        builtin_template(
+            REASON_NEW_REPLY_ON_COMMENT, DEFAULT_TEMPLATE_LANGUAGE,
+            "{{ props.commenter }} replied to your comment",
+            "{{ props.commenter }} replied to your comment \"{{ props.subjectTitle }}\": {{ props.content }}",
+            "<p>{{ props.commenter }} replied to your comment \"{{ props.subjectTitle }}\":</p><p>{{ props.content }}</p>",
+        ),
+        builtin_template(
+            REASON_NEW_REPLY_ON_COMMENT, "zh-CN",
+            "{{ props.commenter }} 回复了你的评论",
+            "{{ props.commenter }} 回复了你的评论“{{ props.subjectTitle }}”：{{ props.content }}",
+            "<p>{{ props.commenter }} 回复了你的评论“{{ props.subjectTitle }}”：</p><p>{{ props.content }}</p>",
+        ),
+        builtin_template(
+            REASON_NEW_USER_REGISTERED, DEFAULT_TEMPLATE_LANGUAGE,
+            "New user {{ props.displayName }} registered",
+            "A new user {{ props.displayName }} ({{ props.username }}) has registered.",
+            "<p>A new user <strong>{{ props.displayName }}</strong> ({{ props.username }}) has registered.</p>",
+        ),
+        builtin_template(
+            REASON_NEW_USER_REGISTERED, "zh-CN",
+            "新用户 {{ props.displayName }} 已注册",
+            "新用户 {{ props.displayName }}（{{ props.username }}）已注册。",
+            "<p>新用户 <strong>{{ props.displayName }}</strong>（{{ props.username }}）已注册。</p>",
+        ),
+        builtin_template(
+            REASON_SYSTEM_FAILURE, DEFAULT_TEMPLATE_LANGUAGE,
+            "Plugin {{ props.pluginId }} failed",
+            "Plugin {{ props.pluginId }} failed its health checks and was stopped. {{ props.message | default(value='') }}",
+            "<p>Plugin <strong>{{ props.pluginId }}</strong> failed its health checks and was stopped.</p><p>{{ props.message | default(value='') }}</p>",
+        ),
+        builtin_template(
+            REASON_SYSTEM_FAILURE, "zh-CN",
+            "插件 {{ props.pluginId }} 已失败",
+            "插件 {{ props.pluginId }} 健康检查连续失败，已被停止。{{ props.message | default(value='') }}",
+            "<p>插件 <strong>{{ props.pluginId }}</strong> 健康检查连续失败，已被停止。</p><p>{{ props.message | default(value='') }}</p>",
+        ),
+    ]
+}
+
+/// 把站内路径补全为绝对地址，已是绝对地址或未配置外部访问地址时原样返回
+pub fn absolute_url(path: &str, external_url: Option<&str>) -> String {
+    match external_url {
+        Some(base) if path.starts_with('/') && !path.starts_with("//") => {
+            format!("{}{}", base.trim_end_matches('/'), path)
+        }
+        _ => path.to_string(),
+    }
+}
+
+fn escape_html(input: &str) -> String {
+    input.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#x27;")
+}
+
+/// 模板变量
+///
+/// - `props`：原因的属性
+/// - `subject`：原因主题（apiVersion、kind、name、title、url）
+/// - `author`：触发原因的用户
+/// - `subscriber`：接收通知的订阅者（name）
+/// - `reasonType`：原因类型
+/// - `site`：站点信息（url）
+pub fn template_variables(reason: &Reason, subscriber: &str, external_url: Option<&str>) -> Value {
+    let subject = &reason.spec.subject;
+    let props: Map<String, Value> = reason.spec.attributes.iter()
+        .flat_map(|attributes| attributes.iter())
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect();
+    json!({
+        "props": props,
+        "subject": {
+            "apiVersion": subject.api_version,
+            "kind": subject.kind,
+            "name": subject.name,
+            "title": subject.title,
+            "url": subject.url.as_deref().map(|url| absolute_url(url, external_url)),
+        },
+        "author": reason.spec.author,
+        "subscriber": { "name": subscriber },
+        "reasonType": reason.spec.reason_type,
+        "site": { "url": external_url },
+    })
+}
+
+/// 用Tera渲染模板内容，HTML正文自动转义变量，标题和纯文本正文不转义
+///
+/// 模板中可以使用 `absolute_url` 过滤器把站内路径补全为绝对地址
+pub fn render_template(content: &TemplateContent, variables: &Value, external_url: Option<&str>) -> Result<RenderedNotification> {
+    let mut tera = tera::Tera::default();
+    // Tera默认还会转义 `/`，链接地址中不需要
+    tera.set_escape_fn(escape_html);
+    let base = external_url.map(str::to_string);
+    tera.register_filter("absolute_url", move |value: &Value, _: &HashMap<String, Value>| {
+        let path = tera::try_get_value!("absolute_url", "value", String, value);
+        Ok(Value::String(absolute_url(&path, base.as_deref())))
+    });
+    tera.add_raw_templates(vec![
+        ("title.txt", content.title.as_str()),
+        ("raw.txt", content.raw_body.as_deref().unwrap_or_default()),
+        ("html.html", content.html_body.as_deref().unwrap_or_default()),
+    ]).map_err(|e| anyhow::anyhow!("Invalid notification template: {}", e))?;
+    let context = tera::Context::from_value(variables.clone())
+        .map_err(|e| anyhow::anyhow!("Invalid notification template variables: {}", e))?;
+    let render = |name: &str| tera.render(name, &context)
+        .map_err(|e| anyhow::anyhow!("Failed to render notification template {}: {:?}", name, e));
+    Ok(RenderedNotification {
+        title: render("title.txt")?.trim().to_string(),
+        raw_body: render("raw.txt")?,
+        html_body: render("html.html")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_domain::notification::{ReasonSpec, ReasonSubject};
+
+    fn template(name: &str, language: &str, created_secs: i64) -> NotificationTemplate {
+        let mut metadata = Metadata::new(name.to_string());
+        metadata.creation_timestamp = chrono::DateTime::from_timestamp(created_secs, 0);
+        NotificationTemplate {
+            metadata,
+            spec: Some(NotificationTemplateSpec {
+                reason_selector: Some(ReasonSelector { reason_type: "new-comment-on-post".to_string(), language: language.to_string() }),
+                template: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_select_template() {
+        let templates = vec![template("old-default", "default", 1), template("zh", "zh-CN", 2), template("new-default", "default", 3)];
+        assert_eq!(select_template(templates.clone(), "zh-CN").unwrap().metadata.name, "zh");
+        assert_eq!(select_template(templates.clone(), "en").unwrap().metadata.name, "new-default");
+        assert!(select_template(vec![template("zh", "zh-CN", 2)], "en").is_none());
+    }
+
+    #[test]
+    fn test_select_template_normalizes_language() {
+        let templates = vec![template("default", "default", 1), template("zh", "zh-CN", 2)];
+        assert_eq!(select_template(templates.clone(), "zh_cn").unwrap().metadata.name, "zh");
+        assert_eq!(select_template(templates, "zh-TW").unwrap().metadata.name, "zh");
+    }
+
+    #[test]
+    fn test_render_autoescapes_html() {
+        let reason = |reason_type: &str, attributes: &[&str]| Reason {
+            metadata: Metadata::new("reason".to_string()),
+            spec: ReasonSpec {
+                reason_type: reason_type.to_string(),
+                subject: ReasonSubject {
+                    api_version: "v1alpha1".to_string(),
+                    kind: "User".to_string(),
+                    name: "ryan".to_string(),
+                    title: "Ryan".to_string(),
+                    url: None,
+                },
+                author: "ryan".to_string(),
+                attributes: Some(attributes.iter().map(|key| (key.to_string(), "<b>x</b>".to_string())).collect()),
+            },
+        };
+        let comment = ["commentName", "commenter", "content", "subjectTitle"];
+        for builtin in builtin_templates() {
+            let spec = builtin.spec.unwrap();
+            let reason = match spec.reason_selector.unwrap().reason_type.as_str() {
+                REASON_NEW_USER_REGISTERED => reason(REASON_NEW_USER_REGISTERED, &["username", "displayName"]),
+                REASON_SYSTEM_FAILURE => reason(REASON_SYSTEM_FAILURE, &["pluginId"]),
+                reason_type => reason(reason_type, &comment),
+            };
+            let variables = template_variables(&reason, "admin", None);
+            let rendered = render_template(&spec.template.unwrap(), &variables, None).unwrap();
+            assert!(rendered.html_body.contains("&lt;b&gt;x"), "{}", builtin.metadata.name);
+            assert!(!rendered.html_body.contains("<b>"), "{}", builtin.metadata.name);
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let reason = Reason {
+            metadata: Metadata::new("reason".to_string()),
+            spec: ReasonSpec {
+                reason_type: "new-comment-on-post".to_string(),
+                subject: ReasonSubject {
+                    api_version: "content.halo.run/v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: "hello".to_string(),
+                    title: "Hello <World>".to_string(),
+                    url: Some("/archives/hello".to_string()),
+                },
+                author: "guqing".to_string(),
+                attributes: Some(HashMap::from([("commenter".to_string(), "Ryan".to_string())])),
+            },
+        };
+        let content = TemplateContent {
+            title: "{{ props.commenter }} commented on {{ subject.title }}".to_string(),
+            raw_body: Some("Hi {{ subscriber.name }}, see {{ subject.url }}".to_string()),
+            html_body: Some("<a href=\"{{ '/console' | absolute_url }}\">{{ subject.title }}</a>".to_string()),
+        };
+        let variables = template_variables(&reason, "admin", Some("https://example.com/"));
+        let rendered = render_template(&content, &variables, Some("https://example.com/")).unwrap();
+        assert_eq!(rendered.title, "Ryan commented on Hello <World>");
+        assert_eq!(rendered.raw_body, "Hi admin, see https://example.com/archives/hello");
+        assert_eq!(rendered.html_body, "<a href=\"https://example.com/console\">Hello &lt;World&gt;</a>");
+    }
+}
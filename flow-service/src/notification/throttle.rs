@@ -0,0 +1,169 @@
+use anyhow::Result;
+use flow_domain::notification::Reason;
+use flow_infra::cache::Cache;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// 同一接收者、原因类型和主题的通知计数键前缀
+const COUNT_KEY_PREFIX: &str = "notification:throttle:count:";
+
+/// 窗口内第一条站内通知名称的键前缀，后续通知合并到这条通知中
+const FIRST_KEY_PREFIX: &str = "notification:throttle:first:";
+
+/// 通知去重和限流配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationThrottleConfig {
+    /// 合并窗口（秒），窗口内同一接收者、原因类型和主题的通知合并为一条，0表示不合并
+    #[serde(default = "default_window")]
+    pub window: u64,
+}
+
+fn default_window() -> u64 {
+    600
+}
+
+impl Default for NotificationThrottleConfig {
+    fn default() -> Self {
+        Self { window: default_window() }
+    }
+}
+
+/// 限流的判断结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThrottleDecision {
+    /// 窗口内的第一条通知，正常创建和发送
+    First,
+    /// 窗口内的重复通知：合并到窗口内的第一条站内通知（`count` 为窗口内的通知总数），不再发送站外通知
+    Repeat { count: u64, first: Option<String> },
+}
+
+/// 接收者、原因类型和主题组成的限流键
+pub fn throttle_key(recipient: &str, reason: &Reason) -> String {
+    let subject = &reason.spec.subject;
+    format!("{}:{}:{}/{}:{}", recipient, reason.spec.reason_type, subject.api_version, subject.kind, subject.name)
+}
+
+/// 合并后的通知标题
+pub fn aggregated_title(title: &str, count: u64) -> String {
+    if count <= 1 {
+        title.to_string()
+    } else {
+        format!("{} (+{})", title, count - 1)
+    }
+}
+
+/// 通知去重和限流，计数保存在Redis中，多个实例共享
+pub struct NotificationThrottle {
+    cache: Arc<dyn Cache>,
+    config: NotificationThrottleConfig,
+}
+
+impl NotificationThrottle {
+    pub fn new(cache: Arc<dyn Cache>, config: NotificationThrottleConfig) -> Self {
+        Self { cache, config }
+    }
+
+    /// 记录一条通知并判断是否为窗口内的重复通知
+    pub async fn check(&self, recipient: &str, reason: &Reason) -> Result<ThrottleDecision> {
+        if self.config.window == 0 {
+            return Ok(ThrottleDecision::First);
+        }
+        let key = throttle_key(recipient, reason);
+        let count = self.cache.incr(&format!("{}{}", COUNT_KEY_PREFIX, key), Some(self.config.window)).await
+            .map_err(|e| anyhow::anyhow!("Failed to count notifications: {}", e))?;
+        if count <= 1 {
+            return Ok(ThrottleDecision::First);
+        }
+        let first = self.cache.get(&format!("{}{}", FIRST_KEY_PREFIX, key)).await
+            .map_err(|e| anyhow::anyhow!("Failed to get throttled notification: {}", e))?;
+        Ok(ThrottleDecision::Repeat { count: count as u64, first })
+    }
+
+    /// 记录窗口内承载合并内容的站内通知
+    pub async fn remember(&self, recipient: &str, reason: &Reason, notification_name: &str) -> Result<()> {
+        if self.config.window == 0 {
+            return Ok(());
+        }
+        let key = format!("{}{}", FIRST_KEY_PREFIX, throttle_key(recipient, reason));
+        self.cache.set(&key, notification_name, Some(self.config.window)).await
+            .map_err(|e| anyhow::anyhow!("Failed to remember throttled notification: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::notification::{ReasonSpec, ReasonSubject};
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, String>>);
+
+    #[async_trait::async_trait]
+    impl Cache for MemoryCache {
+        async fn get(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.0.lock().await.get(key).cloned())
+        }
+
+        async fn set(&self, key: &str, value: &str, _ttl: Option<u64>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.lock().await.insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.0.lock().await.remove(key);
+            Ok(())
+        }
+
+        async fn incr(&self, key: &str, _ttl: Option<u64>) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+            let mut data = self.0.lock().await;
+            let count = data.get(key).and_then(|value| value.parse::<i64>().ok()).unwrap_or(0) + 1;
+            data.insert(key.to_string(), count.to_string());
+            Ok(count)
+        }
+    }
+
+    fn reason(subject_name: &str) -> Reason {
+        Reason {
+            metadata: Metadata::new("reason".to_string()),
+            spec: ReasonSpec {
+                reason_type: "new-comment-on-post".to_string(),
+                subject: ReasonSubject {
+                    api_version: "content.halo.run/v1alpha1".to_string(),
+                    kind: "Post".to_string(),
+                    name: subject_name.to_string(),
+                    title: "Hello".to_string(),
+                    url: None,
+                },
+                author: "guqing".to_string(),
+                attributes: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collapse_repeated_notifications() {
+        let throttle = NotificationThrottle::new(Arc::new(MemoryCache::default()), NotificationThrottleConfig::default());
+        assert_eq!(throttle.check("admin", &reason("hello")).await.unwrap(), ThrottleDecision::First);
+        throttle.remember("admin", &reason("hello"), "n1").await.unwrap();
+        assert_eq!(
+            throttle.check("admin", &reason("hello")).await.unwrap(),
+            ThrottleDecision::Repeat { count: 2, first: Some("n1".to_string()) }
+        );
+        assert_eq!(throttle.check("admin", &reason("other")).await.unwrap(), ThrottleDecision::First);
+        assert_eq!(throttle.check("guqing", &reason("hello")).await.unwrap(), ThrottleDecision::First);
+
+        let disabled = NotificationThrottle::new(Arc::new(MemoryCache::default()), NotificationThrottleConfig { window: 0 });
+        disabled.check("admin", &reason("hello")).await.unwrap();
+        assert_eq!(disabled.check("admin", &reason("hello")).await.unwrap(), ThrottleDecision::First);
+    }
+
+    #[test]
+    fn test_aggregated_title() {
+        assert_eq!(aggregated_title("Ryan commented on Hello", 1), "Ryan commented on Hello");
+        assert_eq!(aggregated_title("Ryan commented on Hello", 20), "Ryan commented on Hello (+19)");
+    }
+}
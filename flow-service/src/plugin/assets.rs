@@ -0,0 +1,176 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_plugin::{PluginManager, PluginState};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// 插件目录中存放前端资源（控制台扩展的入口脚本、样式表、Logo等）的子目录
+pub const PLUGIN_ASSETS_DIR: &str = "assets";
+
+/// 插件前端资源的访问地址，带上版本参数，插件升级后浏览器会重新获取
+pub fn asset_url(plugin_id: &str, path: &str, version: &str) -> String {
+    format!("/plugins/{}/assets/{}?version={}", plugin_id, path.trim_start_matches('/'), version)
+}
+
+/// 控制台扩展的入口脚本，位于资源目录中
+pub const CONSOLE_ENTRY: &str = "console/main.js";
+
+/// 控制台扩展的样式表，位于资源目录中
+pub const CONSOLE_STYLESHEET: &str = "console/style.css";
+
+/// 插件没有声明Logo时依次查找的资源文件
+const LOGO_CANDIDATES: [&str; 2] = ["logo.svg", "logo.png"];
+
+/// 插件状态中记录的前端资源访问地址
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PluginAssetUrls {
+    pub entry: Option<String>,
+    pub stylesheet: Option<String>,
+    pub logo: Option<String>,
+}
+
+/// 查找目录插件资源目录中的入口脚本、样式表和Logo，资源目录中不存在的文件为None
+///
+/// `logo`为插件规格中声明的Logo，是资源目录中的文件时转换为访问地址，否则原样保留
+pub fn asset_urls(plugin_id: &str, plugin_path: &Path, version: &str, logo: Option<&str>) -> PluginAssetUrls {
+    let assets_root = plugin_path.join(PLUGIN_ASSETS_DIR);
+    let url = |path: &str| relative_asset_path(path)
+        .filter(|relative| assets_root.join(relative).is_file())
+        .map(|_| asset_url(plugin_id, path, version));
+    let logo = match logo {
+        Some(logo) => url(logo).or_else(|| Some(logo.to_string())),
+        None => LOGO_CANDIDATES.iter().find_map(|candidate| url(candidate)),
+    };
+    PluginAssetUrls {
+        entry: url(CONSOLE_ENTRY),
+        stylesheet: url(CONSOLE_STYLESHEET),
+        logo,
+    }
+}
+
+/// 校验请求的资源路径，只允许普通路径段，防止访问资源目录以外的文件
+pub fn relative_asset_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    Some(path.to_path_buf())
+}
+
+/// 插件前端资源
+#[derive(Debug, Clone)]
+pub struct PluginAsset {
+    pub content: Vec<u8>,
+    pub media_type: String,
+    /// 根据文件大小和修改时间生成的弱ETag
+    pub etag: String,
+    /// 插件当前版本，请求带有相同版本参数时资源可以长期缓存
+    pub version: String,
+}
+
+/// 插件前端资源服务trait
+#[async_trait]
+pub trait PluginAssetService: Send + Sync {
+    /// 读取已启动插件的前端资源，插件未启动或资源不存在时返回None
+    async fn get_asset(&self, plugin_id: &str, path: &str) -> Result<Option<PluginAsset>>;
+}
+
+/// 默认插件前端资源服务实现，从目录插件的 `assets` 子目录读取资源
+pub struct DefaultPluginAssetService {
+    plugin_manager: Arc<dyn PluginManager>,
+}
+
+impl DefaultPluginAssetService {
+    pub fn new(plugin_manager: Arc<dyn PluginManager>) -> Self {
+        Self { plugin_manager }
+    }
+}
+
+#[async_trait]
+impl PluginAssetService for DefaultPluginAssetService {
+    async fn get_asset(&self, plugin_id: &str, path: &str) -> Result<Option<PluginAsset>> {
+        let Some(relative) = relative_asset_path(path) else {
+            return Ok(None);
+        };
+        let Some(wrapper) = self.plugin_manager.get_plugin(plugin_id).await else {
+            return Ok(None);
+        };
+        if wrapper.state != PluginState::Started {
+            return Ok(None);
+        }
+        // 单文件插件没有前端资源
+        let plugin_path = Path::new(&wrapper.plugin_path);
+        if !plugin_path.is_dir() {
+            return Ok(None);
+        }
+
+        // 符号链接解析后仍需位于资源目录内
+        let assets_root = plugin_path.join(PLUGIN_ASSETS_DIR);
+        let (Ok(root), Ok(file)) = (
+            tokio::fs::canonicalize(&assets_root).await,
+            tokio::fs::canonicalize(assets_root.join(&relative)).await,
+        ) else {
+            return Ok(None);
+        };
+        if !file.starts_with(&root) {
+            return Ok(None);
+        }
+        let metadata = tokio::fs::metadata(&file).await?;
+        if !metadata.is_file() {
+            return Ok(None);
+        }
+
+        let modified = metadata.modified().ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let media_type = mime_guess::from_path(&file).first_or_octet_stream().to_string();
+        Ok(Some(PluginAsset {
+            content: tokio::fs::read(&file).await?,
+            media_type,
+            etag: format!("W/\"{:x}-{:x}\"", metadata.len(), modified),
+            version: wrapper.version().to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_asset_path() {
+        assert_eq!(relative_asset_path("console/main.js"), Some(PathBuf::from("console/main.js")));
+        assert_eq!(relative_asset_path("logo.png"), Some(PathBuf::from("logo.png")));
+        assert_eq!(relative_asset_path("../plugin.yaml"), None);
+        assert_eq!(relative_asset_path("console/../../plugin.yaml"), None);
+        assert_eq!(relative_asset_path("/etc/passwd"), None);
+        assert_eq!(relative_asset_path(""), None);
+    }
+
+    #[test]
+    fn test_asset_url() {
+        assert_eq!(asset_url("search", "console/main.js", "1.2.0"), "/plugins/search/assets/console/main.js?version=1.2.0");
+        assert_eq!(asset_url("search", "/logo.png", "1.2.0"), "/plugins/search/assets/logo.png?version=1.2.0");
+    }
+
+    #[test]
+    fn test_asset_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let assets = dir.path().join(PLUGIN_ASSETS_DIR);
+        std::fs::create_dir_all(assets.join("console")).unwrap();
+        std::fs::write(assets.join(CONSOLE_ENTRY), "export default {}").unwrap();
+        std::fs::write(assets.join("logo.png"), b"png").unwrap();
+
+        let urls = asset_urls("search", dir.path(), "1.2.0", None);
+        assert_eq!(urls.entry.as_deref(), Some("/plugins/search/assets/console/main.js?version=1.2.0"));
+        assert_eq!(urls.stylesheet, None);
+        assert_eq!(urls.logo.as_deref(), Some("/plugins/search/assets/logo.png?version=1.2.0"));
+
+        // 声明的Logo不在资源目录中时原样保留
+        let urls = asset_urls("search", dir.path(), "1.2.0", Some("https://example.com/logo.svg"));
+        assert_eq!(urls.logo.as_deref(), Some("https://example.com/logo.svg"));
+        assert_eq!(asset_urls("search", &dir.path().join("search.wasm"), "1.2.0", None), PluginAssetUrls::default());
+    }
+}
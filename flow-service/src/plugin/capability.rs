@@ -0,0 +1,106 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use flow_plugin::{PluginCapabilities, PluginManager};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 保存管理员批准的插件能力的ConfigMap（插件ID -> 能力JSON）
+pub const CAPABILITY_APPROVALS_CONFIG_MAP: &str = "plugin-capability-approvals";
+
+/// 插件能力审批信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginCapabilityReview {
+    /// 插件描述符中声明的能力
+    pub requested: PluginCapabilities,
+    /// 已批准的能力，未批准过时为None
+    pub approved: Option<PluginCapabilities>,
+    /// 声明了但尚未批准的能力，不为空时插件无法启动
+    pub pending: Vec<String>,
+}
+
+/// 插件能力审批服务trait
+#[async_trait]
+pub trait PluginCapabilityService: Send + Sync {
+    /// 获取已加载插件声明的能力和审批情况，插件未加载时返回None
+    async fn get_capabilities(&self, plugin_id: &str) -> Result<Option<PluginCapabilityReview>>;
+
+    /// 批准插件的能力（替换之前的批准），插件未加载时返回None
+    async fn approve_capabilities(&self, plugin_id: &str, capabilities: PluginCapabilities) -> Result<Option<PluginCapabilityReview>>;
+
+    /// 将保存的批准记录恢复到插件管理器，返回恢复的插件数量
+    async fn restore_approvals(&self) -> Result<usize>;
+}
+
+/// 默认插件能力审批服务实现
+pub struct DefaultPluginCapabilityService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    plugin_manager: Arc<dyn PluginManager>,
+}
+
+impl DefaultPluginCapabilityService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>, plugin_manager: Arc<dyn PluginManager>) -> Self {
+        Self { extension_client, plugin_manager }
+    }
+
+    async fn fetch_approvals(&self) -> Result<Option<ConfigMap>> {
+        self.extension_client.fetch(CAPABILITY_APPROVALS_CONFIG_MAP).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch capability approvals: {}", e))
+    }
+
+    async fn review(&self, plugin_id: &str) -> Option<PluginCapabilityReview> {
+        let wrapper = self.plugin_manager.get_plugin(plugin_id).await?;
+        let requested = wrapper.descriptor.capabilities.clone();
+        let approved = self.plugin_manager.approved_capabilities(plugin_id).await;
+        let pending = requested.missing(&approved.clone().unwrap_or_default());
+        Some(PluginCapabilityReview { requested, approved, pending })
+    }
+}
+
+#[async_trait]
+impl PluginCapabilityService for DefaultPluginCapabilityService {
+    async fn get_capabilities(&self, plugin_id: &str) -> Result<Option<PluginCapabilityReview>> {
+        Ok(self.review(plugin_id).await)
+    }
+
+    async fn approve_capabilities(&self, plugin_id: &str, capabilities: PluginCapabilities) -> Result<Option<PluginCapabilityReview>> {
+        if self.plugin_manager.get_plugin(plugin_id).await.is_none() {
+            return Ok(None);
+        }
+        let approval = serde_json::to_string(&capabilities)?;
+        match self.fetch_approvals().await? {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).insert(plugin_id.to_string(), approval);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(CAPABILITY_APPROVALS_CONFIG_MAP),
+                data: Some(HashMap::from([(plugin_id.to_string(), approval)])),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save capability approval: {}", e))?;
+
+        self.plugin_manager.approve_capabilities(plugin_id, capabilities).await;
+        tracing::info!("Approved capabilities of plugin {}", plugin_id);
+        Ok(self.review(plugin_id).await)
+    }
+
+    async fn restore_approvals(&self) -> Result<usize> {
+        let Some(config_map) = self.fetch_approvals().await? else {
+            return Ok(0);
+        };
+        let mut restored = 0;
+        for (plugin_id, approval) in config_map.data.unwrap_or_default() {
+            match serde_json::from_str::<PluginCapabilities>(&approval) {
+                Ok(capabilities) => {
+                    self.plugin_manager.approve_capabilities(&plugin_id, capabilities).await;
+                    restored += 1;
+                }
+                Err(e) => tracing::warn!("Ignoring invalid capability approval of plugin {}: {}", plugin_id, e),
+            }
+        }
+        Ok(restored)
+    }
+}
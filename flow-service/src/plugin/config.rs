@@ -0,0 +1,198 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_domain::plugin::Plugin;
+use flow_domain::setting::{Setting, SettingForm};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use flow_plugin::PluginManager;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::theme::config::{resolve_values, stored_values, validate_values};
+
+/// 插件设置值不符合表单定义时的错误信息前缀
+pub const PLUGIN_CONFIG_INVALID_ERROR: &str = "Invalid plugin config";
+
+/// 插件设置：表单定义和解析后的设置值
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginConfig {
+    /// 设置表单，插件没有设置表单时为空
+    pub forms: Vec<SettingForm>,
+    /// 设置值（分组 -> 字段 -> 值），未保存的字段使用表单中的默认值
+    pub values: Value,
+}
+
+/// 插件设置服务trait
+#[async_trait]
+pub trait PluginConfigService: Send + Sync {
+    /// 获取插件的设置表单定义（`spec.settingName`），插件或设置不存在时返回None
+    async fn get_setting(&self, plugin_name: &str) -> Result<Option<Setting>>;
+
+    /// 获取插件的设置表单和设置值，插件不存在时返回None
+    async fn get_config(&self, plugin_name: &str) -> Result<Option<PluginConfig>>;
+
+    /// 按表单定义校验并保存设置值，只更新提交的分组，并通知运行中的插件，插件不存在时返回None
+    async fn update_config(&self, plugin_name: &str, values: Value) -> Result<Option<PluginConfig>>;
+}
+
+/// 默认插件设置服务实现
+pub struct DefaultPluginConfigService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    plugin_manager: Option<Arc<dyn PluginManager>>,
+}
+
+impl DefaultPluginConfigService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client, plugin_manager: None }
+    }
+
+    /// 设置插件管理器，设置值变更后通知运行中的插件
+    pub fn with_plugin_manager(mut self, plugin_manager: Arc<dyn PluginManager>) -> Self {
+        self.plugin_manager = Some(plugin_manager);
+        self
+    }
+
+    async fn fetch_plugin(&self, plugin_name: &str) -> Result<Option<Plugin>> {
+        self.extension_client.fetch(plugin_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))
+    }
+
+    async fn fetch_setting(&self, plugin: &Plugin) -> Result<Option<Setting>> {
+        let Some(setting_name) = plugin.spec.setting_name.as_deref() else {
+            return Ok(None);
+        };
+        self.extension_client.fetch(setting_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch setting {}: {}", setting_name, e))
+    }
+
+    async fn fetch_config_map(&self, plugin: &Plugin) -> Result<Option<ConfigMap>> {
+        let name = config_map_name(plugin);
+        self.extension_client.fetch(&name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch ConfigMap {}: {}", name, e))
+    }
+}
+
+/// 插件保存设置值的ConfigMap名称，未指定时为 `{插件名称}-config`
+fn config_map_name(plugin: &Plugin) -> String {
+    plugin.spec.config_map_name.clone()
+        .unwrap_or_else(|| format!("{}-config", plugin.metadata.name))
+}
+
+#[async_trait]
+impl PluginConfigService for DefaultPluginConfigService {
+    async fn get_setting(&self, plugin_name: &str) -> Result<Option<Setting>> {
+        match self.fetch_plugin(plugin_name).await? {
+            Some(plugin) => self.fetch_setting(&plugin).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn get_config(&self, plugin_name: &str) -> Result<Option<PluginConfig>> {
+        let Some(plugin) = self.fetch_plugin(plugin_name).await? else {
+            return Ok(None);
+        };
+        let forms = self.fetch_setting(&plugin).await?.map(|s| s.spec.forms).unwrap_or_default();
+        let config_map = self.fetch_config_map(&plugin).await?;
+        let values = resolve_values(&forms, &stored_values(config_map.as_ref()));
+        Ok(Some(PluginConfig { forms, values }))
+    }
+
+    async fn update_config(&self, plugin_name: &str, values: Value) -> Result<Option<PluginConfig>> {
+        let Some(plugin) = self.fetch_plugin(plugin_name).await? else {
+            return Ok(None);
+        };
+        let forms = self.fetch_setting(&plugin).await?.map(|s| s.spec.forms).unwrap_or_default();
+        let groups = validate_values(&forms, &values)
+            .map_err(|errors| anyhow::anyhow!("{}: {}", PLUGIN_CONFIG_INVALID_ERROR, errors.join("; ")))?;
+
+        let mut data = HashMap::new();
+        for (group, group_values) in &groups {
+            data.insert(group.clone(), serde_json::to_string(group_values)?);
+        }
+        let config_map = match self.fetch_config_map(&plugin).await? {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).extend(data);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(config_map_name(&plugin)),
+                data: Some(data),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save plugin config: {}", e))?;
+
+        let values = resolve_values(&forms, &stored_values(Some(&config_map)));
+        if let Some(plugin_manager) = &self.plugin_manager {
+            // 设置已保存，插件处理失败不影响本次更新
+            if let Err(e) = plugin_manager.notify_config_change(plugin_name, values.clone()).await {
+                tracing::warn!("Plugin {} failed to apply config change: {}", plugin_name, e);
+            }
+        }
+        Ok(Some(PluginConfig { forms, values }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_domain::setting::SettingSpec;
+    use serde_json::json;
+
+    async fn service() -> (DefaultPluginConfigService, Arc<ReactiveExtensionClient>) {
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        let plugin: Plugin = serde_json::from_value(json!({
+            "metadata": { "name": "hello" },
+            "spec": { "version": "1.0.0", "setting_name": "hello-settings" }
+        })).unwrap();
+        client.create(plugin).await.unwrap();
+        client.create(Setting {
+            metadata: Metadata::new("hello-settings"),
+            spec: SettingSpec {
+                forms: serde_json::from_value(json!([{
+                    "group": "basic",
+                    "formSchema": [
+                        { "$formkit": "text", "name": "greeting", "value": "Hello", "validation": "required" },
+                        { "$formkit": "number", "name": "times", "validation": "min:1" }
+                    ]
+                }])).unwrap(),
+            },
+        }).await.unwrap();
+        (DefaultPluginConfigService::new(client.clone()), client)
+    }
+
+    #[tokio::test]
+    async fn test_get_config_uses_form_defaults() {
+        let (service, _) = service().await;
+
+        let config = service.get_config("hello").await.unwrap().unwrap();
+
+        assert_eq!(config.forms.len(), 1);
+        assert_eq!(config.values, json!({ "basic": { "greeting": "Hello" } }));
+        assert!(service.get_config("missing").await.unwrap().is_none());
+        assert!(service.get_setting("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_config_saves_values() {
+        let (service, client) = service().await;
+
+        let config = service.update_config("hello", json!({ "basic": { "greeting": "Hi", "times": 2 } })).await.unwrap().unwrap();
+
+        assert_eq!(config.values, json!({ "basic": { "greeting": "Hi", "times": 2 } }));
+        let config_map = client.fetch::<ConfigMap>("hello-config").await.unwrap().unwrap();
+        assert_eq!(stored_values(Some(&config_map))["basic"]["times"], json!(2));
+        assert_eq!(service.get_config("hello").await.unwrap().unwrap().values, config.values);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_rejects_invalid_values() {
+        let (service, client) = service().await;
+
+        let error = service.update_config("hello", json!({ "basic": { "greeting": "", "times": 0 } })).await.unwrap_err();
+
+        assert!(error.to_string().starts_with(PLUGIN_CONFIG_INVALID_ERROR));
+        assert!(client.fetch::<ConfigMap>("hello-config").await.unwrap().is_none());
+        assert!(service.update_config("missing", json!({})).await.unwrap().is_none());
+    }
+}
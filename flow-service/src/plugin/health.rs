@@ -0,0 +1,162 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::ExtensionClient;
+use flow_domain::plugin::{Plugin, PluginPhase, PluginStatus};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_plugin::probe::{DEFAULT_FAILURE_THRESHOLD, DEFAULT_PROBE_TIMEOUT};
+use flow_plugin::{PluginManager, PluginProbe, PluginState};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// 插件健康探测配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginProbeConfig {
+    /// 探测间隔（秒）
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// 单次健康检查超时时间（秒）
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// 连续失败多少次后将插件标记为失败
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+}
+
+fn default_interval() -> u64 {
+    30
+}
+
+fn default_timeout() -> u64 {
+    DEFAULT_PROBE_TIMEOUT.as_secs()
+}
+
+fn default_failure_threshold() -> u32 {
+    DEFAULT_FAILURE_THRESHOLD
+}
+
+impl Default for PluginProbeConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_interval(),
+            timeout: default_timeout(),
+            failure_threshold: default_failure_threshold(),
+        }
+    }
+}
+
+/// 将探测结果写入插件状态，插件已被标记为失败时阶段改为Failed
+pub fn apply_probe(status: Option<PluginStatus>, probe: &PluginProbe, failed: bool) -> PluginStatus {
+    let mut status = status.unwrap_or(PluginStatus {
+        phase: PluginPhase::default(),
+        last_start_time: None,
+        last_probe_state: None,
+        last_probe_time: None,
+        probe_failures: 0,
+        entry: None,
+        stylesheet: None,
+        logo: None,
+        load_location: None,
+        conditions: Vec::new(),
+    });
+    status.last_probe_state = Some(probe.state.as_str().to_string());
+    status.last_probe_time = Some(probe.last_probe_time);
+    status.probe_failures = probe.consecutive_failures;
+    if failed {
+        status.phase = PluginPhase::Failed;
+    }
+    status
+}
+
+/// 插件健康探测服务trait
+#[async_trait]
+pub trait PluginHealthService: Send + Sync {
+    /// 探测所有运行中的插件，并将结果记录到对应Plugin扩展对象的状态中，返回探测的插件数量
+    async fn probe_all(&self) -> Result<usize>;
+}
+
+/// 默认插件健康探测服务实现
+pub struct DefaultPluginHealthService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    plugin_manager: Arc<dyn PluginManager>,
+}
+
+impl DefaultPluginHealthService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>, plugin_manager: Arc<dyn PluginManager>) -> Self {
+        Self { extension_client, plugin_manager }
+    }
+
+    async fn record(&self, plugin_id: &str, probe: &PluginProbe) -> Result<()> {
+        // 没有对应Plugin扩展对象的插件（如开发模式加载的插件）只在插件管理器中保留探测结果
+        let Some(mut plugin) = self.extension_client.fetch::<Plugin>(plugin_id).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))? else {
+            return Ok(());
+        };
+        let failed = self.plugin_manager.get_plugin(plugin_id).await
+            .is_some_and(|wrapper| wrapper.state == PluginState::Failed);
+        plugin.status = Some(apply_probe(plugin.status.take(), probe, failed));
+        self.extension_client.update(plugin).await
+            .map_err(|e| anyhow::anyhow!("Failed to update plugin status: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PluginHealthService for DefaultPluginHealthService {
+    async fn probe_all(&self) -> Result<usize> {
+        let probes = self.plugin_manager.probe_plugins().await;
+        for (plugin_id, probe) in &probes {
+            if let Err(e) = self.record(plugin_id, probe).await {
+                warn!("Failed to record health of plugin {}: {}", plugin_id, e);
+            }
+        }
+        Ok(probes.len())
+    }
+}
+
+/// 启动后台插件健康探测任务
+pub fn spawn_plugin_probe_job(
+    service: Arc<dyn PluginHealthService>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // 第一次立即触发的tick跳过，插件启动后再开始探测
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = service.probe_all().await {
+                warn!("Plugin health probe failed: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_apply_probe() {
+        let probe = PluginProbe::record(None, Err("timed out".to_string()), Utc::now());
+        let status = apply_probe(None, &probe, false);
+        assert_eq!(status.phase, PluginPhase::Pending);
+        assert_eq!(status.last_probe_state.as_deref(), Some("Unhealthy"));
+        assert_eq!(status.last_probe_time, Some(probe.last_probe_time));
+        assert_eq!(status.probe_failures, 1);
+
+        let status = apply_probe(Some(status), &probe, true);
+        assert_eq!(status.phase, PluginPhase::Failed);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config: PluginProbeConfig = serde_json::from_str(r#"{"failureThreshold": 5}"#).unwrap();
+        assert_eq!(config.interval, 30);
+        assert_eq!(config.timeout, 10);
+        assert_eq!(config.failure_threshold, 5);
+    }
+}
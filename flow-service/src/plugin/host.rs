@@ -0,0 +1,175 @@
+use anyhow::{anyhow, bail, Result};
+use flow_api::extension::{Extension, ExtensionClient, GroupVersionKind, ListOptions, Metadata};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_plugin::ExtensionHost;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// 插件通过宿主接口读写的扩展对象，保留插件提交的全部字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PluginExtension {
+    metadata: Metadata,
+    #[serde(rename = "apiVersion", default)]
+    api_version: String,
+    #[serde(default)]
+    kind: String,
+    #[serde(flatten)]
+    data: Map<String, Value>,
+}
+
+impl PluginExtension {
+    /// 解析插件提交的扩展对象，类型以宿主检查过权限的类型为准，忽略对象中声明的类型
+    fn from_value(gvk: &GroupVersionKind, extension: Value) -> Result<Self> {
+        let mut extension: Self = serde_json::from_value(extension)
+            .map_err(|e| anyhow!("Invalid extension: {}", e))?;
+        extension.api_version = api_version(gvk);
+        extension.kind = gvk.kind.clone();
+        Ok(extension)
+    }
+
+    fn to_value(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn is(&self, gvk: &GroupVersionKind) -> bool {
+        self.api_version == api_version(gvk) && self.kind == gvk.kind
+    }
+}
+
+impl Extension for PluginExtension {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn group_version_kind(&self) -> GroupVersionKind {
+        match self.api_version.split_once('/') {
+            Some((group, version)) => GroupVersionKind::new(group, version, &self.kind),
+            None => GroupVersionKind::new("", &self.api_version, &self.kind),
+        }
+    }
+}
+
+/// 扩展对象的apiVersion，核心组省略组名
+fn api_version(gvk: &GroupVersionKind) -> String {
+    if gvk.group.is_empty() {
+        gvk.version.clone()
+    } else {
+        format!("{}/{}", gvk.group, gvk.version)
+    }
+}
+
+/// 扩展对象的存储名称：`{group}/{version}/{name}`
+fn store_name(gvk: &GroupVersionKind, name: &str) -> String {
+    format!("{}/{}/{}", gvk.group, gvk.version, name)
+}
+
+/// 基于扩展客户端的插件宿主接口
+///
+/// 插件运行时在调用前按插件的实际能力检查扩展类型，这里保证每个操作只作用于该类型的对象：
+/// 写入的对象类型以检查过的类型为准，不能覆盖或删除同一存储名称下其他类型的对象
+pub struct ClientExtensionHost {
+    extension_client: Arc<ReactiveExtensionClient>,
+    runtime: Handle,
+}
+
+impl ClientExtensionHost {
+    /// 需要在Tokio运行时中创建，插件在阻塞线程中调用宿主接口时通过该运行时等待异步操作完成
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client, runtime: Handle::current() }
+    }
+
+    fn fetch(&self, gvk: &GroupVersionKind, name: &str) -> Result<Option<PluginExtension>> {
+        self.runtime.block_on(self.extension_client.fetch::<PluginExtension>(&store_name(gvk, name)))
+            .map_err(|e| anyhow!("Failed to fetch extension {}: {}", store_name(gvk, name), e))
+    }
+
+    /// 获取指定类型的对象，不存在或为其他类型时返回错误
+    fn fetch_existing(&self, gvk: &GroupVersionKind, name: &str) -> Result<PluginExtension> {
+        self.fetch(gvk, name)?
+            .filter(|extension| extension.is(gvk))
+            .ok_or_else(|| anyhow!("Extension {} not found", store_name(gvk, name)))
+    }
+}
+
+impl ExtensionHost for ClientExtensionHost {
+    fn get(&self, gvk: &GroupVersionKind, name: &str) -> Result<Option<Value>> {
+        self.fetch(gvk, name)?
+            .filter(|extension| extension.is(gvk))
+            .map(|extension| extension.to_value())
+            .transpose()
+    }
+
+    fn list(&self, gvk: &GroupVersionKind) -> Result<Vec<Value>> {
+        let extensions = self.runtime.block_on(self.extension_client.list_all::<PluginExtension>(ListOptions::default()))
+            .map_err(|e| anyhow!("Failed to list extensions {}: {}", gvk.to_string(), e))?;
+        extensions.iter()
+            .filter(|extension| extension.is(gvk))
+            .map(PluginExtension::to_value)
+            .collect()
+    }
+
+    fn create(&self, gvk: &GroupVersionKind, extension: Value) -> Result<Value> {
+        let extension = PluginExtension::from_value(gvk, extension)?;
+        let name = store_name(gvk, &extension.metadata.name);
+        if self.fetch(gvk, &extension.metadata.name)?.is_some() {
+            bail!("Extension {} already exists", name);
+        }
+        self.runtime.block_on(self.extension_client.create(extension))
+            .map_err(|e| anyhow!("Failed to create extension {}: {}", name, e))?
+            .to_value()
+    }
+
+    fn update(&self, gvk: &GroupVersionKind, extension: Value) -> Result<Value> {
+        let extension = PluginExtension::from_value(gvk, extension)?;
+        let name = store_name(gvk, &extension.metadata.name);
+        self.fetch_existing(gvk, &extension.metadata.name)?;
+        self.runtime.block_on(self.extension_client.update(extension))
+            .map_err(|e| anyhow!("Failed to update extension {}: {}", name, e))?
+            .to_value()
+    }
+
+    fn delete(&self, gvk: &GroupVersionKind, name: &str) -> Result<()> {
+        self.fetch_existing(gvk, name)?;
+        self.runtime.block_on(self.extension_client.delete::<PluginExtension>(&store_name(gvk, name)))
+            .map_err(|e| anyhow!("Failed to delete extension {}: {}", store_name(gvk, name), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_infra::system_setting::ConfigMap;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_host_confines_calls_to_kind() {
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        client.create(ConfigMap { metadata: Metadata::new("system"), data: None }).await.unwrap();
+        let host = ClientExtensionHost::new(client.clone());
+
+        // 插件在阻塞线程中调用宿主接口
+        tokio::task::spawn_blocking(move || {
+            let note = GroupVersionKind::new("", "v1alpha1", "Note");
+            let created = host.create(&note, json!({
+                "apiVersion": "v1alpha1", "kind": "ConfigMap", "metadata": {"name": "hello"}, "spec": {"title": "Hello"},
+            })).unwrap();
+            assert_eq!(created["kind"], "Note");
+            assert!(host.create(&note, json!({"metadata": {"name": "hello"}})).is_err());
+            assert_eq!(host.get(&note, "hello").unwrap().unwrap()["spec"]["title"], "Hello");
+            assert_eq!(host.list(&note).unwrap().len(), 1);
+
+            // 同一存储名称下的其他类型对象不能被读取、覆盖或删除
+            assert!(host.get(&note, "system").unwrap().is_none());
+            assert!(host.create(&note, json!({"metadata": {"name": "system"}})).is_err());
+            assert!(host.update(&note, json!({"metadata": {"name": "system"}})).is_err());
+            assert!(host.delete(&note, "system").is_err());
+
+            host.delete(&note, "hello").unwrap();
+            assert!(host.list(&note).unwrap().is_empty());
+        }).await.unwrap();
+
+        assert!(client.fetch::<ConfigMap>("system").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,413 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::Engine;
+use flow_plugin::dependency::startup_order;
+use flow_plugin::wasm::descriptor_from_module;
+use flow_plugin::{PluginManager, PluginState, PluginWrapper};
+use reqwest::Url;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::attachment::remote_import::validate_remote_url;
+use crate::attachment::RemoteFetcher;
+use crate::plugin::{PluginCapabilityReview, PluginCapabilityService};
+
+/// 安装请求无效（缺少地址、插件市场未配置等）时的错误信息前缀
+pub const PLUGIN_INSTALL_REQUEST_ERROR: &str = "Invalid plugin install request";
+
+/// 插件包无效（不是WASM插件、缺少描述符等）时的错误信息前缀
+pub const PLUGIN_PACKAGE_INVALID_ERROR: &str = "Invalid plugin package";
+
+/// 插件包校验和或签名校验失败时的错误信息前缀
+pub const PLUGIN_VERIFICATION_ERROR: &str = "Plugin package verification failed";
+
+/// 插件市场中找不到插件版本时的错误信息前缀
+pub const PLUGIN_RELEASE_NOT_FOUND_ERROR: &str = "Plugin release not found";
+
+/// 已安装的插件不是远程安装的，无法升级时的错误信息前缀
+pub const PLUGIN_INSTALL_CONFLICT_ERROR: &str = "Plugin cannot be upgraded remotely";
+
+/// 新版本启动失败（已回滚）时的错误信息前缀
+pub const PLUGIN_START_FAILED_ERROR: &str = "Plugin failed to start";
+
+/// 远程安装配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInstallConfig {
+    /// 插件包最大大小（字节）
+    #[serde(default = "default_max_size")]
+    pub max_size: u64,
+    /// 下载超时时间（秒）
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// 插件市场索引地址，按插件ID安装时使用
+    #[serde(default)]
+    pub marketplace_url: Option<String>,
+    /// 信任的签名公钥（Ed25519，Base64编码）
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+    /// 是否要求插件包签名，不要求时至少需要SHA-256校验和
+    #[serde(default)]
+    pub require_signature: bool,
+    /// 是否允许从内网地址下载，仅用于开发测试
+    #[serde(default)]
+    pub allow_private_networks: bool,
+}
+
+fn default_max_size() -> u64 {
+    50 * 1024 * 1024
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+impl Default for PluginInstallConfig {
+    fn default() -> Self {
+        Self {
+            max_size: default_max_size(),
+            timeout: default_timeout(),
+            marketplace_url: None,
+            trusted_keys: Vec::new(),
+            require_signature: false,
+            allow_private_networks: false,
+        }
+    }
+}
+
+/// 远程安装请求：指定插件包地址，或指定插件ID从插件市场安装
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInstallRequest {
+    /// 插件包（`.wasm`）地址
+    pub url: Option<String>,
+    /// 插件ID，指定地址时用于校验插件包
+    pub plugin_id: Option<String>,
+    /// 插件市场中的版本，为空时安装最新版本
+    pub version: Option<String>,
+    /// 插件包的SHA-256校验和（十六进制）
+    pub sha256: Option<String>,
+    /// 插件包的Ed25519签名（Base64编码）
+    pub signature: Option<String>,
+}
+
+/// 插件市场索引
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketplaceIndex {
+    #[serde(default)]
+    pub plugins: Vec<MarketplaceRelease>,
+}
+
+/// 插件市场中的插件版本
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketplaceRelease {
+    pub id: String,
+    pub version: String,
+    /// 插件包地址，可以是相对索引地址的路径
+    pub url: String,
+    pub sha256: Option<String>,
+    pub signature: Option<String>,
+}
+
+/// 远程安装结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInstallResult {
+    pub plugin_id: String,
+    pub version: String,
+    /// 升级前的版本，新安装时为None
+    pub previous_version: Option<String>,
+    /// 插件是否已启动，需要批准能力的新插件不会自动启动
+    pub started: bool,
+    /// 插件声明的能力和审批情况
+    pub capabilities: Option<PluginCapabilityReview>,
+}
+
+/// 插件市场中的指定版本，未指定版本时选择最高的版本
+pub fn select_release<'a>(index: &'a MarketplaceIndex, plugin_id: &str, version: Option<&str>) -> Option<&'a MarketplaceRelease> {
+    let mut releases = index.plugins.iter().filter(|release| release.id == plugin_id);
+    match version {
+        Some(version) => releases.find(|release| release.version == version),
+        None => releases
+            .filter_map(|release| semver::Version::parse(&release.version).ok().map(|v| (v, release)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, release)| release),
+    }
+}
+
+/// 校验插件包的SHA-256校验和与签名，至少需要其中一项
+pub fn verify_package(package: &[u8], sha256: Option<&str>, signature: Option<&str>, config: &PluginInstallConfig) -> Result<()> {
+    if sha256.is_none() && signature.is_none() {
+        anyhow::bail!("{}: a sha256 checksum or signature is required", PLUGIN_VERIFICATION_ERROR);
+    }
+    if config.require_signature && signature.is_none() {
+        anyhow::bail!("{}: a signature is required", PLUGIN_VERIFICATION_ERROR);
+    }
+    if let Some(expected) = sha256 {
+        let actual = hex::encode(Sha256::digest(package));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!("{}: sha256 checksum mismatch, got {}", PLUGIN_VERIFICATION_ERROR, actual);
+        }
+    }
+    if let Some(signature) = signature {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let signature = base64.decode(signature.trim())
+            .map_err(|e| anyhow::anyhow!("{}: invalid signature: {}", PLUGIN_VERIFICATION_ERROR, e))?;
+        let verified = config.trusted_keys.iter()
+            .filter_map(|key| base64.decode(key.trim()).ok())
+            .any(|key| UnparsedPublicKey::new(&ED25519, key).verify(package, &signature).is_ok());
+        if !verified {
+            anyhow::bail!("{}: signature is not from a trusted key", PLUGIN_VERIFICATION_ERROR);
+        }
+    }
+    Ok(())
+}
+
+/// 插件ID可以作为文件名使用
+fn is_valid_plugin_id(plugin_id: &str) -> bool {
+    !plugin_id.is_empty()
+        && !plugin_id.starts_with('.')
+        && plugin_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// 下载插件包的来源
+struct PackageSource {
+    url: String,
+    plugin_id: Option<String>,
+    sha256: Option<String>,
+    signature: Option<String>,
+}
+
+/// 插件远程安装服务trait
+#[async_trait]
+pub trait PluginInstallService: Send + Sync {
+    /// 下载、校验并安装或升级插件
+    /// 升级后的插件启动失败时恢复为原版本，并恢复之前已启动的插件
+    async fn install(&self, request: PluginInstallRequest) -> Result<PluginInstallResult>;
+}
+
+/// 默认插件远程安装服务实现，插件包安装为插件目录下的 `{插件ID}.wasm`
+pub struct DefaultPluginInstallService {
+    plugin_manager: Arc<dyn PluginManager>,
+    plugins_dir: PathBuf,
+    config: PluginInstallConfig,
+    fetcher: RemoteFetcher,
+    capability_service: Option<Arc<dyn PluginCapabilityService>>,
+}
+
+impl DefaultPluginInstallService {
+    pub fn new(plugin_manager: Arc<dyn PluginManager>, plugins_dir: PathBuf, config: PluginInstallConfig) -> Self {
+        let fetcher = RemoteFetcher::new(config.max_size, config.timeout, config.allow_private_networks);
+        Self { plugin_manager, plugins_dir, config, fetcher, capability_service: None }
+    }
+
+    /// 设置能力审批服务，安装结果中附带插件声明的能力供管理员审批
+    pub fn with_capability_service(mut self, capability_service: Arc<dyn PluginCapabilityService>) -> Self {
+        self.capability_service = Some(capability_service);
+        self
+    }
+
+    /// 确定插件包地址，按插件ID安装时从插件市场索引中查找
+    async fn resolve_source(&self, request: PluginInstallRequest) -> Result<PackageSource> {
+        if let Some(url) = request.url.filter(|url| !url.trim().is_empty()) {
+            return Ok(PackageSource {
+                url,
+                plugin_id: request.plugin_id,
+                sha256: request.sha256,
+                signature: request.signature,
+            });
+        }
+        let plugin_id = request.plugin_id
+            .ok_or_else(|| anyhow::anyhow!("{}: url or pluginId is required", PLUGIN_INSTALL_REQUEST_ERROR))?;
+        let marketplace_url = self.config.marketplace_url.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("{}: no plugin marketplace is configured", PLUGIN_INSTALL_REQUEST_ERROR))?;
+        let index_url = Url::parse(marketplace_url)?;
+        let index: MarketplaceIndex = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout))
+            .build()?
+            .get(index_url.clone())
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin marketplace index: {}", e))?
+            .json().await
+            .map_err(|e| anyhow::anyhow!("Invalid plugin marketplace index: {}", e))?;
+        let release = select_release(&index, &plugin_id, request.version.as_deref())
+            .ok_or_else(|| anyhow::anyhow!(
+                "{}: {} {}", PLUGIN_RELEASE_NOT_FOUND_ERROR, plugin_id, request.version.as_deref().unwrap_or("latest")
+            ))?;
+        Ok(PackageSource {
+            url: index_url.join(&release.url)?.to_string(),
+            plugin_id: Some(plugin_id),
+            // 插件市场中的校验信息优先
+            sha256: release.sha256.clone().or(request.sha256),
+            signature: release.signature.clone().or(request.signature),
+        })
+    }
+
+    /// 安装新插件，能力均已批准时启动；启动失败时卸载并删除插件包
+    async fn install_new(&self, plugin_id: &str, target: &Path, package: &[u8]) -> Result<bool> {
+        tokio::fs::create_dir_all(&self.plugins_dir).await?;
+        tokio::fs::write(target, package).await?;
+        if let Err(e) = self.plugin_manager.load_plugin(target.to_path_buf()).await {
+            let _ = tokio::fs::remove_file(target).await;
+            anyhow::bail!("{}: {}", PLUGIN_PACKAGE_INVALID_ERROR, e);
+        }
+        let Some(wrapper) = self.plugin_manager.get_plugin(plugin_id).await else {
+            return Ok(false);
+        };
+        let approved = self.plugin_manager.approved_capabilities(plugin_id).await.unwrap_or_default();
+        if !wrapper.descriptor.capabilities.missing(&approved).is_empty() {
+            // 等待管理员批准能力后再启动
+            return Ok(false);
+        }
+        if let Err(e) = self.plugin_manager.start_plugin(plugin_id).await {
+            if let Err(e) = self.plugin_manager.unload_plugin(plugin_id).await {
+                tracing::warn!("Failed to unload plugin {} after failed install: {}", plugin_id, e);
+            }
+            let _ = tokio::fs::remove_file(target).await;
+            anyhow::bail!("{}: {}", PLUGIN_START_FAILED_ERROR, e);
+        }
+        Ok(true)
+    }
+
+    /// 升级已安装的插件，重新加载或启动失败时恢复原插件包
+    async fn upgrade(&self, previous: &PluginWrapper, target: &Path, package: &[u8]) -> Result<bool> {
+        let plugin_id = previous.plugin_id();
+        if Path::new(&previous.plugin_path) != target {
+            anyhow::bail!("{}: {} is installed from {}", PLUGIN_INSTALL_CONFLICT_ERROR, plugin_id, previous.plugin_path);
+        }
+        let started: BTreeSet<String> = self.plugin_manager.get_started_plugins().await.iter()
+            .map(|wrapper| wrapper.plugin_id().to_string())
+            .collect();
+        let backup = target.with_extension("wasm.bak");
+        tokio::fs::copy(target, &backup).await?;
+        tokio::fs::write(target, package).await?;
+
+        match self.plugin_manager.reload_plugin(plugin_id).await {
+            Ok(()) => {
+                let _ = tokio::fs::remove_file(&backup).await;
+                let state = self.plugin_manager.get_plugin(plugin_id).await.map(|wrapper| wrapper.state);
+                Ok(state == Some(PluginState::Started))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to upgrade plugin {}, rolling back to {}: {}", plugin_id, previous.version(), e);
+                tokio::fs::rename(&backup, target).await?;
+                if let Err(e) = self.plugin_manager.reload_plugin(plugin_id).await {
+                    tracing::error!("Failed to restore plugin {} {}: {}", plugin_id, previous.version(), e);
+                }
+                self.restore_started(&started).await;
+                anyhow::bail!("{}: {}; rolled back to version {}", PLUGIN_START_FAILED_ERROR, e, previous.version())
+            }
+        }
+    }
+
+    /// 按依赖顺序重新启动之前已启动的插件
+    async fn restore_started(&self, started: &BTreeSet<String>) {
+        let plugins = self.plugin_manager.get_plugins().await;
+        let descriptors: Vec<_> = plugins.iter()
+            .filter(|wrapper| started.contains(wrapper.plugin_id()))
+            .map(|wrapper| wrapper.descriptor.clone())
+            .collect();
+        for plugin_id in startup_order(&descriptors).unwrap_or_default() {
+            if let Err(e) = self.plugin_manager.start_plugin(&plugin_id).await {
+                tracing::warn!("Failed to restart plugin {} after rollback: {}", plugin_id, e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PluginInstallService for DefaultPluginInstallService {
+    async fn install(&self, request: PluginInstallRequest) -> Result<PluginInstallResult> {
+        let source = self.resolve_source(request).await?;
+        let url = validate_remote_url(source.url.trim())?;
+        let response = self.fetcher.fetch(url).await?;
+        let package = self.fetcher.read_body(response).await?;
+        verify_package(&package, source.sha256.as_deref(), source.signature.as_deref(), &self.config)?;
+
+        let descriptor = descriptor_from_module(&package)
+            .map_err(|e| anyhow::anyhow!("{}: {}", PLUGIN_PACKAGE_INVALID_ERROR, e))?
+            .ok_or_else(|| anyhow::anyhow!("{}: missing embedded plugin descriptor", PLUGIN_PACKAGE_INVALID_ERROR))?;
+        if let Some(expected) = source.plugin_id.as_deref().filter(|expected| *expected != descriptor.id) {
+            anyhow::bail!("{}: package contains plugin {}, expected {}", PLUGIN_PACKAGE_INVALID_ERROR, descriptor.id, expected);
+        }
+        if !is_valid_plugin_id(&descriptor.id) {
+            anyhow::bail!("{}: invalid plugin id {:?}", PLUGIN_PACKAGE_INVALID_ERROR, descriptor.id);
+        }
+
+        let target = self.plugins_dir.join(format!("{}.wasm", descriptor.id));
+        let previous = self.plugin_manager.get_plugin(&descriptor.id).await;
+        let started = match &previous {
+            Some(previous) => self.upgrade(previous, &target, &package).await?,
+            None => self.install_new(&descriptor.id, &target, &package).await?,
+        };
+        tracing::info!("Installed plugin {} {} from {}", descriptor.id, descriptor.version, source.url);
+
+        let capabilities = match &self.capability_service {
+            Some(capability_service) => capability_service.get_capabilities(&descriptor.id).await?,
+            None => None,
+        };
+        Ok(PluginInstallResult {
+            plugin_id: descriptor.id,
+            version: descriptor.version,
+            previous_version: previous.map(|previous| previous.version().to_string()),
+            started,
+            capabilities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn release(id: &str, version: &str) -> MarketplaceRelease {
+        MarketplaceRelease {
+            id: id.to_string(),
+            version: version.to_string(),
+            url: format!("{}-{}.wasm", id, version),
+            sha256: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_select_release() {
+        let index = MarketplaceIndex {
+            plugins: vec![release("search", "1.2.0"), release("search", "1.10.0"), release("editor", "2.0.0"), release("search", "beta")],
+        };
+        assert_eq!(select_release(&index, "search", None).unwrap().version, "1.10.0");
+        assert_eq!(select_release(&index, "search", Some("1.2.0")).unwrap().version, "1.2.0");
+        assert!(select_release(&index, "search", Some("3.0.0")).is_none());
+        assert!(select_release(&index, "comments", None).is_none());
+    }
+
+    #[test]
+    fn test_verify_package() {
+        let package = b"\0asm\x01\0\0\0";
+        let checksum = hex::encode(Sha256::digest(package));
+        let mut config = PluginInstallConfig::default();
+        assert!(verify_package(package, Some(&checksum), None, &config).is_ok());
+        assert!(verify_package(package, Some(&checksum.to_uppercase()), None, &config).is_ok());
+        assert!(verify_package(package, Some("00"), None, &config).unwrap_err().to_string().contains("checksum mismatch"));
+        assert!(verify_package(package, None, None, &config).is_err());
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let signature = base64.encode(key_pair.sign(package).as_ref());
+        assert!(verify_package(package, None, Some(&signature), &config).unwrap_err().to_string().contains("trusted key"));
+
+        config.trusted_keys = vec![base64.encode(key_pair.public_key().as_ref())];
+        config.require_signature = true;
+        assert!(verify_package(package, None, Some(&signature), &config).is_ok());
+        assert!(verify_package(package, Some(&checksum), None, &config).unwrap_err().to_string().contains("signature is required"));
+        assert!(verify_package(b"tampered", None, Some(&signature), &config).is_err());
+    }
+}
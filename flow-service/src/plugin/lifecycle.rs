@@ -0,0 +1,419 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use flow_api::extension::{ExtensionClient, ListOptions, ListResult};
+use flow_api::extension::Metadata;
+use flow_domain::plugin::{License, Plugin, PluginAuthor, PluginCondition, PluginPhase, PluginSpec, PluginStatus};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_plugin::dependency::startup_order;
+use flow_plugin::metrics::render_prometheus;
+use flow_plugin::{PluginDescriptor, PluginManager, PluginState, PluginStats};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::plugin::assets::{asset_urls, PluginAssetUrls};
+
+/// 插件不存在时的错误信息前缀
+pub const PLUGIN_NOT_FOUND_ERROR: &str = "Plugin not found";
+
+/// 插件未加载到插件管理器且没有加载位置时的错误信息前缀
+pub const PLUGIN_NOT_LOADED_ERROR: &str = "Plugin is not loaded";
+
+/// 插件启动、停止或重新加载失败时的错误信息前缀
+pub const PLUGIN_LIFECYCLE_ERROR: &str = "Plugin lifecycle operation failed";
+
+/// 根据是否启用和插件管理器中的状态计算插件阶段，插件未加载时为Pending
+pub fn plugin_phase(enabled: bool, state: Option<PluginState>) -> PluginPhase {
+    match state {
+        None => PluginPhase::Pending,
+        Some(PluginState::Created) if enabled => PluginPhase::Resolved,
+        Some(PluginState::Created) => PluginPhase::Disabled,
+        Some(PluginState::Starting) => PluginPhase::Starting,
+        Some(PluginState::Started) => PluginPhase::Started,
+        Some(PluginState::Stopping) if enabled => PluginPhase::Stopped,
+        Some(PluginState::Stopping) => PluginPhase::Disabling,
+        Some(PluginState::Stopped) if enabled => PluginPhase::Stopped,
+        Some(PluginState::Stopped) => PluginPhase::Disabled,
+        Some(PluginState::Failed) => PluginPhase::Failed,
+    }
+}
+
+/// 为插件目录中找到但没有Plugin扩展对象的插件包创建扩展对象，新发现的插件默认启用
+pub fn plugin_from_descriptor(descriptor: &PluginDescriptor, location: &str) -> Plugin {
+    Plugin {
+        metadata: Metadata::new(descriptor.id.clone()),
+        spec: PluginSpec {
+            display_name: None,
+            version: descriptor.version.clone(),
+            author: descriptor.provider.clone().map(|name| PluginAuthor { name, website: None }),
+            logo: None,
+            plugin_dependencies: descriptor.dependencies.clone(),
+            homepage: None,
+            repo: None,
+            issues: None,
+            description: descriptor.description.clone(),
+            license: descriptor.license.iter().map(|name| License { name: name.clone(), url: None }).collect(),
+            requires: descriptor.requires.clone(),
+            enabled: true,
+            setting_name: None,
+            config_map_name: None,
+        },
+        status: Some(PluginStatus { load_location: Some(location.to_string()), ..PluginStatus::default() }),
+    }
+}
+
+/// 插件生命周期服务trait
+///
+/// 管理Plugin扩展对象，并通过插件管理器启用、停用和重新加载插件，插件状态中的阶段与插件管理器保持一致
+#[async_trait]
+pub trait PluginLifecycleService: Send + Sync {
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Plugin>>;
+
+    async fn get(&self, name: &str) -> Result<Option<Plugin>>;
+
+    async fn create(&self, plugin: Plugin) -> Result<Plugin>;
+
+    /// 更新插件规格，状态保持不变；`spec.enabled` 变化时启用或停用插件
+    async fn update(&self, plugin: Plugin) -> Result<Plugin>;
+
+    /// 停止并卸载插件，删除Plugin扩展对象，插件不存在时返回false
+    async fn delete(&self, name: &str) -> Result<bool>;
+
+    /// 启用并启动插件，插件未加载时从状态中记录的加载位置加载
+    async fn enable(&self, name: &str) -> Result<Plugin>;
+
+    /// 停止并停用插件
+    async fn disable(&self, name: &str) -> Result<Plugin>;
+
+    /// 从加载位置重新加载插件，已启用的插件重新加载后启动
+    async fn reload(&self, name: &str) -> Result<Plugin>;
+
+    /// 服务启动时扫描插件目录加载插件包，为缺少Plugin扩展对象的插件创建对象，
+    /// 然后按依赖顺序启动已启用的插件；单个插件启动失败不影响其他插件，返回同步后的插件
+    async fn load_installed(&self) -> Result<Vec<Plugin>>;
+
+    /// 插件的调用次数、耗时、错误率和内存占用
+    async fn stats(&self, name: &str) -> Result<PluginStats>;
+
+    /// 所有已加载插件的统计，Prometheus文本格式
+    async fn metrics(&self) -> String;
+}
+
+/// 默认插件生命周期服务实现
+pub struct DefaultPluginLifecycleService {
+    extension_client: Arc<ReactiveExtensionClient>,
+    plugin_manager: Arc<dyn PluginManager>,
+}
+
+impl DefaultPluginLifecycleService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>, plugin_manager: Arc<dyn PluginManager>) -> Self {
+        Self { extension_client, plugin_manager }
+    }
+
+    async fn fetch(&self, name: &str) -> Result<Plugin> {
+        self.extension_client.fetch::<Plugin>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))?
+            .ok_or_else(|| anyhow::anyhow!("{}: {}", PLUGIN_NOT_FOUND_ERROR, name))
+    }
+
+    async fn save(&self, plugin: Plugin) -> Result<Plugin> {
+        self.extension_client.update(plugin).await
+            .map_err(|e| anyhow::anyhow!("Failed to save plugin: {}", e))
+    }
+
+    /// 按插件管理器中的状态更新插件阶段、加载位置和前端资源地址，只有已启动的插件提供前端资源
+    async fn sync_status(&self, plugin: &mut Plugin) {
+        let wrapper = self.plugin_manager.get_plugin(&plugin.metadata.name).await;
+        let assets = match &wrapper {
+            Some(wrapper) if wrapper.state == PluginState::Started => {
+                asset_urls(&plugin.metadata.name, Path::new(&wrapper.plugin_path), wrapper.version(), plugin.spec.logo.as_deref())
+            }
+            _ => PluginAssetUrls::default(),
+        };
+        let status = plugin.status.get_or_insert_with(PluginStatus::default);
+        status.phase = plugin_phase(plugin.spec.enabled, wrapper.as_ref().map(|w| w.state));
+        status.entry = assets.entry;
+        status.stylesheet = assets.stylesheet;
+        status.logo = assets.logo;
+        if let Some(wrapper) = wrapper {
+            status.load_location = Some(wrapper.plugin_path.clone());
+            status.conditions = wrapper.conditions.iter()
+                .map(|c| PluginCondition {
+                    condition_type: c.condition_type.clone(),
+                    reason: c.reason.clone(),
+                    message: c.message.clone(),
+                    last_transition_time: c.last_transition_time,
+                })
+                .collect();
+        }
+    }
+
+    /// 确保插件已加载到插件管理器
+    async fn ensure_loaded(&self, plugin: &Plugin) -> Result<()> {
+        let name = &plugin.metadata.name;
+        if self.plugin_manager.get_plugin(name).await.is_some() {
+            return Ok(());
+        }
+        let location = plugin.status.as_ref()
+            .and_then(|status| status.load_location.clone())
+            .ok_or_else(|| anyhow::anyhow!("{}: {}", PLUGIN_NOT_LOADED_ERROR, name))?;
+        let plugin_id = self.plugin_manager.load_plugin(PathBuf::from(&location)).await
+            .map_err(|e| anyhow::anyhow!("{}: failed to load {} from {}: {}", PLUGIN_LIFECYCLE_ERROR, name, location, e))?;
+        if plugin_id != *name {
+            anyhow::bail!("{}: {} contains plugin {}", PLUGIN_NOT_LOADED_ERROR, location, plugin_id);
+        }
+        Ok(())
+    }
+
+    /// 启动插件并记录结果，启动失败时阶段为Failed
+    async fn start(&self, mut plugin: Plugin) -> Result<Plugin> {
+        let name = plugin.metadata.name.clone();
+        let result = self.plugin_manager.start_plugin(&name).await;
+        self.sync_status(&mut plugin).await;
+        match result {
+            Ok(()) => {
+                if let Some(status) = plugin.status.as_mut() {
+                    status.last_start_time = Some(Utc::now());
+                }
+                self.save(plugin).await
+            }
+            Err(e) => {
+                if let Some(status) = plugin.status.as_mut() {
+                    status.phase = PluginPhase::Failed;
+                }
+                self.save(plugin).await?;
+                anyhow::bail!("{}: failed to start {}: {}", PLUGIN_LIFECYCLE_ERROR, name, e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PluginLifecycleService for DefaultPluginLifecycleService {
+    async fn list(&self, options: ListOptions) -> Result<ListResult<Plugin>> {
+        let mut result = self.extension_client.list::<Plugin>(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list plugins: {}", e))?;
+        for plugin in result.items.iter_mut() {
+            self.sync_status(plugin).await;
+        }
+        Ok(result)
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Plugin>> {
+        let Some(mut plugin) = self.extension_client.fetch::<Plugin>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))? else {
+            return Ok(None);
+        };
+        self.sync_status(&mut plugin).await;
+        Ok(Some(plugin))
+    }
+
+    async fn create(&self, mut plugin: Plugin) -> Result<Plugin> {
+        let location = plugin.status.take().and_then(|status| status.load_location);
+        plugin.status = Some(PluginStatus { load_location: location, ..PluginStatus::default() });
+        self.sync_status(&mut plugin).await;
+        let enabled = plugin.spec.enabled;
+        let plugin = self.extension_client.create(plugin).await
+            .map_err(|e| anyhow::anyhow!("Failed to create plugin: {}", e))?;
+        if !enabled {
+            return Ok(plugin);
+        }
+        self.ensure_loaded(&plugin).await?;
+        self.start(plugin).await
+    }
+
+    async fn update(&self, mut plugin: Plugin) -> Result<Plugin> {
+        let existing = self.fetch(&plugin.metadata.name).await?;
+        let enabled = plugin.spec.enabled;
+        plugin.spec.enabled = existing.spec.enabled;
+        plugin.status = existing.status;
+        let plugin = self.save(plugin).await?;
+        match (existing.spec.enabled, enabled) {
+            (false, true) => self.enable(&plugin.metadata.name).await,
+            (true, false) => self.disable(&plugin.metadata.name).await,
+            _ => Ok(plugin),
+        }
+    }
+
+    async fn delete(&self, name: &str) -> Result<bool> {
+        if self.extension_client.fetch::<Plugin>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))?
+            .is_none()
+        {
+            return Ok(false);
+        }
+        if self.plugin_manager.get_plugin(name).await.is_some() {
+            self.plugin_manager.unload_plugin(name).await
+                .map_err(|e| anyhow::anyhow!("{}: failed to unload {}: {}", PLUGIN_LIFECYCLE_ERROR, name, e))?;
+        }
+        self.extension_client.delete::<Plugin>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to delete plugin: {}", e))?;
+        Ok(true)
+    }
+
+    async fn enable(&self, name: &str) -> Result<Plugin> {
+        let mut plugin = self.fetch(name).await?;
+        plugin.spec.enabled = true;
+        self.ensure_loaded(&plugin).await?;
+        self.start(plugin).await
+    }
+
+    async fn disable(&self, name: &str) -> Result<Plugin> {
+        let mut plugin = self.fetch(name).await?;
+        plugin.spec.enabled = false;
+        if self.plugin_manager.get_plugin(name).await.is_some() {
+            plugin.status.get_or_insert_with(PluginStatus::default).phase = PluginPhase::Disabling;
+            let mut plugin = self.save(plugin).await?;
+            let result = self.plugin_manager.stop_plugin(name).await;
+            self.sync_status(&mut plugin).await;
+            let plugin = self.save(plugin).await?;
+            if let Err(e) = result {
+                anyhow::bail!("{}: failed to stop {}: {}", PLUGIN_LIFECYCLE_ERROR, name, e);
+            }
+            return Ok(plugin);
+        }
+        self.sync_status(&mut plugin).await;
+        self.save(plugin).await
+    }
+
+    async fn reload(&self, name: &str) -> Result<Plugin> {
+        let mut plugin = self.fetch(name).await?;
+        if self.plugin_manager.get_plugin(name).await.is_none() {
+            self.ensure_loaded(&plugin).await?;
+            if plugin.spec.enabled {
+                return self.start(plugin).await;
+            }
+            self.sync_status(&mut plugin).await;
+            return self.save(plugin).await;
+        }
+        let result = self.plugin_manager.reload_plugin(name).await;
+        self.sync_status(&mut plugin).await;
+        if let Err(e) = result {
+            if let Some(status) = plugin.status.as_mut() {
+                status.phase = PluginPhase::Failed;
+            }
+            self.save(plugin).await?;
+            anyhow::bail!("{}: failed to reload {}: {}", PLUGIN_LIFECYCLE_ERROR, name, e);
+        }
+        let started = plugin.status.as_ref().is_some_and(|status| status.phase == PluginPhase::Started);
+        if plugin.spec.enabled && !started {
+            return self.start(plugin).await;
+        }
+        self.save(plugin).await
+    }
+
+    async fn load_installed(&self) -> Result<Vec<Plugin>> {
+        let loaded = self.plugin_manager.scan_and_load().await?;
+        tracing::info!("Loaded {} plugins from the plugins directory", loaded.len());
+        let wrappers = self.plugin_manager.get_plugins().await;
+        for wrapper in &wrappers {
+            let name = wrapper.plugin_id();
+            let exists = self.extension_client.fetch::<Plugin>(name).await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch plugin: {}", e))?
+                .is_some();
+            if !exists {
+                self.extension_client.create(plugin_from_descriptor(&wrapper.descriptor, &wrapper.plugin_path)).await
+                    .map_err(|e| anyhow::anyhow!("Failed to create plugin: {}", e))?;
+                tracing::info!("Created plugin {} found in {}", name, wrapper.plugin_path);
+            }
+        }
+
+        let mut plugins = Vec::new();
+        for name in startup_order(wrappers.iter().map(|w| &w.descriptor))? {
+            let mut plugin = self.fetch(&name).await?;
+            if plugin.spec.enabled {
+                match self.start(plugin).await {
+                    Ok(plugin) => plugins.push(plugin),
+                    Err(e) => {
+                        tracing::warn!("Failed to start plugin {}: {}", name, e);
+                        if let Some(plugin) = self.get(&name).await? {
+                            plugins.push(plugin);
+                        }
+                    }
+                }
+            } else {
+                self.sync_status(&mut plugin).await;
+                plugins.push(self.save(plugin).await?);
+            }
+        }
+        Ok(plugins)
+    }
+
+    async fn stats(&self, name: &str) -> Result<PluginStats> {
+        if let Some(stats) = self.plugin_manager.get_stats(name).await {
+            return Ok(stats);
+        }
+        self.fetch(name).await?;
+        anyhow::bail!("{}: {}", PLUGIN_NOT_LOADED_ERROR, name)
+    }
+
+    async fn metrics(&self) -> String {
+        render_prometheus(&self.plugin_manager.get_all_stats().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enable_phases() {
+        assert_eq!(plugin_phase(true, None), PluginPhase::Pending);
+        assert_eq!(plugin_phase(true, Some(PluginState::Created)), PluginPhase::Resolved);
+        assert_eq!(plugin_phase(true, Some(PluginState::Starting)), PluginPhase::Starting);
+        assert_eq!(plugin_phase(true, Some(PluginState::Started)), PluginPhase::Started);
+        assert_eq!(plugin_phase(true, Some(PluginState::Failed)), PluginPhase::Failed);
+    }
+
+    #[test]
+    fn test_plugin_from_descriptor() {
+        let descriptor = PluginDescriptor::from_yaml(
+            "id: links\nversion: 1.2.0\nprovider: halo\nlicense: [GPL-3.0]\ndependencies:\n  comment-widget: '^1.0'\n"
+        ).unwrap();
+        let plugin = plugin_from_descriptor(&descriptor, "/plugins/links.wasm");
+        assert_eq!(plugin.metadata.name, "links");
+        assert!(plugin.spec.enabled);
+        assert_eq!(plugin.spec.version, "1.2.0");
+        assert_eq!(plugin.spec.author.unwrap().name, "halo");
+        assert_eq!(plugin.spec.license[0].name, "GPL-3.0");
+        assert_eq!(plugin.spec.plugin_dependencies["comment-widget"], "^1.0");
+        assert_eq!(plugin.status.unwrap().load_location.as_deref(), Some("/plugins/links.wasm"));
+    }
+
+    #[tokio::test]
+    async fn test_started_plugin_status_has_asset_urls() {
+        let plugins_root = tempfile::tempdir().unwrap();
+        let plugin_path = plugins_root.path().join("hello");
+        let assets = plugin_path.join(crate::plugin::PLUGIN_ASSETS_DIR);
+        std::fs::create_dir_all(assets.join("console")).unwrap();
+        std::fs::write(plugin_path.join("plugin.yaml"), "id: hello\nversion: 1.0.0\n").unwrap();
+        std::fs::write(assets.join("console/main.js"), "export default {}").unwrap();
+        std::fs::write(assets.join("console/style.css"), "body {}").unwrap();
+        std::fs::write(assets.join("logo.svg"), "<svg/>").unwrap();
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        let plugin_manager = Arc::new(flow_plugin::DefaultPluginManager::new(plugins_root.path().to_path_buf()));
+        let service = DefaultPluginLifecycleService::new(client, plugin_manager);
+
+        let plugins = service.load_installed().await.unwrap();
+
+        let status = plugins[0].status.clone().unwrap();
+        assert_eq!(status.phase, PluginPhase::Started);
+        assert_eq!(status.entry.as_deref(), Some("/plugins/hello/assets/console/main.js?version=1.0.0"));
+        assert_eq!(status.stylesheet.as_deref(), Some("/plugins/hello/assets/console/style.css?version=1.0.0"));
+        assert_eq!(status.logo.as_deref(), Some("/plugins/hello/assets/logo.svg?version=1.0.0"));
+
+        // 停用后不再提供前端资源，重新启用后恢复
+        let status = service.disable("hello").await.unwrap().status.unwrap();
+        assert_eq!(status.entry, None);
+        assert_eq!(status.logo, None);
+        let status = service.enable("hello").await.unwrap().status.unwrap();
+        assert_eq!(status.entry.as_deref(), Some("/plugins/hello/assets/console/main.js?version=1.0.0"));
+    }
+
+    #[test]
+    fn test_disable_phases() {
+        assert_eq!(plugin_phase(false, Some(PluginState::Stopping)), PluginPhase::Disabling);
+        assert_eq!(plugin_phase(false, Some(PluginState::Stopped)), PluginPhase::Disabled);
+        assert_eq!(plugin_phase(false, Some(PluginState::Created)), PluginPhase::Disabled);
+        assert_eq!(plugin_phase(true, Some(PluginState::Stopped)), PluginPhase::Stopped);
+    }
+}
@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use flow_api::extension::GroupVersionKind;
+use flow_infra::websocket::{WebSocketEndpoint, WebSocketMessage, WebSocketReceiver, WebSocketSender};
+use flow_plugin::{PluginLogBuffer, PluginLogEntry};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+/// 实时查看插件日志的WebSocket端点
+///
+/// 路径为 `/apis/api.console.halo.run/v1alpha1/plugins/-/logs`。连接后客户端发送要查看的插件名称，
+/// 服务端先发送该插件最近的日志，之后每产生一行日志发送一条JSON消息
+pub struct PluginLogEndpoint {
+    logs: Arc<PluginLogBuffer>,
+}
+
+impl PluginLogEndpoint {
+    pub fn new(logs: Arc<PluginLogBuffer>) -> Self {
+        Self { logs }
+    }
+}
+
+async fn send_entry(sender: &mut Box<dyn WebSocketSender>, entry: &PluginLogEntry) -> bool {
+    match serde_json::to_string(entry) {
+        Ok(text) => sender.send(WebSocketMessage::Text(text)).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+#[async_trait]
+impl WebSocketEndpoint for PluginLogEndpoint {
+    fn url_path(&self) -> &str {
+        "plugins/-/logs"
+    }
+
+    fn group_version(&self) -> GroupVersionKind {
+        GroupVersionKind::new("api.console.halo.run", "v1alpha1", "Plugin")
+    }
+
+    async fn handle_connection(
+        &self,
+        mut sender: Box<dyn WebSocketSender>,
+        mut receiver: Box<dyn WebSocketReceiver>,
+    ) {
+        // 第一条文本消息为插件名称
+        let plugin_id = loop {
+            match receiver.recv().await {
+                Some(Ok(WebSocketMessage::Text(text))) if !text.trim().is_empty() => break text.trim().to_string(),
+                Some(Ok(WebSocketMessage::Ping(data))) => {
+                    if sender.send(WebSocketMessage::Pong(data)).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(WebSocketMessage::Close)) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            }
+        };
+
+        // 先订阅再发送历史日志，避免两者之间的日志丢失
+        let mut live = self.logs.subscribe();
+        for entry in self.logs.recent(&plugin_id, None) {
+            if !send_entry(&mut sender, &entry).await {
+                return;
+            }
+        }
+        loop {
+            tokio::select! {
+                message = receiver.recv() => match message {
+                    Some(Ok(WebSocketMessage::Ping(data))) => {
+                        if sender.send(WebSocketMessage::Pong(data)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(WebSocketMessage::Close)) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+                entry = live.recv() => match entry {
+                    Ok(entry) if entry.plugin_id == plugin_id => {
+                        if !send_entry(&mut sender, &entry).await {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Log viewer of plugin {} skipped {} lines", plugin_id, skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+}
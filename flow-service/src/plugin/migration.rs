@@ -0,0 +1,55 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use flow_plugin::{MigrationStore, PluginMigrationState};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 保存插件迁移状态的ConfigMap（插件ID -> 迁移状态JSON）
+pub const PLUGIN_MIGRATIONS_CONFIG_MAP: &str = "plugin-migrations";
+
+/// 将插件迁移状态保存在ConfigMap中
+pub struct ConfigMapMigrationStore {
+    extension_client: Arc<ReactiveExtensionClient>,
+}
+
+impl ConfigMapMigrationStore {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client }
+    }
+
+    async fn fetch_states(&self) -> Result<Option<ConfigMap>> {
+        self.extension_client.fetch(PLUGIN_MIGRATIONS_CONFIG_MAP).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch plugin migrations: {}", e))
+    }
+}
+
+#[async_trait]
+impl MigrationStore for ConfigMapMigrationStore {
+    async fn load(&self, plugin_id: &str) -> Result<Option<PluginMigrationState>> {
+        let state = self.fetch_states().await?
+            .and_then(|config_map| config_map.data)
+            .and_then(|mut data| data.remove(plugin_id));
+        match state {
+            Some(state) => Ok(Some(serde_json::from_str(&state)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, plugin_id: &str, state: &PluginMigrationState) -> Result<()> {
+        let state = serde_json::to_string(state)?;
+        match self.fetch_states().await? {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).insert(plugin_id.to_string(), state);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(PLUGIN_MIGRATIONS_CONFIG_MAP),
+                data: Some(HashMap::from([(plugin_id.to_string(), state)])),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save migration state of plugin {}: {}", plugin_id, e))?;
+        Ok(())
+    }
+}
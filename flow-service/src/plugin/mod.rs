@@ -0,0 +1,19 @@
+pub mod capability;
+pub mod config;
+pub mod install;
+pub mod assets;
+pub mod migration;
+pub mod health;
+pub mod lifecycle;
+pub mod logs;
+pub mod host;
+
+pub use config::{PluginConfigService, DefaultPluginConfigService, PluginConfig, PLUGIN_CONFIG_INVALID_ERROR};
+pub use capability::{PluginCapabilityService, DefaultPluginCapabilityService, PluginCapabilityReview, CAPABILITY_APPROVALS_CONFIG_MAP};
+pub use install::{PluginInstallService, DefaultPluginInstallService, PluginInstallConfig, PluginInstallRequest, PluginInstallResult};
+pub use assets::{PluginAssetService, DefaultPluginAssetService, PluginAsset, PLUGIN_ASSETS_DIR};
+pub use migration::{ConfigMapMigrationStore, PLUGIN_MIGRATIONS_CONFIG_MAP};
+pub use health::{PluginHealthService, DefaultPluginHealthService, PluginProbeConfig, spawn_plugin_probe_job};
+pub use lifecycle::{PluginLifecycleService, DefaultPluginLifecycleService, PLUGIN_NOT_FOUND_ERROR, PLUGIN_NOT_LOADED_ERROR, PLUGIN_LIFECYCLE_ERROR};
+pub use logs::PluginLogEndpoint;
+pub use host::ClientExtensionHost;
@@ -1,4 +1,4 @@
-use flow_api::search::{SearchOption, SearchResult, HaloDocument, CacheStats};
+use flow_api::search::{SearchOption, SearchResult, HaloDocument, CacheStats, Suggestion};
 use flow_infra::cache::Cache;
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -204,5 +204,10 @@ impl SearchService for CachedSearchService {
         
         self.inner.delete_all().await
     }
+    
+    async fn suggest(&self, keyword: &str, limit: u32) -> Result<Vec<Suggestion>> {
+        // 建议查询只读前缀字段，足够快，不经过缓存
+        self.inner.suggest(keyword, limit).await
+    }
 }
 
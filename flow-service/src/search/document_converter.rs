@@ -1,11 +1,14 @@
 use flow_api::search::HaloDocument;
+use flow_domain::attachment::Attachment;
 use flow_domain::content::{Post, SinglePage};
+use crate::attachment::ALT_TEXT_ANNO;
 use crate::content::ContentWrapper;
 
 /// 文档类型常量
 pub mod doc_type {
     pub const POST: &str = "post.content.halo.run";
     pub const SINGLE_PAGE: &str = "singlepage.content.halo.run";
+    pub const ATTACHMENT: &str = "attachment.storage.halo.run";
 }
 
 /// 文档转换器，将内容实体转换为HaloDocument
@@ -23,13 +26,17 @@ impl DocumentConverter {
         // 获取标题
         let title = spec.title.clone();
         
+        // 加密文章不索引摘要和内容，搜索和搜索建议不会泄露受保护的文字
+        let locked = post.requires_password();
+        
         // 获取描述（从status.excerpt）
         let description = status
             .and_then(|s| s.excerpt.as_ref())
+            .filter(|_| !locked)
             .map(|s| s.clone());
         
         // 获取内容（安全内容，无HTML标签）
-        let content_text = content.content.clone();
+        let content_text = if locked { String::new() } else { content.content.clone() };
         
         // 获取分类和标签
         let categories = spec.categories.clone();
@@ -42,7 +49,7 @@ impl DocumentConverter {
         let recycled = post.is_deleted();
         
         // 获取公开状态
-        let exposed = post.is_public();
+        let exposed = post.is_public() && !locked;
         
         // 获取所有者
         let owner_name = spec.owner.clone().unwrap_or_default();
@@ -142,6 +149,55 @@ impl DocumentConverter {
         }
     }
     
+    /// 将Attachment转换为HaloDocument，extracted_text为从文件中提取的文本（如PDF正文）
+    pub fn convert_attachment(attachment: &Attachment, extracted_text: Option<&str>) -> HaloDocument {
+        let spec = &attachment.spec;
+        
+        // 标题使用显示名称，没有时使用附件名称
+        let title = spec.display_name.clone()
+            .unwrap_or_else(|| attachment.metadata.name.clone());
+        
+        // 描述使用替代文本
+        let description = attachment.metadata.annotations.as_ref()
+            .and_then(|a| a.get(ALT_TEXT_ANNO))
+            .filter(|alt| !alt.trim().is_empty())
+            .cloned();
+        
+        // 内容包含媒体类型和提取的文本，便于按类型和正文搜索
+        let content_text = [spec.media_type.as_deref(), extracted_text]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        
+        let permalink = attachment.status.as_ref()
+            .and_then(|s| s.permalink.clone())
+            .unwrap_or_default();
+        
+        let creation_timestamp = attachment.metadata.creation_timestamp;
+        
+        HaloDocument {
+            id: Self::attachment_doc_id(&attachment.metadata.name),
+            metadata_name: attachment.metadata.name.clone(),
+            annotations: attachment.metadata.annotations.clone(),
+            title,
+            description,
+            content: content_text,
+            categories: None,
+            tags: spec.tags.clone(),
+            // 附件上传后即可通过永久链接访问
+            published: true,
+            recycled: false,
+            exposed: true,
+            owner_name: spec.owner_name.clone().unwrap_or_default(),
+            creation_timestamp,
+            update_timestamp: creation_timestamp,
+            permalink,
+            doc_type: doc_type::ATTACHMENT.to_string(),
+        }
+    }
+    
     /// 生成Post的文档ID
     pub fn post_doc_id(post_name: &str) -> String {
         format!("{}-{}", doc_type::POST, post_name)
@@ -151,5 +207,43 @@ impl DocumentConverter {
     pub fn single_page_doc_id(page_name: &str) -> String {
         format!("{}-{}", doc_type::SINGLE_PAGE, page_name)
     }
+    
+    /// 生成Attachment的文档ID
+    pub fn attachment_doc_id(attachment_name: &str) -> String {
+        format!("{}-{}", doc_type::ATTACHMENT, attachment_name)
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::content::{PostSpec, PostStatus, VisibleEnum};
+
+    #[test]
+    fn test_convert_password_protected_post() {
+        let mut post = Post {
+            metadata: Metadata::new("hello"),
+            spec: serde_json::from_value::<PostSpec>(serde_json::json!({"title": "Hello", "slug": "hello"})).unwrap(),
+            status: Some(PostStatus { excerpt: Some("summary".to_string()), ..Default::default() }),
+        };
+        let content = ContentWrapper {
+            snapshot_name: "snapshot".to_string(),
+            raw: "body".to_string(),
+            content: "body".to_string(),
+            raw_type: "markdown".to_string(),
+        };
+        let document = DocumentConverter::convert_post(&post, &content);
+        assert!(document.exposed);
+        assert_eq!(document.content, "body");
+
+        post.spec.visible = Some(VisibleEnum::Private);
+        post.spec.password = Some("hash".to_string());
+        let document = DocumentConverter::convert_post(&post, &content);
+        assert!(!document.exposed);
+        assert_eq!(document.title, "Hello");
+        assert_eq!(document.description, None);
+        assert!(document.content.is_empty());
+    }
+}
@@ -1,13 +1,16 @@
-use flow_api::search::{SearchOption, SearchResult, SearchEngine, HaloDocument};
+use flow_api::search::{SearchOption, SearchResult, SearchEngine, HaloDocument, Suggestion};
 use async_trait::async_trait;
 use std::sync::Arc;
 use anyhow::Result;
+use tracing::warn;
 
 pub mod document_converter;
 pub mod cached;
+pub mod registry;
 
 pub use document_converter::DocumentConverter;
 pub use cached::{CachedSearchService, SearchStats};
+pub use registry::{SearchEngineRegistry, SearchEngineMode, SearchEngineInfo};
 
 /// 搜索服务trait
 #[async_trait]
@@ -23,55 +26,93 @@ pub trait SearchService: Send + Sync {
     
     /// 删除所有文档
     async fn delete_all(&self) -> Result<()>;
+    
+    /// 按关键词前缀返回公开文档的标题和标签建议
+    async fn suggest(&self, keyword: &str, limit: u32) -> Result<Vec<Suggestion>>;
 }
 
 /// 默认搜索服务实现
+/// 搜索由注册表中的主引擎处理，索引更新写入内置引擎和所有插件引擎
 pub struct DefaultSearchService {
-    engine: Arc<dyn SearchEngine>,
+    registry: Arc<SearchEngineRegistry>,
 }
 
 impl DefaultSearchService {
     pub fn new(engine: Arc<dyn SearchEngine>) -> Self {
-        Self { engine }
+        Self::with_registry(Arc::new(SearchEngineRegistry::new(engine)))
+    }
+    
+    /// 使用搜索引擎注册表创建，插件注册的引擎在运行时生效
+    pub fn with_registry(registry: Arc<SearchEngineRegistry>) -> Self {
+        Self { registry }
+    }
+    
+    /// 内置引擎不可用时拒绝更新索引
+    fn builtin(&self) -> Result<Arc<dyn SearchEngine>> {
+        let engine = self.registry.builtin();
+        if !engine.available() {
+            anyhow::bail!("Search engine is not available");
+        }
+        Ok(engine)
+    }
+    
+    /// 可用的插件引擎，插件引擎更新失败只记录日志
+    fn plugin_engines(&self) -> Vec<(String, Arc<dyn SearchEngine>)> {
+        self.registry.plugin_engines().into_iter()
+            .filter(|(_, engine)| engine.available())
+            .collect()
     }
 }
 
 #[async_trait]
 impl SearchService for DefaultSearchService {
     async fn search(&self, option: SearchOption) -> Result<SearchResult> {
-        if !self.engine.available() {
+        let engine = self.registry.primary();
+        if !engine.available() {
             anyhow::bail!("Search engine is not available");
         }
         
-        self.engine.search(option).await
+        engine.search(option).await
             .map_err(|e| anyhow::anyhow!("Search failed: {}", e))
     }
     
     async fn add_or_update(&self, documents: Vec<HaloDocument>) -> Result<()> {
-        if !self.engine.available() {
-            anyhow::bail!("Search engine is not available");
+        for (owner, engine) in self.plugin_engines() {
+            if let Err(e) = engine.add_or_update(documents.clone()).await {
+                warn!("Failed to add or update documents in search engine of {}: {}", owner, e);
+            }
         }
-        
-        self.engine.add_or_update(documents).await
+        self.builtin()?.add_or_update(documents).await
             .map_err(|e| anyhow::anyhow!("Failed to add or update documents: {}", e))
     }
     
     async fn delete_document(&self, doc_ids: Vec<String>) -> Result<()> {
-        if !self.engine.available() {
-            anyhow::bail!("Search engine is not available");
+        for (owner, engine) in self.plugin_engines() {
+            if let Err(e) = engine.delete_document(doc_ids.clone()).await {
+                warn!("Failed to delete documents in search engine of {}: {}", owner, e);
+            }
         }
-        
-        self.engine.delete_document(doc_ids).await
+        self.builtin()?.delete_document(doc_ids).await
             .map_err(|e| anyhow::anyhow!("Failed to delete documents: {}", e))
     }
     
     async fn delete_all(&self) -> Result<()> {
-        if !self.engine.available() {
+        for (owner, engine) in self.plugin_engines() {
+            if let Err(e) = engine.delete_all().await {
+                warn!("Failed to delete all documents in search engine of {}: {}", owner, e);
+            }
+        }
+        self.builtin()?.delete_all().await
+            .map_err(|e| anyhow::anyhow!("Failed to delete all documents: {}", e))
+    }
+    
+    async fn suggest(&self, keyword: &str, limit: u32) -> Result<Vec<Suggestion>> {
+        let engine = self.registry.primary();
+        if !engine.available() {
             anyhow::bail!("Search engine is not available");
         }
         
-        self.engine.delete_all().await
-            .map_err(|e| anyhow::anyhow!("Failed to delete all documents: {}", e))
+        engine.suggest(keyword, limit).await
+            .map_err(|e| anyhow::anyhow!("Suggest failed: {}", e))
     }
 }
-
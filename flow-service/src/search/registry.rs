@@ -0,0 +1,235 @@
+use anyhow::Result;
+use flow_api::search::{HaloDocument, SearchEngine};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+/// 重建索引时每批写入的文档数
+const HANDOFF_BATCH_SIZE: usize = 100;
+
+/// 插件搜索引擎的使用方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngineMode {
+    /// 接管搜索：搜索请求由该引擎处理
+    Replace,
+    /// 补充：只同步索引，搜索仍由当前引擎处理
+    Supplement,
+}
+
+/// 已注册的搜索引擎
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchEngineInfo {
+    /// 注册者（插件ID），内置引擎为 `builtin`
+    pub owner: String,
+    pub mode: SearchEngineMode,
+    /// 是否正在处理搜索请求
+    pub active: bool,
+}
+
+/// 内置引擎的注册者名称
+pub const BUILTIN_ENGINE: &str = "builtin";
+
+struct RegisteredEngine {
+    owner: String,
+    engine: Arc<dyn SearchEngine>,
+    mode: SearchEngineMode,
+}
+
+/// 搜索引擎注册表
+///
+/// 插件可以注册搜索引擎接管或补充内置引擎。索引更新会同时写入内置引擎和所有插件引擎，
+/// 内置引擎始终保持完整索引，插件引擎注册时从内置引擎导出文档重建索引，注销后直接回退到内置引擎
+pub struct SearchEngineRegistry {
+    builtin: Arc<dyn SearchEngine>,
+    engines: RwLock<Vec<RegisteredEngine>>,
+}
+
+impl SearchEngineRegistry {
+    pub fn new(builtin: Arc<dyn SearchEngine>) -> Self {
+        Self {
+            builtin,
+            engines: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// 注册插件的搜索引擎，同一插件重复注册时替换之前的引擎
+    ///
+    /// 注册前先清空新引擎并写入内置引擎中的所有文档，返回重建索引的文档数量；
+    /// 重建失败时不注册
+    pub async fn register(&self, owner: &str, engine: Arc<dyn SearchEngine>, mode: SearchEngineMode) -> Result<usize> {
+        let documents = self.builtin.documents().await
+            .map_err(|e| anyhow::anyhow!("Failed to export search documents: {}", e))?;
+        let count = documents.len();
+        handoff(engine.as_ref(), documents).await
+            .map_err(|e| anyhow::anyhow!("Failed to reindex search engine of {}: {}", owner, e))?;
+
+        let mut engines = self.engines.write().unwrap();
+        engines.retain(|e| e.owner != owner);
+        engines.push(RegisteredEngine { owner: owner.to_string(), engine, mode });
+        info!("Registered search engine of {} ({:?}), reindexed {} documents", owner, mode, count);
+        Ok(count)
+    }
+
+    /// 注销插件的搜索引擎
+    pub fn unregister(&self, owner: &str) -> bool {
+        let mut engines = self.engines.write().unwrap();
+        let before = engines.len();
+        engines.retain(|e| e.owner != owner);
+        engines.len() != before
+    }
+
+    /// 处理搜索请求的引擎：最后注册的可用的接管引擎，没有时为内置引擎
+    pub fn primary(&self) -> Arc<dyn SearchEngine> {
+        self.engines.read().unwrap().iter().rev()
+            .find(|e| e.mode == SearchEngineMode::Replace && e.engine.available())
+            .map(|e| e.engine.clone())
+            .unwrap_or_else(|| self.builtin.clone())
+    }
+
+    /// 内置引擎
+    pub fn builtin(&self) -> Arc<dyn SearchEngine> {
+        self.builtin.clone()
+    }
+
+    /// 需要同步索引更新的插件引擎（注册者，引擎）
+    pub fn plugin_engines(&self) -> Vec<(String, Arc<dyn SearchEngine>)> {
+        self.engines.read().unwrap().iter()
+            .map(|e| (e.owner.clone(), e.engine.clone()))
+            .collect()
+    }
+
+    /// 所有搜索引擎及其状态
+    pub fn list(&self) -> Vec<SearchEngineInfo> {
+        let engines = self.engines.read().unwrap();
+        let active = engines.iter()
+            .rposition(|e| e.mode == SearchEngineMode::Replace && e.engine.available());
+        let mut list = vec![SearchEngineInfo {
+            owner: BUILTIN_ENGINE.to_string(),
+            mode: SearchEngineMode::Replace,
+            active: active.is_none(),
+        }];
+        list.extend(engines.iter().enumerate().map(|(index, e)| SearchEngineInfo {
+            owner: e.owner.clone(),
+            mode: e.mode,
+            active: active == Some(index),
+        }));
+        list
+    }
+}
+
+/// 清空引擎并分批写入文档
+async fn handoff(engine: &dyn SearchEngine, documents: Vec<HaloDocument>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    engine.delete_all().await?;
+    for batch in documents.chunks(HANDOFF_BATCH_SIZE) {
+        engine.add_or_update(batch.to_vec()).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use flow_api::search::{SearchOption, SearchResult};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryEngine {
+        documents: Mutex<Vec<HaloDocument>>,
+    }
+
+    #[async_trait]
+    impl SearchEngine for MemoryEngine {
+        fn available(&self) -> bool {
+            true
+        }
+
+        async fn add_or_update(&self, documents: Vec<HaloDocument>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.documents.lock().unwrap().extend(documents);
+            Ok(())
+        }
+
+        async fn delete_document(&self, doc_ids: Vec<String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.documents.lock().unwrap().retain(|d| !doc_ids.contains(&d.id));
+            Ok(())
+        }
+
+        async fn delete_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.documents.lock().unwrap().clear();
+            Ok(())
+        }
+
+        async fn search(&self, option: SearchOption) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+            let hits = self.documents.lock().unwrap().clone();
+            Ok(SearchResult {
+                total: hits.len() as u64,
+                hits,
+                keyword: option.keyword,
+                limit: option.limit,
+                processing_time_millis: 0,
+                from_cache: false,
+                cache_stats: None,
+            })
+        }
+
+        async fn documents(&self) -> Result<Vec<HaloDocument>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.documents.lock().unwrap().clone())
+        }
+    }
+
+    fn document(id: &str) -> HaloDocument {
+        HaloDocument {
+            id: id.to_string(),
+            metadata_name: id.to_string(),
+            annotations: None,
+            title: id.to_string(),
+            description: None,
+            content: String::new(),
+            categories: None,
+            tags: None,
+            published: true,
+            recycled: false,
+            exposed: true,
+            owner_name: "admin".to_string(),
+            creation_timestamp: None,
+            update_timestamp: None,
+            permalink: format!("/archives/{}", id),
+            doc_type: "post.content.halo.run".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_reindexes_from_builtin() {
+        let builtin = Arc::new(MemoryEngine::default());
+        builtin.add_or_update(vec![document("a"), document("b")]).await.unwrap();
+        let registry = SearchEngineRegistry::new(builtin.clone());
+
+        let plugin = Arc::new(MemoryEngine::default());
+        plugin.add_or_update(vec![document("stale")]).await.unwrap();
+        assert_eq!(registry.register("algolia", plugin.clone(), SearchEngineMode::Replace).await.unwrap(), 2);
+        let ids: Vec<String> = plugin.documents().await.unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_primary_engine() {
+        let builtin: Arc<dyn SearchEngine> = Arc::new(MemoryEngine::default());
+        let registry = SearchEngineRegistry::new(builtin.clone());
+        let plugin: Arc<dyn SearchEngine> = Arc::new(MemoryEngine::default());
+        let mirror: Arc<dyn SearchEngine> = Arc::new(MemoryEngine::default());
+
+        registry.register("mirror", mirror.clone(), SearchEngineMode::Supplement).await.unwrap();
+        assert!(Arc::ptr_eq(&registry.primary(), &builtin));
+        registry.register("algolia", plugin.clone(), SearchEngineMode::Replace).await.unwrap();
+        assert!(Arc::ptr_eq(&registry.primary(), &plugin));
+        assert_eq!(registry.plugin_engines().len(), 2);
+        let active: Vec<bool> = registry.list().iter().map(|e| e.active).collect();
+        assert_eq!(active, vec![false, false, true]);
+
+        assert!(registry.unregister("algolia"));
+        assert!(Arc::ptr_eq(&registry.primary(), &builtin));
+        assert!(!registry.unregister("algolia"));
+    }
+}
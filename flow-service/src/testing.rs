@@ -0,0 +1,149 @@
+//! 单元测试共用的内存扩展客户端和扩展仓库，启用`testing`特性时也供其他crate的测试使用
+
+use async_trait::async_trait;
+use flow_api::extension::query::Condition;
+use flow_api::extension::{Extension, ExtensionClient, ListOptions, ListResult};
+use flow_infra::database::extension_store::Model as ExtensionStoreModel;
+use flow_infra::database::ExtensionRepository;
+use flow_infra::extension::ReactiveExtensionClient;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use crate::attachment::thumbnail::DefaultThumbnailService;
+use crate::attachment::DefaultAttachmentService;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 按类型保存扩展对象的内存客户端，与数据库仓库一样按页返回
+///
+/// 支持按字段路径（如`spec.subjectRef.name`）求值的等值、逻辑查询条件
+#[derive(Default)]
+pub struct MemoryClient(Mutex<Vec<(&'static str, String, Value)>>);
+
+impl MemoryClient {
+    /// 某类型扩展对象的数量
+    pub fn count<E: Extension>(&self) -> usize {
+        let kind = std::any::type_name::<E>();
+        self.0.lock().unwrap().iter().filter(|(k, _, _)| *k == kind).count()
+    }
+}
+
+/// 按以点分隔的字段路径读取值
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn matches(condition: &Condition, value: &Value) -> bool {
+    match condition {
+        Condition::Empty => true,
+        Condition::And { left, right } => matches(left, value) && matches(right, value),
+        Condition::Or { left, right } => matches(left, value) || matches(right, value),
+        Condition::Not { condition } => !matches(condition, value),
+        Condition::Equal { index_name, value: expected } => field(value, index_name) == Some(expected),
+        Condition::NotEqual { index_name, value: expected } => field(value, index_name) != Some(expected),
+        Condition::In { index_name, values } => field(value, index_name).is_some_and(|v| values.contains(v)),
+        other => unimplemented!("unsupported condition in MemoryClient: {:?}", other),
+    }
+}
+
+#[async_trait]
+impl ExtensionClient for MemoryClient {
+    async fn create<E: Extension + serde::Serialize>(&self, extension: E) -> Result<E, BoxError> {
+        self.update(extension).await
+    }
+
+    async fn update<E: Extension + serde::Serialize>(&self, extension: E) -> Result<E, BoxError> {
+        let kind = std::any::type_name::<E>();
+        let name = extension.metadata().name.clone();
+        let value = serde_json::to_value(&extension)?;
+        let mut store = self.0.lock().unwrap();
+        match store.iter_mut().find(|(k, n, _)| *k == kind && *n == name) {
+            Some(entry) => entry.2 = value,
+            None => store.push((kind, name, value)),
+        }
+        Ok(extension)
+    }
+
+    async fn delete<E: Extension>(&self, name: &str) -> Result<(), BoxError> {
+        let kind = std::any::type_name::<E>();
+        self.0.lock().unwrap().retain(|(k, n, _)| !(*k == kind && n == name));
+        Ok(())
+    }
+
+    async fn fetch<E: Extension + for<'de> serde::Deserialize<'de>>(&self, name: &str) -> Result<Option<E>, BoxError> {
+        let kind = std::any::type_name::<E>();
+        let store = self.0.lock().unwrap();
+        Ok(store.iter()
+            .find(|(k, n, _)| *k == kind && n == name)
+            .map(|(_, _, value)| serde_json::from_value(value.clone()))
+            .transpose()?)
+    }
+
+    async fn list<E: Extension + for<'de> serde::Deserialize<'de>>(&self, options: ListOptions) -> Result<ListResult<E>, BoxError> {
+        let kind = std::any::type_name::<E>();
+        let page = options.page.unwrap_or(0);
+        let size = options.size.unwrap_or(10);
+        let condition = options.to_condition();
+        let store = self.0.lock().unwrap();
+        let items = store.iter()
+            .filter(|(k, _, value)| *k == kind && matches(&condition, value))
+            .skip(page as usize * size as usize)
+            .take(size as usize)
+            .map(|(_, _, value)| serde_json::from_value(value.clone()))
+            .collect::<Result<Vec<E>, _>>()?;
+        let total = items.len() as u64;
+        Ok(ListResult::new(items, total, page, size))
+    }
+}
+
+/// 保存在内存中的扩展仓库，供依赖`ReactiveExtensionClient`的服务测试使用
+///
+/// 与数据库仓库一样忽略查询条件、按页返回；存储名称为`{group}/{version}/{name}`，
+/// 而客户端按对象名称查找和删除，因此名称与存储名称的最后一段相同即视为匹配
+#[derive(Default)]
+pub struct MemoryRepository(Mutex<Vec<ExtensionStoreModel>>);
+
+impl MemoryRepository {
+    fn is_named(store: &ExtensionStoreModel, name: &str) -> bool {
+        store.name == name || store.name.rsplit('/').next() == Some(name)
+    }
+}
+
+#[async_trait]
+impl ExtensionRepository for MemoryRepository {
+    async fn save(&self, store: ExtensionStoreModel) -> Result<(), BoxError> {
+        let mut stores = self.0.lock().unwrap();
+        match stores.iter_mut().find(|s| s.name == store.name) {
+            Some(existing) => *existing = store,
+            None => stores.push(store),
+        }
+        Ok(())
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<ExtensionStoreModel>, BoxError> {
+        Ok(self.0.lock().unwrap().iter().find(|s| Self::is_named(s, name)).cloned())
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), BoxError> {
+        self.0.lock().unwrap().retain(|s| !Self::is_named(s, name));
+        Ok(())
+    }
+
+    async fn list(&self, options: ListOptions) -> Result<Vec<ExtensionStoreModel>, BoxError> {
+        let page = options.page.unwrap_or(0) as usize;
+        let size = options.size.unwrap_or(10) as usize;
+        Ok(self.0.lock().unwrap().iter().skip(page * size).take(size).cloned().collect())
+    }
+}
+
+/// 使用内存扩展客户端和临时目录本地存储的附件服务，文件保存在`root/upload`下
+pub fn attachment_service(client: Arc<ReactiveExtensionClient>, root: &Path) -> DefaultAttachmentService {
+    let upload_path = root.join("upload");
+    DefaultAttachmentService::new(
+        client,
+        Arc::new(flow_infra::attachment::LocalAttachmentStorage::new(upload_path.clone())),
+        Arc::new(DefaultThumbnailService::new(root.join("thumbnails"), 0.8)),
+        upload_path,
+        "http://localhost".to_string(),
+    )
+}
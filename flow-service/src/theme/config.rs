@@ -0,0 +1,441 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_api::extension::{ExtensionClient, Metadata};
+use flow_domain::setting::{Setting, SettingForm};
+use flow_domain::theme::Theme;
+use flow_infra::extension::ReactiveExtensionClient;
+use flow_infra::system_setting::ConfigMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 主题设置值不符合表单定义时的错误信息前缀
+pub const THEME_CONFIG_INVALID_ERROR: &str = "Invalid theme config";
+
+/// 主题设置：表单定义和解析后的设置值
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeConfig {
+    /// 设置表单，主题没有设置表单时为空
+    pub forms: Vec<SettingForm>,
+    /// 设置值（分组 -> 字段 -> 值），未保存的字段使用表单中的默认值
+    pub values: Value,
+}
+
+/// 主题设置服务trait
+#[async_trait]
+pub trait ThemeConfigService: Send + Sync {
+    /// 获取主题的设置表单和设置值，主题不存在时返回None
+    async fn get_config(&self, theme_name: &str) -> Result<Option<ThemeConfig>>;
+
+    /// 按表单定义校验并保存设置值，只更新提交的分组，主题不存在时返回None
+    async fn update_config(&self, theme_name: &str, values: Value) -> Result<Option<ThemeConfig>>;
+
+    /// 获取主题解析后的设置值（用于模板中的 `theme.config`），主题不存在时返回空对象
+    async fn resolved_values(&self, theme_name: &str) -> Result<Value>;
+
+    /// 将已保存的设置值导出为ConfigMap格式的YAML，主题不存在时返回None
+    async fn export_config(&self, theme_name: &str) -> Result<Option<String>>;
+
+    /// 导入 `export_config` 导出的YAML，按表单定义校验后保存，只更新导入的分组，主题不存在时返回None
+    async fn import_config(&self, theme_name: &str, yaml: &str) -> Result<Option<ThemeConfig>>;
+}
+
+/// 默认主题设置服务实现
+pub struct DefaultThemeConfigService {
+    extension_client: Arc<ReactiveExtensionClient>,
+}
+
+impl DefaultThemeConfigService {
+    pub fn new(extension_client: Arc<ReactiveExtensionClient>) -> Self {
+        Self { extension_client }
+    }
+
+    /// 获取主题及其设置表单
+    async fn fetch_theme(&self, theme_name: &str) -> Result<Option<(Theme, Vec<SettingForm>)>> {
+        let theme: Option<Theme> = self.extension_client.fetch(theme_name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch theme: {}", e))?;
+        let Some(theme) = theme else {
+            return Ok(None);
+        };
+        let forms = match theme.spec.setting_name.as_deref() {
+            Some(setting_name) => {
+                let setting: Option<Setting> = self.extension_client.fetch(setting_name).await
+                    .map_err(|e| anyhow::anyhow!("Failed to fetch setting {}: {}", setting_name, e))?;
+                setting.map(|s| s.spec.forms).unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        Ok(Some((theme, forms)))
+    }
+
+    async fn fetch_config_map(&self, theme: &Theme) -> Result<Option<ConfigMap>> {
+        let name = config_map_name(theme);
+        self.extension_client.fetch(&name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch ConfigMap {}: {}", name, e))
+    }
+}
+
+/// 主题保存设置值的ConfigMap名称，未指定时为 `{主题名称}-config`
+pub(crate) fn config_map_name(theme: &Theme) -> String {
+    theme.spec.config_map_name.clone()
+        .unwrap_or_else(|| format!("{}-config", theme.metadata.name))
+}
+
+/// 读取ConfigMap中保存的设置值，每个分组的值为JSON对象字符串
+pub(crate) fn stored_values(config_map: Option<&ConfigMap>) -> HashMap<String, Map<String, Value>> {
+    let Some(data) = config_map.and_then(|c| c.data.as_ref()) else {
+        return HashMap::new();
+    };
+    data.iter()
+        .filter_map(|(group, json)| match serde_json::from_str::<Map<String, Value>>(json) {
+            Ok(values) => Some((group.clone(), values)),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid config group {}: {}", group, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 导出文件的ConfigMap格式，data中每个分组的值为对象（而非JSON字符串），便于阅读和修改
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigExport {
+    #[serde(rename = "apiVersion", default)]
+    api_version: String,
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    metadata: Option<ExportMetadata>,
+    #[serde(default)]
+    data: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportMetadata {
+    name: String,
+}
+
+/// 将表单中定义的分组的已保存设置值导出为YAML
+fn export_yaml(name: &str, forms: &[SettingForm], stored: &HashMap<String, Map<String, Value>>) -> Result<String> {
+    let data = forms.iter()
+        .filter_map(|form| stored.get(&form.group).map(|values| (form.group.clone(), Value::Object(values.clone()))))
+        .collect();
+    let export = ConfigExport {
+        api_version: "v1alpha1".to_string(),
+        kind: "ConfigMap".to_string(),
+        metadata: Some(ExportMetadata { name: name.to_string() }),
+        data,
+    };
+    Ok(serde_yaml::to_string(&export)?)
+}
+
+/// 解析导入的YAML，返回 `{分组: {字段: 值}}`；分组的值也可以是JSON对象字符串（直接导出的ConfigMap）
+fn parse_import(yaml: &str) -> Result<Value> {
+    let export: ConfigExport = serde_yaml::from_str(yaml)
+        .map_err(|e| anyhow::anyhow!("{}: {}", THEME_CONFIG_INVALID_ERROR, e))?;
+    if !export.kind.is_empty() && export.kind != "ConfigMap" {
+        anyhow::bail!("{}: unexpected kind {}", THEME_CONFIG_INVALID_ERROR, export.kind);
+    }
+    let mut values = Map::new();
+    for (group, value) in export.data {
+        let value = match value {
+            Value::String(json) => serde_json::from_str(&json)
+                .map_err(|e| anyhow::anyhow!("{}: {}: {}", THEME_CONFIG_INVALID_ERROR, group, e))?,
+            value => value,
+        };
+        values.insert(group, value);
+    }
+    Ok(Value::Object(values))
+}
+
+/// 表单中定义了名称的字段
+fn named_fields(form: &SettingForm) -> impl Iterator<Item = (&str, &Value)> {
+    form.form_schema.iter()
+        .filter_map(|field| field.get("name").and_then(Value::as_str).map(|name| (name, field)))
+}
+
+/// 用保存的设置值覆盖表单默认值
+pub(crate) fn resolve_values(forms: &[SettingForm], stored: &HashMap<String, Map<String, Value>>) -> Value {
+    let mut values = Map::new();
+    for form in forms {
+        let mut group: Map<String, Value> = named_fields(form)
+            .filter_map(|(name, field)| field.get("value").map(|value| (name.to_string(), value.clone())))
+            .collect();
+        if let Some(saved) = stored.get(&form.group) {
+            group.extend(saved.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        values.insert(form.group.clone(), Value::Object(group));
+    }
+    Value::Object(values)
+}
+
+/// 按表单定义校验提交的设置值，返回所有错误
+pub(crate) fn validate_values(forms: &[SettingForm], values: &Value) -> std::result::Result<Map<String, Value>, Vec<String>> {
+    let Some(groups) = values.as_object() else {
+        return Err(vec!["values must be an object".to_string()]);
+    };
+    let mut errors = Vec::new();
+    for (group, group_values) in groups {
+        let Some(form) = forms.iter().find(|f| &f.group == group) else {
+            errors.push(format!("{}: unknown group", group));
+            continue;
+        };
+        let Some(group_values) = group_values.as_object() else {
+            errors.push(format!("{}: must be an object", group));
+            continue;
+        };
+        for name in group_values.keys() {
+            if !named_fields(form).any(|(field_name, _)| field_name == name) {
+                errors.push(format!("{}.{}: unknown field", group, name));
+            }
+        }
+        for (name, field) in named_fields(form) {
+            let value = group_values.get(name).unwrap_or(&Value::Null);
+            if let Err(e) = validate_field(field, value) {
+                errors.push(format!("{}.{}: {}", group, name, e));
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(groups.clone())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 按字段类型（`$formkit`）和校验规则（`validation`）校验单个字段
+fn validate_field(field: &Value, value: &Value) -> std::result::Result<(), String> {
+    let rules: Vec<&str> = field.get("validation")
+        .and_then(Value::as_str)
+        .map(|v| v.split('|').map(str::trim).filter(|r| !r.is_empty()).collect())
+        .unwrap_or_default();
+    let is_empty = match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    };
+    if is_empty {
+        return match rules.contains(&"required") {
+            true => Err("is required".to_string()),
+            false => Ok(()),
+        };
+    }
+
+    let kind = field.get("$formkit").and_then(Value::as_str).unwrap_or("text");
+    let options = field_options(field);
+    let multiple = field.get("multiple").and_then(Value::as_bool).unwrap_or(false);
+    let check_option = |value: &Value| match &options {
+        Some(options) if !options.contains(value) => Err(format!("{} is not an allowed option", value)),
+        _ => Ok(()),
+    };
+    match kind {
+        "number" | "range" if !value.is_number() => return Err("must be a number".to_string()),
+        "checkbox" if options.is_some() => {
+            let items = value.as_array().ok_or("must be an array")?;
+            items.iter().try_for_each(check_option)?;
+        }
+        "checkbox" | "switch" | "toggle" if !value.is_boolean() => return Err("must be a boolean".to_string()),
+        "select" | "radio" if multiple => {
+            let items = value.as_array().ok_or("must be an array")?;
+            items.iter().try_for_each(check_option)?;
+        }
+        "select" | "radio" => check_option(value)?,
+        "repeater" | "list" if !value.is_array() => return Err("must be an array".to_string()),
+        "group" if !value.is_object() => return Err("must be an object".to_string()),
+        "text" | "textarea" | "url" | "email" | "color" | "password" | "code" | "date" | "time"
+        | "datetime-local" | "attachment" | "tel" | "search" if !value.is_string() => {
+            return Err("must be a string".to_string());
+        }
+        _ => {}
+    }
+
+    for rule in rules {
+        let (rule, args) = rule.split_once(':').unwrap_or((rule, ""));
+        match rule {
+            "url" => {
+                let url = value.as_str().unwrap_or_default();
+                if !(url.starts_with("http://") || url.starts_with("https://") || url.starts_with('/')) {
+                    return Err("must be a URL".to_string());
+                }
+            }
+            "email" => {
+                let email = value.as_str().unwrap_or_default();
+                let valid = email.split_once('@')
+                    .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'));
+                if !valid {
+                    return Err("must be an email address".to_string());
+                }
+            }
+            "number" if !value.is_number() => return Err("must be a number".to_string()),
+            "min" | "max" => {
+                let (Some(number), Ok(bound)) = (value.as_f64(), args.parse::<f64>()) else {
+                    continue;
+                };
+                if rule == "min" && number < bound {
+                    return Err(format!("must be at least {}", args));
+                }
+                if rule == "max" && number > bound {
+                    return Err(format!("must be at most {}", args));
+                }
+            }
+            "length" => {
+                let len = match value {
+                    Value::String(s) => s.chars().count(),
+                    Value::Array(items) => items.len(),
+                    _ => continue,
+                };
+                let mut bounds = args.split(',').map(|b| b.trim().parse::<usize>().ok());
+                let min = bounds.next().flatten().unwrap_or(0);
+                let max = bounds.next().flatten().unwrap_or(usize::MAX);
+                if len < min {
+                    return Err(format!("length must be at least {}", min));
+                }
+                if len > max {
+                    return Err(format!("length must be at most {}", max));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 字段的可选值，支持字符串数组、`{label, value}` 对象数组和 `value -> label` 映射
+fn field_options(field: &Value) -> Option<Vec<Value>> {
+    match field.get("options")? {
+        Value::Array(options) => Some(options.iter()
+            .map(|option| option.get("value").cloned().unwrap_or_else(|| option.clone()))
+            .collect()),
+        Value::Object(options) => Some(options.keys().map(|k| Value::String(k.clone())).collect()),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl ThemeConfigService for DefaultThemeConfigService {
+    async fn get_config(&self, theme_name: &str) -> Result<Option<ThemeConfig>> {
+        let Some((theme, forms)) = self.fetch_theme(theme_name).await? else {
+            return Ok(None);
+        };
+        let config_map = self.fetch_config_map(&theme).await?;
+        let values = resolve_values(&forms, &stored_values(config_map.as_ref()));
+        Ok(Some(ThemeConfig { forms, values }))
+    }
+
+    async fn update_config(&self, theme_name: &str, values: Value) -> Result<Option<ThemeConfig>> {
+        let Some((theme, forms)) = self.fetch_theme(theme_name).await? else {
+            return Ok(None);
+        };
+        let groups = validate_values(&forms, &values)
+            .map_err(|errors| anyhow::anyhow!("{}: {}", THEME_CONFIG_INVALID_ERROR, errors.join("; ")))?;
+
+        let mut data = HashMap::new();
+        for (group, group_values) in &groups {
+            data.insert(group.clone(), serde_json::to_string(group_values)?);
+        }
+        let config_map = match self.fetch_config_map(&theme).await? {
+            Some(mut config_map) => {
+                config_map.data.get_or_insert_with(HashMap::new).extend(data);
+                self.extension_client.update(config_map).await
+            }
+            None => self.extension_client.create(ConfigMap {
+                metadata: Metadata::new(config_map_name(&theme)),
+                data: Some(data),
+            }).await,
+        }.map_err(|e| anyhow::anyhow!("Failed to save theme config: {}", e))?;
+
+        let values = resolve_values(&forms, &stored_values(Some(&config_map)));
+        Ok(Some(ThemeConfig { forms, values }))
+    }
+
+    async fn resolved_values(&self, theme_name: &str) -> Result<Value> {
+        Ok(self.get_config(theme_name).await?
+            .map(|config| config.values)
+            .unwrap_or_else(|| Value::Object(Map::new())))
+    }
+
+    async fn export_config(&self, theme_name: &str) -> Result<Option<String>> {
+        let Some((theme, forms)) = self.fetch_theme(theme_name).await? else {
+            return Ok(None);
+        };
+        let config_map = self.fetch_config_map(&theme).await?;
+        let yaml = export_yaml(&config_map_name(&theme), &forms, &stored_values(config_map.as_ref()))?;
+        Ok(Some(yaml))
+    }
+
+    async fn import_config(&self, theme_name: &str, yaml: &str) -> Result<Option<ThemeConfig>> {
+        let values = parse_import(yaml)?;
+        self.update_config(theme_name, values).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn forms() -> Vec<SettingForm> {
+        serde_json::from_value(json!([{
+            "group": "style",
+            "label": "样式",
+            "formSchema": [
+                { "$formkit": "text", "name": "title", "value": "我的博客", "validation": "required|length:1,20" },
+                { "$formkit": "select", "name": "layout", "value": "grid", "options": [
+                    { "label": "网格", "value": "grid" },
+                    { "label": "列表", "value": "list" }
+                ]},
+                { "$formkit": "number", "name": "columns", "validation": "min:1|max:4" },
+                { "$formkit": "url", "name": "logo", "validation": "url" }
+            ]
+        }])).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_values_overrides_defaults() {
+        let stored = HashMap::from([
+            ("style".to_string(), json!({ "layout": "list" }).as_object().unwrap().clone()),
+        ]);
+        let values = resolve_values(&forms(), &stored);
+        assert_eq!(values, json!({ "style": { "title": "我的博客", "layout": "list" } }));
+        assert_eq!(resolve_values(&[], &stored), json!({}));
+    }
+
+    #[test]
+    fn test_validate_values() {
+        let valid = json!({ "style": { "title": "新标题", "layout": "grid", "columns": 3, "logo": "/logo.png" } });
+        assert!(validate_values(&forms(), &valid).is_ok());
+
+        let invalid = json!({
+            "style": { "title": "", "layout": "masonry", "columns": 6, "logo": "logo.png", "extra": 1 },
+            "unknown": {}
+        });
+        let errors = validate_values(&forms(), &invalid).unwrap_err();
+        assert_eq!(errors.len(), 6);
+        assert!(errors.contains(&"style.title: is required".to_string()));
+        assert!(errors.contains(&"style.columns: must be at most 4".to_string()));
+        assert!(errors.contains(&"unknown: unknown group".to_string()));
+    }
+
+    #[test]
+    fn test_export_and_import_yaml() {
+        let stored = HashMap::from([
+            ("style".to_string(), json!({ "layout": "list", "columns": 2 }).as_object().unwrap().clone()),
+            ("removed".to_string(), json!({ "old": true }).as_object().unwrap().clone()),
+        ]);
+        let yaml = export_yaml("earth-config", &forms(), &stored).unwrap();
+        assert!(yaml.contains("kind: ConfigMap"));
+        assert!(yaml.contains("name: earth-config"));
+        assert!(!yaml.contains("removed"));
+        assert_eq!(parse_import(&yaml).unwrap(), json!({ "style": { "layout": "list", "columns": 2 } }));
+
+        // 直接导出的ConfigMap中分组的值为JSON字符串
+        let raw = "data:\n  style: '{\"layout\":\"grid\"}'\n";
+        assert_eq!(parse_import(raw).unwrap(), json!({ "style": { "layout": "grid" } }));
+
+        let err = parse_import("kind: Theme\n").unwrap_err().to_string();
+        assert!(err.starts_with(THEME_CONFIG_INVALID_ERROR));
+        assert!(parse_import("data: [").unwrap_err().to_string().starts_with(THEME_CONFIG_INVALID_ERROR));
+    }
+}
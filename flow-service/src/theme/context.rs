@@ -0,0 +1,276 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::content::MenuService;
+use crate::security::UserService;
+use reqwest::Url;
+use flow_infra::system_setting::{BasicSetting, SystemSettingService};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 渲染主题页面的请求信息
+#[derive(Debug, Clone, Default)]
+pub struct RenderRequest {
+    /// 请求路径
+    pub path: String,
+    /// 查询参数
+    pub query: HashMap<String, String>,
+    /// 从请求中推断的站点地址（未配置外部访问地址时使用）
+    pub site_url: String,
+    /// 当前登录的用户名，匿名访问时为None
+    pub username: Option<String>,
+}
+
+/// 模板上下文提供者，为每次主题渲染提供一个全局模板变量
+#[async_trait]
+pub trait TemplateContextContributor: Send + Sync {
+    /// 模板变量名称
+    fn name(&self) -> &str;
+
+    /// 生成模板变量的值
+    async fn contribute(&self, request: &RenderRequest) -> Result<Value>;
+}
+
+/// 模板上下文提供者注册表，插件可注册额外的全局模板变量
+pub struct TemplateContextRegistry {
+    contributors: RwLock<Vec<Arc<dyn TemplateContextContributor>>>,
+}
+
+impl TemplateContextRegistry {
+    pub fn new() -> Self {
+        Self {
+            contributors: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// 注册提供者，同名提供者会被替换
+    pub fn register(&self, contributor: Arc<dyn TemplateContextContributor>) {
+        let mut contributors = self.contributors.write().unwrap();
+        contributors.retain(|c| c.name() != contributor.name());
+        contributors.push(contributor);
+    }
+
+    /// 移除指定名称的提供者
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn TemplateContextContributor>> {
+        let mut contributors = self.contributors.write().unwrap();
+        let index = contributors.iter().position(|c| c.name() == name)?;
+        Some(contributors.remove(index))
+    }
+
+    /// 已注册的提供者名称
+    pub fn names(&self) -> Vec<String> {
+        self.contributors.read().unwrap().iter().map(|c| c.name().to_string()).collect()
+    }
+
+    /// 生成全部全局模板变量，单个提供者出错时只记录日志
+    pub async fn contribute(&self, request: &RenderRequest) -> HashMap<String, Value> {
+        let contributors = self.contributors.read().unwrap().clone();
+        let mut variables = HashMap::new();
+        for contributor in contributors {
+            match contributor.contribute(request).await {
+                Ok(value) => {
+                    variables.insert(contributor.name().to_string(), value);
+                }
+                Err(e) => tracing::warn!("Failed to contribute {} to template context: {}", contributor.name(), e),
+            }
+        }
+        variables
+    }
+}
+
+impl Default for TemplateContextRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 站点信息（模板中的 `site`），站点地址优先使用配置的外部访问地址
+pub fn site_value(setting: &BasicSetting, site_url: &str) -> Value {
+    serde_json::json!({
+        "title": setting.title,
+        "subtitle": setting.subtitle,
+        "logo": setting.logo,
+        "favicon": setting.favicon,
+        "url": site_url.trim_end_matches('/'),
+    })
+}
+
+/// 请求信息（模板中的 `request`），url为包含查询参数的完整地址
+pub fn request_value(request: &RenderRequest, site_url: &str) -> Value {
+    let mut url = format!("{}{}", site_url.trim_end_matches('/'), request.path);
+    if !request.query.is_empty() {
+        let mut query: Vec<(&String, &String)> = request.query.iter().collect();
+        query.sort();
+        if let Ok(mut parsed) = Url::parse(&url) {
+            parsed.query_pairs_mut().extend_pairs(query);
+            url = parsed.to_string();
+        }
+    }
+    serde_json::json!({
+        "path": request.path,
+        "query": request.query,
+        "url": url,
+    })
+}
+
+/// 站点基本设置提供者（`site`）
+pub struct SiteContextContributor {
+    system_setting_service: Arc<dyn SystemSettingService>,
+    external_url: Option<String>,
+}
+
+impl SiteContextContributor {
+    pub fn new(system_setting_service: Arc<dyn SystemSettingService>) -> Self {
+        Self { system_setting_service, external_url: None }
+    }
+
+    /// 设置站点的外部访问地址，优先于请求中的地址
+    pub fn with_external_url(mut self, external_url: Option<String>) -> Self {
+        self.external_url = external_url;
+        self
+    }
+}
+
+#[async_trait]
+impl TemplateContextContributor for SiteContextContributor {
+    fn name(&self) -> &str {
+        "site"
+    }
+
+    async fn contribute(&self, request: &RenderRequest) -> Result<Value> {
+        let setting = self.system_setting_service.get_basic_setting().await?.unwrap_or_default();
+        Ok(site_value(&setting, self.external_url.as_deref().unwrap_or(&request.site_url)))
+    }
+}
+
+/// 当前登录用户提供者（`currentUser`），匿名访问时为null
+pub struct CurrentUserContextContributor {
+    user_service: Arc<dyn UserService>,
+}
+
+impl CurrentUserContextContributor {
+    pub fn new(user_service: Arc<dyn UserService>) -> Self {
+        Self { user_service }
+    }
+}
+
+#[async_trait]
+impl TemplateContextContributor for CurrentUserContextContributor {
+    fn name(&self) -> &str {
+        "currentUser"
+    }
+
+    async fn contribute(&self, request: &RenderRequest) -> Result<Value> {
+        let Some(username) = &request.username else {
+            return Ok(Value::Null);
+        };
+        let user = self.user_service.get(username).await
+            .map_err(|e| anyhow::anyhow!("Failed to get user {}: {}", username, e))?;
+        // 只暴露公开资料，不包含邮箱等敏感信息
+        Ok(user.map(|user| serde_json::json!({
+            "name": user.metadata.name,
+            "displayName": user.spec.display_name,
+            "avatar": user.spec.avatar,
+            "bio": user.spec.bio,
+        })).unwrap_or(Value::Null))
+    }
+}
+
+/// 主导航菜单提供者（`menu`）
+pub struct MenuContextContributor {
+    menu_service: Arc<dyn MenuService>,
+}
+
+impl MenuContextContributor {
+    pub fn new(menu_service: Arc<dyn MenuService>) -> Self {
+        Self { menu_service }
+    }
+}
+
+#[async_trait]
+impl TemplateContextContributor for MenuContextContributor {
+    fn name(&self) -> &str {
+        "menu"
+    }
+
+    async fn contribute(&self, _request: &RenderRequest) -> Result<Value> {
+        match self.menu_service.get_primary_tree().await {
+            Ok(Some(tree)) => Ok(serde_json::to_value(tree)?),
+            Ok(None) => Ok(Value::Null),
+            Err(e) => Err(anyhow::anyhow!("Failed to get primary menu: {}", e)),
+        }
+    }
+}
+
+/// 请求信息提供者（`request`）
+pub struct RequestContextContributor {
+    external_url: Option<String>,
+}
+
+impl RequestContextContributor {
+    pub fn new() -> Self {
+        Self { external_url: None }
+    }
+
+    /// 设置站点的外部访问地址，优先于请求中的地址
+    pub fn with_external_url(mut self, external_url: Option<String>) -> Self {
+        self.external_url = external_url;
+        self
+    }
+}
+
+impl Default for RequestContextContributor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TemplateContextContributor for RequestContextContributor {
+    fn name(&self) -> &str {
+        "request"
+    }
+
+    async fn contribute(&self, request: &RenderRequest) -> Result<Value> {
+        Ok(request_value(request, self.external_url.as_deref().unwrap_or(&request.site_url)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_value() {
+        let setting = BasicSetting {
+            title: Some("Flow".to_string()),
+            logo: Some("/upload/logo.png".to_string()),
+            ..Default::default()
+        };
+        let site = site_value(&setting, "https://example.com/");
+        assert_eq!(site["title"], "Flow");
+        assert_eq!(site["logo"], "/upload/logo.png");
+        assert_eq!(site["subtitle"], Value::Null);
+        assert_eq!(site["url"], "https://example.com");
+    }
+
+    #[test]
+    fn test_request_value() {
+        let request = RenderRequest {
+            path: "/archives".to_string(),
+            query: HashMap::from([
+                ("q".to_string(), "a b".to_string()),
+                ("page".to_string(), "2".to_string()),
+            ]),
+            site_url: "http://localhost:8090".to_string(),
+            username: None,
+        };
+        let value = request_value(&request, "https://example.com/");
+        assert_eq!(value["path"], "/archives");
+        assert_eq!(value["query"]["page"], "2");
+        assert_eq!(value["url"], "https://example.com/archives?page=2&q=a+b");
+
+        let request = RenderRequest { query: HashMap::new(), ..request };
+        assert_eq!(request_value(&request, &request.site_url)["url"], "http://localhost:8090/archives");
+    }
+}
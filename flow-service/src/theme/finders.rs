@@ -1,26 +1,205 @@
 use flow_api::theme::Finder;
-use crate::content::{PostService, CategoryService, TagService};
+use flow_api::extension::ListOptions;
+use crate::content::{PostService, CategoryService, TagService, SeriesService, MenuService, LinkService, MomentService, MomentQuery, PhotoService, SinglePageService, CommentService, PostQuery, ListedPost, ArchiveQuery, translation, page_tree, public_comment, PublicComment, PublicCommentQuery};
 use crate::theme::ThemeService;
+use crate::security::UserService;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
 use anyhow::Result;
+use flow_domain::content::{constant, Post, SinglePage, Tag, VisibleEnum};
+
+/// 列表页每页显示的默认文章数量
+pub const DEFAULT_PAGE_SIZE: u32 = 10;
+
+/// 按slug或链接查找内容时每次列出的最大数量
+const SCAN_SIZE: u32 = 1000;
+
+/// 构建分页数据，page从1开始
+pub fn page_value(items: Vec<Value>, total: u64, page: u32, size: u32) -> Value {
+    let total_pages = total.div_ceil(size.max(1) as u64);
+    serde_json::json!({
+        "items": items,
+        "total": total,
+        "page": page,
+        "size": size,
+        "totalPages": total_pages,
+        "hasPrevious": page > 1,
+        "hasNext": (page as u64) < total_pages,
+        "prevPage": (page > 1).then(|| page - 1),
+        "nextPage": ((page as u64) < total_pages).then(|| page + 1),
+    })
+}
+
+/// 对已排序的全部条目分页，page从1开始
+fn paginate(items: Vec<Value>, page: u32, size: u32) -> Value {
+    let total = items.len() as u64;
+    let skip = (page.saturating_sub(1) as usize).saturating_mul(size as usize);
+    let items = items.into_iter().skip(skip).take(size as usize).collect();
+    page_value(items, total, page, size)
+}
+
+/// 去掉路径末尾的页码部分（`/page/{n}`），得到列表的第一页路径
+pub fn page_base_path(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    match trimmed.rsplit_once("/page/") {
+        Some((base, page)) if !page.is_empty() && page.bytes().all(|b| b.is_ascii_digit()) => {
+            if base.is_empty() { "/" } else { base }
+        }
+        _ => path,
+    }
+}
+
+/// 列表指定页的链接，第一页为列表路径本身，其他页为 `{列表路径}/page/{n}`
+pub fn page_url(base_path: &str, page: u32) -> String {
+    let base = base_path.trim_end_matches('/');
+    match page {
+        0 | 1 if base.is_empty() => "/".to_string(),
+        0 | 1 => base.to_string(),
+        _ => format!("{}/page/{}", base, page),
+    }
+}
+
+/// 为分页数据添加上一页和下一页的链接（`prevUrl`、`nextUrl`）
+pub fn with_page_urls(mut value: Value, base_path: &str) -> Value {
+    let url = |key: &str| value.get(key)
+        .and_then(Value::as_u64)
+        .map(|page| Value::String(page_url(base_path, page as u32)))
+        .unwrap_or(Value::Null);
+    let (prev_url, next_url) = (url("prevPage"), url("nextPage"));
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("prevUrl".to_string(), prev_url);
+        obj.insert("nextUrl".to_string(), next_url);
+    }
+    value
+}
 
 /// PostFinder - 在模板中查询Post数据
 /// 注意：Finder的数据查询在模板渲染前预加载，然后通过TemplateContext传递给模板
 pub struct PostFinder {
     post_service: Arc<dyn PostService>,
+    series_service: Option<Arc<dyn SeriesService>>,
+    /// 当前访问的语言，设置后优先返回该语言的文章版本
+    language: Option<String>,
+    /// 当前请求已解锁的加密文章，其余加密文章不返回内容和摘要
+    unlocked: Option<String>,
 }
 
 impl PostFinder {
     pub fn new(post_service: Arc<dyn PostService>) -> Self {
-        Self { post_service }
+        Self {
+            post_service,
+            series_service: None,
+            language: None,
+            unlocked: None,
+        }
+    }
+    
+    /// 设置当前请求已通过密码验证的加密文章
+    pub fn with_unlocked(mut self, post_name: &str) -> Self {
+        self.unlocked = Some(post_name.to_string());
+        self
+    }
+    
+    /// 文章需要密码且当前请求未解锁
+    fn is_locked(&self, post: &Post) -> bool {
+        post.requires_password() && self.unlocked.as_deref() != Some(post.metadata.name.as_str())
+    }
+    
+    /// 移除未解锁的加密文章的摘要
+    fn redact_locked(&self, post: &mut Post) {
+        if self.is_locked(post) {
+            post.spec.excerpt = None;
+            if let Some(status) = post.status.as_mut() {
+                status.excerpt = None;
+            }
+        }
+    }
+    
+    /// 设置当前访问的语言
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language.filter(|l| !l.is_empty());
+        self
+    }
+    
+    /// 设置Series服务，用于在结果中附加系列的上一篇/下一篇导航
+    pub fn with_series_service(mut self, series_service: Arc<dyn SeriesService>) -> Self {
+        self.series_service = Some(series_service);
+        self
+    }
+    
+    /// 在Post结果中附加系列导航数据（`series`字段）
+    async fn attach_series_navigation(&self, post_name: &str, value: &mut Value) {
+        let Some(series_service) = &self.series_service else {
+            return;
+        };
+        match series_service.navigation_for_post(post_name).await {
+            Ok(Some(navigation)) => {
+                if let (Some(obj), Ok(nav)) = (value.as_object_mut(), serde_json::to_value(navigation)) {
+                    obj.insert("series".to_string(), nav);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to get series navigation for post {}: {}", post_name, e),
+        }
+    }
+    
+    /// 在Post结果中附加多语言数据（`hreflang`字段）
+    fn attach_hreflang(translations: &[Post], value: &mut Value) {
+        let links = translation::build_hreflang(translations);
+        if let (Some(obj), Ok(links)) = (value.as_object_mut(), serde_json::to_value(links)) {
+            obj.insert("hreflang".to_string(), links);
+        }
+    }
+    
+    /// 移除不应暴露给主题的字段（访问密码哈希）
+    fn sanitize(value: &mut Value) {
+        if let Some(spec) = value.get_mut("spec").and_then(Value::as_object_mut) {
+            spec.remove("password");
+        }
+    }
+    
+    /// 将Post转换为模板数据，按当前语言切换到对应的翻译版本并附加导航数据
+    async fn to_template_value(&self, post: Post) -> Result<Value> {
+        let translations = match self.post_service.list_translations(&post.metadata.name).await {
+            Ok(translations) => translations,
+            Err(e) => {
+                tracing::warn!("Failed to list translations for post {}: {}", post.metadata.name, e);
+                Vec::new()
+            }
+        };
+        
+        let post = match self.language.as_deref() {
+            Some(language) if post.spec.language.as_deref() != Some(language) => {
+                translation::find_translation(&translations, language)
+                    .filter(|p| p.is_published())
+                    .cloned()
+                    .unwrap_or(post)
+            }
+            _ => post,
+        };
+        
+        let mut post = post;
+        self.redact_locked(&mut post);
+        let name = post.metadata.name.clone();
+        let contributors = post.status.as_ref()
+            .and_then(|s| s.contributors.clone())
+            .unwrap_or_else(|| post.merge_contributors(None));
+        let mut value = serde_json::to_value(post)?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("contributors".to_string(), serde_json::to_value(contributors)?);
+        }
+        Self::sanitize(&mut value);
+        self.attach_series_navigation(&name, &mut value).await;
+        Self::attach_hreflang(&translations, &mut value);
+        Ok(value)
     }
     
     /// 根据名称获取Post（用于模板渲染前预加载）
     pub async fn get_by_name(&self, name: &str) -> Result<Value> {
         match self.post_service.get_by_username(name, "").await {
-            Ok(Some(post)) => Ok(serde_json::to_value(post)?),
+            Ok(Some(post)) => self.to_template_value(post).await,
             Ok(None) => Ok(Value::Null),
             Err(e) => Err(anyhow::anyhow!("Failed to get post: {}", e)),
         }
@@ -40,7 +219,7 @@ impl PostFinder {
                 // 查找匹配slug的Post
                 for listed_post in result.items {
                     if listed_post.post.spec.slug == slug {
-                        return Ok(serde_json::to_value(listed_post.post)?);
+                        return self.to_template_value(listed_post.post).await;
                     }
                 }
                 Ok(Value::Null)
@@ -52,13 +231,90 @@ impl PostFinder {
     /// 列出Posts（用于模板渲染前预加载）
     pub async fn list(&self, query: Option<crate::content::PostQuery>) -> Result<Value> {
         
-        let query = query.unwrap_or_default();
+        let mut query = query.unwrap_or_default();
+        if query.language.is_none() {
+            query.language = self.language.clone();
+        }
         
         match self.post_service.list_post(query).await {
-            Ok(result) => Ok(serde_json::to_value(result.items)?),
+            Ok(mut result) => {
+                for item in result.items.iter_mut() {
+                    self.redact_locked(&mut item.post);
+                }
+                let mut value = serde_json::to_value(result.items)?;
+                if let Some(items) = value.as_array_mut() {
+                    for item in items.iter_mut().filter_map(|item| item.get_mut("post")) {
+                        Self::sanitize(item);
+                    }
+                }
+                Ok(value)
+            }
             Err(e) => Err(anyhow::anyhow!("Failed to list posts: {}", e)),
         }
     }
+    
+    /// 分页列出公开的已发布文章，置顶文章在前，其余按发布时间倒序（用于首页、分类和标签页面）
+    ///
+    /// page从1开始，返回 `{items, total, page, size, totalPages, hasPrevious, hasNext, prevPage, nextPage}`
+    pub async fn list_page(&self, query: PostQuery, page: u32, size: u32) -> Result<Value> {
+        let query = PostQuery {
+            published: Some(true),
+            language: query.language.or_else(|| self.language.clone()),
+            page: None,
+            size: Some(SCAN_SIZE),
+            ..query
+        };
+        let result = self.post_service.list_post(query).await
+            .map_err(|e| anyhow::anyhow!("Failed to list posts: {}", e))?;
+        let mut items: Vec<ListedPost> = result.items.into_iter()
+            .filter(|item| item.post.is_public() && !item.post.is_deleted())
+            .collect();
+        let pinned = |item: &ListedPost| item.post.spec.pinned.unwrap_or(false);
+        items.sort_by(|a, b| pinned(b).cmp(&pinned(a))
+            .then(b.post.spec.publish_time.cmp(&a.post.spec.publish_time)));
+        
+        let mut values = Vec::new();
+        for item in items {
+            let mut value = serde_json::to_value(item)?;
+            if let Some(post) = value.get_mut("post") {
+                Self::sanitize(post);
+            }
+            values.push(value);
+        }
+        Ok(paginate(values, page, size))
+    }
+    
+    /// 获取文章的已发布内容（用于文章页面），未解锁的加密文章返回Null
+    pub async fn content(&self, name: &str) -> Result<Value> {
+        match self.post_service.get_by_username(name, "").await {
+            Ok(Some(post)) if self.is_locked(&post) => return Ok(Value::Null),
+            Ok(_) => {}
+            Err(e) => return Err(anyhow::anyhow!("Failed to get post: {}", e)),
+        }
+        match self.post_service.get_release_content(name).await {
+            Ok(content) => Ok(serde_json::to_value(content)?),
+            Err(e) => Err(anyhow::anyhow!("Failed to get post content: {}", e)),
+        }
+    }
+    
+    /// 按年月列出归档（用于归档页面和侧边栏组件）
+    pub async fn archives(&self, query: crate::content::ArchiveQuery) -> Result<Value> {
+        match self.post_service.list_archives(query).await {
+            Ok(archives) => {
+                let mut value = serde_json::to_value(archives)?;
+                let posts = value.as_array_mut().into_iter().flatten()
+                    .filter_map(|year| year.get_mut("months")?.as_array_mut())
+                    .flatten()
+                    .filter_map(|month| month.get_mut("posts")?.as_array_mut())
+                    .flatten();
+                for item in posts.filter_map(|item| item.get_mut("post")) {
+                    Self::sanitize(item);
+                }
+                Ok(value)
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to list archives: {}", e)),
+        }
+    }
 }
 
 #[async_trait]
@@ -87,14 +343,39 @@ impl CategoryFinder {
         }
     }
     
+    /// 根据slug获取Category（用于分类页面）
+    pub async fn get_by_slug(&self, slug: &str) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        match self.category_service.list(options).await {
+            Ok(result) => match result.items.into_iter().find(|c| c.spec.slug == slug) {
+                Some(category) => Ok(serde_json::to_value(category)?),
+                None => Ok(Value::Null),
+            },
+            Err(e) => Err(anyhow::anyhow!("Failed to list categories: {}", e)),
+        }
+    }
+    
     /// 列出Categories（用于模板渲染前预加载）
     pub async fn list(&self) -> Result<Value> {
-        use flow_api::extension::ListOptions;
         match self.category_service.list(ListOptions::default()).await {
             Ok(result) => Ok(serde_json::to_value(result.items)?),
             Err(e) => Err(anyhow::anyhow!("Failed to list categories: {}", e)),
         }
     }
+    
+    /// 分页列出Categories，按优先级排序，优先级相同时按显示名称排序
+    ///
+    /// page从1开始，返回与 `PostFinder::list_page` 相同的分页结构
+    pub async fn list_page(&self, page: u32, size: u32) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let mut categories = self.category_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list categories: {}", e))?
+            .items;
+        categories.sort_by(|a, b| a.spec.priority.unwrap_or(0).cmp(&b.spec.priority.unwrap_or(0))
+            .then_with(|| a.spec.display_name.cmp(&b.spec.display_name)));
+        let values = categories.into_iter().map(serde_json::to_value).collect::<Result<_, _>>()?;
+        Ok(paginate(values, page, size))
+    }
 }
 
 #[async_trait]
@@ -123,14 +404,38 @@ impl TagFinder {
         }
     }
     
+    /// 根据slug获取Tag（用于标签页面）
+    pub async fn get_by_slug(&self, slug: &str) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        match self.tag_service.list(options).await {
+            Ok(result) => match result.items.into_iter().find(|t| t.spec.slug == slug) {
+                Some(tag) => Ok(serde_json::to_value(tag)?),
+                None => Ok(Value::Null),
+            },
+            Err(e) => Err(anyhow::anyhow!("Failed to list tags: {}", e)),
+        }
+    }
+    
     /// 列出Tags（用于模板渲染前预加载）
     pub async fn list(&self) -> Result<Value> {
-        use flow_api::extension::ListOptions;
         match self.tag_service.list(ListOptions::default()).await {
             Ok(result) => Ok(serde_json::to_value(result.items)?),
             Err(e) => Err(anyhow::anyhow!("Failed to list tags: {}", e)),
         }
     }
+    
+    /// 分页列出Tags，按显示名称排序
+    ///
+    /// page从1开始，返回与 `PostFinder::list_page` 相同的分页结构
+    pub async fn list_page(&self, page: u32, size: u32) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let mut tags = self.tag_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list tags: {}", e))?
+            .items;
+        tags.sort_by(|a, b| a.spec.display_name.cmp(&b.spec.display_name));
+        let values = tags.into_iter().map(serde_json::to_value).collect::<Result<_, _>>()?;
+        Ok(paginate(values, page, size))
+    }
 }
 
 #[async_trait]
@@ -140,6 +445,508 @@ impl Finder for TagFinder {
     }
 }
 
+/// SinglePageFinder - 在模板中查询独立页面数据（只返回公开的已发布页面）
+pub struct SinglePageFinder {
+    single_page_service: Arc<dyn SinglePageService>,
+}
+
+impl SinglePageFinder {
+    pub fn new(single_page_service: Arc<dyn SinglePageService>) -> Self {
+        Self { single_page_service }
+    }
+    
+    /// 根据访问路径获取页面，子页面的路径包含所有祖先页面的slug（如 /about/team）
+    pub async fn get_by_permalink(&self, path: &str) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let pages = self.single_page_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list single pages: {}", e))?
+            .items;
+        let by_name: HashMap<&str, &SinglePage> = pages.iter()
+            .map(|p| (p.metadata.name.as_str(), p))
+            .collect();
+        let path = match path.trim_end_matches('/') {
+            "" => "/",
+            path => path,
+        };
+        let page = pages.iter().find(|page| {
+            page.is_published()
+                && !page.spec.deleted.unwrap_or(false)
+                && matches!(page.spec.visible, Some(VisibleEnum::Public) | None)
+                && page_tree::nested_permalink(page, &by_name) == path
+        });
+        match page {
+            Some(page) => Ok(serde_json::to_value(page)?),
+            None => Ok(Value::Null),
+        }
+    }
+    
+    /// 获取页面的已发布内容（用于页面渲染）
+    pub async fn content(&self, name: &str) -> Result<Value> {
+        match self.single_page_service.get_release_content(name).await {
+            Ok(content) => Ok(serde_json::to_value(content)?),
+            Err(e) => Err(anyhow::anyhow!("Failed to get single page content: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for SinglePageFinder {
+    fn name(&self) -> &str {
+        "singlePageFinder"
+    }
+}
+
+/// MenuFinder - 在模板中查询导航菜单数据
+pub struct MenuFinder {
+    menu_service: Arc<dyn MenuService>,
+}
+
+impl MenuFinder {
+    pub fn new(menu_service: Arc<dyn MenuService>) -> Self {
+        Self { menu_service }
+    }
+    
+    /// 根据名称获取菜单及其菜单项树（用于模板渲染前预加载）
+    pub async fn get_by_name(&self, name: &str) -> Result<Value> {
+        match self.menu_service.get_tree(name).await {
+            Ok(Some(tree)) => Ok(serde_json::to_value(tree)?),
+            Ok(None) => Ok(Value::Null),
+            Err(e) => Err(anyhow::anyhow!("Failed to get menu: {}", e)),
+        }
+    }
+    
+    /// 获取主菜单及其菜单项树（用于模板渲染前预加载）
+    pub async fn get_primary(&self) -> Result<Value> {
+        match self.menu_service.get_primary_tree().await {
+            Ok(Some(tree)) => Ok(serde_json::to_value(tree)?),
+            Ok(None) => Ok(Value::Null),
+            Err(e) => Err(anyhow::anyhow!("Failed to get primary menu: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for MenuFinder {
+    fn name(&self) -> &str {
+        "menuFinder"
+    }
+    
+    async fn preload(&self) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Some(self.get_primary().await?))
+    }
+}
+
+/// ArchiveFinder - 在模板中查询按年月分组的文章归档
+pub struct ArchiveFinder {
+    post_service: Arc<dyn PostService>,
+}
+
+impl ArchiveFinder {
+    pub fn new(post_service: Arc<dyn PostService>) -> Self {
+        Self { post_service }
+    }
+    
+    /// 按年月列出归档，每个月份包含文章列表
+    pub async fn list(&self, query: ArchiveQuery) -> Result<Value> {
+        PostFinder::new(self.post_service.clone()).archives(query).await
+    }
+}
+
+#[async_trait]
+impl Finder for ArchiveFinder {
+    fn name(&self) -> &str {
+        "archiveFinder"
+    }
+    
+    async fn preload(&self) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Some(self.list(ArchiveQuery::default()).await?))
+    }
+}
+
+/// 标签云的权重等级数量（1为最少，5为最多）
+pub const TAG_CLOUD_LEVELS: u32 = 5;
+
+/// 构建标签云：只包含有公开文章的标签，按显示名称排序，
+/// 权重按文章数量在最少和最多之间线性映射到 `1..=TAG_CLOUD_LEVELS`
+pub fn tag_cloud_value(tags: Vec<Tag>) -> Result<Value> {
+    let mut tags: Vec<(Tag, u32)> = tags.into_iter()
+        .filter_map(|tag| {
+            let count = tag.status.as_ref()?.visible_post_count.filter(|c| *c > 0)? as u32;
+            Some((tag, count))
+        })
+        .collect();
+    tags.sort_by(|(a, _), (b, _)| a.spec.display_name.cmp(&b.spec.display_name));
+    let min = tags.iter().map(|(_, count)| *count).min().unwrap_or(0);
+    let max = tags.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let mut items = Vec::with_capacity(tags.len());
+    for (tag, count) in tags {
+        let weight = match max - min {
+            0 => 1,
+            range => 1 + (count - min) * (TAG_CLOUD_LEVELS - 1) / range,
+        };
+        items.push(serde_json::json!({
+            "tag": serde_json::to_value(tag)?,
+            "count": count,
+            "weight": weight,
+        }));
+    }
+    Ok(Value::Array(items))
+}
+
+/// TagCloudFinder - 在模板中查询按公开文章数量加权的标签（用于标签云组件）
+pub struct TagCloudFinder {
+    tag_service: Arc<dyn TagService>,
+}
+
+impl TagCloudFinder {
+    pub fn new(tag_service: Arc<dyn TagService>) -> Self {
+        Self { tag_service }
+    }
+    
+    /// 列出标签云，返回 `[{tag, count, weight}]`
+    pub async fn list(&self) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let tags = self.tag_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list tags: {}", e))?
+            .items;
+        tag_cloud_value(tags)
+    }
+}
+
+#[async_trait]
+impl Finder for TagCloudFinder {
+    fn name(&self) -> &str {
+        "tagCloudFinder"
+    }
+    
+    async fn preload(&self) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Some(self.list().await?))
+    }
+}
+
+/// 读取文章统计注解中的访问量，注解不存在或格式错误时为0
+pub fn post_visits(post: &Post) -> u64 {
+    post.metadata.annotations.as_ref()
+        .and_then(|annotations| annotations.get(constant::POST_STATS_ANNO))
+        .and_then(|stats| serde_json::from_str::<Value>(stats).ok())
+        .and_then(|stats| stats.get("visit")?.as_u64())
+        .unwrap_or(0)
+}
+
+/// SiteStatsFinder - 在模板中查询站点统计（文章、评论、分类数量和总访问量）
+pub struct SiteStatsFinder {
+    post_service: Arc<dyn PostService>,
+    comment_service: Arc<dyn CommentService>,
+    category_service: Arc<dyn CategoryService>,
+}
+
+impl SiteStatsFinder {
+    pub fn new(
+        post_service: Arc<dyn PostService>,
+        comment_service: Arc<dyn CommentService>,
+        category_service: Arc<dyn CategoryService>,
+    ) -> Self {
+        Self { post_service, comment_service, category_service }
+    }
+    
+    /// 获取站点统计，只统计访客可见的文章和评论
+    ///
+    /// 返回 `{post, comment, category, visit}`
+    pub async fn get(&self) -> Result<Value> {
+        let query = PostQuery {
+            published: Some(true),
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        let posts = self.post_service.list_post(query).await
+            .map_err(|e| anyhow::anyhow!("Failed to list posts: {}", e))?;
+        let posts: Vec<&Post> = posts.items.iter()
+            .map(|item| &item.post)
+            .filter(|post| post.is_public() && !post.is_deleted())
+            .collect();
+        
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let comments = self.comment_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list comments: {}", e))?;
+        let comment_count = comments.items.iter()
+            .filter(|comment| public_comment::is_publicly_visible(comment))
+            .count();
+        
+        let categories = self.category_service.list(ListOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Failed to list categories: {}", e))?;
+        
+        Ok(serde_json::json!({
+            "post": posts.len(),
+            "comment": comment_count,
+            "category": categories.total,
+            "visit": posts.iter().map(|post| post_visits(post)).sum::<u64>(),
+        }))
+    }
+}
+
+#[async_trait]
+impl Finder for SiteStatsFinder {
+    fn name(&self) -> &str {
+        "siteStatsFinder"
+    }
+    
+    async fn preload(&self) -> Result<Option<Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Some(self.get().await?))
+    }
+}
+
+/// 作者的公开资料，不包含邮箱、手机号等私密信息
+pub fn contributor_value(user: &flow_domain::security::User, post_count: u64) -> Value {
+    serde_json::json!({
+        "name": user.metadata.name,
+        "displayName": user.spec.display_name,
+        "avatar": user.spec.avatar,
+        "bio": user.spec.bio,
+        "postCount": post_count,
+    })
+}
+
+/// ContributorFinder - 在模板中查询作者资料及其发布的文章
+pub struct ContributorFinder {
+    user_service: Arc<dyn UserService>,
+    post_service: Arc<dyn PostService>,
+}
+
+impl ContributorFinder {
+    pub fn new(user_service: Arc<dyn UserService>, post_service: Arc<dyn PostService>) -> Self {
+        Self { user_service, post_service }
+    }
+    
+    /// 根据用户名获取作者资料（显示名称、头像、简介和公开文章数），不存在时返回Null
+    pub async fn get_contributor(&self, name: &str) -> Result<Value> {
+        let user = self.user_service.get(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to get user: {}", e))?;
+        let Some(user) = user else {
+            return Ok(Value::Null);
+        };
+        let query = PostQuery {
+            published: Some(true),
+            contributor: Some(name.to_string()),
+            size: Some(SCAN_SIZE),
+            ..Default::default()
+        };
+        let posts = self.post_service.list_post(query).await
+            .map_err(|e| anyhow::anyhow!("Failed to list posts: {}", e))?;
+        let post_count = posts.items.iter()
+            .filter(|item| item.post.is_public() && !item.post.is_deleted())
+            .count() as u64;
+        Ok(contributor_value(&user, post_count))
+    }
+    
+    /// 分页列出作者参与（所有者或共同作者）的公开文章
+    pub async fn list_posts(&self, name: &str, page: u32, size: u32) -> Result<Value> {
+        let query = PostQuery {
+            contributor: Some(name.to_string()),
+            ..Default::default()
+        };
+        PostFinder::new(self.post_service.clone()).list_page(query, page, size).await
+    }
+}
+
+#[async_trait]
+impl Finder for ContributorFinder {
+    fn name(&self) -> &str {
+        "contributorFinder"
+    }
+}
+
+/// CommentFinder - 在模板中查询评论主题下访客可见的评论树
+pub struct CommentFinder {
+    comment_service: Arc<dyn CommentService>,
+    user_service: Option<Arc<dyn UserService>>,
+}
+
+impl CommentFinder {
+    pub fn new(comment_service: Arc<dyn CommentService>) -> Self {
+        Self { comment_service, user_service: None }
+    }
+    
+    /// 设置User服务，用于补充注册用户评论者的头像
+    pub fn with_user_service(mut self, user_service: Arc<dyn UserService>) -> Self {
+        self.user_service = Some(user_service);
+        self
+    }
+    
+    /// 分页列出主题下已批准的顶层评论，每条评论包含评论者信息、回复数量和最早的若干条回复
+    ///
+    /// page从1开始，返回 `{items, total, page, size, totalPages, hasPrevious, hasNext}`
+    pub async fn list(&self, kind: &str, name: &str, page: u32, size: u32) -> Result<Value> {
+        let options = ListOptions { size: Some(SCAN_SIZE), ..Default::default() };
+        let comments = self.comment_service.list(options).await
+            .map_err(|e| anyhow::anyhow!("Failed to list comments: {}", e))?;
+        let query = PublicCommentQuery {
+            group: None,
+            kind: kind.to_string(),
+            name: name.to_string(),
+            sort: None,
+            cursor: None,
+            size: Some(size),
+            reply_size: None,
+        };
+        let (mut items, total) = public_comment::public_comments_by_page(&comments.items, &query, page);
+        self.fill_avatars(&mut items).await;
+        Ok(page_value(items.into_iter().map(serde_json::to_value).collect::<Result<_, _>>()?, total, page, size))
+    }
+    
+    /// 补充注册用户评论者（包括内嵌回复）的头像，查询失败时忽略
+    async fn fill_avatars(&self, comments: &mut [PublicComment]) {
+        let Some(user_service) = &self.user_service else {
+            return;
+        };
+        let usernames: std::collections::HashSet<String> = comments.iter()
+            .flat_map(|comment| std::iter::once(comment).chain(comment.replies.iter()))
+            .map(|comment| &comment.owner)
+            .filter(|owner| owner.kind != flow_domain::content::CommentOwner::KIND_EMAIL)
+            .filter_map(|owner| owner.name.clone())
+            .collect();
+        
+        let mut avatars: HashMap<String, String> = HashMap::new();
+        for username in usernames {
+            if let Ok(Some(avatar)) = user_service.get(&username).await.map(|user| user.and_then(|u| u.spec.avatar)) {
+                avatars.insert(username, avatar);
+            }
+        }
+        
+        for comment in comments.iter_mut() {
+            for owner in std::iter::once(&mut comment.owner).chain(comment.replies.iter_mut().map(|reply| &mut reply.owner)) {
+                if owner.kind != flow_domain::content::CommentOwner::KIND_EMAIL {
+                    owner.avatar = owner.name.as_ref().and_then(|name| avatars.get(name)).cloned();
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for CommentFinder {
+    fn name(&self) -> &str {
+        "commentFinder"
+    }
+}
+
+/// LinkFinder - 在模板中查询友情链接数据
+pub struct LinkFinder {
+    link_service: Arc<dyn LinkService>,
+}
+
+impl LinkFinder {
+    pub fn new(link_service: Arc<dyn LinkService>) -> Self {
+        Self { link_service }
+    }
+    
+    /// 按分组列出友情链接（用于模板渲染前预加载）
+    pub async fn list_by_group(&self) -> Result<Value> {
+        match self.link_service.list_grouped().await {
+            Ok(grouped) => Ok(serde_json::to_value(grouped)?),
+            Err(e) => Err(anyhow::anyhow!("Failed to list grouped links: {}", e)),
+        }
+    }
+    
+    /// 列出所有友情链接（按分组顺序展开，用于模板渲染前预加载）
+    pub async fn list(&self) -> Result<Value> {
+        match self.link_service.list_grouped().await {
+            Ok(grouped) => {
+                let links: Vec<_> = grouped.into_iter().flat_map(|g| g.links).collect();
+                Ok(serde_json::to_value(links)?)
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to list links: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for LinkFinder {
+    fn name(&self) -> &str {
+        "linkFinder"
+    }
+}
+
+/// MomentFinder - 在模板中查询瞬间数据（只返回公开的瞬间）
+pub struct MomentFinder {
+    moment_service: Arc<dyn MomentService>,
+}
+
+impl MomentFinder {
+    pub fn new(moment_service: Arc<dyn MomentService>) -> Self {
+        Self { moment_service }
+    }
+    
+    /// 根据名称获取公开的瞬间（用于模板渲染前预加载）
+    pub async fn get_by_name(&self, name: &str) -> Result<Value> {
+        match self.moment_service.get(name).await {
+            Ok(Some(moment)) if moment.is_public() => Ok(serde_json::to_value(moment)?),
+            Ok(_) => Ok(Value::Null),
+            Err(e) => Err(anyhow::anyhow!("Failed to get moment: {}", e)),
+        }
+    }
+    
+    /// 分页列出公开的瞬间（用于模板渲染前预加载）
+    pub async fn list(&self, query: Option<MomentQuery>) -> Result<Value> {
+        let mut query = query.unwrap_or_default();
+        query.visible = Some(flow_domain::content::VisibleEnum::Public);
+        match self.moment_service.list(query).await {
+            Ok(result) => Ok(serde_json::json!({
+                "items": result.items,
+                "total": result.total,
+                "page": result.page,
+                "size": result.size,
+            })),
+            Err(e) => Err(anyhow::anyhow!("Failed to list moments: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for MomentFinder {
+    fn name(&self) -> &str {
+        "momentFinder"
+    }
+}
+
+/// PhotoFinder - 在模板中查询图库数据
+pub struct PhotoFinder {
+    photo_service: Arc<dyn PhotoService>,
+}
+
+impl PhotoFinder {
+    pub fn new(photo_service: Arc<dyn PhotoService>) -> Self {
+        Self { photo_service }
+    }
+    
+    /// 按分组列出照片（用于模板渲染前预加载）
+    pub async fn groups(&self) -> Result<Value> {
+        match self.photo_service.list_grouped().await {
+            Ok(grouped) => Ok(serde_json::to_value(grouped)?),
+            Err(e) => Err(anyhow::anyhow!("Failed to list grouped photos: {}", e)),
+        }
+    }
+    
+    /// 列出指定分组的照片，group为None时列出所有照片（用于模板渲染前预加载）
+    pub async fn list_by_group(&self, group: Option<&str>) -> Result<Value> {
+        let photos = match group {
+            Some(group) => self.photo_service.list_by_group(Some(group)).await,
+            None => self.photo_service.list_grouped().await
+                .map(|grouped| grouped.into_iter().flat_map(|g| g.photos).collect()),
+        };
+        match photos {
+            Ok(photos) => Ok(serde_json::to_value(photos)?),
+            Err(e) => Err(anyhow::anyhow!("Failed to list photos: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Finder for PhotoFinder {
+    fn name(&self) -> &str {
+        "photoFinder"
+    }
+}
+
 /// ThemeFinder - 在模板中查询Theme数据
 pub struct ThemeFinder {
     theme_service: Arc<dyn ThemeService>,
@@ -173,3 +980,118 @@ impl Finder for ThemeFinder {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_value() {
+        let items = vec![Value::from(1), Value::from(2)];
+        let page = page_value(items, 12, 2, 5);
+        assert_eq!(page["totalPages"], 3);
+        assert_eq!(page["hasPrevious"], true);
+        assert_eq!(page["hasNext"], true);
+
+        assert_eq!(page["prevPage"], 1);
+        assert_eq!(page["nextPage"], 3);
+
+        let last = page_value(Vec::new(), 12, 3, 5);
+        assert_eq!(last["hasNext"], false);
+        assert_eq!(last["nextPage"], Value::Null);
+        assert_eq!(page_value(Vec::new(), 0, 1, 10)["totalPages"], 0);
+
+        let items: Vec<Value> = (1..=12).map(Value::from).collect();
+        let page = paginate(items, 3, 5);
+        assert_eq!(page["items"], serde_json::json!([11, 12]));
+        assert_eq!(page["total"], 12);
+    }
+
+    #[test]
+    fn test_tag_cloud_value() {
+        let tag = |name: &str, count: Option<i32>| Tag {
+            metadata: flow_api::extension::Metadata::new(name),
+            spec: flow_domain::content::TagSpec {
+                display_name: name.to_string(),
+                slug: name.to_string(),
+                color: None,
+                cover: None,
+            },
+            status: count.map(|count| flow_domain::content::TagStatus {
+                visible_post_count: Some(count),
+                ..Default::default()
+            }),
+        };
+        let cloud = tag_cloud_value(vec![
+            tag("rust", Some(9)),
+            tag("empty", Some(0)),
+            tag("go", Some(1)),
+            tag("new", None),
+            tag("java", Some(5)),
+        ]).unwrap();
+        let summary: Vec<(&str, u64, u64)> = cloud.as_array().unwrap().iter()
+            .map(|item| (
+                item["tag"]["metadata"]["name"].as_str().unwrap(),
+                item["count"].as_u64().unwrap(),
+                item["weight"].as_u64().unwrap(),
+            ))
+            .collect();
+        assert_eq!(summary, vec![("go", 1, 1), ("java", 5, 3), ("rust", 9, 5)]);
+
+        let single = tag_cloud_value(vec![tag("rust", Some(2))]).unwrap();
+        assert_eq!(single[0]["weight"], 1);
+    }
+
+    #[test]
+    fn test_page_urls() {
+        assert_eq!(page_base_path("/categories/rust/page/3"), "/categories/rust");
+        assert_eq!(page_base_path("/page/2"), "/");
+        assert_eq!(page_base_path("/archives/page"), "/archives/page");
+        assert_eq!(page_url("/", 1), "/");
+        assert_eq!(page_url("/", 2), "/page/2");
+        assert_eq!(page_url("/tags/rust/", 1), "/tags/rust");
+
+        let page = with_page_urls(page_value(Vec::new(), 30, 2, 10), "/archives");
+        assert_eq!(page["prevUrl"], "/archives");
+        assert_eq!(page["nextUrl"], "/archives/page/3");
+        let first = with_page_urls(page_value(Vec::new(), 5, 1, 10), "/");
+        assert_eq!(first["prevUrl"], Value::Null);
+        assert_eq!(first["nextUrl"], Value::Null);
+    }
+
+    #[test]
+    fn test_contributor_value_hides_private_fields() {
+        let user = flow_domain::security::User {
+            metadata: flow_api::extension::Metadata::new("alice"),
+            spec: flow_domain::security::UserSpec {
+                display_name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                password: Some("secret".to_string()),
+                bio: Some("Hi".to_string()),
+                ..Default::default()
+            },
+            status: None,
+        };
+        let value = contributor_value(&user, 3);
+        assert_eq!(value["displayName"], "Alice");
+        assert_eq!(value["postCount"], 3);
+        assert!(!value.to_string().contains("alice@example.com"));
+        assert!(!value.to_string().contains("secret"));
+    }
+
+    #[test]
+    fn test_post_visits() {
+        let mut post: Post = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "hello"},
+            "spec": {"title": "Hello", "slug": "hello"},
+        })).unwrap();
+        assert_eq!(post_visits(&post), 0);
+        
+        let annotations = post.metadata.annotations.get_or_insert_with(Default::default);
+        annotations.insert(constant::POST_STATS_ANNO.to_string(), r#"{"visit":42,"upvote":3}"#.to_string());
+        assert_eq!(post_visits(&post), 42);
+        
+        let annotations = post.metadata.annotations.get_or_insert_with(Default::default);
+        annotations.insert(constant::POST_STATS_ANNO.to_string(), "broken".to_string());
+        assert_eq!(post_visits(&post), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use flow_domain::theme::Theme;
+use flow_domain::setting::Setting;
 use std::path::{Path, PathBuf};
 use std::fs;
 use anyhow::{Context, Result};
@@ -132,7 +133,11 @@ impl ThemeInstaller {
             let mut file = archive.by_index(i)
                 .context(format!("Failed to read file {} from ZIP", i))?;
             
-            let out_path = dest_dir.join(file.name());
+            // 忽略路径越出解压目录的条目
+            let Some(relative_path) = file.enclosed_name() else {
+                continue;
+            };
+            let out_path = dest_dir.join(relative_path);
             
             if file.name().ends_with('/') {
                 // 目录
@@ -157,6 +162,8 @@ impl ThemeInstaller {
     }
     
     /// 查找主题manifest文件（theme.yaml或theme.yml）
+    ///
+    /// 根目录下没有manifest且只有一个子目录时（如Git仓库归档），在该子目录中查找
     pub fn locate_theme_manifest(&self, dir: &Path) -> Option<PathBuf> {
         for manifest_name in &["theme.yaml", "theme.yml"] {
             let manifest_path = dir.join(manifest_name);
@@ -164,7 +171,14 @@ impl ThemeInstaller {
                 return Some(manifest_path);
             }
         }
-        None
+        let mut entries = fs::read_dir(dir).ok()?.filter_map(|e| e.ok());
+        let single = entries.next()?;
+        if entries.next().is_some() || !single.path().is_dir() {
+            return None;
+        }
+        ["theme.yaml", "theme.yml"].iter()
+            .map(|name| single.path().join(name))
+            .find(|path| path.exists())
     }
     
     /// 加载主题manifest文件
@@ -177,6 +191,22 @@ impl ThemeInstaller {
         
         Ok(theme)
     }
+    
+    /// 加载主题目录中的设置表单定义（settings.yaml或settings.yml），不存在时返回None
+    pub fn load_theme_setting(&self, dir: &Path) -> Result<Option<Setting>> {
+        let Some(setting_path) = ["settings.yaml", "settings.yml"].iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&setting_path)
+            .context(format!("Failed to read theme setting: {:?}", setting_path))?;
+        
+        let setting: Setting = serde_yaml::from_str(&content)
+            .context(format!("Failed to parse theme setting: {:?}", setting_path))?;
+        
+        Ok(Some(setting))
+    }
 }
 
 /// 检查目录是否为空
@@ -1,9 +1,24 @@
 pub mod finders;
 pub mod installer;
+pub mod config;
+pub mod robots;
+pub mod watcher;
+pub mod requires;
+pub mod remote;
+pub mod context;
+pub mod seo;
 
-pub use finders::{PostFinder, CategoryFinder, TagFinder, ThemeFinder};
+pub use finders::{PostFinder, CategoryFinder, TagFinder, SinglePageFinder, MenuFinder, ArchiveFinder, SiteStatsFinder, TagCloudFinder, CommentFinder, ContributorFinder, LinkFinder, MomentFinder, PhotoFinder, ThemeFinder};
+
+pub use config::{ThemeConfigService, DefaultThemeConfigService, ThemeConfig, THEME_CONFIG_INVALID_ERROR};
+pub use robots::{RobotsService, DefaultRobotsService, ROBOTS_TXT_TOO_LARGE_ERROR};
+pub use watcher::spawn_theme_watcher;
+pub use requires::{FLOW_VERSION, THEME_REQUIRES_UNSATISFIED_ERROR};
+pub use context::{TemplateContextContributor, TemplateContextRegistry, RenderRequest, SiteContextContributor, CurrentUserContextContributor, MenuContextContributor, RequestContextContributor};
+pub use remote::{RemoteThemeSource, RemoteThemeFetcher, REMOTE_THEME_TOO_LARGE_ERROR, THEME_CHECKSUM_MISMATCH_ERROR};
 
 use flow_domain::theme::Theme;
+use flow_domain::setting::Setting;
 use flow_api::extension::{ExtensionClient, ListOptions};
 use flow_infra::extension::ReactiveExtensionClient;
 use flow_infra::system_setting::{SystemSettingService, DefaultSystemSettingService};
@@ -30,18 +45,33 @@ pub trait ThemeService: Send + Sync {
     /// 安装主题（从ZIP文件）
     async fn install_theme(&self, content: Vec<u8>) -> Result<Theme>;
     
+    /// 从远程地址或Git仓库安装主题
+    async fn install_theme_from_remote(&self, source: RemoteThemeSource) -> Result<Theme>;
+    
     /// 升级主题
     async fn upgrade_theme(&self, name: &str, content: Vec<u8>) -> Result<Theme>;
     
     /// 重新加载主题
     async fn reload_theme(&self, name: &str) -> Result<Theme>;
+    
+    /// 卸载主题，删除主题目录和Theme Extension；delete_config为true时同时删除设置表单和设置值
+    async fn uninstall_theme(&self, name: &str, delete_config: bool) -> Result<Theme>;
 }
 
+/// 主题不存在时的错误信息前缀
+pub const THEME_NOT_FOUND_ERROR: &str = "Theme not found";
+
+/// 卸载激活中的主题时的错误信息前缀
+pub const THEME_IN_USE_ERROR: &str = "Cannot uninstall the active theme";
+
 /// 默认Theme服务实现
 pub struct DefaultThemeService {
     extension_client: Arc<ReactiveExtensionClient>,
     system_setting_service: Arc<dyn SystemSettingService>,
     theme_root: PathBuf,
+    /// 当前运行的Flow版本，用于校验主题的requires
+    flow_version: semver::Version,
+    remote_fetcher: RemoteThemeFetcher,
 }
 
 impl DefaultThemeService {
@@ -53,8 +83,57 @@ impl DefaultThemeService {
             extension_client,
             system_setting_service,
             theme_root,
+            flow_version: requires::flow_version(),
+            remote_fetcher: RemoteThemeFetcher::new(),
         }
     }
+    
+    /// 设置远程主题包下载器
+    pub fn with_remote_fetcher(mut self, remote_fetcher: RemoteThemeFetcher) -> Self {
+        self.remote_fetcher = remote_fetcher;
+        self
+    }
+}
+
+impl DefaultThemeService {
+    /// 删除扩展对象，对象不存在时视为删除成功
+    async fn delete_if_exists<E>(&self, name: &str) -> Result<()>
+    where
+        E: flow_api::extension::Extension + for<'de> serde::Deserialize<'de>,
+    {
+        let existing: Option<E> = self.extension_client.fetch(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch {}: {}", name, e))?;
+        if existing.is_some() {
+            self.extension_client.delete::<E>(name).await
+                .map_err(|e| anyhow::anyhow!("Failed to delete {}: {}", name, e))?;
+        }
+        Ok(())
+    }
+    
+    /// 将主题目录中的设置表单同步为Setting扩展对象
+    async fn sync_setting(&self, theme: &Theme) -> Result<()> {
+        let Some(location) = theme.status.as_ref().and_then(|s| s.location.as_ref()) else {
+            return Ok(());
+        };
+        let installer = installer::ThemeInstaller::new(self.theme_root.clone());
+        let Some(mut setting) = installer.load_theme_setting(std::path::Path::new(location))? else {
+            return Ok(());
+        };
+        let existing: Option<Setting> = self.extension_client.fetch(&setting.metadata.name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch setting: {}", e))?;
+        match existing {
+            Some(existing) => {
+                setting.metadata.version = existing.metadata.version;
+                self.extension_client.update(setting).await
+                    .map_err(|e| anyhow::anyhow!("Failed to update setting: {}", e))?;
+            }
+            None => {
+                self.extension_client.create(setting).await
+                    .map_err(|e| anyhow::anyhow!("Failed to create setting: {}", e))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -66,11 +145,25 @@ impl ThemeService for DefaultThemeService {
     
     async fn set_active_theme(&self, theme_name: &str) -> Result<()> {
         // 验证主题是否存在
-        let _theme: Option<Theme> = self.extension_client.fetch(theme_name).await
+        let theme: Option<Theme> = self.extension_client.fetch(theme_name).await
             .map_err(|e| anyhow::anyhow!("Failed to fetch theme: {}", e))?;
         
-        if _theme.is_none() {
+        let Some(mut theme) = theme else {
             anyhow::bail!("Theme not found: {}", theme_name);
+        };
+        
+        // 验证主题要求的Flow版本，不满足时记录到主题状态并拒绝激活
+        let previous_status = serde_json::to_value(&theme.status)?;
+        let satisfied = requires::apply_requires_check(&mut theme, &self.flow_version);
+        if serde_json::to_value(&theme.status)? != previous_status {
+            self.extension_client.update(theme.clone()).await
+                .map_err(|e| anyhow::anyhow!("Failed to update theme status: {}", e))?;
+        }
+        if !satisfied {
+            let message = requires::check_requires(&theme.spec.requires, &self.flow_version)
+                .err()
+                .unwrap_or_default();
+            anyhow::bail!("{}: {}", THEME_REQUIRES_UNSATISFIED_ERROR, message);
         }
         
         // 更新系统设置
@@ -100,28 +193,37 @@ impl ThemeService for DefaultThemeService {
         // 创建安装器
         let installer = ThemeInstaller::new(self.theme_root.clone());
         
-        // 安装主题
-        let theme = installer.install_theme(content, false).await?;
+        // 安装主题，不满足版本要求的主题标记为Failed
+        let mut theme = installer.install_theme(content, false).await?;
+        requires::apply_requires_check(&mut theme, &self.flow_version);
         
         // 创建Theme Extension
         self.extension_client.create(theme.clone()).await
             .map_err(|e| anyhow::anyhow!("Failed to create theme extension: {}", e))?;
+        self.sync_setting(&theme).await?;
         
         Ok(theme)
     }
     
+    async fn install_theme_from_remote(&self, source: RemoteThemeSource) -> Result<Theme> {
+        let content = self.remote_fetcher.fetch(&source).await?;
+        self.install_theme(content).await
+    }
+    
     async fn upgrade_theme(&self, name: &str, content: Vec<u8>) -> Result<Theme> {
         use installer::ThemeInstaller;
         
         // 创建安装器
         let installer = ThemeInstaller::new(self.theme_root.clone());
         
-        // 升级主题
-        let theme = installer.upgrade_theme(name, content).await?;
+        // 升级主题，不满足版本要求的主题标记为Failed
+        let mut theme = installer.upgrade_theme(name, content).await?;
+        requires::apply_requires_check(&mut theme, &self.flow_version);
         
         // 更新Theme Extension
         self.extension_client.update(theme.clone()).await
             .map_err(|e| anyhow::anyhow!("Failed to update theme extension: {}", e))?;
+        self.sync_setting(&theme).await?;
         
         Ok(theme)
     }
@@ -160,12 +262,117 @@ impl ThemeService for DefaultThemeService {
             conditions: None,
             location: Some(theme_path.to_string_lossy().to_string()),
         });
+        requires::apply_requires_check(&mut theme_with_location, &self.flow_version);
         
         // 更新Theme Extension（这会触发缓存刷新）
         self.extension_client.update(theme_with_location.clone()).await
             .map_err(|e| anyhow::anyhow!("Failed to update theme extension: {}", e))?;
+        self.sync_setting(&theme_with_location).await?;
         
         Ok(theme_with_location)
     }
+    
+    async fn uninstall_theme(&self, name: &str, delete_config: bool) -> Result<Theme> {
+        let theme: Option<Theme> = self.extension_client.fetch(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch theme: {}", e))?;
+        let Some(theme) = theme else {
+            anyhow::bail!("{}: {}", THEME_NOT_FOUND_ERROR, name);
+        };
+        if self.get_active_theme().await?.as_deref() == Some(name) {
+            anyhow::bail!("{}: {}", THEME_IN_USE_ERROR, name);
+        }
+        
+        // 先删除依赖主题的设置表单和设置值，已不存在时视为删除成功
+        if delete_config {
+            if let Some(setting_name) = theme.spec.setting_name.as_deref() {
+                self.delete_if_exists::<Setting>(setting_name).await
+                    .context("Failed to delete theme setting")?;
+            }
+            self.delete_if_exists::<flow_infra::system_setting::ConfigMap>(&config::config_map_name(&theme)).await
+                .context("Failed to delete theme config")?;
+        }
+        self.extension_client.delete::<Theme>(name).await
+            .map_err(|e| anyhow::anyhow!("Failed to delete theme extension: {}", e))?;
+        
+        // 最后删除主题目录
+        let theme_path = self.theme_root.join(&theme.metadata.name);
+        if theme_path.exists() {
+            tokio::fs::remove_dir_all(&theme_path).await
+                .with_context(|| format!("Failed to remove theme directory: {:?}", theme_path))?;
+        }
+        
+        Ok(theme)
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::setting::SettingSpec;
+    use flow_domain::theme::{Author, ThemeSpec};
+    use flow_infra::system_setting::ConfigMap;
+
+    fn theme(name: &str, setting_name: Option<&str>, config_map_name: Option<&str>) -> Theme {
+        Theme {
+            metadata: Metadata::new(name),
+            spec: ThemeSpec {
+                display_name: name.to_string(),
+                author: Author { name: "halo".to_string(), website: None },
+                description: None,
+                logo: None,
+                homepage: None,
+                repo: None,
+                issues: None,
+                version: "1.0.0".to_string(),
+                requires: "*".to_string(),
+                setting_name: setting_name.map(str::to_string),
+                config_map_name: config_map_name.map(str::to_string),
+                license: None,
+                custom_templates: None,
+                template_engine: None,
+            },
+            status: None,
+        }
+    }
+
+    async fn install(theme: Theme) -> (DefaultThemeService, Arc<ReactiveExtensionClient>, tempfile::TempDir) {
+        let theme_root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(theme_root.path().join(&theme.metadata.name)).unwrap();
+        let client = Arc::new(ReactiveExtensionClient::new(Arc::new(crate::testing::MemoryRepository::default())));
+        client.create(theme).await.unwrap();
+        let service = DefaultThemeService::new(client.clone(), theme_root.path().to_path_buf());
+        (service, client, theme_root)
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_theme_without_setting() {
+        let (service, client, theme_root) = install(theme("earth", None, None)).await;
+
+        service.uninstall_theme("earth", true).await.unwrap();
+
+        assert!(client.fetch::<Theme>("earth").await.unwrap().is_none());
+        assert!(!theme_root.path().join("earth").exists());
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_theme_with_config_map() {
+        let (service, client, theme_root) = install(theme("earth", Some("earth-setting"), Some("earth-configmap"))).await;
+        client.create(Setting {
+            metadata: Metadata::new("earth-setting"),
+            spec: SettingSpec { forms: Vec::new() },
+        }).await.unwrap();
+        client.create(ConfigMap {
+            metadata: Metadata::new("earth-configmap"),
+            data: Some(Default::default()),
+        }).await.unwrap();
+
+        service.uninstall_theme("earth", true).await.unwrap();
+
+        assert!(client.fetch::<Setting>("earth-setting").await.unwrap().is_none());
+        assert!(client.fetch::<ConfigMap>("earth-configmap").await.unwrap().is_none());
+        assert!(client.fetch::<Theme>("earth").await.unwrap().is_none());
+        assert!(!theme_root.path().join("earth").exists());
+    }
+}
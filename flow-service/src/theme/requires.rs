@@ -0,0 +1,118 @@
+use chrono::Utc;
+use flow_domain::theme::{Condition, Theme, ThemePhase, ThemeStatus};
+use semver::{Version, VersionReq};
+
+/// 当前运行的Flow版本
+pub const FLOW_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 主题要求的Flow版本不满足时的错误信息前缀
+pub const THEME_REQUIRES_UNSATISFIED_ERROR: &str = "Theme requires unsatisfied";
+
+/// 版本要求检查结果的条件类型
+pub const REQUIRES_CONDITION_TYPE: &str = "RequiresSatisfied";
+
+/// 检查主题的版本要求（SemVer范围，如 `>=2.0.0`、`*`），不满足时返回原因
+pub fn check_requires(requires: &str, version: &Version) -> Result<(), String> {
+    let requirement = VersionReq::parse(requires.trim())
+        .map_err(|e| format!("Invalid requires version range {:?}: {}", requires, e))?;
+    if requirement.matches(version) {
+        Ok(())
+    } else {
+        Err(format!("Theme requires Flow {}, but the running version is {}", requires, version))
+    }
+}
+
+/// 检查主题的版本要求并记录到状态中，不满足时将主题标记为Failed，返回是否满足
+pub fn apply_requires_check(theme: &mut Theme, version: &Version) -> bool {
+    let result = check_requires(&theme.spec.requires, version);
+    let status = theme.status.get_or_insert(ThemeStatus {
+        phase: None,
+        conditions: None,
+        location: None,
+    });
+    let conditions = status.conditions.get_or_insert_with(Vec::new);
+    let previous_len = conditions.len();
+    conditions.retain(|c| c.r#type != REQUIRES_CONDITION_TYPE);
+    let was_unsatisfied = conditions.len() != previous_len;
+    match result {
+        Ok(()) => {
+            // 之前仅因版本要求失败的主题（如Flow升级后）恢复为Ready
+            if conditions.is_empty() {
+                status.conditions = None;
+                if was_unsatisfied && status.phase == Some(ThemePhase::Failed) {
+                    status.phase = Some(ThemePhase::Ready);
+                }
+            }
+            true
+        }
+        Err(message) => {
+            conditions.push(Condition {
+                r#type: REQUIRES_CONDITION_TYPE.to_string(),
+                status: "False".to_string(),
+                reason: Some("UnsatisfiedRequiresVersion".to_string()),
+                message: Some(message),
+                last_transition_time: Some(Utc::now()),
+            });
+            status.phase = Some(ThemePhase::Failed);
+            false
+        }
+    }
+}
+
+/// 当前运行的Flow版本
+pub fn flow_version() -> Version {
+    Version::parse(FLOW_VERSION).expect("package version is valid SemVer")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::Metadata;
+    use flow_domain::theme::{Author, ThemeSpec};
+
+    #[test]
+    fn test_check_requires() {
+        let version = Version::parse("2.10.1").unwrap();
+        assert!(check_requires("*", &version).is_ok());
+        assert!(check_requires(">=2.0.0", &version).is_ok());
+        assert!(check_requires(">=2.11.0", &version).unwrap_err().contains("2.10.1"));
+        assert!(check_requires("not a version", &version).unwrap_err().starts_with("Invalid requires"));
+    }
+
+    #[test]
+    fn test_apply_requires_check() {
+        let mut theme = Theme {
+            metadata: Metadata::new("earth"),
+            spec: ThemeSpec {
+                display_name: "Earth".to_string(),
+                author: Author { name: "halo".to_string(), website: None },
+                description: None,
+                logo: None,
+                homepage: None,
+                repo: None,
+                issues: None,
+                version: "1.0.0".to_string(),
+                requires: ">=3.0.0".to_string(),
+                setting_name: None,
+                config_map_name: None,
+                license: None,
+                custom_templates: None,
+                template_engine: None,
+            },
+            status: Some(ThemeStatus { phase: Some(ThemePhase::Ready), conditions: None, location: None }),
+        };
+        let version = Version::parse("2.0.0").unwrap();
+        assert!(!apply_requires_check(&mut theme, &version));
+        let status = theme.status.as_ref().unwrap();
+        assert_eq!(status.phase, Some(ThemePhase::Failed));
+        assert_eq!(status.conditions.as_ref().unwrap().len(), 1);
+
+        // 重复检查不会产生重复的条件，满足后移除条件
+        assert!(!apply_requires_check(&mut theme, &version));
+        assert_eq!(theme.status.as_ref().unwrap().conditions.as_ref().unwrap().len(), 1);
+        theme.spec.requires = "*".to_string();
+        assert!(apply_requires_check(&mut theme, &version));
+        assert!(theme.status.as_ref().unwrap().conditions.is_none());
+        assert_eq!(theme.status.as_ref().unwrap().phase, Some(ThemePhase::Ready));
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flow_infra::system_setting::{RobotsSetting, SystemSettingService};
+use std::sync::Arc;
+
+/// robots.txt内容的最大字节数（搜索引擎通常只读取前500KiB）
+pub const MAX_ROBOTS_TXT_BYTES: usize = 500 * 1024;
+
+/// robots.txt内容过大时的错误信息前缀
+pub const ROBOTS_TXT_TOO_LARGE_ERROR: &str = "robots.txt is too large";
+
+/// 默认禁止抓取的路径：后台、API和认证端点
+const DISALLOWED_PATHS: &[&str] = &["/console", "/uc", "/api/", "/apis/", "/oauth2/", "/login", "/logout"];
+
+/// robots.txt服务trait
+#[async_trait]
+pub trait RobotsService: Send + Sync {
+    /// 获取对外提供的robots.txt内容，未自定义时使用默认内容
+    ///
+    /// site_url为站点地址（未配置外部访问地址时从请求中获取），用于生成Sitemap链接
+    async fn robots_txt(&self, site_url: &str) -> Result<String>;
+
+    /// 获取自定义的robots.txt内容
+    async fn get_custom(&self) -> Result<Option<String>>;
+
+    /// 更新自定义的robots.txt内容，为空时恢复默认内容
+    async fn update_custom(&self, content: Option<String>) -> Result<()>;
+}
+
+/// 默认robots.txt服务实现，自定义内容保存在系统设置中
+pub struct DefaultRobotsService {
+    system_setting_service: Arc<dyn SystemSettingService>,
+    external_url: Option<String>,
+}
+
+impl DefaultRobotsService {
+    pub fn new(system_setting_service: Arc<dyn SystemSettingService>) -> Self {
+        Self { system_setting_service, external_url: None }
+    }
+
+    /// 设置站点的外部访问地址，优先于请求中的地址用于生成Sitemap链接
+    pub fn with_external_url(mut self, external_url: Option<String>) -> Self {
+        self.external_url = external_url;
+        self
+    }
+}
+
+/// 生成默认的robots.txt：允许抓取前台页面，禁止后台和API路径，并引用站点地图
+pub fn default_robots_txt(site_url: &str) -> String {
+    let mut lines = vec!["User-agent: *".to_string(), "Allow: /".to_string()];
+    lines.extend(DISALLOWED_PATHS.iter().map(|path| format!("Disallow: {}", path)));
+    lines.push(String::new());
+    lines.push(format!("Sitemap: {}/sitemap.xml", site_url.trim_end_matches('/')));
+    lines.join("\n") + "\n"
+}
+
+/// 去除首尾空白，空内容视为未自定义
+fn normalize_custom(content: Option<String>) -> Option<String> {
+    content
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .map(|c| c + "\n")
+}
+
+#[async_trait]
+impl RobotsService for DefaultRobotsService {
+    async fn robots_txt(&self, site_url: &str) -> Result<String> {
+        if let Some(content) = self.get_custom().await? {
+            return Ok(content);
+        }
+        Ok(default_robots_txt(self.external_url.as_deref().unwrap_or(site_url)))
+    }
+
+    async fn get_custom(&self) -> Result<Option<String>> {
+        let setting = self.system_setting_service.get_robots_setting().await?;
+        Ok(normalize_custom(setting.and_then(|s| s.content)))
+    }
+
+    async fn update_custom(&self, content: Option<String>) -> Result<()> {
+        let content = normalize_custom(content);
+        if content.as_ref().is_some_and(|c| c.len() > MAX_ROBOTS_TXT_BYTES) {
+            anyhow::bail!("{}: limit is {} bytes", ROBOTS_TXT_TOO_LARGE_ERROR, MAX_ROBOTS_TXT_BYTES);
+        }
+        self.system_setting_service.update_robots_setting(RobotsSetting { content }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_robots_txt() {
+        let robots = default_robots_txt("https://example.com/");
+        assert!(robots.starts_with("User-agent: *\nAllow: /\n"));
+        assert!(robots.contains("Disallow: /console\n"));
+        assert!(robots.contains("Disallow: /api/\n"));
+        assert!(robots.ends_with("\nSitemap: https://example.com/sitemap.xml\n"));
+    }
+
+    #[test]
+    fn test_normalize_custom() {
+        assert_eq!(normalize_custom(Some("  \n".to_string())), None);
+        assert_eq!(normalize_custom(None), None);
+        assert_eq!(
+            normalize_custom(Some("\nUser-agent: *\nDisallow: /\n\n".to_string())),
+            Some("User-agent: *\nDisallow: /\n".to_string())
+        );
+    }
+}
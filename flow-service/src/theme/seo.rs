@@ -0,0 +1,288 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 分类、标签、专栏等以displayName作为标题的页面模型
+const TAXONOMY_MODELS: &[&str] = &["category", "tag", "series"];
+
+/// 页面的SEO信息
+struct PageSeo<'a> {
+    /// 内容标题，首页等列表页为None
+    title: Option<&'a str>,
+    description: Option<String>,
+    /// 封面图片（相对地址会转换为绝对地址）
+    cover: Option<&'a str>,
+    /// 文章模型（生成Article结构化数据）
+    article: Option<&'a Value>,
+}
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// 将站内路径转换为绝对地址
+fn absolute_url(site_url: &str, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") || path.starts_with("//") {
+        return path.to_string();
+    }
+    format!("{}/{}", site_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// 转义HTML属性值
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 从页面模型中识别当前页面的内容
+fn page_seo(model: &HashMap<String, Value>) -> PageSeo<'_> {
+    if let Some(post) = model.get("post").filter(|v| v.is_object()) {
+        return PageSeo {
+            title: str_at(post, "/spec/title"),
+            description: str_at(post, "/status/excerpt")
+                .or_else(|| str_at(post, "/spec/excerpt/raw"))
+                .map(str::to_string),
+            cover: str_at(post, "/spec/cover"),
+            article: Some(post),
+        };
+    }
+    if let Some(page) = model.get("singlePage").filter(|v| v.is_object()) {
+        return PageSeo {
+            title: str_at(page, "/spec/title"),
+            description: str_at(page, "/status/excerpt")
+                .or_else(|| str_at(page, "/spec/excerpt/raw"))
+                .map(str::to_string),
+            cover: str_at(page, "/spec/cover"),
+            article: None,
+        };
+    }
+    if let Some(author) = model.get("author").filter(|v| v.is_object()) {
+        return PageSeo {
+            title: str_at(author, "/displayName"),
+            description: str_at(author, "/bio").map(str::to_string),
+            cover: str_at(author, "/avatar"),
+            article: None,
+        };
+    }
+    for key in TAXONOMY_MODELS {
+        if let Some(value) = model.get(*key).filter(|v| v.is_object()) {
+            return PageSeo {
+                title: str_at(value, "/spec/displayName"),
+                description: str_at(value, "/spec/description").map(str::to_string),
+                cover: str_at(value, "/spec/cover"),
+                article: None,
+            };
+        }
+    }
+    PageSeo { title: None, description: None, cover: None, article: None }
+}
+
+/// 文章的Article结构化数据
+fn article_json_ld(post: &Value, headline: &str, seo: &Map<String, Value>, site: &Value, site_url: &str) -> Value {
+    let mut article = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "Article",
+        "headline": headline,
+        "mainEntityOfPage": seo["canonical"],
+    });
+    let fields = [
+        ("description", seo.get("description").cloned().unwrap_or(Value::Null)),
+        ("image", seo.get("image").cloned().unwrap_or(Value::Null)),
+        ("datePublished", post.pointer("/spec/publishTime").cloned().unwrap_or(Value::Null)),
+        ("dateModified", post.pointer("/status/lastModifyTime").cloned().unwrap_or(Value::Null)),
+    ];
+    for (key, value) in fields {
+        if !value.is_null() {
+            article[key] = value;
+        }
+    }
+    if let Some(owner) = str_at(post, "/spec/owner") {
+        article["author"] = serde_json::json!({"@type": "Person", "name": owner});
+    }
+    if let Some(title) = str_at(site, "/title") {
+        let mut publisher = serde_json::json!({"@type": "Organization", "name": title});
+        if let Some(logo) = str_at(site, "/logo") {
+            publisher["logo"] = serde_json::json!({"@type": "ImageObject", "url": absolute_url(site_url, logo)});
+        }
+        article["publisher"] = publisher;
+    }
+    article
+}
+
+/// 首页到当前页面的BreadcrumbList结构化数据
+fn breadcrumb_json_ld(site: &Value, site_url: &str, title: &str, canonical: &str) -> Value {
+    let home = str_at(site, "/title").unwrap_or("Home");
+    serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": [
+            {"@type": "ListItem", "position": 1, "name": home, "item": absolute_url(site_url, "/")},
+            {"@type": "ListItem", "position": 2, "name": title, "item": canonical},
+        ],
+    })
+}
+
+/// 根据页面模型、站点信息（`site`）和请求信息（`request`）生成SEO数据（模板中的 `seo`）
+///
+/// 包含规范链接、标题、描述、图片、Open Graph和Twitter Card标签（`meta`）、
+/// JSON-LD结构化数据（`jsonLd`），以及可直接输出到`<head>`中的完整标记（`head`）
+pub fn seo_value(model: &HashMap<String, Value>, site: &Value, request: &Value) -> Value {
+    let site_url = str_at(site, "/url").unwrap_or_default();
+    let site_title = str_at(site, "/title");
+    let page = page_seo(model);
+    let path = str_at(request, "/path").unwrap_or("/");
+    let canonical = page.article
+        .and_then(|post| str_at(post, "/status/permalink"))
+        .map(|permalink| absolute_url(site_url, permalink))
+        .unwrap_or_else(|| absolute_url(site_url, path));
+    let title = match (page.title, site_title) {
+        (Some(title), Some(site_title)) => format!("{} - {}", title, site_title),
+        (Some(title), None) => title.to_string(),
+        (None, site_title) => site_title.unwrap_or_default().to_string(),
+    };
+    let description = page.description.or_else(|| str_at(site, "/subtitle").map(str::to_string));
+    let image = page.cover.or_else(|| str_at(site, "/logo")).map(|image| absolute_url(site_url, image));
+
+    let mut seo = Map::new();
+    seo.insert("canonical".to_string(), Value::String(canonical.clone()));
+    seo.insert("title".to_string(), Value::String(title));
+    seo.insert("description".to_string(), description.clone().map(Value::String).unwrap_or(Value::Null));
+    seo.insert("image".to_string(), image.clone().map(Value::String).unwrap_or(Value::Null));
+
+    let og_type = if page.article.is_some() { "article" } else { "website" };
+    let og_title = page.title.or(site_title).unwrap_or_default();
+    let mut meta: Vec<(&str, &str, String)> = vec![
+        ("property", "og:type", og_type.to_string()),
+        ("property", "og:title", og_title.to_string()),
+        ("property", "og:url", canonical.clone()),
+    ];
+    if let Some(site_title) = site_title {
+        meta.push(("property", "og:site_name", site_title.to_string()));
+    }
+    if let Some(description) = &description {
+        meta.push(("property", "og:description", description.clone()));
+    }
+    if let Some(image) = &image {
+        meta.push(("property", "og:image", image.clone()));
+    }
+    if let Some(post) = page.article {
+        if let Some(time) = str_at(post, "/spec/publishTime") {
+            meta.push(("property", "article:published_time", time.to_string()));
+        }
+        if let Some(time) = str_at(post, "/status/lastModifyTime") {
+            meta.push(("property", "article:modified_time", time.to_string()));
+        }
+    }
+    let card = if page.cover.is_some() { "summary_large_image" } else { "summary" };
+    meta.push(("name", "twitter:card", card.to_string()));
+    meta.push(("name", "twitter:title", og_title.to_string()));
+    if let Some(description) = &description {
+        meta.push(("name", "twitter:description", description.clone()));
+    }
+    if let Some(image) = &image {
+        meta.push(("name", "twitter:image", image.clone()));
+    }
+
+    let mut json_ld = Vec::new();
+    if let Some(post) = page.article {
+        json_ld.push(article_json_ld(post, og_title, &seo, site, site_url));
+    }
+    if let Some(title) = page.title {
+        json_ld.push(breadcrumb_json_ld(site, site_url, title, &canonical));
+    }
+
+    let mut head = vec![format!("<link rel=\"canonical\" href=\"{}\">", escape_html(&canonical))];
+    if let Some(description) = &description {
+        head.push(format!("<meta name=\"description\" content=\"{}\">", escape_html(description)));
+    }
+    head.extend(meta.iter().map(|(attr, key, content)| {
+        format!("<meta {}=\"{}\" content=\"{}\">", attr, key, escape_html(content))
+    }));
+    for data in &json_ld {
+        // 避免内容中的 `</script>` 提前结束脚本
+        let json = data.to_string().replace("</", "<\\/");
+        head.push(format!("<script type=\"application/ld+json\">{}</script>", json));
+    }
+
+    let meta = meta.into_iter().map(|(attr, key, content)| {
+        let mut tag = Map::new();
+        tag.insert(attr.to_string(), Value::String(key.to_string()));
+        tag.insert("content".to_string(), Value::String(content));
+        Value::Object(tag)
+    });
+    seo.insert("meta".to_string(), Value::Array(meta.collect()));
+    seo.insert("jsonLd".to_string(), Value::Array(json_ld));
+    seo.insert("head".to_string(), Value::String(head.join("\n")));
+    Value::Object(seo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> Value {
+        serde_json::json!({
+            "title": "Flow",
+            "subtitle": "A blog",
+            "logo": "/upload/logo.png",
+            "url": "https://example.com",
+        })
+    }
+
+    #[test]
+    fn test_seo_value_for_post() {
+        let model = HashMap::from([("post".to_string(), serde_json::json!({
+            "spec": {
+                "title": "Hello <World>",
+                "cover": "/upload/cover.png",
+                "owner": "admin",
+                "publishTime": "2024-01-01T00:00:00Z",
+            },
+            "status": { "permalink": "/archives/hello", "excerpt": "An \"intro\"" },
+        }))]);
+        let request = serde_json::json!({"path": "/archives/hello", "query": {"utm": "x"}});
+        let seo = seo_value(&model, &site(), &request);
+
+        assert_eq!(seo["canonical"], "https://example.com/archives/hello");
+        assert_eq!(seo["title"], "Hello <World> - Flow");
+        assert_eq!(seo["image"], "https://example.com/upload/cover.png");
+        assert_eq!(seo["jsonLd"][0]["@type"], "Article");
+        assert_eq!(seo["jsonLd"][0]["author"]["name"], "admin");
+        assert_eq!(seo["jsonLd"][0]["publisher"]["logo"]["url"], "https://example.com/upload/logo.png");
+        assert_eq!(seo["jsonLd"][1]["@type"], "BreadcrumbList");
+        assert_eq!(seo["jsonLd"][1]["itemListElement"][1]["name"], "Hello <World>");
+
+        let head = seo["head"].as_str().unwrap();
+        assert!(head.contains("<meta property=\"og:type\" content=\"article\">"));
+        assert!(head.contains("<meta property=\"og:title\" content=\"Hello &lt;World&gt;\">"));
+        assert!(head.contains("<meta name=\"description\" content=\"An &quot;intro&quot;\">"));
+        assert!(head.contains("<meta name=\"twitter:card\" content=\"summary_large_image\">"));
+        assert!(head.contains("\"headline\":\"Hello <World>\""));
+        assert!(!head.contains("</World>"));
+    }
+
+    #[test]
+    fn test_seo_value_for_list_page() {
+        let request = serde_json::json!({"path": "/page/2"});
+        let seo = seo_value(&HashMap::new(), &site(), &request);
+        assert_eq!(seo["canonical"], "https://example.com/page/2");
+        assert_eq!(seo["title"], "Flow");
+        assert_eq!(seo["description"], "A blog");
+        assert_eq!(seo["meta"][0], serde_json::json!({"property": "og:type", "content": "website"}));
+        assert_eq!(seo["jsonLd"], serde_json::json!([]));
+
+        let category = HashMap::from([("category".to_string(), serde_json::json!({
+            "spec": { "displayName": "Rust", "description": null },
+        }))]);
+        let seo = seo_value(&category, &Value::Null, &request);
+        assert_eq!(seo["title"], "Rust");
+        assert_eq!(seo["canonical"], "/page/2");
+        assert_eq!(seo["jsonLd"][0]["@type"], "BreadcrumbList");
+    }
+}
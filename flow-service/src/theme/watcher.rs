@@ -0,0 +1,132 @@
+use crate::theme::ThemeService;
+use anyhow::Result;
+use flow_infra::theme::TemplateEngineManager;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 收到第一个变化后等待的时间，合并编辑器保存时产生的多个事件
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 主题清单和设置表单文件，变化时需要重新读取
+const MANIFEST_FILES: &[&str] = &["theme.yaml", "theme.yml", "settings.yaml", "settings.yml"];
+
+/// 主题目录中发生变化的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeFileChange {
+    /// 主题名称（主题根目录下的一级目录名）
+    pub theme_name: String,
+    /// 是否为主题清单或设置表单文件
+    pub manifest: bool,
+}
+
+/// 解析变化文件所属的主题，忽略主题根目录下的文件、隐藏文件和编辑器临时文件
+pub fn theme_file_change(theme_root: &Path, path: &Path) -> Option<ThemeFileChange> {
+    let relative = path.strip_prefix(theme_root).ok()?;
+    let mut components = relative.components();
+    let Some(Component::Normal(theme_name)) = components.next() else {
+        return None;
+    };
+    let file_name = relative.file_name()?.to_str()?;
+    if components.next().is_none()
+        || file_name.starts_with('.')
+        || file_name.ends_with('~')
+        || file_name.ends_with(".swp")
+    {
+        return None;
+    }
+    Some(ThemeFileChange {
+        theme_name: theme_name.to_str()?.to_string(),
+        manifest: relative.components().count() == 2 && MANIFEST_FILES.contains(&file_name),
+    })
+}
+
+/// 监听主题目录（开发模式），模板或静态资源变化时清除主题的模板引擎缓存
+///
+/// 设置了theme_service时，主题清单或设置表单变化后重新加载主题。
+/// 监听在后台任务中持续运行，无法监听目录时返回错误
+pub fn spawn_theme_watcher(
+    theme_root: PathBuf,
+    template_engine_manager: Arc<TemplateEngineManager>,
+    theme_service: Option<Arc<dyn ThemeService>>,
+) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) => {
+            let _ = tx.send(event);
+        }
+        Err(e) => tracing::warn!("Theme watcher error: {}", e),
+    })?;
+    std::fs::create_dir_all(&theme_root)?;
+    watcher.watch(&theme_root, RecursiveMode::Recursive)?;
+    tracing::info!("Watching theme directory {:?} for changes", theme_root);
+
+    tokio::spawn(async move {
+        // 监听器随任务一直存活
+        let _watcher = watcher;
+        while let Some(event) = rx.recv().await {
+            tokio::time::sleep(DEBOUNCE).await;
+            let mut events = vec![event];
+            while let Ok(event) = rx.try_recv() {
+                events.push(event);
+            }
+
+            // 主题名称 -> 清单是否变化
+            let mut changed: HashMap<String, bool> = HashMap::new();
+            let changes = events.iter()
+                .filter(|event| matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)))
+                .flat_map(|event| event.paths.iter())
+                .filter_map(|path| theme_file_change(&theme_root, path));
+            for change in changes {
+                *changed.entry(change.theme_name).or_default() |= change.manifest;
+            }
+
+            for (theme_name, manifest) in changed {
+                if manifest {
+                    if let Some(theme_service) = &theme_service {
+                        if let Err(e) = theme_service.reload_theme(&theme_name).await {
+                            tracing::warn!("Failed to reload theme {} after manifest change: {}", theme_name, e);
+                        }
+                    }
+                }
+                template_engine_manager.clear_cache(&theme_name).await;
+                tracing::info!("Theme {} changed, template cache cleared", theme_name);
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_file_change() {
+        let root = Path::new("/work/themes");
+        assert_eq!(
+            theme_file_change(root, Path::new("/work/themes/earth/templates/post.html")),
+            Some(ThemeFileChange { theme_name: "earth".to_string(), manifest: false })
+        );
+        assert_eq!(
+            theme_file_change(root, Path::new("/work/themes/earth/theme.yaml")),
+            Some(ThemeFileChange { theme_name: "earth".to_string(), manifest: true })
+        );
+        assert_eq!(
+            theme_file_change(root, Path::new("/work/themes/earth/templates/theme.yaml")).map(|c| c.manifest),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_theme_file_change_ignored() {
+        let root = Path::new("/work/themes");
+        assert_eq!(theme_file_change(root, Path::new("/work/themes/earth")), None);
+        assert_eq!(theme_file_change(root, Path::new("/work/themes/earth/templates/.post.html.swp")), None);
+        assert_eq!(theme_file_change(root, Path::new("/work/themes/earth/templates/post.html~")), None);
+        assert_eq!(theme_file_change(root, Path::new("/other/earth/post.html")), None);
+    }
+}
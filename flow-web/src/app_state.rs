@@ -1,15 +1,20 @@
 use flow_api::security::AuthorizationManager;
+use flow_api::extension::scheme::SharedSchemeManager;
 use flow_service::security::{AuthService, RoleService, UserService, PasswordService, UserConnectionService, TotpAuthService};
-use flow_service::content::{PostService, SinglePageService, CommentService, CategoryService, TagService, SnapshotService};
-use flow_service::search::SearchService;
-use flow_service::attachment::{AttachmentService, PolicyService, GroupService, SharedUrlService};
-use flow_service::theme::ThemeService;
-use flow_service::notification::{NotificationService, NotificationCenter};
+use flow_service::content::{PostService, SinglePageService, CommentService, CategoryService, TagService, MenuService, LinkService, MomentService, PhotoService, SeriesService, SnapshotService, LinkCheckService, ContentConverterRegistry, PublishValidatorRegistry, CoverService, PostAccessService, SlugRedirectService, CommentReactionService};
+use flow_service::search::{SearchService, SearchEngineRegistry};
+use flow_service::attachment::{AttachmentService, PolicyService, GroupService, SharedUrlService, UploadSessionService, ImageTransformService, QuotaService, AttachmentMigrationService, AttachmentArchiveService, AttachmentReferenceService, RemoteImportService, ThumbnailJobService, AttachmentBatchService, PolicyStorageResolver};
+use flow_service::theme::{ThemeService, ThemeConfigService, RobotsService, TemplateContextRegistry};
+use flow_service::notification::{
+    NotificationService, NotificationCenter, NotifierRegistry, NotificationPreferenceService, NotifierDescriptorService, ReasonTypeService,
+};
+use flow_service::plugin::{PluginConfigService, PluginCapabilityService, PluginInstallService, PluginAssetService, PluginLifecycleService};
+use flow_plugin::{EventBus, PluginLogBuffer};
 use flow_service::migration::{BackupService, DefaultRestoreService};
 use flow_infra::{
     security::{JwtService, SessionService, RateLimiter, OAuth2TokenCache, OAuth2StateCache, TwoFactorAuthCache},
     extension::ReactiveExtensionClient,
-    theme::{ThemeResolver, TemplateEngineManager},
+    theme::{ThemeResolver, TemplateEngineManager, DefaultFinderRegistry},
     websocket::WebSocketEndpointManager,
 };
 use std::sync::Arc;
@@ -25,27 +30,92 @@ pub struct AppState {
     pub session_service: Arc<dyn SessionService>,
     pub rate_limiter: Arc<dyn RateLimiter>,
     pub extension_client: Arc<ReactiveExtensionClient>,
+    /// 扩展类型注册表（插件启动时注册其扩展类型，`/apis` 端点按其校验和索引）
+    pub scheme_manager: SharedSchemeManager,
     pub user_service: Arc<dyn UserService>,
     pub role_service: Arc<dyn RoleService>,
     pub password_service: Arc<dyn PasswordService>,
     pub post_service: Arc<dyn PostService>,
+    pub post_access_service: Arc<dyn PostAccessService>,
     pub single_page_service: Arc<dyn SinglePageService>,
     pub comment_service: Arc<dyn CommentService>,
+    pub comment_reaction_service: Arc<dyn CommentReactionService>,
     pub category_service: Arc<dyn CategoryService>,
     pub tag_service: Arc<dyn TagService>,
+    pub menu_service: Arc<dyn MenuService>,
+    pub link_service: Arc<dyn LinkService>,
+    pub moment_service: Arc<dyn MomentService>,
+    pub photo_service: Arc<dyn PhotoService>,
+    pub series_service: Arc<dyn SeriesService>,
+    pub slug_redirect_service: Arc<dyn SlugRedirectService>,
     pub snapshot_service: Arc<dyn SnapshotService>,
     pub search_service: Arc<dyn SearchService>,
+    /// 搜索引擎注册表（插件可注册接管或补充内置引擎的搜索引擎）
+    pub search_engine_registry: Arc<SearchEngineRegistry>,
     pub attachment_service: Arc<dyn AttachmentService>,   
+    /// 分片上传会话服务（tus协议）
+    pub upload_session_service: Arc<dyn UploadSessionService>,
+    /// 图片处理服务（按参数缩放附件图片）
+    pub image_transform_service: Arc<dyn ImageTransformService>,
+    /// 存储配额服务
+    pub quota_service: Arc<dyn QuotaService>,
+    /// 附件迁移服务
+    pub attachment_migration_service: Arc<dyn AttachmentMigrationService>,
+    /// 附件打包下载服务
+    pub attachment_archive_service: Arc<dyn AttachmentArchiveService>,
+    /// 附件引用跟踪服务
+    pub attachment_reference_service: Arc<dyn AttachmentReferenceService>,
+    /// 远程导入服务
+    pub remote_import_service: Arc<dyn RemoteImportService>,
+    /// 缩略图重新生成任务服务
+    pub thumbnail_job_service: Arc<dyn ThumbnailJobService>,
+    /// 附件批量操作服务
+    pub attachment_batch_service: Arc<dyn AttachmentBatchService>,
     pub policy_service: Arc<dyn PolicyService>,
+    /// 存储策略解析器（内置及插件注册的存储模板）
+    pub storage_resolver: Arc<PolicyStorageResolver>,
     pub group_service: Arc<dyn GroupService>,
     pub shared_url_service: Arc<dyn SharedUrlService>,
+    pub link_check_service: Arc<dyn LinkCheckService>,
+    /// 内容格式注册表（插件可注册额外的格式）
+    pub content_converter_registry: Arc<ContentConverterRegistry>,
+    pub cover_service: Arc<dyn CoverService>,
+    /// 文章发布校验器注册表（插件可注册额外的校验）
+    pub publish_validator_registry: Arc<PublishValidatorRegistry>,
     pub theme_service: Arc<dyn ThemeService>,
+    pub theme_config_service: Arc<dyn ThemeConfigService>,
+    pub robots_service: Arc<dyn RobotsService>,
+    /// 插件设置服务
+    pub plugin_config_service: Arc<dyn PluginConfigService>,
+    /// 插件能力审批服务
+    pub plugin_capability_service: Arc<dyn PluginCapabilityService>,
+    /// 插件远程安装服务
+    pub plugin_install_service: Arc<dyn PluginInstallService>,
+    /// 插件前端资源服务
+    pub plugin_asset_service: Arc<dyn PluginAssetService>,
+    pub plugin_lifecycle_service: Arc<dyn PluginLifecycleService>,
+    /// 插件最近输出的日志
+    pub plugin_logs: Arc<PluginLogBuffer>,
+    /// 插件事件总线（发布领域事件给订阅的插件）
+    pub event_bus: Arc<EventBus>,
     pub theme_root: PathBuf,
     pub theme_resolver: Arc<ThemeResolver>,
     pub template_engine_manager: Arc<TemplateEngineManager>,
+    /// 渲染主题页面时预加载数据的Finder注册表（插件可注册额外的Finder）
+    pub finder_registry: Arc<DefaultFinderRegistry>,
+    /// 主题渲染时注入的全局模板变量注册表（插件可注册额外的变量）
+    pub template_context_registry: Arc<TemplateContextRegistry>,
     pub websocket_manager: Arc<WebSocketEndpointManager>,
     pub notification_service: Arc<dyn NotificationService>,
     pub notification_center: Arc<dyn NotificationCenter>,
+    /// 通知器注册表（插件可注册短信、钉钉等通知器）
+    pub notifier_registry: Arc<NotifierRegistry>,
+    /// 用户通知偏好服务
+    pub notification_preference_service: Arc<dyn NotificationPreferenceService>,
+    /// 通知渠道描述及其发送者、接收者配置
+    pub notifier_descriptor_service: Arc<dyn NotifierDescriptorService>,
+    /// 原因类型注册表
+    pub reason_type_service: Arc<dyn ReasonTypeService>,
     pub backup_service: Arc<dyn BackupService>,
     pub restore_service: Arc<DefaultRestoreService>,
     pub user_connection_service: Arc<dyn UserConnectionService>,
@@ -55,5 +125,7 @@ pub struct AppState {
     pub totp_auth_service: Arc<dyn TotpAuthService>,
     /// TOTP发行者名称（用于2FA二维码）
     pub totp_issuer: String,
+    /// 受信任的反向代理地址，见 [`crate::extractors::ClientIp`]
+    pub trusted_proxies: Arc<Vec<std::net::IpAddr>>,
 }
 
@@ -1,9 +1,17 @@
 pub mod multipart_with_user;
 
-use axum::extract::FromRequestParts;
+use axum::extract::{ConnectInfo, FromRequestParts, Query};
 use axum::http::request::Parts;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
+use axum::http::header::COOKIE;
 use flow_api::security::AuthenticatedUser;
+use flow_infra::theme::ThemePreview;
+use flow_service::theme::RenderRequest;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use crate::AppState;
+use crate::handlers::robots::site_url_from_headers;
 
 /// 当前用户提取器
 /// 从请求扩展中提取已认证的用户信息
@@ -24,3 +32,105 @@ where
     }
 }
 
+
+/// 客户端地址提取器
+/// 使用连接的对端地址；只有对端是配置的受信任代理时，才从X-Forwarded-For（或X-Real-IP）中取客户端地址，
+/// 避免客户端通过伪造请求头冒充其他地址。没有连接信息时为None
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+        Ok(ClientIp(resolve_client_ip(peer, &parts.headers, &state.trusted_proxies)))
+    }
+}
+
+/// 根据对端地址和转发请求头确定客户端地址
+///
+/// X-Forwarded-For从右向左跳过受信任的代理，第一个不受信任的地址即为客户端地址
+pub fn resolve_client_ip(peer: Option<IpAddr>, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = peer?;
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+    let forwarded: Vec<IpAddr> = headers.get_all("x-forwarded-for").iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    if let Some(ip) = forwarded.iter().rev().find(|ip| !trusted_proxies.contains(ip)) {
+        return Some(*ip);
+    }
+    headers.get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .or(Some(peer))
+}
+
+/// 主题预览令牌的查询参数和Cookie名称
+pub const PREVIEW_THEME_PARAM: &str = "preview-theme";
+
+/// 主题预览提取器
+/// 从 `?preview-theme=` 参数或Cookie中读取预览令牌，只有令牌签发给当前登录用户时才生效，
+/// 其他访问者始终看到激活的主题
+pub struct PreviewTheme {
+    /// 有效的预览信息
+    pub preview: Option<ThemePreview>,
+    /// 查询参数中的令牌（需要写入Cookie），空字符串表示退出预览
+    pub query_token: Option<String>,
+    /// 当前请求信息，用于生成全局模板变量
+    pub request: RenderRequest,
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for PreviewTheme {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let query = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .map(|Query(params)| params)
+            .unwrap_or_default();
+        let query_token = query.get(PREVIEW_THEME_PARAM).cloned();
+        let cookie_token = parts.headers.get_all(COOKIE).iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == PREVIEW_THEME_PARAM)
+            .map(|(_, token)| token.to_string());
+        let user = parts.extensions.get::<AuthenticatedUser>();
+        let preview = match (query_token.as_deref().or(cookie_token.as_deref()), user) {
+            (Some(token), Some(user)) if !token.is_empty() => state.theme_resolver.verify_preview_token(token, &user.username),
+            _ => None,
+        };
+        let request = RenderRequest {
+            path: parts.uri.path().to_string(),
+            query,
+            site_url: site_url_from_headers(&parts.headers),
+            username: user.map(|user| user.username.clone()),
+        };
+        Ok(PreviewTheme { preview, query_token, request })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_client_ip() {
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.1.1.1, 2.2.2.2, 10.0.0.1".parse().unwrap());
+
+        // 未配置受信任代理时忽略转发请求头
+        assert_eq!(resolve_client_ip(Some(peer), &headers, &[]), Some(peer));
+        // 经过受信任代理时取最右侧不受信任的地址
+        assert_eq!(resolve_client_ip(Some(proxy), &headers, &[proxy]), Some("2.2.2.2".parse().unwrap()));
+        assert_eq!(resolve_client_ip(Some(proxy), &HeaderMap::new(), &[proxy]), Some(proxy));
+        assert_eq!(resolve_client_ip(None, &headers, &[proxy]), None);
+    }
+}
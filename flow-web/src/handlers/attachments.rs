@@ -1,13 +1,25 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
-use flow_domain::attachment::{Attachment, ThumbnailSize};
+use flow_domain::attachment::{Attachment, ThumbnailFormat, ThumbnailSize};
+use flow_service::attachment::thumbnail::negotiate_thumbnail_format;
 use flow_api::extension::{ListOptions, ListResult};
 use flow_api::extension::query::Condition;
-use crate::{AppState, extractors::multipart_with_user::MultipartWithUser};
+use flow_api::security::AuthenticatedUser;
+use flow_service::attachment::{ArchiveSelection, BatchOperation, ByteRange, TagUpdate, ImageTransform, MigrationRequest, RemoteImportRequest, SpooledFile, UploadValidationError, entity_tag, etag_matches, parse_range};
+use flow_service::attachment::image_transform::{INVALID_TRANSFORM_ERROR, NOT_AN_IMAGE_ERROR};
+use flow_service::attachment::quota_service::QUOTA_EXCEEDED_ERROR;
+use flow_service::attachment::reference::ATTACHMENT_REFERENCED_ERROR;
+use flow_service::attachment::thumbnail_job::THUMBNAIL_JOB_RUNNING_ERROR;
+use flow_service::attachment::batch::{BATCH_TARGET_NOT_FOUND_ERROR, MAX_BATCH_SIZE};
+use flow_service::attachment::remote_import::{REMOTE_FETCH_ERROR, REMOTE_FILE_TOO_LARGE_ERROR, REMOTE_MEDIA_TYPE_ERROR, REMOTE_URL_FORBIDDEN_ERROR};
+use flow_service::attachment::shared_url::SHARED_URL_CACHE_REQUIRED_ERROR;
+use flow_service::attachment::spool::FILE_TOO_LARGE_ERROR;
+use flow_service::attachment::upload_scanner::INFECTED_FILE_ERROR;
+use crate::{AppState, extractors::{CurrentUser, multipart_with_user::MultipartWithUser}};
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
@@ -18,8 +30,8 @@ pub async fn upload_attachment(
     State(state): State<AppState>,
     MultipartWithUser { mut multipart, user }: MultipartWithUser,
 ) -> Result<Response, StatusCode> {
-    // 1. 从multipart中提取文件和其他参数
-    let mut file_content = Vec::new();
+    // 1. 从multipart中提取文件和其他参数，文件内容边接收边写入临时文件
+    let mut file_content: Option<SpooledFile> = None;
     let mut filename = None;
     let mut media_type = None;
     let mut policy_name = None;
@@ -41,10 +53,31 @@ pub async fn upload_attachment(
                     media_type = Some(content_type.to_string());
                 }
                 
-                // 读取文件内容
-                while let Some(chunk) = field.chunk().await
-                    .map_err(|_| StatusCode::BAD_REQUEST)? {
-                    file_content.extend_from_slice(&chunk);
+                // 字段借用了multipart，通过通道转换为字节流
+                let (sender, receiver) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(4);
+                let pump = async move {
+                    loop {
+                        let item = match field.chunk().await {
+                            Ok(Some(chunk)) => Ok(chunk.to_vec()),
+                            Ok(None) => break,
+                            Err(e) => Err(std::io::Error::other(e.to_string())),
+                        };
+                        let failed = item.is_err();
+                        if sender.send(item).await.is_err() || failed {
+                            break;
+                        }
+                    }
+                };
+                let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+                    receiver.recv().await.map(|chunk| (chunk, receiver))
+                });
+                let (_, spooled) = tokio::join!(pump, state.attachment_service.spool(Box::pin(stream)));
+                match spooled {
+                    Ok(spooled) => file_content = Some(spooled),
+                    Err(e) if e.to_string().starts_with(FILE_TOO_LARGE_ERROR) => {
+                        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                    Err(_) => return Err(StatusCode::BAD_REQUEST),
                 }
             }
             "policyName" => {
@@ -73,11 +106,10 @@ pub async fn upload_attachment(
     
     let filename = filename.ok_or(StatusCode::BAD_REQUEST)?;
     
-    // 2. 验证文件大小（从配置读取，默认100MB）
-    const MAX_FILE_SIZE: usize = 100 * 1024 * 1024; // 100MB
-    if file_content.len() > MAX_FILE_SIZE {
-        return Err(StatusCode::PAYLOAD_TOO_LARGE);
-    }
+    // 2. 文件大小已在接收时按配置检查，超过时返回413
+    let file_content = file_content.ok_or(StatusCode::BAD_REQUEST)?
+        .into_stream().await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
     // 3. 获取当前用户（如果有）
     let owner_name = user.map(|u| u.username);
@@ -92,6 +124,13 @@ pub async fn upload_attachment(
         group_name,
     ).await {
         Ok(attachment) => Ok(Json(attachment).into_response()),
+        Err(e) if e.is::<UploadValidationError>() => Ok(upload_validation_response(&e)),
+        Err(e) if e.to_string().starts_with(QUOTA_EXCEEDED_ERROR) || e.to_string().starts_with(FILE_TOO_LARGE_ERROR) => {
+            Ok((StatusCode::PAYLOAD_TOO_LARGE, Json(json!({"error": e.to_string()}))).into_response())
+        }
+        Err(e) if e.to_string().starts_with(INFECTED_FILE_ERROR) => {
+            Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(json!({"error": e.to_string()}))).into_response())
+        }
         Err(e) => {
             eprintln!("Failed to upload attachment: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -99,6 +138,41 @@ pub async fn upload_attachment(
     }
 }
 
+/// 上传校验未通过时返回结构化的校验错误，文件过大为413，其余为415
+pub(crate) fn upload_validation_response(e: &anyhow::Error) -> Response {
+    match e.downcast_ref::<UploadValidationError>() {
+        Some(validation) if validation.is_too_large() => (StatusCode::PAYLOAD_TOO_LARGE, Json(validation)).into_response(),
+        Some(validation) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, Json(validation)).into_response(),
+        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// 获取我的附件存储用量和配额
+/// GET /api/v1alpha1/uc/attachments/-/usage
+pub async fn get_my_storage_usage(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+) -> Result<Response, StatusCode> {
+    match state.quota_service.user_usage(&username).await {
+        Ok(usage) => Ok(Json(usage).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取按存储策略、分组和用户统计的存储用量
+/// GET /api/v1alpha1/attachments/-/stats
+pub async fn get_storage_stats(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.quota_service.storage_stats().await {
+        Ok(stats) => Ok(Json(stats).into_response()),
+        Err(e) => {
+            tracing::error!("Failed to compute storage stats: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// 获取附件
 /// GET /api/v1alpha1/attachments/:name
 pub async fn get_attachment(
@@ -175,14 +249,66 @@ pub async fn list_attachments(
     }
 }
 
+/// 删除附件请求参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteAttachmentParams {
+    /// 附件仍被引用时是否强制删除
+    #[serde(default)]
+    pub force: bool,
+}
+
 /// 删除附件
-/// DELETE /api/v1alpha1/attachments/:name
+/// DELETE /api/v1alpha1/attachments/:name?force=true
+///
+/// 附件仍被文章、页面或主题引用时返回409和引用列表，除非指定force
 pub async fn delete_attachment(
     Path(name): Path<String>,
+    Query(params): Query<DeleteAttachmentParams>,
     State(state): State<AppState>,
 ) -> Result<Response, StatusCode> {
-    match state.attachment_service.delete(&name).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT.into_response()),
+    let attachment = match state.attachment_service.get(&name).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    let used_by = state.attachment_reference_service.used_by(&attachment).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !used_by.is_empty() {
+        if !params.force {
+            return Ok((StatusCode::CONFLICT, Json(json!({
+                "message": ATTACHMENT_REFERENCED_ERROR,
+                "usedBy": used_by,
+            }))).into_response());
+        }
+        tracing::warn!("Force deleting attachment {} referenced by {} subjects", name, used_by.len());
+    }
+    
+    if state.attachment_service.delete(&name).await.is_err() {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    
+    // 清除引用该附件的文章封面
+    if let Err(e) = state.cover_service.release_attachment(&attachment).await {
+        tracing::warn!("Failed to release covers referencing attachment {}: {}", name, e);
+    }
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// 获取引用附件的文章、页面和主题
+/// GET /api/v1alpha1/attachments/:name/references
+pub async fn get_attachment_references(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    let attachment = match state.attachment_service.get(&name).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    match state.attachment_reference_service.used_by(&attachment).await {
+        Ok(used_by) => Ok(Json(used_by).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -205,11 +331,227 @@ pub async fn update_attachment(
     }
 }
 
+/// 将附件迁移到其他存储策略和分组
+/// POST /api/v1alpha1/attachments/-/migrations
+pub async fn migrate_attachments(
+    State(state): State<AppState>,
+    Json(request): Json<MigrationRequest>,
+) -> Result<Response, StatusCode> {
+    if request.attachment_names.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    match state.attachment_migration_service.migrate(request).await {
+        Ok(report) => Ok(Json(report).into_response()),
+        Err(e) => {
+            tracing::error!("Failed to migrate attachments: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 批量删除请求
+#[derive(Deserialize)]
+pub struct BatchDeleteRequest {
+    #[serde(rename = "attachmentNames")]
+    pub attachment_names: Vec<String>,
+    /// 是否同时删除仍被引用的附件
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// 批量移动分组请求
+#[derive(Deserialize)]
+pub struct BatchMoveRequest {
+    #[serde(rename = "attachmentNames")]
+    pub attachment_names: Vec<String>,
+    /// 目标分组，为空表示未分组
+    #[serde(rename = "groupName")]
+    pub group_name: Option<String>,
+}
+
+/// 批量修改标签请求
+#[derive(Deserialize)]
+pub struct BatchRetagRequest {
+    #[serde(rename = "attachmentNames")]
+    pub attachment_names: Vec<String>,
+    #[serde(flatten)]
+    pub tags: TagUpdate,
+}
+
+/// 批量修改上传者请求
+#[derive(Deserialize)]
+pub struct BatchOwnerRequest {
+    #[serde(rename = "attachmentNames")]
+    pub attachment_names: Vec<String>,
+    #[serde(rename = "ownerName")]
+    pub owner_name: String,
+}
+
+/// 执行批量操作，返回成功和失败的附件
+async fn batch_response(state: &AppState, attachment_names: &[String], operation: BatchOperation) -> Result<Response, StatusCode> {
+    if attachment_names.is_empty() || attachment_names.len() > MAX_BATCH_SIZE {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    match state.attachment_batch_service.apply(attachment_names, operation).await {
+        Ok(report) => Ok(Json(report).into_response()),
+        Err(e) if e.to_string().starts_with(BATCH_TARGET_NOT_FOUND_ERROR) => {
+            Ok((StatusCode::BAD_REQUEST, Json(json!({ "message": e.to_string() }))).into_response())
+        }
+        Err(e) => {
+            tracing::error!("Failed to apply batch operation to attachments: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// 批量删除附件，仍被引用的附件记录为失败，除非指定force
+/// POST /api/v1alpha1/attachments/-/batch/delete
+pub async fn batch_delete_attachments(
+    State(state): State<AppState>,
+    Json(request): Json<BatchDeleteRequest>,
+) -> Result<Response, StatusCode> {
+    batch_response(&state, &request.attachment_names, BatchOperation::Delete { force: request.force }).await
+}
+
+/// 批量将附件移动到分组
+/// POST /api/v1alpha1/attachments/-/batch/group
+pub async fn batch_move_attachments(
+    State(state): State<AppState>,
+    Json(request): Json<BatchMoveRequest>,
+) -> Result<Response, StatusCode> {
+    let group_name = request.group_name.filter(|g| !g.is_empty());
+    batch_response(&state, &request.attachment_names, BatchOperation::MoveToGroup { group_name }).await
+}
+
+/// 批量修改附件标签
+/// POST /api/v1alpha1/attachments/-/batch/tags
+pub async fn batch_retag_attachments(
+    State(state): State<AppState>,
+    Json(request): Json<BatchRetagRequest>,
+) -> Result<Response, StatusCode> {
+    batch_response(&state, &request.attachment_names, BatchOperation::Retag(request.tags)).await
+}
+
+/// 批量修改附件上传者
+/// POST /api/v1alpha1/attachments/-/batch/owner
+pub async fn batch_change_attachment_owner(
+    State(state): State<AppState>,
+    Json(request): Json<BatchOwnerRequest>,
+) -> Result<Response, StatusCode> {
+    if request.owner_name.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    batch_response(&state, &request.attachment_names, BatchOperation::ChangeOwner { owner_name: request.owner_name }).await
+}
+
+/// 打包下载请求
+#[derive(Deserialize)]
+pub struct ArchiveRequest {
+    #[serde(rename = "attachmentNames")]
+    pub attachment_names: Vec<String>,
+}
+
+/// 以zip格式流式返回附件
+pub(crate) async fn archive_response(state: &AppState, selection: ArchiveSelection, filename: &str) -> Result<Response, StatusCode> {
+    let stream = match state.attachment_archive_service.archive(selection).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::error!("Failed to archive attachments: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let filename = filename.replace(['"', '\\', '\r', '\n'], "_");
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip".to_string()),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.zip\"", filename)),
+        ],
+        axum::body::Body::from_stream(stream),
+    ).into_response())
+}
+
+/// 将选中的附件打包下载
+/// POST /api/v1alpha1/attachments/-/archive
+pub async fn archive_attachments(
+    State(state): State<AppState>,
+    Json(request): Json<ArchiveRequest>,
+) -> Result<Response, StatusCode> {
+    if request.attachment_names.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    archive_response(&state, ArchiveSelection::Attachments(request.attachment_names), "attachments").await
+}
+
+/// 从外部地址导入附件
+/// POST /api/v1alpha1/attachments/-/import
+pub async fn import_attachment(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Json(request): Json<RemoteImportRequest>,
+) -> Result<Response, StatusCode> {
+    match state.remote_import_service.import(request, Some(username)).await {
+        Ok(attachment) => Ok(Json(attachment).into_response()),
+        Err(e) if e.is::<UploadValidationError>() => Ok(upload_validation_response(&e)),
+        Err(e) => {
+            let message = e.to_string();
+            let status = if message.starts_with(REMOTE_URL_FORBIDDEN_ERROR) {
+                StatusCode::BAD_REQUEST
+            } else if message.starts_with(REMOTE_FILE_TOO_LARGE_ERROR) || message.starts_with(QUOTA_EXCEEDED_ERROR) {
+                StatusCode::PAYLOAD_TOO_LARGE
+            } else if message.starts_with(REMOTE_MEDIA_TYPE_ERROR) {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            } else if message.starts_with(INFECTED_FILE_ERROR) {
+                StatusCode::UNPROCESSABLE_ENTITY
+            } else if message.starts_with(REMOTE_FETCH_ERROR) {
+                StatusCode::BAD_GATEWAY
+            } else {
+                tracing::error!("Failed to import attachment: {}", message);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            };
+            Ok((status, Json(json!({"error": message}))).into_response())
+        }
+    }
+}
+
+/// 重新生成缩略图请求
+#[derive(Debug, Default, Deserialize)]
+pub struct ThumbnailRegenerationRequest {
+    /// 是否重新生成所有附件的缩略图（包括已是最新的）
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// 启动缩略图重新生成任务
+/// POST /api/v1alpha1/attachments/-/thumbnails/regeneration
+pub async fn regenerate_thumbnails(
+    State(state): State<AppState>,
+    request: Option<Json<ThumbnailRegenerationRequest>>,
+) -> Result<Response, StatusCode> {
+    let force = request.map(|Json(r)| r.force).unwrap_or_default();
+    match state.thumbnail_job_service.start(force).await {
+        Ok(progress) => Ok((StatusCode::ACCEPTED, Json(progress)).into_response()),
+        Err(e) if e.to_string().starts_with(THUMBNAIL_JOB_RUNNING_ERROR) => Err(StatusCode::CONFLICT),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取缩略图重新生成任务的进度
+/// GET /api/v1alpha1/attachments/-/thumbnails/regeneration
+pub async fn get_thumbnail_regeneration(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.thumbnail_job_service.progress().await {
+        Some(progress) => Ok(Json(progress).into_response()),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
 /// 获取缩略图
 /// GET /api/v1alpha1/attachments/:name/thumbnails/:size
 pub async fn get_thumbnail(
     Path((name, size)): Path<(String, String)>,
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
 ) -> Result<Response, StatusCode> {
     // 1. 获取Attachment
     let attachment = match state.attachment_service.get(&name).await {
@@ -219,19 +561,26 @@ pub async fn get_thumbnail(
     };
     
     // 2. 解析缩略图尺寸
-    let _thumbnail_size = ThumbnailSize::from_str(&size)
+    let thumbnail_size = ThumbnailSize::from_str(&size)
         .ok_or(StatusCode::BAD_REQUEST)?;
     
-    // 3. 从status中获取缩略图URL
+    // 3. 从status中获取缩略图URL，按Accept头优先返回AVIF/WebP格式
     if let Some(ref status) = attachment.status {
         if let Some(ref thumbnails) = status.thumbnails {
-            if let Some(thumbnail_url) = thumbnails.get(&size.to_uppercase()) {
+            let available: Vec<ThumbnailFormat> = ThumbnailFormat::ALL.into_iter()
+                .filter(|format| thumbnails.contains_key(&ThumbnailFormat::thumbnail_key(thumbnail_size, Some(*format))))
+                .collect();
+            let accept = headers.get(axum::http::header::ACCEPT)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            let format = negotiate_thumbnail_format(accept, &available);
+            if let Some(thumbnail_url) = thumbnails.get(&ThumbnailFormat::thumbnail_key(thumbnail_size, format)) {
                 // 返回重定向到缩略图URL
                 // 注意：实际实现中，缩略图应该通过静态资源服务提供
                 // 这里返回URL，客户端可以重定向访问
                 return Ok((
                     StatusCode::TEMPORARY_REDIRECT,
-                    [("Location", thumbnail_url.as_str())],
+                    [("Location", thumbnail_url.as_str()), ("Vary", "Accept")],
                     thumbnail_url.clone(),
                 ).into_response());
             }
@@ -242,12 +591,163 @@ pub async fn get_thumbnail(
     Err(StatusCode::NOT_FOUND)
 }
 
+/// 签名链接查询参数
+#[derive(Deserialize)]
+pub struct SignedUrlQuery {
+    /// 有效期（秒），默认1小时
+    #[serde(rename = "expiresIn")]
+    pub expires_in: Option<u64>,
+}
+
+/// 生成附件的签名临时访问链接（私有Bucket的对象存储附件）
+/// GET /api/v1alpha1/attachments/:name/signed-url
+pub async fn get_signed_url(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Query(query): Query<SignedUrlQuery>,
+) -> Result<Response, StatusCode> {
+    match state.attachment_service.get(&name).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+    
+    let expires_in = std::time::Duration::from_secs(query.expires_in.unwrap_or(3600));
+    match state.attachment_service.signed_url(&name, expires_in).await {
+        Ok(Some(url)) => Ok(Json(json!({"url": url, "expiresIn": expires_in.as_secs()})).into_response()),
+        // 附件所在存储不支持签名链接
+        Ok(None) => Err(StatusCode::NOT_IMPLEMENTED),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 检查匿名访问者能否读取附件内容：已登录用户由授权中间件检查权限，匿名访问只允许存储策略开启了公开访问的附件
+async fn ensure_readable(state: &AppState, attachment: &Attachment, user: Option<&AuthenticatedUser>) -> Result<(), StatusCode> {
+    if user.is_some() {
+        return Ok(());
+    }
+    match state.attachment_service.is_public(attachment).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(StatusCode::UNAUTHORIZED),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 代理访问附件内容（没有公开链接的远程存储附件），匿名访问只允许存储策略开启了公开访问的附件
+/// GET /api/v1alpha1/attachments/:name/content
+pub async fn get_attachment_content(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let attachment = match state.attachment_service.get(&name).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    ensure_readable(&state, &attachment, user.as_deref()).await?;
+    
+    // 内容未变化时返回304
+    let etag = entity_tag(&attachment);
+    let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok());
+    if if_none_match.is_some_and(|tags| etag_matches(tags, &etag)) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+    
+    // 附件大小已知时支持Range请求，If-Range与ETag不一致时返回完整内容
+    let size = attachment.spec.size;
+    let range_header = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let if_range = headers.get(header::IF_RANGE).and_then(|v| v.to_str().ok());
+    let range = match size {
+        Some(size) if if_range.is_none_or(|tag| tag == etag) => parse_range(range_header, size),
+        _ => ByteRange::Full,
+    };
+    let content_type = attachment.spec.media_type.clone()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    
+    let content = match range {
+        ByteRange::Unsatisfiable => {
+            let content_range = format!("bytes */{}", size.unwrap_or(0));
+            return Ok((StatusCode::RANGE_NOT_SATISFIABLE, [(header::CONTENT_RANGE, content_range)]).into_response());
+        }
+        ByteRange::Full => state.attachment_service.read_content(&name).await,
+        ByteRange::Partial { start, len } => state.attachment_service.read_content_range(&name, start, len).await,
+    };
+    let stream = match content {
+        Ok(Some((_, stream))) => stream,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::BAD_GATEWAY),
+    };
+    
+    let (status, content_length, content_range) = match (range, size) {
+        (ByteRange::Partial { start, len }, Some(size)) => {
+            (StatusCode::PARTIAL_CONTENT, Some(len), Some(format!("bytes {}-{}/{}", start, start + len - 1, size)))
+        }
+        _ => (StatusCode::OK, size, None),
+    };
+    let mut response = (status, axum::body::Body::from_stream(stream)).into_response();
+    let response_headers = response.headers_mut();
+    let header_value = |value: String| value.parse().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    response_headers.insert(header::CONTENT_TYPE, header_value(content_type)?);
+    response_headers.insert(header::ETAG, header_value(etag)?);
+    if let Some(content_length) = content_length {
+        response_headers.insert(header::ACCEPT_RANGES, header_value("bytes".to_string())?);
+        response_headers.insert(header::CONTENT_LENGTH, header_value(content_length.to_string())?);
+    }
+    if let Some(content_range) = content_range {
+        response_headers.insert(header::CONTENT_RANGE, header_value(content_range)?);
+    }
+    Ok(response)
+}
+
+/// 按参数缩放附件图片，供主题请求指定尺寸的图片，匿名访问只允许存储策略开启了公开访问的附件
+/// GET /api/v1alpha1/attachments/:name/image?w=600&h=400&fit=cover&format=webp
+pub async fn get_transformed_image(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    user: Option<Extension<AuthenticatedUser>>,
+    Query(transform): Query<ImageTransform>,
+) -> Result<Response, StatusCode> {
+    let attachment = match state.attachment_service.get(&name).await {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    ensure_readable(&state, &attachment, user.as_deref()).await?;
+    // 需要登录才能访问的图片不允许共享缓存，匿名访问通过检查时附件必然公开
+    let public = user.is_none() || state.attachment_service.is_public(&attachment).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let cache_control = if public {
+        "public, max-age=31536000, immutable"
+    } else {
+        "private, max-age=31536000, immutable"
+    };
+    
+    match state.image_transform_service.transform(&name, &transform).await {
+        Ok(Some(image)) => Ok((
+            [
+                (axum::http::header::CONTENT_TYPE, image.media_type),
+                (axum::http::header::CACHE_CONTROL, cache_control),
+            ],
+            image.content,
+        ).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) if e.to_string().starts_with(INVALID_TRANSFORM_ERROR) => Err(StatusCode::BAD_REQUEST),
+        Err(e) if e.to_string().starts_with(NOT_AN_IMAGE_ERROR) => Err(StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 /// 生成共享URL
 /// POST /api/v1alpha1/attachments/:name/shared-urls
 #[derive(Deserialize)]
 pub struct GenerateSharedUrlRequest {
     #[serde(rename = "expiresInHours")]
     pub expires_in_hours: Option<u32>,
+    /// 最大下载次数，为空表示不限制
+    #[serde(rename = "maxDownloads")]
+    pub max_downloads: Option<u32>,
 }
 
 pub async fn generate_shared_url(
@@ -263,8 +763,9 @@ pub async fn generate_shared_url(
     }
     
     // 生成共享URL
-    match state.shared_url_service.generate_shared_url(&name, request.expires_in_hours) {
+    match state.shared_url_service.generate_shared_url(&name, request.expires_in_hours, request.max_downloads).await {
         Ok(shared_url) => Ok(Json(shared_url).into_response()),
+        Err(e) if e.to_string().starts_with(SHARED_URL_CACHE_REQUIRED_ERROR) => Err(StatusCode::NOT_IMPLEMENTED),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -275,7 +776,7 @@ pub async fn list_shared_urls(
     Path(name): Path<String>,
     State(state): State<AppState>,
 ) -> Result<Response, StatusCode> {
-    match state.shared_url_service.get_shared_urls(&name) {
+    match state.shared_url_service.get_shared_urls(&name).await {
         Ok(urls) => Ok(Json(json!({"items": urls})).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
@@ -287,30 +788,42 @@ pub async fn revoke_shared_url(
     Path(token): Path<String>,
     State(state): State<AppState>,
 ) -> Result<Response, StatusCode> {
-    match state.shared_url_service.revoke_shared_url(&token) {
+    match state.shared_url_service.revoke_shared_url(&token).await {
         Ok(_) => Ok(StatusCode::NO_CONTENT.into_response()),
+        Err(e) if e.to_string().starts_with(SHARED_URL_CACHE_REQUIRED_ERROR) => Err(StatusCode::NOT_IMPLEMENTED),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
-/// 通过共享URL访问附件
+/// 通过共享URL下载附件
 /// GET /api/v1alpha1/attachments/shared/:token
 pub async fn get_attachment_by_shared_url(
     Path(token): Path<String>,
     State(state): State<AppState>,
 ) -> Result<Response, StatusCode> {
-    // 验证token
-    let attachment_name = match state.shared_url_service.validate_token(&token) {
+    // 验证token（计一次下载）
+    let attachment_name = match state.shared_url_service.validate_token(&token).await {
         Ok(Some(name)) => name,
         Ok(None) => return Err(StatusCode::NOT_FOUND),
         Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
     };
     
-    // 获取附件
-    match state.attachment_service.get(&attachment_name).await {
-        Ok(Some(attachment)) => Ok(Json(attachment).into_response()),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    let (attachment, stream) = match state.attachment_service.read_content(&attachment_name).await {
+        Ok(Some(content)) => content,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::BAD_GATEWAY),
+    };
+    let content_type = attachment.spec.media_type.clone()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let filename = attachment.spec.display_name.as_deref()
+        .unwrap_or(&attachment.metadata.name)
+        .replace(['"', '\\', '\r', '\n'], "_");
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type),
+            (axum::http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            (axum::http::header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        axum::body::Body::from_stream(stream),
+    ).into_response())
 }
-
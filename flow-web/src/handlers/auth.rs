@@ -7,6 +7,7 @@ use axum::{
 use flow_api::security::AuthenticatedUser;
 use flow_domain::security::User;
 use flow_infra::security::TwoFactorAuthState;
+use flow_plugin::event::{Event, USER_LOGGED_IN};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
@@ -185,6 +186,11 @@ pub async fn login(
         Err(_) => None, // Session创建失败不影响登录
     };
 
+    state.event_bus.publish(Event::new(USER_LOGGED_IN, serde_json::json!({
+        "username": request.username,
+        "roles": roles,
+    })));
+
     let expires_in = state.jwt_service.expiration();
     let response = LoginResponse {
         access_token: token,
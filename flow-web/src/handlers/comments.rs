@@ -1,13 +1,18 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
-use flow_domain::content::Comment;
+use flow_domain::content::{constant::POST_KIND, Comment};
 use flow_api::extension::ListOptions;
+use flow_api::security::AuthenticatedUser;
+use flow_service::content::{PublicCommentQuery, PublicReplyQuery};
 use crate::AppState;
-use serde::Serialize;
+use crate::extractors::ClientIp;
+use crate::handlers::post_access::is_post_locked;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 
 /// Comment列表响应
 #[derive(Debug, Serialize)]
@@ -110,3 +115,160 @@ pub async fn approve_comment(
     }
 }
 
+/// 垃圾评论审核操作
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpamReviewAction {
+    /// 非垃圾评论：恢复并批准
+    Ham,
+    /// 确认为垃圾评论：删除
+    Spam,
+}
+
+/// 垃圾评论批量审核请求
+#[derive(Debug, Deserialize)]
+pub struct SpamReviewRequest {
+    pub names: Vec<String>,
+    pub action: SpamReviewAction,
+}
+
+/// 垃圾评论批量审核响应
+#[derive(Debug, Serialize)]
+pub struct SpamReviewResponse {
+    pub reviewed: usize,
+}
+
+/// 列出垃圾评论
+/// GET /api/v1alpha1/spam-comments
+pub async fn list_spam_comments(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.comment_service.list_spam().await {
+        Ok(comments) => Ok(Json(comments).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 批量审核垃圾评论
+/// POST /api/v1alpha1/spam-comments/review
+pub async fn review_spam_comments(
+    State(state): State<AppState>,
+    Json(request): Json<SpamReviewRequest>,
+) -> Result<Response, StatusCode> {
+    let is_spam = matches!(request.action, SpamReviewAction::Spam);
+    match state.comment_service.review_spam(&request.names, is_spam).await {
+        Ok(reviewed) => Ok(Json(SpamReviewResponse { reviewed }).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取回应者身份（已登录用户使用用户名，匿名访客使用客户端地址）
+fn reaction_identity(user: Option<&AuthenticatedUser>, client_ip: Option<IpAddr>) -> String {
+    if let Some(user) = user {
+        return format!("user:{}", user.username);
+    }
+    match client_ip {
+        Some(ip) => format!("ip:{}", ip),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// 将回应服务的错误转换为状态码
+fn reaction_error_status(e: &(dyn std::error::Error + Send + Sync)) -> StatusCode {
+    let message = e.to_string();
+    if message.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else if message.starts_with("Unsupported reaction") {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// 添加评论回应（幂等，同一用户或IP重复回应不会重复计数）
+/// POST /api/v1alpha1/comments/{name}/reactions/{reaction}
+pub async fn react_to_comment(
+    State(state): State<AppState>,
+    Path((name, reaction)): Path<(String, String)>,
+    user: Option<Extension<AuthenticatedUser>>,
+    ClientIp(client_ip): ClientIp,
+) -> Result<Response, StatusCode> {
+    let identity = reaction_identity(user.as_deref(), client_ip);
+    match state.comment_reaction_service.react(&name, &reaction, &identity).await {
+        Ok(comment) => Ok(Json(comment.status_or_default().reactions.unwrap_or_default()).into_response()),
+        Err(e) => Err(reaction_error_status(e.as_ref())),
+    }
+}
+
+/// 取消评论回应
+/// DELETE /api/v1alpha1/comments/{name}/reactions/{reaction}
+pub async fn remove_comment_reaction(
+    State(state): State<AppState>,
+    Path((name, reaction)): Path<(String, String)>,
+    user: Option<Extension<AuthenticatedUser>>,
+    ClientIp(client_ip): ClientIp,
+) -> Result<Response, StatusCode> {
+    let identity = reaction_identity(user.as_deref(), client_ip);
+    match state.comment_reaction_service.unreact(&name, &reaction, &identity).await {
+        Ok(comment) => Ok(Json(comment.status_or_default().reactions.unwrap_or_default()).into_response()),
+        Err(e) => Err(reaction_error_status(e.as_ref())),
+    }
+}
+
+/// 列出主题下公开的评论（访客可见，不包含邮箱、IP），使用游标分页
+/// GET /api/v1alpha1/comments/-/public?kind=Post&name={post}&sort=popularity&cursor=...
+pub async fn list_public_comments(
+    State(state): State<AppState>,
+    Query(query): Query<PublicCommentQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    // 未解锁的加密文章不公开评论
+    if query.kind == POST_KIND && is_post_locked(&state, &query.name, &headers).await {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    match state.comment_service.list_public(&query).await {
+        Ok(page) => Ok(Json(page).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出评论下公开的回复，按创建时间正序
+/// GET /api/v1alpha1/comments/{name}/replies
+pub async fn list_public_replies(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<PublicReplyQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let subject_ref = match state.comment_service.get(&name).await {
+        Ok(Some(comment)) => comment.spec.subject_ref,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    if subject_ref.kind == POST_KIND && is_post_locked(&state, &subject_ref.name, &headers).await {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    match state.comment_service.list_public_replies(&name, &query).await {
+        Ok(Some(page)) => Ok(Json(page).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::resolve_client_ip;
+
+    #[test]
+    fn test_reaction_identity_ignores_spoofed_forwarded_for() {
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let mut spoofed = HeaderMap::new();
+        spoofed.insert("x-forwarded-for", "198.51.100.1".parse().unwrap());
+
+        let honest = reaction_identity(None, resolve_client_ip(Some(peer), &HeaderMap::new(), &[]));
+        let forged = reaction_identity(None, resolve_client_ip(Some(peer), &spoofed, &[]));
+        assert_eq!(honest, "ip:203.0.113.7");
+        assert_eq!(forged, honest);
+    }
+}
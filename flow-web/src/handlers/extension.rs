@@ -5,8 +5,9 @@ use axum::{
     Json,
 };
 use flow_api::extension::{Extension, ExtensionClient, GroupVersionKind, ListOptions};
+use flow_api::extension::scheme::{Scheme, SchemeIndex};
 use crate::{AppState, handlers::extension_utils::DynamicExtension};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 
 /// Extension端点路径参数
@@ -39,6 +40,33 @@ fn parse_extension_path(path: &str) -> Option<ExtensionPath> {
     })
 }
 
+/// 按注册的Scheme校验扩展对象，不符合时返回错误响应，未注册的类型不校验
+fn scheme_violation(state: &AppState, gvk: &GroupVersionKind, extension: &Value) -> Option<Response> {
+    let Ok(schemes) = state.scheme_manager.read() else {
+        return Some(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+    let errors = schemes.get(gvk)?.validate(extension).err()?;
+    Some((
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": format!("Invalid extension: {}", errors.join("; "))})),
+    ).into_response())
+}
+
+/// 按Scheme中注册的索引解析字段选择器（`name=value`，多个条件以逗号分隔）
+fn field_filters(scheme: &Scheme, selector: &str) -> Result<Vec<(SchemeIndex, String)>, String> {
+    selector.split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| {
+            let (name, value) = term.split_once('=')
+                .ok_or_else(|| format!("Invalid field selector: {}", term))?;
+            let index = scheme.index(name.trim())
+                .ok_or_else(|| format!("Field {} is not indexed", name.trim()))?;
+            Ok((index.clone(), value.trim().to_string()))
+        })
+        .collect()
+}
+
 /// 获取Extension
 /// GET /apis/{group}/{version}/{resource}/{name}
 pub async fn get_extension(
@@ -107,6 +135,18 @@ pub async fn list_extensions(
         &extension_path.resource,
     );
     
+    // 注册了Scheme的类型按其索引过滤字段选择器
+    let filters = {
+        let schemes = state.scheme_manager.read().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        match (schemes.get(&gvk), params.get("fieldSelector")) {
+            (Some(scheme), Some(selector)) => match field_filters(scheme, selector) {
+                Ok(filters) => filters,
+                Err(e) => return Ok((StatusCode::BAD_REQUEST, Json(json!({"error": e}))).into_response()),
+            },
+            _ => Vec::new(),
+        }
+    };
+    
     // 使用DynamicExtension列出扩展对象
     match state.extension_client.list::<DynamicExtension>(options).await {
         Ok(result) => {
@@ -120,6 +160,7 @@ pub async fn list_extensions(
                         && ext_gvk.kind == gvk.kind
                 })
                 .map(|ext| ext.to_value())
+                .filter(|value| filters.iter().all(|(index, expected)| index.values(value).contains(expected)))
                 .collect();
             
             let response = serde_json::json!({
@@ -165,6 +206,9 @@ pub async fn create_extension(
     if actual_gvk != expected_gvk {
         return Err(StatusCode::BAD_REQUEST);
     }
+    if let Some(response) = scheme_violation(&state, &actual_gvk, &dynamic_ext.to_value()) {
+        return Ok(response);
+    }
     
     // 创建扩展对象
     match state.extension_client.create(dynamic_ext).await {
@@ -206,6 +250,9 @@ pub async fn update_extension(
     if dynamic_ext.metadata.name != name {
         return Err(StatusCode::BAD_REQUEST);
     }
+    if let Some(response) = scheme_violation(&state, &actual_gvk, &dynamic_ext.to_value()) {
+        return Ok(response);
+    }
     
     // 更新扩展对象
     match state.extension_client.update(dynamic_ext).await {
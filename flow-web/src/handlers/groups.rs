@@ -1,10 +1,11 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use flow_domain::attachment::Group;
+use flow_service::attachment::ArchiveSelection;
 use flow_api::extension::ListOptions;
 use crate::AppState;
 use serde_json::json;
@@ -123,3 +124,17 @@ pub async fn update_group_count(
     }
 }
 
+/// 将分组内的所有附件打包下载
+/// GET /api/v1alpha1/groups/:name/download
+pub async fn download_group(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    let group = match state.group_service.get(&name).await {
+        Ok(Some(group)) => group,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let filename = group.spec.display_name.clone();
+    super::attachments::archive_response(&state, ArchiveSelection::Group(name), &filename).await
+}
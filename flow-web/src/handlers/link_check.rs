@@ -0,0 +1,29 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use crate::AppState;
+
+/// 获取最近一次失效链接检查报告
+/// GET /api/v1alpha1/link-check
+pub async fn get_link_check_report(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.link_check_service.last_report().await {
+        Some(report) => Ok(Json(report).into_response()),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// 立即执行失效链接检查
+/// POST /api/v1alpha1/link-check
+pub async fn run_link_check(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.link_check_service.check_all().await {
+        Ok(report) => Ok(Json(report).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
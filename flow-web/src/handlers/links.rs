@@ -0,0 +1,187 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_domain::content::{Link, LinkGroup};
+use flow_api::extension::ListOptions;
+use crate::AppState;
+use serde::Serialize;
+
+/// Link列表响应
+#[derive(Debug, Serialize)]
+pub struct LinkListResponse {
+    pub items: Vec<Link>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// LinkGroup列表响应
+#[derive(Debug, Serialize)]
+pub struct LinkGroupListResponse {
+    pub items: Vec<LinkGroup>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// 创建Link
+/// POST /api/v1alpha1/links
+pub async fn create_link(
+    State(state): State<AppState>,
+    Json(link): Json<Link>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.create(link).await {
+        Ok(link) => Ok(Json(link).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取Link
+/// GET /api/v1alpha1/links/{name}
+pub async fn get_link(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.get(&name).await {
+        Ok(Some(link)) => Ok(Json(link).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出Links
+/// GET /api/v1alpha1/links
+pub async fn list_links(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.list(params).await {
+        Ok(result) => {
+            let response = LinkListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新Link
+/// PUT /api/v1alpha1/links/{name}
+pub async fn update_link(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(link): Json<Link>,
+) -> Result<Response, StatusCode> {
+    if link.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.link_service.update(link).await {
+        Ok(link) => Ok(Json(link).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除Link
+/// DELETE /api/v1alpha1/links/{name}
+pub async fn delete_link(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 创建LinkGroup
+/// POST /api/v1alpha1/linkgroups
+pub async fn create_link_group(
+    State(state): State<AppState>,
+    Json(group): Json<LinkGroup>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.create_group(group).await {
+        Ok(group) => Ok(Json(group).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取LinkGroup
+/// GET /api/v1alpha1/linkgroups/{name}
+pub async fn get_link_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.get_group(&name).await {
+        Ok(Some(group)) => Ok(Json(group).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出LinkGroups
+/// GET /api/v1alpha1/linkgroups
+pub async fn list_link_groups(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.list_groups(params).await {
+        Ok(result) => {
+            let response = LinkGroupListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新LinkGroup
+/// PUT /api/v1alpha1/linkgroups/{name}
+pub async fn update_link_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(group): Json<LinkGroup>,
+) -> Result<Response, StatusCode> {
+    if group.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.link_service.update_group(group).await {
+        Ok(group) => Ok(Json(group).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除LinkGroup（分组下的链接变为未分组）
+/// DELETE /api/v1alpha1/linkgroups/{name}
+pub async fn delete_link_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.delete_group(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 按分组列出所有友情链接
+/// GET /api/v1alpha1/links/-/grouped
+pub async fn list_grouped_links(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.link_service.list_grouped().await {
+        Ok(grouped) => Ok(Json(grouped).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -0,0 +1,201 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_domain::content::{Menu, MenuItem};
+use flow_api::extension::ListOptions;
+use crate::AppState;
+use serde::Serialize;
+
+/// Menu列表响应
+#[derive(Debug, Serialize)]
+pub struct MenuListResponse {
+    pub items: Vec<Menu>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// MenuItem列表响应
+#[derive(Debug, Serialize)]
+pub struct MenuItemListResponse {
+    pub items: Vec<MenuItem>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// 创建Menu
+/// POST /api/v1alpha1/menus
+pub async fn create_menu(
+    State(state): State<AppState>,
+    Json(menu): Json<Menu>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.create(menu).await {
+        Ok(menu) => Ok(Json(menu).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取Menu
+/// GET /api/v1alpha1/menus/{name}
+pub async fn get_menu(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.get(&name).await {
+        Ok(Some(menu)) => Ok(Json(menu).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出Menus
+/// GET /api/v1alpha1/menus
+pub async fn list_menus(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.list(params).await {
+        Ok(result) => {
+            let response = MenuListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新Menu
+/// PUT /api/v1alpha1/menus/{name}
+pub async fn update_menu(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(menu): Json<Menu>,
+) -> Result<Response, StatusCode> {
+    if menu.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.menu_service.update(menu).await {
+        Ok(menu) => Ok(Json(menu).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除Menu（同时删除其菜单项）
+/// DELETE /api/v1alpha1/menus/{name}
+pub async fn delete_menu(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取Menu及其菜单项树
+/// GET /api/v1alpha1/menus/{name}/tree
+pub async fn get_menu_tree(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.get_tree(&name).await {
+        Ok(Some(tree)) => Ok(Json(tree).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取主菜单及其菜单项树
+/// GET /api/v1alpha1/menus/-/primary
+pub async fn get_primary_menu(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.get_primary_tree().await {
+        Ok(Some(tree)) => Ok(Json(tree).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 创建MenuItem
+/// POST /api/v1alpha1/menuitems
+pub async fn create_menu_item(
+    State(state): State<AppState>,
+    Json(item): Json<MenuItem>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.create_item(item).await {
+        Ok(item) => Ok(Json(item).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取MenuItem
+/// GET /api/v1alpha1/menuitems/{name}
+pub async fn get_menu_item(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.get_item(&name).await {
+        Ok(Some(item)) => Ok(Json(item).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出MenuItems
+/// GET /api/v1alpha1/menuitems
+pub async fn list_menu_items(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.list_items(params).await {
+        Ok(result) => {
+            let response = MenuItemListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新MenuItem
+/// PUT /api/v1alpha1/menuitems/{name}
+pub async fn update_menu_item(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(item): Json<MenuItem>,
+) -> Result<Response, StatusCode> {
+    if item.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.menu_service.update_item(item).await {
+        Ok(item) => Ok(Json(item).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除MenuItem（同时从菜单和父菜单项中移除）
+/// DELETE /api/v1alpha1/menuitems/{name}
+pub async fn delete_menu_item(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.menu_service.delete_item(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -2,10 +2,18 @@ pub mod auth;
 pub mod users;
 pub mod roles;
 pub mod posts;
+pub mod post_access;
 pub mod single_pages;
 pub mod comments;
 pub mod categories;
 pub mod tags;
+pub mod menus;
+pub mod links;
+pub mod moments;
+pub mod photos;
+pub mod series;
+pub mod link_check;
+pub mod slug_redirects;
 pub mod uc;
 pub mod extension;
 pub mod extension_utils;
@@ -13,8 +21,11 @@ pub mod extension_router;
 pub mod search;
 pub mod theme;
 pub mod theme_routes;
+pub mod plugins;
+pub mod robots;
 pub mod static_resources;
 pub mod attachments;
+pub mod uploads;
 pub mod policies;
 pub mod groups;
 pub mod websocket;
@@ -28,18 +39,29 @@ pub use auth::*;
 pub use users::*;
 pub use roles::*;
 pub use posts::*;
+pub use post_access::*;
 pub use single_pages::*;
 pub use comments::*;
 pub use categories::*;
 pub use tags::*;
+pub use menus::*;
+pub use links::*;
+pub use moments::*;
+pub use photos::*;
+pub use series::*;
+pub use link_check::*;
+pub use slug_redirects::*;
 pub use uc::*;
 pub use extension::*;
 pub use extension_router::*;
 pub use search::*;
 pub use theme::*;
 pub use theme_routes::*;
+pub use plugins::*;
+pub use robots::*;
 pub use static_resources::*;
 pub use attachments::*;
+pub use uploads::*;
 pub use policies::*;
 pub use groups::*;
 pub use websocket::*;
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_domain::content::Moment;
+use flow_service::content::MomentQuery;
+use crate::{AppState, extractors::CurrentUser};
+use serde::Serialize;
+
+/// Moment列表响应
+#[derive(Debug, Serialize)]
+pub struct MomentListResponse {
+    pub items: Vec<Moment>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+async fn list_moments_response(state: &AppState, query: MomentQuery) -> Result<Response, StatusCode> {
+    match state.moment_service.list(query).await {
+        Ok(result) => {
+            let response = MomentListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取当前用户的Moment，不存在返回404，不属于当前用户返回403
+async fn fetch_my_moment(state: &AppState, name: &str, username: &str) -> Result<Moment, StatusCode> {
+    match state.moment_service.get(name).await {
+        Ok(Some(moment)) if moment.is_owned_by(username) => Ok(moment),
+        Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出所有Moments
+/// GET /api/v1alpha1/moments
+pub async fn list_moments(
+    State(state): State<AppState>,
+    Query(query): Query<MomentQuery>,
+) -> Result<Response, StatusCode> {
+    list_moments_response(&state, query).await
+}
+
+/// 获取Moment
+/// GET /api/v1alpha1/moments/{name}
+pub async fn get_moment(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.moment_service.get(&name).await {
+        Ok(Some(moment)) => Ok(Json(moment).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除Moment
+/// DELETE /api/v1alpha1/moments/{name}
+pub async fn delete_moment(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.moment_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出我的Moments
+/// GET /api/v1alpha1/uc/moments
+pub async fn list_my_moments(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Query(mut query): Query<MomentQuery>,
+) -> Result<Response, StatusCode> {
+    query.owner = Some(username);
+    list_moments_response(&state, query).await
+}
+
+/// 发布Moment
+/// POST /api/v1alpha1/uc/moments
+pub async fn create_my_moment(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Json(mut moment): Json<Moment>,
+) -> Result<Response, StatusCode> {
+    moment.spec.owner = Some(username);
+    
+    match state.moment_service.create(moment).await {
+        Ok(moment) => Ok(Json(moment).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新我的Moment
+/// PUT /api/v1alpha1/uc/moments/{name}
+pub async fn update_my_moment(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(name): Path<String>,
+    Json(mut moment): Json<Moment>,
+) -> Result<Response, StatusCode> {
+    if moment.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    let old_moment = fetch_my_moment(&state, &name, &username).await?;
+    moment.spec.owner = old_moment.spec.owner;
+    
+    match state.moment_service.update(moment).await {
+        Ok(moment) => Ok(Json(moment).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除我的Moment
+/// DELETE /api/v1alpha1/uc/moments/{name}
+pub async fn delete_my_moment(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    fetch_my_moment(&state, &name, &username).await?;
+    
+    match state.moment_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -2,10 +2,14 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::Json;
 use flow_api::extension::{ListOptions, ListResult};
-use flow_domain::notification::{Notification, NotificationSpec};
+use flow_domain::notification::{Notification, NotificationSpec, NotifierDescriptor};
+use flow_service::notification::{
+    NotificationPreference, NotifierConfig, NOTIFICATION_PREFERENCE_INVALID_ERROR, NOTIFIER_CONFIG_INVALID_ERROR,
+    NOTIFIER_DESCRIPTOR_NOT_FOUND_ERROR,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::AppState;
+use crate::{AppState, extractors::CurrentUser};
 
 /// 列出通知
 pub async fn list_notifications(
@@ -104,6 +108,29 @@ pub async fn delete_notification(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// 批量删除通知请求
+#[derive(Debug, Deserialize)]
+pub struct DeleteNotificationsRequest {
+    pub names: Vec<String>,
+}
+
+/// 批量删除通知响应
+#[derive(Debug, Serialize)]
+pub struct DeleteNotificationsResponse {
+    pub deleted: usize,
+}
+
+/// 批量删除通知
+pub async fn delete_notifications(
+    State(state): State<AppState>,
+    Json(request): Json<DeleteNotificationsRequest>,
+) -> Result<Json<DeleteNotificationsResponse>, StatusCode> {
+    let deleted = state.notification_service.delete_all(&request.names).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    
+    Ok(Json(DeleteNotificationsResponse { deleted }))
+}
+
 /// 标记通知为已读
 pub async fn mark_notification_as_read(
     Path(name): Path<String>,
@@ -147,3 +174,98 @@ pub async fn get_unread_count(
     Ok(Json(UnreadCountResponse { count }))
 }
 
+
+/// 列出已注册的通知器名称，用于在通知偏好中选择
+pub async fn list_notifiers(
+    State(state): State<AppState>,
+) -> Json<Vec<String>> {
+    Json(state.notifier_registry.names())
+}
+
+/// 获取当前用户的通知偏好
+pub async fn get_my_notification_preference(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+) -> Result<Json<NotificationPreference>, StatusCode> {
+    state.notification_preference_service.get_preference(&username).await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 更新当前用户的通知偏好，请求体为 `{reasonTypeNotifier: {原因类型: {notifiers: [...]}}, digest: immediate|hourly|daily}`
+pub async fn update_my_notification_preference(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Json(preference): Json<NotificationPreference>,
+) -> Result<Json<NotificationPreference>, StatusCode> {
+    match state.notification_preference_service.update_preference(&username, preference).await {
+        Ok(preference) => Ok(Json(preference)),
+        Err(e) if e.to_string().starts_with(NOTIFICATION_PREFERENCE_INVALID_ERROR) => Err(StatusCode::BAD_REQUEST),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 通知渠道配置错误对应的状态码
+fn notifier_config_status(error: &anyhow::Error) -> StatusCode {
+    let message = error.to_string();
+    if message.starts_with(NOTIFIER_DESCRIPTOR_NOT_FOUND_ERROR) {
+        StatusCode::NOT_FOUND
+    } else if message.starts_with(NOTIFIER_CONFIG_INVALID_ERROR) {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// 列出通知渠道描述，包含发送者和接收者设置的引用
+pub async fn list_notifier_descriptors(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<NotifierDescriptor>>, StatusCode> {
+    state.notifier_descriptor_service.list_descriptors().await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 获取通知渠道的站点级发送者配置
+pub async fn get_notifier_sender_config(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<NotifierConfig>, StatusCode> {
+    state.notifier_descriptor_service.get_sender_config(&name).await
+        .map(Json)
+        .map_err(|e| notifier_config_status(&e))
+}
+
+/// 更新通知渠道的站点级发送者配置
+pub async fn update_notifier_sender_config(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(config): Json<NotifierConfig>,
+) -> Result<Json<NotifierConfig>, StatusCode> {
+    state.notifier_descriptor_service.update_sender_config(&name, config).await
+        .map(Json)
+        .map_err(|e| notifier_config_status(&e))
+}
+
+/// 获取当前用户在通知渠道上的接收者配置
+pub async fn get_my_notifier_receiver_config(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(name): Path<String>,
+) -> Result<Json<NotifierConfig>, StatusCode> {
+    state.notifier_descriptor_service.get_receiver_config(&name, &username).await
+        .map(Json)
+        .map_err(|e| notifier_config_status(&e))
+}
+
+/// 更新当前用户在通知渠道上的接收者配置
+pub async fn update_my_notifier_receiver_config(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(name): Path<String>,
+    Json(config): Json<NotifierConfig>,
+) -> Result<Json<NotifierConfig>, StatusCode> {
+    state.notifier_descriptor_service.update_receiver_config(&name, &username, config).await
+        .map(Json)
+        .map_err(|e| notifier_config_status(&e))
+}
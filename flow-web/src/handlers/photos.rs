@@ -0,0 +1,215 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_domain::content::{Photo, PhotoGroup};
+use flow_api::extension::ListOptions;
+use flow_service::content::photo_service::INVALID_ATTACHMENT_ERROR;
+use crate::AppState;
+use serde::Serialize;
+
+/// Photo列表响应
+#[derive(Debug, Serialize)]
+pub struct PhotoListResponse {
+    pub items: Vec<Photo>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// PhotoGroup列表响应
+#[derive(Debug, Serialize)]
+pub struct PhotoGroupListResponse {
+    pub items: Vec<PhotoGroup>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// 创建Photo（引用的附件不存在时返回400）
+/// POST /api/v1alpha1/photos
+pub async fn create_photo(
+    State(state): State<AppState>,
+    Json(photo): Json<Photo>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.create(photo).await {
+        Ok(photo) => Ok(Json(photo).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_ATTACHMENT_ERROR) => Err(StatusCode::BAD_REQUEST),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取Photo
+/// GET /api/v1alpha1/photos/{name}
+pub async fn get_photo(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.get(&name).await {
+        Ok(Some(photo)) => Ok(Json(photo).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出Photos
+/// GET /api/v1alpha1/photos
+pub async fn list_photos(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.list(params).await {
+        Ok(result) => {
+            let response = PhotoListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新Photo
+/// PUT /api/v1alpha1/photos/{name}
+pub async fn update_photo(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(photo): Json<Photo>,
+) -> Result<Response, StatusCode> {
+    if photo.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.photo_service.update(photo).await {
+        Ok(photo) => Ok(Json(photo).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_ATTACHMENT_ERROR) => Err(StatusCode::BAD_REQUEST),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除Photo
+/// DELETE /api/v1alpha1/photos/{name}
+pub async fn delete_photo(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 创建PhotoGroup
+/// POST /api/v1alpha1/photogroups
+pub async fn create_photo_group(
+    State(state): State<AppState>,
+    Json(group): Json<PhotoGroup>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.create_group(group).await {
+        Ok(group) => Ok(Json(group).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取PhotoGroup
+/// GET /api/v1alpha1/photogroups/{name}
+pub async fn get_photo_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.get_group(&name).await {
+        Ok(Some(group)) => Ok(Json(group).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出PhotoGroups
+/// GET /api/v1alpha1/photogroups
+pub async fn list_photo_groups(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.list_groups(params).await {
+        Ok(result) => {
+            let response = PhotoGroupListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新PhotoGroup
+/// PUT /api/v1alpha1/photogroups/{name}
+pub async fn update_photo_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(group): Json<PhotoGroup>,
+) -> Result<Response, StatusCode> {
+    if group.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.photo_service.update_group(group).await {
+        Ok(group) => Ok(Json(group).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除PhotoGroup（同时删除分组下的照片）
+/// DELETE /api/v1alpha1/photogroups/{name}
+pub async fn delete_photo_group(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.delete_group(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出分组下的照片（按排序）
+/// GET /api/v1alpha1/photogroups/{name}/photos
+pub async fn list_group_photos(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.list_by_group(Some(&name)).await {
+        Ok(photos) => Ok(Json(photos).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 按给定的照片名称顺序重排分组下的照片
+/// PUT /api/v1alpha1/photogroups/{name}/photos
+pub async fn reorder_group_photos(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(names): Json<Vec<String>>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.reorder(&name, names).await {
+        Ok(photos) => Ok(Json(photos).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 按分组列出所有照片
+/// GET /api/v1alpha1/photos/-/grouped
+pub async fn list_grouped_photos(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.photo_service.list_grouped().await {
+        Ok(grouped) => Ok(Json(grouped).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -0,0 +1,364 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use flow_service::attachment::remote_import::{REMOTE_FETCH_ERROR, REMOTE_FILE_TOO_LARGE_ERROR, REMOTE_URL_FORBIDDEN_ERROR};
+use flow_service::attachment::etag_matches;
+use flow_service::plugin::PLUGIN_CONFIG_INVALID_ERROR;
+use flow_service::plugin::PluginInstallRequest;
+use flow_service::plugin::{PLUGIN_LIFECYCLE_ERROR, PLUGIN_NOT_FOUND_ERROR, PLUGIN_NOT_LOADED_ERROR};
+use flow_api::extension::ListOptions;
+use flow_domain::plugin::Plugin;
+use flow_service::plugin::install::{
+    PLUGIN_INSTALL_CONFLICT_ERROR, PLUGIN_INSTALL_REQUEST_ERROR, PLUGIN_PACKAGE_INVALID_ERROR,
+    PLUGIN_RELEASE_NOT_FOUND_ERROR, PLUGIN_START_FAILED_ERROR, PLUGIN_VERIFICATION_ERROR,
+};
+use flow_plugin::PluginCapabilities;
+use crate::AppState;
+use serde::Deserialize;
+use serde_json::json;
+
+/// 插件生命周期操作失败时的响应
+fn lifecycle_error(e: anyhow::Error) -> axum::response::Response {
+    let message = e.to_string();
+    let status = if message.starts_with(PLUGIN_NOT_FOUND_ERROR) {
+        StatusCode::NOT_FOUND
+    } else if message.starts_with(PLUGIN_NOT_LOADED_ERROR) {
+        StatusCode::CONFLICT
+    } else if message.starts_with(PLUGIN_LIFECYCLE_ERROR) {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        tracing::error!("Plugin operation failed: {}", message);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(json!({"error": message}))).into_response()
+}
+
+/// 列出插件，状态中的阶段与插件管理器一致
+/// GET /api/v1alpha1/plugins
+pub async fn list_plugins(
+    Query(options): Query<ListOptions>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.list(options).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 获取插件
+/// GET /api/v1alpha1/plugins/:name
+pub async fn get_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.get(&name).await {
+        Ok(Some(plugin)) => (StatusCode::OK, Json(plugin)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 创建插件，`spec.enabled` 为true时从 `status.loadLocation` 加载并启动
+/// POST /api/v1alpha1/plugins
+pub async fn create_plugin(
+    State(state): State<AppState>,
+    Json(plugin): Json<Plugin>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.create(plugin).await {
+        Ok(plugin) => (StatusCode::CREATED, Json(plugin)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 更新插件
+/// PUT /api/v1alpha1/plugins/:name
+pub async fn update_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(plugin): Json<Plugin>,
+) -> impl IntoResponse {
+    if plugin.metadata.name != name {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Plugin name mismatch"})),
+        ).into_response();
+    }
+    match state.plugin_lifecycle_service.update(plugin).await {
+        Ok(plugin) => (StatusCode::OK, Json(plugin)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 停止、卸载并删除插件
+/// DELETE /api/v1alpha1/plugins/:name
+pub async fn delete_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.delete(&name).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 启用并启动插件
+/// PUT /api/v1alpha1/plugins/:name/enable
+pub async fn enable_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.enable(&name).await {
+        Ok(plugin) => (StatusCode::OK, Json(plugin)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 停止并停用插件
+/// PUT /api/v1alpha1/plugins/:name/disable
+pub async fn disable_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.disable(&name).await {
+        Ok(plugin) => (StatusCode::OK, Json(plugin)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 重新加载插件
+/// PUT /api/v1alpha1/plugins/:name/reload
+pub async fn reload_plugin(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.reload(&name).await {
+        Ok(plugin) => (StatusCode::OK, Json(plugin)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 获取插件的调用次数、耗时、错误率和内存占用
+pub async fn get_plugin_stats(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.stats(&name).await {
+        Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 插件日志查询参数
+#[derive(Debug, Deserialize)]
+pub struct PluginLogQuery {
+    /// 最多返回的行数（最近的若干行），默认返回保留的全部日志
+    pub limit: Option<usize>,
+}
+
+/// 获取插件最近输出的日志，按时间顺序
+pub async fn get_plugin_logs(
+    Path(name): Path<String>,
+    Query(query): Query<PluginLogQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_lifecycle_service.get(&name).await {
+        Ok(Some(_)) => (StatusCode::OK, Json(state.plugin_logs.recent(&name, query.limit))).into_response(),
+        Ok(None) => lifecycle_error(anyhow::anyhow!("{}: {}", PLUGIN_NOT_FOUND_ERROR, name)),
+        Err(e) => lifecycle_error(e),
+    }
+}
+
+/// 以Prometheus文本格式输出所有插件的统计
+pub async fn plugin_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.plugin_lifecycle_service.metrics().await,
+    )
+}
+
+/// 获取插件的设置表单定义
+pub async fn get_plugin_setting(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_config_service.get_setting(&name).await {
+        Ok(Some(setting)) => (StatusCode::OK, Json(setting)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin setting not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get plugin setting: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 获取插件设置（表单定义和设置值）
+pub async fn get_plugin_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_config_service.get_config(&name).await {
+        Ok(Some(config)) => (StatusCode::OK, Json(config)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get plugin config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 更新插件设置，请求体为 `{分组: {字段: 值}}`，只更新提交的分组，运行中的插件会收到变更通知
+pub async fn update_plugin_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(values): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    match state.plugin_config_service.update_config(&name, values).await {
+        Ok(Some(config)) => (StatusCode::OK, Json(config)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) if e.to_string().starts_with(PLUGIN_CONFIG_INVALID_ERROR) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to update plugin config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 获取插件声明的能力和审批情况，`pending` 不为空时插件需要管理员批准后才能启动
+pub async fn get_plugin_capabilities(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.plugin_capability_service.get_capabilities(&name).await {
+        Ok(Some(review)) => (StatusCode::OK, Json(review)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get plugin capabilities: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 批准插件的能力，请求体为批准的能力（通常为审批信息中的 `requested`）
+pub async fn approve_plugin_capabilities(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(capabilities): Json<PluginCapabilities>,
+) -> impl IntoResponse {
+    match state.plugin_capability_service.approve_capabilities(&name, capabilities).await {
+        Ok(Some(review)) => (StatusCode::OK, Json(review)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Plugin not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to approve plugin capabilities: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 从地址或插件市场安装、升级插件
+/// 请求体为 `{url, pluginId, version, sha256, signature}`，新版本启动失败时回滚到原版本
+pub async fn install_plugin(
+    State(state): State<AppState>,
+    Json(request): Json<PluginInstallRequest>,
+) -> impl IntoResponse {
+    match state.plugin_install_service.install(request).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => {
+            let message = e.to_string();
+            let status = if [PLUGIN_INSTALL_REQUEST_ERROR, PLUGIN_PACKAGE_INVALID_ERROR, PLUGIN_VERIFICATION_ERROR, REMOTE_URL_FORBIDDEN_ERROR]
+                .iter().any(|prefix| message.starts_with(prefix))
+            {
+                StatusCode::BAD_REQUEST
+            } else if message.starts_with(PLUGIN_RELEASE_NOT_FOUND_ERROR) {
+                StatusCode::NOT_FOUND
+            } else if message.starts_with(PLUGIN_INSTALL_CONFLICT_ERROR) {
+                StatusCode::CONFLICT
+            } else if message.starts_with(REMOTE_FILE_TOO_LARGE_ERROR) {
+                StatusCode::PAYLOAD_TOO_LARGE
+            } else if message.starts_with(PLUGIN_START_FAILED_ERROR) {
+                StatusCode::UNPROCESSABLE_ENTITY
+            } else if message.starts_with(REMOTE_FETCH_ERROR) {
+                StatusCode::BAD_GATEWAY
+            } else {
+                tracing::error!("Failed to install plugin: {}", message);
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, Json(json!({"error": message}))).into_response()
+        }
+    }
+}
+
+/// 插件前端资源查询参数
+#[derive(Debug, Deserialize)]
+pub struct PluginAssetQuery {
+    /// 插件版本，与当前版本一致时资源可以长期缓存
+    pub version: Option<String>,
+}
+
+/// 获取插件的前端资源（控制台扩展的入口脚本、样式表、Logo等）
+/// GET /plugins/:name/assets/*path
+pub async fn get_plugin_asset(
+    Path((name, path)): Path<(String, String)>,
+    Query(query): Query<PluginAssetQuery>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let asset = match state.plugin_asset_service.get_asset(&name, &path).await {
+        Ok(Some(asset)) => asset,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to read asset {} of plugin {}: {}", path, name, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // 带有当前版本参数的地址在插件升级后会变化，可以长期缓存；其他请求每次用ETag校验
+    let cache_control = if query.version.as_deref() == Some(asset.version.as_str()) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "public, no-cache"
+    };
+    let if_none_match = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok());
+    if if_none_match.is_some_and(|tags| etag_matches(tags, &asset.etag)) {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, asset.etag), (header::CACHE_CONTROL, cache_control.to_string())],
+        ).into_response();
+    }
+    (
+        [
+            (header::CONTENT_TYPE, asset.media_type),
+            (header::ETAG, asset.etag),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        asset.content,
+    ).into_response()
+}
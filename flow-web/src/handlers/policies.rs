@@ -35,6 +35,14 @@ pub async fn list_policies(
     }
 }
 
+/// 列出可用的存储模板（内置模板及插件注册的模板）
+/// GET /api/v1alpha1/policytemplates
+pub async fn list_policy_templates(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.storage_resolver.templates())).into_response()
+}
+
 /// 获取Policy
 /// GET /api/v1alpha1/policies/:name
 pub async fn get_policy(
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::{HeaderValue, SET_COOKIE}},
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_service::content::post_access_service::{unlock_cookie, unlock_token_from_cookie};
+use flow_api::extension::ExtensionClient;
+use flow_domain::content::Post;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 解锁令牌请求头（供无法使用Cookie的客户端使用）
+pub const UNLOCK_TOKEN_HEADER: &str = "X-Post-Unlock-Token";
+
+/// 设置文章密码请求
+#[derive(Debug, Deserialize)]
+pub struct SetPostPasswordRequest {
+    /// 为空时清除密码
+    pub password: Option<String>,
+}
+
+/// 解锁文章请求
+#[derive(Debug, Deserialize)]
+pub struct UnlockPostRequest {
+    pub password: String,
+}
+
+/// 解锁文章响应
+#[derive(Debug, Serialize)]
+pub struct UnlockPostResponse {
+    pub token: String,
+    #[serde(rename = "expiresIn")]
+    pub expires_in: u64,
+}
+
+/// 设置或清除文章访问密码
+/// PUT /api/v1alpha1/posts/{name}/password
+pub async fn set_post_password(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<SetPostPasswordRequest>,
+) -> Result<Response, StatusCode> {
+    match state.post_access_service.set_password(&name, request.password.as_deref()).await {
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().contains("not found") => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 使用密码解锁文章，成功时通过Cookie和响应体返回短期有效的解锁令牌
+/// POST /api/v1alpha1/posts/{name}/unlock
+pub async fn unlock_post(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<UnlockPostRequest>,
+) -> Result<Response, StatusCode> {
+    let token = match state.post_access_service.unlock(&name, &request.password).await {
+        Ok(Some(token)) => token,
+        Ok(None) => return Err(StatusCode::UNAUTHORIZED),
+        Err(e) if e.to_string().contains("not found") => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let expires_in = state.post_access_service.unlock_ttl();
+    let cookie_value = unlock_cookie(&name, &token, expires_in);
+
+    let mut response = Json(UnlockPostResponse { token, expires_in }).into_response();
+    response.headers_mut().insert(
+        SET_COOKIE,
+        HeaderValue::from_str(&cookie_value).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+    Ok(response)
+}
+
+/// 从请求头中获取文章的解锁令牌（优先使用请求头，其次使用Cookie）
+pub fn unlock_token_from_headers(headers: &HeaderMap, post_name: &str) -> Option<String> {
+    if let Some(token) = headers.get(UNLOCK_TOKEN_HEADER).and_then(|v| v.to_str().ok()) {
+        return Some(token.to_string());
+    }
+
+    headers.get("cookie")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|cookies| unlock_token_from_cookie(cookies, post_name))
+}
+
+/// 加密文章对当前请求是否锁定：文章需要密码且请求中没有有效的解锁令牌，读取文章失败时视为锁定
+pub async fn is_post_locked(state: &AppState, post_name: &str, headers: &HeaderMap) -> bool {
+    match state.extension_client.fetch::<Post>(post_name).await {
+        Ok(Some(post)) => {
+            let token = unlock_token_from_headers(headers, post_name);
+            !state.post_access_service.can_access(&post, token.as_deref())
+        }
+        Ok(None) => false,
+        Err(e) => {
+            tracing::warn!("Failed to fetch post {} for access check: {}", post_name, e);
+            true
+        }
+    }
+}
+
+/// 密码页面使用的文章数据，只包含名称和标题
+pub fn locked_post_value(post_value: &Value) -> Value {
+    json!({
+        "metadata": { "name": post_value.pointer("/metadata/name").cloned().unwrap_or_default() },
+        "spec": { "title": post_value.pointer("/spec/title").cloned().unwrap_or_default() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_token_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(unlock_token_from_headers(&headers, "hello"), None);
+
+        headers.insert("cookie", HeaderValue::from_static("a=1; FLOW_POST_UNLOCK_hello=cookie-token"));
+        assert_eq!(unlock_token_from_headers(&headers, "hello").as_deref(), Some("cookie-token"));
+        assert_eq!(unlock_token_from_headers(&headers, "other"), None);
+
+        headers.insert(UNLOCK_TOKEN_HEADER, HeaderValue::from_static("header-token"));
+        assert_eq!(unlock_token_from_headers(&headers, "hello").as_deref(), Some("header-token"));
+    }
+
+    #[test]
+    fn test_locked_post_value() {
+        let post = json!({
+            "metadata": { "name": "hello", "annotations": { "a": "b" } },
+            "spec": { "title": "Hello", "excerpt": { "raw": "secret summary" }, "password": "hash" },
+            "status": { "excerpt": "secret summary" },
+        });
+        assert_eq!(locked_post_value(&post), json!({
+            "metadata": { "name": "hello" },
+            "spec": { "title": "Hello" },
+        }));
+    }
+}
@@ -5,7 +5,9 @@ use axum::{
     Json,
 };
 use flow_domain::content::Post;
-use flow_service::content::{PostQuery, PostRequest, ContentRequest};
+use flow_service::content::{PostQuery, PostRequest, ContentRequest, ArchiveQuery, ListedPost};
+use flow_service::content::cover_service::INVALID_COVER_ERROR;
+use flow_service::content::PublishValidationError;
 use crate::{AppState, extractors::CurrentUser};
 use serde::{Deserialize, Serialize};
 
@@ -91,7 +93,8 @@ pub async fn create_post(
     };
     
     match state.post_service.draft_post(post_request).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_COVER_ERROR) => Err(StatusCode::BAD_REQUEST),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -104,7 +107,7 @@ pub async fn get_post(
     Path(name): Path<String>,
 ) -> Result<Response, StatusCode> {
     match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => Ok(Json(post).into_response()),
+        Ok(Some(post)) => Ok(Json(post.without_password()).into_response()),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
@@ -122,7 +125,7 @@ pub async fn list_posts(
     match state.post_service.list_post(query).await {
         Ok(result) => {
             let response = PostListResponse {
-                items: result.items,
+                items: result.items.into_iter().map(ListedPost::without_password).collect(),
                 total: result.total,
                 page: result.page as u64,
                 size: result.size as u64,
@@ -157,11 +160,20 @@ pub async fn update_post(
     };
     
     match state.post_service.update_post(post_request).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_COVER_ERROR) => Err(StatusCode::BAD_REQUEST),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
+/// 发布校验未通过时返回422及校验错误列表，其余错误返回500
+pub(crate) fn publish_error_response(e: &(dyn std::error::Error + Send + Sync + 'static)) -> Result<Response, StatusCode> {
+    match e.downcast_ref::<PublishValidationError>() {
+        Some(validation) => Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(validation)).into_response()),
+        None => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 /// 发布Post
 /// PUT /api/v1alpha1/posts/{name}/publish
 pub async fn publish_post(
@@ -181,8 +193,8 @@ pub async fn publish_post(
     // let head_snapshot = params.get("headSnapshot");
     
     match state.post_service.publish(post).await {
-        Ok(post) => Ok(Json(post).into_response()),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) => publish_error_response(e.as_ref()),
     }
 }
 
@@ -200,7 +212,7 @@ pub async fn unpublish_post(
     };
     
     match state.post_service.unpublish(post).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -214,7 +226,50 @@ pub async fn recycle_post(
 ) -> Result<Response, StatusCode> {
     
     match state.post_service.recycle(&name, &username).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 按年月列出已发布文章的归档（每个月份内的文章按page/size分页）
+/// GET /api/v1alpha1/archives
+pub async fn list_archives(
+    State(state): State<AppState>,
+    Query(query): Query<ArchiveQuery>,
+) -> Result<Response, StatusCode> {
+    if query.month.is_some_and(|m| !(1..=12).contains(&m)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    match state.post_service.list_archives(query).await {
+        Ok(archives) => Ok(Json(archives).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出支持的内容格式（rawType）
+/// GET /api/v1alpha1/content-formats
+pub async fn list_content_formats(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    Ok(Json(state.content_converter_registry.raw_types()).into_response())
+}
+
+/// 设置共同作者请求
+#[derive(Debug, Deserialize)]
+pub struct SetContributorsRequest {
+    pub contributors: Vec<String>,
+}
+
+/// 设置Post的共同作者
+/// PUT /api/v1alpha1/posts/{name}/contributors
+pub async fn set_post_contributors(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<SetContributorsRequest>,
+) -> Result<Response, StatusCode> {
+    match state.post_service.set_contributors(&name, request.contributors).await {
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().contains("not found") => Err(StatusCode::NOT_FOUND),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -287,7 +342,7 @@ pub async fn revert_post_to_snapshot(
     Json(request): Json<RevertSnapshotRequest>,
 ) -> Result<Response, StatusCode> {
     match state.post_service.revert_to_snapshot(&name, &request.snapshot_name).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -328,6 +383,10 @@ fn parse_post_query(params: serde_json::Value) -> PostQuery {
         query.tag = Some(tag.to_string());
     }
     
+    if let Some(language) = params.get("language").and_then(|v| v.as_str()) {
+        query.language = Some(language.to_string());
+    }
+    
     if let Some(visible) = params.get("visible").and_then(|v| v.as_str()) {
         query.visible = match visible {
             "PUBLIC" => Some(flow_domain::content::VisibleEnum::Public),
@@ -0,0 +1,73 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_service::theme::ROBOTS_TXT_TOO_LARGE_ERROR;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+
+/// robots.txt设置
+#[derive(Debug, Serialize)]
+pub struct RobotsSettingResponse {
+    /// 自定义内容，为空表示使用默认内容
+    pub content: Option<String>,
+    /// 当前对外提供的内容
+    pub effective: String,
+}
+
+/// 更新robots.txt请求
+#[derive(Debug, Deserialize)]
+pub struct UpdateRobotsRequest {
+    /// 为空时恢复默认内容
+    pub content: Option<String>,
+}
+
+/// 从请求头推断站点地址（未配置外部访问地址时用于生成Sitemap链接）
+pub(crate) fn site_url_from_headers(headers: &HeaderMap) -> String {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let scheme = header("x-forwarded-proto").unwrap_or("http");
+    let host = header("x-forwarded-host")
+        .or_else(|| header(header::HOST.as_str()))
+        .unwrap_or("localhost");
+    format!("{}://{}", scheme, host)
+}
+
+/// 提供robots.txt
+/// GET /robots.txt
+pub async fn robots_txt(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let content = state.robots_service.robots_txt(&site_url_from_headers(&headers)).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], content).into_response())
+}
+
+/// 获取robots.txt设置
+/// GET /api/v1alpha1/robots
+pub async fn get_robots_setting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let content = state.robots_service.get_custom().await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let effective = state.robots_service.robots_txt(&site_url_from_headers(&headers)).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(RobotsSettingResponse { content, effective }).into_response())
+}
+
+/// 更新robots.txt内容
+/// PUT /api/v1alpha1/robots
+pub async fn update_robots_setting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateRobotsRequest>,
+) -> Result<Response, StatusCode> {
+    match state.robots_service.update_custom(request.content).await {
+        Ok(()) => get_robots_setting(State(state), headers).await,
+        Err(e) if e.to_string().starts_with(ROBOTS_TXT_TOO_LARGE_ERROR) => Err(StatusCode::PAYLOAD_TOO_LARGE),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -122,3 +122,67 @@ pub async fn search(
     }
 }
 
+/// 搜索建议请求参数
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: u32,
+}
+
+fn default_suggest_limit() -> u32 {
+    5
+}
+
+/// 搜索建议端点：边输入边搜索，返回以关键词为前缀的公开文档标题和标签
+pub async fn search_suggest(
+    Query(query): Query<SuggestQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if query.q.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": "Keyword cannot be empty"
+            })),
+        ).into_response();
+    }
+    
+    if query.limit == 0 || query.limit > 20 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": "Limit must be between 1 and 20"
+            })),
+        ).into_response();
+    }
+    
+    match state.search_service.suggest(&query.q, query.limit).await {
+        Ok(suggestions) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "keyword": query.q,
+                "suggestions": suggestions,
+            })),
+        ).into_response(),
+        Err(e) => {
+            tracing::warn!("Suggest failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "status": "error",
+                    "message": format!("Suggest failed: {}", e)
+                })),
+            ).into_response()
+        }
+    }
+}
+
+/// 列出内置和插件注册的搜索引擎，以及当前处理搜索请求的引擎
+pub async fn list_search_engines(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.search_engine_registry.list()))
+}
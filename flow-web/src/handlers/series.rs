@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_domain::content::Series;
+use flow_api::extension::ListOptions;
+use crate::AppState;
+use serde::Serialize;
+
+/// Series列表响应
+#[derive(Debug, Serialize)]
+pub struct SeriesListResponse {
+    pub items: Vec<Series>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// 创建Series
+/// POST /api/v1alpha1/series
+pub async fn create_series(
+    State(state): State<AppState>,
+    Json(series): Json<Series>,
+) -> Result<Response, StatusCode> {
+    match state.series_service.create(series).await {
+        Ok(series) => Ok(Json(series).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取Series
+/// GET /api/v1alpha1/series/{name}
+pub async fn get_series(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.series_service.get(&name).await {
+        Ok(Some(series)) => Ok(Json(series).into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 列出Series
+/// GET /api/v1alpha1/series
+pub async fn list_series(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.series_service.list(params).await {
+        Ok(result) => {
+            let response = SeriesListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 更新Series
+/// PUT /api/v1alpha1/series/{name}
+pub async fn update_series(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(series): Json<Series>,
+) -> Result<Response, StatusCode> {
+    if series.metadata.name != name {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    
+    match state.series_service.update(series).await {
+        Ok(series) => Ok(Json(series).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除Series
+/// DELETE /api/v1alpha1/series/{name}
+pub async fn delete_series(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.series_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
@@ -6,6 +6,7 @@ use axum::{
 };
 use flow_domain::content::SinglePage;
 use flow_api::extension::ListOptions;
+use flow_service::content::single_page_service::INVALID_PARENT_ERROR;
 use crate::{AppState, extractors::CurrentUser};
 use serde::Serialize;
 
@@ -18,6 +19,15 @@ pub struct SinglePageListResponse {
     pub size: u64,
 }
 
+/// 父页面校验失败返回400，其余错误返回500
+fn hierarchy_error_status(e: &(dyn std::error::Error + Send + Sync)) -> StatusCode {
+    if e.to_string().starts_with(INVALID_PARENT_ERROR) {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
 /// 创建SinglePage
 /// POST /api/v1alpha1/singlepages
 pub async fn create_single_page(
@@ -30,7 +40,7 @@ pub async fn create_single_page(
     
     match state.single_page_service.create(page).await {
         Ok(page) => Ok(Json(page).into_response()),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(hierarchy_error_status(e.as_ref())),
     }
 }
 
@@ -80,7 +90,7 @@ pub async fn update_single_page(
     
     match state.single_page_service.update(page).await {
         Ok(page) => Ok(Json(page).into_response()),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => Err(hierarchy_error_status(e.as_ref())),
     }
 }
 
@@ -132,3 +142,24 @@ pub async fn unpublish_single_page(
     }
 }
 
+/// 获取所有SinglePage组成的页面树
+/// GET /api/v1alpha1/singlepages/-/tree
+pub async fn get_single_page_tree(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.single_page_service.list_tree(false).await {
+        Ok(tree) => Ok(Json(tree).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取已发布页面组成的页面树，供主题构建导航
+/// GET /api/v1alpha1/page-tree
+pub async fn get_published_page_tree(
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    match state.single_page_service.list_tree(true).await {
+        Ok(tree) => Ok(Json(tree).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use flow_domain::content::SlugRedirect;
+use flow_api::extension::ListOptions;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+
+/// SlugRedirect列表响应
+#[derive(Debug, Serialize)]
+pub struct SlugRedirectListResponse {
+    pub items: Vec<SlugRedirect>,
+    pub total: u64,
+    pub page: u64,
+    pub size: u64,
+}
+
+/// 清理重定向请求
+#[derive(Debug, Deserialize)]
+pub struct PruneSlugRedirectsRequest {
+    /// 只清理指向该文章或分类的重定向
+    #[serde(rename = "subjectName")]
+    pub subject_name: Option<String>,
+
+    /// 只清理在该时间之前创建的重定向
+    pub before: Option<DateTime<Utc>>,
+}
+
+/// 清理重定向响应
+#[derive(Debug, Serialize)]
+pub struct PruneSlugRedirectsResponse {
+    pub pruned: usize,
+}
+
+/// 列出重定向
+/// GET /api/v1alpha1/redirects
+pub async fn list_slug_redirects(
+    State(state): State<AppState>,
+    Query(params): Query<ListOptions>,
+) -> Result<Response, StatusCode> {
+    match state.slug_redirect_service.list(params).await {
+        Ok(result) => {
+            let response = SlugRedirectListResponse {
+                items: result.items,
+                total: result.total,
+                page: result.page as u64,
+                size: result.size as u64,
+            };
+            Ok(Json(response).into_response())
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 删除重定向
+/// DELETE /api/v1alpha1/redirects/{name}
+pub async fn delete_slug_redirect(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, StatusCode> {
+    match state.slug_redirect_service.delete(&name).await {
+        Ok(_) => Ok((StatusCode::NO_CONTENT, "").into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 批量清理重定向
+/// POST /api/v1alpha1/redirects/prune
+pub async fn prune_slug_redirects(
+    State(state): State<AppState>,
+    Json(request): Json<PruneSlugRedirectsRequest>,
+) -> Result<Response, StatusCode> {
+    match state.slug_redirect_service.prune(request.subject_name.as_deref(), request.before).await {
+        Ok(pruned) => Ok(Json(PruneSlugRedirectsResponse { pruned }).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
@@ -3,9 +3,12 @@ use axum::http::StatusCode;
 use axum::response::Json;
 use flow_api::extension::{ListOptions, ListResult, ExtensionClient};
 use flow_domain::notification::{
-    Reason, ReasonSpec,
+    Reason, ReasonSpec, ReasonType,
     Subscription, SubscriptionSubscriber, InterestReason,
 };
+use flow_service::notification::{
+    REASON_ATTRIBUTES_INVALID_ERROR, REASON_TYPE_NOT_FOUND_ERROR, SUBSCRIPTION_EXPRESSION_INVALID_ERROR,
+};
 use serde::Deserialize;
 use std::collections::HashMap;
 use crate::AppState;
@@ -59,7 +62,13 @@ pub async fn create_subscription(
         request.reason,
     ).await
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(|e| {
+            if e.to_string().starts_with(SUBSCRIPTION_EXPRESSION_INVALID_ERROR) {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })
 }
 
 /// 删除订阅
@@ -145,12 +154,41 @@ pub async fn create_reason(
         metadata: Metadata::new(name),
         spec: request.spec,
     };
+    state.reason_type_service.validate(&reason).await
+        .map_err(|e| {
+            let message = e.to_string();
+            if message.starts_with(REASON_TYPE_NOT_FOUND_ERROR) || message.starts_with(REASON_ATTRIBUTES_INVALID_ERROR) {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })?;
     
     state.extension_client.create(reason).await
         .map(Json)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+/// 列出原因类型，用于通知偏好和模板编辑中选择
+pub async fn list_reason_types(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ReasonType>>, StatusCode> {
+    state.reason_type_service.list_reason_types().await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 获取原因类型
+pub async fn get_reason_type(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ReasonType>, StatusCode> {
+    state.reason_type_service.get_reason_type(&name).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
 /// 删除原因
 pub async fn delete_reason(
     Path(name): Path<String>,
@@ -1,13 +1,21 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
 use axum::body::Bytes;
 use flow_api::extension::ListOptions;
+use flow_service::theme::{RemoteThemeSource, REMOTE_THEME_TOO_LARGE_ERROR, THEME_CONFIG_INVALID_ERROR, THEME_IN_USE_ERROR, THEME_NOT_FOUND_ERROR};
 use crate::AppState;
+use crate::extractors::{CurrentUser, PREVIEW_THEME_PARAM};
+use flow_domain::theme::CustomTemplateKind;
+use serde::Deserialize;
 use serde_json::json;
+use std::time::Duration;
+
+/// 路径中表示当前激活主题的名称
+const ACTIVE_THEME_PLACEHOLDER: &str = "-";
 
 /// 列出主题
 pub async fn list_themes(
@@ -40,6 +48,83 @@ pub async fn get_theme(
     }
 }
 
+/// 卸载主题查询参数
+#[derive(Debug, Deserialize)]
+pub struct UninstallThemeQuery {
+    /// 是否同时删除主题的设置表单和设置值，默认保留以便重新安装后恢复
+    #[serde(rename = "deleteConfig", default)]
+    pub delete_config: bool,
+}
+
+/// 卸载主题（不允许卸载激活中的主题）
+pub async fn uninstall_theme(
+    Path(name): Path<String>,
+    Query(query): Query<UninstallThemeQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.theme_service.uninstall_theme(&name, query.delete_config).await {
+        Ok(theme) => {
+            state.template_engine_manager.clear_cache(&name).await;
+            (StatusCode::OK, Json(theme)).into_response()
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let status = if message.starts_with(THEME_NOT_FOUND_ERROR) {
+                StatusCode::NOT_FOUND
+            } else if message.starts_with(THEME_IN_USE_ERROR) {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (
+                status,
+                Json(json!({"error": format!("Failed to uninstall theme: {}", message)})),
+            ).into_response()
+        }
+    }
+}
+
+/// 列出主题为指定内容类型（post、category、page）声明的自定义模板，供编辑器选择
+///
+/// 主题名称为`-`时使用当前激活的主题
+pub async fn list_custom_templates(
+    Path((name, kind)): Path<(String, CustomTemplateKind)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let name = if name == ACTIVE_THEME_PLACEHOLDER {
+        match state.theme_service.get_active_theme().await {
+            Ok(Some(active)) => active,
+            Ok(None) => return (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "No active theme"})),
+            ).into_response(),
+            Err(e) => return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get active theme: {}", e)})),
+            ).into_response(),
+        }
+    } else {
+        name
+    };
+    
+    match state.theme_service.get_theme(&name).await {
+        Ok(Some(theme)) => {
+            let templates = theme.spec.custom_templates.as_ref()
+                .map(|templates| templates.templates(kind).to_vec())
+                .unwrap_or_default();
+            (StatusCode::OK, Json(json!({"items": templates}))).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get theme: {}", e)})),
+        ).into_response(),
+    }
+}
+
 /// 激活主题
 pub async fn activate_theme(
     Path(name): Path<String>,
@@ -71,23 +156,48 @@ pub async fn activate_theme(
 }
 
 /// 安装主题
+///
+/// 请求体为ZIP包时直接安装；Content-Type为JSON时按远程来源（下载地址或Git仓库）在服务端下载后安装
 pub async fn install_theme(
     State(state): State<AppState>,
+    headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
-    // 将请求体转换为Vec<u8>
-    let content = body.to_vec();
+    let is_json = headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
     
-    match state.theme_service.install_theme(content).await {
+    let result = if is_json {
+        let source: RemoteThemeSource = match serde_json::from_slice(&body) {
+            Ok(source) => source,
+            Err(e) => return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("Invalid remote theme source: {}", e)})),
+            ).into_response(),
+        };
+        state.theme_service.install_theme_from_remote(source).await
+    } else {
+        // 将请求体转换为Vec<u8>
+        state.theme_service.install_theme(body.to_vec()).await
+    };
+    
+    match result {
         Ok(theme) => {
             // 清除模板引擎缓存（如果主题已存在）
             state.template_engine_manager.clear_cache(&theme.metadata.name).await;
             (StatusCode::OK, Json(theme)).into_response()
         }
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": format!("Failed to install theme: {}", e)})),
-        ).into_response(),
+        Err(e) => {
+            let status = if e.to_string().starts_with(REMOTE_THEME_TOO_LARGE_ERROR) {
+                StatusCode::PAYLOAD_TOO_LARGE
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (
+                status,
+                Json(json!({"error": format!("Failed to install theme: {}", e)})),
+            ).into_response()
+        }
     }
 }
 
@@ -131,3 +241,125 @@ pub async fn reload_theme(
     }
 }
 
+
+/// 获取主题设置（表单定义和设置值）
+pub async fn get_theme_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.theme_config_service.get_config(&name).await {
+        Ok(Some(config)) => (StatusCode::OK, Json(config)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get theme config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 更新主题设置，请求体为 `{分组: {字段: 值}}`，只更新提交的分组
+pub async fn update_theme_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    Json(values): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    match state.theme_config_service.update_config(&name, values).await {
+        Ok(Some(config)) => (StatusCode::OK, Json(config)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) if e.to_string().starts_with(THEME_CONFIG_INVALID_ERROR) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to update theme config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 导出主题设置，返回ConfigMap格式的YAML文件
+pub async fn export_theme_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.theme_config_service.export_config(&name).await {
+        Ok(Some(yaml)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/yaml; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}-config.yaml\"", name)),
+            ],
+            yaml,
+        ).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to export theme config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 导入主题设置，请求体为导出的YAML，按表单定义校验后只更新导入的分组
+pub async fn import_theme_config(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    body: String,
+) -> impl IntoResponse {
+    match state.theme_config_service.import_config(&name, &body).await {
+        Ok(Some(config)) => (StatusCode::OK, Json(config)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) if e.to_string().starts_with(THEME_CONFIG_INVALID_ERROR) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to import theme config: {}", e)})),
+        ).into_response(),
+    }
+}
+
+/// 预览令牌的有效期
+const THEME_PREVIEW_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 生成主题预览链接，令牌只对当前用户有效，访问链接后通过Cookie在有效期内继续预览
+pub async fn preview_theme(
+    Path(name): Path<String>,
+    CurrentUser(username): CurrentUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.theme_service.get_theme(&name).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Theme not found: {}", name)})),
+        ).into_response(),
+        Err(e) => return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to get theme: {}", e)})),
+        ).into_response(),
+    }
+    match state.theme_resolver.sign_preview_token(&name, &username, THEME_PREVIEW_TTL) {
+        Ok(token) => (StatusCode::OK, Json(json!({
+            "token": token,
+            "expiresIn": THEME_PREVIEW_TTL.as_secs(),
+            "url": format!("/?{}={}", PREVIEW_THEME_PARAM, token),
+        }))).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to create theme preview: {}", e)})),
+        ).into_response(),
+    }
+}
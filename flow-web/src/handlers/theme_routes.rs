@@ -1,24 +1,355 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::{StatusCode, header},
+    extract::{OriginalUri, Path, Query, State},
+    http::{HeaderMap, Method, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use flow_infra::theme::template_engine::TemplateContext;
-use flow_service::theme::finders::{PostFinder, CategoryFinder, TagFinder};
+use flow_domain::theme::{CustomTemplateKind, ThemeContext, CUSTOM_TEMPLATE_ANNO};
+use flow_service::theme::finders::{PostFinder, CategoryFinder, TagFinder, SinglePageFinder, CommentFinder, ContributorFinder, DEFAULT_PAGE_SIZE, page_base_path, with_page_urls};
 use flow_service::content::PostQuery;
+use flow_service::theme::seo::seo_value;
+use flow_api::theme::FinderRegistry;
 use crate::AppState;
+use crate::extractors::{PreviewTheme, PREVIEW_THEME_PARAM};
+use crate::handlers::post_access::{is_post_locked, locked_post_value};
 use std::collections::HashMap;
 
+/// 加密文章的密码输入页模板
+const POST_PASSWORD_TEMPLATE: &str = "post-password.html";
+
+/// 旧链接已迁移时返回301重定向（slug变更后的旧链接）
+async fn redirect_moved(state: &AppState, path: &str) -> Option<Response> {
+    match state.slug_redirect_service.resolve(path).await {
+        Ok(Some(target)) => Some(
+            Response::builder()
+                .status(StatusCode::MOVED_PERMANENTLY)
+                .header(header::LOCATION, target)
+                .body(axum::body::Body::empty())
+                .unwrap()
+                .into_response()
+        ),
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!("Failed to resolve slug redirect for {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// 内容所选的自定义模板（内容类型和模板文件）
+type ChosenTemplate = (CustomTemplateKind, String);
+
+/// 读取内容所选的自定义模板：优先使用自定义模板注解，其次使用spec.template
+fn chosen_template(value: &serde_json::Value, kind: CustomTemplateKind) -> Option<ChosenTemplate> {
+    let annotation = value.get("metadata")
+        .and_then(|metadata| metadata.get("annotations"))
+        .and_then(|annotations| annotations.get(CUSTOM_TEMPLATE_ANNO));
+    annotation.or_else(|| value.pointer("/spec/template"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| (kind, t.to_string()))
+}
+
+/// 使用当前主题渲染页面
+///
+/// 优先使用内容所选且主题声明的自定义模板，否则按顺序使用第一个存在的候选模板，
+/// 都不存在时使用最后一个（由模板引擎报告缺失）。渲染失败时返回主题的错误页面
+async fn render_page(
+    state: &AppState,
+    preview: &PreviewTheme,
+    custom_template: Option<ChosenTemplate>,
+    candidates: &[String],
+    model: HashMap<String, serde_json::Value>,
+    status: StatusCode,
+) -> Response {
+    let theme_context = match state.theme_resolver.get_theme_with_preview(preview.preview.as_ref()).await {
+        Ok(Some(ctx)) => ctx,
+        Ok(None) => return internal_error(state, preview, "No active theme".to_string()).await,
+        Err(e) => return internal_error(state, preview, format!("Failed to get theme context: {}", e)).await,
+    };
+    
+    let custom_template = match custom_template {
+        Some((kind, chosen)) => state.theme_resolver.resolve_custom_template(&theme_context, kind, &chosen).await,
+        None => None,
+    };
+    let template_name = custom_template
+        .or_else(|| state.theme_resolver.resolve_template(&theme_context, candidates))
+        .or_else(|| candidates.last().cloned())
+        .unwrap_or_default();
+    
+    match render_template(state, preview, &theme_context, &template_name, model).await {
+        Ok(rendered) => {
+            let mut response = html_response(status, rendered);
+            apply_preview(&mut response, preview);
+            response
+        }
+        Err(e) => internal_error(state, preview, format!("Failed to render template: {}", e)).await,
+    }
+}
+
+/// 使用主题的模板引擎渲染模板
+///
+/// 分类和标签列表以及模板上下文注册表提供的全局变量（站点信息、当前用户、主导航菜单和请求信息）
+/// 作为公共数据添加到模板上下文中，并根据页面模型生成SEO数据（`seo`）
+async fn render_template(
+    state: &AppState,
+    preview: &PreviewTheme,
+    theme_context: &ThemeContext,
+    template_name: &str,
+    model: HashMap<String, serde_json::Value>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let engine = state.template_engine_manager.get_template_engine(theme_context).await;
+    
+    let theme_config = match state.theme_config_service.resolved_values(&theme_context.name).await {
+        Ok(values) => values,
+        Err(e) => {
+            tracing::warn!("Failed to load config of theme {}: {}", theme_context.name, e);
+            serde_json::Value::Object(Default::default())
+        }
+    };
+    let mut finders = common_finder_data(state).await;
+    finders.extend(state.template_context_registry.contribute(&preview.request).await);
+    let seo = seo_value(
+        &model,
+        finders.get("site").unwrap_or(&serde_json::Value::Null),
+        finders.get("request").unwrap_or(&serde_json::Value::Null),
+    );
+    finders.insert("seo".to_string(), seo);
+    let template_context = TemplateContext::new()
+        .with_model(model)
+        .with_finders(finders)
+        .with_theme_config(theme_config);
+    engine.render(template_name, &template_context)
+}
+
+fn html_response(status: StatusCode, body: String) -> Response {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(axum::body::Body::from(body))
+        .unwrap()
+        .into_response()
+}
+
+/// 错误页面的候选模板：`error/{状态码}.html`，其他服务器错误最后使用`error/500.html`
+fn error_templates(status: StatusCode) -> Vec<String> {
+    let mut candidates = vec![format!("error/{}.html", status.as_u16())];
+    if status.is_server_error() && status != StatusCode::INTERNAL_SERVER_ERROR {
+        candidates.push("error/500.html".to_string());
+    }
+    candidates
+}
+
+/// 主题没有错误模板或渲染失败时使用的默认错误页面
+fn default_error_page(status: StatusCode) -> Response {
+    let title = format!("{} {}", status.as_u16(), status.canonical_reason().unwrap_or("Error"));
+    html_response(
+        status,
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body><h1>{title}</h1><p><a href=\"/\">Home</a></p></body>\n</html>\n"
+        ),
+    )
+}
+
+/// 使用当前主题的错误模板（模型为 `error`）渲染错误页面，主题没有对应模板时使用默认页面
+///
+/// 服务器错误的详细信息只记录到日志，不展示给访客
+async fn error_page(state: &AppState, preview: &PreviewTheme, status: StatusCode, message: String) -> Response {
+    let message = if status.is_server_error() {
+        tracing::error!("Failed to render {}: {}", preview.request.path, message);
+        None
+    } else {
+        Some(message)
+    };
+    let model = HashMap::from([(
+        "error".to_string(),
+        serde_json::json!({
+            "status": status.as_u16(),
+            "title": status.canonical_reason(),
+            "message": message,
+        }),
+    )]);
+    
+    let template = match state.theme_resolver.get_theme_with_preview(preview.preview.as_ref()).await {
+        Ok(Some(ctx)) => state.theme_resolver.resolve_template(&ctx, &error_templates(status))
+            .map(|template_name| (ctx, template_name)),
+        _ => None,
+    };
+    let mut response = match template {
+        Some((ctx, template_name)) => match render_template(state, preview, &ctx, &template_name, model).await {
+            Ok(rendered) => html_response(status, rendered),
+            Err(e) => {
+                tracing::warn!("Failed to render error template {} of theme {}: {}", template_name, ctx.name, e);
+                default_error_page(status)
+            }
+        },
+        None => default_error_page(status),
+    };
+    apply_preview(&mut response, preview);
+    response
+}
+
+/// 预览页面不允许缓存；通过查询参数进入预览时写入Cookie，使后续页面继续预览，参数为空时退出预览
+fn apply_preview(response: &mut Response, preview: &PreviewTheme) {
+    if preview.preview.is_some() {
+        response.headers_mut().insert(header::CACHE_CONTROL, header::HeaderValue::from_static("no-store"));
+    }
+    let max_age = match (&preview.query_token, &preview.preview) {
+        (Some(token), _) if token.is_empty() => 0,
+        (Some(_), Some(valid)) => valid.remaining_seconds(),
+        _ => return,
+    };
+    let cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        PREVIEW_THEME_PARAM,
+        preview.query_token.as_deref().unwrap_or_default(),
+        max_age
+    );
+    if let Ok(value) = header::HeaderValue::from_str(&cookie) {
+        response.headers_mut().append(header::SET_COOKIE, value);
+    }
+}
+
+/// 每个页面都可用的公共数据，加载失败时只记录日志
+async fn common_finder_data(state: &AppState) -> HashMap<String, serde_json::Value> {
+    let mut finders = HashMap::new();
+    let categories = CategoryFinder::new(state.category_service.clone()).list().await;
+    let tags = TagFinder::new(state.tag_service.clone()).list().await;
+    for (key, value) in [("categories", categories), ("tags", tags)] {
+        match value {
+            Ok(value) => {
+                finders.insert(key.to_string(), value);
+            }
+            Err(e) => tracing::warn!("Failed to load {} for theme rendering: {}", key, e),
+        }
+    }
+    // 注册表中的Finder以名称作为模板变量
+    for (name, finder) in state.finder_registry.get_all() {
+        match finder.preload().await {
+            Ok(Some(value)) => {
+                finders.insert(name, value);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to preload {} for theme rendering: {}", name, e),
+        }
+    }
+    finders
+}
+
+/// 评论区页码的查询参数
+const COMMENT_PAGE_PARAM: &str = "comment-page";
+
+/// 加载评论主题下的评论区数据（页码由`comment-page`查询参数指定），加载失败时返回None
+async fn comment_section(state: &AppState, kind: &str, name: &str, params: &HashMap<String, String>) -> Option<serde_json::Value> {
+    let page = params.get(COMMENT_PAGE_PARAM)
+        .and_then(|page| page.parse().ok())
+        .filter(|page| *page > 0)
+        .unwrap_or(1);
+    let finder = CommentFinder::new(state.comment_service.clone())
+        .with_user_service(state.user_service.clone());
+    match finder.list(kind, name, page, DEFAULT_PAGE_SIZE).await {
+        Ok(comments) => Some(comments),
+        Err(e) => {
+            tracing::warn!("Failed to load comments of {} {} for theme rendering: {}", kind, name, e);
+            None
+        }
+    }
+}
+
+async fn not_found(state: &AppState, preview: &PreviewTheme, message: String) -> Response {
+    error_page(state, preview, StatusCode::NOT_FOUND, message).await
+}
+
+async fn internal_error(state: &AppState, preview: &PreviewTheme, message: String) -> Response {
+    error_page(state, preview, StatusCode::INTERNAL_SERVER_ERROR, message).await
+}
+
+/// 解析路径中的页码（`/page/{n}`），没有页码时为第一页，页码无效时返回None
+fn page_number(params: &HashMap<String, String>) -> Option<u32> {
+    match params.get("page") {
+        Some(page) => page.parse().ok().filter(|page| *page > 0),
+        None => Some(1),
+    }
+}
+
+/// 渲染分页文章列表，超出范围的页码返回404，分页数据包含上一页和下一页的链接
+async fn render_post_list(
+    state: &AppState,
+    preview: &PreviewTheme,
+    query: PostQuery,
+    page: u32,
+    custom_template: Option<ChosenTemplate>,
+    candidates: &[String],
+    mut model: HashMap<String, serde_json::Value>,
+) -> Response {
+    let post_finder = PostFinder::new(state.post_service.clone());
+    let posts_value = match post_finder.list_page(query, page, DEFAULT_PAGE_SIZE).await {
+        Ok(value) => value,
+        Err(e) => return internal_error(state, preview, format!("Failed to list posts: {}", e)).await,
+    };
+    let empty = posts_value["items"].as_array().is_none_or(|items| items.is_empty());
+    if page > 1 && empty {
+        return not_found(state, preview, format!("Page not found: {}", page)).await;
+    }
+    let posts_value = with_page_urls(posts_value, page_base_path(&preview.request.path));
+    model.insert("posts".to_string(), posts_value);
+    render_page(state, preview, custom_template, candidates, model, StatusCode::OK).await
+}
+
+/// 首页路由（`/`）
+pub async fn index_page(
+    Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    render_index(&state, &preview, 1, &params).await
+}
+
+/// 首页分页路由（`/page/{n}`）
+pub async fn index_page_n(
+    Path(path_params): Path<HashMap<String, String>>,
+    Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    match page_number(&path_params) {
+        Some(page) => render_index(&state, &preview, page, &params).await,
+        None => not_found(&state, &preview, "Page not found".to_string()).await,
+    }
+}
+
+async fn render_index(state: &AppState, preview: &PreviewTheme, page: u32, params: &HashMap<String, String>) -> Response {
+    render_post_list(
+        state,
+        preview,
+        PostQuery {
+            language: params.get("lang").cloned(),
+            ..Default::default()
+        },
+        page,
+        None,
+        &["index.html".to_string()],
+        HashMap::new(),
+    ).await
+}
+
 /// 渲染主题模板
 pub async fn render_theme_template(
     Path(template_name): Path<String>,
     Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // 1. 获取当前主题（支持预览参数）
-    let preview_theme = params.get("preview");
-    let theme_context = match state.theme_resolver.get_theme_with_preview(preview_theme.map(|s| s.as_str())).await {
-        Ok(ctx) => ctx,
+    // 1. 获取当前主题（支持预览令牌）
+    let theme_context = match state.theme_resolver.get_theme_with_preview(preview.preview.as_ref()).await {
+        Ok(Some(ctx)) => ctx,
+        Ok(None) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "No active theme"
+            ).into_response();
+        }
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -36,7 +367,7 @@ pub async fn render_theme_template(
     // 添加模型数据（从查询参数中提取）
     let mut model: HashMap<String, serde_json::Value> = HashMap::new();
     for (key, value) in params {
-        if key != "preview" {
+        if key != PREVIEW_THEME_PARAM {
             model.insert(key, serde_json::Value::String(value));
         }
     }
@@ -61,324 +392,332 @@ pub async fn render_theme_template(
     }
 }
 
-/// 文章页面路由
+/// 文章页面路由（`/archives/{slug}`）
 pub async fn post_page(
     Path(slug): Path<String>,
     Query(params): Query<HashMap<String, String>>,
+    uri: OriginalUri,
+    headers: HeaderMap,
+    preview: PreviewTheme,
     State(state): State<AppState>,
-) -> impl IntoResponse {
+) -> Response {
     // 1. 根据slug查找Post
-    let post_finder = PostFinder::new(state.post_service.clone());
+    let post_finder = PostFinder::new(state.post_service.clone())
+        .with_series_service(state.series_service.clone())
+        .with_language(params.get("lang").cloned());
     let post_value = match post_finder.get_by_slug(&slug).await {
         Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get post: {}", e)
-            ).into_response();
-        }
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get post: {}", e)).await,
     };
     
     if post_value.is_null() {
-        return (
-            StatusCode::NOT_FOUND,
-            format!("Post not found: {}", slug)
-        ).into_response();
+        if let Some(response) = redirect_moved(&state, uri.path()).await {
+            return response;
+        }
+        return not_found(&state, &preview, format!("Post not found: {}", slug)).await;
     }
     
-    // 2. 确定使用的模板（自定义模板或默认模板）
-    let template_name = params.get("template")
-        .map(|s| s.as_str())
-        .unwrap_or("post.html");
+    // 2. 加密文章未解锁时渲染密码页面，不暴露文章数据
+    let post_name = post_value.pointer("/metadata/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let mut model: HashMap<String, serde_json::Value> = HashMap::new();
+    if is_post_locked(&state, &post_name, &headers).await {
+        model.insert("post".to_string(), locked_post_value(&post_value));
+        return render_page(&state, &preview, None, &[POST_PASSWORD_TEMPLATE.to_string()], model, StatusCode::UNAUTHORIZED).await;
+    }
     
-    // 3. 构建模板上下文
-    let theme_context = match state.theme_resolver.get_active_theme_context().await {
-        Ok(Some(ctx)) => ctx,
-        Ok(None) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "No active theme"
-            ).into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get theme context: {}", e)
-            ).into_response();
-        }
+    // 3. 加载已发布的内容，模板查找顺序：文章所选的自定义模板 -> post.html
+    let content = match post_finder.with_unlocked(&post_name).content(&post_name).await {
+        Ok(value) => value,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get post content: {}", e)).await,
     };
+    let custom_template = chosen_template(&post_value, CustomTemplateKind::Post);
     
-    let engine = state.template_engine_manager.get_template_engine(&theme_context).await;
-    
-    let mut template_context = TemplateContext::new();
-    let mut model: HashMap<String, serde_json::Value> = HashMap::new();
     model.insert("post".to_string(), post_value);
-    template_context = template_context.with_model(model);
-    
-    // 4. 渲染模板
-    match engine.render(template_name, &template_context) {
-        Ok(rendered) => {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-                .body(axum::body::Body::from(rendered))
-                .unwrap()
-                .into_response()
-        }
-        Err(e) => {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to render template: {}", e)
-            ).into_response()
-        }
+    model.insert("content".to_string(), content);
+    if let Some(comments) = comment_section(&state, "Post", &post_name, &params).await {
+        model.insert("comments".to_string(), comments);
     }
+    render_page(&state, &preview, custom_template, &["post.html".to_string()], model, StatusCode::OK).await
 }
 
-/// 分类页面路由
+/// 分类页面路由（`/categories/{slug}` 和 `/categories/{slug}/page/{n}`）
 pub async fn category_page(
-    Path(name): Path<String>,
+    Path(path_params): Path<HashMap<String, String>>,
     Query(params): Query<HashMap<String, String>>,
+    uri: OriginalUri,
+    preview: PreviewTheme,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    // 1. 根据name查找Category
+) -> Response {
+    let slug = path_params.get("slug").cloned().unwrap_or_default();
+    let Some(page) = page_number(&path_params) else {
+        return not_found(&state, &preview, format!("Category not found: {}", slug)).await;
+    };
+    
+    // 1. 根据slug查找Category
     let category_finder = CategoryFinder::new(state.category_service.clone());
-    let category_value = match category_finder.get_by_name(&name).await {
+    let category_value = match category_finder.get_by_slug(&slug).await {
         Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get category: {}", e)
-            ).into_response();
-        }
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get category: {}", e)).await,
     };
     
     if category_value.is_null() {
-        return (
-            StatusCode::NOT_FOUND,
-            format!("Category not found: {}", name)
-        ).into_response();
+        if let Some(response) = redirect_moved(&state, uri.path()).await {
+            return response;
+        }
+        return not_found(&state, &preview, format!("Category not found: {}", slug)).await;
     }
     
-    // 2. 查询该分类下的Posts
-    let post_finder = PostFinder::new(state.post_service.clone());
+    // 2. 模板查找顺序：分类所选的自定义模板 -> category.html
+    let custom_template = chosen_template(&category_value, CustomTemplateKind::Category);
+    
+    // 3. 查询该分类下的Posts并渲染
     let query = PostQuery {
-        category: Some(name.clone()),
-        published: Some(true),
+        category: category_value.pointer("/metadata/name").and_then(|v| v.as_str()).map(str::to_string),
+        language: params.get("lang").cloned(),
         ..Default::default()
     };
-    let posts_value = match post_finder.list(Some(query)).await {
-        Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to list posts: {}", e)
-            ).into_response();
-        }
-    };
-    
-    // 3. 确定使用的模板
-    let template_name = params.get("template")
-        .map(|s| s.as_str())
-        .unwrap_or("category.html");
-    
-    // 4. 构建模板上下文并渲染
-    let theme_context = match state.theme_resolver.get_active_theme_context().await {
-        Ok(Some(ctx)) => ctx,
-        Ok(None) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "No active theme"
-            ).into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get theme context: {}", e)
-            ).into_response();
-        }
-    };
-    
-    let engine = state.template_engine_manager.get_template_engine(&theme_context).await;
-    
-    let mut template_context = TemplateContext::new();
     let mut model: HashMap<String, serde_json::Value> = HashMap::new();
     model.insert("category".to_string(), category_value);
-    model.insert("posts".to_string(), posts_value);
-    template_context = template_context.with_model(model);
-    
-    match engine.render(template_name, &template_context) {
-        Ok(rendered) => {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-                .body(axum::body::Body::from(rendered))
-                .unwrap()
-                .into_response()
-        }
-        Err(e) => {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to render template: {}", e)
-            ).into_response()
-        }
-    }
+    render_post_list(&state, &preview, query, page, custom_template, &["category.html".to_string()], model).await
 }
 
-/// 标签页面路由
+/// 标签页面路由（`/tags/{slug}` 和 `/tags/{slug}/page/{n}`）
 pub async fn tag_page(
-    Path(name): Path<String>,
+    Path(path_params): Path<HashMap<String, String>>,
     Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    // 1. 根据name查找Tag
+) -> Response {
+    let slug = path_params.get("slug").cloned().unwrap_or_default();
+    let Some(page) = page_number(&path_params) else {
+        return not_found(&state, &preview, format!("Tag not found: {}", slug)).await;
+    };
+    
+    // 1. 根据slug查找Tag
     let tag_finder = TagFinder::new(state.tag_service.clone());
-    let tag_value = match tag_finder.get_by_name(&name).await {
+    let tag_value = match tag_finder.get_by_slug(&slug).await {
         Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get tag: {}", e)
-            ).into_response();
-        }
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get tag: {}", e)).await,
     };
     
     if tag_value.is_null() {
-        return (
-            StatusCode::NOT_FOUND,
-            format!("Tag not found: {}", name)
-        ).into_response();
+        return not_found(&state, &preview, format!("Tag not found: {}", slug)).await;
     }
     
-    // 2. 查询该标签下的Posts
-    let post_finder = PostFinder::new(state.post_service.clone());
+    // 2. 查询该标签下的Posts并渲染
     let query = PostQuery {
-        tag: Some(name.clone()),
-        published: Some(true),
+        tag: tag_value.pointer("/metadata/name").and_then(|v| v.as_str()).map(str::to_string),
+        language: params.get("lang").cloned(),
         ..Default::default()
     };
-    let posts_value = match post_finder.list(Some(query)).await {
-        Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to list posts: {}", e)
-            ).into_response();
-        }
+    let mut model: HashMap<String, serde_json::Value> = HashMap::new();
+    model.insert("tag".to_string(), tag_value);
+    render_post_list(&state, &preview, query, page, None, &["tag.html".to_string()], model).await
+}
+
+/// 作者归档页面路由（`/authors/{name}`和`/authors/{name}/page/{n}`）
+pub async fn author_page(
+    Path(path_params): Path<HashMap<String, String>>,
+    Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    let name = path_params.get("name").cloned().unwrap_or_default();
+    let Some(page) = page_number(&path_params) else {
+        return not_found(&state, &preview, format!("Author not found: {}", name)).await;
     };
     
-    // 3. 确定使用的模板
-    let template_name = params.get("template")
-        .map(|s| s.as_str())
-        .unwrap_or("tag.html");
-    
-    // 4. 构建模板上下文并渲染
-    let theme_context = match state.theme_resolver.get_active_theme_context().await {
-        Ok(Some(ctx)) => ctx,
-        Ok(None) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "No active theme"
-            ).into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get theme context: {}", e)
-            ).into_response();
-        }
+    // 1. 查找作者资料
+    let finder = ContributorFinder::new(state.user_service.clone(), state.post_service.clone());
+    let author_value = match finder.get_contributor(&name).await {
+        Ok(value) => value,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get author: {}", e)).await,
     };
     
-    let engine = state.template_engine_manager.get_template_engine(&theme_context).await;
+    if author_value.is_null() {
+        return not_found(&state, &preview, format!("Author not found: {}", name)).await;
+    }
     
-    let mut template_context = TemplateContext::new();
+    // 2. 查询作者参与的Posts并渲染
+    let query = PostQuery {
+        contributor: Some(name),
+        language: params.get("lang").cloned(),
+        ..Default::default()
+    };
     let mut model: HashMap<String, serde_json::Value> = HashMap::new();
-    model.insert("tag".to_string(), tag_value);
-    model.insert("posts".to_string(), posts_value);
-    template_context = template_context.with_model(model);
-    
-    match engine.render(template_name, &template_context) {
-        Ok(rendered) => {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-                .body(axum::body::Body::from(rendered))
-                .unwrap()
-                .into_response()
-        }
-        Err(e) => {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to render template: {}", e)
-            ).into_response()
-        }
-    }
+    model.insert("author".to_string(), author_value);
+    render_post_list(&state, &preview, query, page, None, &["author.html".to_string()], model).await
 }
 
-/// 归档页面路由
+/// 归档页面路由（`/archives`）
 pub async fn archive_page(
     Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    // 1. 查询所有已发布的Posts
-    let post_finder = PostFinder::new(state.post_service.clone());
+) -> Response {
+    render_archive(&state, &preview, 1, &params).await
+}
+
+/// 归档分页路由（`/archives/page/{n}`）
+pub async fn archive_page_n(
+    Path(path_params): Path<HashMap<String, String>>,
+    Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    match page_number(&path_params) {
+        Some(page) => render_archive(&state, &preview, page, &params).await,
+        None => not_found(&state, &preview, "Page not found".to_string()).await,
+    }
+}
+
+async fn render_archive(state: &AppState, preview: &PreviewTheme, page: u32, params: &HashMap<String, String>) -> Response {
     let query = PostQuery {
-        published: Some(true),
+        language: params.get("lang").cloned(),
         ..Default::default()
     };
-    let posts_value = match post_finder.list(Some(query)).await {
-        Ok(value) => value,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to list posts: {}", e)
-            ).into_response();
+    render_post_list(state, preview, query, page, None, &["archive.html".to_string()], HashMap::new()).await
+}
+
+/// 系列归档页面路由（`/series/{slug}`）
+pub async fn series_page(
+    Path(slug): Path<String>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    // 1. 根据slug查找Series
+    let series = match state.series_service.get_by_slug(&slug).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return not_found(&state, &preview, format!("Series not found: {}", slug)).await,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get series: {}", e)).await,
+    };
+    
+    // 2. 按系列顺序加载文章
+    let post_finder = PostFinder::new(state.post_service.clone());
+    let mut posts = Vec::new();
+    for post_name in &series.spec.posts {
+        match post_finder.get_by_name(post_name).await {
+            Ok(value) if !value.is_null() => posts.push(value),
+            Ok(_) => {}
+            Err(e) => return internal_error(&state, &preview, format!("Failed to get post: {}", e)).await,
         }
+    }
+    
+    // 3. 模板查找顺序：系列自定义模板 -> series-{slug}.html -> series.html
+    let mut candidates = Vec::new();
+    if let Some(template) = series.spec.template.as_deref() {
+        candidates.push(template.to_string());
+    }
+    candidates.push(format!("series-{}.html", series.spec.slug));
+    candidates.push("series.html".to_string());
+    
+    let series_value = match serde_json::to_value(&series) {
+        Ok(value) => value,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to serialize series: {}", e)).await,
     };
     
-    // 2. 确定使用的模板
-    let template_name = params.get("template")
-        .map(|s| s.as_str())
-        .unwrap_or("archive.html");
+    let mut model: HashMap<String, serde_json::Value> = HashMap::new();
+    model.insert("series".to_string(), series_value);
+    model.insert("posts".to_string(), serde_json::Value::Array(posts));
+    render_page(&state, &preview, None, &candidates, model, StatusCode::OK).await
+}
+
+/// 独立页面路由（未匹配其他路由的GET请求）
+///
+/// 按页面路径（如 `/about/team`）查找已发布的独立页面，
+/// 模板查找顺序：页面所选的自定义模板 -> page.html
+pub async fn single_page_page(
+    method: Method,
+    uri: OriginalUri,
+    Query(params): Query<HashMap<String, String>>,
+    preview: PreviewTheme,
+    State(state): State<AppState>,
+) -> Response {
+    let path = uri.path();
+    if (method != Method::GET && method != Method::HEAD) || !is_public_site_path(path) {
+        return (StatusCode::NOT_FOUND, format!("Not found: {}", path)).into_response();
+    }
     
-    // 3. 构建模板上下文并渲染
-    let theme_context = match state.theme_resolver.get_active_theme_context().await {
-        Ok(Some(ctx)) => ctx,
-        Ok(None) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "No active theme"
-            ).into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to get theme context: {}", e)
-            ).into_response();
-        }
+    let finder = SinglePageFinder::new(state.single_page_service.clone());
+    let page_value = match finder.get_by_permalink(path).await {
+        Ok(value) => value,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get single page: {}", e)).await,
     };
+    if page_value.is_null() {
+        if let Some(response) = redirect_moved(&state, path).await {
+            return response;
+        }
+        return not_found(&state, &preview, format!("Not found: {}", path)).await;
+    }
     
-    let engine = state.template_engine_manager.get_template_engine(&theme_context).await;
+    let page_name = page_value.pointer("/metadata/name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let content = match finder.content(&page_name).await {
+        Ok(value) => value,
+        Err(e) => return internal_error(&state, &preview, format!("Failed to get single page content: {}", e)).await,
+    };
+    let custom_template = chosen_template(&page_value, CustomTemplateKind::Page);
     
-    let mut template_context = TemplateContext::new();
     let mut model: HashMap<String, serde_json::Value> = HashMap::new();
-    model.insert("posts".to_string(), posts_value);
-    template_context = template_context.with_model(model);
-    
-    match engine.render(template_name, &template_context) {
-        Ok(rendered) => {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-                .body(axum::body::Body::from(rendered))
-                .unwrap()
-                .into_response()
+    model.insert("singlePage".to_string(), page_value);
+    model.insert("content".to_string(), content);
+    if let Some(comments) = comment_section(&state, "SinglePage", &page_name, &params).await {
+        model.insert("comments".to_string(), comments);
+    }
+    render_page(&state, &preview, custom_template, &["page.html".to_string()], model, StatusCode::OK).await
+}
+
+/// 是否为前台站点路径：主题页面、主题静态资源、插件前端资源和按页面路径匹配的独立页面
+///
+/// API和认证端点不属于前台站点；独立页面路径只由非空的页面slug组成，
+/// 包含保留段`-`（如 `/-/uploads`）或以`.`开头的路径段的路径也不属于前台站点
+pub fn is_public_site_path(path: &str) -> bool {
+    if path == "/" {
+        return true;
+    }
+    let Some(rest) = path.strip_prefix('/') else {
+        return false;
+    };
+    let rest = rest.strip_suffix('/').unwrap_or(rest);
+    if matches!(rest.split('/').next(), Some("api" | "apis" | "oauth2")) {
+        return false;
+    }
+    rest.split('/').all(|segment| !segment.is_empty() && segment != "-" && !segment.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_templates() {
+        assert_eq!(error_templates(StatusCode::NOT_FOUND), ["error/404.html"]);
+        assert_eq!(error_templates(StatusCode::INTERNAL_SERVER_ERROR), ["error/500.html"]);
+        // 其他服务器错误没有对应模板时使用500模板
+        assert_eq!(error_templates(StatusCode::SERVICE_UNAVAILABLE), ["error/503.html", "error/500.html"]);
+    }
+
+    #[test]
+    fn test_public_site_path() {
+        for path in ["/", "/archives", "/archives/hello/", "/themes/earth/assets/app.css", "/plugins/links/assets/main.js", "/about/team"] {
+            assert!(is_public_site_path(path), "{}", path);
         }
-        Err(e) => {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to render template: {}", e)
-            ).into_response()
+        for path in ["/api", "/api/v1alpha1/posts", "/apis/content.halo.run/v1alpha1/posts", "/oauth2/callback/github", "/-/uploads", "/about/-/edit", "/.env", "//about", ""] {
+            assert!(!is_public_site_path(path), "{}", path);
         }
     }
-}
 
+    #[tokio::test]
+    async fn test_default_error_page() {
+        let response = default_error_page(StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/html; charset=utf-8");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8(body.to_vec()).unwrap().contains("<h1>404 Not Found</h1>"));
+    }
+}
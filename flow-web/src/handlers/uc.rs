@@ -5,8 +5,10 @@ use axum::{
     Json,
 };
 use flow_domain::content::{Post, Snapshot};
-use flow_service::content::{PostQuery, PostRequest, ContentRequest};
+use flow_service::content::{PostQuery, PostRequest, PostService, ContentRequest, ListedPost};
+use flow_service::content::cover_service::INVALID_COVER_ERROR;
 use crate::{AppState, extractors::CurrentUser};
+use super::posts::{SetContributorsRequest, publish_error_response};
 use serde::{Deserialize, Serialize};
 
 /// 创建我的Post（草稿）
@@ -39,7 +41,8 @@ pub async fn create_my_post(
     };
     
     match state.post_service.draft_post(post_request).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_COVER_ERROR) => Err(StatusCode::BAD_REQUEST),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -52,11 +55,8 @@ pub async fn get_my_post(
     Path(name): Path<String>,
 ) -> Result<Response, StatusCode> {
     
-    match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => Ok(Json(post).into_response()),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+    let post = fetch_editable_post(state.post_service.as_ref(), &name, &username, false).await?;
+    Ok(Json(post.without_password()).into_response())
 }
 
 /// 列出我的Posts
@@ -69,13 +69,13 @@ pub async fn list_my_posts(
     
     // 解析查询参数
     let mut query = parse_post_query(params);
-    // UC端点只返回当前用户的posts
-    query.owner = Some(username);
+    // UC端点只返回当前用户拥有或参与编写的posts
+    query.contributor = Some(username);
     
     match state.post_service.list_post(query).await {
         Ok(result) => {
             let response = UcPostListResponse {
-                items: result.items,
+                items: result.items.into_iter().map(ListedPost::without_password).collect(),
                 total: result.total,
                 page: result.page as u64,
                 size: result.size as u64,
@@ -101,15 +101,12 @@ pub async fn update_my_post(
     }
     
     // 获取原始Post以限制可更新字段
-    let old_post = match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(p)) => p,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    let old_post = fetch_editable_post(state.post_service.as_ref(), &name, &username, false).await?;
     
-    // 限制字段更新（不允许修改owner、publish状态等）
+    // 限制字段更新（不允许修改owner、共同作者、publish状态等）
     let old_spec = &old_post.spec;
     post.spec.owner = old_spec.owner.clone();
+    post.spec.contributors = old_spec.contributors.clone();
     post.spec.publish = old_spec.publish;
     post.spec.head_snapshot = old_spec.head_snapshot.clone();
     post.spec.base_snapshot = old_spec.base_snapshot.clone();
@@ -127,7 +124,8 @@ pub async fn update_my_post(
     };
     
     match state.post_service.update_post(post_request).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) if e.to_string().starts_with(INVALID_COVER_ERROR) => Err(StatusCode::BAD_REQUEST),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -140,15 +138,11 @@ pub async fn publish_my_post(
     Path(name): Path<String>,
 ) -> Result<Response, StatusCode> {
     
-    let post = match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => post,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    let post = fetch_editable_post(state.post_service.as_ref(), &name, &username, true).await?;
     
     match state.post_service.publish(post).await {
-        Ok(post) => Ok(Json(post).into_response()),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(e) => publish_error_response(e.as_ref()),
     }
 }
 
@@ -160,14 +154,10 @@ pub async fn unpublish_my_post(
     Path(name): Path<String>,
 ) -> Result<Response, StatusCode> {
     
-    let post = match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => post,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    let post = fetch_editable_post(state.post_service.as_ref(), &name, &username, true).await?;
     
     match state.post_service.unpublish(post).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -180,8 +170,10 @@ pub async fn recycle_my_post(
     Path(name): Path<String>,
 ) -> Result<Response, StatusCode> {
     
+    fetch_editable_post(state.post_service.as_ref(), &name, &username, true).await?;
+    
     match state.post_service.recycle(&name, &username).await {
-        Ok(post) => Ok(Json(post).into_response()),
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -196,11 +188,7 @@ pub async fn get_my_post_draft(
 ) -> Result<Response, StatusCode> {
     
     // 获取Post
-    let post = match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => post,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    let post = fetch_editable_post(state.post_service.as_ref(), &name, &username, false).await?;
     
     // 获取head snapshot或base snapshot
     let snapshot_name = post.spec.head_snapshot
@@ -239,11 +227,7 @@ pub async fn update_my_post_draft(
 ) -> Result<Response, StatusCode> {
     
     // 获取Post
-    let post = match state.post_service.get_by_username(&name, &username).await {
-        Ok(Some(post)) => post,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    let post = fetch_editable_post(state.post_service.as_ref(), &name, &username, false).await?;
     
     // 验证snapshot属于该Post
     if snapshot.spec.subject_ref.name != name {
@@ -257,132 +241,101 @@ pub async fn update_my_post_draft(
         return Err(StatusCode::BAD_REQUEST);
     }
     
-    // 获取base snapshot
-    let base_snapshot_name = post.spec.base_snapshot.as_ref()
+    let base_snapshot_name = post.spec.base_snapshot.clone()
         .ok_or(StatusCode::BAD_REQUEST)?;
-    let base_snapshot = match state.snapshot_service.get(base_snapshot_name).await {
-        Ok(Some(s)) => s,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
-    
-    // 获取head snapshot
-    let head_snapshot = match state.snapshot_service.get(head_snapshot_name).await {
-        Ok(Some(s)) => s,
-        Ok(None) => return Err(StatusCode::NOT_FOUND),
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
-    
-    // 从请求的snapshot中提取内容
-    let new_raw = snapshot.spec.raw_patch.as_deref().unwrap_or("");
-    let new_content = snapshot.spec.content_patch.as_deref().unwrap_or("");
-    
-    // 获取base snapshot的完整内容（用于计算diff）
-    let base_raw = base_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-    let base_content = base_snapshot.spec.content_patch.as_deref().unwrap_or("");
-    
-    // 如果head snapshot不是base snapshot，需要先获取head snapshot的完整内容
-    let head_raw = if head_snapshot_name == base_snapshot_name {
-        base_raw.to_string()
-    } else {
-        // 应用head snapshot的patch到base snapshot
-        let head_raw_patch = head_snapshot.spec.raw_patch.as_deref().unwrap_or("");
-        if head_raw_patch.is_empty() {
-            base_raw.to_string()
-        } else {
-            match flow_service::content::patch_utils::apply_patch(base_raw, head_raw_patch) {
-                Ok(r) => r,
-                Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-            }
-        }
-    };
-    
-    let head_content = if head_snapshot_name == base_snapshot_name {
-        base_content.to_string()
-    } else {
-        let head_content_patch = head_snapshot.spec.content_patch.as_deref().unwrap_or("");
-        if head_content_patch.is_empty() {
-            base_content.to_string()
-        } else {
-            match flow_service::content::patch_utils::apply_patch(base_content, head_content_patch) {
-                Ok(c) => c,
-                Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-            }
-        }
-    };
-    
-    // 计算diff（从head snapshot到新内容）
-    let raw_patch = if head_raw == new_raw {
-        "[]".to_string()
-    } else {
-        match flow_service::content::patch_utils::diff_to_json_patch(&head_raw, new_raw) {
-            Ok(p) => p,
-            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-        }
-    };
     
-    let content_patch = if head_content == new_content {
-        "[]".to_string()
-    } else {
-        match flow_service::content::patch_utils::diff_to_json_patch(&head_content, new_content) {
-            Ok(p) => p,
-            Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-        }
-    };
+    // 请求中的rawPatch/contentPatch为完整内容，保存时转换为相对base snapshot的补丁
+    let new_raw = snapshot.spec.raw_patch.clone();
+    let new_content = snapshot.spec.content_patch.clone();
     
     // 检查head snapshot是否等于release snapshot
     let release_snapshot_name = post.spec.release_snapshot.as_ref();
     let should_create_new = release_snapshot_name.map(|r| r == head_snapshot_name).unwrap_or(false);
     
-    if should_create_new {
+    let revision = if should_create_new {
         // 创建新的snapshot
         use flow_api::extension::Metadata;
         use uuid::Uuid;
         use chrono::Utc;
         
         let mut new_snapshot = Snapshot {
-            metadata: Metadata {
-                name: format!("{}-snapshot-{}", name, Uuid::new_v4().to_string().chars().take(8).collect::<String>()),
-                version: Some(0),
-                creation_timestamp: Some(Utc::now()),
-                labels: None,
-                annotations: None,
-            },
+            metadata: Metadata::new(format!("{}-snapshot-{}", name, Uuid::new_v4().to_string().chars().take(8).collect::<String>())),
             spec: snapshot.spec.clone(),
         };
         
         new_snapshot.spec.parent_snapshot_name = Some(head_snapshot_name.clone());
-        new_snapshot.spec.raw_patch = Some(raw_patch);
-        new_snapshot.spec.content_patch = Some(content_patch);
         new_snapshot.spec.last_modify_time = Some(Utc::now());
         new_snapshot.spec.owner = username.clone();
         new_snapshot.add_contributor(username.clone());
-        
-        let created_snapshot = match state.snapshot_service.create(new_snapshot).await {
-            Ok(s) => s,
+        new_snapshot
+    } else {
+        // 更新现有的head snapshot
+        let mut updated_snapshot = match state.snapshot_service.get(head_snapshot_name).await {
+            Ok(Some(s)) => s,
+            Ok(None) => return Err(StatusCode::NOT_FOUND),
             Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
         };
-        
-        // 更新Post的head_snapshot
-        let mut updated_post = post;
-        updated_post.spec.head_snapshot = Some(created_snapshot.metadata.name.clone());
-        match state.post_service.update_by(updated_post).await {
-            Ok(_) => Ok(Json(created_snapshot).into_response()),
-            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-        }
-    } else {
-        // 更新现有的snapshot
-        let mut updated_snapshot = head_snapshot;
-        updated_snapshot.spec.raw_patch = Some(raw_patch);
-        updated_snapshot.spec.content_patch = Some(content_patch);
+        updated_snapshot.spec.raw_type = snapshot.spec.raw_type.clone();
+        updated_snapshot.spec.raw_patch = new_raw;
+        updated_snapshot.spec.content_patch = new_content;
         updated_snapshot.spec.last_modify_time = Some(chrono::Utc::now());
         updated_snapshot.add_contributor(username.clone());
-        
-        match state.snapshot_service.update(updated_snapshot).await {
-            Ok(s) => Ok(Json(s).into_response()),
-            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        updated_snapshot
+    };
+    
+    let saved_snapshot = match state.snapshot_service.save_revision(revision, Some(&base_snapshot_name)).await {
+        Ok(s) => s,
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    if should_create_new {
+        // 更新Post的head_snapshot
+        let mut updated_post = post;
+        updated_post.spec.head_snapshot = Some(saved_snapshot.metadata.name.clone());
+        if state.post_service.update_by(updated_post).await.is_err() {
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     }
+    sync_contributors(&state, &name).await;
+    Ok(Json(saved_snapshot).into_response())
+}
+
+/// 设置我的Post的共同作者（仅所有者可操作）
+/// PUT /api/v1alpha1/uc/posts/{name}/contributors
+pub async fn set_my_post_contributors(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(name): Path<String>,
+    Json(request): Json<SetContributorsRequest>,
+) -> Result<Response, StatusCode> {
+    fetch_editable_post(state.post_service.as_ref(), &name, &username, true).await?;
+    
+    match state.post_service.set_contributors(&name, request.contributors).await {
+        Ok(post) => Ok(Json(post.without_password()).into_response()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// 获取当前用户可编辑的Post：owner_only为true时仅所有者，否则所有者或共同作者
+async fn fetch_editable_post(post_service: &dyn PostService, name: &str, username: &str, owner_only: bool) -> Result<Post, StatusCode> {
+    let post = match post_service.get_by_username(name, username).await {
+        Ok(Some(post)) => post,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    
+    let allowed = if owner_only { post.is_owned_by(username) } else { post.can_edit(username) };
+    if !allowed {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(post)
+}
+
+/// 根据快照作者刷新Post的贡献者列表
+async fn sync_contributors(state: &AppState, name: &str) {
+    if let Err(e) = state.post_service.sync_contributors(name).await {
+        tracing::warn!("Failed to sync contributors for post {}: {}", name, e);
+    }
 }
 
 /// Post列表响应
@@ -431,6 +384,10 @@ fn parse_post_query(params: serde_json::Value) -> PostQuery {
         query.tag = Some(tag.to_string());
     }
     
+    if let Some(language) = params.get("language").and_then(|v| v.as_str()) {
+        query.language = Some(language.to_string());
+    }
+    
     if let Some(visible) = params.get("visible").and_then(|v| v.as_str()) {
         query.visible = match visible {
             "PUBLIC" => Some(flow_domain::content::VisibleEnum::Public),
@@ -451,3 +408,37 @@ fn parse_post_query(params: serde_json::Value) -> PostQuery {
     query
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_api::extension::{ExtensionClient, Metadata};
+    use flow_domain::content::PostSpec;
+    use flow_service::content::DefaultPostService;
+    use flow_service::testing::MemoryClient;
+    use std::sync::Arc;
+
+    async fn service() -> DefaultPostService<MemoryClient> {
+        let mut spec: PostSpec = serde_json::from_value(serde_json::json!({"title": "hello", "slug": "hello"})).unwrap();
+        spec.owner = Some("alice".to_string());
+        spec.contributors = Some(vec!["bob".to_string()]);
+        let client = Arc::new(MemoryClient::default());
+        client.create(Post { metadata: Metadata::new("hello"), spec, status: None }).await.unwrap();
+        DefaultPostService::new(client)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_editable_post() {
+        let service = service().await;
+        assert!(fetch_editable_post(&service, "hello", "alice", true).await.is_ok());
+        assert!(fetch_editable_post(&service, "hello", "bob", false).await.is_ok());
+        assert_eq!(fetch_editable_post(&service, "missing", "alice", false).await.unwrap_err(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_editable_post_forbidden() {
+        let service = service().await;
+        // 共同作者不能执行仅限所有者的操作，其他用户不能编辑
+        assert_eq!(fetch_editable_post(&service, "hello", "bob", true).await.unwrap_err(), StatusCode::FORBIDDEN);
+        assert_eq!(fetch_editable_post(&service, "hello", "carol", false).await.unwrap_err(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,235 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    Json,
+};
+use flow_service::attachment::{NewUploadSession, UploadSession, UploadValidationError};
+use flow_service::attachment::quota_service::QUOTA_EXCEEDED_ERROR;
+use flow_service::attachment::upload_scanner::INFECTED_FILE_ERROR;
+use flow_service::attachment::upload_session::{
+    parse_upload_metadata, TUS_VERSION, UPLOAD_LENGTH_EXCEEDED_ERROR, UPLOAD_OFFSET_MISMATCH_ERROR,
+};
+use crate::{AppState, extractors::CurrentUser, handlers::attachments::upload_validation_response};
+
+/// 单个分片允许的最大大小（64MiB）
+pub const MAX_UPLOAD_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+const TUS_RESUMABLE: HeaderName = HeaderName::from_static("tus-resumable");
+const TUS_VERSION_HEADER: HeaderName = HeaderName::from_static("tus-version");
+const TUS_EXTENSION: HeaderName = HeaderName::from_static("tus-extension");
+const TUS_MAX_SIZE: HeaderName = HeaderName::from_static("tus-max-size");
+const UPLOAD_LENGTH: HeaderName = HeaderName::from_static("upload-length");
+const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+const UPLOAD_METADATA: HeaderName = HeaderName::from_static("upload-metadata");
+const UPLOAD_EXPIRES: HeaderName = HeaderName::from_static("upload-expires");
+/// 上传完成后生成的附件名称
+const ATTACHMENT_NAME: HeaderName = HeaderName::from_static("x-attachment-name");
+
+const OFFSET_OCTET_STREAM: &str = "application/offset+octet-stream";
+
+/// 响应都需要携带Tus-Resumable头
+fn tus_response(status: StatusCode, headers: Vec<(HeaderName, String)>) -> Response {
+    let mut response = status.into_response();
+    let response_headers = response.headers_mut();
+    response_headers.insert(TUS_RESUMABLE, HeaderValue::from_static(TUS_VERSION));
+    for (name, value) in headers {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response_headers.insert(name, value);
+        }
+    }
+    response
+}
+
+/// 超出存储配额时在响应体中说明原因
+fn quota_exceeded(message: &str) -> Response {
+    let mut response = tus_response(StatusCode::PAYLOAD_TOO_LARGE, vec![]);
+    *response.body_mut() = axum::body::Body::from(message.to_string());
+    response
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// 检查客户端使用的协议版本，不支持时返回412响应
+fn check_tus_version(headers: &HeaderMap) -> Option<Response> {
+    if header_str(headers, &TUS_RESUMABLE) == Some(TUS_VERSION) {
+        return None;
+    }
+    Some(tus_response(StatusCode::PRECONDITION_FAILED, vec![(TUS_VERSION_HEADER, TUS_VERSION.to_string())]))
+}
+
+/// 获取当前用户的上传会话，不存在返回404，不属于当前用户返回403
+async fn fetch_my_session(state: &AppState, id: &str, username: &str) -> Result<UploadSession, StatusCode> {
+    match state.upload_session_service.get(id).await {
+        Ok(Some(session)) if session.owner_name.as_deref() == Some(username) => Ok(session),
+        Ok(Some(_)) => Err(StatusCode::FORBIDDEN),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+fn offset_headers(session: &UploadSession) -> Vec<(HeaderName, String)> {
+    let mut headers = vec![
+        (UPLOAD_OFFSET, session.offset.to_string()),
+        (UPLOAD_LENGTH, session.length.to_string()),
+        (UPLOAD_EXPIRES, session.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()),
+    ];
+    if let Some(attachment_name) = &session.attachment_name {
+        headers.push((ATTACHMENT_NAME, attachment_name.clone()));
+    }
+    headers
+}
+
+/// 查询服务端支持的tus协议版本和扩展
+/// OPTIONS /api/v1alpha1/attachments/-/uploads
+pub async fn tus_options(State(state): State<AppState>) -> Response {
+    tus_response(StatusCode::NO_CONTENT, vec![
+        (TUS_VERSION_HEADER, TUS_VERSION.to_string()),
+        (TUS_EXTENSION, "creation,termination,expiration".to_string()),
+        (TUS_MAX_SIZE, state.upload_session_service.max_size().to_string()),
+    ])
+}
+
+/// 创建分片上传会话
+/// POST /api/v1alpha1/attachments/-/uploads
+///
+/// Upload-Metadata支持filename、filetype、policyName和groupName
+pub async fn create_upload(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(response) = check_tus_version(&headers) {
+        return response;
+    }
+    let Some(length) = header_str(&headers, &UPLOAD_LENGTH).and_then(|v| v.parse::<u64>().ok()) else {
+        return tus_response(StatusCode::BAD_REQUEST, vec![]);
+    };
+    if length > state.upload_session_service.max_size() {
+        return tus_response(StatusCode::PAYLOAD_TOO_LARGE, vec![]);
+    }
+
+    let mut metadata = header_str(&headers, &UPLOAD_METADATA)
+        .map(parse_upload_metadata)
+        .unwrap_or_default();
+    let new_session = NewUploadSession {
+        filename: metadata.remove("filename").filter(|f| !f.is_empty()).unwrap_or_else(|| "file".to_string()),
+        media_type: metadata.remove("filetype").filter(|t| !t.is_empty()),
+        owner_name: Some(username),
+        policy_name: metadata.remove("policyName").filter(|p| !p.is_empty()),
+        group_name: metadata.remove("groupName").filter(|g| !g.is_empty()),
+        length,
+    };
+
+    match state.upload_session_service.create(new_session).await {
+        Ok(session) => {
+            let mut response_headers = offset_headers(&session);
+            response_headers.push((header::LOCATION, format!("/api/v1alpha1/attachments/-/uploads/{}", session.id)));
+            tus_response(StatusCode::CREATED, response_headers)
+        }
+        Err(e) if e.to_string().starts_with(UPLOAD_LENGTH_EXCEEDED_ERROR) => tus_response(StatusCode::PAYLOAD_TOO_LARGE, vec![]),
+        Err(e) if e.to_string().starts_with(QUOTA_EXCEEDED_ERROR) => quota_exceeded(&e.to_string()),
+        Err(_) => tus_response(StatusCode::INTERNAL_SERVER_ERROR, vec![]),
+    }
+}
+
+/// 查询上传进度
+/// HEAD /api/v1alpha1/attachments/-/uploads/:id
+pub async fn head_upload(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(response) = check_tus_version(&headers) {
+        return response;
+    }
+    match fetch_my_session(&state, &id, &username).await {
+        Ok(session) => {
+            let mut response_headers = offset_headers(&session);
+            response_headers.push((header::CACHE_CONTROL, "no-store".to_string()));
+            tus_response(StatusCode::OK, response_headers)
+        }
+        Err(status) => tus_response(status, vec![]),
+    }
+}
+
+/// 获取上传会话（包含完成后生成的附件名称）
+/// GET /api/v1alpha1/attachments/-/uploads/:id
+pub async fn get_upload(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(id): Path<String>,
+) -> Response {
+    match fetch_my_session(&state, &id, &username).await {
+        Ok(session) => Json(session).into_response(),
+        Err(status) => tus_response(status, vec![]),
+    }
+}
+
+/// 从Upload-Offset开始写入分片
+/// PATCH /api/v1alpha1/attachments/-/uploads/:id
+pub async fn patch_upload(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Some(response) = check_tus_version(&headers) {
+        return response;
+    }
+    if header_str(&headers, &header::CONTENT_TYPE) != Some(OFFSET_OCTET_STREAM) {
+        return tus_response(StatusCode::UNSUPPORTED_MEDIA_TYPE, vec![]);
+    }
+    let Some(offset) = header_str(&headers, &UPLOAD_OFFSET).and_then(|v| v.parse::<u64>().ok()) else {
+        return tus_response(StatusCode::BAD_REQUEST, vec![]);
+    };
+    if let Err(status) = fetch_my_session(&state, &id, &username).await {
+        return tus_response(status, vec![]);
+    }
+
+    match state.upload_session_service.append(&id, offset, &body).await {
+        Ok(session) => tus_response(StatusCode::NO_CONTENT, offset_headers(&session)),
+        Err(e) if e.to_string().starts_with(UPLOAD_OFFSET_MISMATCH_ERROR) => tus_response(StatusCode::CONFLICT, vec![]),
+        Err(e) if e.to_string().starts_with(UPLOAD_LENGTH_EXCEEDED_ERROR) => tus_response(StatusCode::PAYLOAD_TOO_LARGE, vec![]),
+        Err(e) if e.to_string().starts_with(QUOTA_EXCEEDED_ERROR) => quota_exceeded(&e.to_string()),
+        Err(e) if e.is::<UploadValidationError>() => {
+            let mut response = upload_validation_response(&e);
+            response.headers_mut().insert(TUS_RESUMABLE, HeaderValue::from_static(TUS_VERSION));
+            response
+        }
+        Err(e) if e.to_string().starts_with(INFECTED_FILE_ERROR) => {
+            let mut response = tus_response(StatusCode::UNPROCESSABLE_ENTITY, vec![]);
+            *response.body_mut() = axum::body::Body::from(e.to_string());
+            response
+        }
+        Err(e) => {
+            tracing::error!("Failed to append upload {}: {}", id, e);
+            tus_response(StatusCode::INTERNAL_SERVER_ERROR, vec![])
+        }
+    }
+}
+
+/// 终止上传
+/// DELETE /api/v1alpha1/attachments/-/uploads/:id
+pub async fn delete_upload(
+    State(state): State<AppState>,
+    CurrentUser(username): CurrentUser,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(response) = check_tus_version(&headers) {
+        return response;
+    }
+    if let Err(status) = fetch_my_session(&state, &id, &username).await {
+        return tus_response(status, vec![]);
+    }
+    match state.upload_session_service.terminate(&id).await {
+        Ok(_) => tus_response(StatusCode::NO_CONTENT, vec![]),
+        Err(_) => tus_response(StatusCode::INTERNAL_SERVER_ERROR, vec![]),
+    }
+}
@@ -6,6 +6,7 @@ use axum::{
 };
 use flow_api::extension::ListOptions;
 use flow_domain::security::User;
+use flow_plugin::event::{Event, USER_REGISTERED};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use chrono::Utc;
@@ -19,6 +20,7 @@ pub struct CreateUserRequest {
     pub password: String,
     pub avatar: Option<String>,
     pub bio: Option<String>,
+    pub language: Option<String>,
 }
 
 /// 更新用户请求
@@ -29,6 +31,7 @@ pub struct UpdateUserRequest {
     pub avatar: Option<String>,
     pub bio: Option<String>,
     pub disabled: Option<bool>,
+    pub language: Option<String>,
 }
 
 /// 用户列表响应
@@ -81,12 +84,20 @@ pub async fn create_user(
             totp_encrypted_secret: None,
             disabled: Some(false),
             login_history_limit: Some(10),
+            attachment_quota: None,
+            language: request.language,
         },
         status: None,
     };
 
     match state.user_service.create(user).await {
-        Ok(user) => Ok(Json(user).into_response()),
+        Ok(user) => {
+            state.event_bus.publish(Event::new(USER_REGISTERED, serde_json::json!({
+                "username": user.metadata.name,
+                "displayName": user.spec.display_name,
+            })));
+            Ok(Json(user).into_response())
+        }
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
@@ -134,6 +145,9 @@ pub async fn update_user(
     if let Some(disabled) = request.disabled {
         user.spec.disabled = Some(disabled);
     }
+    if let Some(language) = request.language {
+        user.spec.language = Some(language).filter(|language| !language.is_empty());
+    }
 
     match state.user_service.update(user).await {
         Ok(user) => Ok(Json(user).into_response()),
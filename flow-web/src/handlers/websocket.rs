@@ -154,14 +154,15 @@ pub async fn handle_websocket(
     
     // 3. 认证和权限检查通过，升级WebSocket连接
     ws.on_upgrade(move |socket| async move {
-        handle_websocket_connection(socket, endpoint).await;
+        handle_websocket_connection(socket, endpoint, &user.username).await;
     })
 }
 
 /// 处理WebSocket连接
 async fn handle_websocket_connection(
     socket: WebSocket, 
-    endpoint: std::sync::Arc<dyn flow_infra::websocket::WebSocketEndpoint>
+    endpoint: std::sync::Arc<dyn flow_infra::websocket::WebSocketEndpoint>,
+    username: &str,
 ) {
     // 分离WebSocket的发送器和接收器
     let (sender, receiver) = socket.split();
@@ -170,8 +171,8 @@ async fn handle_websocket_connection(
     let ws_sender: Box<dyn WebSocketSender> = Box::new(AxumWebSocketSender { sender });
     let ws_receiver: Box<dyn WebSocketReceiver> = Box::new(AxumWebSocketReceiver { receiver });
     
-    // 调用endpoint的handle_connection方法
-    endpoint.handle_connection(ws_sender, ws_receiver).await;
+    // 调用endpoint的连接处理方法，传入已认证的用户
+    endpoint.handle_user_connection(username, ws_sender, ws_receiver).await;
 }
 
 
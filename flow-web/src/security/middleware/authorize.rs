@@ -12,16 +12,24 @@ pub async fn authorize_middleware(
     request: Request,
     next: Next,
 ) -> Response {
+    // 主题渲染的前台页面和主题静态资源对所有访问者公开
+    if is_public_page_request(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
     // 从请求扩展中获取用户信息
     let user = match request.extensions().get::<flow_api::security::AuthenticatedUser>() {
         Some(user) => user,
         None => {
             // 未认证用户，对于某些公开端点允许访问
-            // 这里简化处理，实际应该检查路径是否为公开端点
             let path = request.uri().path();
             if path == "/health" || path == "/api/v1alpha1/health" {
                 return next.run(request).await;
             }
+            // 前台数据、加密文章解锁、评论回应和附件访问端点允许匿名访问
+            if is_anonymous_endpoint(request.method(), path) {
+                return next.run(request).await;
+            }
             // 其他端点需要认证
             return Response::builder()
                 .status(StatusCode::UNAUTHORIZED)
@@ -60,3 +68,43 @@ pub async fn authorize_middleware(
     next.run(request).await
 }
 
+
+/// 检查是否为允许匿名访问的端点
+/// - POST /api/v1alpha1/posts/{name}/unlock
+/// - GET /api/v1alpha1/archives
+/// - GET /api/v1alpha1/page-tree
+/// - GET /api/v1alpha1/menus/-/primary
+/// - GET /api/v1alpha1/links/-/grouped
+/// - GET /api/v1alpha1/comments/-/public
+/// - GET /api/v1alpha1/comments/{name}/replies
+/// - GET /api/v1alpha1/attachments/{name}/content
+/// - GET /api/v1alpha1/attachments/{name}/image
+/// - GET /api/v1alpha1/attachments/shared/{token}
+/// - POST|DELETE /api/v1alpha1/comments/{name}/reactions/{reaction}
+///
+/// 附件内容和图片缩放端点由处理器检查，匿名访问只允许存储策略开启了公开访问的附件
+fn is_anonymous_endpoint(method: &axum::http::Method, path: &str) -> bool {
+    use axum::http::Method;
+    let segments: Vec<&str> = match path.strip_prefix("/api/v1alpha1/") {
+        Some(rest) => rest.split('/').collect(),
+        None => return false,
+    };
+    if segments.iter().any(|s| s.is_empty()) {
+        return false;
+    }
+    match segments.as_slice() {
+        ["posts", _, "unlock"] => method == Method::POST,
+        ["archives"] | ["page-tree"] | ["menus", "-", "primary"] | ["links", "-", "grouped"] => method == Method::GET,
+        ["comments", "-", "public"] | ["comments", _, "replies"] => method == Method::GET,
+        ["attachments", _, "content"] | ["attachments", _, "image"] | ["attachments", "shared", _] => method == Method::GET,
+        ["comments", _, "reactions", _] => method == Method::POST || method == Method::DELETE,
+        _ => false,
+    }
+}
+
+/// 检查是否为前台页面请求：主题页面、站点静态资源和独立页面路径的GET/HEAD请求
+fn is_public_page_request(method: &axum::http::Method, path: &str) -> bool {
+    use axum::http::Method;
+    (method == Method::GET || method == Method::HEAD)
+        && crate::handlers::is_public_site_path(path)
+}
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use flow_domain::attachment::ThumbnailFormat;
+use flow_infra::search::{CjkTokenizerConfig, ElasticsearchConfig};
+use flow_service::attachment::{ClamAvConfig, FfmpegConfig, RemoteImportConfig};
+use flow_service::notification::{NotificationRetentionConfig, NotificationThrottleConfig};
+use flow_service::plugin::{PluginInstallConfig, PluginProbeConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -16,6 +21,9 @@ pub struct ServerConfig {
     pub host: String,
     pub workers: Option<usize>,
     pub max_request_body_size: String,
+    /// 受信任的反向代理地址，只有来自这些地址的请求才采用X-Forwarded-For中的客户端地址
+    #[serde(default)]
+    pub trusted_proxies: Vec<std::net::IpAddr>,
 }
 
 impl Default for ServerConfig {
@@ -25,6 +33,7 @@ impl Default for ServerConfig {
             host: "0.0.0.0".to_string(),
             workers: None,
             max_request_body_size: "10MB".to_string(),
+            trusted_proxies: Vec::new(),
         }
     }
 }
@@ -82,6 +91,35 @@ pub struct FlowConfig {
     pub search: SearchConfig,
     pub plugin: PluginConfig,
     pub attachment: AttachmentConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub notification: NotificationConfig,
+}
+
+/// 通知配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// 站内通知保留策略，由后台任务定期清理
+    #[serde(default)]
+    pub retention: NotificationRetentionConfig,
+    /// 重复通知合并窗口
+    #[serde(default)]
+    pub throttle: NotificationThrottleConfig,
+}
+
+/// 主题配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// 开发模式：监听主题目录，模板和静态资源变化后自动清除模板缓存
+    #[serde(default)]
+    pub watch: bool,
+    /// 开发模式下theme.yaml或settings.yaml变化时重新加载主题
+    #[serde(default)]
+    pub reload_manifest: bool,
+    /// 是否允许从内网地址下载远程主题，仅用于开发测试
+    #[serde(default)]
+    pub allow_private_networks: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,14 +167,30 @@ impl Default for CacheConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchConfig {
+    /// 搜索引擎：tantivy（默认）、elasticsearch 或 opensearch
     pub engine: String,
     pub index_path: PathBuf,
+    /// Tantivy引擎的中日韩分词（日文、韩文词典）
+    #[serde(default)]
+    pub tokenizer: CjkTokenizerConfig,
+    /// engine 为 elasticsearch 或 opensearch 时的集群配置
+    #[serde(default)]
+    pub elasticsearch: Option<ElasticsearchConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
     pub runtime: String,
     pub plugins_dir: PathBuf,
+    /// 开发模式：构建产物或plugin.yaml变化后自动重新加载插件
+    #[serde(default)]
+    pub dev_mode: bool,
+    /// 远程安装插件（下载大小限制、插件市场、签名公钥）
+    #[serde(default)]
+    pub install: PluginInstallConfig,
+    /// 运行中插件的健康探测（间隔、超时、连续失败阈值）
+    #[serde(default)]
+    pub probe: PluginProbeConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,10 +199,35 @@ pub struct AttachmentConfig {
     pub storage_path: PathBuf,
     /// 缩略图质量（0.0-1.0）
     pub thumbnail_quality: f32,
+    /// 除原格式外额外生成的缩略图格式（webp、avif）
+    #[serde(default = "default_thumbnail_formats")]
+    pub thumbnail_formats: Vec<ThumbnailFormat>,
     /// 最大文件大小（字节）
     pub max_file_size: u64,
     /// 基础URL（用于生成permalink）
     pub base_url: Option<String>,
+    /// ClamAV病毒扫描配置，为空时不扫描上传的文件
+    #[serde(default)]
+    pub clamav: Option<ClamAvConfig>,
+    /// 是否将感染的文件隔离到附件目录的quarantine子目录（否则直接丢弃）
+    #[serde(default)]
+    pub quarantine_infected: bool,
+    /// ffmpeg配置，启用video-thumbnails特性且配置后为上传的视频生成封面缩略图
+    #[serde(default)]
+    pub ffmpeg: Option<FfmpegConfig>,
+    /// 共享链接的签名密钥，为空时使用jwt_secret
+    #[serde(default)]
+    pub shared_url_secret: Option<String>,
+    /// 从外部地址导入附件的配置（大小、媒体类型限制等）
+    #[serde(default)]
+    pub remote_import: RemoteImportConfig,
+    /// pdftotext可执行文件路径，配置后提取PDF附件中的文本用于搜索
+    #[serde(default)]
+    pub pdftotext: Option<String>,
+}
+
+fn default_thumbnail_formats() -> Vec<ThumbnailFormat> {
+    vec![ThumbnailFormat::Webp]
 }
 
 impl Default for AttachmentConfig {
@@ -156,8 +235,15 @@ impl Default for AttachmentConfig {
         Self {
             storage_path: PathBuf::from("attachments"),
             thumbnail_quality: 0.85,
+            thumbnail_formats: default_thumbnail_formats(),
             max_file_size: 100 * 1024 * 1024, // 100MB
             base_url: None,
+            clamav: None,
+            quarantine_infected: false,
+            ffmpeg: None,
+            shared_url_secret: None,
+            remote_import: RemoteImportConfig::default(),
+            pdftotext: None,
         }
     }
 }
@@ -184,12 +270,19 @@ impl Default for Config {
                 search: SearchConfig {
                     engine: "tantivy".to_string(),
                     index_path: work_dir.join("indices"),
+                    tokenizer: CjkTokenizerConfig::default(),
+                    elasticsearch: None,
                 },
                 plugin: PluginConfig {
                     runtime: "ffi".to_string(),
                     plugins_dir: work_dir.join("plugins"),
+                    dev_mode: false,
+                    install: PluginInstallConfig::default(),
+                    probe: PluginProbeConfig::default(),
                 },
                 attachment: AttachmentConfig::default(),
+                theme: ThemeConfig::default(),
+                notification: NotificationConfig::default(),
             },
         }
     }
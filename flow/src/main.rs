@@ -102,7 +102,7 @@ async fn main() -> Result<()> {
     let listener = TcpListener::bind(&addr).await
         .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
     
-    serve(listener, app.into_make_service())
+    serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await
         .map_err(|e| format!("Server error: {}", e))?;
 
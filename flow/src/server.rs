@@ -11,24 +11,35 @@ use flow_service::security::{
     AuthService, RoleService, UserService, PasswordService, DefaultPasswordService,
 };
 use flow_service::content::{
-    PostService, DefaultPostService, SearchIndexingPostService,
-    SinglePageService, DefaultSinglePageService, SearchIndexingSinglePageService,
+    PostService, DefaultPostService, SearchIndexingPostService, ReferenceTrackingPostService, TagCountingPostService,
+    SinglePageService, DefaultSinglePageService, SearchIndexingSinglePageService, ReferenceTrackingSinglePageService,
     CommentService, DefaultCommentService,
     CategoryService, DefaultCategoryService,
     TagService, DefaultTagService,
+    SeriesService, DefaultSeriesService,
+};
+use flow_service::theme::{ThemeService, DefaultThemeService, RemoteThemeFetcher, ThemeConfigService, DefaultThemeConfigService, RobotsService, DefaultRobotsService, MenuFinder, ArchiveFinder, SiteStatsFinder, TagCloudFinder, TemplateContextRegistry, TemplateContextContributor, SiteContextContributor, CurrentUserContextContributor, MenuContextContributor, RequestContextContributor};
+use flow_api::theme::{Finder, FinderRegistry};
+use flow_service::plugin::{PluginConfigService, DefaultPluginConfigService, PluginCapabilityService, DefaultPluginCapabilityService, PluginInstallService, DefaultPluginInstallService, PluginAssetService, DefaultPluginAssetService, ConfigMapMigrationStore, PluginHealthService, DefaultPluginHealthService, PluginLifecycleService, DefaultPluginLifecycleService, ClientExtensionHost};
+use flow_plugin::{PluginManager, DefaultPluginManager, DirectoryPluginLoader, WasmPluginLoader, EventBus, ExtensionHost};
+use flow_api::extension::scheme::{DefaultSchemeManager, SchemeManager, SharedSchemeManager};
+use flow_service::notification::{
+    NotificationService, NotificationCenter, DefaultNotificationService, DefaultNotificationRetentionService, DefaultSubscriptionListener, DefaultNotificationCenter,
+    NotifierRegistry, NotificationPreferenceService, DefaultNotificationPreferenceService,
+    TelegramNotifier, SlackNotifier, DiscordNotifier, TELEGRAM_NOTIFIER, SLACK_NOTIFIER, DISCORD_NOTIFIER,
+    NotifierDescriptorService, DefaultNotifierDescriptorService, ReasonTypeService, DefaultReasonTypeService, NotificationThrottle, NotificationDigestService, DefaultNotificationDigestService,
+    NotificationBroadcaster, NotificationPushEndpoint,
 };
-use flow_service::theme::{ThemeService, DefaultThemeService};
-use flow_service::notification::{NotificationService, NotificationCenter, DefaultNotificationService, DefaultNotificationCenter, NotificationSender};
 use async_trait::async_trait;
 use flow_infra::{
     database::DatabaseManager,
     security::{JwtService, SessionService, RateLimiter},
     extension::ReactiveExtensionClient,
-    search::TantivySearchEngine,
+    search::{CjkTokenizer, ElasticsearchSearchEngine, TantivySearchEngine},
     index::{IndicesManager, FulltextFieldMapping},
 };
 use flow_api::search::SearchEngine;
-use flow_service::search::{SearchService, DefaultSearchService};
+use flow_service::search::{SearchService, DefaultSearchService, SearchEngineRegistry};
 use flow_web::AppState;
 use std::sync::Arc;
 use tower::ServiceBuilder;
@@ -63,54 +74,155 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/v1alpha1/posts/:name/release-content", get(flow_web::get_post_release_content))
         .route("/api/v1alpha1/posts/:name/content", get(flow_web::get_post_content).delete(flow_web::delete_post_content))
         .route("/api/v1alpha1/posts/:name/revert-content", axum::routing::put(flow_web::revert_post_to_snapshot))
+        .route("/api/v1alpha1/posts/:name/password", axum::routing::put(flow_web::set_post_password))
+        .route("/api/v1alpha1/posts/:name/contributors", axum::routing::put(flow_web::set_post_contributors))
+        .route("/api/v1alpha1/archives", get(flow_web::list_archives))
+        .route("/api/v1alpha1/content-formats", get(flow_web::list_content_formats))
+        .route("/api/v1alpha1/posts/:name/unlock", post(flow_web::unlock_post))
         // SinglePage管理路由
         .route("/api/v1alpha1/singlepages", get(flow_web::list_single_pages).post(flow_web::create_single_page))
+        .route("/api/v1alpha1/singlepages/-/tree", get(flow_web::get_single_page_tree))
+        .route("/api/v1alpha1/page-tree", get(flow_web::get_published_page_tree))
         .route("/api/v1alpha1/singlepages/:name", get(flow_web::get_single_page).put(flow_web::update_single_page).delete(flow_web::delete_single_page))
         .route("/api/v1alpha1/singlepages/:name/publish", axum::routing::put(flow_web::publish_single_page))
         .route("/api/v1alpha1/singlepages/:name/unpublish", axum::routing::put(flow_web::unpublish_single_page))
         // Comment管理路由
         .route("/api/v1alpha1/comments", get(flow_web::list_comments).post(flow_web::create_comment))
+        .route("/api/v1alpha1/comments/-/public", get(flow_web::list_public_comments))
+        .route("/api/v1alpha1/comments/:name/replies", get(flow_web::list_public_replies))
         .route("/api/v1alpha1/comments/:name", get(flow_web::get_comment).put(flow_web::update_comment).delete(flow_web::delete_comment))
         .route("/api/v1alpha1/comments/:name/approve", axum::routing::put(flow_web::approve_comment))
+        .route("/api/v1alpha1/comments/:name/reactions/:reaction", post(flow_web::react_to_comment).delete(flow_web::remove_comment_reaction))
+        .route("/api/v1alpha1/spam-comments", get(flow_web::list_spam_comments))
+        .route("/api/v1alpha1/spam-comments/review", post(flow_web::review_spam_comments))
         // Category管理路由
         .route("/api/v1alpha1/categories", get(flow_web::list_categories).post(flow_web::create_category))
         .route("/api/v1alpha1/categories/:name", get(flow_web::get_category).put(flow_web::update_category).delete(flow_web::delete_category))
         // Tag管理路由
         .route("/api/v1alpha1/tags", get(flow_web::list_tags).post(flow_web::create_tag))
         .route("/api/v1alpha1/tags/:name", get(flow_web::get_tag).put(flow_web::update_tag).delete(flow_web::delete_tag))
+        .route("/api/v1alpha1/menus", get(flow_web::list_menus).post(flow_web::create_menu))
+        .route("/api/v1alpha1/menus/-/primary", get(flow_web::get_primary_menu))
+        .route("/api/v1alpha1/menus/:name", get(flow_web::get_menu).put(flow_web::update_menu).delete(flow_web::delete_menu))
+        .route("/api/v1alpha1/menus/:name/tree", get(flow_web::get_menu_tree))
+        .route("/api/v1alpha1/menuitems", get(flow_web::list_menu_items).post(flow_web::create_menu_item))
+        .route("/api/v1alpha1/menuitems/:name", get(flow_web::get_menu_item).put(flow_web::update_menu_item).delete(flow_web::delete_menu_item))
+        .route("/api/v1alpha1/links", get(flow_web::list_links).post(flow_web::create_link))
+        .route("/api/v1alpha1/links/-/grouped", get(flow_web::list_grouped_links))
+        .route("/api/v1alpha1/links/:name", get(flow_web::get_link).put(flow_web::update_link).delete(flow_web::delete_link))
+        .route("/api/v1alpha1/linkgroups", get(flow_web::list_link_groups).post(flow_web::create_link_group))
+        .route("/api/v1alpha1/photos", get(flow_web::list_photos).post(flow_web::create_photo))
+        .route("/api/v1alpha1/photos/-/grouped", get(flow_web::list_grouped_photos))
+        .route("/api/v1alpha1/photos/:name", get(flow_web::get_photo).put(flow_web::update_photo).delete(flow_web::delete_photo))
+        .route("/api/v1alpha1/photogroups", get(flow_web::list_photo_groups).post(flow_web::create_photo_group))
+        .route("/api/v1alpha1/photogroups/:name", get(flow_web::get_photo_group).put(flow_web::update_photo_group).delete(flow_web::delete_photo_group))
+        .route("/api/v1alpha1/photogroups/:name/photos", get(flow_web::list_group_photos).put(flow_web::reorder_group_photos))
+        .route("/api/v1alpha1/moments", get(flow_web::list_moments))
+        .route("/api/v1alpha1/moments/:name", get(flow_web::get_moment).delete(flow_web::delete_moment))
+        .route("/api/v1alpha1/linkgroups/:name", get(flow_web::get_link_group).put(flow_web::update_link_group).delete(flow_web::delete_link_group))
+        // Series管理路由
+        .route("/api/v1alpha1/series", get(flow_web::list_series).post(flow_web::create_series))
+        .route("/api/v1alpha1/series/:name", get(flow_web::get_series).put(flow_web::update_series).delete(flow_web::delete_series))
+        // 重定向管理路由
+        .route("/api/v1alpha1/redirects", get(flow_web::list_slug_redirects))
+        .route("/api/v1alpha1/redirects/prune", post(flow_web::prune_slug_redirects))
+        .route("/api/v1alpha1/redirects/:name", axum::routing::delete(flow_web::delete_slug_redirect))
+        .route("/api/v1alpha1/link-check", get(flow_web::get_link_check_report).post(flow_web::run_link_check))
         // 搜索路由
         .route("/api/v1alpha1/search", get(flow_web::search))
+        .route("/api/v1alpha1/search/engines", get(flow_web::list_search_engines))
+        .route("/api/v1alpha1/search/suggest", get(flow_web::search_suggest))
         // 主题管理路由
         .route("/api/v1alpha1/themes", get(flow_web::list_themes))
         .route("/api/v1alpha1/themes", axum::routing::post(flow_web::install_theme))
-        .route("/api/v1alpha1/themes/:name", get(flow_web::get_theme))
+        .route("/api/v1alpha1/themes/:name", get(flow_web::get_theme).delete(flow_web::uninstall_theme))
         .route("/api/v1alpha1/themes/:name/activate", axum::routing::put(flow_web::activate_theme))
         .route("/api/v1alpha1/themes/:name/reload", axum::routing::post(flow_web::reload_theme))
         .route("/api/v1alpha1/themes/:name/upgrade", axum::routing::post(flow_web::upgrade_theme))
+        .route("/api/v1alpha1/themes/:name/preview", axum::routing::post(flow_web::preview_theme))
+        .route("/api/v1alpha1/themes/:name/config", get(flow_web::get_theme_config).put(flow_web::update_theme_config))
+        .route("/api/v1alpha1/themes/:name/config/export", get(flow_web::export_theme_config))
+        .route("/api/v1alpha1/themes/:name/config/import", axum::routing::post(flow_web::import_theme_config))
+        .route("/api/v1alpha1/themes/:name/templates/:kind", get(flow_web::list_custom_templates))
+        // 插件设置路由
+        .route("/api/v1alpha1/plugins", get(flow_web::list_plugins).post(flow_web::create_plugin))
+        .route("/api/v1alpha1/plugins/-/install", post(flow_web::install_plugin))
+        .route("/api/v1alpha1/plugins/:name", get(flow_web::get_plugin).put(flow_web::update_plugin).delete(flow_web::delete_plugin))
+        .route("/api/v1alpha1/plugins/:name/enable", axum::routing::put(flow_web::enable_plugin))
+        .route("/api/v1alpha1/plugins/:name/disable", axum::routing::put(flow_web::disable_plugin))
+        .route("/api/v1alpha1/plugins/:name/reload", axum::routing::put(flow_web::reload_plugin))
+        .route("/api/v1alpha1/plugins/:name/stats", get(flow_web::get_plugin_stats))
+        .route("/api/v1alpha1/plugins/:name/logs", get(flow_web::get_plugin_logs))
+        .route("/api/v1alpha1/plugins/-/metrics", get(flow_web::plugin_metrics))
+        .route("/api/v1alpha1/plugins/:name/setting", get(flow_web::get_plugin_setting))
+        .route("/api/v1alpha1/plugins/:name/config", get(flow_web::get_plugin_config).put(flow_web::update_plugin_config))
+        .route("/api/v1alpha1/plugins/:name/capabilities", get(flow_web::get_plugin_capabilities).put(flow_web::approve_plugin_capabilities))
+        // 插件前端资源（控制台扩展的入口脚本、样式表和Logo）
+        .route("/plugins/:name/assets/*path", get(flow_web::get_plugin_asset))
         // 主题静态资源路由
         .route("/themes/*path", get(flow_web::serve_theme_static))
+        .route("/robots.txt", get(flow_web::robots_txt))
+        .route("/api/v1alpha1/robots", get(flow_web::get_robots_setting).put(flow_web::update_robots_setting))
+        // 主题渲染的前台页面，独立页面由fallback按页面路径匹配
+        .route("/", get(flow_web::index_page))
+        .route("/page/:page", get(flow_web::index_page_n))
+        .route("/archives", get(flow_web::archive_page))
+        .route("/archives/page/:page", get(flow_web::archive_page_n))
+        .route("/archives/:slug", get(flow_web::post_page))
+        .route("/categories/:slug", get(flow_web::category_page))
+        .route("/categories/:slug/page/:page", get(flow_web::category_page))
+        .route("/tags/:slug", get(flow_web::tag_page))
+        .route("/tags/:slug/page/:page", get(flow_web::tag_page))
+        .route("/authors/:name", get(flow_web::author_page))
+        .route("/authors/:name/page/:page", get(flow_web::author_page))
+        .route("/series/:slug", get(flow_web::series_page))
         // 附件管理路由
-        .route("/api/v1alpha1/attachments", get(flow_web::list_attachments).post(flow_web::upload_attachment))
+        // 上传的文件边接收边写入临时文件，大小由附件服务按配置限制
+        .route("/api/v1alpha1/attachments", get(flow_web::list_attachments)
+            .post(flow_web::upload_attachment).layer(axum::extract::DefaultBodyLimit::disable()))
         .route("/api/v1alpha1/attachments/:name", get(flow_web::get_attachment).put(flow_web::update_attachment).delete(flow_web::delete_attachment))
         .route("/api/v1alpha1/attachments/:name/thumbnails/:size", get(flow_web::get_thumbnail))
+        .route("/api/v1alpha1/attachments/:name/signed-url", get(flow_web::get_signed_url))
+        .route("/api/v1alpha1/attachments/:name/content", get(flow_web::get_attachment_content))
+        .route("/api/v1alpha1/attachments/:name/image", get(flow_web::get_transformed_image))
+        .route("/api/v1alpha1/attachments/:name/references", get(flow_web::get_attachment_references))
+        .route("/api/v1alpha1/attachments/-/migrations", post(flow_web::migrate_attachments))
+        .route("/api/v1alpha1/attachments/-/archive", post(flow_web::archive_attachments))
+        .route("/api/v1alpha1/attachments/-/batch/delete", post(flow_web::batch_delete_attachments))
+        .route("/api/v1alpha1/attachments/-/batch/group", post(flow_web::batch_move_attachments))
+        .route("/api/v1alpha1/attachments/-/batch/tags", post(flow_web::batch_retag_attachments))
+        .route("/api/v1alpha1/attachments/-/batch/owner", post(flow_web::batch_change_attachment_owner))
+        .route("/api/v1alpha1/attachments/-/import", post(flow_web::import_attachment))
+        .route("/api/v1alpha1/attachments/-/stats", get(flow_web::get_storage_stats))
+        .route("/api/v1alpha1/attachments/-/thumbnails/regeneration", get(flow_web::get_thumbnail_regeneration).post(flow_web::regenerate_thumbnails))
+        // 分片上传路由（tus协议）
+        .route("/api/v1alpha1/attachments/-/uploads", post(flow_web::create_upload).options(flow_web::tus_options))
+        .route("/api/v1alpha1/attachments/-/uploads/:id", get(flow_web::get_upload).head(flow_web::head_upload).delete(flow_web::delete_upload)
+            .patch(flow_web::patch_upload).layer(axum::extract::DefaultBodyLimit::max(flow_web::MAX_UPLOAD_CHUNK_SIZE)))
         // 共享URL路由
         .route("/api/v1alpha1/attachments/:name/shared-urls", get(flow_web::list_shared_urls).post(flow_web::generate_shared_url))
         .route("/api/v1alpha1/attachments/shared-urls/:token", axum::routing::delete(flow_web::revoke_shared_url))
         .route("/api/v1alpha1/attachments/shared/:token", get(flow_web::get_attachment_by_shared_url))
         // 通知管理路由
-        .route("/api/v1alpha1/notifications", get(flow_web::list_notifications).post(flow_web::create_notification))
+        .route("/api/v1alpha1/notifications", get(flow_web::list_notifications).post(flow_web::create_notification).delete(flow_web::delete_notifications))
         .route("/api/v1alpha1/notifications/:name", get(flow_web::get_notification).put(flow_web::update_notification).delete(flow_web::delete_notification))
         .route("/api/v1alpha1/notifications/:name/read", axum::routing::put(flow_web::mark_notification_as_read))
         .route("/api/v1alpha1/notifications/read-all", axum::routing::put(flow_web::mark_all_notifications_as_read))
         .route("/api/v1alpha1/notifications/:recipient/unread-count", get(flow_web::get_unread_count))
+        .route("/api/v1alpha1/notifiers", get(flow_web::list_notifiers))
+        .route("/api/v1alpha1/notifier-descriptors", get(flow_web::list_notifier_descriptors))
+        .route("/api/v1alpha1/notifiers/:name/sender-config", get(flow_web::get_notifier_sender_config).put(flow_web::update_notifier_sender_config))
         // 订阅管理路由
         .route("/api/v1alpha1/subscriptions", get(flow_web::list_subscriptions).post(flow_web::create_subscription))
         .route("/api/v1alpha1/subscriptions/:name", get(flow_web::get_subscription).delete(flow_web::delete_subscription))
         .route("/api/v1alpha1/subscriptions/:name/unsubscribe", get(flow_web::unsubscribe_by_token))
         // 原因管理路由
+        .route("/api/v1alpha1/reason-types", get(flow_web::list_reason_types))
+        .route("/api/v1alpha1/reason-types/:name", get(flow_web::get_reason_type))
         .route("/api/v1alpha1/reasons", get(flow_web::list_reasons).post(flow_web::create_reason))
         .route("/api/v1alpha1/reasons/:name", get(flow_web::get_reason).delete(flow_web::delete_reason))
         // Policy管理路由
+        .route("/api/v1alpha1/policytemplates", get(flow_web::list_policy_templates))
         .route("/api/v1alpha1/policies", get(flow_web::list_policies).post(flow_web::create_policy))
         .route("/api/v1alpha1/policies/:name", get(flow_web::get_policy).put(flow_web::update_policy).delete(flow_web::delete_policy))
         // Group管理路由
@@ -124,6 +236,7 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/v1alpha1/backups/:name", axum::routing::delete(flow_web::delete_backup))
         .route("/api/v1alpha1/backups/restore", axum::routing::post(flow_web::restore_backup))
         .route("/api/v1alpha1/groups/:name/update-count", axum::routing::post(flow_web::update_group_count))
+        .route("/api/v1alpha1/groups/:name/download", get(flow_web::download_group))
         // OAuth2路由
         .route("/oauth2/authorize/:registration_id", get(flow_web::oauth2_authorize))
         .route("/oauth2/callback/:registration_id", get(flow_web::oauth2_callback))
@@ -135,6 +248,7 @@ pub fn create_router(state: AppState) -> Router {
         .nest("/apis", extension_routes())
         // SwaggerUI文档 - 暂时注释掉，需要修复 utoipa-swagger-ui 9.0 的集成
         // .merge(SwaggerUi::new("/swagger-ui/*"))
+        .fallback(flow_web::single_page_page)
         .layer(
             ServiceBuilder::new()
                 // 注意：在Axum/Tower中，中间件的执行顺序与添加顺序相反
@@ -182,7 +296,11 @@ fn uc_routes() -> Router<AppState> {
         .route("/posts/:name/publish", axum::routing::put(flow_web::publish_my_post))
         .route("/posts/:name/unpublish", axum::routing::put(flow_web::unpublish_my_post))
         .route("/posts/:name/recycle", axum::routing::delete(flow_web::recycle_my_post))
+        .route("/attachments/-/usage", get(flow_web::get_my_storage_usage))
+        .route("/moments", get(flow_web::list_my_moments).post(flow_web::create_my_moment))
+        .route("/moments/:name", axum::routing::put(flow_web::update_my_moment).delete(flow_web::delete_my_moment))
         .route("/posts/:name/draft", get(flow_web::get_my_post_draft).put(flow_web::update_my_post_draft))
+        .route("/posts/:name/contributors", axum::routing::put(flow_web::set_my_post_contributors))
         // 2FA路由
         .route("/authentications/two-factor/settings", get(flow_web::get_two_factor_settings))
         .route("/authentications/two-factor/settings/enabled", axum::routing::put(flow_web::enable_two_factor))
@@ -190,6 +308,9 @@ fn uc_routes() -> Router<AppState> {
         .route("/authentications/two-factor/totp", axum::routing::post(flow_web::configure_totp))
         .route("/authentications/two-factor/totp/-", axum::routing::delete(flow_web::delete_totp))
         .route("/authentications/two-factor/totp/auth-link", get(flow_web::get_totp_auth_link))
+        // 通知偏好
+        .route("/notification-preferences", get(flow_web::get_my_notification_preference).put(flow_web::update_my_notification_preference))
+        .route("/notifiers/:name/receiver-config", get(flow_web::get_my_notifier_receiver_config).put(flow_web::update_my_notifier_receiver_config))
 }
 
 /// Extension路由（动态路径）
@@ -283,64 +404,75 @@ pub async fn init_app_state(
         flow_service::security::DefaultAuthorizationManager::new(role_service.clone())
     );
     
-    // 创建基础Post服务
-    let base_post_service: Arc<dyn PostService> = Arc::new(
-        DefaultPostService::new(extension_client.clone())
-    );
-
-    // 创建基础SinglePage服务
-    let base_single_page_service: Arc<dyn SinglePageService> = Arc::new(
-        DefaultSinglePageService::new(extension_client.clone())
-    );
-
-    // 创建Comment服务
-    let comment_service: Arc<dyn CommentService> = Arc::new(
-        DefaultCommentService::new(extension_client.clone())
-    );
-
-    // 创建Category服务
-    let category_service: Arc<dyn CategoryService> = Arc::new(
-        DefaultCategoryService::new(extension_client.clone())
-    );
-
-    // 创建Tag服务
-    let tag_service: Arc<dyn TagService> = Arc::new(
-        DefaultTagService::new(extension_client.clone())
+    // 创建Slug重定向服务
+    use flow_service::content::{SlugRedirectService, DefaultSlugRedirectService};
+    let slug_redirect_service: Arc<dyn SlugRedirectService> = Arc::new(
+        DefaultSlugRedirectService::new(extension_client.clone())
     );
 
-    // 创建Snapshot服务
-    use flow_service::content::{SnapshotService, DefaultSnapshotService};
-    let snapshot_service: Arc<dyn SnapshotService> = Arc::new(
-        DefaultSnapshotService::new(extension_client.clone())
+    // 创建通知服务
+    // 通知创建和已读状态变化通过WebSocket实时推送给接收者
+    let notification_broadcaster = Arc::new(NotificationBroadcaster::new());
+    let notification_service: Arc<dyn NotificationService> = Arc::new(
+        DefaultNotificationService::new(extension_client.clone())
+            .with_broadcaster(notification_broadcaster.clone())
     );
-
-    // 初始化搜索服务
-    let index_path = &config.flow.search.index_path;
-    let search_engine: Arc<dyn SearchEngine> = Arc::new(
-        TantivySearchEngine::new(index_path).await
-            .map_err(|e| format!("Failed to initialize search engine: {}", e))?
+    // 按保留策略定期删除过期的已读通知和超出数量上限的通知
+    let retention = config.flow.notification.retention.clone();
+    let retention_interval = std::time::Duration::from_secs(retention.interval.max(60));
+    flow_service::notification::spawn_notification_cleanup_job(
+        Arc::new(DefaultNotificationRetentionService::new(notification_service.clone(), retention)),
+        retention_interval,
     );
-    let search_service: Arc<dyn SearchService> = Arc::new(
-        DefaultSearchService::new(search_engine.clone())
+    
+    // 站内通知由通知中心直接创建，内置Telegram、Slack和Discord通知器，插件可注册其他通知器（短信、钉钉等），用户在通知偏好中按原因类型选择
+    let notifier_registry = Arc::new(NotifierRegistry::new());
+    notifier_registry.register(TELEGRAM_NOTIFIER, Arc::new(TelegramNotifier::new()));
+    notifier_registry.register(SLACK_NOTIFIER, Arc::new(SlackNotifier::new()));
+    notifier_registry.register(DISCORD_NOTIFIER, Arc::new(DiscordNotifier::new()));
+    // 通知渠道描述通知器的发送者和接收者设置，内置渠道在启动时保存
+    let notifier_descriptor_service = Arc::new(DefaultNotifierDescriptorService::new(extension_client.clone()));
+    if let Err(e) = notifier_descriptor_service.install_builtin().await {
+        tracing::warn!("Failed to install builtin notifier descriptors: {}", e);
+    }
+    let notifier_descriptor_service: Arc<dyn NotifierDescriptorService> = notifier_descriptor_service;
+    let notification_preference_service: Arc<dyn NotificationPreferenceService> = Arc::new(
+        DefaultNotificationPreferenceService::new(extension_client.clone(), notifier_registry.clone())
+            .with_descriptors(notifier_descriptor_service.clone())
     );
-
-    // 初始化索引引擎
-    let indices_manager = Arc::new(IndicesManager::new());
-    let fulltext_mapping = Arc::new(FulltextFieldMapping::default());
-    let _index_engine = flow_infra::index::engine::DefaultIndexEngine::with_search_engine(
-        indices_manager,
-        Some(search_engine),
-        fulltext_mapping,
+    
+    // 选择按小时或按天接收的用户，站外通知合并为摘要由后台任务定期发送
+    let notification_digest_service: Arc<dyn NotificationDigestService> = Arc::new(
+        DefaultNotificationDigestService::new(
+            extension_client.clone(),
+            notification_preference_service.clone(),
+            notifier_registry.clone(),
+        ).with_descriptors(notifier_descriptor_service.clone())
     );
-
-    // 创建带搜索索引的Post服务（包装基础服务）
-    let post_service: Arc<dyn PostService> = Arc::new(
-        SearchIndexingPostService::new(base_post_service.clone(), search_service.clone())
+    flow_service::notification::spawn_digest_job(
+        notification_digest_service.clone(),
+        flow_service::notification::DEFAULT_DIGEST_CHECK_INTERVAL,
     );
-
-    // 创建带搜索索引的SinglePage服务（包装基础服务）
-    let single_page_service: Arc<dyn SinglePageService> = Arc::new(
-        SearchIndexingSinglePageService::new(base_single_page_service.clone(), search_service.clone())
+    
+    // 原因类型声明每种通知原因的属性，通知中心发送前据此校验，内置原因类型在启动时保存
+    let reason_type_service = Arc::new(DefaultReasonTypeService::new(extension_client.clone()));
+    if let Err(e) = reason_type_service.install_builtin().await {
+        tracing::warn!("Failed to install builtin reason types: {}", e);
+    }
+    let reason_type_service: Arc<dyn ReasonTypeService> = reason_type_service;
+    
+    // 创建通知中心
+    let notification_center: Arc<dyn NotificationCenter> = Arc::new(
+        DefaultNotificationCenter::new(
+            extension_client.clone(),
+            notification_service.clone(),
+            notifier_registry.clone(),
+        ).with_preferences(notification_preference_service.clone())
+            .with_descriptors(notifier_descriptor_service.clone())
+            .with_digests(notification_digest_service)
+            .with_external_url(config.flow.external_url.clone())
+            .with_reason_types(reason_type_service.clone())
+            .with_throttle(Arc::new(NotificationThrottle::new(cache.clone(), config.flow.notification.throttle.clone())))
     );
 
     // 初始化附件服务
@@ -349,6 +481,18 @@ pub async fn init_app_state(
         PolicyService, DefaultPolicyService,
         GroupService, DefaultGroupService,
         SharedUrlService, DefaultSharedUrlService,
+        PolicyStorageResolver, PluginStorageCleanup,
+        UploadSessionService, DefaultUploadSessionService,
+        ImageTransformService, DefaultImageTransformService,
+        QuotaService, DefaultQuotaService,
+        ClamAvScanner, InfectedFileAction,
+        AttachmentMigrationService, DefaultAttachmentMigrationService,
+        AttachmentArchiveService, DefaultAttachmentArchiveService,
+        AttachmentReferenceService, DefaultAttachmentReferenceService,
+        RemoteImportService, DefaultRemoteImportService,
+        ThumbnailJobService, DefaultThumbnailJobService,
+        SearchIndexingAttachmentService, PdftotextExtractor,
+        AttachmentBatchService, DefaultAttachmentBatchService,
     };
     use flow_service::attachment::thumbnail::{ThumbnailService, DefaultThumbnailService};
     use flow_infra::attachment::{AttachmentStorage, LocalAttachmentStorage};
@@ -375,22 +519,298 @@ pub async fn init_app_state(
         LocalAttachmentStorage::new(attachment_root.clone())
     );
     
+    // 创建存储策略解析器（按附件策略选择本地存储或对象存储）
+    let storage_resolver = Arc::new(
+        PolicyStorageResolver::new(extension_client.clone(), storage.clone())
+    );
+    
     // 创建缩略图服务
     let thumbnail_service: Arc<dyn ThumbnailService> = Arc::new(
         DefaultThumbnailService::new(thumbnail_dir, attachment_config.thumbnail_quality)
+            .with_formats(attachment_config.thumbnail_formats.clone())
+    );
+    
+    // 创建存储配额服务（用户和分组的配额与用量）
+    let quota_service: Arc<dyn QuotaService> = Arc::new(
+        DefaultQuotaService::new(extension_client.clone())
     );
     
     // 创建附件服务
-    let attachment_service: Arc<dyn AttachmentService> = Arc::new(
-        DefaultAttachmentService::new(
+    let attachment_service = DefaultAttachmentService::new(
+        extension_client.clone(),
+        storage,
+        thumbnail_service,
+        upload_path,
+        base_url,
+    ).with_storage_resolver(storage_resolver.clone())
+        .with_max_file_size(attachment_config.max_file_size)
+        .with_quota_service(quota_service.clone())
+        .with_notification_center(notification_center.clone());
+    
+    // 配置了ClamAV时上传前扫描文件
+    let attachment_service = match &attachment_config.clamav {
+        Some(clamav) => {
+            let action = if attachment_config.quarantine_infected {
+                InfectedFileAction::Quarantine(attachment_root.join("quarantine"))
+            } else {
+                InfectedFileAction::Reject
+            };
+            attachment_service.with_upload_scanner(Arc::new(ClamAvScanner::new(clamav.clone())), action)
+        }
+        None => attachment_service,
+    };
+    
+    // 配置了ffmpeg时为上传的视频生成封面缩略图
+    #[cfg(feature = "video-thumbnails")]
+    let attachment_service = match &attachment_config.ffmpeg {
+        Some(ffmpeg) => attachment_service.with_video_processor(Arc::new(
+            flow_service::attachment::FfmpegVideoProcessor::new(ffmpeg.clone())
+        )),
+        None => attachment_service,
+    };
+    #[cfg(not(feature = "video-thumbnails"))]
+    if attachment_config.ffmpeg.is_some() {
+        tracing::warn!("ffmpeg is configured but video thumbnails are disabled; rebuild with the video-thumbnails feature");
+    }
+    let attachment_service: Arc<dyn AttachmentService> = Arc::new(attachment_service);
+    
+    // 初始化搜索服务
+    let search_config = &config.flow.search;
+    let search_engine: Arc<dyn SearchEngine> = match search_config.engine.as_str() {
+        "elasticsearch" | "opensearch" => {
+            let es_config = search_config.elasticsearch.clone()
+                .ok_or_else(|| format!("Search engine {} requires flow.search.elasticsearch", search_config.engine))?;
+            Arc::new(
+                ElasticsearchSearchEngine::new(es_config).await
+                    .map_err(|e| format!("Failed to initialize search engine: {}", e))?
+            )
+        }
+        _ => {
+            let tokenizer = CjkTokenizer::from_config(&search_config.tokenizer)
+                .map_err(|e| format!("Failed to initialize search tokenizer: {}", e))?;
+            Arc::new(
+                TantivySearchEngine::with_tokenizer(&search_config.index_path, tokenizer).await
+                    .map_err(|e| format!("Failed to initialize search engine: {}", e))?
+            )
+        }
+    };
+    // 插件可以通过注册表注册搜索引擎接管或补充内置的Tantivy引擎
+    let search_engine_registry = Arc::new(SearchEngineRegistry::new(search_engine.clone()));
+    let search_service: Arc<dyn SearchService> = Arc::new(
+        DefaultSearchService::with_registry(search_engine_registry.clone())
+    );
+
+    // 附件上传/更新/删除时更新搜索索引，配置了pdftotext时同时索引PDF正文
+    let attachment_service = SearchIndexingAttachmentService::new(attachment_service, search_service.clone());
+    let attachment_service = match &attachment_config.pdftotext {
+        Some(pdftotext) => attachment_service.with_text_extractor(Arc::new(PdftotextExtractor::new(pdftotext.clone()))),
+        None => attachment_service,
+    };
+    let attachment_service: Arc<dyn AttachmentService> = Arc::new(attachment_service);
+    
+    // 创建分片上传会话服务（tus协议，临时数据保存在附件目录的tus子目录）
+    let upload_session_service: Arc<dyn UploadSessionService> = Arc::new(
+        DefaultUploadSessionService::new(attachment_service.clone(), attachment_root.join("tus"))
+            .with_quota_service(quota_service.clone())
+    );
+    
+    // 创建图片处理服务（按参数缩放和转换格式，结果缓存在附件目录的transforms子目录）
+    let image_transform_service: Arc<dyn ImageTransformService> = Arc::new(
+        DefaultImageTransformService::new(attachment_service.clone(), attachment_root.join("transforms"))
+    );
+    
+    // 创建附件打包下载服务（以流的方式生成zip）
+    let attachment_archive_service: Arc<dyn AttachmentArchiveService> = Arc::new(
+        DefaultAttachmentArchiveService::new(attachment_service.clone())
+    );
+    
+    // 创建远程导入服务（在服务端下载外部地址的文件并保存为附件）
+    let remote_import_service: Arc<dyn RemoteImportService> = Arc::new(
+        DefaultRemoteImportService::new(attachment_service.clone(), attachment_config.remote_import.clone())
+    );
+    
+    // 创建缩略图重新生成任务服务（修改缩略图配置后为已有附件补充缩略图）
+    let thumbnail_job_service: Arc<dyn ThumbnailJobService> = Arc::new(
+        DefaultThumbnailJobService::new(attachment_service.clone())
+    );
+    
+    // 创建附件引用跟踪服务（发布文章和页面时记录引用的附件）
+    let attachment_reference_service: Arc<dyn AttachmentReferenceService> = Arc::new(
+        DefaultAttachmentReferenceService::new(extension_client.clone(), attachment_service.clone())
+    );
+    
+    // 创建封面服务（校验封面引用的附件并提供缩略图）
+    use flow_service::content::{CoverService, DefaultCoverService};
+    let cover_service: Arc<dyn CoverService> = Arc::new(
+        DefaultCoverService::new(extension_client.clone(), attachment_service.clone())
+    );
+
+    // 创建内容格式注册表（内置markdown、html、richtext，插件可注册其他格式）
+    use flow_service::content::ContentConverterRegistry;
+    let content_converter_registry = Arc::new(ContentConverterRegistry::default());
+
+    // 创建发布校验器注册表（内置标题别名、内容、附件引用校验，插件可注册其他校验）
+    use flow_service::content::{PublishValidatorRegistry, AttachmentReferenceValidator};
+    let publish_validator_registry = Arc::new(PublishValidatorRegistry::default());
+    publish_validator_registry.register(Arc::new(AttachmentReferenceValidator::new(attachment_service.clone())));
+
+    // 创建插件事件总线（文章发布、评论创建、用户登录等领域事件分发给订阅的插件）
+    let event_bus = Arc::new(EventBus::new());
+    // 插件停止、卸载或失败时移除其注册的附件存储
+    event_bus.subscribe(
+        PluginStorageCleanup::OWNER,
+        PluginStorageCleanup::event_types(),
+        Arc::new(PluginStorageCleanup::new(storage_resolver.clone())),
+    );
+
+    // 文章作者、评论作者和超级管理员根据事件自动订阅默认的通知原因
+    event_bus.subscribe(
+        DefaultSubscriptionListener::OWNER,
+        DefaultSubscriptionListener::event_types(),
+        Arc::new(DefaultSubscriptionListener::new(extension_client.clone(), notification_center.clone())),
+    );
+
+    // 创建基础Post服务
+    let base_post_service: Arc<dyn PostService> = Arc::new(
+        DefaultPostService::new(extension_client.clone())
+            .with_redirect_service(slug_redirect_service.clone())
+            .with_converter_registry(content_converter_registry.clone())
+            .with_cover_service(cover_service.clone())
+            .with_publish_validators(publish_validator_registry.clone())
+            .with_event_bus(event_bus.clone())
+    );
+
+    // 创建基础SinglePage服务
+    let base_single_page_service: Arc<dyn SinglePageService> = Arc::new(
+        DefaultSinglePageService::new(extension_client.clone())
+            .with_converter_registry(content_converter_registry.clone())
+    );
+
+    // 创建Comment服务（保存前使用Akismet检测垃圾评论，配置来自系统设置）
+    use flow_service::content::AkismetSpamChecker;
+    use flow_infra::system_setting::DefaultSystemSettingService;
+    let spam_checker = Arc::new(AkismetSpamChecker::new(
+        Arc::new(DefaultSystemSettingService::new(extension_client.clone()))
+    ));
+    let comment_service: Arc<dyn CommentService> = Arc::new(
+        DefaultCommentService::new(extension_client.clone())
+            .with_spam_checker(spam_checker)
+            .with_notification_center(notification_center.clone())
+            .with_event_bus(event_bus.clone())
+    );
+
+    // 创建评论回应服务（使用Redis按用户或IP去重）
+    use flow_service::content::{CommentReactionService, DefaultCommentReactionService};
+    let comment_reaction_service: Arc<dyn CommentReactionService> = Arc::new(
+        DefaultCommentReactionService::new(comment_service.clone(), cache.clone())
+    );
+
+    // 创建Category服务
+    let category_service: Arc<dyn CategoryService> = Arc::new(
+        DefaultCategoryService::new(extension_client.clone())
+            .with_redirect_service(slug_redirect_service.clone())
+    );
+
+    // 创建Tag服务
+    let tag_service: Arc<dyn TagService> = Arc::new(
+        DefaultTagService::new(extension_client.clone())
+    );
+
+    // 创建Menu服务（主菜单配置来自系统设置）
+    use flow_service::content::{MenuService, DefaultMenuService};
+    let menu_service: Arc<dyn MenuService> = Arc::new(
+        DefaultMenuService::new(extension_client.clone())
+            .with_setting_service(Arc::new(DefaultSystemSettingService::new(extension_client.clone())))
+    );
+
+    // 创建友情链接服务
+    use flow_service::content::{LinkService, DefaultLinkService};
+    let link_service: Arc<dyn LinkService> = Arc::new(
+        DefaultLinkService::new(extension_client.clone())
+    );
+
+    // 创建瞬间服务
+    use flow_service::content::{MomentService, DefaultMomentService};
+    let moment_service: Arc<dyn MomentService> = Arc::new(
+        DefaultMomentService::new(extension_client.clone())
+            .with_converter_registry(content_converter_registry.clone())
+    );
+
+    // 创建图库服务（照片引用附件）
+    use flow_service::content::{PhotoService, DefaultPhotoService};
+    let photo_service: Arc<dyn PhotoService> = Arc::new(
+        DefaultPhotoService::new(extension_client.clone(), attachment_service.clone())
+    );
+
+    // 创建Series服务
+    let series_service: Arc<dyn SeriesService> = Arc::new(
+        DefaultSeriesService::new(extension_client.clone())
+    );
+
+    // 创建Snapshot服务
+    use flow_service::content::{SnapshotService, DefaultSnapshotService};
+    let snapshot_service: Arc<dyn SnapshotService> = Arc::new(
+        DefaultSnapshotService::new(extension_client.clone())
+    );
+
+    // 初始化索引引擎
+    let indices_manager = Arc::new(IndicesManager::new());
+    let fulltext_mapping = Arc::new(FulltextFieldMapping::default());
+    let _index_engine = flow_infra::index::engine::DefaultIndexEngine::with_search_engine(
+        indices_manager,
+        Some(search_engine),
+        fulltext_mapping,
+    );
+
+    // 创建带搜索索引、附件引用跟踪和标签文章计数的Post服务（包装基础服务）
+    let post_service: Arc<dyn PostService> = Arc::new(
+        SearchIndexingPostService::new(base_post_service.clone(), search_service.clone())
+    );
+    let post_service: Arc<dyn PostService> = Arc::new(
+        ReferenceTrackingPostService::new(post_service, attachment_reference_service.clone())
+    );
+    let post_service: Arc<dyn PostService> = Arc::new(
+        TagCountingPostService::new(post_service, tag_service.clone())
+    );
+
+    // 创建加密文章访问服务（解锁令牌使用独立签发者，不能作为登录令牌使用）
+    use flow_service::content::{post_access_service, PostAccessService, DefaultPostAccessService};
+    let unlock_jwt_service = Arc::new(JwtService::new(
+        &config.flow.security.jwt_secret,
+        post_access_service::UNLOCK_TOKEN_ISSUER.to_string(),
+        post_access_service::DEFAULT_UNLOCK_TTL_SECONDS,
+    )?);
+    let post_access_service: Arc<dyn PostAccessService> = Arc::new(
+        DefaultPostAccessService::new(post_service.clone(), password_service.clone(), unlock_jwt_service)
+    );
+
+    // 创建带搜索索引和附件引用跟踪的SinglePage服务（包装基础服务）
+    let single_page_service: Arc<dyn SinglePageService> = Arc::new(
+        SearchIndexingSinglePageService::new(base_single_page_service.clone(), search_service.clone())
+    );
+    let single_page_service: Arc<dyn SinglePageService> = Arc::new(
+        ReferenceTrackingSinglePageService::new(single_page_service, attachment_reference_service.clone())
+    );
+
+    // 创建附件迁移服务（在存储策略和分组之间迁移附件，并替换文章中引用的地址）
+    let attachment_migration_service: Arc<dyn AttachmentMigrationService> = Arc::new(
+        DefaultAttachmentMigrationService::new(
             extension_client.clone(),
-            storage,
-            thumbnail_service,
-            upload_path,
-            base_url,
+            attachment_service.clone(),
+            post_service.clone(),
         )
     );
-    
+
+    // 创建附件批量操作服务（批量删除、移动分组、修改标签和上传者）
+    let attachment_batch_service: Arc<dyn AttachmentBatchService> = Arc::new(
+        DefaultAttachmentBatchService::new(
+            extension_client.clone(),
+            attachment_service.clone(),
+            attachment_reference_service.clone(),
+        ).with_cover_service(cover_service.clone())
+            .with_quota_service(quota_service.clone())
+    );
+
     // 创建Policy服务
     let policy_service: Arc<dyn PolicyService> = Arc::new(
         DefaultPolicyService::new(extension_client.clone())
@@ -404,15 +824,121 @@ pub async fn init_app_state(
         )
     );
     
-    // 创建共享URL服务
+    // 创建共享URL服务（HMAC签名的无状态链接，撤销和下载次数记录在Redis中）
+    let shared_url_secret = config.flow.attachment.shared_url_secret.as_deref()
+        .unwrap_or(&config.flow.security.jwt_secret);
     let shared_url_service: Arc<dyn SharedUrlService> = Arc::new(
-        DefaultSharedUrlService::new()
+        DefaultSharedUrlService::new(shared_url_secret).with_cache(cache.clone())
+    );
+
+    // 创建失效链接检查服务并启动后台检查任务
+    // 仅更新注解，使用基础服务避免触发重新索引
+    use flow_service::content::link_check_service::{self, LinkCheckService, DefaultLinkCheckService};
+    let link_check_service: Arc<dyn LinkCheckService> = Arc::new(
+        DefaultLinkCheckService::new(
+            base_post_service.clone(),
+            base_single_page_service.clone(),
+            category_service.clone(),
+            tag_service.clone(),
+            attachment_service.clone(),
+            config.flow.external_url.clone(),
+        )
+    );
+    link_check_service::spawn_link_check_job(
+        link_check_service.clone(),
+        link_check_service::DEFAULT_CHECK_INTERVAL,
     );
 
     // 创建主题服务
     let theme_root = config.flow.work_dir.join("themes");
     let theme_service: Arc<dyn ThemeService> = Arc::new(
         DefaultThemeService::new(extension_client.clone(), theme_root.clone())
+            .with_remote_fetcher(RemoteThemeFetcher::new().with_allow_private_networks(config.flow.theme.allow_private_networks))
+    );
+    let theme_config_service: Arc<dyn ThemeConfigService> = Arc::new(
+        DefaultThemeConfigService::new(extension_client.clone())
+    );
+    let robots_service: Arc<dyn RobotsService> = Arc::new(
+        DefaultRobotsService::new(Arc::new(DefaultSystemSettingService::new(extension_client.clone())))
+            .with_external_url(config.flow.external_url.clone())
+    );
+    
+    // 创建Finder注册表（内置Finder在创建主题组件时注册，插件启动后注册其声明的Finder）
+    let finder_registry = Arc::new(flow_infra::theme::DefaultFinderRegistry::new());
+    
+    // 创建插件管理器和插件设置服务
+    let plugin_logs = Arc::new(flow_plugin::PluginLogBuffer::default());
+    // 内置扩展类型在加载插件前注册，插件不能注册同组同名的类型
+    let mut builtin_schemes = DefaultSchemeManager::new();
+    for scheme in flow_domain::scheme::builtin_schemes() {
+        builtin_schemes.register(scheme)?;
+    }
+    let scheme_manager: SharedSchemeManager = Arc::new(std::sync::RwLock::new(builtin_schemes));
+    // 插件通过宿主接口读写扩展对象
+    let plugin_host: Arc<dyn ExtensionHost> = Arc::new(ClientExtensionHost::new(extension_client.clone()));
+    let plugin_manager = DefaultPluginManager::new(config.flow.plugin.plugins_dir.clone())
+        .with_scheme_manager(scheme_manager.clone())
+        .with_event_bus(event_bus.clone())
+        .with_finder_registry(finder_registry.clone())
+        .with_controller_manager(Arc::new(flow_plugin::ControllerManager::new(extension_client.clone())))
+        .with_migration_store(Arc::new(ConfigMapMigrationStore::new(extension_client.clone())))
+        .with_health_probe(
+            std::time::Duration::from_secs(config.flow.plugin.probe.timeout),
+            config.flow.plugin.probe.failure_threshold,
+        )
+        .with_loader(Arc::new(
+            WasmPluginLoader::new()
+                .with_host(plugin_host.clone())
+                .with_log_buffer(plugin_logs.clone())
+                .with_scheme_manager(scheme_manager.clone())
+        ))
+        // 插件目录从描述符声明的构建产物加载
+        .with_loader(Arc::new(
+            DirectoryPluginLoader::new().with_wasm_loader(
+                WasmPluginLoader::new()
+                    .with_host(plugin_host.clone())
+                    .with_log_buffer(plugin_logs.clone())
+                    .with_scheme_manager(scheme_manager.clone())
+            )
+        ));
+    let plugin_manager: Arc<dyn PluginManager> = Arc::new(plugin_manager);
+    if config.flow.plugin.dev_mode {
+        if let Err(e) = flow_plugin::spawn_plugin_watcher(
+            config.flow.plugin.plugins_dir.clone(),
+            plugin_manager.clone(),
+        ) {
+            tracing::warn!("Failed to watch plugin directory: {}", e);
+        }
+    }
+    let plugin_config_service: Arc<dyn PluginConfigService> = Arc::new(
+        DefaultPluginConfigService::new(extension_client.clone())
+            .with_plugin_manager(plugin_manager.clone())
+    );
+    let plugin_capability_service: Arc<dyn PluginCapabilityService> = Arc::new(
+        DefaultPluginCapabilityService::new(extension_client.clone(), plugin_manager.clone())
+    );
+    if let Err(e) = plugin_capability_service.restore_approvals().await {
+        tracing::warn!("Failed to restore plugin capability approvals: {}", e);
+    }
+    let plugin_install_service: Arc<dyn PluginInstallService> = Arc::new(
+        DefaultPluginInstallService::new(
+            plugin_manager.clone(),
+            config.flow.plugin.plugins_dir.clone(),
+            config.flow.plugin.install.clone(),
+        ).with_capability_service(plugin_capability_service.clone())
+    );
+    let plugin_asset_service: Arc<dyn PluginAssetService> = Arc::new(
+        DefaultPluginAssetService::new(plugin_manager.clone())
+    );
+    let plugin_lifecycle_service: Arc<dyn PluginLifecycleService> = Arc::new(
+        DefaultPluginLifecycleService::new(extension_client.clone(), plugin_manager.clone())
+    );
+    let plugin_health_service: Arc<dyn PluginHealthService> = Arc::new(
+        DefaultPluginHealthService::new(extension_client.clone(), plugin_manager.clone())
+    );
+    flow_service::plugin::spawn_plugin_probe_job(
+        plugin_health_service,
+        std::time::Duration::from_secs(config.flow.plugin.probe.interval.max(1)),
     );
     
     // 创建主题解析器和模板引擎管理器
@@ -420,11 +946,50 @@ pub async fn init_app_state(
         flow_infra::theme::ThemeResolver::new(
             extension_client.clone(),
             theme_root.clone()
-        )
+        ).with_preview_secret(&config.flow.security.jwt_secret)
     );
     let template_engine_manager = Arc::new(
         flow_infra::theme::TemplateEngineManager::new(theme_root.clone())
     );
+    // 注册渲染主题页面时预加载数据的Finder
+    let finders: Vec<Box<dyn Finder>> = vec![
+        Box::new(MenuFinder::new(menu_service.clone())),
+        Box::new(ArchiveFinder::new(post_service.clone())),
+        Box::new(TagCloudFinder::new(tag_service.clone())),
+        Box::new(SiteStatsFinder::new(post_service.clone(), comment_service.clone(), category_service.clone())),
+    ];
+    for finder in finders {
+        finder_registry.register(finder.name().to_string(), finder);
+    }
+    // 内置Finder注册后再加载插件，插件与内置Finder重名时拒绝启动
+    match plugin_lifecycle_service.load_installed().await {
+        Ok(plugins) => tracing::info!("Synchronized {} plugins at startup", plugins.len()),
+        Err(e) => tracing::warn!("Failed to load installed plugins: {}", e),
+    }
+    // 注册每次渲染主题页面时注入的全局模板变量
+    let template_context_registry = Arc::new(TemplateContextRegistry::new());
+    let contributors: Vec<Arc<dyn TemplateContextContributor>> = vec![
+        Arc::new(
+            SiteContextContributor::new(Arc::new(DefaultSystemSettingService::new(extension_client.clone())))
+                .with_external_url(config.flow.external_url.clone())
+        ),
+        Arc::new(CurrentUserContextContributor::new(user_service.clone())),
+        Arc::new(MenuContextContributor::new(menu_service.clone())),
+        Arc::new(RequestContextContributor::new().with_external_url(config.flow.external_url.clone())),
+    ];
+    for contributor in contributors {
+        template_context_registry.register(contributor);
+    }
+    if config.flow.theme.watch {
+        let manifest_reloader = config.flow.theme.reload_manifest.then(|| theme_service.clone());
+        if let Err(e) = flow_service::theme::spawn_theme_watcher(
+            theme_root.clone(),
+            template_engine_manager.clone(),
+            manifest_reloader,
+        ) {
+            tracing::warn!("Failed to watch theme directory: {}", e);
+        }
+    }
     
     // 创建WebSocket端点管理器
     let websocket_manager = Arc::new(
@@ -497,38 +1062,11 @@ pub async fn init_app_state(
     });
     
     websocket_manager.register(echo_endpoint).await;
-
-    // 创建通知服务
-    let notification_service: Arc<dyn NotificationService> = Arc::new(
-        DefaultNotificationService::new(extension_client.clone())
-    );
-    
-    // 创建一个简单的通知发送器实现（站内通知）
-    struct InMemoryNotificationSender;
-    
-    #[async_trait]
-    impl NotificationSender for InMemoryNotificationSender {
-        async fn send_notification(
-            &self,
-            _notifier_extension_name: &str,
-            _context: flow_service::notification::NotificationContext,
-        ) -> anyhow::Result<()> {
-            // TODO: 实现实际的通知发送逻辑
-            // 目前站内通知已经通过NotificationService创建，这里可以用于扩展其他通知方式（邮件、短信等）
-            Ok(())
-        }
-    }
-    
-    let notification_sender: Arc<dyn NotificationSender> = Arc::new(InMemoryNotificationSender);
-    
-    // 创建通知中心
-    let notification_center: Arc<dyn NotificationCenter> = Arc::new(
-        DefaultNotificationCenter::new(
-            extension_client.clone(),
-            notification_service.clone(),
-            notification_sender,
-        )
-    );
+    websocket_manager.register(Arc::new(flow_service::plugin::PluginLogEndpoint::new(plugin_logs.clone()))).await;
+    websocket_manager.register(Arc::new(NotificationPushEndpoint::new(
+        notification_broadcaster.clone(),
+        notification_service.clone(),
+    ))).await;
 
     // 创建备份和恢复服务
     use flow_service::migration::{DefaultBackupService, DefaultRestoreService};
@@ -598,27 +1136,66 @@ pub async fn init_app_state(
         session_service,
         rate_limiter,
         extension_client,
+        scheme_manager,
         user_service,
         role_service,
         password_service,
         post_service,
+        post_access_service,
         single_page_service,
         comment_service,
+        comment_reaction_service,
         category_service,
         tag_service,
+        menu_service,
+        link_service,
+        moment_service,
+        photo_service,
+        series_service,
+        slug_redirect_service,
         snapshot_service,
         search_service,
+        search_engine_registry,
         attachment_service,
+        upload_session_service,
+        image_transform_service,
+        quota_service,
+        attachment_migration_service,
+        attachment_archive_service,
+        attachment_reference_service,
+        remote_import_service,
+        thumbnail_job_service,
+        attachment_batch_service,
         policy_service,
+        storage_resolver,
         group_service,
         shared_url_service,
+        link_check_service,
+        content_converter_registry,
+        cover_service,
+        publish_validator_registry,
         theme_service,
+        theme_config_service,
+        robots_service,
+        plugin_config_service,
+        plugin_capability_service,
+        plugin_install_service,
+        plugin_asset_service,
+        plugin_lifecycle_service,
+        plugin_logs,
+        event_bus,
         theme_root,
         theme_resolver,
         template_engine_manager,
+        finder_registry,
+        template_context_registry,
         websocket_manager,
         notification_service,
         notification_center,
+        notifier_registry,
+        notification_preference_service,
+        notifier_descriptor_service,
+        reason_type_service,
         backup_service,
         restore_service,
         user_connection_service,
@@ -627,6 +1204,7 @@ pub async fn init_app_state(
         two_factor_auth_cache,
         totp_auth_service,
         totp_issuer: config.flow.security.totp_issuer.clone(),
+        trusted_proxies: Arc::new(config.server.trusted_proxies.clone()),
     })
 }
 